@@ -0,0 +1,70 @@
+-- Migration: Add RBAC tables for users, roles, teams and permission grants
+-- Each row keeps the full entity as JSON in `data`; the other columns are
+-- copies of the fields used for lookups and ad-hoc queries.
+
+CREATE TABLE IF NOT EXISTS rbac_users (
+    id TEXT PRIMARY KEY,
+    username TEXT NOT NULL,
+    email TEXT NOT NULL,
+    active INTEGER NOT NULL DEFAULT 1,
+    data TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS rbac_roles (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    active INTEGER NOT NULL DEFAULT 1,
+    data TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS rbac_security_groups (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    data TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS rbac_access_policies (
+    id TEXT PRIMARY KEY,
+    priority INTEGER NOT NULL DEFAULT 0,
+    active INTEGER NOT NULL DEFAULT 1,
+    data TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS rbac_role_assignments (
+    id TEXT PRIMARY KEY,
+    user_id TEXT NOT NULL,
+    role_id TEXT NOT NULL,
+    approval_status TEXT NOT NULL,
+    data TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS security_teams (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    parent_team TEXT,
+    active INTEGER NOT NULL DEFAULT 1,
+    data TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS permission_grants (
+    id TEXT PRIMARY KEY,
+    subject_type TEXT NOT NULL,
+    subject_id TEXT NOT NULL,
+    permission_name TEXT NOT NULL,
+    resource_type TEXT NOT NULL,
+    resource_id TEXT NOT NULL,
+    active INTEGER NOT NULL DEFAULT 1,
+    data TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_rbac_users_username ON rbac_users(username);
+CREATE INDEX IF NOT EXISTS idx_rbac_role_assignments_user ON rbac_role_assignments(user_id);
+CREATE INDEX IF NOT EXISTS idx_security_teams_parent ON security_teams(parent_team);
+CREATE INDEX IF NOT EXISTS idx_permission_grants_subject ON permission_grants(subject_type, subject_id);
@@ -0,0 +1,36 @@
+-- Migration: Add team knowledge sync tables
+-- Client side: what this installation last exchanged with the team sync server.
+-- Server side: the encrypted records the self-hosted sync server keeps per team.
+
+CREATE TABLE IF NOT EXISTS team_sync_state (
+    key TEXT PRIMARY KEY,
+    value TEXT NOT NULL
+);
+
+-- One row per fact or shared thread that takes part in team sync
+CREATE TABLE IF NOT EXISTS team_sync_records (
+    kind TEXT NOT NULL, -- 'fact' or 'thread'
+    record_key TEXT NOT NULL, -- semantic fingerprint or thread id
+    version TEXT NOT NULL DEFAULT '{}', -- JSON version vector last exchanged
+    content_hash TEXT, -- blake3 of the payload last exchanged; NULL until first push
+    shared INTEGER NOT NULL DEFAULT 1, -- 0 once this installation removed it from the team
+    synced_at TEXT,
+    PRIMARY KEY (kind, record_key)
+);
+
+-- Only ciphertext is stored; ids are keyed hashes the server cannot reverse
+CREATE TABLE IF NOT EXISTS team_sync_server_records (
+    team TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    id TEXT NOT NULL,
+    version TEXT NOT NULL, -- JSON version vector
+    deleted INTEGER NOT NULL DEFAULT 0,
+    nonce TEXT NOT NULL,
+    ciphertext TEXT NOT NULL,
+    author TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    seq INTEGER NOT NULL,
+    PRIMARY KEY (team, kind, id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_team_sync_server_records_seq ON team_sync_server_records(team, seq);
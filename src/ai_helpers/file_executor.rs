@@ -70,6 +70,31 @@ pub enum OperationType {
     },
 }
 
+impl OperationType {
+    /// Permission, resource type and resource ID checked before running
+    fn access_requests(&self) -> Vec<(&'static str, &'static str, String)> {
+        let write = |path: &Path| ("code.write", "file", path.display().to_string());
+        match self {
+            OperationType::CreateFile { path, .. }
+            | OperationType::UpdateFile { path, .. }
+            | OperationType::DeleteFile { path }
+            | OperationType::CreateDirectory { path } => vec![write(path)],
+            OperationType::MoveFile { from, to } => vec![write(from), write(to)],
+            OperationType::RunCommand { command, .. } => {
+                vec![("command.run", "command", command.clone())]
+            }
+            OperationType::SearchFiles { path, .. } => vec![(
+                "code.read",
+                "file",
+                path.as_deref()
+                    .unwrap_or(Path::new("."))
+                    .display()
+                    .to_string(),
+            )],
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FileChange {
     pub find: String,
@@ -186,6 +211,11 @@ impl AIHelperFileExecutor {
             return Ok(report);
         }
 
+        for (permission, resource_type, resource_id) in operation.action.access_requests() {
+            crate::security::enforcement::authorize(permission, resource_type, &resource_id)
+                .await?;
+        }
+
         match &operation.action {
             OperationType::CreateFile { path, content } => {
                 self.create_file(path, content).await?;
@@ -0,0 +1,1166 @@
+//! Background indexing daemon
+//!
+//! Keeps the symbol index current by watching a repository with `notify`.
+//! Bursts of file changes are debounced into batches, and only files whose
+//! content hash differs from the one stored in SQLite are re-parsed. Deleted
+//! files have their symbols removed, and a branch switch (a change to
+//! `.git/HEAD`) triggers a metadata-gated resync rather than per-event work.
+//! Progress is published on a watch channel and persisted so other processes
+//! such as `hive search` can tell whether the index is up to date.
+
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Utc};
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+use rusqlite::{params, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::future::Future;
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, watch};
+use tracing::{debug, info, warn};
+
+use crate::analysis::languages;
+use crate::analysis::symbol_index::{self, IndexedFile, SymbolIndexer};
+use crate::analysis::walker::{RepositoryWalker, DEFAULT_EXCLUDED_DIRS, HIVE_IGNORE_FILE};
+use crate::analytics::metrics;
+use crate::core::database::DatabaseManager;
+
+/// Directory names skipped unless test files are included
+const TEST_DIRS: &[&str] = &["tests", "test"];
+
+/// A watcher whose status has not been refreshed for this long is
+/// considered gone (crashed or killed)
+pub const LIVE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Minimum interval between persisted progress updates while indexing
+const PERSIST_INTERVAL: Duration = Duration::from_millis(250);
+
+/// The set of files a project index covers
+#[derive(Debug, Clone)]
+pub struct IndexScope {
+    root: PathBuf,
+    include_tests: bool,
+    exclude: Vec<String>,
+    ignore: Gitignore,
+}
+
+impl IndexScope {
+    /// Create a scope rooted at `root` (canonicalized)
+    pub fn new(root: impl AsRef<Path>) -> Result<Self> {
+        let root = root.as_ref();
+        let root = root
+            .canonicalize()
+            .with_context(|| format!("Failed to resolve {}", root.display()))?;
+        let ignore = root_ignore(&root);
+
+        Ok(Self {
+            root,
+            include_tests: false,
+            exclude: Vec::new(),
+            ignore,
+        })
+    }
+
+    /// Index files under `tests`/`test` directories
+    pub fn include_tests(mut self, yes: bool) -> Self {
+        self.include_tests = yes;
+        self
+    }
+
+    /// Skip paths containing any of these substrings
+    pub fn exclude(mut self, patterns: Vec<String>) -> Self {
+        self.exclude = patterns;
+        self
+    }
+
+    /// Canonical project root
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Whether test directories are indexed
+    pub fn includes_tests(&self) -> bool {
+        self.include_tests
+    }
+
+    /// Substring exclude patterns
+    pub fn exclude_patterns(&self) -> &[String] {
+        &self.exclude
+    }
+
+    /// Discover every indexable file in the project
+    pub fn discover(&self) -> Result<Vec<PathBuf>> {
+        self.discover_in(&self.root)
+    }
+
+    /// Discover indexable files below `dir`
+    pub fn discover_in(&self, dir: &Path) -> Result<Vec<PathBuf>> {
+        let mut walker = RepositoryWalker::new(dir)
+            .follow_links(true)
+            .extensions(&source_extensions());
+
+        if !self.include_tests {
+            walker = walker.exclude_dirs(TEST_DIRS);
+        }
+
+        let mut files: Vec<PathBuf> = walker
+            .walk_paths()?
+            .into_iter()
+            .filter(|path| !self.is_excluded(path))
+            .collect();
+        files.sort();
+        Ok(files)
+    }
+
+    /// Whether `path` is an indexable file inside this scope
+    pub fn contains(&self, path: &Path) -> bool {
+        is_indexable(path) && self.passes_filters(path, false)
+    }
+
+    /// Whether a changed path could affect the index. Deleted paths can't
+    /// be stat'ed, so anything not filtered out by location is accepted.
+    fn may_contain(&self, path: &Path) -> bool {
+        self.passes_filters(path, path.is_dir())
+    }
+
+    fn passes_filters(&self, path: &Path, is_dir: bool) -> bool {
+        let Ok(relative) = path.strip_prefix(&self.root) else {
+            return false;
+        };
+
+        for component in relative.components() {
+            let Component::Normal(name) = component else {
+                return false;
+            };
+            let name = name.to_string_lossy();
+            if name.starts_with('.')
+                || DEFAULT_EXCLUDED_DIRS.contains(&name.as_ref())
+                || (!self.include_tests && TEST_DIRS.contains(&name.as_ref()))
+            {
+                return false;
+            }
+        }
+
+        !self.is_excluded(path)
+            && !self
+                .ignore
+                .matched_path_or_any_parents(path, is_dir)
+                .is_ignore()
+    }
+
+    fn is_excluded(&self, path: &Path) -> bool {
+        let path_str = path.to_string_lossy();
+        self.exclude
+            .iter()
+            .any(|pattern| path_str.contains(pattern.as_str()))
+    }
+}
+
+/// Every extension the language registry can extract symbols from
+pub fn source_extensions() -> Vec<&'static str> {
+    languages::all()
+        .iter()
+        .filter(|spec| spec.grammar().is_some() || spec.patterns().is_some())
+        .flat_map(|spec| spec.info().extensions.iter().copied())
+        .collect()
+}
+
+fn is_indexable(path: &Path) -> bool {
+    let Some(ext) = path.extension().and_then(|e| e.to_str()) else {
+        return false;
+    };
+    let ext = ext.to_lowercase();
+    source_extensions().contains(&ext.as_str())
+}
+
+/// Root-level `.gitignore` and `.hiveignore` rules, used to filter watch
+/// events (full scans go through the walker, which also honours nested files)
+fn root_ignore(root: &Path) -> Gitignore {
+    let mut builder = GitignoreBuilder::new(root);
+    for name in [".gitignore", HIVE_IGNORE_FILE] {
+        let file = root.join(name);
+        if file.is_file() {
+            if let Some(e) = builder.add(&file) {
+                debug!("Ignoring malformed {}: {}", file.display(), e);
+            }
+        }
+    }
+    builder.build().unwrap_or_else(|_| Gitignore::empty())
+}
+
+/// Name of the checked-out branch, or the short commit for a detached HEAD
+pub fn current_branch(root: &Path) -> Option<String> {
+    let head = std::fs::read_to_string(root.join(".git").join("HEAD")).ok()?;
+    parse_head(&head)
+}
+
+fn parse_head(head: &str) -> Option<String> {
+    let head = head.trim();
+    if let Some(reference) = head.strip_prefix("ref:") {
+        let reference = reference.trim();
+        return Some(
+            reference
+                .strip_prefix("refs/heads/")
+                .unwrap_or(reference)
+                .to_string(),
+        );
+    }
+    (!head.is_empty()).then(|| head.chars().take(8).collect())
+}
+
+/// Background watcher configuration
+#[derive(Debug, Clone)]
+pub struct WatchConfig {
+    /// Quiet period after the last change before a batch is processed
+    pub debounce: Duration,
+    /// Upper bound on how long a continuous stream of changes is buffered
+    pub max_batch_delay: Duration,
+    /// Batches touching more paths than this fall back to a full resync
+    pub full_sync_threshold: usize,
+    /// Interval at which a live watcher refreshes its persisted status
+    /// (must stay well below [`LIVE_TIMEOUT`])
+    pub heartbeat: Duration,
+}
+
+impl Default for WatchConfig {
+    fn default() -> Self {
+        Self {
+            debounce: Duration::from_millis(300),
+            max_batch_delay: Duration::from_secs(5),
+            full_sync_threshold: 500,
+            heartbeat: Duration::from_secs(10),
+        }
+    }
+}
+
+/// What the indexer is currently doing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IndexPhase {
+    Idle,
+    Scanning,
+    Indexing,
+}
+
+/// Progress of the current (or last) indexing pass
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexProgress {
+    pub root: PathBuf,
+    pub phase: IndexPhase,
+    /// A watcher is attached to the project
+    pub watching: bool,
+    pub branch: Option<String>,
+    /// Files considered by the current pass
+    pub total: usize,
+    pub processed: usize,
+    pub indexed: usize,
+    pub unchanged: usize,
+    pub removed: usize,
+    pub errors: usize,
+    /// Changed paths waiting for the debounce window to close
+    pub pending: usize,
+    /// When the index last caught up with the working tree
+    pub last_sync: Option<DateTime<Utc>>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl IndexProgress {
+    fn new(root: &Path) -> Self {
+        Self {
+            root: root.to_path_buf(),
+            phase: IndexPhase::Idle,
+            watching: false,
+            branch: current_branch(root),
+            total: 0,
+            processed: 0,
+            indexed: 0,
+            unchanged: 0,
+            removed: 0,
+            errors: 0,
+            pending: 0,
+            last_sync: None,
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn begin(&mut self, phase: IndexPhase, total: usize) {
+        self.phase = phase;
+        self.total = total;
+        self.processed = 0;
+        self.indexed = 0;
+        self.unchanged = 0;
+        self.removed = 0;
+        self.errors = 0;
+    }
+
+    /// A watcher is attached and has refreshed its status recently
+    pub fn is_live(&self) -> bool {
+        self.watching
+            && Utc::now().signed_duration_since(self.updated_at)
+                < chrono::Duration::from_std(LIVE_TIMEOUT)
+                    .unwrap_or_else(|_| chrono::Duration::zero())
+    }
+
+    /// Work is in flight or queued
+    pub fn is_busy(&self) -> bool {
+        self.phase != IndexPhase::Idle || self.pending > 0
+    }
+}
+
+/// Summary of an indexing pass
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct SyncReport {
+    pub discovered: usize,
+    pub indexed: usize,
+    pub unchanged: usize,
+    pub removed: usize,
+    pub errors: usize,
+    /// Symbols extracted from re-indexed files
+    pub symbols: usize,
+    pub elapsed_ms: f64,
+}
+
+impl SyncReport {
+    /// Whether the pass changed the index
+    pub fn changed(&self) -> bool {
+        self.indexed > 0 || self.removed > 0
+    }
+}
+
+/// Persisted status of an indexed project
+#[derive(Debug, Clone)]
+pub struct IndexStatus {
+    pub root: PathBuf,
+    pub include_tests: bool,
+    pub exclude: Vec<String>,
+    pub progress: IndexProgress,
+}
+
+impl IndexStatus {
+    /// Rebuild the scope the project was indexed with
+    pub fn scope(&self) -> Result<IndexScope> {
+        Ok(IndexScope::new(&self.root)?
+            .include_tests(self.include_tests)
+            .exclude(self.exclude.clone()))
+    }
+}
+
+/// SQLite-backed store of per-project indexing status
+#[derive(Clone)]
+pub struct IndexStatusStore {
+    db: Arc<DatabaseManager>,
+}
+
+impl IndexStatusStore {
+    /// Create the store, initializing its table
+    pub fn new(db: Arc<DatabaseManager>) -> Result<Self> {
+        let conn = db.get_connection()?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS index_status (
+                root TEXT PRIMARY KEY,
+                include_tests INTEGER NOT NULL DEFAULT 0,
+                exclude TEXT NOT NULL DEFAULT '[]',
+                progress TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+        drop(conn);
+
+        Ok(Self { db })
+    }
+
+    /// Record the latest progress for a project
+    pub fn save(&self, scope: &IndexScope, progress: &IndexProgress) -> Result<()> {
+        let conn = self.db.get_connection()?;
+        conn.execute(
+            "INSERT OR REPLACE INTO index_status (root, include_tests, exclude, progress, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                scope.root().to_string_lossy(),
+                scope.includes_tests() as i32,
+                serde_json::to_string(scope.exclude_patterns())?,
+                serde_json::to_string(progress)?,
+                progress.updated_at.to_rfc3339(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Status for an exact project root
+    pub fn load(&self, root: &Path) -> Result<Option<IndexStatus>> {
+        let conn = self.db.get_connection()?;
+        let row = conn
+            .query_row(
+                "SELECT root, include_tests, exclude, progress FROM index_status WHERE root = ?1",
+                params![root.to_string_lossy()],
+                Self::row_to_raw,
+            )
+            .optional()?;
+        row.map(Self::parse).transpose()
+    }
+
+    /// Every project with a recorded status
+    pub fn list(&self) -> Result<Vec<IndexStatus>> {
+        let conn = self.db.get_connection()?;
+        let mut stmt = conn.prepare(
+            "SELECT root, include_tests, exclude, progress FROM index_status ORDER BY root",
+        )?;
+        let rows = stmt
+            .query_map([], Self::row_to_raw)?
+            .collect::<Result<Vec<_>, _>>()?;
+        rows.into_iter().map(Self::parse).collect()
+    }
+
+    /// The innermost indexed project containing `path`
+    pub fn covering(&self, path: &Path) -> Result<Option<IndexStatus>> {
+        let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        Ok(self
+            .list()?
+            .into_iter()
+            .filter(|status| path.starts_with(&status.root))
+            .max_by_key(|status| status.root.components().count()))
+    }
+
+    /// Forget every recorded status
+    pub fn clear(&self) -> Result<()> {
+        let conn = self.db.get_connection()?;
+        conn.execute("DELETE FROM index_status", [])?;
+        Ok(())
+    }
+
+    fn row_to_raw(row: &rusqlite::Row<'_>) -> rusqlite::Result<(String, bool, String, String)> {
+        Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+    }
+
+    fn parse(
+        (root, include_tests, exclude, progress): (String, bool, String, String),
+    ) -> Result<IndexStatus> {
+        Ok(IndexStatus {
+            root: PathBuf::from(root),
+            include_tests,
+            exclude: serde_json::from_str(&exclude).unwrap_or_default(),
+            progress: serde_json::from_str(&progress).context("Failed to decode index progress")?,
+        })
+    }
+}
+
+/// How aggressively a file is re-checked
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Freshness {
+    /// Trust an unchanged size and modification time
+    Metadata,
+    /// Re-hash the content
+    Content,
+    /// Re-index unconditionally
+    Force,
+}
+
+enum FileOutcome {
+    Indexed(usize),
+    Unchanged,
+    Removed,
+    Failed,
+}
+
+/// File changes accumulated while waiting for the debounce window
+#[derive(Debug, Default)]
+struct ChangeBatch {
+    paths: HashSet<PathBuf>,
+    branch_changed: bool,
+    first_change: Option<tokio::time::Instant>,
+    last_change: Option<tokio::time::Instant>,
+}
+
+impl ChangeBatch {
+    /// Record the paths of a notify event that may affect the index
+    fn record(&mut self, event: &Event, scope: &IndexScope) {
+        if !matches!(
+            event.kind,
+            EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+        ) {
+            return;
+        }
+
+        let git_dir = scope.root().join(".git");
+        for path in &event.paths {
+            if let Ok(relative) = path.strip_prefix(&git_dir) {
+                if relative == Path::new("HEAD") {
+                    self.branch_changed = true;
+                    self.touch();
+                }
+                continue;
+            }
+
+            if scope.may_contain(path) {
+                self.paths.insert(path.clone());
+                self.touch();
+            }
+        }
+    }
+
+    fn touch(&mut self) {
+        let now = tokio::time::Instant::now();
+        self.first_change.get_or_insert(now);
+        self.last_change = Some(now);
+    }
+
+    fn len(&self) -> usize {
+        self.paths.len()
+    }
+
+    /// When the batch should be processed, if it holds anything
+    fn deadline(&self, config: &WatchConfig) -> Option<tokio::time::Instant> {
+        let first = self.first_change?;
+        let last = self.last_change?;
+        Some((last + config.debounce).min(first + config.max_batch_delay))
+    }
+}
+
+/// Keeps a project's symbol index in sync with its working tree
+pub struct BackgroundIndexer {
+    indexer: Arc<SymbolIndexer>,
+    scope: IndexScope,
+    config: WatchConfig,
+    status: IndexStatusStore,
+    progress: watch::Sender<IndexProgress>,
+    last_persist: Mutex<Option<Instant>>,
+}
+
+impl BackgroundIndexer {
+    /// Create an indexer for `scope`, picking up where a previous run left off
+    pub fn new(
+        db: Arc<DatabaseManager>,
+        indexer: Arc<SymbolIndexer>,
+        scope: IndexScope,
+    ) -> Result<Self> {
+        let status = IndexStatusStore::new(db)?;
+
+        let mut progress = IndexProgress::new(scope.root());
+        if let Some(previous) = status.load(scope.root())? {
+            progress.last_sync = previous.progress.last_sync;
+        }
+        let (progress, _) = watch::channel(progress);
+
+        Ok(Self {
+            indexer,
+            scope,
+            config: WatchConfig::default(),
+            status,
+            progress,
+            last_persist: Mutex::new(None),
+        })
+    }
+
+    /// Override the watcher configuration
+    pub fn with_config(mut self, config: WatchConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// The project this indexer covers
+    pub fn scope(&self) -> &IndexScope {
+        &self.scope
+    }
+
+    /// Subscribe to progress updates
+    pub fn subscribe(&self) -> watch::Receiver<IndexProgress> {
+        self.progress.subscribe()
+    }
+
+    /// Current progress snapshot
+    pub fn progress(&self) -> IndexProgress {
+        self.progress.borrow().clone()
+    }
+
+    /// Reconcile the index with the working tree: index new and changed
+    /// files and drop files that no longer exist. Unless `force` is set,
+    /// files whose size and modification time match the stored fingerprint
+    /// are skipped without being read.
+    pub async fn sync(&self, force: bool) -> Result<SyncReport> {
+        let start = Instant::now();
+        let root = self.scope.root().to_path_buf();
+
+        self.update(true, |progress| {
+            progress.begin(IndexPhase::Scanning, 0);
+            progress.branch = current_branch(&root);
+        });
+
+        let scope = self.scope.clone();
+        let files = tokio::task::spawn_blocking(move || scope.discover()).await??;
+
+        let mut known: HashMap<PathBuf, IndexedFile> = self
+            .indexer
+            .indexed_files_under(&root)?
+            .into_iter()
+            .map(|file| (file.path.clone(), file))
+            .collect();
+
+        let freshness = if force {
+            Freshness::Force
+        } else {
+            Freshness::Metadata
+        };
+
+        let mut report = SyncReport {
+            discovered: files.len(),
+            ..Default::default()
+        };
+        let candidates: Vec<(PathBuf, Option<IndexedFile>)> = files
+            .into_iter()
+            .map(|path| {
+                let known = known.remove(&path);
+                (path, known)
+            })
+            .collect();
+        // Whatever is left was indexed before but is no longer on disk
+        let stale: Vec<PathBuf> = known.into_keys().collect();
+
+        self.update(true, |progress| {
+            progress.begin(IndexPhase::Indexing, candidates.len() + stale.len())
+        });
+
+        for (path, known) in &candidates {
+            let outcome = self.refresh_file(path, known.as_ref(), freshness).await;
+            self.record(&mut report, outcome);
+        }
+        for path in &stale {
+            let outcome = self.remove_file(path).await;
+            self.record(&mut report, outcome);
+        }
+
+        report.elapsed_ms = start.elapsed().as_secs_f64() * 1000.0;
+        self.finish();
+
+        let registry = metrics::global();
+        registry.record(metrics::INDEX_SYNC_MS, report.elapsed_ms);
+        if let Err(e) = self
+            .status
+            .db
+            .get_connection()
+            .and_then(|conn| registry.flush(&conn))
+        {
+            warn!("Failed to persist index metrics: {}", e);
+        }
+
+        info!(
+            "Index sync for {}: {} indexed, {} unchanged, {} removed, {} errors in {:.0}ms",
+            root.display(),
+            report.indexed,
+            report.unchanged,
+            report.removed,
+            report.errors,
+            report.elapsed_ms
+        );
+
+        Ok(report)
+    }
+
+    /// Re-index a set of changed paths. Files are re-hashed, directories
+    /// are walked, and paths that no longer exist have their symbols (and
+    /// those of any indexed files beneath them) removed.
+    pub async fn apply_changes(&self, paths: &HashSet<PathBuf>) -> Result<SyncReport> {
+        let start = Instant::now();
+
+        let mut files = BTreeSet::new();
+        let mut gone = BTreeSet::new();
+        for path in paths {
+            if path.is_dir() {
+                let scope = self.scope.clone();
+                let dir = path.clone();
+                let found = tokio::task::spawn_blocking(move || scope.discover_in(&dir)).await??;
+                files.extend(found.into_iter().filter(|file| self.scope.contains(file)));
+            } else if path.is_file() {
+                if self.scope.contains(path) {
+                    files.insert(path.clone());
+                }
+            } else {
+                gone.extend(
+                    self.indexer
+                        .indexed_files_under(path)?
+                        .into_iter()
+                        .map(|file| file.path),
+                );
+            }
+        }
+
+        let mut report = SyncReport {
+            discovered: files.len(),
+            ..Default::default()
+        };
+
+        self.update(true, |progress| {
+            progress.begin(IndexPhase::Indexing, files.len() + gone.len());
+            progress.pending = 0;
+        });
+
+        for path in &files {
+            let known = self.indexer.indexed_file(path)?;
+            let outcome = self
+                .refresh_file(path, known.as_ref(), Freshness::Content)
+                .await;
+            self.record(&mut report, outcome);
+        }
+        for path in &gone {
+            let outcome = self.remove_file(path).await;
+            self.record(&mut report, outcome);
+        }
+
+        report.elapsed_ms = start.elapsed().as_secs_f64() * 1000.0;
+        self.finish();
+
+        debug!(
+            "Applied {} changed paths: {} indexed, {} removed",
+            paths.len(),
+            report.indexed,
+            report.removed
+        );
+
+        Ok(report)
+    }
+
+    /// Bring the index up to date, then watch the project and apply
+    /// debounced changes until `shutdown` resolves
+    pub async fn watch<F>(&self, shutdown: F) -> Result<()>
+    where
+        F: Future<Output = ()>,
+    {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let mut watcher = notify::recommended_watcher(move |res: notify::Result<Event>| {
+            let _ = tx.send(res);
+        })?;
+        watcher.watch(self.scope.root(), RecursiveMode::Recursive)?;
+        info!("Watching {} for index updates", self.scope.root().display());
+
+        self.update(true, |progress| progress.watching = true);
+
+        // Changes made during the initial sync queue up in the channel
+        if let Err(e) = self.sync(false).await {
+            self.update(true, |progress| progress.watching = false);
+            return Err(e);
+        }
+
+        tokio::pin!(shutdown);
+        let mut heartbeat = tokio::time::interval(self.config.heartbeat);
+        let mut batch = ChangeBatch::default();
+
+        loop {
+            let deadline = batch.deadline(&self.config);
+            let flush_at =
+                deadline.unwrap_or_else(|| tokio::time::Instant::now() + self.config.heartbeat);
+
+            tokio::select! {
+                _ = &mut shutdown => break,
+                event = rx.recv() => match event {
+                    Some(Ok(event)) => {
+                        batch.record(&event, &self.scope);
+                        let pending = batch.len();
+                        self.update(false, |progress| progress.pending = pending);
+                    }
+                    Some(Err(e)) => warn!("Index watch error: {}", e),
+                    None => break,
+                },
+                _ = tokio::time::sleep_until(flush_at), if deadline.is_some() => {
+                    let batch = std::mem::take(&mut batch);
+                    if let Err(e) = self.flush(batch).await {
+                        warn!("Failed to apply index changes: {}", e);
+                    }
+                }
+                _ = heartbeat.tick() => self.update(true, |_| {}),
+            }
+        }
+
+        drop(watcher);
+        self.update(true, |progress| {
+            progress.watching = false;
+            progress.pending = 0;
+        });
+        info!("Stopped watching {}", self.scope.root().display());
+
+        Ok(())
+    }
+
+    async fn flush(&self, batch: ChangeBatch) -> Result<()> {
+        if batch.branch_changed {
+            info!(
+                "Branch changed to {}, resyncing index",
+                current_branch(self.scope.root()).unwrap_or_else(|| "unknown".to_string())
+            );
+            self.sync(false).await?;
+        } else if batch.paths.len() > self.config.full_sync_threshold {
+            debug!(
+                "{} changed paths exceed the batch threshold, resyncing",
+                batch.paths.len()
+            );
+            self.sync(false).await?;
+        } else if !batch.paths.is_empty() {
+            self.apply_changes(&batch.paths).await?;
+        }
+        Ok(())
+    }
+
+    async fn refresh_file(
+        &self,
+        path: &Path,
+        known: Option<&IndexedFile>,
+        freshness: Freshness,
+    ) -> FileOutcome {
+        match self.try_refresh_file(path, known, freshness).await {
+            Ok(outcome) => outcome,
+            Err(e) => {
+                debug!("Failed to index {}: {}", path.display(), e);
+                FileOutcome::Failed
+            }
+        }
+    }
+
+    async fn try_refresh_file(
+        &self,
+        path: &Path,
+        known: Option<&IndexedFile>,
+        freshness: Freshness,
+    ) -> Result<FileOutcome> {
+        let metadata = tokio::fs::metadata(path).await?;
+        let size = metadata.len();
+        let modified = symbol_index::modified_nanos(&metadata);
+
+        if let Some(known) = known {
+            if freshness == Freshness::Metadata
+                && modified.is_some()
+                && known.size == size
+                && known.modified == modified
+            {
+                return Ok(FileOutcome::Unchanged);
+            }
+        }
+
+        let content = tokio::fs::read_to_string(path)
+            .await
+            .context("Failed to read file")?;
+
+        if let Some(known) = known {
+            if freshness != Freshness::Force
+                && known.content_hash == symbol_index::content_hash(&content)
+            {
+                self.indexer.touch_file(path, size, modified)?;
+                return Ok(FileOutcome::Unchanged);
+            }
+        }
+
+        let symbols = self.indexer.index_file(path, &content).await?;
+        Ok(FileOutcome::Indexed(symbols))
+    }
+
+    async fn remove_file(&self, path: &Path) -> FileOutcome {
+        match self.indexer.remove_file(path).await {
+            Ok(_) => FileOutcome::Removed,
+            Err(e) => {
+                debug!("Failed to remove {} from index: {}", path.display(), e);
+                FileOutcome::Failed
+            }
+        }
+    }
+
+    fn record(&self, report: &mut SyncReport, outcome: FileOutcome) {
+        match outcome {
+            FileOutcome::Indexed(symbols) => {
+                report.indexed += 1;
+                report.symbols += symbols;
+            }
+            FileOutcome::Unchanged => report.unchanged += 1,
+            FileOutcome::Removed => report.removed += 1,
+            FileOutcome::Failed => report.errors += 1,
+        }
+
+        self.update(false, |progress| {
+            progress.processed += 1;
+            progress.indexed = report.indexed;
+            progress.unchanged = report.unchanged;
+            progress.removed = report.removed;
+            progress.errors = report.errors;
+        });
+    }
+
+    fn finish(&self) {
+        self.update(true, |progress| {
+            progress.phase = IndexPhase::Idle;
+            progress.last_sync = Some(Utc::now());
+        });
+    }
+
+    /// Apply a progress change, notify subscribers and persist it (always
+    /// when `persist` is set, otherwise at most every [`PERSIST_INTERVAL`])
+    fn update(&self, persist: bool, change: impl FnOnce(&mut IndexProgress)) {
+        self.progress.send_modify(|progress| {
+            change(progress);
+            progress.updated_at = Utc::now();
+        });
+
+        let due = {
+            let mut last = self
+                .last_persist
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            let due = persist || !last.is_some_and(|at| at.elapsed() < PERSIST_INTERVAL);
+            if due {
+                *last = Some(Instant::now());
+            }
+            due
+        };
+
+        if due {
+            let snapshot = self.progress();
+            if let Err(e) = self.status.save(&self.scope, &snapshot) {
+                debug!("Failed to persist index progress: {}", e);
+            }
+        }
+    }
+}
+
+/// Make sure the index covering `path` reflects the working tree.
+///
+/// When a live watcher owns the project its progress is returned as-is;
+/// otherwise a previously indexed project is resynced (cheaply, using the
+/// stored fingerprints). Returns `None` if `path` was never indexed.
+pub async fn ensure_current(
+    db: Arc<DatabaseManager>,
+    indexer: Arc<SymbolIndexer>,
+    path: &Path,
+) -> Result<Option<IndexFreshness>> {
+    let store = IndexStatusStore::new(db.clone())?;
+    let Some(status) = store.covering(path)? else {
+        return Ok(None);
+    };
+
+    if status.progress.is_live() {
+        return Ok(Some(IndexFreshness::Watched(status.progress)));
+    }
+
+    let scope = status
+        .scope()
+        .map_err(|e| anyhow!("Indexed project is no longer available: {}", e))?;
+    let report = BackgroundIndexer::new(db, indexer, scope)?
+        .sync(false)
+        .await?;
+    Ok(Some(IndexFreshness::Refreshed(report)))
+}
+
+/// How [`ensure_current`] brought the index up to date
+#[derive(Debug, Clone)]
+pub enum IndexFreshness {
+    /// A background watcher keeps the project current
+    Watched(IndexProgress),
+    /// No watcher was running, so the project was resynced in-process
+    Refreshed(SyncReport),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::database::DatabaseConfig;
+    use std::fs;
+    use tempfile::TempDir;
+
+    struct Fixture {
+        _db_dir: TempDir,
+        repo: TempDir,
+        db: Arc<DatabaseManager>,
+        indexer: Arc<SymbolIndexer>,
+    }
+
+    impl Fixture {
+        async fn new() -> Result<Self> {
+            let db_dir = TempDir::new()?;
+            let config = DatabaseConfig {
+                path: db_dir.path().join("index.db"),
+                ..DatabaseConfig::default()
+            };
+            let db = Arc::new(DatabaseManager::new(config).await?);
+            let indexer = Arc::new(SymbolIndexer::new(db.clone()).await?);
+            Ok(Self {
+                _db_dir: db_dir,
+                repo: TempDir::new()?,
+                db,
+                indexer,
+            })
+        }
+
+        fn write(&self, relative: &str, content: &str) -> PathBuf {
+            let path = self.repo.path().join(relative);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(&path, content).unwrap();
+            path.canonicalize().unwrap()
+        }
+
+        fn background(&self) -> Result<BackgroundIndexer> {
+            BackgroundIndexer::new(
+                self.db.clone(),
+                self.indexer.clone(),
+                IndexScope::new(self.repo.path())?,
+            )
+        }
+    }
+
+    fn event(kind: EventKind, path: PathBuf) -> Event {
+        Event::new(kind).add_path(path)
+    }
+
+    #[tokio::test]
+    async fn test_sync_skips_unchanged_and_drops_deleted_files() -> Result<()> {
+        let fixture = Fixture::new().await?;
+        fixture.write("src/lib.rs", "pub fn alpha() {}\n");
+        let beta = fixture.write("src/beta.rs", "pub fn beta() {}\n");
+        let background = fixture.background()?;
+
+        let first = background.sync(false).await?;
+        assert_eq!(first.indexed, 2);
+        assert!(!fixture.indexer.search("beta", 10).await?.is_empty());
+
+        let second = background.sync(false).await?;
+        assert_eq!(second.indexed, 0);
+        assert_eq!(second.unchanged, 2);
+
+        fs::write(&beta, "pub fn beta() {}\npub fn gamma() {}\n")?;
+        let third = background.sync(false).await?;
+        assert_eq!(third.indexed, 1);
+        assert!(!fixture.indexer.search("gamma", 10).await?.is_empty());
+
+        fs::remove_file(&beta)?;
+        let fourth = background.sync(false).await?;
+        assert_eq!(fourth.removed, 1);
+        assert!(fixture.indexer.search("gamma", 10).await?.is_empty());
+        assert!(fixture.indexer.indexed_file(&beta)?.is_none());
+
+        let progress = background.progress();
+        assert_eq!(progress.phase, IndexPhase::Idle);
+        assert!(progress.last_sync.is_some());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_apply_changes_rehashes_and_removes_directories() -> Result<()> {
+        let fixture = Fixture::new().await?;
+        let lib = fixture.write("src/lib.rs", "pub fn alpha() {}\n");
+        let nested = fixture.write("src/nested/mod.rs", "pub fn nested() {}\n");
+        let background = fixture.background()?;
+        background.sync(false).await?;
+
+        // Rewriting identical content is detected by the hash
+        fs::write(&lib, "pub fn alpha() {}\n")?;
+        let report = background
+            .apply_changes(&HashSet::from([lib.clone()]))
+            .await?;
+        assert_eq!(report.unchanged, 1);
+        assert_eq!(report.indexed, 0);
+
+        let nested_dir = nested.parent().unwrap().to_path_buf();
+        fs::remove_dir_all(&nested_dir)?;
+        let report = background
+            .apply_changes(&HashSet::from([nested_dir]))
+            .await?;
+        assert_eq!(report.removed, 1);
+        assert!(fixture.indexer.search("nested", 10).await?.is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_status_is_persisted_and_found_for_subdirectories() -> Result<()> {
+        let fixture = Fixture::new().await?;
+        let lib = fixture.write("src/lib.rs", "pub fn alpha() {}\n");
+        fixture.background()?.sync(false).await?;
+
+        let store = IndexStatusStore::new(fixture.db.clone())?;
+        let status = store
+            .covering(lib.parent().unwrap())?
+            .expect("project status");
+        assert_eq!(status.root, fixture.repo.path().canonicalize()?);
+        assert_eq!(status.progress.indexed, 1);
+        assert!(!status.progress.is_live());
+
+        let other = TempDir::new()?;
+        assert!(store.covering(other.path())?.is_none());
+
+        // A stale project is resynced in place
+        fs::write(&lib, "pub fn alpha() {}\npub fn delta() {}\n")?;
+        let freshness = ensure_current(fixture.db.clone(), fixture.indexer.clone(), &lib).await?;
+        match freshness {
+            Some(IndexFreshness::Refreshed(report)) => assert_eq!(report.indexed, 1),
+            other => panic!("unexpected freshness: {:?}", other),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_scope_filters_watch_paths() -> Result<()> {
+        let repo = TempDir::new()?;
+        fs::write(repo.path().join(".gitignore"), "generated/\n")?;
+        let scope = IndexScope::new(repo.path())?;
+        let root = scope.root().to_path_buf();
+
+        assert!(scope.contains(&root.join("src/lib.rs")));
+        assert!(scope.contains(&root.join("queries/report.sql")));
+        assert!(!scope.contains(&root.join("README.md")));
+        assert!(!scope.contains(&root.join("target/debug/build.rs")));
+        assert!(!scope.contains(&root.join(".hidden/lib.rs")));
+        assert!(!scope.contains(&root.join("tests/it.rs")));
+        assert!(!scope.contains(&root.join("generated/api.rs")));
+        assert!(!scope.contains(Path::new("/elsewhere/lib.rs")));
+
+        let scope = scope
+            .include_tests(true)
+            .exclude(vec!["vendor".to_string()]);
+        assert!(scope.contains(&root.join("tests/it.rs")));
+        assert!(!scope.contains(&root.join("vendor/lib.rs")));
+        Ok(())
+    }
+
+    #[test]
+    fn test_change_batch_debounces_and_detects_branch_switch() -> Result<()> {
+        let repo = TempDir::new()?;
+        let scope = IndexScope::new(repo.path())?;
+        let root = scope.root().to_path_buf();
+        let config = WatchConfig::default();
+
+        let mut batch = ChangeBatch::default();
+        assert!(batch.deadline(&config).is_none());
+
+        batch.record(
+            &event(
+                EventKind::Access(notify::event::AccessKind::Any),
+                root.join("a.rs"),
+            ),
+            &scope,
+        );
+        assert!(batch.deadline(&config).is_none());
+
+        let modify = EventKind::Modify(notify::event::ModifyKind::Any);
+        batch.record(&event(modify, root.join("src/a.rs")), &scope);
+        batch.record(&event(modify, root.join("target/out.rs")), &scope);
+        batch.record(&event(modify, root.join(".git/index")), &scope);
+        assert_eq!(batch.len(), 1);
+        assert!(!batch.branch_changed);
+
+        let deadline = batch.deadline(&config).expect("pending batch");
+        assert!(deadline <= batch.first_change.unwrap() + config.max_batch_delay);
+
+        batch.record(&event(modify, root.join(".git/HEAD")), &scope);
+        assert!(batch.branch_changed);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_head() {
+        assert_eq!(
+            parse_head("ref: refs/heads/feature/index\n"),
+            Some("feature/index".to_string())
+        );
+        assert_eq!(
+            parse_head("0123456789abcdef0123456789abcdef01234567\n"),
+            Some("01234567".to_string())
+        );
+        assert_eq!(parse_head(""), None);
+    }
+}
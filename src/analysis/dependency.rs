@@ -19,6 +19,7 @@ use tokio::sync::RwLock;
 use tracing::{debug, info, instrument};
 
 use crate::analysis::parser::TreeSitterParser;
+use crate::analysis::walker::RepositoryWalker;
 use crate::core::ast::{ImportInfo, ParseResult};
 use crate::core::Language;
 
@@ -213,36 +214,13 @@ impl DependencyAnalyzer {
 
     /// Discover all modules in a project
     async fn discover_modules(&self, root_path: &Path) -> Result<Vec<PathBuf>> {
-        let mut modules = Vec::new();
-        let mut stack = vec![root_path.to_path_buf()];
-
-        while let Some(dir) = stack.pop() {
-            let mut entries = tokio::fs::read_dir(&dir).await?;
-
-            while let Some(entry) = entries.next_entry().await? {
-                let path = entry.path();
-
-                if path.is_dir() {
-                    let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
-
-                    // Skip common non-source directories
-                    if !matches!(
-                        name,
-                        ".git"
-                            | "target"
-                            | "node_modules"
-                            | ".venv"
-                            | "__pycache__"
-                            | "dist"
-                            | "build"
-                    ) {
-                        stack.push(path);
-                    }
-                } else if self.is_source_file(&path) {
-                    modules.push(path);
-                }
-            }
-        }
+        let modules = RepositoryWalker::new(root_path)
+            .walk_async()
+            .await?
+            .into_iter()
+            .map(|file| file.path)
+            .filter(|path| self.is_source_file(path))
+            .collect();
 
         Ok(modules)
     }
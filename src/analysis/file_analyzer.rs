@@ -2,7 +2,8 @@
 
 use anyhow::Result;
 use std::path::{Path, PathBuf};
-use walkdir::WalkDir;
+
+use super::walker::RepositoryWalker;
 
 /// Analyzes files to find relevant targets for transformations
 pub struct FileAnalyzer;
@@ -25,53 +26,23 @@ impl FileAnalyzer {
             "zsh", "fish", "ps1", "psm1", "psd1",
         ];
 
-        // Walk directory
-        for entry in WalkDir::new(path)
+        // Walk directory (ignore files, build/dependency directories and
+        // hidden entries are handled by the shared walker)
+        let walked = RepositoryWalker::new(path)
             .follow_links(true)
-            .into_iter()
-            .filter_map(|e| e.ok())
-        {
-            let path = entry.path();
-
-            // Skip hidden directories and common build/dependency directories
-            if path.is_dir() {
-                let dir_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
-
-                if dir_name.starts_with('.')
-                    || matches!(
-                        dir_name,
-                        "target"
-                            | "node_modules"
-                            | "dist"
-                            | "build"
-                            | "__pycache__"
-                            | ".git"
-                            | ".svn"
-                            | "vendor"
-                            | "deps"
-                    )
-                {
+            .extensions(&extensions)
+            .walk_async()
+            .await?;
+
+        let query_lower = query.map(|q| q.to_lowercase());
+        for file in walked {
+            // If query is provided, check if file name or path contains it
+            if let Some(q) = &query_lower {
+                if !file.path.to_string_lossy().to_lowercase().contains(q) {
                     continue;
                 }
             }
-
-            // Check if it's a file with a relevant extension
-            if path.is_file() {
-                if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
-                    if extensions.contains(&ext) {
-                        // If query is provided, check if file name or path contains it
-                        if let Some(q) = query {
-                            let path_str = path.to_string_lossy().to_lowercase();
-                            let query_lower = q.to_lowercase();
-                            if path_str.contains(&query_lower) {
-                                files.push(path.to_path_buf());
-                            }
-                        } else {
-                            files.push(path.to_path_buf());
-                        }
-                    }
-                }
-            }
+            files.push(file.path);
         }
 
         // Sort by path for consistent ordering
@@ -6,6 +6,7 @@
 //! - Shebang lines
 //! - Magic bytes
 
+use crate::core::language;
 use crate::core::Language;
 use anyhow::{anyhow, Result};
 use std::path::Path;
@@ -28,63 +29,21 @@ impl LanguageDetector {
     /// Create a new language detector
     pub fn new() -> Self {
         let mut extension_map = std::collections::HashMap::new();
+        let mut filename_map = std::collections::HashMap::new();
 
-        // Rust
-        extension_map.insert("rs".to_string(), Language::Rust);
-
-        // JavaScript/TypeScript
-        extension_map.insert("js".to_string(), Language::JavaScript);
-        extension_map.insert("jsx".to_string(), Language::JavaScript);
-        extension_map.insert("mjs".to_string(), Language::JavaScript);
-        extension_map.insert("cjs".to_string(), Language::JavaScript);
-        extension_map.insert("ts".to_string(), Language::TypeScript);
-        extension_map.insert("tsx".to_string(), Language::TypeScript);
-
-        // Python
-        extension_map.insert("py".to_string(), Language::Python);
-        extension_map.insert("pyw".to_string(), Language::Python);
-        extension_map.insert("pyi".to_string(), Language::Python);
-
-        // Go
-        extension_map.insert("go".to_string(), Language::Go);
-
-        // Java
-        extension_map.insert("java".to_string(), Language::Java);
-
-        // C/C++
-        extension_map.insert("c".to_string(), Language::C);
-        extension_map.insert("h".to_string(), Language::C);
-        extension_map.insert("cpp".to_string(), Language::Cpp);
-        extension_map.insert("cxx".to_string(), Language::Cpp);
-        extension_map.insert("cc".to_string(), Language::Cpp);
-        extension_map.insert("hpp".to_string(), Language::Cpp);
-        extension_map.insert("hxx".to_string(), Language::Cpp);
-        extension_map.insert("hh".to_string(), Language::Cpp);
-        extension_map.insert("c++".to_string(), Language::Cpp);
-        extension_map.insert("h++".to_string(), Language::Cpp);
-
-        // Ruby
-        extension_map.insert("rb".to_string(), Language::Ruby);
-        extension_map.insert("rake".to_string(), Language::Ruby);
-
-        // PHP
-        extension_map.insert("php".to_string(), Language::PHP);
-        extension_map.insert("php3".to_string(), Language::PHP);
-        extension_map.insert("php4".to_string(), Language::PHP);
-        extension_map.insert("php5".to_string(), Language::PHP);
-        extension_map.insert("php7".to_string(), Language::PHP);
-        extension_map.insert("phtml".to_string(), Language::PHP);
-
-        // Swift
-        extension_map.insert("swift".to_string(), Language::Swift);
+        for info in language::all() {
+            for ext in info.extensions {
+                extension_map.insert(ext.to_string(), info.language);
+            }
+            for filename in info.filenames {
+                filename_map.insert(filename.to_string(), info.language);
+            }
+        }
 
-        let mut filename_map = std::collections::HashMap::new();
-        filename_map.insert("Makefile".to_string(), Language::Unknown);
-        filename_map.insert("makefile".to_string(), Language::Unknown);
-        filename_map.insert("Dockerfile".to_string(), Language::Unknown);
-        filename_map.insert("dockerfile".to_string(), Language::Unknown);
-        filename_map.insert("Rakefile".to_string(), Language::Ruby);
-        filename_map.insert("Gemfile".to_string(), Language::Ruby);
+        // Recognised build files without a supported grammar
+        for filename in ["Makefile", "makefile", "Dockerfile", "dockerfile"] {
+            filename_map.insert(filename.to_string(), Language::Unknown);
+        }
 
         Self {
             extension_map,
@@ -139,21 +98,7 @@ impl LanguageDetector {
             return None;
         }
 
-        let shebang = first_line.to_lowercase();
-
-        if shebang.contains("python") || shebang.contains("python3") {
-            Some(Language::Python)
-        } else if shebang.contains("node") || shebang.contains("deno") {
-            Some(Language::JavaScript)
-        } else if shebang.contains("ruby") {
-            Some(Language::Ruby)
-        } else if shebang.contains("php") {
-            Some(Language::PHP)
-        } else if shebang.contains("bash") || shebang.contains("sh") {
-            Some(Language::Unknown) // Shell scripts
-        } else {
-            None
-        }
+        language::by_shebang(&first_line.to_lowercase()).map(|info| info.language)
     }
 
     /// Detect language from path or content (convenience method)
@@ -281,62 +226,6 @@ impl Default for LanguageDetector {
     }
 }
 
-// Add Ruby, PHP, and Swift to the Language enum in core/mod.rs
-impl Language {
-    /// Get all supported languages
-    pub fn all() -> Vec<Language> {
-        vec![
-            Language::Rust,
-            Language::TypeScript,
-            Language::JavaScript,
-            Language::Python,
-            Language::Go,
-            Language::Java,
-            Language::Cpp,
-            Language::C,
-            Language::Ruby,
-            Language::PHP,
-            Language::Swift,
-        ]
-    }
-
-    /// Get file extensions for a language
-    pub fn extensions(&self) -> Vec<&'static str> {
-        match self {
-            Language::Rust => vec!["rs"],
-            Language::TypeScript => vec!["ts", "tsx"],
-            Language::JavaScript => vec!["js", "jsx", "mjs", "cjs"],
-            Language::Python => vec!["py", "pyw", "pyi"],
-            Language::Go => vec!["go"],
-            Language::Java => vec!["java"],
-            Language::Cpp => vec!["cpp", "cxx", "cc", "hpp", "hxx", "hh", "c++", "h++"],
-            Language::C => vec!["c", "h"],
-            Language::Ruby => vec!["rb", "rake"],
-            Language::PHP => vec!["php", "php3", "php4", "php5", "php7", "phtml"],
-            Language::Swift => vec!["swift"],
-            Language::Unknown => vec![],
-        }
-    }
-
-    /// Get display name for a language
-    pub fn display_name(&self) -> &'static str {
-        match self {
-            Language::Rust => "Rust",
-            Language::TypeScript => "TypeScript",
-            Language::JavaScript => "JavaScript",
-            Language::Python => "Python",
-            Language::Go => "Go",
-            Language::Java => "Java",
-            Language::Cpp => "C++",
-            Language::C => "C",
-            Language::Ruby => "Ruby",
-            Language::PHP => "PHP",
-            Language::Swift => "Swift",
-            Language::Unknown => "Unknown",
-        }
-    }
-}
-
 #[cfg(all(test, feature = "legacy-tests"))]
 mod tests {
     use super::*;
@@ -0,0 +1,824 @@
+//! Language registry
+//!
+//! How Hive extracts structure from each language: a tree-sitter grammar with
+//! symbol, import and highlight queries, or line patterns for formats without
+//! a usable grammar. Recognising files (extensions, filenames, shebangs) and
+//! comment syntax live one layer down in `crate::core::language`.
+//!
+//! Adding a language means adding a `Language` variant, its `LanguageInfo`
+//! in `core::language` and one `LanguageSpec` entry here.
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::collections::HashMap;
+
+use crate::core::ast::{
+    AstNode, CodeMetrics, ImportInfo, NodeMetadata, ParseResult, Symbol, SymbolKind,
+};
+use crate::core::language::{self, LanguageInfo};
+use crate::core::{Language, Position};
+
+/// How Hive parses a language
+pub struct LanguageSpec {
+    pub language: Language,
+    pub backend: Backend,
+}
+
+/// How structure is extracted from a language
+pub enum Backend {
+    /// Tree-sitter grammar with queries
+    TreeSitter(Grammar),
+    /// Line-oriented regular expressions
+    Patterns(PatternRules),
+    /// Detection and metrics only
+    None,
+}
+
+/// Tree-sitter grammar and the queries run against it. Empty queries are
+/// treated as absent.
+pub struct Grammar {
+    pub language: fn() -> tree_sitter::Language,
+    pub symbols: &'static str,
+    pub imports: &'static str,
+    pub highlights: &'static str,
+}
+
+/// Line patterns for languages parsed without tree-sitter. Each regex must
+/// have a `name` capture group; import regexes need a `module` group.
+pub struct PatternRules {
+    pub symbols: &'static [(SymbolKind, &'static str)],
+    pub imports: &'static [&'static str],
+}
+
+impl LanguageSpec {
+    /// Identity and comment syntax of the language
+    pub fn info(&self) -> &'static LanguageInfo {
+        language::info(self.language).expect("every parsed language has a LanguageInfo")
+    }
+
+    /// Tree-sitter grammar, if the language has one
+    pub fn grammar(&self) -> Option<&Grammar> {
+        match &self.backend {
+            Backend::TreeSitter(grammar) => Some(grammar),
+            _ => None,
+        }
+    }
+
+    /// Line patterns, if the language is parsed without tree-sitter
+    pub fn patterns(&self) -> Option<&PatternRules> {
+        match &self.backend {
+            Backend::Patterns(rules) => Some(rules),
+            _ => None,
+        }
+    }
+}
+
+static SPECS: &[LanguageSpec] = &[
+    LanguageSpec {
+        language: Language::Rust,
+        backend: Backend::TreeSitter(Grammar {
+            language: tree_sitter_rust::language,
+            symbols: r#"
+            (function_item name: (identifier) @function.name) @function
+            (struct_item name: (type_identifier) @struct.name) @struct
+            (enum_item name: (type_identifier) @enum.name) @enum
+            (trait_item name: (type_identifier) @trait.name) @trait
+            (impl_item type: (type_identifier) @impl.type) @impl
+            (const_item name: (identifier) @const.name) @const
+            (static_item name: (identifier) @static.name) @static
+            (mod_item name: (identifier) @module.name) @module
+            (type_alias name: (type_identifier) @type_alias.name) @type_alias
+            "#,
+            imports: r#"
+            (use_declaration) @import
+            "#,
+            highlights: r#"
+            "fn" @keyword.function
+            "let" @keyword
+            "mut" @keyword
+            "const" @keyword
+            "static" @keyword
+            "if" @keyword.control
+            "else" @keyword.control
+            "match" @keyword.control
+            "for" @keyword.control
+            "while" @keyword.control
+            "loop" @keyword.control
+            "return" @keyword.control
+            "break" @keyword.control
+            "continue" @keyword.control
+            (string_literal) @string
+            (integer_literal) @number
+            (float_literal) @number
+            (line_comment) @comment
+            (block_comment) @comment
+            (identifier) @variable
+            (type_identifier) @type
+            "#,
+        }),
+    },
+    LanguageSpec {
+        language: Language::TypeScript,
+        backend: Backend::TreeSitter(Grammar {
+            language: tree_sitter_typescript::language_typescript,
+            symbols: ECMASCRIPT_SYMBOLS,
+            imports: ECMASCRIPT_IMPORTS,
+            highlights: ECMASCRIPT_HIGHLIGHTS,
+        }),
+    },
+    LanguageSpec {
+        language: Language::JavaScript,
+        backend: Backend::TreeSitter(Grammar {
+            language: tree_sitter_javascript::language,
+            symbols: ECMASCRIPT_SYMBOLS,
+            imports: ECMASCRIPT_IMPORTS,
+            highlights: ECMASCRIPT_HIGHLIGHTS,
+        }),
+    },
+    LanguageSpec {
+        language: Language::Python,
+        backend: Backend::TreeSitter(Grammar {
+            language: tree_sitter_python::language,
+            symbols: r#"
+            (function_definition name: (identifier) @function.name) @function
+            (class_definition name: (identifier) @class.name) @class
+            "#,
+            imports: r#"
+            (import_statement) @import
+            (import_from_statement) @import
+            "#,
+            highlights: r#"
+            ["def" "class" "if" "elif" "else" "for" "while" "try" "except" "finally" "with" "return" "break" "continue"] @keyword
+            (string) @string
+            (integer) @number
+            (float) @number
+            (comment) @comment
+            (identifier) @variable
+            "#,
+        }),
+    },
+    LanguageSpec {
+        language: Language::Go,
+        backend: Backend::TreeSitter(Grammar {
+            language: tree_sitter_go::language,
+            symbols: r#"
+            (function_declaration name: (identifier) @function.name) @function
+            (type_declaration (type_spec name: (type_identifier) @type.name)) @type
+            (const_declaration (const_spec name: (identifier) @const.name)) @const
+            (var_declaration (var_spec name: (identifier) @var.name)) @var
+            "#,
+            imports: r#"
+            (import_declaration) @import
+            "#,
+            highlights: r#"
+            ["func" "type" "struct" "interface" "const" "var" "if" "else" "for" "switch" "case" "return" "break" "continue"] @keyword
+            (string_literal) @string
+            (int_literal) @number
+            (float_literal) @number
+            (comment) @comment
+            (identifier) @variable
+            (type_identifier) @type
+            "#,
+        }),
+    },
+    LanguageSpec {
+        language: Language::Java,
+        backend: Backend::TreeSitter(Grammar {
+            language: tree_sitter_java::language,
+            symbols: r#"
+            (class_declaration name: (identifier) @class.name) @class
+            (interface_declaration name: (identifier) @interface.name) @interface
+            (method_declaration name: (identifier) @method.name) @method
+            (field_declaration declarator: (variable_declarator name: (identifier) @field.name)) @field
+            "#,
+            imports: r#"
+            (import_declaration) @import
+            "#,
+            highlights: r#"
+            ["class" "interface" "public" "private" "protected" "static" "final" "if" "else" "for" "while" "switch" "case" "return" "break" "continue"] @keyword
+            (string_literal) @string
+            (decimal_integer_literal) @number
+            (decimal_floating_point_literal) @number
+            (comment) @comment
+            (identifier) @variable
+            (type_identifier) @type
+            "#,
+        }),
+    },
+    LanguageSpec {
+        language: Language::Cpp,
+        backend: Backend::TreeSitter(Grammar {
+            language: tree_sitter_cpp::language,
+            symbols: C_FAMILY_SYMBOLS,
+            imports: C_FAMILY_IMPORTS,
+            highlights: C_FAMILY_HIGHLIGHTS,
+        }),
+    },
+    LanguageSpec {
+        language: Language::C,
+        backend: Backend::TreeSitter(Grammar {
+            language: tree_sitter_c::language,
+            symbols: C_FAMILY_SYMBOLS,
+            imports: C_FAMILY_IMPORTS,
+            highlights: C_FAMILY_HIGHLIGHTS,
+        }),
+    },
+    LanguageSpec {
+        language: Language::Ruby,
+        backend: Backend::TreeSitter(Grammar {
+            language: tree_sitter_ruby::language,
+            symbols: r#"
+            (method name: (identifier) @method.name) @method
+            (class name: (constant) @class.name) @class
+            (module name: (constant) @module.name) @module
+            (assignment left: (identifier) @variable.name) @variable
+            "#,
+            imports: r#"
+            (call method: (identifier) @method (#match? @method "^(require|require_relative|load)$")) @import
+            "#,
+            highlights: r#"
+            ["def" "class" "module" "end" "if" "elsif" "else" "unless" "case" "when" "for" "while" "until" "return" "break" "next"] @keyword
+            (string) @string
+            (integer) @number
+            (float) @number
+            (comment) @comment
+            (identifier) @variable
+            (constant) @type
+            "#,
+        }),
+    },
+    LanguageSpec {
+        language: Language::PHP,
+        backend: Backend::TreeSitter(Grammar {
+            language: tree_sitter_php::language_php,
+            symbols: r#"
+            (function_definition name: (name) @function.name) @function
+            (class_declaration name: (name) @class.name) @class
+            (method_declaration name: (name) @method.name) @method
+            (property_declaration (property_element name: (variable_name) @property.name)) @property
+            "#,
+            imports: r#"
+            (include_expression) @import
+            (require_expression) @import
+            (include_once_expression) @import
+            (require_once_expression) @import
+            "#,
+            highlights: r#"
+            ["function" "class" "interface" "trait" "public" "private" "protected" "static" "final" "abstract" "if" "else" "elseif" "for" "foreach" "while" "do" "switch" "case" "return" "break" "continue"] @keyword
+            (string) @string
+            (integer) @number
+            (float) @number
+            (comment) @comment
+            (variable_name) @variable
+            (name) @type
+            "#,
+        }),
+    },
+    // No Swift grammar builds against tree-sitter 0.20 yet
+    LanguageSpec {
+        language: Language::Swift,
+        backend: Backend::None,
+    },
+    LanguageSpec {
+        language: Language::Kotlin,
+        backend: Backend::TreeSitter(Grammar {
+            language: tree_sitter_kotlin::language,
+            symbols: r#"
+            (class_declaration (type_identifier) @class.name) @class
+            (object_declaration (type_identifier) @class.name) @class
+            (function_declaration (simple_identifier) @function.name) @function
+            (property_declaration (variable_declaration (simple_identifier) @variable.name)) @variable
+            (type_alias (type_identifier) @type_alias.name) @type_alias
+            "#,
+            imports: r#"
+            (import_header) @import
+            "#,
+            highlights: r#"
+            ["fun" "val" "var" "class" "object" "interface" "if" "else" "when" "for" "while" "do" "try" "catch" "finally" "return"] @keyword
+            (string_literal) @string
+            (integer_literal) @number
+            (real_literal) @number
+            (line_comment) @comment
+            (multiline_comment) @comment
+            (simple_identifier) @variable
+            (type_identifier) @type
+            "#,
+        }),
+    },
+    LanguageSpec {
+        language: Language::CSharp,
+        backend: Backend::TreeSitter(Grammar {
+            language: tree_sitter_c_sharp::language,
+            symbols: r#"
+            (class_declaration name: (identifier) @class.name) @class
+            (record_declaration name: (identifier) @class.name) @class
+            (interface_declaration name: (identifier) @interface.name) @interface
+            (struct_declaration name: (identifier) @struct.name) @struct
+            (enum_declaration name: (identifier) @enum.name) @enum
+            (method_declaration name: (identifier) @method.name) @method
+            (property_declaration name: (identifier) @property.name) @property
+            (namespace_declaration name: (_) @namespace.name) @namespace
+            "#,
+            imports: r#"
+            (using_directive) @import
+            "#,
+            highlights: r#"
+            ["class" "interface" "struct" "enum" "namespace" "using" "public" "private" "protected" "internal" "static" "readonly" "async" "await" "if" "else" "for" "foreach" "while" "switch" "case" "return" "break" "continue"] @keyword
+            (string_literal) @string
+            (integer_literal) @number
+            (real_literal) @number
+            (comment) @comment
+            (identifier) @variable
+            "#,
+        }),
+    },
+    LanguageSpec {
+        language: Language::Scala,
+        backend: Backend::TreeSitter(Grammar {
+            language: tree_sitter_scala::language,
+            symbols: r#"
+            (class_definition name: (identifier) @class.name) @class
+            (object_definition name: (identifier) @class.name) @class
+            (trait_definition name: (identifier) @trait.name) @trait
+            (function_definition name: (identifier) @function.name) @function
+            (val_definition pattern: (identifier) @const.name) @const
+            (type_definition name: (type_identifier) @type_alias.name) @type_alias
+            "#,
+            imports: r#"
+            (import_declaration) @import
+            "#,
+            highlights: r#"
+            ["def" "val" "var" "class" "object" "trait" "extends" "with" "if" "else" "match" "case" "for" "while" "return"] @keyword
+            (string) @string
+            (integer_literal) @number
+            (floating_point_literal) @number
+            (comment) @comment
+            (identifier) @variable
+            (type_identifier) @type
+            "#,
+        }),
+    },
+    LanguageSpec {
+        language: Language::Bash,
+        backend: Backend::TreeSitter(Grammar {
+            language: tree_sitter_bash::language,
+            symbols: r#"
+            (function_definition name: (word) @function.name) @function
+            (variable_assignment name: (variable_name) @variable.name) @variable
+            "#,
+            imports: r#"
+            (command name: (command_name (word) @command (#match? @command "^(source|\\.)$"))) @import
+            "#,
+            highlights: r#"
+            ["if" "then" "else" "elif" "fi" "for" "while" "do" "done" "case" "esac" "function" "in"] @keyword
+            (string) @string
+            (raw_string) @string
+            (comment) @comment
+            (variable_name) @variable
+            (command_name) @function
+            "#,
+        }),
+    },
+    LanguageSpec {
+        language: Language::Sql,
+        backend: Backend::Patterns(PatternRules {
+            symbols: &[
+                (
+                    SymbolKind::Struct,
+                    r#"(?i)^\s*create\s+(?:or\s+replace\s+)?(?:temp(?:orary)?\s+)?table\s+(?:if\s+not\s+exists\s+)?(?P<name>[\w."`\[\]]+)"#,
+                ),
+                (
+                    SymbolKind::Class,
+                    r#"(?i)^\s*create\s+(?:or\s+replace\s+)?(?:materialized\s+)?view\s+(?:if\s+not\s+exists\s+)?(?P<name>[\w."`\[\]]+)"#,
+                ),
+                (
+                    SymbolKind::Function,
+                    r#"(?i)^\s*create\s+(?:or\s+replace\s+)?(?:function|procedure)\s+(?:if\s+not\s+exists\s+)?(?P<name>[\w."`\[\]]+)"#,
+                ),
+                (
+                    SymbolKind::Method,
+                    r#"(?i)^\s*create\s+(?:or\s+replace\s+)?trigger\s+(?:if\s+not\s+exists\s+)?(?P<name>[\w."`\[\]]+)"#,
+                ),
+                (
+                    SymbolKind::Property,
+                    r#"(?i)^\s*create\s+(?:unique\s+)?index\s+(?:if\s+not\s+exists\s+)?(?P<name>[\w."`\[\]]+)"#,
+                ),
+                (
+                    SymbolKind::TypeAlias,
+                    r#"(?i)^\s*create\s+type\s+(?P<name>[\w."`\[\]]+)"#,
+                ),
+            ],
+            imports: &[
+                // psql includes
+                r#"^\s*\\i[r]?\s+(?P<module>\S+)"#,
+            ],
+        }),
+    },
+    LanguageSpec {
+        language: Language::Toml,
+        backend: Backend::TreeSitter(Grammar {
+            language: tree_sitter_toml::language,
+            symbols: r#"
+            (table [(bare_key) (dotted_key) (quoted_key)] @module.name) @module
+            (table_array_element [(bare_key) (dotted_key) (quoted_key)] @module.name) @module
+            (pair [(bare_key) (dotted_key) (quoted_key)] @property.name) @property
+            "#,
+            imports: "",
+            highlights: r#"
+            (string) @string
+            (integer) @number
+            (float) @number
+            (boolean) @constant
+            (comment) @comment
+            (bare_key) @property
+            "#,
+        }),
+    },
+    LanguageSpec {
+        language: Language::Yaml,
+        backend: Backend::Patterns(PatternRules {
+            symbols: &[
+                // Top-level keys
+                (
+                    SymbolKind::Module,
+                    r#"^(?P<name>[A-Za-z_][\w.\-]*|"[^"]+"|'[^']+')\s*:(?:\s|$)"#,
+                ),
+                // Anchors
+                (SymbolKind::Constant, r#"&(?P<name>[\w\-]+)"#),
+            ],
+            imports: &[
+                // GitHub Actions / compose style references
+                r#"^\s*-?\s*uses:\s*(?P<module>\S+)"#,
+                r#"^\s*-?\s*(?:extends|include|\$ref):\s*['"]?(?P<module>[^'"\s]+)"#,
+            ],
+        }),
+    },
+];
+
+const ECMASCRIPT_SYMBOLS: &str = r#"
+(function_declaration name: (identifier) @function.name) @function
+(class_declaration name: (identifier) @class.name) @class
+(interface_declaration name: (identifier) @interface.name) @interface
+(variable_declaration (variable_declarator name: (identifier) @variable.name)) @variable
+(method_definition name: (property_identifier) @method.name) @method
+"#;
+
+const ECMASCRIPT_IMPORTS: &str = r#"
+(import_statement) @import
+"#;
+
+const ECMASCRIPT_HIGHLIGHTS: &str = r#"
+["function" "const" "let" "var" "class" "interface" "type"] @keyword
+["if" "else" "for" "while" "do" "switch" "case" "break" "continue" "return"] @keyword.control
+(string) @string
+(number) @number
+(comment) @comment
+(identifier) @variable
+(type_identifier) @type
+"#;
+
+const C_FAMILY_SYMBOLS: &str = r#"
+(function_definition declarator: (function_declarator declarator: (identifier) @function.name)) @function
+(struct_specifier name: (type_identifier) @struct.name) @struct
+(class_specifier name: (type_identifier) @class.name) @class
+"#;
+
+const C_FAMILY_IMPORTS: &str = r#"
+(preproc_include) @import
+"#;
+
+const C_FAMILY_HIGHLIGHTS: &str = r#"
+["if" "else" "for" "while" "do" "switch" "case" "return" "break" "continue"] @keyword.control
+["struct" "class" "enum" "typedef" "const" "static"] @keyword
+(string_literal) @string
+(number_literal) @number
+(comment) @comment
+(identifier) @variable
+(type_identifier) @type
+"#;
+
+static BY_LANGUAGE: Lazy<HashMap<Language, &'static LanguageSpec>> =
+    Lazy::new(|| SPECS.iter().map(|spec| (spec.language, spec)).collect());
+
+static COMPILED_PATTERNS: Lazy<HashMap<Language, CompiledPatterns>> = Lazy::new(|| {
+    SPECS
+        .iter()
+        .filter_map(|spec| Some((spec.language, CompiledPatterns::new(spec.patterns()?))))
+        .collect()
+});
+
+/// Every registered language
+pub fn all() -> &'static [LanguageSpec] {
+    SPECS
+}
+
+/// Registry entry for a language; `None` for `Language::Unknown`
+pub fn spec(language: Language) -> Option<&'static LanguageSpec> {
+    BY_LANGUAGE.get(&language).copied()
+}
+
+/// Pattern rules with their regexes compiled
+struct CompiledPatterns {
+    symbols: Vec<(SymbolKind, Regex)>,
+    imports: Vec<Regex>,
+}
+
+impl CompiledPatterns {
+    fn new(rules: &PatternRules) -> Self {
+        Self {
+            symbols: rules
+                .symbols
+                .iter()
+                .filter_map(|(kind, pattern)| Some((*kind, Regex::new(pattern).ok()?)))
+                .collect(),
+            imports: rules
+                .imports
+                .iter()
+                .filter_map(|pattern| Regex::new(pattern).ok())
+                .collect(),
+        }
+    }
+}
+
+/// Parse a pattern-backed language. Produces a flat AST with one node per
+/// symbol so downstream metrics and indexing work unchanged.
+pub fn parse_with_patterns(language: Language, source: &str) -> Option<ParseResult> {
+    let info = language::info(language)?;
+    let compiled = COMPILED_PATTERNS.get(&language)?;
+
+    let mut symbols = Vec::new();
+    let mut imports = Vec::new();
+    let mut children = Vec::new();
+    let mut offset = 0;
+
+    for (line_no, line) in source.split_inclusive('\n').enumerate() {
+        let trimmed = line.trim();
+        if !trimmed.is_empty() && !info.is_comment_line(trimmed) {
+            for (kind, regex) in &compiled.symbols {
+                for captures in regex.captures_iter(line) {
+                    let Some(name) = captures.name("name") else {
+                        continue;
+                    };
+                    let location = Position {
+                        line: line_no,
+                        column: name.start(),
+                        offset: offset + name.start(),
+                    };
+                    let name = name
+                        .as_str()
+                        .trim_matches(|c| matches!(c, '"' | '\'' | '`' | '[' | ']'))
+                        .to_string();
+
+                    children.push(AstNode {
+                        node_type: kind.as_str().to_string(),
+                        name: Some(name.clone()),
+                        start_pos: location,
+                        end_pos: Position {
+                            line: line_no,
+                            column: line.trim_end_matches('\n').len(),
+                            offset: offset + line.trim_end_matches('\n').len(),
+                        },
+                        children: vec![],
+                        metadata: definition_metadata(),
+                    });
+                    symbols.push(Symbol {
+                        name,
+                        kind: *kind,
+                        location,
+                        parent: None,
+                        signature: Some(trimmed.to_string()),
+                        docs: None,
+                    });
+                }
+            }
+
+            for regex in &compiled.imports {
+                if let Some(module) = regex.captures(line).and_then(|c| c.name("module")) {
+                    imports.push(ImportInfo {
+                        module: module.as_str().to_string(),
+                        items: vec![],
+                        location: Position {
+                            line: line_no,
+                            column: module.start(),
+                            offset: offset + module.start(),
+                        },
+                        is_wildcard: false,
+                    });
+                }
+            }
+        }
+        offset += line.len();
+    }
+
+    let (lines_of_code, comment_lines) = info.count_lines(source);
+    let function_count = symbols
+        .iter()
+        .filter(|s| matches!(s.kind, SymbolKind::Function | SymbolKind::Method))
+        .count();
+    let class_count = symbols
+        .iter()
+        .filter(|s| matches!(s.kind, SymbolKind::Class | SymbolKind::Struct))
+        .count();
+    let line_count = source.lines().count();
+
+    Some(ParseResult {
+        ast: AstNode {
+            node_type: "source_file".to_string(),
+            name: None,
+            start_pos: Position::default(),
+            end_pos: Position {
+                line: line_count.saturating_sub(1),
+                column: source.lines().last().map(str::len).unwrap_or(0),
+                offset: source.len(),
+            },
+            children,
+            metadata: NodeMetadata {
+                is_definition: false,
+                ..definition_metadata()
+            },
+        },
+        symbols,
+        imports,
+        errors: vec![],
+        metrics: CodeMetrics {
+            lines_of_code,
+            comment_lines,
+            complexity: 1,
+            function_count,
+            class_count,
+            max_nesting: 1,
+        },
+    })
+}
+
+fn definition_metadata() -> NodeMetadata {
+    NodeMetadata {
+        is_definition: true,
+        is_reference: false,
+        visibility: None,
+        doc_comment: None,
+        type_info: None,
+        complexity: 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_language_has_one_spec() {
+        let mut seen = std::collections::HashSet::new();
+        for spec in SPECS {
+            assert!(
+                seen.insert(spec.language),
+                "{:?} registered twice",
+                spec.language
+            );
+            assert_eq!(spec.info().language, spec.language);
+        }
+        for info in language::all() {
+            assert!(spec(info.language).is_some(), "{} has no spec", info.id);
+        }
+        assert!(spec(Language::Unknown).is_none());
+    }
+
+    #[test]
+    fn test_sql_patterns() {
+        let source = "-- schema\nCREATE TABLE IF NOT EXISTS users (id INTEGER);\ncreate or replace view active_users as select * from users;\nCREATE UNIQUE INDEX idx_users_email ON users(email);\nCREATE FUNCTION touch() RETURNS trigger AS $$ $$;\n\\i seed.sql\n";
+        let result = parse_with_patterns(Language::Sql, source).unwrap();
+
+        let names: Vec<_> = result
+            .symbols
+            .iter()
+            .map(|s| (s.name.as_str(), s.kind))
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                ("users", SymbolKind::Struct),
+                ("active_users", SymbolKind::Class),
+                ("idx_users_email", SymbolKind::Property),
+                ("touch", SymbolKind::Function),
+            ]
+        );
+        assert_eq!(result.symbols[0].location.line, 1);
+        assert_eq!(result.imports[0].module, "seed.sql");
+        assert_eq!(result.metrics.function_count, 1);
+        assert_eq!(result.metrics.comment_lines, 1);
+    }
+
+    #[test]
+    fn test_yaml_patterns() {
+        let source = "# CI\nname: build\ndefaults: &defaults\n  runs-on: ubuntu-latest\njobs:\n  test:\n    steps:\n      - uses: actions/checkout@v4\n";
+        let result = parse_with_patterns(Language::Yaml, source).unwrap();
+
+        let modules: Vec<_> = result
+            .symbols
+            .iter()
+            .filter(|s| s.kind == SymbolKind::Module)
+            .map(|s| s.name.as_str())
+            .collect();
+        assert_eq!(modules, vec!["name", "defaults", "jobs"]);
+        assert!(result
+            .symbols
+            .iter()
+            .any(|s| s.kind == SymbolKind::Constant && s.name == "defaults"));
+        assert_eq!(result.imports[0].module, "actions/checkout@v4");
+    }
+
+    fn symbols(language: Language, source: &str) -> Vec<(String, SymbolKind)> {
+        let mut parser = crate::analysis::parser::TreeSitterParser::new(language).unwrap();
+        parser
+            .parse(source)
+            .unwrap()
+            .symbols
+            .into_iter()
+            .map(|s| (s.name, s.kind))
+            .collect()
+    }
+
+    fn assert_has(symbols: &[(String, SymbolKind)], name: &str, kind: SymbolKind) {
+        assert!(
+            symbols.iter().any(|(n, k)| n == name && *k == kind),
+            "missing {:?} {} in {:?}",
+            kind,
+            name,
+            symbols
+        );
+    }
+
+    #[test]
+    fn test_every_grammar_query_compiles() {
+        for spec in SPECS.iter().filter(|spec| spec.grammar().is_some()) {
+            if let Err(e) = crate::analysis::parser::TreeSitterParser::new(spec.language) {
+                panic!("{}: {:#}", spec.info().id, e);
+            }
+        }
+    }
+
+    #[test]
+    fn test_kotlin_symbols() {
+        let symbols = symbols(
+            Language::Kotlin,
+            "class Greeter {\n    fun greet(): String = \"hi\"\n}\n\nval answer = 42\n",
+        );
+        assert_has(&symbols, "Greeter", SymbolKind::Class);
+        assert_has(&symbols, "greet", SymbolKind::Function);
+        assert_has(&symbols, "answer", SymbolKind::Variable);
+    }
+
+    #[test]
+    fn test_csharp_symbols() {
+        let symbols = symbols(
+            Language::CSharp,
+            "namespace Demo {\n    public class Greeter {\n        public string Name { get; set; }\n        public void Greet() {}\n    }\n}\n",
+        );
+        assert_has(&symbols, "Demo", SymbolKind::Namespace);
+        assert_has(&symbols, "Greeter", SymbolKind::Class);
+        assert_has(&symbols, "Name", SymbolKind::Property);
+        assert_has(&symbols, "Greet", SymbolKind::Method);
+    }
+
+    #[test]
+    fn test_scala_symbols() {
+        let symbols = symbols(
+            Language::Scala,
+            "trait Shape\n\nobject Main {\n  val answer = 42\n  def run(): Unit = {}\n}\n",
+        );
+        assert_has(&symbols, "Shape", SymbolKind::Trait);
+        assert_has(&symbols, "Main", SymbolKind::Class);
+        assert_has(&symbols, "answer", SymbolKind::Constant);
+        assert_has(&symbols, "run", SymbolKind::Function);
+    }
+
+    #[test]
+    fn test_bash_symbols() {
+        let symbols = symbols(
+            Language::Bash,
+            "#!/bin/bash\nNAME=world\ngreet() {\n  echo \"hi $NAME\"\n}\n",
+        );
+        assert_has(&symbols, "greet", SymbolKind::Function);
+        assert_has(&symbols, "NAME", SymbolKind::Variable);
+    }
+
+    #[test]
+    fn test_toml_symbols() {
+        let symbols = symbols(
+            Language::Toml,
+            "[package]\nname = \"hive\"\n\n[[bin]]\npath = \"src/main.rs\"\n",
+        );
+        assert_has(&symbols, "package", SymbolKind::Module);
+        assert_has(&symbols, "bin", SymbolKind::Module);
+        assert_has(&symbols, "name", SymbolKind::Property);
+    }
+
+    #[test]
+    fn test_tree_sitter_languages_have_no_patterns() {
+        assert!(parse_with_patterns(Language::Rust, "fn main() {}").is_none());
+        assert!(spec(Language::Kotlin).unwrap().grammar().is_some());
+        let swift = spec(Language::Swift).unwrap();
+        assert!(swift.grammar().is_none() && swift.patterns().is_none());
+        assert!(crate::analysis::parser::TreeSitterParser::new(Language::Swift).is_err());
+    }
+}
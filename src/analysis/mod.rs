@@ -4,23 +4,28 @@
 //! - Multi-language AST parsing with tree-sitter
 //! - Incremental parsing with <5ms updates
 //! - Syntax highlighting for TUI
-//! - Language detection
+//! - Language detection backed by a single language registry
 //! - Performance monitoring
 //! - Symbol indexing with FTS5
 //! - Dependency analysis with petgraph
 //! - Repository intelligence
+//! - Gitignore-aware parallel repository walking
+//! - Background indexing that keeps the symbol index current
 
+pub mod background_indexer;
 pub mod dependency;
 pub mod fast_parse;
 pub mod file_analyzer;
 pub mod incremental;
 pub mod language_detector;
+pub mod languages;
 pub mod parser;
 pub mod performance;
 pub mod repository_intelligence;
 pub mod symbol_index;
 pub mod syntax_highlighter;
 pub mod types;
+pub mod walker;
 
 pub use language_detector::{detect_language, LanguageDetector};
 pub use parser::{
@@ -30,6 +35,10 @@ pub use parser::{
 // Type aliases for compatibility with other modules
 pub type Parser = TreeSitterParser;
 pub type AST = crate::core::AstNode;
+pub use background_indexer::{
+    BackgroundIndexer, IndexPhase, IndexProgress, IndexScope, IndexStatusStore, SyncReport,
+    WatchConfig,
+};
 pub use dependency::{
     DependencyAnalysis, DependencyAnalyzer, DependencyEdge, DependencyGraph, DependencyKind,
     ModuleNode,
@@ -42,9 +51,11 @@ pub use repository_intelligence::{
     RepositoryAnalyzer, SecurityReport, TechnicalDebtReport,
 };
 pub use symbol_index::{
-    CallGraphInfo, IndexStatistics, ReferenceKind, SymbolEntry, SymbolIndexer, SymbolReference,
+    CallGraphInfo, IndexStatistics, IndexedFile, ReferenceKind, SymbolEntry, SymbolIndexer,
+    SymbolReference,
 };
 pub use syntax_highlighter::SyntaxHighlighter;
+pub use walker::{RepositoryWalker, WalkedFile};
 
 // Re-export core types for convenience
 pub use crate::core::ParseResult;
@@ -103,12 +114,16 @@ impl AnalysisEngine {
             }
         }
 
-        // Parse the file
-        let mut registry = self.parser_registry.lock().await;
-        let parser = registry.get_parser(language).await?;
-        let mut parser = parser.lock().await;
-
-        let result = parser.parse(content)?;
+        // Parse the file (pattern-backed languages skip tree-sitter)
+        let result = match languages::parse_with_patterns(language, content) {
+            Some(result) => result,
+            None => {
+                let mut registry = self.parser_registry.lock().await;
+                let parser = registry.get_parser(language).await?;
+                let mut parser = parser.lock().await;
+                parser.parse(content)?
+            }
+        };
 
         // Cache the result
         if let Ok(serialized) = serde_json::to_vec(&result) {
@@ -15,7 +15,9 @@ use crate::core::ast::{
     AstNode, CodeMetrics, ErrorSeverity, ImportInfo, NodeMetadata, ParseError, ParseResult, Symbol,
     SymbolKind,
 };
-use crate::core::{HiveError, Language, Position};
+use crate::core::{language, HiveError, Language, Position};
+
+use super::languages;
 
 /// Tree-sitter based parser implementation
 pub struct TreeSitterParser {
@@ -95,340 +97,22 @@ pub enum HighlightType {
 impl TreeSitterParser {
     /// Create a new parser for the given language
     pub fn new(language: Language) -> Result<Self> {
-        let (ts_language, symbol_query, import_query, highlight_query) = match language {
-            Language::Rust => {
-                let lang = tree_sitter_rust::language();
-                let symbol_query = tree_sitter::Query::new(
-                    lang,
-                    r#"
-                    (function_item name: (identifier) @function.name) @function
-                    (struct_item name: (type_identifier) @struct.name) @struct
-                    (enum_item name: (type_identifier) @enum.name) @enum
-                    (trait_item name: (type_identifier) @trait.name) @trait
-                    (impl_item type: (type_identifier) @impl.type) @impl
-                    (const_item name: (identifier) @const.name) @const
-                    (static_item name: (identifier) @static.name) @static
-                    (mod_item name: (identifier) @module.name) @module
-                    (type_alias name: (type_identifier) @type_alias.name) @type_alias
-                    "#,
-                )
-                .ok();
-
-                let import_query = tree_sitter::Query::new(
-                    lang,
-                    r#"
-                    (use_declaration) @import
-                    "#,
-                )
-                .ok();
-
-                let highlight_query = tree_sitter::Query::new(
-                    lang,
-                    r#"
-                    "fn" @keyword.function
-                    "let" @keyword
-                    "mut" @keyword
-                    "const" @keyword
-                    "static" @keyword
-                    "if" @keyword.control
-                    "else" @keyword.control
-                    "match" @keyword.control
-                    "for" @keyword.control
-                    "while" @keyword.control
-                    "loop" @keyword.control
-                    "return" @keyword.control
-                    "break" @keyword.control
-                    "continue" @keyword.control
-                    (string_literal) @string
-                    (integer_literal) @number
-                    (float_literal) @number
-                    (line_comment) @comment
-                    (block_comment) @comment
-                    (identifier) @variable
-                    (type_identifier) @type
-                    "#,
-                )
-                .ok();
-
-                (lang, symbol_query, import_query, highlight_query)
-            }
-            Language::TypeScript | Language::JavaScript => {
-                let lang = if language == Language::TypeScript {
-                    tree_sitter_typescript::language_typescript()
-                } else {
-                    tree_sitter_javascript::language()
-                };
-
-                let symbol_query = tree_sitter::Query::new(
-                    lang,
-                    r#"
-                    (function_declaration name: (identifier) @function.name) @function
-                    (class_declaration name: (identifier) @class.name) @class
-                    (interface_declaration name: (identifier) @interface.name) @interface
-                    (variable_declaration (variable_declarator name: (identifier) @variable.name)) @variable
-                    (method_definition name: (property_identifier) @method.name) @method
-                    "#
-                ).ok();
-
-                let import_query = tree_sitter::Query::new(
-                    lang,
-                    r#"
-                    (import_statement) @import
-                    "#,
-                )
-                .ok();
-
-                let highlight_query = tree_sitter::Query::new(
-                    lang,
-                    r#"
-                    ["function" "const" "let" "var" "class" "interface" "type"] @keyword
-                    ["if" "else" "for" "while" "do" "switch" "case" "break" "continue" "return"] @keyword.control
-                    (string) @string
-                    (number) @number
-                    (comment) @comment
-                    (identifier) @variable
-                    (type_identifier) @type
-                    "#
-                ).ok();
-
-                (lang, symbol_query, import_query, highlight_query)
-            }
-            Language::Python => {
-                let lang = tree_sitter_python::language();
-
-                let symbol_query = tree_sitter::Query::new(
-                    lang,
-                    r#"
-                    (function_definition name: (identifier) @function.name) @function
-                    (class_definition name: (identifier) @class.name) @class
-                    "#,
-                )
-                .ok();
-
-                let import_query = tree_sitter::Query::new(
-                    lang,
-                    r#"
-                    (import_statement) @import
-                    (import_from_statement) @import
-                    "#,
-                )
-                .ok();
-
-                let highlight_query = tree_sitter::Query::new(
-                    lang,
-                    r#"
-                    ["def" "class" "if" "elif" "else" "for" "while" "try" "except" "finally" "with" "return" "break" "continue"] @keyword
-                    (string) @string
-                    (integer) @number
-                    (float) @number
-                    (comment) @comment
-                    (identifier) @variable
-                    "#
-                ).ok();
-
-                (lang, symbol_query, import_query, highlight_query)
-            }
-            Language::Go => {
-                let lang = tree_sitter_go::language();
-
-                let symbol_query = tree_sitter::Query::new(
-                    lang,
-                    r#"
-                    (function_declaration name: (identifier) @function.name) @function
-                    (type_declaration (type_spec name: (type_identifier) @type.name)) @type
-                    (const_declaration (const_spec name: (identifier) @const.name)) @const
-                    (var_declaration (var_spec name: (identifier) @var.name)) @var
-                    "#,
-                )
-                .ok();
-
-                let import_query = tree_sitter::Query::new(
-                    lang,
-                    r#"
-                    (import_declaration) @import
-                    "#,
-                )
-                .ok();
-
-                let highlight_query = tree_sitter::Query::new(
-                    lang,
-                    r#"
-                    ["func" "type" "struct" "interface" "const" "var" "if" "else" "for" "switch" "case" "return" "break" "continue"] @keyword
-                    (string_literal) @string
-                    (int_literal) @number
-                    (float_literal) @number
-                    (comment) @comment
-                    (identifier) @variable
-                    (type_identifier) @type
-                    "#
-                ).ok();
-
-                (lang, symbol_query, import_query, highlight_query)
-            }
-            Language::Java => {
-                let lang = tree_sitter_java::language();
-
-                let symbol_query = tree_sitter::Query::new(
-                    lang,
-                    r#"
-                    (class_declaration name: (identifier) @class.name) @class
-                    (interface_declaration name: (identifier) @interface.name) @interface
-                    (method_declaration name: (identifier) @method.name) @method
-                    (field_declaration declarator: (variable_declarator name: (identifier) @field.name)) @field
-                    "#
-                ).ok();
-
-                let import_query = tree_sitter::Query::new(
-                    lang,
-                    r#"
-                    (import_declaration) @import
-                    "#,
-                )
-                .ok();
-
-                let highlight_query = tree_sitter::Query::new(
-                    lang,
-                    r#"
-                    ["class" "interface" "public" "private" "protected" "static" "final" "if" "else" "for" "while" "switch" "case" "return" "break" "continue"] @keyword
-                    (string_literal) @string
-                    (decimal_integer_literal) @number
-                    (decimal_floating_point_literal) @number
-                    (comment) @comment
-                    (identifier) @variable
-                    (type_identifier) @type
-                    "#
-                ).ok();
-
-                (lang, symbol_query, import_query, highlight_query)
-            }
-            Language::Cpp | Language::C => {
-                let lang = if language == Language::Cpp {
-                    tree_sitter_cpp::language()
-                } else {
-                    tree_sitter_c::language()
-                };
-
-                let symbol_query = tree_sitter::Query::new(
-                    lang,
-                    r#"
-                    (function_definition declarator: (function_declarator declarator: (identifier) @function.name)) @function
-                    (struct_specifier name: (type_identifier) @struct.name) @struct
-                    (class_specifier name: (type_identifier) @class.name) @class
-                    "#
-                ).ok();
-
-                let import_query = tree_sitter::Query::new(
-                    lang,
-                    r#"
-                    (preproc_include) @import
-                    "#,
-                )
-                .ok();
-
-                let highlight_query = tree_sitter::Query::new(
-                    lang,
-                    r#"
-                    ["if" "else" "for" "while" "do" "switch" "case" "return" "break" "continue"] @keyword.control
-                    ["struct" "class" "enum" "typedef" "const" "static"] @keyword
-                    (string_literal) @string
-                    (number_literal) @number
-                    (comment) @comment
-                    (identifier) @variable
-                    (type_identifier) @type
-                    "#
-                ).ok();
-
-                (lang, symbol_query, import_query, highlight_query)
-            }
-            Language::Ruby => {
-                let lang = tree_sitter_ruby::language();
-
-                let symbol_query = tree_sitter::Query::new(
-                    lang,
-                    r#"
-                    (method name: (identifier) @method.name) @method
-                    (class name: (constant) @class.name) @class
-                    (module name: (constant) @module.name) @module
-                    (assignment left: (identifier) @variable.name) @variable
-                    "#,
-                )
-                .ok();
-
-                let import_query = tree_sitter::Query::new(
-                    lang,
-                    r#"
-                    (call method: (identifier) @method (#match? @method "^(require|require_relative|load)$")) @import
-                    "#
-                ).ok();
-
-                let highlight_query = tree_sitter::Query::new(
-                    lang,
-                    r#"
-                    ["def" "class" "module" "end" "if" "elsif" "else" "unless" "case" "when" "for" "while" "until" "return" "break" "next"] @keyword
-                    (string) @string
-                    (integer) @number
-                    (float) @number
-                    (comment) @comment
-                    (identifier) @variable
-                    (constant) @type
-                    "#
-                ).ok();
-
-                (lang, symbol_query, import_query, highlight_query)
-            }
-            Language::PHP => {
-                let lang = tree_sitter_php::language_php();
-
-                let symbol_query = tree_sitter::Query::new(
-                    lang,
-                    r#"
-                    (function_definition name: (name) @function.name) @function
-                    (class_declaration name: (name) @class.name) @class
-                    (method_declaration name: (name) @method.name) @method
-                    (property_declaration (property_element name: (variable_name) @property.name)) @property
-                    "#
-                ).ok();
-
-                let import_query = tree_sitter::Query::new(
-                    lang,
-                    r#"
-                    (include_expression) @import
-                    (require_expression) @import
-                    (include_once_expression) @import
-                    (require_once_expression) @import
-                    "#,
-                )
-                .ok();
-
-                let highlight_query = tree_sitter::Query::new(
-                    lang,
-                    r#"
-                    ["function" "class" "interface" "trait" "public" "private" "protected" "static" "final" "abstract" "if" "else" "elseif" "for" "foreach" "while" "do" "switch" "case" "return" "break" "continue"] @keyword
-                    (string) @string
-                    (integer) @number
-                    (float) @number
-                    (comment) @comment
-                    (variable_name) @variable
-                    (name) @type
-                    "#
-                ).ok();
-
-                (lang, symbol_query, import_query, highlight_query)
-            }
-            Language::Swift => {
-                // Swift parsing temporarily disabled due to tree-sitter version incompatibility
-                // TODO: Update tree-sitter-swift to version 0.20+ to match other parsers
-                let lang = tree_sitter_rust::language(); // Use Rust parser as temporary fallback
-
-                // No Swift-specific queries for now
-                let symbol_query = None;
-                let import_query = None;
-                let highlight_query = None;
-
-                (lang, symbol_query, import_query, highlight_query)
+        let grammar = languages::spec(language)
+            .and_then(|spec| spec.grammar())
+            .ok_or_else(|| anyhow!("Unsupported language: {:?}", language))?;
+
+        let ts_language = (grammar.language)();
+        let compile = |kind: &str, source: &str| -> Result<Option<tree_sitter::Query>> {
+            if source.trim().is_empty() {
+                return Ok(None);
             }
-            _ => return Err(anyhow!("Unsupported language: {:?}", language)),
+            tree_sitter::Query::new(ts_language, source)
+                .map(Some)
+                .map_err(|e| anyhow!("Invalid {} query for {:?}: {}", kind, language, e))
         };
+        let symbol_query = compile("symbol", grammar.symbols)?;
+        let import_query = compile("import", grammar.imports)?;
+        let highlight_query = compile("highlight", grammar.highlights)?;
 
         let mut parser = Parser::new();
         parser
@@ -756,7 +440,10 @@ impl TreeSitterParser {
                     "class" => SymbolKind::Class,
                     "interface" => SymbolKind::Interface,
                     "struct" => SymbolKind::Struct,
+                    "trait" => SymbolKind::Trait,
                     "enum" => SymbolKind::Enum,
+                    "namespace" => SymbolKind::Namespace,
+                    "property" => SymbolKind::Property,
                     "const" | "constant" => SymbolKind::Constant,
                     "variable" | "var" => SymbolKind::Variable,
                     "module" | "mod" => SymbolKind::Module,
@@ -799,6 +486,11 @@ impl TreeSitterParser {
 
         for m in matches {
             for capture in m.captures {
+                // Skip helper captures used only by predicates
+                if import_query.capture_names()[capture.index as usize] != "import" {
+                    continue;
+                }
+
                 let node = capture.node;
                 let import_text = node.utf8_text(source.as_bytes())?;
 
@@ -855,6 +547,40 @@ impl TreeSitterParser {
                     }
                 }
             }
+            Language::Kotlin | Language::Scala | Language::Java => {
+                if let Some(rest) = import_text.trim().strip_prefix("import ") {
+                    let import_path = rest.trim_end_matches(';').trim();
+                    let is_wildcard = import_path.ends_with(".*") || import_path.ends_with("._");
+                    let module = import_path
+                        .trim_end_matches(".*")
+                        .trim_end_matches("._")
+                        .trim_start_matches("static ");
+                    return Ok((module.to_string(), vec![], is_wildcard));
+                }
+            }
+            Language::CSharp => {
+                if let Some(rest) = import_text.trim().strip_prefix("using ") {
+                    let rest = rest.trim_end_matches(';').trim();
+                    let rest = rest.strip_prefix("static ").unwrap_or(rest);
+                    // `using Alias = Namespace.Type;`
+                    let (module, items) = match rest.split_once('=') {
+                        Some((alias, target)) => {
+                            (target.trim().to_string(), vec![alias.trim().to_string()])
+                        }
+                        None => (rest.to_string(), vec![]),
+                    };
+                    return Ok((module, items, false));
+                }
+            }
+            Language::Bash => {
+                let mut words = import_text.split_whitespace();
+                if matches!(words.next(), Some("source") | Some(".")) {
+                    if let Some(file) = words.next() {
+                        let file = file.trim_matches(|c| c == '"' || c == '\'');
+                        return Ok((file.to_string(), vec![], false));
+                    }
+                }
+            }
             _ => {
                 // Generic parsing for other languages
                 return Ok((import_text.to_string(), vec![], false));
@@ -906,39 +632,11 @@ impl TreeSitterParser {
 
     /// Calculate code metrics
     pub fn calculate_metrics(&self, ast: &AstNode, source: &str) -> CodeMetrics {
-        let lines: Vec<&str> = source.lines().collect();
-        let mut code_lines = 0;
-        let mut comment_lines = 0;
-
-        for line in &lines {
-            let trimmed = line.trim();
-            if trimmed.is_empty() {
-                continue;
-            }
-
-            // Language-specific comment detection
-            let is_comment = match self.language {
-                Language::Rust
-                | Language::Cpp
-                | Language::C
-                | Language::Java
-                | Language::Go
-                | Language::JavaScript
-                | Language::TypeScript => {
-                    trimmed.starts_with("//")
-                        || trimmed.starts_with("/*")
-                        || trimmed.starts_with("*")
-                }
-                Language::Python => trimmed.starts_with("#"),
-                _ => false,
-            };
-
-            if is_comment {
-                comment_lines += 1;
-            } else {
-                code_lines += 1;
-            }
-        }
+        // Language-specific comment detection
+        let (code_lines, comment_lines) = match language::info(self.language) {
+            Some(info) => info.count_lines(source),
+            None => (source.lines().filter(|l| !l.trim().is_empty()).count(), 0),
+        };
 
         CodeMetrics {
             lines_of_code: code_lines,
@@ -9,6 +9,8 @@ use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tracing::{debug, info, warn};
 
+use crate::analytics::metrics;
+
 /// Performance monitor for tracking parse metrics
 pub struct PerformanceMonitor {
     /// Total files parsed
@@ -109,6 +111,14 @@ impl PerformanceMonitor {
     /// Record a parse operation
     pub fn record_parse(&self, duration: Duration, is_incremental: bool) {
         let duration_us = duration.as_micros() as u64;
+        metrics::global().record(
+            if is_incremental {
+                metrics::INCREMENTAL_PARSE_MS
+            } else {
+                metrics::PARSE_MS
+            },
+            duration.as_secs_f64() * 1000.0,
+        );
 
         if is_incremental {
             self.incremental_parses.fetch_add(1, Ordering::Relaxed);
@@ -144,16 +154,19 @@ impl PerformanceMonitor {
     /// Record a cache hit
     pub fn record_cache_hit(&self) {
         self.cache_hits.fetch_add(1, Ordering::Relaxed);
+        metrics::global().increment(metrics::CACHE_HITS);
     }
 
     /// Record a cache miss
     pub fn record_cache_miss(&self) {
         self.cache_misses.fetch_add(1, Ordering::Relaxed);
+        metrics::global().increment(metrics::CACHE_MISSES);
     }
 
     /// Record a parse error
     pub fn record_parse_error(&self) {
         self.parse_errors.fetch_add(1, Ordering::Relaxed);
+        metrics::global().increment(metrics::PARSE_ERRORS);
     }
 
     /// Get current metrics
@@ -20,6 +20,7 @@ use tracing::{debug, info, instrument};
 use crate::analysis::{
     dependency::{DependencyAnalysis, DependencyAnalyzer},
     symbol_index::{SymbolEntry, SymbolIndexer},
+    walker::RepositoryWalker,
 };
 use crate::core::ast::{CodeMetrics, ParseResult};
 use crate::core::database::DatabaseManager;
@@ -728,37 +729,20 @@ impl RepositoryAnalyzer {
         let scanners = self.security_scanners.read().await;
         let mut vulnerabilities = Vec::new();
 
-        // Walk through files and scan
-        for entry in std::fs::read_dir(root_path)? {
-            let entry = entry?;
-            let path = entry.path();
-
-            if path.is_file() {
-                // Only scan certain file types
-                let extension = path.extension().and_then(|ext| ext.to_str()).unwrap_or("");
-                let scannable_extensions = vec![
-                    "rs", "js", "ts", "py", "java", "go", "php", "rb", "cpp", "c", "h",
-                ];
-
-                if scannable_extensions.contains(&extension) {
-                    if let Ok(content) = std::fs::read_to_string(&path) {
-                        for scanner in scanners.iter() {
-                            let mut file_vulns = scanner.scan(&content, &path);
-                            vulnerabilities.append(&mut file_vulns);
-                        }
-                    }
+        // Only scan certain file types
+        let files = RepositoryWalker::new(root_path)
+            .extensions(&[
+                "rs", "js", "ts", "py", "java", "go", "php", "rb", "cpp", "c", "h",
+            ])
+            .walk_async()
+            .await?;
+
+        for file in files {
+            if let Ok(content) = std::fs::read_to_string(&file.path) {
+                for scanner in scanners.iter() {
+                    let mut file_vulns = scanner.scan(&content, &file.path);
+                    vulnerabilities.append(&mut file_vulns);
                 }
-            } else if path.is_dir()
-                && !path
-                    .file_name()
-                    .unwrap_or_default()
-                    .to_str()
-                    .unwrap_or("")
-                    .starts_with('.')
-            {
-                // Recursively scan subdirectories (basic implementation)
-                let sub_report = Box::pin(self.scan_security(&path)).await?;
-                vulnerabilities.extend(sub_report.vulnerabilities);
             }
         }
 
@@ -16,6 +16,7 @@ use std::time::Instant;
 use tokio::sync::RwLock;
 use tracing::{debug, info, instrument};
 
+use crate::analysis::languages;
 use crate::analysis::parser::TreeSitterParser;
 use crate::core::database::{DatabaseManager, DbConnection};
 use crate::core::{
@@ -116,6 +117,23 @@ pub struct SymbolIndexer {
     stats: Arc<RwLock<IndexStatistics>>,
 }
 
+/// Content fingerprint of an indexed file, used to skip unchanged files
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexedFile {
+    /// File path as stored in the symbol tables
+    pub path: PathBuf,
+    /// BLAKE3 hash of the indexed content
+    pub content_hash: String,
+    /// File size in bytes at index time
+    pub size: u64,
+    /// Modification time (unix nanoseconds) at index time, if known
+    pub modified: Option<i64>,
+    /// Number of symbols extracted from the file
+    pub symbol_count: usize,
+    /// When the file was last indexed
+    pub indexed_at: DateTime<Utc>,
+}
+
 /// Index statistics
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct IndexStatistics {
@@ -195,6 +213,16 @@ impl SymbolIndexer {
             [],
         )?;
 
+        // Keep FTS in sync when symbols are removed
+        conn.execute(
+            "CREATE TRIGGER IF NOT EXISTS symbols_delete_fts
+             AFTER DELETE ON symbols BEGIN
+                INSERT INTO symbols_fts(symbols_fts, rowid, id, name, documentation, signature, file_path)
+                VALUES ('delete', old.rowid, old.id, old.name, old.documentation, old.signature, old.file_path);
+             END",
+            [],
+        )?;
+
         // Symbol references table
         conn.execute(
             "CREATE TABLE IF NOT EXISTS symbol_references (
@@ -212,6 +240,19 @@ impl SymbolIndexer {
             [],
         )?;
 
+        // Content hashes of indexed files for incremental re-indexing
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS indexed_files (
+                file_path TEXT PRIMARY KEY,
+                content_hash TEXT NOT NULL,
+                size INTEGER NOT NULL,
+                modified INTEGER,
+                symbol_count INTEGER NOT NULL DEFAULT 0,
+                indexed_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+
         // Indexes for performance
         conn.execute(
             "CREATE INDEX IF NOT EXISTS idx_symbols_name ON symbols(name)",
@@ -237,20 +278,23 @@ impl SymbolIndexer {
         Ok(())
     }
 
-    /// Index a file
+    /// Index a file, returning the number of symbols extracted
     #[instrument(skip(self, content))]
-    pub async fn index_file(&self, file_path: &Path, content: &str) -> Result<()> {
+    pub async fn index_file(&self, file_path: &Path, content: &str) -> Result<usize> {
         let start = Instant::now();
 
         // Detect language
         let language = self.detect_language(file_path)?;
 
-        // Get or create parser
-        let parser = self.get_parser(language).await?;
-        let mut parser = parser.lock().await;
-
-        // Parse file
-        let parse_result = parser.parse(content)?;
+        // Parse file (pattern-backed languages skip tree-sitter)
+        let parse_result = match languages::parse_with_patterns(language, content) {
+            Some(result) => result,
+            None => {
+                let parser = self.get_parser(language).await?;
+                let mut parser = parser.lock().await;
+                parser.parse(content)?
+            }
+        };
 
         // Extract symbols with enhanced metadata
         let symbols = self.extract_symbols(&parse_result, file_path, content)?;
@@ -258,16 +302,17 @@ impl SymbolIndexer {
         // Extract references
         let references = self.extract_references(&parse_result, file_path, content)?;
 
+        // Fingerprint the content so unchanged files can be skipped later
+        let (size, modified) = file_fingerprint(file_path, content);
+        let content_hash = content_hash(content);
+
         // Store in database - complete all DB operations before async
-        {
+        let stale_ids = {
             let mut conn = self.db.get_connection()?;
             let tx = conn.transaction()?;
 
-            // Clear existing symbols for this file
-            tx.execute(
-                "DELETE FROM symbols WHERE file_path = ?1",
-                params![file_path.to_str()],
-            )?;
+            // Clear existing symbols and references for this file
+            let stale_ids = Self::delete_file_rows(&tx, file_path)?;
 
             // Insert symbols
             for symbol in &symbols {
@@ -279,10 +324,26 @@ impl SymbolIndexer {
                 self.insert_reference(&tx, reference)?;
             }
 
+            tx.execute(
+                "INSERT OR REPLACE INTO indexed_files (
+                    file_path, content_hash, size, modified, symbol_count, indexed_at
+                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    file_path.to_str(),
+                    content_hash,
+                    size as i64,
+                    modified,
+                    symbols.len() as i64,
+                    Utc::now().to_rfc3339(),
+                ],
+            )?;
+
             tx.commit()?;
-        } // conn and tx are dropped here, before any await
+            stale_ids
+        }; // conn and tx are dropped here, before any await
 
         // Update call graph
+        self.call_graph.write().await.remove_symbols(&stale_ids);
         self.update_call_graph(&symbols, &references).await?;
 
         // Update statistics
@@ -298,9 +359,168 @@ impl SymbolIndexer {
             elapsed
         );
 
+        Ok(symbols.len())
+    }
+
+    /// Remove every symbol and reference extracted from a file.
+    ///
+    /// Returns the number of symbols removed.
+    pub async fn remove_file(&self, file_path: &Path) -> Result<usize> {
+        let removed = {
+            let mut conn = self.db.get_connection()?;
+            let tx = conn.transaction()?;
+            let removed = Self::delete_file_rows(&tx, file_path)?;
+            tx.commit()?;
+            removed
+        };
+
+        self.call_graph.write().await.remove_symbols(&removed);
+
+        let mut stats = self.stats.write().await;
+        stats.total_symbols = stats.total_symbols.saturating_sub(removed.len());
+
+        debug!(
+            "Removed {} symbols for {}",
+            removed.len(),
+            file_path.display()
+        );
+
+        Ok(removed.len())
+    }
+
+    /// Look up the stored fingerprint of an indexed file
+    pub fn indexed_file(&self, file_path: &Path) -> Result<Option<IndexedFile>> {
+        let conn = self.db.get_connection()?;
+        let file = conn
+            .query_row(
+                "SELECT file_path, content_hash, size, modified, symbol_count, indexed_at
+                 FROM indexed_files WHERE file_path = ?1",
+                params![file_path.to_str()],
+                Self::row_to_indexed_file,
+            )
+            .optional()?;
+        Ok(file)
+    }
+
+    /// List every indexed file at or below `root`
+    pub fn indexed_files_under(&self, root: &Path) -> Result<Vec<IndexedFile>> {
+        let conn = self.db.get_connection()?;
+        let root_str = root.to_string_lossy();
+        let prefix = format!(
+            "{}{}%",
+            escape_like(root_str.trim_end_matches(std::path::MAIN_SEPARATOR)),
+            std::path::MAIN_SEPARATOR
+        );
+
+        let mut stmt = conn.prepare(
+            "SELECT file_path, content_hash, size, modified, symbol_count, indexed_at
+             FROM indexed_files
+             WHERE file_path = ?1 OR file_path LIKE ?2 ESCAPE '\\'
+             ORDER BY file_path",
+        )?;
+        let files = stmt
+            .query_map(params![root_str, prefix], Self::row_to_indexed_file)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // LIKE is case-insensitive, so confirm the prefix component-wise
+        Ok(files
+            .into_iter()
+            .filter(|file| file.path.starts_with(root))
+            .collect())
+    }
+
+    /// Refresh the stored size and modification time of a file whose
+    /// content hash is unchanged
+    pub fn touch_file(&self, file_path: &Path, size: u64, modified: Option<i64>) -> Result<()> {
+        let conn = self.db.get_connection()?;
+        conn.execute(
+            "UPDATE indexed_files SET size = ?2, modified = ?3 WHERE file_path = ?1",
+            params![file_path.to_str(), size as i64, modified],
+        )?;
         Ok(())
     }
 
+    /// Delete a file's symbols, references and fingerprint inside a
+    /// transaction, returning the removed symbol IDs
+    fn delete_file_rows(tx: &Transaction, file_path: &Path) -> Result<Vec<String>> {
+        let path = file_path.to_str();
+
+        let mut stmt = tx.prepare("SELECT id FROM symbols WHERE file_path = ?1")?;
+        let ids = stmt
+            .query_map(params![path], |row| row.get::<_, String>(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+        drop(stmt);
+
+        tx.execute(
+            "DELETE FROM symbol_references WHERE file_path = ?1",
+            params![path],
+        )?;
+        tx.execute("DELETE FROM symbols WHERE file_path = ?1", params![path])?;
+        tx.execute(
+            "DELETE FROM indexed_files WHERE file_path = ?1",
+            params![path],
+        )?;
+
+        Ok(ids)
+    }
+
+    fn row_to_indexed_file(row: &rusqlite::Row<'_>) -> rusqlite::Result<IndexedFile> {
+        let indexed_at: String = row.get(5)?;
+        Ok(IndexedFile {
+            path: PathBuf::from(row.get::<_, String>(0)?),
+            content_hash: row.get(1)?,
+            size: row.get::<_, i64>(2)? as u64,
+            modified: row.get(3)?,
+            symbol_count: row.get::<_, i64>(4)? as usize,
+            indexed_at: DateTime::parse_from_rfc3339(&indexed_at)
+                .map(|dt| dt.with_timezone(&Utc))
+                .unwrap_or_else(|_| Utc::now()),
+        })
+    }
+
+    /// Symbols of one file, in source order
+    pub fn symbols_in_file(&self, file_path: &Path) -> Result<Vec<SymbolEntry>> {
+        let conn = self.db.get_connection()?;
+        let mut stmt = conn
+            .prepare("SELECT * FROM symbols WHERE file_path = ?1 ORDER BY start_line, start_col")?;
+        let symbols = stmt
+            .query_map(params![file_path.to_str()], Self::row_to_symbol)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(symbols)
+    }
+
+    /// Map a `symbols` row, selected with `*`, to an entry
+    fn row_to_symbol(row: &rusqlite::Row<'_>) -> rusqlite::Result<SymbolEntry> {
+        Ok(SymbolEntry {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            kind: serde_json::from_str(&row.get::<_, String>(2)?).unwrap_or_default(),
+            file_path: PathBuf::from(row.get::<_, String>(3)?),
+            start_pos: Position {
+                line: row.get(4)?,
+                column: row.get(5)?,
+                offset: 0,
+            },
+            end_pos: Position {
+                line: row.get(6)?,
+                column: row.get(7)?,
+                offset: 0,
+            },
+            parent_id: row.get(8)?,
+            signature: row.get(9)?,
+            documentation: row.get(10)?,
+            visibility: row.get(11)?,
+            type_info: row.get(12)?,
+            complexity: row.get(13)?,
+            quality_score: row.get(14)?,
+            reference_count: row.get(15)?,
+            is_exported: row.get(16)?,
+            usage_count: 0,
+            last_modified: chrono::Utc::now(),
+            attributes: HashMap::new(),
+        })
+    }
+
     /// Search symbols with FTS5
     #[instrument(skip(self))]
     pub async fn search(&self, query: &str, limit: usize) -> Result<Vec<SymbolEntry>> {
@@ -316,36 +536,7 @@ impl SymbolIndexer {
         )?;
 
         let symbols = stmt
-            .query_map(params![query, limit], |row| {
-                Ok(SymbolEntry {
-                    id: row.get(0)?,
-                    name: row.get(1)?,
-                    kind: serde_json::from_str(&row.get::<_, String>(2)?).unwrap(),
-                    file_path: PathBuf::from(row.get::<_, String>(3)?),
-                    start_pos: Position {
-                        line: row.get(4)?,
-                        column: row.get(5)?,
-                        offset: 0,
-                    },
-                    end_pos: Position {
-                        line: row.get(6)?,
-                        column: row.get(7)?,
-                        offset: 0,
-                    },
-                    parent_id: row.get(8)?,
-                    signature: row.get(9)?,
-                    documentation: row.get(10)?,
-                    visibility: row.get(11)?,
-                    type_info: row.get(12)?,
-                    complexity: row.get(13)?,
-                    quality_score: row.get(14)?,
-                    reference_count: row.get(15)?,
-                    is_exported: row.get(16)?,
-                    usage_count: 0,
-                    last_modified: chrono::Utc::now(),
-                    attributes: HashMap::new(),
-                })
-            })?
+            .query_map(params![query, limit], Self::row_to_symbol)?
             .collect::<Result<Vec<_>, _>>()?;
 
         let elapsed = start.elapsed().as_secs_f64() * 1000.0;
@@ -643,23 +834,11 @@ impl SymbolIndexer {
         }
     }
 
-    /// Detect language from file extension
+    /// Detect language from file name or extension
     fn detect_language(&self, path: &Path) -> Result<Language> {
-        let ext = path
-            .extension()
-            .and_then(|e| e.to_str())
-            .ok_or_else(|| anyhow!("No file extension"))?;
-
-        match ext {
-            "rs" => Ok(Language::Rust),
-            "ts" | "tsx" => Ok(Language::TypeScript),
-            "js" | "jsx" => Ok(Language::JavaScript),
-            "py" => Ok(Language::Python),
-            "go" => Ok(Language::Go),
-            "java" => Ok(Language::Java),
-            "cpp" | "cc" | "cxx" => Ok(Language::Cpp),
-            "c" => Ok(Language::C),
-            _ => Err(anyhow!("Unsupported language: {}", ext)),
+        match crate::core::detect_language(path) {
+            Language::Unknown => Err(anyhow!("Unsupported language: {}", path.display())),
+            language => Ok(language),
         }
     }
 
@@ -840,6 +1019,55 @@ impl CallGraph {
         let to_node = self.add_symbol(to);
         self.graph.add_edge(from_node, to_node, kind);
     }
+
+    fn remove_symbols(&mut self, symbol_ids: &[String]) {
+        for symbol_id in symbol_ids {
+            let Some(node) = self.symbol_to_node.remove(symbol_id) else {
+                continue;
+            };
+            self.node_to_symbol.remove(&node);
+
+            // Removing a node moves the last node into the freed index
+            let last = NodeIndex::new(self.graph.node_count() - 1);
+            self.graph.remove_node(node);
+            if last != node {
+                if let Some(moved) = self.node_to_symbol.remove(&last) {
+                    self.symbol_to_node.insert(moved.clone(), node);
+                    self.node_to_symbol.insert(node, moved);
+                }
+            }
+        }
+    }
+}
+
+/// Hash file content for change detection
+pub fn content_hash(content: &str) -> String {
+    blake3::hash(content.as_bytes()).to_hex().to_string()
+}
+
+/// Size and modification time of a file on disk, falling back to the
+/// content length when the file cannot be stat'ed
+pub fn file_fingerprint(file_path: &Path, content: &str) -> (u64, Option<i64>) {
+    match std::fs::metadata(file_path) {
+        Ok(metadata) => (metadata.len(), modified_nanos(&metadata)),
+        Err(_) => (content.len() as u64, None),
+    }
+}
+
+/// Modification time of a file in unix nanoseconds
+pub fn modified_nanos(metadata: &std::fs::Metadata) -> Option<i64> {
+    metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+        .and_then(|duration| i64::try_from(duration.as_nanos()).ok())
+}
+
+fn escape_like(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
 }
 
 /// Call graph information
@@ -0,0 +1,400 @@
+//! Shared repository walker
+//!
+//! Every scanner and indexer discovers files through this walker so they all
+//! agree on what belongs to a repository:
+//! - `.gitignore` semantics (nested files, negations, `.git/info/exclude`,
+//!   global `core.excludesFile`), honoured even outside a git checkout
+//! - Project-specific exclusions from `.hiveignore` (gitignore syntax)
+//! - VCS metadata, `node_modules` and `target` are never entered
+//! - Oversized and binary files are filtered out
+//! - Directories are traversed in parallel
+
+use anyhow::{anyhow, Result};
+use ignore::{DirEntry, WalkBuilder, WalkState};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Arc};
+use tracing::debug;
+
+/// Ignore file with gitignore syntax for Hive-specific exclusions
+pub const HIVE_IGNORE_FILE: &str = ".hiveignore";
+
+/// Dependency, output and VCS directories that are never worth crawling
+///
+/// Ambiguous names such as `build` or `vendor` are left to ignore files, since
+/// some projects keep real sources under them.
+pub const DEFAULT_EXCLUDED_DIRS: &[&str] = &[".git", ".hg", ".svn", "node_modules", "target"];
+
+/// Default per-file size limit (10MB)
+pub const DEFAULT_MAX_FILE_SIZE: u64 = 10 * 1024 * 1024;
+
+/// Bytes inspected when sniffing for binary content
+const BINARY_SNIFF_LEN: usize = 8 * 1024;
+
+/// A file discovered by the walker
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WalkedFile {
+    pub path: PathBuf,
+    pub relative_path: PathBuf,
+    pub size: u64,
+}
+
+/// Gitignore-aware, parallel file walker
+#[derive(Debug, Clone)]
+pub struct RepositoryWalker {
+    root: PathBuf,
+    respect_ignore_files: bool,
+    include_hidden: bool,
+    follow_links: bool,
+    max_depth: Option<usize>,
+    max_file_size: Option<u64>,
+    skip_binary: bool,
+    extensions: Option<Vec<String>>,
+    excluded_dirs: Vec<String>,
+    threads: usize,
+}
+
+impl RepositoryWalker {
+    /// Create a walker rooted at `root` with the default filters
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            respect_ignore_files: true,
+            include_hidden: false,
+            follow_links: false,
+            max_depth: None,
+            max_file_size: Some(DEFAULT_MAX_FILE_SIZE),
+            skip_binary: true,
+            extensions: None,
+            excluded_dirs: DEFAULT_EXCLUDED_DIRS
+                .iter()
+                .map(|d| d.to_string())
+                .collect(),
+            threads: 0,
+        }
+    }
+
+    /// Honour `.gitignore`, `.ignore` and `.hiveignore` files (default: on)
+    pub fn respect_ignore_files(mut self, yes: bool) -> Self {
+        self.respect_ignore_files = yes;
+        self
+    }
+
+    /// Yield hidden files and enter hidden directories (default: off)
+    pub fn include_hidden(mut self, yes: bool) -> Self {
+        self.include_hidden = yes;
+        self
+    }
+
+    /// Follow symbolic links (default: off)
+    pub fn follow_links(mut self, yes: bool) -> Self {
+        self.follow_links = yes;
+        self
+    }
+
+    /// Limit traversal depth; the root is depth 0
+    pub fn max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = Some(depth);
+        self
+    }
+
+    /// Skip files larger than `bytes`; `None` disables the limit
+    pub fn max_file_size(mut self, bytes: Option<u64>) -> Self {
+        self.max_file_size = bytes;
+        self
+    }
+
+    /// Skip files that look binary (default: on)
+    pub fn skip_binary(mut self, yes: bool) -> Self {
+        self.skip_binary = yes;
+        self
+    }
+
+    /// Only yield files with one of these extensions (case-insensitive)
+    pub fn extensions<S: AsRef<str>>(mut self, extensions: &[S]) -> Self {
+        self.extensions = Some(
+            extensions
+                .iter()
+                .map(|e| e.as_ref().trim_start_matches('.').to_lowercase())
+                .collect(),
+        );
+        self
+    }
+
+    /// Never enter directories with these names, in addition to the defaults
+    pub fn exclude_dirs<S: AsRef<str>>(mut self, names: &[S]) -> Self {
+        self.excluded_dirs
+            .extend(names.iter().map(|n| n.as_ref().to_string()));
+        self
+    }
+
+    /// Number of traversal threads; 0 picks based on available CPUs
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.threads = threads;
+        self
+    }
+
+    /// Walk the tree and return matching files sorted by path
+    pub fn walk(&self) -> Result<Vec<WalkedFile>> {
+        if !self.root.exists() {
+            return Err(anyhow!("Path does not exist: {}", self.root.display()));
+        }
+
+        // A single file root is yielded as-is if it passes the filters
+        if self.root.is_file() {
+            let size = self.root.metadata()?.len();
+            let file = WalkedFile {
+                relative_path: self.root.file_name().map(PathBuf::from).unwrap_or_default(),
+                path: self.root.clone(),
+                size,
+            };
+            return Ok(self
+                .accepts_file(&file.path, size)
+                .then_some(file)
+                .into_iter()
+                .collect());
+        }
+
+        let (tx, rx) = mpsc::channel();
+        let filter = Arc::new(self.clone());
+
+        self.builder().build_parallel().run(|| {
+            let tx = tx.clone();
+            let filter = filter.clone();
+            Box::new(move |entry| {
+                let entry = match entry {
+                    Ok(entry) => entry,
+                    Err(e) => {
+                        debug!("Skipping unreadable entry: {}", e);
+                        return WalkState::Continue;
+                    }
+                };
+
+                if let Some(file) = filter.to_walked_file(&entry) {
+                    if tx.send(file).is_err() {
+                        return WalkState::Quit;
+                    }
+                }
+                WalkState::Continue
+            })
+        });
+        drop(tx);
+
+        let mut files: Vec<WalkedFile> = rx.into_iter().collect();
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(files)
+    }
+
+    /// Walk on the blocking thread pool, for async callers
+    pub async fn walk_async(self) -> Result<Vec<WalkedFile>> {
+        tokio::task::spawn_blocking(move || self.walk()).await?
+    }
+
+    /// Walk and return only the paths
+    pub fn walk_paths(&self) -> Result<Vec<PathBuf>> {
+        Ok(self.walk()?.into_iter().map(|f| f.path).collect())
+    }
+
+    fn builder(&self) -> WalkBuilder {
+        let mut builder = WalkBuilder::new(&self.root);
+        builder
+            .hidden(!self.include_hidden)
+            .follow_links(self.follow_links)
+            .max_depth(self.max_depth)
+            .threads(self.threads)
+            .parents(self.respect_ignore_files)
+            .ignore(self.respect_ignore_files)
+            .git_ignore(self.respect_ignore_files)
+            .git_global(self.respect_ignore_files)
+            .git_exclude(self.respect_ignore_files)
+            .require_git(false);
+
+        if self.respect_ignore_files {
+            builder.add_custom_ignore_filename(HIVE_IGNORE_FILE);
+        }
+
+        let excluded = self.excluded_dirs.clone();
+        builder.filter_entry(move |entry| {
+            let is_dir = entry.file_type().map(|t| t.is_dir()).unwrap_or(false);
+            // Never filter the root itself
+            if !is_dir || entry.depth() == 0 {
+                return true;
+            }
+            let name = entry.file_name().to_string_lossy();
+            !excluded.iter().any(|d| d.as_str() == name)
+        });
+
+        builder
+    }
+
+    fn to_walked_file(&self, entry: &DirEntry) -> Option<WalkedFile> {
+        if !entry.file_type().map(|t| t.is_file()).unwrap_or(false) {
+            return None;
+        }
+
+        let path = entry.path();
+        let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+        if !self.accepts_file(path, size) {
+            return None;
+        }
+
+        Some(WalkedFile {
+            path: path.to_path_buf(),
+            relative_path: path.strip_prefix(&self.root).unwrap_or(path).to_path_buf(),
+            size,
+        })
+    }
+
+    fn accepts_file(&self, path: &Path, size: u64) -> bool {
+        if let Some(extensions) = &self.extensions {
+            let ext = path
+                .extension()
+                .and_then(|e| e.to_str())
+                .map(|e| e.to_lowercase())
+                .unwrap_or_default();
+            if !extensions.iter().any(|e| *e == ext) {
+                return false;
+            }
+        }
+
+        if let Some(limit) = self.max_file_size {
+            if size > limit {
+                debug!("Skipping large file: {} ({} bytes)", path.display(), size);
+                return false;
+            }
+        }
+
+        !(self.skip_binary && is_binary_file(path))
+    }
+}
+
+/// Heuristic binary check: a NUL byte in the first few KB
+pub fn is_binary_file(path: &Path) -> bool {
+    let mut buffer = [0u8; BINARY_SNIFF_LEN];
+    match std::fs::File::open(path).and_then(|mut f| f.read(&mut buffer)) {
+        Ok(read) => buffer[..read].contains(&0),
+        Err(_) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn write(root: &Path, relative: &str, content: &[u8]) {
+        let path = root.join(relative);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    fn relative_paths(files: &[WalkedFile]) -> Vec<String> {
+        files
+            .iter()
+            .map(|f| f.relative_path.to_string_lossy().replace('\\', "/"))
+            .collect()
+    }
+
+    #[test]
+    fn test_skips_default_excluded_dirs() {
+        let dir = TempDir::new().unwrap();
+        write(dir.path(), "src/main.rs", b"fn main() {}");
+        write(
+            dir.path(),
+            "node_modules/pkg/index.js",
+            b"module.exports = 1;",
+        );
+        write(dir.path(), "target/debug/build.rs", b"fn main() {}");
+        write(dir.path(), "web/node_modules/pkg/index.js", b"x");
+        write(dir.path(), "vendor/github.com/pkg/lib.go", b"package pkg");
+        write(dir.path(), "build/gradle/Plugin.kt", b"class Plugin");
+
+        // Only unambiguous dependency and output directories are skipped by name
+        let files = RepositoryWalker::new(dir.path()).walk().unwrap();
+        assert_eq!(
+            relative_paths(&files),
+            vec![
+                "build/gradle/Plugin.kt",
+                "src/main.rs",
+                "vendor/github.com/pkg/lib.go"
+            ]
+        );
+
+        write(dir.path(), ".gitignore", b"vendor/\n");
+        let files = RepositoryWalker::new(dir.path()).walk().unwrap();
+        assert!(!relative_paths(&files).contains(&"vendor/github.com/pkg/lib.go".to_string()));
+    }
+
+    #[test]
+    fn test_honours_nested_gitignore_and_negation() {
+        let dir = TempDir::new().unwrap();
+        write(dir.path(), ".gitignore", b"*.log\n");
+        write(dir.path(), "app.log", b"log");
+        write(dir.path(), "src/lib.rs", b"pub fn lib() {}");
+        write(dir.path(), "src/.gitignore", b"generated/\n!keep.log\n");
+        write(dir.path(), "src/generated/out.rs", b"// generated");
+        write(dir.path(), "src/keep.log", b"kept");
+
+        let files = RepositoryWalker::new(dir.path()).walk().unwrap();
+        assert_eq!(relative_paths(&files), vec!["src/keep.log", "src/lib.rs"]);
+    }
+
+    #[test]
+    fn test_honours_hiveignore() {
+        let dir = TempDir::new().unwrap();
+        write(dir.path(), ".hiveignore", b"fixtures/\n");
+        write(dir.path(), "fixtures/big.json", b"{}");
+        write(dir.path(), "src/main.rs", b"fn main() {}");
+
+        let files = RepositoryWalker::new(dir.path()).walk().unwrap();
+        assert_eq!(relative_paths(&files), vec!["src/main.rs"]);
+
+        let all = RepositoryWalker::new(dir.path())
+            .respect_ignore_files(false)
+            .walk()
+            .unwrap();
+        assert_eq!(
+            relative_paths(&all),
+            vec!["fixtures/big.json", "src/main.rs"]
+        );
+    }
+
+    #[test]
+    fn test_binary_size_and_extension_filters() {
+        let dir = TempDir::new().unwrap();
+        write(dir.path(), "image.png", &[0x89, b'P', b'N', b'G', 0, 0, 1]);
+        write(dir.path(), "big.rs", &vec![b'a'; 2048]);
+        write(dir.path(), "small.rs", b"fn f() {}");
+        write(dir.path(), "notes.md", b"# Notes");
+
+        let files = RepositoryWalker::new(dir.path())
+            .max_file_size(Some(1024))
+            .walk()
+            .unwrap();
+        assert_eq!(relative_paths(&files), vec!["notes.md", "small.rs"]);
+
+        let rust_only = RepositoryWalker::new(dir.path())
+            .extensions(&["RS"])
+            .max_file_size(None)
+            .walk()
+            .unwrap();
+        assert_eq!(relative_paths(&rust_only), vec!["big.rs", "small.rs"]);
+    }
+
+    #[test]
+    fn test_max_depth_and_extra_excludes() {
+        let dir = TempDir::new().unwrap();
+        write(dir.path(), "a.rs", b"");
+        write(dir.path(), "one/b.rs", b"");
+        write(dir.path(), "one/two/c.rs", b"");
+        write(dir.path(), "tests/d.rs", b"");
+
+        let shallow = RepositoryWalker::new(dir.path())
+            .max_depth(2)
+            .exclude_dirs(&["tests"])
+            .walk()
+            .unwrap();
+        assert_eq!(relative_paths(&shallow), vec!["a.rs", "one/b.rs"]);
+    }
+}
@@ -15,6 +15,8 @@ use std::sync::Arc;
 use tokio::sync::{mpsc, RwLock};
 use tokio::time::{interval, Interval};
 
+use crate::analytics::metrics::{self, MetricsRegistry};
+
 /// Alert severity levels
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum AlertSeverity {
@@ -194,6 +196,36 @@ struct MetricTimeSeries {
     last_updated: DateTime<Utc>,
 }
 
+impl MetricStore {
+    /// Append a value and drop those older than `max_age`
+    fn push(&mut self, name: &str, timestamp: DateTime<Utc>, value: f64) {
+        let cutoff = Utc::now() - self.max_age;
+        let series = self
+            .metrics
+            .entry(name.to_string())
+            .or_insert_with(|| MetricTimeSeries {
+                values: Vec::new(),
+                last_updated: timestamp,
+            });
+
+        series.values.push((timestamp, value));
+        series.last_updated = timestamp;
+        series.values.retain(|(timestamp, _)| *timestamp > cutoff);
+    }
+
+    /// Copy samples recorded after `since`; returns the newest timestamp seen
+    fn sync_from(&mut self, registry: &MetricsRegistry, since: DateTime<Utc>) -> DateTime<Utc> {
+        let mut latest = since;
+        for name in registry.names() {
+            for (timestamp, value) in registry.samples_since(&name, since) {
+                self.push(&name, timestamp, value);
+                latest = latest.max(timestamp);
+            }
+        }
+        latest
+    }
+}
+
 /// Notification request
 struct NotificationRequest {
     alert: Alert,
@@ -291,25 +323,7 @@ impl AlertManager {
     /// Update metric value
     pub async fn update_metric(&self, name: &str, value: f64) -> Result<()> {
         let mut store = self.metric_store.write().await;
-        let now = Utc::now();
-
-        let max_age = store.max_age; // Get max_age before the mutable borrow
-
-        let series = store
-            .metrics
-            .entry(name.to_string())
-            .or_insert_with(|| MetricTimeSeries {
-                values: Vec::new(),
-                last_updated: now,
-            });
-
-        series.values.push((now, value));
-        series.last_updated = now;
-
-        // Clean old values
-        let cutoff = now - max_age;
-        series.values.retain(|(timestamp, _)| *timestamp > cutoff);
-
+        store.push(name, Utc::now(), value);
         Ok(())
     }
 
@@ -335,10 +349,18 @@ impl AlertManager {
                 let config = config.read().await;
                 interval(config.check_interval.to_std().unwrap())
             };
+            let registry = metrics::global();
+            let mut last_sync = Utc::now();
 
             loop {
                 interval.tick().await;
 
+                // Pull samples recorded since the last check from the live registry
+                last_sync = {
+                    let mut store = metric_store.write().await;
+                    store.sync_from(&registry, last_sync)
+                };
+
                 // Check each rule
                 let rules_snapshot = rules.read().await.clone();
 
@@ -660,11 +682,11 @@ impl AlertManager {
             alert_type: AlertType::CostThreshold,
             severity: AlertSeverity::Warning,
             conditions: vec![AlertCondition {
-                metric: "cost.hourly".to_string(),
+                metric: metrics::COST.to_string(),
                 operator: ConditionOperator::GreaterThan,
                 threshold: 100.0,
-                duration: None,
-                aggregation: None,
+                duration: Some(Duration::hours(1)),
+                aggregation: Some(AggregationType::Sum),
             }],
             condition_logic: ConditionLogic::Any,
             cooldown: Duration::hours(1),
@@ -708,7 +730,7 @@ impl AlertManager {
             alert_type: AlertType::PerformanceDegradation,
             severity: AlertSeverity::Warning,
             conditions: vec![AlertCondition {
-                metric: "performance.api.latency".to_string(),
+                metric: metrics::STAGE_LATENCY_MS.to_string(),
                 operator: ConditionOperator::GreaterThan,
                 threshold: 1000.0, // 1 second
                 duration: Some(Duration::minutes(5)),
@@ -9,7 +9,7 @@
 //! - Interactive controls
 
 use anyhow::{Context, Result};
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Datelike, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
@@ -17,6 +17,7 @@ use tokio::sync::{broadcast, RwLock};
 use tokio::time::interval;
 use tracing::{debug, info};
 
+use crate::analytics::metrics;
 use crate::analytics::AdvancedAnalyticsConfig;
 use crate::core::database::{get_database, ActivityLog};
 
@@ -384,19 +385,26 @@ impl DashboardEngine {
     }
 
     async fn fetch_line_chart_data(&self, data_source: &str) -> Result<WidgetData> {
-        // Fetch time series data
-        let activities = ActivityLog::get_recent(100).await?;
-        let mut data_points = Vec::new();
-
-        for activity in activities {
-            if let Ok(timestamp) = activity.created_at.parse::<DateTime<Utc>>() {
-                data_points.push(DataPoint {
-                    timestamp,
-                    value: 1.0, // Count queries
-                    label: None,
-                });
-            }
-        }
+        // Hourly buckets of the live metric over the last day
+        let metric = metric_for_source(data_source);
+        let rollups = metrics::load_history(
+            metric,
+            Utc::now() - chrono::Duration::hours(24),
+            chrono::Duration::hours(1),
+        )
+        .await?;
+        let data_points = rollups
+            .into_iter()
+            .map(|rollup| DataPoint {
+                timestamp: rollup.bucket,
+                value: if metric == metrics::QUERIES {
+                    rollup.count as f64
+                } else {
+                    rollup.mean()
+                },
+                label: None,
+            })
+            .collect();
 
         let series = vec![Series {
             name: "Query Volume".to_string(),
@@ -449,12 +457,13 @@ impl DashboardEngine {
     }
 
     async fn fetch_gauge_data(&self, data_source: &str) -> Result<WidgetData> {
-        // Performance gauge
-        let db = get_database().await?;
-        let stats = db.get_statistics().await?;
-
-        // Calculate average response time (placeholder)
-        let avg_response_time = 245.0;
+        // Performance gauge over the last hour
+        let avg_response_time = metrics::load_summary(
+            metric_for_source(data_source),
+            Utc::now() - chrono::Duration::hours(1),
+        )
+        .await?
+        .mean();
 
         let mut metadata = HashMap::new();
         metadata.insert("min".to_string(), serde_json::json!(0));
@@ -488,6 +497,15 @@ impl DashboardEngine {
             "total_messages" => stats.message_count as f64,
             "active_users" => stats.user_count as f64,
             "models_available" => stats.model_count as f64,
+            "total_cost" => {
+                let month_start = Utc::now()
+                    .date_naive()
+                    .with_day(1)
+                    .and_then(|day| day.and_hms_opt(0, 0, 0))
+                    .map(|start| start.and_utc())
+                    .unwrap_or_else(Utc::now);
+                metrics::load_summary(metrics::COST, month_start).await?.sum
+            }
             _ => 0.0,
         };
 
@@ -796,25 +814,26 @@ impl DataStreamer {
     }
 
     async fn start_streaming(&self, config: Arc<RwLock<AdvancedAnalyticsConfig>>) -> Result<()> {
-        let streams = Arc::clone(&self.streams);
         let sender = self.sender.clone();
+        let period = config.read().await.real_time_interval_secs.max(1);
 
         tokio::spawn(async move {
-            let mut interval = interval(std::time::Duration::from_secs(1));
+            let mut interval = interval(std::time::Duration::from_secs(period));
 
             loop {
                 interval.tick().await;
+                if sender.receiver_count() == 0 {
+                    continue;
+                }
 
-                // Generate sample metrics
-                let metric = RealTimeMetric {
-                    name: "queries_per_second".to_string(),
-                    value: rand::random::<f64>() * 10.0,
-                    unit: "qps".to_string(),
-                    timestamp: Utc::now(),
-                    tags: HashMap::new(),
-                };
-
-                let _ = sender.send(metric);
+                match live_metrics().await {
+                    Ok(live) => {
+                        for metric in live {
+                            let _ = sender.send(metric);
+                        }
+                    }
+                    Err(e) => debug!("Live metrics unavailable: {}", e),
+                }
             }
         });
 
@@ -1090,6 +1109,59 @@ impl WidgetRenderer for TableRenderer {
 
 // Helper functions
 
+/// Registry metric behind a widget data source
+fn metric_for_source(data_source: &str) -> &str {
+    match data_source {
+        "query_volume" => metrics::QUERIES,
+        "avg_response_time" => metrics::QUERY_DURATION_MS,
+        "total_cost" => metrics::COST,
+        other => other,
+    }
+}
+
+/// Current values of the streamed metrics, read from the metrics registry
+async fn live_metrics() -> Result<Vec<RealTimeMetric>> {
+    let since = Utc::now() - chrono::Duration::minutes(1);
+    let metric = |name: &str, value: f64, unit: &str| RealTimeMetric {
+        name: name.to_string(),
+        value,
+        unit: unit.to_string(),
+        timestamp: Utc::now(),
+        tags: HashMap::new(),
+    };
+
+    // Rollups are per minute, so the summary covers everything since the
+    // start of the previous minute
+    let queries = metrics::load_summary(metrics::QUERIES, since).await?;
+    let elapsed = (Utc::now() - queries.bucket).num_seconds().max(1);
+    let mut live = vec![metric(
+        "queries_per_second",
+        queries.count as f64 / elapsed as f64,
+        "qps",
+    )];
+
+    let latency = metrics::load_summary(metrics::STAGE_LATENCY_MS, since).await?;
+    if latency.count > 0 {
+        live.push(metric("stage_latency", latency.mean(), "ms"));
+    }
+
+    let hits = metrics::load_summary(metrics::CACHE_HITS, since)
+        .await?
+        .count;
+    let misses = metrics::load_summary(metrics::CACHE_MISSES, since)
+        .await?
+        .count;
+    if hits + misses > 0 {
+        live.push(metric(
+            "cache_hit_rate",
+            hits as f64 * 100.0 / (hits + misses) as f64,
+            "%",
+        ));
+    }
+
+    Ok(live)
+}
+
 fn create_sparkline(points: &[DataPoint], width: usize) -> String {
     if points.is_empty() || width == 0 {
         return String::new();
@@ -1121,16 +1193,6 @@ fn create_sparkline(points: &[DataPoint], width: usize) -> String {
     sparkline
 }
 
-// External dependency placeholder
-mod rand {
-    pub fn random<T>() -> T
-    where
-        T: Default,
-    {
-        T::default()
-    }
-}
-
 #[cfg(all(test, feature = "legacy-tests"))]
 mod tests {
     use super::*;
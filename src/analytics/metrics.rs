@@ -0,0 +1,560 @@
+//! Live Metrics Registry
+//!
+//! Collects samples from real events and serves them to the dashboard,
+//! alerting and trend analysis:
+//! - Consensus stage latency, token counts and cost
+//! - Cache hits and misses
+//! - Parse and index timings
+//! - Rolling in-memory windows with per-minute rollups persisted to SQLite
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use once_cell::sync::Lazy;
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+
+use crate::consensus::types::StageResult;
+use crate::core::database::get_database;
+
+/// Completed consensus runs (one sample per run)
+pub const QUERIES: &str = "consensus.queries";
+/// Wall-clock duration of a consensus run
+pub const QUERY_DURATION_MS: &str = "consensus.duration_ms";
+/// Duration of a single stage; also recorded per stage as `<name>.<stage>`
+pub const STAGE_LATENCY_MS: &str = "consensus.stage.latency_ms";
+/// Total tokens used by a stage
+pub const TOKENS: &str = "consensus.tokens";
+/// Cost of a stage in dollars
+pub const COST: &str = "consensus.cost";
+/// Provider errors seen while running a stage
+pub const STAGE_ERRORS: &str = "consensus.stage.errors";
+pub const CACHE_HITS: &str = "cache.hits";
+pub const CACHE_MISSES: &str = "cache.misses";
+/// Full parse of a single file
+pub const PARSE_MS: &str = "index.parse_ms";
+/// Incremental re-parse of an edited file
+pub const INCREMENTAL_PARSE_MS: &str = "index.incremental_parse_ms";
+pub const PARSE_ERRORS: &str = "index.parse_errors";
+/// A full index sync of a repository
+pub const INDEX_SYNC_MS: &str = "index.sync_ms";
+
+/// Width of a persisted rollup bucket in seconds
+const ROLLUP_SECONDS: i64 = 60;
+
+/// Rollups older than this are dropped on flush
+const RETENTION_DAYS: i64 = 90;
+
+/// Upper bound on raw samples kept per metric, whatever the window
+const MAX_SAMPLES: usize = 10_000;
+
+static REGISTRY: Lazy<Arc<MetricsRegistry>> = Lazy::new(|| Arc::new(MetricsRegistry::new()));
+
+/// The process-wide registry that instrumented code records into
+pub fn global() -> Arc<MetricsRegistry> {
+    Arc::clone(&REGISTRY)
+}
+
+/// Aggregate of the samples recorded for one metric in one bucket
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Rollup {
+    pub name: String,
+    pub bucket: DateTime<Utc>,
+    pub count: u64,
+    pub sum: f64,
+    pub min: f64,
+    pub max: f64,
+}
+
+impl Rollup {
+    fn new(name: &str, bucket: DateTime<Utc>) -> Self {
+        Self {
+            name: name.to_string(),
+            bucket,
+            count: 0,
+            sum: 0.0,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+        }
+    }
+
+    fn add(&mut self, value: f64) {
+        self.count += 1;
+        self.sum += value;
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+    }
+
+    fn merge(&mut self, other: &Rollup) {
+        self.count += other.count;
+        self.sum += other.sum;
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+    }
+
+    pub fn mean(&self) -> f64 {
+        if self.count == 0 {
+            0.0
+        } else {
+            self.sum / self.count as f64
+        }
+    }
+}
+
+/// Statistics over the raw samples of a rolling window
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WindowStats {
+    pub count: usize,
+    pub sum: f64,
+    pub mean: f64,
+    pub min: f64,
+    pub max: f64,
+    pub p95: f64,
+    pub last: Option<f64>,
+}
+
+#[derive(Default)]
+struct State {
+    samples: HashMap<String, VecDeque<(DateTime<Utc>, f64)>>,
+    /// Rollups not yet written to SQLite, keyed by name and bucket start
+    pending: HashMap<(String, i64), Rollup>,
+}
+
+/// Registry of live metrics fed by instrumented code
+pub struct MetricsRegistry {
+    window: Duration,
+    state: Mutex<State>,
+}
+
+impl Default for MetricsRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MetricsRegistry {
+    /// Create a registry keeping 15 minutes of raw samples
+    pub fn new() -> Self {
+        Self {
+            window: Duration::minutes(15),
+            state: Mutex::new(State::default()),
+        }
+    }
+
+    /// How long raw samples are kept in memory
+    pub fn with_window(mut self, window: Duration) -> Self {
+        self.window = window;
+        self
+    }
+
+    /// Record a sample taken now
+    pub fn record(&self, name: &str, value: f64) {
+        self.record_at(name, Utc::now(), value);
+    }
+
+    /// Record a single occurrence of an event
+    pub fn increment(&self, name: &str) {
+        self.record(name, 1.0);
+    }
+
+    /// Record a sample taken at `timestamp`
+    pub fn record_at(&self, name: &str, timestamp: DateTime<Utc>, value: f64) {
+        let mut state = self.state.lock().unwrap();
+
+        let cutoff = Utc::now() - self.window;
+        let samples = state.samples.entry(name.to_string()).or_default();
+        samples.push_back((timestamp, value));
+        while samples
+            .front()
+            .is_some_and(|(at, _)| *at < cutoff || samples.len() > MAX_SAMPLES)
+        {
+            samples.pop_front();
+        }
+
+        let bucket = bucket_start(timestamp, ROLLUP_SECONDS);
+        state
+            .pending
+            .entry((name.to_string(), bucket.timestamp()))
+            .or_insert_with(|| Rollup::new(name, bucket))
+            .add(value);
+    }
+
+    /// Record the latency, tokens, cost and errors of a finished stage
+    pub fn record_stage(&self, stage: &StageResult) {
+        let stage_name = stage.stage_name.to_lowercase();
+        if let Some(analytics) = &stage.analytics {
+            let latency_ms = analytics.duration * 1000.0;
+            self.record(STAGE_LATENCY_MS, latency_ms);
+            self.record(&format!("{}.{}", STAGE_LATENCY_MS, stage_name), latency_ms);
+            self.record(COST, analytics.cost);
+            if analytics.error_count > 0 {
+                self.record(STAGE_ERRORS, analytics.error_count as f64);
+            }
+        }
+        if let Some(usage) = &stage.usage {
+            self.record(TOKENS, usage.total_tokens as f64);
+        }
+    }
+
+    /// Record a completed consensus run
+    pub fn record_run(&self, duration_secs: f64) {
+        self.increment(QUERIES);
+        self.record(QUERY_DURATION_MS, duration_secs * 1000.0);
+    }
+
+    /// Names of every metric with samples in the window
+    pub fn names(&self) -> Vec<String> {
+        let state = self.state.lock().unwrap();
+        let mut names: Vec<String> = state
+            .samples
+            .iter()
+            .filter(|(_, samples)| !samples.is_empty())
+            .map(|(name, _)| name.clone())
+            .collect();
+        names.sort();
+        names
+    }
+
+    /// Raw samples of `name` recorded after `since`, oldest first
+    pub fn samples_since(&self, name: &str, since: DateTime<Utc>) -> Vec<(DateTime<Utc>, f64)> {
+        let state = self.state.lock().unwrap();
+        state
+            .samples
+            .get(name)
+            .map(|samples| {
+                samples
+                    .iter()
+                    .filter(|(at, _)| *at > since)
+                    .copied()
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Statistics of `name` over the last `window`
+    pub fn stats(&self, name: &str, window: Duration) -> WindowStats {
+        let samples = self.samples_since(name, Utc::now() - window);
+        if samples.is_empty() {
+            return WindowStats::default();
+        }
+
+        let mut values: Vec<f64> = samples.iter().map(|(_, value)| *value).collect();
+        let last = values.last().copied();
+        values.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+        let sum: f64 = values.iter().sum();
+        let p95_index = ((values.len() as f64 * 0.95).ceil() as usize).saturating_sub(1);
+
+        WindowStats {
+            count: values.len(),
+            sum,
+            mean: sum / values.len() as f64,
+            min: values[0],
+            max: values[values.len() - 1],
+            p95: values[p95_index],
+            last,
+        }
+    }
+
+    /// Samples of `name` per second over the last `window`
+    pub fn rate(&self, name: &str, window: Duration) -> f64 {
+        let seconds = window.num_seconds();
+        if seconds <= 0 {
+            return 0.0;
+        }
+        self.stats(name, window).count as f64 / seconds as f64
+    }
+
+    /// Create the rollup table if it does not exist
+    pub fn ensure_schema(conn: &Connection) -> Result<()> {
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS metric_rollups (
+                name TEXT NOT NULL,
+                bucket INTEGER NOT NULL,
+                count INTEGER NOT NULL,
+                sum REAL NOT NULL,
+                min REAL NOT NULL,
+                max REAL NOT NULL,
+                PRIMARY KEY (name, bucket)
+            );",
+        )?;
+        Ok(())
+    }
+
+    /// Write pending rollups to SQLite, merging with buckets already stored
+    /// by this or another process. Returns the number of rollups written.
+    pub fn flush(&self, conn: &Connection) -> Result<usize> {
+        Self::ensure_schema(conn)?;
+
+        let pending = std::mem::take(&mut self.state.lock().unwrap().pending);
+        if pending.is_empty() {
+            return Ok(0);
+        }
+
+        let written = pending.values().try_fold(0, |written, rollup| {
+            conn.execute(
+                "INSERT INTO metric_rollups (name, bucket, count, sum, min, max)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+                 ON CONFLICT(name, bucket) DO UPDATE SET
+                    count = count + excluded.count,
+                    sum = sum + excluded.sum,
+                    min = MIN(min, excluded.min),
+                    max = MAX(max, excluded.max)",
+                params![
+                    rollup.name,
+                    rollup.bucket.timestamp(),
+                    rollup.count as i64,
+                    rollup.sum,
+                    rollup.min,
+                    rollup.max
+                ],
+            )
+            .map(|_| written + 1)
+        });
+
+        let written = match written {
+            Ok(written) => written,
+            Err(e) => {
+                // Keep the samples for the next flush; rows written before the
+                // failure will be counted twice, which beats losing them all
+                let mut state = self.state.lock().unwrap();
+                for (key, rollup) in pending {
+                    match state.pending.get_mut(&key) {
+                        Some(existing) => existing.merge(&rollup),
+                        None => {
+                            state.pending.insert(key, rollup);
+                        }
+                    }
+                }
+                return Err(e.into());
+            }
+        };
+
+        let cutoff = Utc::now() - Duration::days(RETENTION_DAYS);
+        conn.execute(
+            "DELETE FROM metric_rollups WHERE bucket < ?1",
+            params![cutoff.timestamp()],
+        )?;
+        Ok(written)
+    }
+
+    /// Persisted rollups of `name` since `since`, merged into buckets of
+    /// `bucket` width. Pending samples are flushed first.
+    pub fn history(
+        &self,
+        conn: &Connection,
+        name: &str,
+        since: DateTime<Utc>,
+        bucket: Duration,
+    ) -> Result<Vec<Rollup>> {
+        self.flush(conn)?;
+
+        let mut stmt = conn.prepare(
+            "SELECT bucket, count, sum, min, max FROM metric_rollups
+             WHERE name = ?1 AND bucket >= ?2
+             ORDER BY bucket",
+        )?;
+        let rows = stmt.query_map(
+            params![name, bucket_start(since, ROLLUP_SECONDS).timestamp()],
+            |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, i64>(1)?,
+                    row.get::<_, f64>(2)?,
+                    row.get::<_, f64>(3)?,
+                    row.get::<_, f64>(4)?,
+                ))
+            },
+        )?;
+
+        let width = bucket.num_seconds().max(ROLLUP_SECONDS);
+        let mut merged: BTreeMap<i64, Rollup> = BTreeMap::new();
+        for row in rows {
+            let (start, count, sum, min, max) = row?;
+            let start = DateTime::from_timestamp(start, 0).unwrap_or_default();
+            let coarse = bucket_start(start, width);
+            merged
+                .entry(coarse.timestamp())
+                .or_insert_with(|| Rollup::new(name, coarse))
+                .merge(&Rollup {
+                    name: name.to_string(),
+                    bucket: start,
+                    count: count as u64,
+                    sum,
+                    min,
+                    max,
+                });
+        }
+        Ok(merged.into_values().collect())
+    }
+
+    /// Everything recorded for `name` since `since` as a single rollup
+    pub fn summary(&self, conn: &Connection, name: &str, since: DateTime<Utc>) -> Result<Rollup> {
+        let mut total = Rollup::new(name, bucket_start(since, ROLLUP_SECONDS));
+        for rollup in self.history(conn, name, since, Duration::seconds(ROLLUP_SECONDS))? {
+            total.merge(&rollup);
+        }
+        Ok(total)
+    }
+}
+
+/// Flush the global registry to the application database
+pub async fn persist() -> Result<usize> {
+    let db = get_database().await?;
+    let conn = db.get_connection()?;
+    global().flush(&conn)
+}
+
+/// [`MetricsRegistry::history`] of the global registry
+pub async fn load_history(
+    name: &str,
+    since: DateTime<Utc>,
+    bucket: Duration,
+) -> Result<Vec<Rollup>> {
+    let db = get_database().await?;
+    let conn = db.get_connection()?;
+    global().history(&conn, name, since, bucket)
+}
+
+/// [`MetricsRegistry::summary`] of the global registry
+pub async fn load_summary(name: &str, since: DateTime<Utc>) -> Result<Rollup> {
+    let db = get_database().await?;
+    let conn = db.get_connection()?;
+    global().summary(&conn, name, since)
+}
+
+fn bucket_start(timestamp: DateTime<Utc>, width: i64) -> DateTime<Utc> {
+    let seconds = timestamp.timestamp();
+    DateTime::from_timestamp(seconds - seconds.rem_euclid(width), 0).unwrap_or(timestamp)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consensus::types::{AnalyticsFeatures, StageAnalytics, TokenUsage};
+
+    fn stage(name: &str, duration: f64, cost: f64, tokens: u32) -> StageResult {
+        StageResult {
+            stage_id: "id".to_string(),
+            stage_name: name.to_string(),
+            question: String::new(),
+            answer: String::new(),
+            model: "model".to_string(),
+            conversation_id: "conversation".to_string(),
+            timestamp: Utc::now(),
+            usage: Some(TokenUsage {
+                prompt_tokens: tokens / 2,
+                completion_tokens: tokens - tokens / 2,
+                total_tokens: tokens,
+            }),
+            analytics: Some(StageAnalytics {
+                duration,
+                cost,
+                input_cost: 0.0,
+                output_cost: 0.0,
+                provider: "openrouter".to_string(),
+                model_internal_id: "model".to_string(),
+                quality_score: 1.0,
+                error_count: 0,
+                fallback_used: false,
+                rate_limit_hit: false,
+                retry_count: 0,
+                start_time: Utc::now(),
+                end_time: Utc::now(),
+                time_to_first_token: None,
+                classification_latency: None,
+                memory_usage: None,
+                features: AnalyticsFeatures {
+                    streaming: false,
+                    routing_variant: "test".to_string(),
+                    optimization_applied: None,
+                },
+                candidates: Vec::new(),
+                context: None,
+            }),
+        }
+    }
+
+    #[test]
+    fn test_window_stats_from_stage_results() {
+        let registry = MetricsRegistry::new();
+        registry.record_stage(&stage("Generator", 1.0, 0.01, 100));
+        registry.record_stage(&stage("Curator", 3.0, 0.03, 300));
+
+        let latency = registry.stats(STAGE_LATENCY_MS, Duration::minutes(1));
+        assert_eq!(latency.count, 2);
+        assert_eq!(latency.mean, 2000.0);
+        assert_eq!(latency.p95, 3000.0);
+        assert_eq!(latency.last, Some(3000.0));
+
+        let curator = registry.stats(
+            &format!("{}.curator", STAGE_LATENCY_MS),
+            Duration::minutes(1),
+        );
+        assert_eq!(curator.count, 1);
+        assert_eq!(registry.stats(TOKENS, Duration::minutes(1)).sum, 400.0);
+        assert!((registry.stats(COST, Duration::minutes(1)).sum - 0.04).abs() < 1e-9);
+        assert!(registry
+            .stats(STAGE_ERRORS, Duration::minutes(1))
+            .last
+            .is_none());
+    }
+
+    #[test]
+    fn test_samples_outside_window_are_dropped() {
+        let registry = MetricsRegistry::new().with_window(Duration::minutes(5));
+        registry.record_at(CACHE_HITS, Utc::now() - Duration::minutes(10), 1.0);
+        registry.increment(CACHE_HITS);
+
+        assert_eq!(registry.stats(CACHE_HITS, Duration::hours(1)).count, 1);
+        assert_eq!(registry.rate(CACHE_HITS, Duration::seconds(10)), 0.1);
+        assert_eq!(registry.names(), vec![CACHE_HITS.to_string()]);
+    }
+
+    #[test]
+    fn test_rollups_persist_and_merge_across_flushes() -> Result<()> {
+        let conn = Connection::open_in_memory()?;
+        let hour = bucket_start(Utc::now() - Duration::hours(2), 3600);
+
+        let first = MetricsRegistry::new();
+        first.record_at(QUERY_DURATION_MS, hour, 100.0);
+        first.record_at(QUERY_DURATION_MS, hour + Duration::minutes(30), 300.0);
+        assert_eq!(first.flush(&conn)?, 2);
+        assert_eq!(first.flush(&conn)?, 0);
+
+        // A second process writing into an existing bucket
+        let second = MetricsRegistry::new();
+        second.record_at(QUERY_DURATION_MS, hour + Duration::seconds(10), 50.0);
+        second.record_at(QUERY_DURATION_MS, hour + Duration::hours(1), 200.0);
+
+        let hourly = second.history(&conn, QUERY_DURATION_MS, hour, Duration::hours(1))?;
+        assert_eq!(hourly.len(), 2);
+        assert_eq!(hourly[0].bucket, hour);
+        assert_eq!(hourly[0].count, 3);
+        assert_eq!(hourly[0].min, 50.0);
+        assert_eq!(hourly[0].max, 300.0);
+        assert_eq!(hourly[1].mean(), 200.0);
+
+        let total = second.summary(&conn, QUERY_DURATION_MS, hour)?;
+        assert_eq!(total.count, 4);
+        assert_eq!(total.sum, 650.0);
+        assert!(second
+            .history(&conn, QUERIES, hour, Duration::hours(1))?
+            .is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_flush_prunes_expired_rollups() -> Result<()> {
+        let conn = Connection::open_in_memory()?;
+        let registry = MetricsRegistry::new();
+        registry.record_at(COST, Utc::now() - Duration::days(RETENTION_DAYS + 1), 1.0);
+        registry.record(COST, 2.0);
+        registry.flush(&conn)?;
+
+        let rows: i64 =
+            conn.query_row("SELECT COUNT(*) FROM metric_rollups", [], |row| row.get(0))?;
+        assert_eq!(rows, 1);
+        Ok(())
+    }
+}
@@ -12,6 +12,7 @@
 //! - Custom dashboard builder
 //! - Analytics REST API
 //! - Comprehensive export functionality
+//! - Live metrics registry fed by consensus, cache and index events
 
 pub mod alerts;
 pub mod api;
@@ -20,6 +21,7 @@ pub mod cost_intelligence;
 pub mod dashboard;
 pub mod executive;
 pub mod export;
+pub mod metrics;
 pub mod ml_models;
 pub mod performance;
 pub mod templates;
@@ -15,8 +15,11 @@ use std::sync::Arc;
 use tokio::sync::RwLock;
 use tracing::{debug, info, warn};
 
+use crate::analytics::metrics;
 use crate::analytics::AdvancedAnalyticsConfig;
-use crate::core::database::{get_database, ActivityLog};
+
+/// Days of rollups loaded into a time series model
+const HISTORY_DAYS: i64 = 90;
 
 /// Trend analyzer with ML capabilities
 pub struct TrendAnalyzer {
@@ -413,22 +416,34 @@ impl TrendAnalyzer {
     // Private helper methods
 
     async fn update_model_data(&self, model: &mut TimeSeriesModel) -> Result<()> {
-        // Fetch recent data from database
-        let db = get_database().await?;
-        let activities = ActivityLog::get_recent(1000).await?;
-
-        // Extract metric data points
-        for activity in activities {
-            // Example: Extract query count metric
-            if model.metric_name == "query_count" && activity.event_type == "query_complete" {
-                model.data_points.push_back(DataPoint {
-                    timestamp: activity.created_at.parse()?,
-                    value: 1.0,
-                    metadata: HashMap::new(),
-                });
-            }
-            // Add more metric extraction logic here
-        }
+        // Hourly rollups from the live metrics registry; counts for event
+        // metrics such as queries, means for everything else
+        let metric = match model.metric_name.as_str() {
+            "query_count" => metrics::QUERIES,
+            name => name,
+        };
+        let rollups = metrics::load_history(
+            metric,
+            Utc::now() - Duration::days(HISTORY_DAYS),
+            Duration::hours(1),
+        )
+        .await?;
+
+        model.data_points = rollups
+            .into_iter()
+            .map(|rollup| DataPoint {
+                timestamp: rollup.bucket,
+                value: if metric == metrics::QUERIES {
+                    rollup.count as f64
+                } else {
+                    rollup.mean()
+                },
+                metadata: HashMap::from([
+                    ("count".to_string(), rollup.count.to_string()),
+                    ("max".to_string(), rollup.max.to_string()),
+                ]),
+            })
+            .collect();
 
         // Limit data points to prevent memory growth
         while model.data_points.len() > 10000 {
@@ -7,13 +7,16 @@
 //! - Multi-threaded consensus processing
 //! - No CPU overheating issues
 
-use axum::http::Method;
+use axum::http::{HeaderMap, Method};
 use axum::{
     extract::{
         ws::{WebSocket, WebSocketUpgrade},
-        State,
+        Path, Query, State,
+    },
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Response,
     },
-    response::IntoResponse,
     routing::{get, post},
     Json, Router,
 };
@@ -21,8 +24,10 @@ use futures::{sink::SinkExt, StreamExt};
 use hive_ai::{
     ai_helpers::AIHelperEcosystem,
     consensus::{
+        cancellation::{CancellationReason, CancellationToken},
         engine::ConsensusEngine,
         streaming::{ConsensusEvent, ProgressInfo, StreamingCallbacks},
+        threads::{Thread, ThreadStore},
         types::{Stage, StageResult},
     },
     core::{
@@ -30,6 +35,12 @@ use hive_ai::{
         database::{get_database, initialize_database, DatabaseManager},
     },
     maintenance::{BackgroundMaintenance, MaintenanceConfig},
+    server::jobs::{JobConfig, JobEvent, JobEventKind, JobManager, JobStore, JobSubmission},
+    server::metrics,
+    server::openai::{
+        ApiKeyAuth, ChatCompletionChunk, ChatCompletionRequest, ChatCompletionResponse,
+        ChatStreamCallbacks, ChatStreamEvent, ModelList, OpenAiError, Usage,
+    },
 };
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
@@ -70,8 +81,22 @@ enum WSMessage {
         context: Option<Vec<ContextMessage>>,
     },
     CancelConsensus,
+    AttachJob {
+        job_id: String,
+        offset: Option<u64>,
+    },
+    CancelJob {
+        job_id: String,
+    },
 
     // Server -> Client
+    JobCreated {
+        job_id: String,
+        conversation_id: String,
+    },
+    JobEvent {
+        event: JobEvent,
+    },
     ProfileLoaded {
         name: String,
         models: Vec<String>,
@@ -249,6 +274,8 @@ struct AppState {
     database: Arc<RwLock<Option<Arc<DatabaseManager>>>>,
     ai_helpers: Arc<RwLock<Option<Arc<AIHelperEcosystem>>>>,
     maintenance: Arc<RwLock<Option<Arc<BackgroundMaintenance>>>>,
+    api_auth: ApiKeyAuth,
+    jobs: Option<JobManager>,
 }
 
 #[tokio::main]
@@ -283,7 +310,7 @@ async fn main() -> anyhow::Result<()> {
 
     // Defer consensus engine initialization to avoid blocking server startup
     // We'll initialize it in the background after the server starts listening
-    let consensus_engine = None;
+    let consensus_engine: Arc<RwLock<Option<ConsensusEngine>>> = Arc::new(RwLock::new(None));
     info!("⏳ Consensus engine will be initialized after server starts...");
 
     // AI helpers are now managed internally by ConsensusEngine
@@ -309,12 +336,63 @@ async fn main() -> anyhow::Result<()> {
         None
     };
 
+    // API keys for the OpenAI-compatible endpoints (comma-separated `key` or `user=key`)
+    let api_auth = match std::env::var("HIVE_SERVER_API_KEYS") {
+        Ok(keys) => ApiKeyAuth::from_key_list(&keys).await?,
+        Err(_) => ApiKeyAuth::disabled(),
+    };
+    if api_auth.is_enabled() {
+        info!("🔑 API key authentication enabled for /v1 endpoints");
+    } else {
+        warn!("⚠️ HIVE_SERVER_API_KEYS not set - /v1 endpoints accept unauthenticated requests");
+    }
+
+    // Durable job queue (requires the database)
+    let jobs = match database {
+        Some(ref db) => match JobStore::new(db.clone()) {
+            Ok(store) => {
+                let mut config = JobConfig::default();
+                if let Some(limit) = std::env::var("HIVE_JOBS_PER_USER")
+                    .ok()
+                    .and_then(|v| v.parse().ok())
+                {
+                    config.max_concurrent_per_user = limit;
+                }
+                info!(
+                    "✅ Job queue ready ({} concurrent jobs per user)",
+                    config.max_concurrent_per_user
+                );
+                Some(JobManager::new(
+                    Arc::new(store),
+                    consensus_engine.clone(),
+                    config,
+                ))
+            }
+            Err(e) => {
+                warn!("⚠️ Job queue unavailable: {}", e);
+                None
+            }
+        },
+        None => None,
+    };
+
+    if let Some(ref jobs) = jobs {
+        let jobs = jobs.clone();
+        tokio::spawn(async move {
+            if let Err(e) = jobs.recover().await {
+                error!("Failed to recover consensus jobs: {}", e);
+            }
+        });
+    }
+
     // Create shared state
     let state = Arc::new(AppState {
-        consensus_engine: Arc::new(RwLock::new(consensus_engine)),
+        consensus_engine,
         database: Arc::new(RwLock::new(database.clone())),
         ai_helpers: Arc::new(RwLock::new(ai_helpers)),
         maintenance: Arc::new(RwLock::new(maintenance)),
+        api_auth,
+        jobs,
     });
 
     // Clone state for background initialization
@@ -356,14 +434,25 @@ async fn main() -> anyhow::Result<()> {
         .route("/api/consensus/quick", post(quick_consensus))
         .route("/api/ai-helper/route", post(ai_routing_decision))
         .route("/api/profiles", get(list_profiles))
+        .route("/api/jobs", get(list_jobs).post(submit_job))
+        .route("/api/jobs/:id", get(get_job))
+        .route("/api/jobs/:id/cancel", post(cancel_job))
+        .route("/api/jobs/:id/events", get(job_events))
+        .route("/api/threads", get(list_threads))
+        .route("/api/threads/:id", get(get_thread).delete(delete_thread))
+        .route("/api/threads/:id/branch", post(branch_thread))
         .route("/api/maintenance/status", get(maintenance_status))
         .route("/api/maintenance/sync", post(force_maintenance_sync))
         .route("/health", get(health_check))
+        .route("/metrics", get(metrics::metrics_handler))
+        // OpenAI-compatible API
+        .route("/v1/models", get(openai_list_models))
+        .route("/v1/chat/completions", post(openai_chat_completions))
         // CORS for Electron
         .layer(
             CorsLayer::new()
                 .allow_origin(tower_http::cors::Any)
-                .allow_methods([Method::GET, Method::POST, Method::OPTIONS])
+                .allow_methods([Method::GET, Method::POST, Method::DELETE, Method::OPTIONS])
                 .allow_headers(tower_http::cors::Any),
         )
         .with_state(state);
@@ -392,6 +481,9 @@ async fn main() -> anyhow::Result<()> {
     info!("🔌 WebSocket endpoint: ws://{}/ws", addr);
     info!("🧠 REST consensus: POST http://{}/api/consensus", addr);
     info!("🤖 AI routing: POST http://{}/api/ai-helper/route", addr);
+    info!("🧩 OpenAI-compatible API: http://{}/v1", addr);
+    info!("📬 Job queue: http://{}/api/jobs", addr);
+    info!("📈 Prometheus metrics: http://{}/metrics", addr);
     info!("📊 Multi-threaded processing enabled");
     info!("🔥 CPU overheating protection active");
 
@@ -492,6 +584,9 @@ async fn handle_socket(socket: WebSocket, state: Arc<AppState>) {
         info!("Message forwarding task ended");
     });
 
+    // Jobs started from this socket, for CancelConsensus
+    let mut socket_jobs: Vec<String> = Vec::new();
+
     // Handle incoming messages
     while let Some(msg) = socket_rx.next().await {
         if let Ok(msg) = msg {
@@ -509,6 +604,37 @@ async fn handle_socket(socket: WebSocket, state: Arc<AppState>) {
                                     context,
                                 } => {
                                     info!("Received StartConsensus message!");
+
+                                    // Back the run with a durable job so it survives this socket
+                                    if let Some(ref jobs) = state.jobs {
+                                        let submission = JobSubmission {
+                                            query,
+                                            profile,
+                                            context: build_context_string(context),
+                                            conversation_id,
+                                        };
+                                        match jobs.submit(LOCAL_USER, submission).await {
+                                            Ok(job) => {
+                                                let _ = tx.send(WSMessage::JobCreated {
+                                                    job_id: job.id.clone(),
+                                                    conversation_id: job.conversation_id.clone(),
+                                                });
+                                                socket_jobs.push(job.id.clone());
+                                                forward_job_as_legacy_messages(
+                                                    jobs.clone(),
+                                                    job.id,
+                                                    tx.clone(),
+                                                );
+                                            }
+                                            Err(e) => {
+                                                let _ = tx.send(WSMessage::Error {
+                                                    message: format!("Failed to submit job: {}", e),
+                                                });
+                                            }
+                                        }
+                                        continue;
+                                    }
+
                                     // Run consensus in separate task to avoid blocking
                                     let state_clone = state.clone();
                                     let tx_clone = tx.clone();
@@ -527,7 +653,52 @@ async fn handle_socket(socket: WebSocket, state: Arc<AppState>) {
                                 }
                                 WSMessage::CancelConsensus => {
                                     info!("Consensus cancellation requested");
-                                    // TODO: Implement cancellation token
+                                    if let Some(ref jobs) = state.jobs {
+                                        for job_id in socket_jobs.drain(..) {
+                                            if let Err(e) = jobs.cancel(&job_id).await {
+                                                warn!("Failed to cancel job {}: {}", job_id, e);
+                                            }
+                                        }
+                                    }
+                                }
+                                WSMessage::CancelJob { job_id } => {
+                                    if let Some(ref jobs) = state.jobs {
+                                        if let Err(e) = jobs.cancel(&job_id).await {
+                                            warn!("Failed to cancel job {}: {}", job_id, e);
+                                        }
+                                    }
+                                }
+                                WSMessage::AttachJob { job_id, offset } => {
+                                    let Some(jobs) = state.jobs.clone() else {
+                                        let _ = tx.send(WSMessage::Error {
+                                            message: "Job queue not available".to_string(),
+                                        });
+                                        continue;
+                                    };
+                                    let tx_clone = tx.clone();
+                                    tokio::spawn(async move {
+                                        match jobs.attach(&job_id, offset.unwrap_or(0)).await {
+                                            Ok(events) => {
+                                                futures::pin_mut!(events);
+                                                while let Some(event) = events.next().await {
+                                                    if tx_clone
+                                                        .send(WSMessage::JobEvent { event })
+                                                        .is_err()
+                                                    {
+                                                        break;
+                                                    }
+                                                }
+                                            }
+                                            Err(e) => {
+                                                let _ = tx_clone.send(WSMessage::Error {
+                                                    message: format!(
+                                                        "Failed to attach to job: {}",
+                                                        e
+                                                    ),
+                                                });
+                                            }
+                                        }
+                                    });
                                 }
                                 _ => {
                                     info!("Received other WSMessage type");
@@ -548,6 +719,100 @@ async fn handle_socket(socket: WebSocket, state: Arc<AppState>) {
     info!("WebSocket connection closed");
 }
 
+// User id for requests that arrive without API key authentication
+const LOCAL_USER: &str = "local";
+
+// Build context string from conversation history
+fn build_context_string(context: Option<Vec<ContextMessage>>) -> Option<String> {
+    let ctx_messages = context?;
+    if ctx_messages.is_empty() {
+        return None;
+    }
+
+    let mut ctx = String::new();
+    ctx.push_str("Previous conversation context:\n");
+    for msg in ctx_messages.iter().take(10) {
+        // Limit to last 10 messages for context
+        ctx.push_str(&format!("{}: {}\n", msg.role, msg.content));
+    }
+    Some(ctx)
+}
+
+// Stream a job's events to a socket using the messages the Electron UI already handles
+fn forward_job_as_legacy_messages(
+    jobs: JobManager,
+    job_id: String,
+    tx: mpsc::UnboundedSender<WSMessage>,
+) {
+    tokio::spawn(async move {
+        let events = match jobs.attach(&job_id, 0).await {
+            Ok(events) => events,
+            Err(e) => {
+                let _ = tx.send(WSMessage::Error {
+                    message: format!("Failed to attach to job: {}", e),
+                });
+                return;
+            }
+        };
+        futures::pin_mut!(events);
+
+        while let Some(event) = events.next().await {
+            let msg = match event.kind {
+                JobEventKind::ProfileLoaded { name, models } => {
+                    WSMessage::ProfileLoaded { name, models }
+                }
+                JobEventKind::ModeDecision {
+                    direct_mode,
+                    reason,
+                } => WSMessage::AIHelperDecision {
+                    direct_mode,
+                    reason,
+                },
+                JobEventKind::StageStarted { stage, model } => WSMessage::StageStarted {
+                    stage: stage.display_name().to_string(),
+                    model,
+                },
+                JobEventKind::Chunk { stage, chunk } => WSMessage::StreamChunk {
+                    stage: stage.display_name().to_string(),
+                    chunk,
+                },
+                JobEventKind::StageCompleted {
+                    stage,
+                    prompt_tokens,
+                    completion_tokens,
+                    cost,
+                    ..
+                } => WSMessage::StageCompleted {
+                    stage: stage.display_name().to_string(),
+                    tokens: prompt_tokens + completion_tokens,
+                    cost,
+                },
+                JobEventKind::Error { stage, message } => WSMessage::Error {
+                    message: match stage {
+                        Some(stage) => format!("{} error: {}", stage.display_name(), message),
+                        None => message,
+                    },
+                },
+                JobEventKind::Completed {
+                    result,
+                    total_tokens,
+                    total_cost,
+                } => WSMessage::ConsensusComplete {
+                    result,
+                    total_tokens,
+                    total_cost,
+                },
+                JobEventKind::StatusChanged { .. } => continue,
+            };
+
+            if tx.send(msg).is_err() {
+                // Socket is gone; the job keeps running and can be reattached
+                break;
+            }
+        }
+    });
+}
+
 async fn run_consensus_streaming(
     query: String,
     profile: Option<String>,
@@ -579,21 +844,7 @@ async fn run_consensus_streaming(
     info!("Consensus engine obtained, preparing to process...");
 
     // Build context string from conversation history
-    let context_str = if let Some(ctx_messages) = context {
-        if !ctx_messages.is_empty() {
-            let mut ctx = String::new();
-            ctx.push_str("Previous conversation context:\n");
-            for msg in ctx_messages.iter().take(10) {
-                // Limit to last 10 messages for context
-                ctx.push_str(&format!("{}: {}\n", msg.role, msg.content));
-            }
-            Some(ctx)
-        } else {
-            None
-        }
-    } else {
-        None
-    };
+    let context_str = build_context_string(context);
 
     if let Some(ref ctx) = context_str {
         info!("Using conversation context with {} characters", ctx.len());
@@ -849,6 +1100,461 @@ async fn list_profiles(State(state): State<Arc<AppState>>) -> Json<serde_json::V
     }
 }
 
+// OpenAI-compatible model listing: each consensus profile is a virtual model
+async fn openai_list_models(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Json<ModelList>, OpenAiError> {
+    state.api_auth.authorize(&headers).await?;
+
+    let engine = state
+        .consensus_engine
+        .read()
+        .await
+        .clone()
+        .ok_or_else(|| OpenAiError::unavailable("Consensus engine not initialized"))?;
+
+    let profiles = engine
+        .get_profiles()
+        .await
+        .map_err(|e| OpenAiError::internal(format!("Failed to load profiles: {}", e)))?;
+
+    Ok(Json(ModelList::from_profiles(&profiles)))
+}
+
+// OpenAI-compatible chat completions (streaming SSE and non-streaming)
+async fn openai_chat_completions(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(req): Json<ChatCompletionRequest>,
+) -> Result<Response, OpenAiError> {
+    let api_key = state.api_auth.authorize(&headers).await?;
+
+    let engine = state
+        .consensus_engine
+        .read()
+        .await
+        .clone()
+        .ok_or_else(|| OpenAiError::unavailable("Consensus engine not initialized"))?;
+
+    let consensus_request = req.to_consensus_request(api_key.map(|k| k.user_id))?;
+
+    if let Some(ref profile_name) = consensus_request.profile_override {
+        let profiles = engine
+            .get_profiles()
+            .await
+            .map_err(|e| OpenAiError::internal(format!("Failed to load profiles: {}", e)))?;
+        if !profiles.iter().any(|p| &p.profile_name == profile_name) {
+            return Err(OpenAiError::model_not_found(&req.model));
+        }
+    }
+
+    let id = format!("chatcmpl-{}", uuid::Uuid::new_v4().simple());
+    let created = chrono::Utc::now().timestamp();
+    let model = req.model.clone();
+
+    info!(
+        "OpenAI chat completion {} (model: {}, stream: {})",
+        id, model, req.stream
+    );
+
+    let (tx, mut rx) = mpsc::unbounded_channel::<ChatStreamEvent>();
+    let callbacks = Arc::new(ChatStreamCallbacks::new(tx.clone()));
+    let cancellation_token = CancellationToken::new();
+
+    let run_token = cancellation_token.clone();
+    tokio::spawn(async move {
+        let event = match engine
+            .process_request(&consensus_request, callbacks, run_token)
+            .await
+        {
+            Ok(result) => ChatStreamEvent::Done(Box::new(result)),
+            Err(e) => ChatStreamEvent::Failed(e.to_string()),
+        };
+        let _ = tx.send(event);
+    });
+
+    if !req.stream {
+        // axum drops this future when the client disconnects; the guard then
+        // cancels the run instead of letting it finish unobserved
+        let mut guard = CancelOnDrop(Some(cancellation_token));
+        while let Some(event) = rx.recv().await {
+            match event {
+                ChatStreamEvent::Content(_) => {}
+                ChatStreamEvent::Done(result) => {
+                    guard.disarm();
+                    return Ok(Json(ChatCompletionResponse::from_result(
+                        &id, &model, created, &result,
+                    ))
+                    .into_response());
+                }
+                ChatStreamEvent::Failed(message) => {
+                    guard.disarm();
+                    return Err(OpenAiError::internal(format!(
+                        "Consensus failed: {}",
+                        message
+                    )));
+                }
+            }
+        }
+        guard.disarm();
+        return Err(OpenAiError::internal("Consensus ended without a result"));
+    }
+
+    let include_usage = req.include_usage();
+    let (event_tx, event_rx) = mpsc::unbounded_channel::<Event>();
+
+    // Translate consensus events into SSE frames. If the client disconnects the
+    // receiver is dropped, the next send fails and the consensus run is cancelled.
+    tokio::spawn(async move {
+        let chunk = |value: &ChatCompletionChunk| {
+            Event::default().data(serde_json::to_string(value).unwrap_or_default())
+        };
+
+        let mut frames = vec![chunk(&ChatCompletionChunk::role(&id, &model, created))];
+        loop {
+            let finished = match rx.recv().await {
+                Some(ChatStreamEvent::Content(text)) => {
+                    frames.push(chunk(&ChatCompletionChunk::content(
+                        &id, &model, created, &text,
+                    )));
+                    false
+                }
+                Some(ChatStreamEvent::Done(result)) => {
+                    frames.push(chunk(&ChatCompletionChunk::finish(
+                        &id, &model, created, "stop",
+                    )));
+                    if include_usage {
+                        frames.push(chunk(&ChatCompletionChunk::usage(
+                            &id,
+                            &model,
+                            created,
+                            Usage::from_result(&result),
+                        )));
+                    }
+                    true
+                }
+                Some(ChatStreamEvent::Failed(message)) => {
+                    let error = OpenAiError::internal(format!("Consensus failed: {}", message));
+                    frames.push(Event::default().data(error.body().to_string()));
+                    true
+                }
+                None => true,
+            };
+
+            if finished {
+                frames.push(Event::default().data("[DONE]"));
+            }
+
+            for frame in frames.drain(..) {
+                if event_tx.send(frame).is_err() {
+                    info!("Client disconnected from {}, cancelling consensus", id);
+                    cancellation_token.cancel(CancellationReason::UserRequested);
+                    return;
+                }
+            }
+
+            if finished {
+                break;
+            }
+        }
+    });
+
+    let stream = tokio_stream::wrappers::UnboundedReceiverStream::new(event_rx)
+        .map(Ok::<_, std::convert::Infallible>);
+
+    Ok(Sse::new(stream)
+        .keep_alive(KeepAlive::default())
+        .into_response())
+}
+
+/// Cancels a consensus run when dropped before the run finished
+struct CancelOnDrop(Option<CancellationToken>);
+
+impl CancelOnDrop {
+    fn disarm(&mut self) {
+        self.0 = None;
+    }
+}
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        if let Some(token) = self.0.take() {
+            info!("Client disconnected, cancelling consensus");
+            token.cancel(CancellationReason::UserRequested);
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct JobListQuery {
+    limit: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JobEventsQuery {
+    offset: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ThreadListQuery {
+    limit: Option<usize>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct BranchThreadRequest {
+    /// Number of turns to keep; the whole thread when absent
+    at: Option<usize>,
+}
+
+// Resolve the caller for job endpoints (API key owner, or the local user when auth is off)
+async fn job_user(state: &AppState, headers: &HeaderMap) -> Result<String, OpenAiError> {
+    Ok(state
+        .api_auth
+        .authorize(headers)
+        .await?
+        .map(|key| key.user_id)
+        .unwrap_or_else(|| LOCAL_USER.to_string()))
+}
+
+fn job_manager(state: &AppState) -> Result<JobManager, OpenAiError> {
+    state
+        .jobs
+        .clone()
+        .ok_or_else(|| OpenAiError::unavailable("Job queue not available - no database"))
+}
+
+// Load a job, hiding other users' jobs
+fn owned_job(
+    jobs: &JobManager,
+    job_id: &str,
+    user_id: &str,
+) -> Result<hive_ai::server::jobs::JobRecord, OpenAiError> {
+    match jobs.store().get_job(job_id) {
+        Ok(Some(job)) if job.user_id == user_id => Ok(job),
+        Ok(_) => Err(OpenAiError {
+            status: axum::http::StatusCode::NOT_FOUND,
+            message: format!("Job {} not found", job_id),
+            error_type: "invalid_request_error",
+            code: Some("job_not_found"),
+        }),
+        Err(e) => Err(OpenAiError::internal(format!("Failed to load job: {}", e))),
+    }
+}
+
+// Submit a consensus job; returns immediately with the job id
+async fn submit_job(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(submission): Json<JobSubmission>,
+) -> Result<Json<serde_json::Value>, OpenAiError> {
+    let user_id = job_user(&state, &headers).await?;
+    let jobs = job_manager(&state)?;
+
+    let job = jobs
+        .submit(&user_id, submission)
+        .await
+        .map_err(|e| OpenAiError::invalid_request(e.to_string()))?;
+
+    Ok(Json(serde_json::json!({ "job": job })))
+}
+
+// List the caller's most recent jobs
+async fn list_jobs(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(query): Query<JobListQuery>,
+) -> Result<Json<serde_json::Value>, OpenAiError> {
+    let user_id = job_user(&state, &headers).await?;
+    let jobs = job_manager(&state)?;
+
+    let list = jobs
+        .store()
+        .list_jobs(Some(&user_id), query.limit.unwrap_or(50).min(500))
+        .map_err(|e| OpenAiError::internal(format!("Failed to list jobs: {}", e)))?;
+
+    Ok(Json(serde_json::json!({ "jobs": list })))
+}
+
+// Job status with per-stage output
+async fn get_job(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(job_id): Path<String>,
+) -> Result<Json<serde_json::Value>, OpenAiError> {
+    let user_id = job_user(&state, &headers).await?;
+    let jobs = job_manager(&state)?;
+    let job = owned_job(&jobs, &job_id, &user_id)?;
+
+    let stages = jobs
+        .store()
+        .stage_outputs(&job_id)
+        .map_err(|e| OpenAiError::internal(format!("Failed to load stage output: {}", e)))?;
+
+    Ok(Json(serde_json::json!({ "job": job, "stages": stages })))
+}
+
+// Cancel a queued or running job
+async fn cancel_job(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(job_id): Path<String>,
+) -> Result<Json<serde_json::Value>, OpenAiError> {
+    let user_id = job_user(&state, &headers).await?;
+    let jobs = job_manager(&state)?;
+    owned_job(&jobs, &job_id, &user_id)?;
+
+    let cancelled = jobs
+        .cancel(&job_id)
+        .await
+        .map_err(|e| OpenAiError::internal(format!("Failed to cancel job: {}", e)))?;
+
+    Ok(Json(
+        serde_json::json!({ "job_id": job_id, "cancelled": cancelled }),
+    ))
+}
+
+// Replay a job's events from an offset as SSE and follow it until it finishes.
+// Each event id is its sequence number, so `Last-Event-ID` resumes where a
+// dropped connection left off.
+async fn job_events(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(job_id): Path<String>,
+    Query(query): Query<JobEventsQuery>,
+) -> Result<Response, OpenAiError> {
+    let user_id = job_user(&state, &headers).await?;
+    let jobs = job_manager(&state)?;
+    owned_job(&jobs, &job_id, &user_id)?;
+
+    let resume_from = headers
+        .get("last-event-id")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<u64>().ok())
+        .map(|seq| seq + 1);
+    let offset = query.offset.or(resume_from).unwrap_or(0);
+
+    let events = jobs
+        .attach(&job_id, offset)
+        .await
+        .map_err(|e| OpenAiError::internal(format!("Failed to attach to job: {}", e)))?;
+
+    let stream = events.map(|event| {
+        Ok::<_, std::convert::Infallible>(
+            Event::default()
+                .id(event.seq.to_string())
+                .data(serde_json::to_string(&event).unwrap_or_default()),
+        )
+    });
+
+    Ok(Sse::new(stream)
+        .keep_alive(KeepAlive::default())
+        .into_response())
+}
+
+async fn thread_store(state: &AppState) -> Result<ThreadStore, OpenAiError> {
+    let database = state
+        .database
+        .read()
+        .await
+        .clone()
+        .ok_or_else(|| OpenAiError::unavailable("Threads not available - no database"))?;
+    ThreadStore::new(database)
+        .map_err(|e| OpenAiError::internal(format!("Failed to open thread store: {}", e)))
+}
+
+// Load a thread, hiding other users' threads
+fn owned_thread(
+    state: &AppState,
+    store: &ThreadStore,
+    thread_id: &str,
+    user_id: &str,
+) -> Result<Thread, OpenAiError> {
+    // Threads without an owner predate auth, so only a keyless server shares them
+    let unowned_visible = !state.api_auth.is_enabled();
+    match store.load_exact(thread_id) {
+        Ok(thread)
+            if thread.user_id.as_deref() == Some(user_id)
+                || (unowned_visible && thread.user_id.is_none()) =>
+        {
+            Ok(thread)
+        }
+        _ => Err(OpenAiError {
+            status: axum::http::StatusCode::NOT_FOUND,
+            message: format!("Thread {} not found", thread_id),
+            error_type: "invalid_request_error",
+            code: Some("thread_not_found"),
+        }),
+    }
+}
+
+// List the caller's most recent threads
+async fn list_threads(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(query): Query<ThreadListQuery>,
+) -> Result<Json<serde_json::Value>, OpenAiError> {
+    let user_id = job_user(&state, &headers).await?;
+    let store = thread_store(&state).await?;
+
+    let threads = store
+        .list(Some(&user_id), query.limit.unwrap_or(50).min(500))
+        .map_err(|e| OpenAiError::internal(format!("Failed to list threads: {}", e)))?;
+
+    Ok(Json(serde_json::json!({ "threads": threads })))
+}
+
+// A thread with all of its turns and its rolling summary
+async fn get_thread(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(thread_id): Path<String>,
+) -> Result<Json<serde_json::Value>, OpenAiError> {
+    let user_id = job_user(&state, &headers).await?;
+    let store = thread_store(&state).await?;
+    let thread = owned_thread(&state, &store, &thread_id, &user_id)?;
+
+    Ok(Json(serde_json::json!({ "thread": thread })))
+}
+
+// Delete a thread; branches of it are kept
+async fn delete_thread(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(thread_id): Path<String>,
+) -> Result<Json<serde_json::Value>, OpenAiError> {
+    let user_id = job_user(&state, &headers).await?;
+    let store = thread_store(&state).await?;
+    let thread = owned_thread(&state, &store, &thread_id, &user_id)?;
+
+    store
+        .delete(&thread.id)
+        .map_err(|e| OpenAiError::internal(format!("Failed to delete thread: {}", e)))?;
+
+    Ok(Json(
+        serde_json::json!({ "thread_id": thread.id, "deleted": true }),
+    ))
+}
+
+// Start a new thread from the first `at` turns of another one
+async fn branch_thread(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(thread_id): Path<String>,
+    body: Option<Json<BranchThreadRequest>>,
+) -> Result<Json<serde_json::Value>, OpenAiError> {
+    let user_id = job_user(&state, &headers).await?;
+    let store = thread_store(&state).await?;
+    let thread = owned_thread(&state, &store, &thread_id, &user_id)?;
+    let at = body.map(|Json(body)| body).unwrap_or_default().at;
+
+    let branch = store
+        .branch(&thread.id, at)
+        .map_err(|e| OpenAiError::invalid_request(e.to_string()))?;
+
+    Ok(Json(serde_json::json!({ "thread": branch })))
+}
+
 // Health check
 async fn health_check() -> Json<serde_json::Value> {
     Json(serde_json::json!({
@@ -861,6 +1567,7 @@ async fn health_check() -> Json<serde_json::Value> {
             "streaming": true,
             "multi_threading": true,
             "maintenance": true,
+            "jobs": true,
         },
         "timestamp": chrono::Utc::now().to_rfc3339(),
     }))
@@ -0,0 +1,169 @@
+//! Self-hostable team knowledge sync server
+//!
+//! Stores the encrypted facts and threads that `hive sync` pushes and serves
+//! them back to the other members of the same team. Records are sealed on the
+//! clients; this server never sees their contents.
+//! - `HIVE_SYNC_TEAMS`: comma-separated `team:token` entries (required)
+//! - `HIVE_SYNC_DB`: database path, defaults to `~/.hive/sync-server.db`
+//! - `PORT`: listen port, defaults to 8790
+
+use anyhow::{bail, Context};
+use axum::{
+    extract::{DefaultBodyLimit, Query, State},
+    http::{header::AUTHORIZATION, HeaderMap, StatusCode},
+    routing::{get, post},
+    Json, Router,
+};
+use hive_ai::{
+    core::database::{DatabaseConfig, DatabaseManager},
+    team_sync::{
+        PullResponse, PushRequest, PushResponse, ServerStatus, SyncServerStore, TeamTokens,
+        PAGE_SIZE,
+    },
+};
+use serde::Deserialize;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tracing::{error, info};
+
+/// Large threads in a full page of records still fit
+const MAX_BODY_BYTES: usize = 64 * 1024 * 1024;
+
+struct AppState {
+    store: SyncServerStore,
+    tokens: TeamTokens,
+}
+
+type ApiResult<T> = Result<Json<T>, (StatusCode, String)>;
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    tracing_subscriber::fmt()
+        .with_env_filter("info,hive_ai=info")
+        .init();
+
+    let tokens = TeamTokens::from_list(&std::env::var("HIVE_SYNC_TEAMS").unwrap_or_default())?;
+    if tokens.is_empty() {
+        bail!("HIVE_SYNC_TEAMS is not set; use comma-separated team:token entries");
+    }
+
+    let path = std::env::var("HIVE_SYNC_DB")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| PathBuf::from("~/.hive/sync-server.db"));
+    let database = Arc::new(
+        DatabaseManager::new(DatabaseConfig {
+            path: path.clone(),
+            ..DatabaseConfig::default()
+        })
+        .await?,
+    );
+    let state = Arc::new(AppState {
+        store: SyncServerStore::new(database)?,
+        tokens,
+    });
+
+    let app = Router::new()
+        .route("/health", get(health))
+        .route("/api/sync/status", get(status))
+        .route("/api/sync/push", post(push))
+        .route("/api/sync/pull", get(pull))
+        .layer(DefaultBodyLimit::max(MAX_BODY_BYTES))
+        .with_state(state);
+
+    let port: u16 = match std::env::var("PORT") {
+        Ok(port) => port
+            .parse()
+            .with_context(|| format!("Invalid PORT value: '{}'", port))?,
+        Err(_) => 8790,
+    };
+    let addr = SocketAddr::from(([0, 0, 0, 0], port));
+    info!("🔄 Team sync server listening on http://{}", addr);
+    info!("🗄️ Records stored in {}", path.display());
+
+    axum::Server::bind(&addr)
+        .serve(app.into_make_service())
+        .await?;
+    Ok(())
+}
+
+/// The team of the request's bearer token
+fn authorize(state: &AppState, headers: &HeaderMap) -> Result<String, (StatusCode, String)> {
+    let token = headers
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .ok_or((StatusCode::UNAUTHORIZED, "Missing bearer token".to_string()))?;
+    state
+        .tokens
+        .team(token.trim())
+        .map(str::to_string)
+        .ok_or((StatusCode::UNAUTHORIZED, "Unknown token".to_string()))
+}
+
+fn internal_error(error: anyhow::Error) -> (StatusCode, String) {
+    error!("Sync request failed: {:#}", error);
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        "Internal server error".to_string(),
+    )
+}
+
+async fn health() -> Json<serde_json::Value> {
+    Json(serde_json::json!({ "status": "ok" }))
+}
+
+async fn status(State(state): State<Arc<AppState>>, headers: HeaderMap) -> ApiResult<ServerStatus> {
+    let team = authorize(&state, &headers)?;
+    let (records, deleted) = state.store.counts(&team).map_err(internal_error)?;
+    Ok(Json(ServerStatus {
+        team,
+        records,
+        deleted,
+    }))
+}
+
+async fn push(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(request): Json<PushRequest>,
+) -> ApiResult<PushResponse> {
+    let team = authorize(&state, &headers)?;
+    if request.records.len() > PAGE_SIZE {
+        return Err((
+            StatusCode::PAYLOAD_TOO_LARGE,
+            format!("Push at most {} records per request", PAGE_SIZE),
+        ));
+    }
+    let response = state
+        .store
+        .push(&team, &request.records)
+        .map_err(internal_error)?;
+    info!(
+        "📥 {}: {} records accepted, {} conflicts",
+        team,
+        response.accepted.len(),
+        response.conflicts.len()
+    );
+    Ok(Json(response))
+}
+
+#[derive(Debug, Deserialize)]
+struct PullQuery {
+    #[serde(default)]
+    since: u64,
+    limit: Option<usize>,
+}
+
+async fn pull(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(query): Query<PullQuery>,
+) -> ApiResult<PullResponse> {
+    let team = authorize(&state, &headers)?;
+    let response = state
+        .store
+        .pull(&team, query.since, query.limit.unwrap_or(PAGE_SIZE))
+        .map_err(internal_error)?;
+    Ok(Json(response))
+}
@@ -3,6 +3,7 @@
 //! This module defines the CLI structure using clap with comprehensive
 //! command support and Claude Code-style argument patterns.
 
+use crate::cli::output::OutputMode;
 use clap::{Parser, Subcommand};
 use std::io::IsTerminal;
 use std::path::PathBuf;
@@ -47,6 +48,10 @@ pub struct Cli {
     #[arg(long, global = true, default_value = "text")]
     pub format: String,
 
+    /// Machine-readable output: a JSON document or NDJSON event stream
+    #[arg(long = "output", value_enum, global = true, default_value_t = OutputMode::Text)]
+    pub output_mode: OutputMode,
+
     /// Disable colored output
     #[arg(long, global = true)]
     pub no_color: bool,
@@ -96,6 +101,14 @@ pub enum Commands {
         /// Stream response in real-time
         #[arg(long, default_value = "true")]
         stream: bool,
+
+        /// Continue a conversation thread by id (or id prefix)
+        #[arg(long, value_name = "ID")]
+        thread: Option<String>,
+
+        /// Continue the most recent conversation thread
+        #[arg(long = "continue", conflicts_with = "thread")]
+        continue_thread: bool,
     },
 
     /// Run 4-stage consensus analysis
@@ -113,8 +126,189 @@ pub enum Commands {
         detailed: bool,
 
         /// Save consensus result to file
+        #[arg(short, long = "output-file")]
+        output: Option<PathBuf>,
+
+        /// Pipeline definition to run (name in ~/.hive/pipelines or path to a .toml file)
+        #[arg(long, value_name = "NAME")]
+        pipeline: Option<String>,
+
+        /// Run the generator on N models concurrently and keep the best-scoring answer
+        #[arg(long, value_name = "N")]
+        fan_out: Option<usize>,
+
+        /// With --fan-out, merge the candidates into one answer instead of picking one
+        #[arg(long, requires = "fan_out")]
+        synthesize: bool,
+    },
+
+    /// Run consensus over many queries or files unattended
+    Batch {
+        /// JSONL file with one {"id", "query", "context", "profile"} object per line
+        #[arg(value_name = "FILE", required_unless_present = "files")]
+        input: Option<PathBuf>,
+
+        /// Run one item per file matching this glob instead, with the file as context
+        #[arg(
+            long,
+            value_name = "GLOB",
+            conflicts_with = "input",
+            requires = "prompt"
+        )]
+        files: Option<String>,
+
+        /// Query for each --files item; {path} and {name} are replaced
+        #[arg(long, value_name = "TEMPLATE")]
+        prompt: Option<String>,
+
+        /// Directory for results, the checkpoint and the report
+        #[arg(long, value_name = "DIR", default_value = "hive-batch")]
+        out_dir: PathBuf,
+
+        /// Consensus profile for items that do not name one
+        #[arg(short, long, default_value = "balanced")]
+        profile: String,
+
+        /// Items to run at the same time
+        #[arg(short = 'j', long, default_value = "4")]
+        concurrency: usize,
+
+        /// Stop starting items once this many USD are spent, counting resumed runs
+        #[arg(long, value_name = "USD")]
+        max_cost: Option<f64>,
+
+        /// Retries of an item after transient failures
+        #[arg(long, default_value = "2")]
+        retries: u32,
+
+        /// Ignore the checkpoint of an earlier run and start over
+        #[arg(long)]
+        restart: bool,
+    },
+
+    /// Score consensus profiles against a golden dataset
+    Eval {
+        /// JSONL file with one {"id", "question", "expected", "keywords", "rubric"} case per line
+        #[arg(value_name = "DATASET")]
+        dataset: PathBuf,
+
+        /// Profile to evaluate; repeat to compare, the first one is the baseline
+        #[arg(short, long = "profile", default_value = "balanced")]
+        profiles: Vec<String>,
+
+        /// Replay answers and grades recorded with --record instead of calling models
+        #[arg(long, value_name = "FILE", conflicts_with_all = ["record", "stub"])]
+        replay: Option<PathBuf>,
+
+        /// Record every answer and grade to this file for later --replay
+        #[arg(long, value_name = "FILE", conflicts_with = "stub")]
+        record: Option<PathBuf>,
+
+        /// Answer each case with its own reference to check the dataset offline
+        #[arg(long)]
+        stub: bool,
+
+        /// Model that grades answers against rubrics
+        #[arg(long, value_name = "MODEL", default_value = "openai/gpt-4o-mini")]
+        grader_model: String,
+
+        /// Score a case needs to pass, from 0 to 1
+        #[arg(long, default_value = "0.7")]
+        threshold: f64,
+
+        /// Cases to run at the same time
+        #[arg(short = 'j', long, default_value = "2")]
+        concurrency: usize,
+
+        /// Write the Markdown comparison report to this file
+        #[arg(long, value_name = "FILE")]
+        report: Option<PathBuf>,
+    },
+
+    /// Review a git diff with consensus and report line-anchored findings
+    Review {
+        /// Revisions to review: <base>..<head>, <base>...<head> or <base> (to HEAD);
+        /// defaults to uncommitted changes
+        #[arg(value_name = "RANGE")]
+        range: Option<String>,
+
+        /// Review staged changes only
+        #[arg(long, conflicts_with = "range")]
+        staged: bool,
+
+        /// Consensus profile for the review stages
+        #[arg(short, long, default_value = "balanced")]
+        profile: String,
+
+        /// Pipeline definition to run
+        #[arg(long, default_value = "review")]
+        pipeline: String,
+
+        /// Write a SARIF 2.1.0 log to this file, or to stdout with -
+        #[arg(long, value_name = "FILE")]
+        sarif: Option<PathBuf>,
+
+        /// Exit with code 7 when a finding has at least this severity
+        #[arg(long, value_name = "SEVERITY", value_parser = ["info", "warning", "error"])]
+        fail_on: Option<String>,
+
+        /// Chunks to review at the same time
+        #[arg(short = 'j', long, default_value = "2")]
+        concurrency: usize,
+
+        /// Unchanged lines shown around each change
+        #[arg(long, default_value = "3")]
+        context_lines: u32,
+
+        /// Repository to review
+        #[arg(long, value_name = "DIR", default_value = ".")]
+        repo: PathBuf,
+    },
+
+    /// Propose a Conventional Commits message for the staged changes and commit
+    Commit {
+        /// Consensus profile that writes the message
+        #[arg(short, long, default_value = "speed")]
+        profile: String,
+
+        /// Pipeline definition to run
+        #[arg(long, default_value = "commit")]
+        pipeline: String,
+
+        /// Commit the proposed message without asking
         #[arg(short, long)]
+        yes: bool,
+
+        /// Only print the proposed message
+        #[arg(long, conflicts_with = "yes")]
+        dry_run: bool,
+
+        /// Write the proposal into a prepare-commit-msg message file instead of committing
+        #[arg(long, value_name = "FILE", conflicts_with_all = ["yes", "dry_run"])]
+        hook: Option<PathBuf>,
+
+        /// Repository to commit in
+        #[arg(long, value_name = "DIR", default_value = ".")]
+        repo: PathBuf,
+    },
+
+    /// Generate a Markdown changelog from the commits of a revision range
+    Changelog {
+        /// Revisions to include: <from>..<to>, or <from> (to HEAD)
+        #[arg(value_name = "RANGE")]
+        range: String,
+
+        /// Heading of the changelog; defaults to <to>, or "Unreleased" for HEAD
+        #[arg(long)]
+        title: Option<String>,
+
+        /// Write the changelog to this file instead of stdout
+        #[arg(short, long = "output-file", value_name = "FILE")]
         output: Option<PathBuf>,
+
+        /// Repository to read
+        #[arg(long, value_name = "DIR", default_value = ".")]
+        repo: PathBuf,
     },
 
     /// Analyze and understand any repository
@@ -133,7 +327,7 @@ pub enum Commands {
         focus: Vec<String>,
 
         /// Save analysis to file
-        #[arg(short, long)]
+        #[arg(short, long = "output-file")]
         output: Option<PathBuf>,
 
         /// Include dependency analysis
@@ -273,7 +467,7 @@ pub enum Commands {
         collaborative: bool,
 
         /// Save plan to file
-        #[arg(short, long)]
+        #[arg(short, long = "output-file")]
         output: Option<PathBuf>,
 
         /// Include risk assessment
@@ -515,6 +709,14 @@ pub enum Commands {
         /// Enable CORS for web clients
         #[arg(long)]
         cors: bool,
+
+        /// Also serve Prometheus metrics at /metrics on this address
+        #[arg(long, value_name = "ADDR")]
+        metrics: Option<std::net::SocketAddr>,
+
+        /// Export consensus traces to this OTLP/HTTP collector
+        #[arg(long, value_name = "URL")]
+        otlp_endpoint: Option<String>,
     },
 
     /// Manage semantic indices for fast search
@@ -523,6 +725,24 @@ pub enum Commands {
         command: IndexCommands,
     },
 
+    /// Manage consensus pipeline definitions
+    Pipeline {
+        #[command(subcommand)]
+        command: PipelineCommands,
+    },
+
+    /// Manage conversation threads
+    Thread {
+        #[command(subcommand)]
+        command: ThreadCommands,
+    },
+
+    /// Share facts and threads with your team through a self-hosted sync server
+    Sync {
+        #[command(subcommand)]
+        command: SyncCommands,
+    },
+
     /// Detect language of a file or from stdin
     #[command(alias = "detect", visible_alias = "lang")]
     DetectLanguage {
@@ -577,15 +797,23 @@ pub enum Commands {
         command: HookCommands,
     },
 
-    /// Start interactive mode
+    /// Start the interactive REPL
     Interactive {
         /// Starting mode (planning, execution, hybrid)
         #[arg(short, long, default_value = "hybrid")]
         mode: String,
 
-        /// Disable TUI even if available
+        /// Kept for compatibility; the REPL never starts the TUI
         #[arg(long)]
         no_tui: bool,
+
+        /// Resume the most recent session
+        #[arg(long = "continue", conflicts_with = "session")]
+        continue_session: bool,
+
+        /// Resume a session by id (or id prefix)
+        #[arg(long)]
+        session: Option<String>,
     },
 
     /// Launch full TUI interface (VS Code-like)
@@ -621,7 +849,7 @@ pub enum Commands {
         shell: String,
 
         /// Output file (prints to stdout if not specified)
-        #[arg(short, long)]
+        #[arg(short, long = "output-file")]
         output: Option<PathBuf>,
     },
 
@@ -652,6 +880,10 @@ pub enum Commands {
         /// Show available versions
         #[arg(long)]
         list_versions: bool,
+
+        /// Install from an offline update bundle (.tar or .tar.gz)
+        #[arg(long, value_name = "BUNDLE", conflicts_with_all = ["check_only", "version", "rollback"])]
+        from_file: Option<PathBuf>,
     },
 
     /// Uninstall Hive AI completely
@@ -696,6 +928,115 @@ pub enum Commands {
     },
 }
 
+/// Pipeline definition subcommands
+#[derive(Subcommand)]
+pub enum PipelineCommands {
+    /// List stored pipeline definitions
+    List,
+
+    /// Show the stages of a pipeline
+    Show {
+        /// Pipeline name or path to a .toml file
+        #[arg(value_name = "NAME", default_value = "default")]
+        name: String,
+    },
+
+    /// Validate a pipeline definition file
+    Validate {
+        /// Path to the .toml file
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+    },
+
+    /// Write example pipeline definitions to the pipelines directory
+    Init {
+        /// Overwrite existing files
+        #[arg(short, long)]
+        force: bool,
+    },
+}
+
+/// Conversation thread subcommands
+#[derive(Subcommand)]
+pub enum ThreadCommands {
+    /// List recent threads
+    List {
+        /// Maximum number of threads to show
+        #[arg(short, long, default_value = "20")]
+        limit: usize,
+    },
+
+    /// Show the turns of a thread
+    Show {
+        /// Thread id or id prefix
+        #[arg(value_name = "ID")]
+        id: String,
+    },
+
+    /// Delete a thread and its messages
+    Delete {
+        /// Thread id or id prefix
+        #[arg(value_name = "ID")]
+        id: String,
+    },
+
+    /// Start a new thread from an earlier turn of an existing one
+    Branch {
+        /// Thread id or id prefix
+        #[arg(value_name = "ID")]
+        id: String,
+
+        /// Last turn to keep (1-based); defaults to the whole thread
+        #[arg(long, value_name = "TURN")]
+        at: Option<usize>,
+    },
+}
+
+/// Team knowledge sync subcommands
+#[derive(Subcommand)]
+pub enum SyncCommands {
+    /// Connect to a team sync server, storing the token and team passphrase
+    Init {
+        /// Base URL of the hive-sync-server
+        #[arg(long, value_name = "URL")]
+        server: String,
+
+        /// Team name, as configured on the server
+        #[arg(long)]
+        team: String,
+
+        /// Only sync shared threads; curated facts stay local
+        #[arg(long)]
+        no_facts: bool,
+    },
+
+    /// Show local sync state and the server's record counts
+    Status,
+
+    /// Pull team changes, then push local ones
+    Run,
+
+    /// Apply changes the team made since the last pull
+    Pull,
+
+    /// Send local changes to the server
+    Push,
+
+    /// Share a thread with the team from the next push on
+    Share {
+        /// Thread id or id prefix
+        #[arg(value_name = "ID")]
+        id: String,
+    },
+
+    /// Remove a shared thread from the team, keeping the local copy
+    Unshare {
+        /// Thread id or id prefix
+        #[arg(value_name = "ID")]
+        id: String,
+    },
+}
+
 /// Index management subcommands
 #[derive(Subcommand)]
 pub enum IndexCommands {
@@ -722,6 +1063,28 @@ pub enum IndexCommands {
         progress: bool,
     },
 
+    /// Watch a project and keep its index current in the background
+    Watch {
+        /// Path to watch (defaults to current directory)
+        #[arg(value_name = "PATH")]
+        path: Option<PathBuf>,
+
+        /// Quiet period before a burst of changes is re-indexed (milliseconds)
+        #[arg(long, default_value = "300")]
+        debounce_ms: u64,
+
+        /// Include test files
+        #[arg(long)]
+        include_tests: bool,
+
+        /// Exclude patterns (glob syntax)
+        #[arg(long, value_delimiter = ',')]
+        exclude: Vec<String>,
+    },
+
+    /// Show background indexing progress for indexed projects
+    Status,
+
     /// Show index statistics and health
     Stats {
         /// Show detailed statistics
@@ -823,7 +1186,7 @@ pub enum MigrateCommands {
         detailed: bool,
 
         /// Export analysis report
-        #[arg(short, long)]
+        #[arg(short, long = "output-file")]
         output: Option<PathBuf>,
 
         /// Check compatibility issues
@@ -850,7 +1213,7 @@ pub enum MigrateCommands {
         parallel: bool,
 
         /// Export benchmark results
-        #[arg(short, long)]
+        #[arg(short, long = "output-file")]
         output: Option<PathBuf>,
     },
 
@@ -896,7 +1259,7 @@ pub enum MigrateCommands {
         timing: bool,
 
         /// Export preview to file
-        #[arg(short, long)]
+        #[arg(short, long = "output-file")]
         output: Option<PathBuf>,
     },
 
@@ -1020,7 +1383,7 @@ pub enum AnalyticsCommands {
         charts: bool,
 
         /// Save report to file
-        #[arg(short, long)]
+        #[arg(short, long = "output-file")]
         output: Option<PathBuf>,
     },
 
@@ -1076,7 +1439,7 @@ pub enum MemoryCommands {
     /// Export conversation history
     Export {
         /// Output file
-        #[arg(short, long)]
+        #[arg(short, long = "output-file")]
         output: Option<PathBuf>,
 
         /// Export format (json, csv, markdown)
@@ -1257,6 +1620,45 @@ pub enum ConfigCommands {
         #[arg(short, long)]
         global: bool,
     },
+
+    /// Manage encrypted API keys
+    Secrets {
+        #[command(subcommand)]
+        command: SecretCommands,
+    },
+}
+
+/// Secret storage subcommands; values are never printed
+#[derive(Subcommand)]
+pub enum SecretCommands {
+    /// List stored secrets and the backend each is read from
+    List,
+
+    /// Replace a secret's value, or re-seal all secrets under a new key
+    Rotate {
+        /// Secret to replace, read from a hidden prompt or stdin
+        #[arg(value_name = "NAME")]
+        name: Option<String>,
+
+        /// Seal the secrets file with a passphrase from now on
+        #[arg(long, conflicts_with_all = ["name", "key_file"])]
+        passphrase: bool,
+
+        /// Seal the secrets file with a machine-local key file from now on
+        #[arg(long, conflicts_with = "name")]
+        key_file: bool,
+    },
+
+    /// Remove a secret from the secrets file
+    Remove {
+        /// Secret to remove, e.g. openrouter_api_key
+        #[arg(value_name = "NAME")]
+        name: String,
+
+        /// Do not ask for confirmation
+        #[arg(short, long)]
+        yes: bool,
+    },
 }
 
 /// Enterprise hooks subcommands
@@ -1354,6 +1756,56 @@ pub enum HookCommands {
         #[arg(long)]
         failures_only: bool,
     },
+
+    /// Review approval requests raised by hooks
+    Approvals {
+        #[command(subcommand)]
+        command: ApprovalCommands,
+    },
+}
+
+/// Approval queue subcommands
+#[derive(Subcommand)]
+pub enum ApprovalCommands {
+    /// List approval requests waiting for a decision
+    List {
+        /// Include decided, expired and auto-approved requests
+        #[arg(long)]
+        all: bool,
+
+        /// Maximum number of requests to show
+        #[arg(short, long, default_value = "20")]
+        limit: usize,
+    },
+
+    /// Show a request and the decisions it received
+    Show {
+        /// Request id or id prefix
+        #[arg(value_name = "ID")]
+        id: String,
+    },
+
+    /// Approve a request
+    Approve {
+        /// Request id or id prefix
+        #[arg(value_name = "ID")]
+        id: String,
+
+        /// Why it is approved, for the audit log
+        #[arg(short, long)]
+        reason: Option<String>,
+    },
+
+    /// Reject a request
+    Reject {
+        /// Request id or id prefix
+        #[arg(value_name = "ID")]
+        id: String,
+
+        /// Why it is rejected, for the audit log
+        #[arg(short, long)]
+        reason: Option<String>,
+    },
 }
 
 /// Trust management subcommands
@@ -4,10 +4,13 @@
 //! providing the core functionality behind the command-line interface.
 
 use crate::cli::args::*;
+use crate::cli::output::{
+    consensus_error, emit, exit_code, exit_code_for, Cancelled, ConsensusOutput, NdjsonCallbacks,
+    OutputMode, StatusOutput, ThresholdExceeded, UnsupportedOutput,
+};
 use crate::core::config::{
     get_config, get_config_value, get_hive_config_dir, reset_config, set_config_value,
 };
-use crate::core::error::HiveError;
 use crate::migration::{
     analyzer,
     live_test::{LiveMigrationTester, LiveTestConfig, TestDatabaseSize, TestScenario},
@@ -23,8 +26,44 @@ use rusqlite::params;
 use std::path::PathBuf;
 use std::sync::Arc;
 
+/// Run a parsed command line and return the process exit code
+///
+/// In `--output json|ndjson` mode errors are printed as a JSON error
+/// envelope on stdout; in text mode they go to stderr.
+pub async fn run(cli: Cli) -> i32 {
+    let output = cli.output_mode;
+    if !output.is_text() || cli.no_color {
+        console::set_colors_enabled(false);
+    }
+
+    let Some(command) = cli.command else {
+        if output.is_text() {
+            let _ = <Cli as clap::CommandFactory>::command().print_help();
+        }
+        return exit_code::USAGE;
+    };
+
+    match handle_command(command, output).await {
+        Ok(()) => exit_code::SUCCESS,
+        Err(e) => {
+            if output.is_text() {
+                eprintln!("❌ {} {:#}", style("Error:").red().bold(), e);
+            } else if e.downcast_ref::<ThresholdExceeded>().is_some() {
+                // The result envelope is already out; only the exit code changes
+            } else if let Err(print_error) = crate::cli::output::emit_error(output, &e) {
+                eprintln!("Failed to print error: {}", print_error);
+            }
+            exit_code_for(&e)
+        }
+    }
+}
+
 /// Handle a CLI command
-pub async fn handle_command(command: Commands) -> Result<()> {
+pub async fn handle_command(command: Commands, output: OutputMode) -> Result<()> {
+    if !output.is_text() && !supports_structured_output(&command) {
+        return Err(UnsupportedOutput(output.name()).into());
+    }
+
     match command {
         Commands::Initialize {
             path,
@@ -38,33 +77,172 @@ pub async fn handle_command(command: Commands) -> Result<()> {
             context,
             max_tokens,
             stream,
-        } => handle_ask(question, profile, plan, context, max_tokens, stream).await,
+            thread,
+            continue_thread,
+        } => {
+            handle_ask(
+                question,
+                profile,
+                plan,
+                context,
+                max_tokens,
+                stream,
+                thread,
+                continue_thread,
+                output,
+            )
+            .await
+        }
         Commands::Consensus {
             query,
             profile,
             detailed,
-            output,
-        } => handle_consensus(query, profile, detailed, output).await,
+            output: output_file,
+            pipeline,
+            fan_out,
+            synthesize,
+        } => {
+            handle_consensus(
+                query,
+                profile,
+                detailed,
+                output_file,
+                pipeline,
+                fan_out,
+                synthesize,
+                output,
+            )
+            .await
+        }
+        Commands::Batch {
+            input,
+            files,
+            prompt,
+            out_dir,
+            profile,
+            concurrency,
+            max_cost,
+            retries,
+            restart,
+        } => {
+            let options = crate::consensus::batch::BatchOptions {
+                concurrency,
+                max_retries: retries,
+                max_cost,
+                restart,
+                ..Default::default()
+            };
+            crate::commands::batch::handle_batch(
+                input, files, prompt, out_dir, profile, options, output,
+            )
+            .await
+        }
+        Commands::Eval {
+            dataset,
+            profiles,
+            replay,
+            record,
+            stub,
+            grader_model,
+            threshold,
+            concurrency,
+            report,
+        } => {
+            let source = match (replay, stub) {
+                (Some(path), _) => crate::commands::eval::EvalSource::Replay(path),
+                (None, true) => crate::commands::eval::EvalSource::Stub,
+                (None, false) => crate::commands::eval::EvalSource::Live {
+                    grader_model,
+                    record,
+                },
+            };
+            crate::commands::eval::handle_eval(
+                dataset,
+                profiles,
+                source,
+                threshold,
+                concurrency,
+                report,
+                output,
+            )
+            .await
+        }
+        Commands::Review {
+            range,
+            staged,
+            profile,
+            pipeline,
+            sarif,
+            fail_on,
+            concurrency,
+            context_lines,
+            repo,
+        } => {
+            let spec = crate::consensus::review::DiffSpec::parse(range.as_deref(), staged)?;
+            let options = crate::commands::review::ReviewOptions {
+                profile,
+                pipeline,
+                concurrency,
+                context_lines,
+                sarif,
+                fail_on: fail_on.as_deref().map(str::parse).transpose()?,
+            };
+            crate::commands::review::handle_review(repo, spec, options, output).await
+        }
+        Commands::Commit {
+            profile,
+            pipeline,
+            yes,
+            dry_run,
+            hook,
+            repo,
+        } => {
+            let options = crate::commands::commit::CommitOptions {
+                profile,
+                pipeline,
+                yes,
+                dry_run,
+                hook,
+            };
+            crate::commands::commit::handle_commit(repo, options, output).await
+        }
+        Commands::Changelog {
+            range,
+            title,
+            output: output_file,
+            repo,
+        } => crate::commands::changelog::handle_changelog(repo, &range, title, output_file, output),
         Commands::Analyze {
             target,
             depth,
             focus,
-            output,
+            output: output_file,
             dependencies,
             recommendations,
-        } => handle_analyze(target, depth, focus, output, dependencies, recommendations).await,
+        } => {
+            handle_analyze(
+                target,
+                depth,
+                focus,
+                output_file,
+                dependencies,
+                recommendations,
+                output,
+            )
+            .await
+        }
         Commands::Search {
             query,
             kind,
             path,
             limit,
             fuzzy,
-        } => crate::commands::search::handle_search(query, kind, path, limit, fuzzy).await,
+        } => crate::commands::search::handle_search(query, kind, path, limit, fuzzy, output).await,
         Commands::Plan {
             goal,
             depth,
             collaborative,
-            output,
+            output: output_file,
             risks,
             timeline,
         } => {
@@ -72,9 +250,10 @@ pub async fn handle_command(command: Commands) -> Result<()> {
                 goal,
                 depth,
                 collaborative,
-                output,
+                output_file,
                 risks,
                 timeline,
+                output,
             )
             .await
         }
@@ -156,8 +335,13 @@ pub async fn handle_command(command: Commands) -> Result<()> {
             port,
             host,
             cors,
-        } => handle_serve(mode, port, host, cors).await,
+            metrics,
+            otlp_endpoint,
+        } => handle_serve(mode, port, host, cors, metrics, otlp_endpoint).await,
         Commands::Index { command } => handle_index(command).await,
+        Commands::Pipeline { command } => handle_pipeline(command).await,
+        Commands::Thread { command } => handle_thread(command, output).await,
+        Commands::Sync { command } => crate::commands::sync::handle_sync(command, output).await,
         Commands::References {
             symbol,
             file,
@@ -195,16 +379,21 @@ pub async fn handle_command(command: Commands) -> Result<()> {
             language,
             detailed,
         } => handle_edit_performance_test(iterations, file, language, detailed).await,
-        Commands::Config { command } => handle_config(command).await,
+        Commands::Config { command } => handle_config(command, output).await,
         Commands::Trust { command } => handle_trust(command).await,
-        Commands::Hooks { command } => handle_hooks(command).await,
-        Commands::Interactive { mode, no_tui } => handle_interactive(mode, no_tui).await,
+        Commands::Hooks { command } => handle_hooks(command, output).await,
+        Commands::Interactive {
+            mode,
+            continue_session,
+            session,
+            ..
+        } => handle_interactive(mode, continue_session, session).await,
         Commands::Tui { force, layout } => handle_tui(force, layout).await,
         Commands::Status {
             detailed,
             check_apis,
             performance,
-        } => handle_status(detailed, check_apis, performance).await,
+        } => handle_status(detailed, check_apis, performance, output).await,
         Commands::Completion { shell, output } => handle_completion(shell, output).await,
         Commands::Shell { command } => handle_shell_command(command).await,
         Commands::SelfUpdate {
@@ -213,7 +402,18 @@ pub async fn handle_command(command: Commands) -> Result<()> {
             version,
             rollback,
             list_versions,
-        } => handle_self_update(check_only, force, version, rollback, list_versions).await,
+            from_file,
+        } => {
+            handle_self_update(
+                check_only,
+                force,
+                version,
+                rollback,
+                list_versions,
+                from_file,
+            )
+            .await
+        }
         Commands::Uninstall {
             dry_run,
             preserve_config,
@@ -229,6 +429,28 @@ pub async fn handle_command(command: Commands) -> Result<()> {
     }
 }
 
+/// Whether `command` prints a JSON envelope under `--output json|ndjson`
+fn supports_structured_output(command: &Commands) -> bool {
+    match command {
+        Commands::Ask { .. }
+        | Commands::Consensus { .. }
+        | Commands::Batch { .. }
+        | Commands::Eval { .. }
+        | Commands::Review { .. }
+        | Commands::Commit { .. }
+        | Commands::Changelog { .. }
+        | Commands::Analyze { .. }
+        | Commands::Search { .. }
+        | Commands::Plan { .. }
+        | Commands::Thread { .. }
+        | Commands::Sync { .. }
+        | Commands::Status { .. } => true,
+        Commands::Config { command } => matches!(command, ConfigCommands::Secrets { .. }),
+        Commands::Hooks { command } => matches!(command, HookCommands::Approvals { .. }),
+        _ => false,
+    }
+}
+
 /// Initialize Hive in a project
 async fn handle_init(path: Option<PathBuf>, force: bool, _non_interactive: bool) -> Result<()> {
     let project_path = path.unwrap_or_else(|| PathBuf::from("."));
@@ -327,7 +549,25 @@ async fn handle_ask(
     context: Option<PathBuf>,
     _max_tokens: Option<u32>,
     stream: bool,
+    thread: Option<String>,
+    continue_thread: bool,
+    output: OutputMode,
 ) -> Result<()> {
+    if !output.is_text() || thread.is_some() || continue_thread {
+        let thread_id =
+            crate::commands::thread::resolve_ask_thread(thread.as_deref(), continue_thread).await?;
+        return run_consensus_for_output(
+            "ask",
+            &question,
+            &profile,
+            None,
+            None,
+            Some(thread_id),
+            output,
+        )
+        .await;
+    }
+
     println!("🤔 {} your question...", style("Processing").bold());
 
     if plan {
@@ -369,8 +609,46 @@ async fn handle_consensus(
     profile: String,
     detailed: bool,
     output: Option<PathBuf>,
+    pipeline: Option<String>,
+    fan_out: Option<usize>,
+    synthesize: bool,
+    output_mode: OutputMode,
 ) -> Result<()> {
-    use crate::consensus::ConsensusEngine;
+    use crate::consensus::{
+        ConsensusEngine, FanOutDefinition, MergeStrategy, PipelineDefinition, PipelineStore, Stage,
+    };
+    use crate::core::error::HiveError;
+
+    let mut pipeline = pipeline
+        .map(|name| PipelineStore::default_location().load(&name))
+        .transpose()?;
+    if let Some(count) = fan_out {
+        let merge = if synthesize {
+            MergeStrategy::Synthesize
+        } else {
+            MergeStrategy::Select
+        };
+        let fan_out = FanOutDefinition::with_count(count).with_merge(merge);
+        fan_out.validate()?;
+        pipeline = Some(
+            pipeline
+                .unwrap_or_else(PipelineDefinition::builtin)
+                .with_fan_out(Stage::Generator, fan_out),
+        );
+    }
+
+    if !output_mode.is_text() {
+        return run_consensus_for_output(
+            "consensus",
+            &query,
+            &profile,
+            pipeline,
+            output,
+            None,
+            output_mode,
+        )
+        .await;
+    }
 
     println!(
         "🧠 {} 4-stage consensus analysis...",
@@ -378,6 +656,16 @@ async fn handle_consensus(
     );
     println!("   Query: {}", style(&query).italic());
     println!("   Profile: {}", style(&profile).cyan());
+    if let Some(count) = fan_out {
+        println!("   Fan-out: {} generator models", style(count).cyan());
+    }
+    if let Some(definition) = &pipeline {
+        println!(
+            "   Pipeline: {} ({} stages)",
+            style(&definition.name).cyan(),
+            definition.stages.len()
+        );
+    }
     println!();
 
     // Check OpenRouter API key
@@ -426,10 +714,18 @@ async fn handle_consensus(
         return Ok(());
     }
 
+    let context = crate::consensus::mentions::mention_context(&query).await?;
+
     // Create real consensus engine
     println!("🔧 Initializing consensus engine...");
     let engine = ConsensusEngine::new(None).await?;
 
+    if let Err(e) = load_consensus_hooks(&engine).await {
+        println!("⚠️  Failed to load hooks: {}", e);
+    }
+
+    engine.set_pipeline_definition(pipeline).await;
+
     // Set profile
     engine
         .set_profile(&profile)
@@ -448,7 +744,15 @@ async fn handle_consensus(
     println!("🚀 Running 4-stage consensus pipeline...");
     let start_time = std::time::Instant::now();
 
-    match engine.process(&query, None).await {
+    match engine.process(&query, context).await {
+        Ok(result) if !result.success => {
+            return Err(HiveError::ConsensusFailed {
+                message: result
+                    .error
+                    .unwrap_or_else(|| "pipeline returned no result".to_string()),
+            }
+            .into());
+        }
         Ok(result) => {
             let duration = start_time.elapsed();
             println!();
@@ -501,6 +805,22 @@ async fn handle_consensus(
                             "   Duration: {:.2}s, Cost: ${:.4}",
                             analytics.duration, analytics.cost
                         );
+                        for candidate in &analytics.candidates {
+                            match &candidate.error {
+                                Some(error) => {
+                                    println!("     ✗ {} failed: {}", candidate.model, error)
+                                }
+                                None => println!(
+                                    "     {} {} score {:.0}, agreement {:.2}, {:.2}s, ${:.4}",
+                                    if candidate.selected { "★" } else { "•" },
+                                    candidate.model,
+                                    candidate.score,
+                                    candidate.agreement,
+                                    candidate.duration,
+                                    candidate.cost
+                                ),
+                            }
+                        }
                     }
                     println!(
                         "   Response: {}",
@@ -522,25 +842,8 @@ async fn handle_consensus(
                     style(output_path.display()).cyan()
                 );
 
-                let json_result = serde_json::json!({
-                    "query": query,
-                    "profile": current_profile.profile_name,
-                    "timestamp": chrono::Utc::now().to_rfc3339(),
-                    "success": result.success,
-                    "result": result.result,
-                    "total_duration": result.total_duration,
-                    "total_cost": result.total_cost,
-                    "conversation_id": result.conversation_id,
-                    "stages": result.stages.iter().map(|stage| {
-                        serde_json::json!({
-                            "stage_name": stage.stage_name,
-                            "model": stage.model,
-                            "answer": stage.answer,
-                            "usage": stage.usage,
-                            "analytics": stage.analytics
-                        })
-                    }).collect::<Vec<_>>()
-                });
+                let json_result =
+                    ConsensusOutput::new(&query, &current_profile.profile_name, &result);
 
                 tokio::fs::write(&output_path, serde_json::to_string_pretty(&json_result)?).await?;
             }
@@ -550,13 +853,129 @@ async fn handle_consensus(
             println!(
                 "💡 Make sure your OPENROUTER_API_KEY is valid and you have sufficient credits"
             );
-            return Err(e);
+            return Err(consensus_error(e));
         }
     }
 
     Ok(())
 }
 
+/// Run lifecycle hooks from ~/.hive/hooks, if any are configured
+pub(crate) async fn load_consensus_hooks(engine: &crate::consensus::ConsensusEngine) -> Result<()> {
+    let hooks_dir = get_hive_config_dir().join("hooks");
+    if hooks_dir.is_dir() {
+        let hooks_system = crate::hooks::HooksSystem::new(get_hive_config_dir()).await?;
+        hooks_system.load_hooks(hooks_dir).await?;
+        engine.set_hooks_system(Arc::new(hooks_system)).await;
+    }
+    Ok(())
+}
+
+/// Fail with a configuration error unless an OpenRouter key resolves from
+/// the database, the secret store, config.toml or the environment
+pub(crate) async fn require_openrouter_key() -> Result<()> {
+    use crate::core::api_keys::ApiKeyManager;
+    use crate::core::error::HiveError;
+
+    if let Err(e) = ApiKeyManager::get_openrouter_key().await {
+        tracing::debug!("{}", e);
+        return Err(HiveError::ConfigMissingField {
+            field: "OPENROUTER_API_KEY".to_string(),
+        }
+        .into());
+    }
+    Ok(())
+}
+
+/// Run consensus for `--output json|ndjson` or as a turn of a thread
+///
+/// NDJSON mode streams events; text mode prints the answer and how to continue
+/// the thread.
+async fn run_consensus_for_output(
+    command: &str,
+    query: &str,
+    profile: &str,
+    pipeline: Option<crate::consensus::PipelineDefinition>,
+    output_file: Option<PathBuf>,
+    thread_id: Option<String>,
+    output: OutputMode,
+) -> Result<()> {
+    use crate::consensus::fanout::SilentCallbacks;
+    use crate::consensus::streaming::StreamingCallbacks;
+    use crate::consensus::types::ConsensusRequest;
+    use crate::consensus::{CancellationReason, CancellationToken, ConsensusEngine};
+    use crate::core::error::HiveError;
+
+    require_openrouter_key().await?;
+
+    let context = crate::consensus::mentions::mention_context(query).await?;
+    let engine = ConsensusEngine::new(None).await?;
+    if let Err(e) = load_consensus_hooks(&engine).await {
+        tracing::warn!("Failed to load hooks: {}", e);
+    }
+    engine.set_pipeline_definition(pipeline).await;
+    if let Err(e) = engine.set_profile(profile).await {
+        return Err(HiveError::ConfigInvalid {
+            message: format!("{:#}", e),
+        }
+        .into());
+    }
+    let profile_name = engine.get_current_profile().await.profile_name;
+
+    let callbacks: Arc<dyn StreamingCallbacks> = match output {
+        OutputMode::Ndjson => Arc::new(NdjsonCallbacks),
+        _ => Arc::new(SilentCallbacks),
+    };
+    let request = ConsensusRequest {
+        query: query.to_string(),
+        context,
+        temporal_context: None,
+        profile_override: None,
+        max_tokens: None,
+        user_id: None,
+        conversation_id: thread_id.clone(),
+    };
+    let cancellation = CancellationToken::new();
+    let result = tokio::select! {
+        result = engine.process_request(&request, callbacks, cancellation.clone()) => result,
+        _ = tokio::signal::ctrl_c() => {
+            cancellation.cancel(CancellationReason::UserRequested);
+            return Err(Cancelled.into());
+        }
+    };
+    let result = result.map_err(consensus_error)?;
+    if !result.success {
+        return Err(HiveError::ConsensusFailed {
+            message: result
+                .error
+                .unwrap_or_else(|| "pipeline returned no result".to_string()),
+        }
+        .into());
+    }
+
+    let mut data = ConsensusOutput::new(query, &profile_name, &result);
+    data.thread_id = thread_id;
+    if let Some(path) = output_file {
+        tokio::fs::write(&path, serde_json::to_string_pretty(&data)?).await?;
+    }
+    if output.is_text() {
+        println!("{}", data.result.as_deref().unwrap_or_default());
+        if let Some(thread_id) = &data.thread_id {
+            println!();
+            println!(
+                "{}",
+                style(format!(
+                    "${:.4} · continue with: hive ask --thread {} \"...\"",
+                    data.total_cost,
+                    &thread_id[..thread_id.len().min(8)]
+                ))
+                .dim()
+            );
+        }
+    }
+    emit(output, command, &data)
+}
+
 /// Handle analyze command
 async fn handle_analyze(
     target: Option<String>,
@@ -565,6 +984,7 @@ async fn handle_analyze(
     output: Option<PathBuf>,
     dependencies: bool,
     recommendations: bool,
+    output_mode: OutputMode,
 ) -> Result<()> {
     // Use the real analyze implementation
     let target_path = target
@@ -583,6 +1003,7 @@ async fn handle_analyze(
         architecture,
         dependencies_flag,
         output.map(|p| p.to_string_lossy().to_string()),
+        output_mode,
     )
     .await
 }
@@ -1687,7 +2108,14 @@ async fn handle_tool(
 }
 
 /// Handle serve command
-async fn handle_serve(mode: String, port: u16, host: String, cors: bool) -> Result<()> {
+async fn handle_serve(
+    mode: String,
+    port: u16,
+    host: String,
+    cors: bool,
+    metrics: Option<std::net::SocketAddr>,
+    otlp_endpoint: Option<String>,
+) -> Result<()> {
     println!(
         "🚀 {} {} server on {}:{}...",
         style("Starting").bold(),
@@ -1700,6 +2128,21 @@ async fn handle_serve(mode: String, port: u16, host: String, cors: bool) -> Resu
         println!("🌐 {} enabled", style("CORS").yellow());
     }
 
+    if let Some(endpoint) = otlp_endpoint {
+        let exporter = crate::consensus::telemetry::OtlpExporter::new(&endpoint);
+        println!("🔭 Exporting traces to {}", style(exporter.url()).blue());
+        crate::consensus::telemetry::global().set_exporter(Some(exporter));
+    }
+
+    // The metrics listener runs alongside whichever servers the mode starts
+    let metrics_task = metrics.map(|addr| {
+        println!(
+            "📈 Prometheus metrics on {}",
+            style(format!("http://{}/metrics", addr)).blue()
+        );
+        tokio::spawn(crate::server::metrics::serve(addr))
+    });
+
     match mode.as_str() {
         "mcp" => {
             println!(
@@ -1750,10 +2193,17 @@ async fn handle_serve(mode: String, port: u16, host: String, cors: bool) -> Resu
         _ => {
             println!("❌ {} Unknown server mode: {}", style("Error:").red(), mode);
             println!("💡 Available modes: mcp, lsp, both");
+            if let Some(task) = metrics_task {
+                task.abort();
+            }
             return Ok(());
         }
     }
 
+    if let Some(task) = metrics_task {
+        task.await??;
+    }
+
     Ok(())
 }
 
@@ -1901,6 +2351,16 @@ async fn handle_index(command: IndexCommands) -> Result<()> {
             exclude,
             progress,
         } => crate::commands::index::handle_index_build(path, force, include_tests, exclude).await,
+        IndexCommands::Watch {
+            path,
+            debounce_ms,
+            include_tests,
+            exclude,
+        } => {
+            crate::commands::index::handle_index_watch(path, debounce_ms, include_tests, exclude)
+                .await
+        }
+        IndexCommands::Status => crate::commands::index::handle_index_status().await,
         IndexCommands::Stats { detailed, health } => {
             crate::commands::index::handle_index_stats().await
         }
@@ -1909,8 +2369,36 @@ async fn handle_index(command: IndexCommands) -> Result<()> {
     }
 }
 
+/// Handle pipeline commands
+async fn handle_pipeline(command: PipelineCommands) -> Result<()> {
+    match command {
+        PipelineCommands::List => crate::commands::pipeline::handle_pipeline_list().await,
+        PipelineCommands::Show { name } => {
+            crate::commands::pipeline::handle_pipeline_show(&name).await
+        }
+        PipelineCommands::Validate { file } => {
+            crate::commands::pipeline::handle_pipeline_validate(&file).await
+        }
+        PipelineCommands::Init { force } => {
+            crate::commands::pipeline::handle_pipeline_init(force).await
+        }
+    }
+}
+
+/// Handle thread commands
+async fn handle_thread(command: ThreadCommands, output: OutputMode) -> Result<()> {
+    use crate::commands::thread;
+
+    match command {
+        ThreadCommands::List { limit } => thread::handle_thread_list(limit, output).await,
+        ThreadCommands::Show { id } => thread::handle_thread_show(&id, output).await,
+        ThreadCommands::Delete { id } => thread::handle_thread_delete(&id, output).await,
+        ThreadCommands::Branch { id, at } => thread::handle_thread_branch(&id, at, output).await,
+    }
+}
+
 /// Handle config commands
-async fn handle_config(command: ConfigCommands) -> Result<()> {
+async fn handle_config(command: ConfigCommands, output: OutputMode) -> Result<()> {
     match command {
         ConfigCommands::Show {
             section,
@@ -2198,6 +2686,10 @@ async fn handle_config(command: ConfigCommands) -> Result<()> {
                 style("hive config set").bold()
             );
         }
+
+        ConfigCommands::Secrets { command } => {
+            return crate::commands::secrets::handle_secrets(command, output).await;
+        }
     }
 
     Ok(())
@@ -2633,8 +3125,11 @@ async fn handle_security_command(command: SecurityCommands) -> Result<()> {
 }
 
 /// Handle hooks commands
-async fn handle_hooks(command: HookCommands) -> Result<()> {
+async fn handle_hooks(command: HookCommands, output: OutputMode) -> Result<()> {
     match command {
+        HookCommands::Approvals { command } => {
+            return crate::commands::hooks::handle_approvals(command, output).await;
+        }
         HookCommands::List {
             event,
             enabled_only,
@@ -2936,24 +3431,17 @@ async fn handle_hooks(command: HookCommands) -> Result<()> {
 }
 
 /// Handle interactive command
-async fn handle_interactive(mode: String, no_tui: bool) -> Result<()> {
-    println!(
-        "🎮 {} interactive mode: {}",
-        style("Starting").bold(),
-        style(&mode).cyan()
-    );
-
-    if no_tui {
-        println!("📟 {} CLI mode (TUI disabled)", style("Using simple").dim());
-    }
-
-    // Interactive mode removed - GUI only
-    eprintln!("Interactive CLI mode has been removed. Please use the GUI with --desktop flag.");
-    return Err(
-        HiveError::config_invalid("Interactive CLI mode not available in GUI-only build").into(),
-    );
-
-    Ok(())
+async fn handle_interactive(
+    mode: String,
+    continue_session: bool,
+    session: Option<String>,
+) -> Result<()> {
+    crate::cli::repl::run(crate::cli::repl::ReplOptions {
+        mode,
+        continue_last: continue_session,
+        session,
+    })
+    .await
 }
 
 /// Handle TUI command
@@ -2967,18 +3455,44 @@ async fn handle_tui(force: bool, layout: String) -> Result<()> {
         return Ok(());
     }
 
-    println!("🖥️  {} TUI interface...", style("Launching").bold());
-    println!("📱 Layout: {}", style(&layout).cyan());
-
-    // This would launch the actual TUI
-    // For now, show a placeholder
-    launch_tui_placeholder().await?;
-
-    Ok(())
+    crate::tui::run(&layout).await
 }
 
 /// Handle status command
-async fn handle_status(detailed: bool, check_apis: bool, performance: bool) -> Result<()> {
+async fn handle_status(
+    detailed: bool,
+    check_apis: bool,
+    performance: bool,
+    output: OutputMode,
+) -> Result<()> {
+    if !output.is_text() {
+        let config = get_config().await.ok();
+        let api_key_configured = std::env::var("OPENROUTER_API_KEY").is_ok()
+            || config
+                .as_ref()
+                .and_then(|c| c.openrouter.as_ref())
+                .and_then(|o| o.api_key.as_ref())
+                .is_some_and(|key| !key.is_empty());
+        let (internet, api_available) = if check_apis {
+            (
+                Some(crate::cli::check_internet_connection().await),
+                Some(crate::cli::check_api_status().await),
+            )
+        } else {
+            (None, None)
+        };
+        let data = StatusOutput {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            config_dir: get_hive_config_dir(),
+            config_loaded: config.is_some(),
+            api_key_configured,
+            internet,
+            api_available,
+            memory_bytes: performance.then(crate::cli::get_memory_usage),
+        };
+        return emit(output, "status", &data);
+    }
+
     if detailed {
         crate::cli::banner::show_status_info().await?;
     } else {
@@ -3102,52 +3616,6 @@ async fn simulate_detailed_consensus(profile: &str) -> Result<()> {
     Ok(())
 }
 
-/// Launch TUI placeholder
-async fn launch_tui_placeholder() -> Result<()> {
-    use std::io;
-
-    println!("┌─────────────────────────────────────────────────────────────┐");
-    println!("│  🐝 HiveTechs Consensus - TUI Mode                        │");
-    println!("├─────────────────────────────────────────────────────────────┤");
-    println!("│                                                             │");
-    println!("│  📁 Explorer      │  📝 Editor        │  🧠 Consensus     │");
-    println!("│  ├─ src/          │  fn main() {{      │  Ask anything...  │");
-    println!("│  │  ├─ main.rs     │      println!();  │                   │");
-    println!("│  │  └─ lib.rs      │  }}               │  🔍 Analyzing...  │");
-    println!("│  ├─ tests/         │                   │                   │");
-    println!("│  └─ Cargo.toml     │  cursor here ▌    │  Quality: 8.5/10  │");
-    println!("│                    │                   │                   │");
-    println!("├─────────────────────────────────────────────────────────────┤");
-    println!("│  Terminal: $ cargo build                                   │");
-    println!("│  Finished dev [unoptimized] target(s) in 2.34s            │");
-    println!("│  $ hive analyze .                                          │");
-    println!("│  🔍 Repository analysis complete ✅                       │");
-    println!("├─────────────────────────────────────────────────────────────┤");
-    println!("│  F1: Explorer │ F2: Editor │ F3: Consensus │ F4: Terminal   │");
-    println!("│  Ctrl+P: Quick Open │ Ctrl+Q: Quit │ Status: Ready ✅     │");
-    println!("└─────────────────────────────────────────────────────────────┘");
-    println!();
-    println!(
-        "🚧 {} TUI Mode is in development!",
-        style("Note:").yellow().bold()
-    );
-    println!("📋 Features coming soon:");
-    println!("   • Full file explorer with Git status");
-    println!("   • Syntax-highlighted code editor");
-    println!("   • Real-time consensus analysis");
-    println!("   • Integrated terminal");
-    println!("   • Planning mode visualization");
-    println!("   • Memory and analytics panels");
-    println!();
-    println!("⌨️  Press any key to return to CLI mode...");
-
-    // Simple key wait
-    let mut input = String::new();
-    io::stdin().read_line(&mut input)?;
-
-    Ok(())
-}
-
 /// Handle completion command
 async fn handle_completion(shell: String, output: Option<PathBuf>) -> Result<()> {
     println!(
@@ -3269,6 +3737,7 @@ async fn handle_self_update(
     version: Option<String>,
     rollback: bool,
     list_versions: bool,
+    from_file: Option<PathBuf>,
 ) -> Result<()> {
     if list_versions {
         println!("📋 {} Available versions:", style("Listing").bold());
@@ -3283,23 +3752,66 @@ async fn handle_self_update(
         return Ok(());
     }
 
+    let updater = crate::core::updater::AutoUpdater::new()?;
+
     if rollback {
         println!("⏪ {} to previous version...", style("Rolling back").bold());
-        println!("❌ {} No previous version found", style("Error:").red());
+        updater.rollback().await?;
+        println!(
+            "✅ {} Restored the most recent backup",
+            style("Success:").green().bold()
+        );
+        return Ok(());
+    }
+
+    if let Some(bundle) = from_file {
+        println!(
+            "📦 {} update bundle {}...",
+            style("Verifying").bold(),
+            style(bundle.display()).cyan()
+        );
+        let manifest = updater.install_bundle(&bundle, force).await?;
+        println!(
+            "✅ {} Updated to version {}",
+            style("Success:").green().bold(),
+            style(&manifest.version).cyan()
+        );
+        println!("🔄 Please restart your terminal to use the new version");
         return Ok(());
     }
 
+    println!("🔍 {} for updates...", style("Checking").bold());
+    let update_info = updater.check_for_updates().await?;
+    let release_info = match update_info.release_info {
+        Some(release_info) => release_info,
+        None => {
+            println!(
+                "✅ {} You are running the latest version (v{})",
+                style("Success:").green().bold(),
+                update_info.current_version
+            );
+            return Ok(());
+        }
+    };
+
     if check_only {
-        println!("🔍 {} for updates...", style("Checking").bold());
-        tokio::time::sleep(std::time::Duration::from_millis(500)).await;
         println!(
-            "✅ {} You are running the latest version (v2.0.0)",
-            style("Success:").green().bold()
+            "📦 Version {} is available (current: v{})",
+            style(&release_info.version).cyan(),
+            update_info.current_version
         );
+        println!("   Run {} to install it", style("hive self-update").bold());
         return Ok(());
     }
 
     if let Some(ref ver) = version {
+        if ver.trim_start_matches('v') != release_info.version {
+            anyhow::bail!(
+                "Only the latest release ({}) can be downloaded; use --from-file to install {}",
+                release_info.version,
+                ver
+            );
+        }
         if !force {
             println!(
                 "⚠️  {} Update to version {}?",
@@ -3309,21 +3821,14 @@ async fn handle_self_update(
             println!("   Use {} to proceed", style("--force").bold());
             return Ok(());
         }
-        println!(
-            "⬇️  {} to version {}...",
-            style("Updating").bold(),
-            style(ver).cyan()
-        );
-    } else {
-        println!("⬇️  {} to latest version...", style("Updating").bold());
     }
 
-    // Simulate update process
-    println!("📥 Downloading update...");
-    tokio::time::sleep(std::time::Duration::from_millis(800)).await;
-
-    println!("🔧 Installing update...");
-    tokio::time::sleep(std::time::Duration::from_millis(400)).await;
+    println!(
+        "⬇️  {} to version {}...",
+        style("Updating").bold(),
+        style(&release_info.version).cyan()
+    );
+    updater.install_update(&release_info).await?;
 
     println!(
         "✅ {} Update completed successfully!",
@@ -3439,19 +3944,9 @@ async fn handle_edit_performance_test(
     println!("   Language: {}", style(&language).cyan());
 
     // Parse language
-    let lang = match language.to_lowercase().as_str() {
-        "rust" => Language::Rust,
-        "typescript" | "ts" => Language::TypeScript,
-        "javascript" | "js" => Language::JavaScript,
-        "python" | "py" => Language::Python,
-        "go" => Language::Go,
-        "java" => Language::Java,
-        "cpp" | "c++" => Language::Cpp,
-        "c" => Language::C,
-        "ruby" | "rb" => Language::Ruby,
-        "php" => Language::PHP,
-        "swift" => Language::Swift,
-        _ => {
+    let lang = match Language::from_name(&language) {
+        Some(lang) => lang,
+        None => {
             eprintln!("❌ Unsupported language: {}", language);
             return Ok(());
         }
@@ -3733,6 +4228,11 @@ async fn handle_index_clear(confirm: bool) -> Result<()> {
     conn.execute("DELETE FROM symbols", [])?;
     conn.execute("DELETE FROM symbol_references", [])?;
     conn.execute("DELETE FROM symbols_fts", [])?;
+    conn.execute("DELETE FROM indexed_files", [])?;
+    drop(conn);
+
+    // Forget recorded progress so searches stop refreshing cleared projects
+    crate::analysis::background_indexer::IndexStatusStore::new(db)?.clear()?;
 
     println!(
         "✅ {} All indices cleared successfully",
@@ -169,7 +169,7 @@ _hive_completion() {{
     prev="${{COMP_WORDS[COMP_CWORD-1]}}"
 
     # Base commands
-    opts="analyze ask consensus plan execute improve search memory analytics tool serve index config trust hooks interactive tui status completion self-update help"
+    opts="analyze ask consensus batch eval review commit changelog plan execute improve search memory thread sync analytics tool serve index config trust hooks interactive tui status completion self-update help"
 
     case "${{prev}}" in
         analyze)
@@ -185,6 +185,10 @@ _hive_completion() {{
             COMPREPLY=( $(compgen -W "text json yaml markdown" -- ${{cur}}) )
             return 0
             ;;
+        --output)
+            COMPREPLY=( $(compgen -W "text json ndjson" -- ${{cur}}) )
+            return 0
+            ;;
         --depth)
             COMPREPLY=( $(compgen -W "quick standard comprehensive" -- ${{cur}}) )
             return 0
@@ -198,13 +202,21 @@ _hive_completion() {{
             return 0
             ;;
         config)
-            COMPREPLY=( $(compgen -W "show set get validate reset edit" -- ${{cur}}) )
+            COMPREPLY=( $(compgen -W "show set get validate reset edit secrets" -- ${{cur}}) )
             return 0
             ;;
         memory)
             COMPREPLY=( $(compgen -W "search stats export import clear knowledge" -- ${{cur}}) )
             return 0
             ;;
+        thread)
+            COMPREPLY=( $(compgen -W "list show delete branch" -- ${{cur}}) )
+            return 0
+            ;;
+        sync)
+            COMPREPLY=( $(compgen -W "init status run pull push share unshare" -- ${{cur}}) )
+            return 0
+            ;;
         analytics)
             COMPREPLY=( $(compgen -W "usage performance cost quality report trends" -- ${{cur}}) )
             return 0
@@ -214,7 +226,11 @@ _hive_completion() {{
             return 0
             ;;
         hooks)
-            COMPREPLY=( $(compgen -W "list add remove toggle test validate history" -- ${{cur}}) )
+            COMPREPLY=( $(compgen -W "list add remove toggle test validate history approvals" -- ${{cur}}) )
+            return 0
+            ;;
+        approvals)
+            COMPREPLY=( $(compgen -W "list show approve reject" -- ${{cur}}) )
             return 0
             ;;
         *)
@@ -223,7 +239,7 @@ _hive_completion() {{
 
     # File completion for certain flags
     case "${{cur}}" in
-        -c|--config|--output|-o)
+        -c|--config|--output-file|-o)
             COMPREPLY=( $(compgen -f -- ${{cur}}) )
             return 0
             ;;
@@ -306,6 +322,7 @@ _hive() {{
         '(-v --verbose){{-v,--verbose}}[Increase verbosity]' \
         '(-q --quiet){{-q,--quiet}}[Suppress output]' \
         '(--format)--format[Output format]:format:(text json yaml markdown)' \
+        '(--output)--output[Machine-readable output]:mode:(text json ndjson)' \
         '(--no-color)--no-color[Disable colored output]' \
         '(-c --config){{-c,--config}}[Configuration file]:file:_files' \
         '1: :_hive_commands' \
@@ -318,7 +335,7 @@ _hive() {{
                     _arguments \
                         '(-d --depth){{-d,--depth}}[Analysis depth]:depth:(quick standard comprehensive)' \
                         '(--focus)--focus[Focus areas]:focus:(architecture quality security performance)' \
-                        '(-o --output){{-o,--output}}[Save analysis to file]:file:_files' \
+                        '(-o --output-file){{-o,--output-file}}[Save analysis to file]:file:_files' \
                         '(--dependencies)--dependencies[Include dependency analysis]' \
                         '(--recommendations)--recommendations[Generate recommendations]' \
                         '1:target:_directories'
@@ -330,11 +347,74 @@ _hive() {{
                         '(-c --context){{-c,--context}}[Include file context]:file:_files' \
                         '(--max-tokens)--max-tokens[Maximum response tokens]:tokens:' \
                         '(--stream)--stream[Stream response]:bool:(true false)' \
+                        '(--continue)--thread[Continue a conversation thread]:thread id:' \
+                        '(--thread)--continue[Continue the most recent thread]' \
                         '1:question:'
                     ;;
+                batch)
+                    _arguments \
+                        '(--files)--files[Run one item per matching file]:glob:' \
+                        '(--prompt)--prompt[Query template for --files items]:template:' \
+                        '(--out-dir)--out-dir[Directory for results]:directory:_directories' \
+                        '(-p --profile){{-p,--profile}}[Consensus profile]:profile:(speed balanced cost elite)' \
+                        '(-j --concurrency){{-j,--concurrency}}[Items to run at the same time]:count:' \
+                        '(--max-cost)--max-cost[Budget in USD]:usd:' \
+                        '(--retries)--retries[Retries after transient failures]:count:' \
+                        '(--restart)--restart[Ignore the checkpoint of an earlier run]' \
+                        '1:jsonl file:_files'
+                    ;;
+                eval)
+                    _arguments \
+                        '*'{{-p,--profile}}'[Profile to evaluate]:profile:(speed balanced cost elite)' \
+                        '(--record --stub)--replay[Replay a recorded run]:file:_files' \
+                        '(--replay --stub)--record[Record answers and grades]:file:_files' \
+                        '(--replay --record)--stub[Answer with reference answers]' \
+                        '(--grader-model)--grader-model[Model that grades rubrics]:model:' \
+                        '(--threshold)--threshold[Score a case needs to pass]:score:' \
+                        '(-j --concurrency){{-j,--concurrency}}[Cases to run at the same time]:count:' \
+                        '(--report)--report[Write the Markdown report]:file:_files' \
+                        '1:dataset:_files'
+                    ;;
+                review)
+                    _arguments \
+                        '(1)--staged[Review staged changes only]' \
+                        '(-p --profile){{-p,--profile}}[Consensus profile]:profile:(speed balanced cost elite)' \
+                        '(--pipeline)--pipeline[Pipeline definition to run]:pipeline:' \
+                        '(--sarif)--sarif[Write a SARIF log]:file:_files' \
+                        '(--fail-on)--fail-on[Fail at this severity]:severity:(info warning error)' \
+                        '(-j --concurrency){{-j,--concurrency}}[Chunks to review at the same time]:count:' \
+                        '(--context-lines)--context-lines[Unchanged lines around each change]:lines:' \
+                        '(--repo)--repo[Repository to review]:directory:_directories' \
+                        '1:range:'
+                    ;;
+                commit)
+                    _arguments \
+                        '(-p --profile){{-p,--profile}}[Consensus profile]:profile:(speed balanced cost elite)' \
+                        '(--pipeline)--pipeline[Pipeline definition to run]:pipeline:' \
+                        '(-y --yes --dry-run --hook){{-y,--yes}}[Commit without asking]' \
+                        '(-y --yes --dry-run --hook)--dry-run[Only print the proposed message]' \
+                        '(-y --yes --dry-run --hook)--hook[Write the proposal into a message file]:file:_files' \
+                        '(--repo)--repo[Repository to commit in]:directory:_directories'
+                    ;;
+                changelog)
+                    _arguments \
+                        '(--title)--title[Heading of the changelog]:title:' \
+                        '(-o --output-file){{-o,--output-file}}[Write the changelog to a file]:file:_files' \
+                        '(--repo)--repo[Repository to read]:directory:_directories' \
+                        '1:range:'
+                    ;;
+                serve)
+                    _arguments \
+                        '(-m --mode){{-m,--mode}}[Server mode]:mode:(mcp lsp both)' \
+                        '(-p --port){{-p,--port}}[Port to listen on]:port:' \
+                        '(--host)--host[Bind address]:address:' \
+                        '(--cors)--cors[Enable CORS for web clients]' \
+                        '(--metrics)--metrics[Serve Prometheus metrics on this address]:address:' \
+                        '(--otlp-endpoint)--otlp-endpoint[Export traces to an OTLP collector]:url:'
+                    ;;
                 completion)
                     _arguments \
-                        '(-o --output){{-o,--output}}[Output file]:file:_files' \
+                        '(-o --output-file){{-o,--output-file}}[Output file]:file:_files' \
                         '1:shell:(bash zsh fish powershell)'
                     ;;
                 config)
@@ -343,6 +423,12 @@ _hive() {{
                 memory)
                     _hive_memory_subcommands
                     ;;
+                thread)
+                    _hive_thread_subcommands
+                    ;;
+                sync)
+                    _hive_sync_subcommands
+                    ;;
                 trust)
                     _hive_trust_subcommands
                     ;;
@@ -359,11 +445,18 @@ _hive_commands() {{
         'analyze:Analyze and understand any repository'
         'ask:Ask the AI consensus a question'
         'consensus:Run 4-stage consensus analysis'
+        'batch:Run consensus over many queries or files unattended'
+        'eval:Score consensus profiles against a golden dataset'
+        'review:Review a git diff with consensus'
+        'commit:Propose a commit message for staged changes'
+        'changelog:Generate a Markdown changelog'
         'plan:Enter planning mode for complex tasks'
         'execute:Execute a previously created plan'
         'improve:Apply AI-suggested improvements to files'
         'search:Search for symbols in the codebase'
         'memory:Manage long-term memory and conversations'
+        'thread:Manage conversation threads'
+        'sync:Share knowledge with your team through a sync server'
         'analytics:Generate comprehensive analytics reports'
         'tool:Execute tools and tool chains'
         'serve:Start IDE integration servers'
@@ -389,6 +482,7 @@ _hive_config_subcommands() {{
         'validate:Validate configuration'
         'reset:Reset configuration to defaults'
         'edit:Edit configuration in default editor'
+        'secrets:Manage encrypted API keys'
     )
 
     _describe 'config subcommands' subcommands
@@ -407,6 +501,31 @@ _hive_memory_subcommands() {{
     _describe 'memory subcommands' subcommands
 }}
 
+_hive_thread_subcommands() {{
+    local subcommands=(
+        'list:List recent threads'
+        'show:Show the turns of a thread'
+        'delete:Delete a thread and its messages'
+        'branch:Start a new thread from an earlier turn'
+    )
+
+    _describe 'thread subcommands' subcommands
+}}
+
+_hive_sync_subcommands() {{
+    local subcommands=(
+        'init:Connect to a team sync server'
+        'status:Show local and server sync state'
+        'run:Pull team changes, then push local ones'
+        'pull:Pull changes from the team'
+        'push:Push local changes to the team'
+        'share:Share a thread with the team'
+        'unshare:Stop sharing a thread with the team'
+    )
+
+    _describe 'sync subcommands' subcommands
+}}
+
 _hive_trust_subcommands() {{
     local subcommands=(
         'list:List all trusted directories'
@@ -466,6 +585,7 @@ complete -c hive -s V -l version -d "Show version information"
 complete -c hive -s v -l verbose -d "Increase verbosity"
 complete -c hive -s q -l quiet -d "Suppress output"
 complete -c hive -l format -d "Output format" -x -a "text json yaml markdown"
+complete -c hive -l output -d "Machine-readable output" -x -a "text json ndjson"
 complete -c hive -l no-color -d "Disable colored output"
 complete -c hive -s c -l config -d "Configuration file" -r
 
@@ -473,11 +593,18 @@ complete -c hive -s c -l config -d "Configuration file" -r
 complete -c hive -n "__fish_use_subcommand" -a "analyze" -d "Analyze and understand any repository"
 complete -c hive -n "__fish_use_subcommand" -a "ask" -d "Ask the AI consensus a question"
 complete -c hive -n "__fish_use_subcommand" -a "consensus" -d "Run 4-stage consensus analysis"
+complete -c hive -n "__fish_use_subcommand" -a "batch" -d "Run consensus over many queries or files unattended"
+complete -c hive -n "__fish_use_subcommand" -a "eval" -d "Score consensus profiles against a golden dataset"
+complete -c hive -n "__fish_use_subcommand" -a "review" -d "Review a git diff with consensus"
+complete -c hive -n "__fish_use_subcommand" -a "commit" -d "Propose a commit message for staged changes"
+complete -c hive -n "__fish_use_subcommand" -a "changelog" -d "Generate a Markdown changelog"
 complete -c hive -n "__fish_use_subcommand" -a "plan" -d "Enter planning mode for complex tasks"
 complete -c hive -n "__fish_use_subcommand" -a "execute" -d "Execute a previously created plan"
 complete -c hive -n "__fish_use_subcommand" -a "improve" -d "Apply AI-suggested improvements to files"
 complete -c hive -n "__fish_use_subcommand" -a "search" -d "Search for symbols in the codebase"
 complete -c hive -n "__fish_use_subcommand" -a "memory" -d "Manage long-term memory and conversations"
+complete -c hive -n "__fish_use_subcommand" -a "thread" -d "Manage conversation threads"
+complete -c hive -n "__fish_use_subcommand" -a "sync" -d "Share knowledge with your team through a sync server"
 complete -c hive -n "__fish_use_subcommand" -a "analytics" -d "Generate comprehensive analytics reports"
 complete -c hive -n "__fish_use_subcommand" -a "tool" -d "Execute tools and tool chains"
 complete -c hive -n "__fish_use_subcommand" -a "serve" -d "Start IDE integration servers"
@@ -494,7 +621,7 @@ complete -c hive -n "__fish_use_subcommand" -a "self-update" -d "Self-update Hiv
 # Analyze command options
 complete -c hive -n "__fish_seen_subcommand_from analyze" -s d -l depth -d "Analysis depth" -x -a "quick standard comprehensive"
 complete -c hive -n "__fish_seen_subcommand_from analyze" -l focus -d "Focus areas" -x -a "architecture quality security performance"
-complete -c hive -n "__fish_seen_subcommand_from analyze" -s o -l output -d "Save analysis to file" -r
+complete -c hive -n "__fish_seen_subcommand_from analyze" -s o -l output-file -d "Save analysis to file" -r
 complete -c hive -n "__fish_seen_subcommand_from analyze" -l dependencies -d "Include dependency analysis"
 complete -c hive -n "__fish_seen_subcommand_from analyze" -l recommendations -d "Generate recommendations"
 
@@ -504,9 +631,52 @@ complete -c hive -n "__fish_seen_subcommand_from ask" -l plan -d "Enable plannin
 complete -c hive -n "__fish_seen_subcommand_from ask" -s c -l context -d "Include file context" -r
 complete -c hive -n "__fish_seen_subcommand_from ask" -l max-tokens -d "Maximum response tokens" -x
 complete -c hive -n "__fish_seen_subcommand_from ask" -l stream -d "Stream response" -x -a "true false"
+complete -c hive -n "__fish_seen_subcommand_from ask" -l thread -d "Continue a conversation thread" -x
+complete -c hive -n "__fish_seen_subcommand_from ask" -l continue -d "Continue the most recent thread"
+
+# Batch command options
+complete -c hive -n "__fish_seen_subcommand_from batch" -l files -d "Run one item per matching file" -x
+complete -c hive -n "__fish_seen_subcommand_from batch" -l prompt -d "Query template for --files items" -x
+complete -c hive -n "__fish_seen_subcommand_from batch" -l out-dir -d "Directory for results" -r
+complete -c hive -n "__fish_seen_subcommand_from batch" -s p -l profile -d "Consensus profile" -x -a "speed balanced cost elite"
+complete -c hive -n "__fish_seen_subcommand_from batch" -s j -l concurrency -d "Items to run at the same time" -x
+complete -c hive -n "__fish_seen_subcommand_from batch" -l max-cost -d "Budget in USD" -x
+complete -c hive -n "__fish_seen_subcommand_from batch" -l retries -d "Retries after transient failures" -x
+complete -c hive -n "__fish_seen_subcommand_from batch" -l restart -d "Ignore the checkpoint of an earlier run"
+complete -c hive -n "__fish_seen_subcommand_from eval" -s p -l profile -d "Profile to evaluate" -x -a "speed balanced cost elite"
+complete -c hive -n "__fish_seen_subcommand_from eval" -l replay -d "Replay a recorded run" -r
+complete -c hive -n "__fish_seen_subcommand_from eval" -l record -d "Record answers and grades" -r
+complete -c hive -n "__fish_seen_subcommand_from eval" -l stub -d "Answer with reference answers"
+complete -c hive -n "__fish_seen_subcommand_from eval" -l grader-model -d "Model that grades rubrics" -x
+complete -c hive -n "__fish_seen_subcommand_from eval" -l threshold -d "Score a case needs to pass" -x
+complete -c hive -n "__fish_seen_subcommand_from eval" -s j -l concurrency -d "Cases to run at the same time" -x
+complete -c hive -n "__fish_seen_subcommand_from eval" -l report -d "Write the Markdown report" -r
+complete -c hive -n "__fish_seen_subcommand_from review" -l staged -d "Review staged changes only"
+complete -c hive -n "__fish_seen_subcommand_from review" -s p -l profile -d "Consensus profile" -x -a "speed balanced cost elite"
+complete -c hive -n "__fish_seen_subcommand_from review" -l pipeline -d "Pipeline definition to run" -x
+complete -c hive -n "__fish_seen_subcommand_from review" -l sarif -d "Write a SARIF log" -r
+complete -c hive -n "__fish_seen_subcommand_from review" -l fail-on -d "Fail at this severity" -x -a "info warning error"
+complete -c hive -n "__fish_seen_subcommand_from review" -s j -l concurrency -d "Chunks to review at the same time" -x
+complete -c hive -n "__fish_seen_subcommand_from review" -l context-lines -d "Unchanged lines around each change" -x
+complete -c hive -n "__fish_seen_subcommand_from review" -l repo -d "Repository to review" -x -a "(__fish_complete_directories)"
+complete -c hive -n "__fish_seen_subcommand_from commit" -s p -l profile -d "Consensus profile" -x -a "speed balanced cost elite"
+complete -c hive -n "__fish_seen_subcommand_from commit" -l pipeline -d "Pipeline definition to run" -x
+complete -c hive -n "__fish_seen_subcommand_from commit" -s y -l yes -d "Commit without asking"
+complete -c hive -n "__fish_seen_subcommand_from commit" -l dry-run -d "Only print the proposed message"
+complete -c hive -n "__fish_seen_subcommand_from commit" -l hook -d "Write the proposal into a message file" -r
+complete -c hive -n "__fish_seen_subcommand_from commit" -l repo -d "Repository to commit in" -x -a "(__fish_complete_directories)"
+complete -c hive -n "__fish_seen_subcommand_from changelog" -l title -d "Heading of the changelog" -x
+complete -c hive -n "__fish_seen_subcommand_from changelog" -s o -l output-file -d "Write the changelog to a file" -r
+complete -c hive -n "__fish_seen_subcommand_from changelog" -l repo -d "Repository to read" -x -a "(__fish_complete_directories)"
+complete -c hive -n "__fish_seen_subcommand_from serve" -s m -l mode -d "Server mode" -x -a "mcp lsp both"
+complete -c hive -n "__fish_seen_subcommand_from serve" -s p -l port -d "Port to listen on" -x
+complete -c hive -n "__fish_seen_subcommand_from serve" -l host -d "Bind address" -x
+complete -c hive -n "__fish_seen_subcommand_from serve" -l cors -d "Enable CORS for web clients"
+complete -c hive -n "__fish_seen_subcommand_from serve" -l metrics -d "Serve Prometheus metrics on this address" -x
+complete -c hive -n "__fish_seen_subcommand_from serve" -l otlp-endpoint -d "Export traces to an OTLP collector" -x
 
 # Completion command options
-complete -c hive -n "__fish_seen_subcommand_from completion" -s o -l output -d "Output file" -r
+complete -c hive -n "__fish_seen_subcommand_from completion" -s o -l output-file -d "Output file" -r
 complete -c hive -n "__fish_seen_subcommand_from completion" -a "bash zsh fish powershell" -d "Shell type"
 
 # Config subcommands
@@ -516,6 +686,10 @@ complete -c hive -n "__fish_seen_subcommand_from config" -a "get" -d "Get a conf
 complete -c hive -n "__fish_seen_subcommand_from config" -a "validate" -d "Validate configuration"
 complete -c hive -n "__fish_seen_subcommand_from config" -a "reset" -d "Reset configuration to defaults"
 complete -c hive -n "__fish_seen_subcommand_from config" -a "edit" -d "Edit configuration in default editor"
+complete -c hive -n "__fish_seen_subcommand_from config" -a "secrets" -d "Manage encrypted API keys"
+complete -c hive -n "__fish_seen_subcommand_from secrets" -a "list" -d "List stored secrets"
+complete -c hive -n "__fish_seen_subcommand_from secrets" -a "rotate" -d "Replace a secret or re-seal all secrets"
+complete -c hive -n "__fish_seen_subcommand_from secrets" -a "remove" -d "Remove a secret"
 
 # Memory subcommands
 complete -c hive -n "__fish_seen_subcommand_from memory" -a "search" -d "Search conversation history"
@@ -525,6 +699,28 @@ complete -c hive -n "__fish_seen_subcommand_from memory" -a "import" -d "Import
 complete -c hive -n "__fish_seen_subcommand_from memory" -a "clear" -d "Clear memory"
 complete -c hive -n "__fish_seen_subcommand_from memory" -a "knowledge" -d "Manage knowledge graph"
 
+# Thread subcommands
+complete -c hive -n "__fish_seen_subcommand_from thread" -a "list" -d "List recent threads"
+complete -c hive -n "__fish_seen_subcommand_from thread" -a "show" -d "Show the turns of a thread"
+complete -c hive -n "__fish_seen_subcommand_from thread" -a "delete" -d "Delete a thread and its messages"
+complete -c hive -n "__fish_seen_subcommand_from thread" -a "branch" -d "Start a new thread from an earlier turn"
+
+# Sync subcommands
+complete -c hive -n "__fish_seen_subcommand_from sync" -a "init" -d "Connect to a team sync server"
+complete -c hive -n "__fish_seen_subcommand_from sync" -a "status" -d "Show local and server sync state"
+complete -c hive -n "__fish_seen_subcommand_from sync" -a "run" -d "Pull team changes, then push local ones"
+complete -c hive -n "__fish_seen_subcommand_from sync" -a "pull" -d "Pull changes from the team"
+complete -c hive -n "__fish_seen_subcommand_from sync" -a "push" -d "Push local changes to the team"
+complete -c hive -n "__fish_seen_subcommand_from sync" -a "share" -d "Share a thread with the team"
+complete -c hive -n "__fish_seen_subcommand_from sync" -a "unshare" -d "Stop sharing a thread with the team"
+
+# Hooks approval queue
+complete -c hive -n "__fish_seen_subcommand_from hooks; and not __fish_seen_subcommand_from approvals" -a "approvals" -d "Review approval requests raised by hooks"
+complete -c hive -n "__fish_seen_subcommand_from approvals" -a "list" -d "List approval requests waiting for a decision"
+complete -c hive -n "__fish_seen_subcommand_from approvals" -a "show" -d "Show a request and its decisions"
+complete -c hive -n "__fish_seen_subcommand_from approvals" -a "approve" -d "Approve a request"
+complete -c hive -n "__fish_seen_subcommand_from approvals" -a "reject" -d "Reject a request"
+
 # Trust subcommands
 complete -c hive -n "__fish_seen_subcommand_from trust" -a "list" -d "List all trusted directories"
 complete -c hive -n "__fish_seen_subcommand_from trust" -a "add" -d "Add a directory to trusted paths"
@@ -541,6 +737,7 @@ complete -c hive -n "__fish_seen_subcommand_from self-update" -l force -d "Force
 complete -c hive -n "__fish_seen_subcommand_from self-update" -l version -d "Update to specific version" -x
 complete -c hive -n "__fish_seen_subcommand_from self-update" -l rollback -d "Rollback to previous version"
 complete -c hive -n "__fish_seen_subcommand_from self-update" -l list-versions -d "Show available versions"
+complete -c hive -n "__fish_seen_subcommand_from self-update" -l from-file -d "Install from an offline bundle" -r
 
 # Useful abbreviations
 abbr ha 'hive analyze'
@@ -9,12 +9,15 @@ pub mod banner;
 pub mod commands;
 pub mod completions;
 pub mod framework;
+pub mod output;
+pub mod repl;
 pub mod trust;
 
 pub use args::{Cli, Commands};
 pub use banner::print_banner;
 pub use commands::*;
 pub use framework::CliFramework;
+pub use output::OutputMode;
 
 use crate::core::error::Result;
 use std::env;
@@ -25,10 +28,14 @@ pub async fn should_launch_tui() -> bool {
     false
 }
 
-/// Check TUI capabilities synchronously
+/// Check TUI capabilities synchronously: an interactive terminal of at least 120x30
 pub fn check_tui_capabilities() -> bool {
-    // TUI functionality removed - GUI only
-    false
+    use is_terminal::IsTerminal;
+
+    if !std::io::stdout().is_terminal() {
+        return false;
+    }
+    matches!(crossterm::terminal::size(), Ok((width, height)) if width >= 120 && height >= 30)
 }
 
 /// Initialize CLI system
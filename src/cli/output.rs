@@ -0,0 +1,468 @@
+//! Machine-readable command output
+//!
+//! `--output json` prints one envelope per command with a stable, versioned
+//! `data` schema; `--output ndjson` prints one JSON object per line, streaming
+//! `ConsensusEvent`s for consensus commands before the final `result` line.
+//! Failures map to the exit codes in [`exit_code`].
+
+use anyhow::Result;
+use clap::ValueEnum;
+use serde::Serialize;
+use std::io::Write;
+use std::path::PathBuf;
+
+use crate::analysis::repository_intelligence::RepositoryAnalysis;
+use crate::analysis::symbol_index::SymbolEntry;
+use crate::consensus::streaming::{ConsensusEvent, ProgressInfo, StreamingCallbacks};
+use crate::consensus::threads::ThreadSummary;
+use crate::consensus::types::{ConsensusResult, Stage, StageAnalytics, StageResult, TokenUsage};
+use crate::core::error::{ErrorCategory, HiveError};
+use crate::core::secrets::SecretInfo;
+use crate::team_sync::{ServerStatus, SyncStatus};
+
+/// Version of the JSON schemas below; bumped on incompatible changes
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Process exit codes
+pub mod exit_code {
+    pub const SUCCESS: i32 = 0;
+    /// Any failure without a more specific code
+    pub const FAILURE: i32 = 1;
+    /// Invalid arguments (also used by clap)
+    pub const USAGE: i32 = 2;
+    /// Missing or invalid configuration, including API keys
+    pub const CONFIG: i32 = 3;
+    /// Network, provider or authentication failure
+    pub const NETWORK: i32 = 4;
+    /// The consensus pipeline failed or a hook blocked it
+    pub const CONSENSUS: i32 = 5;
+    /// Trust or security policy denied the operation
+    pub const SECURITY: i32 = 6;
+    /// The command succeeded but its results crossed a `--fail-on` threshold
+    pub const THRESHOLD: i32 = 7;
+    /// Interrupted with Ctrl+C
+    pub const CANCELLED: i32 = 130;
+}
+
+/// Format of everything a command writes to stdout
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputMode {
+    /// Styled text for people
+    #[default]
+    Text,
+    /// One JSON document
+    Json,
+    /// Newline-delimited JSON events
+    Ndjson,
+}
+
+impl OutputMode {
+    pub fn is_text(self) -> bool {
+        self == Self::Text
+    }
+
+    /// The mode as spelled on the command line
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Text => "text",
+            Self::Json => "json",
+            Self::Ndjson => "ndjson",
+        }
+    }
+}
+
+/// The command was interrupted by the user
+#[derive(Debug, thiserror::Error)]
+#[error("Cancelled by user")]
+pub struct Cancelled;
+
+/// The command finished, but its results crossed a CI threshold such as
+/// `hive review --fail-on`; the results have already been printed
+#[derive(Debug, thiserror::Error)]
+#[error("{0}")]
+pub struct ThresholdExceeded(pub String);
+
+/// The command only prints styled text and was run with `--output json|ndjson`
+#[derive(Debug, thiserror::Error)]
+#[error("This command has no machine-readable output; run it without `--output {0}`")]
+pub struct UnsupportedOutput(pub &'static str);
+
+/// Exit code for a command error
+pub fn exit_code_for(error: &anyhow::Error) -> i32 {
+    if error.downcast_ref::<Cancelled>().is_some() {
+        return exit_code::CANCELLED;
+    }
+    if error.downcast_ref::<UnsupportedOutput>().is_some() {
+        return exit_code::USAGE;
+    }
+    if error.downcast_ref::<ThresholdExceeded>().is_some() {
+        return exit_code::THRESHOLD;
+    }
+    match error.chain().find_map(|e| e.downcast_ref::<HiveError>()) {
+        Some(hive_error) => match hive_error.category() {
+            ErrorCategory::Configuration => exit_code::CONFIG,
+            ErrorCategory::Network => exit_code::NETWORK,
+            ErrorCategory::Consensus => exit_code::CONSENSUS,
+            ErrorCategory::Security => exit_code::SECURITY,
+            _ => exit_code::FAILURE,
+        },
+        None => exit_code::FAILURE,
+    }
+}
+
+/// Classify a consensus engine error for the exit code, keeping typed errors
+/// as they are
+pub fn consensus_error(error: anyhow::Error) -> anyhow::Error {
+    if error.chain().any(|cause| cause.is::<HiveError>()) {
+        error
+    } else {
+        HiveError::ConsensusFailed {
+            message: format!("{:#}", error),
+        }
+        .into()
+    }
+}
+
+#[derive(Serialize)]
+struct Envelope<'a, T: Serialize> {
+    schema_version: u32,
+    command: &'a str,
+    ok: bool,
+    data: &'a T,
+}
+
+#[derive(Serialize)]
+struct ErrorEnvelope<'a> {
+    schema_version: u32,
+    ok: bool,
+    error: ErrorBody<'a>,
+}
+
+#[derive(Serialize)]
+struct ErrorBody<'a> {
+    code: i32,
+    category: String,
+    message: &'a str,
+}
+
+/// Print a command's result; a no-op in text mode
+pub fn emit<T: Serialize>(mode: OutputMode, command: &str, data: &T) -> Result<()> {
+    let envelope = Envelope {
+        schema_version: SCHEMA_VERSION,
+        command,
+        ok: true,
+        data,
+    };
+    match mode {
+        OutputMode::Text => Ok(()),
+        OutputMode::Json => print_line(&serde_json::to_string_pretty(&envelope)?),
+        OutputMode::Ndjson => print_line(&tagged("result", &envelope)?),
+    }
+}
+
+/// Print a progress line of type `kind`; only NDJSON mode streams progress
+pub fn emit_event<T: Serialize>(mode: OutputMode, kind: &str, value: &T) -> Result<()> {
+    match mode {
+        OutputMode::Ndjson => print_line(&tagged(kind, value)?),
+        _ => Ok(()),
+    }
+}
+
+/// Print a failed command's error in the machine-readable formats
+pub fn emit_error(mode: OutputMode, error: &anyhow::Error) -> Result<()> {
+    let message = format!("{:#}", error);
+    let category = match error.chain().find_map(|e| e.downcast_ref::<HiveError>()) {
+        Some(hive_error) => hive_error.category().to_string(),
+        None if error.downcast_ref::<UnsupportedOutput>().is_some() => "usage".to_string(),
+        None => "system".to_string(),
+    };
+    let envelope = ErrorEnvelope {
+        schema_version: SCHEMA_VERSION,
+        ok: false,
+        error: ErrorBody {
+            code: exit_code_for(error),
+            category,
+            message: &message,
+        },
+    };
+    match mode {
+        OutputMode::Text => Ok(()),
+        OutputMode::Json => print_line(&serde_json::to_string_pretty(&envelope)?),
+        OutputMode::Ndjson => print_line(&tagged("error", &envelope)?),
+    }
+}
+
+/// Serialize `value` on one line with a leading `"type"` field
+fn tagged<T: Serialize>(kind: &str, value: &T) -> Result<String> {
+    #[derive(Serialize)]
+    struct Tagged<'a, T: Serialize> {
+        #[serde(rename = "type")]
+        kind: &'a str,
+        #[serde(flatten)]
+        value: &'a T,
+    }
+    Ok(serde_json::to_string(&Tagged { kind, value })?)
+}
+
+fn print_line(line: &str) -> Result<()> {
+    let mut stdout = std::io::stdout().lock();
+    writeln!(stdout, "{}", line)?;
+    stdout.flush()?;
+    Ok(())
+}
+
+/// Streams consensus progress as NDJSON `ConsensusEvent` lines
+pub struct NdjsonCallbacks;
+
+impl NdjsonCallbacks {
+    fn send(&self, event: ConsensusEvent) -> Result<()> {
+        print_line(&serde_json::to_string(&event)?)
+    }
+}
+
+impl StreamingCallbacks for NdjsonCallbacks {
+    fn on_stage_start(&self, stage: Stage, model: &str) -> Result<()> {
+        self.send(ConsensusEvent::StageStarted {
+            stage,
+            model: model.to_string(),
+        })
+    }
+
+    fn on_stage_chunk(&self, stage: Stage, chunk: &str, total_content: &str) -> Result<()> {
+        self.send(ConsensusEvent::Token {
+            stage,
+            chunk: chunk.to_string(),
+            total_content: total_content.to_string(),
+        })
+    }
+
+    fn on_stage_progress(&self, stage: Stage, progress: ProgressInfo) -> Result<()> {
+        self.send(ConsensusEvent::Progress {
+            stage,
+            tokens: progress.tokens,
+            estimated_total: progress.estimated_total,
+            percentage: progress.percentage,
+        })
+    }
+
+    fn on_stage_complete(&self, stage: Stage, result: &StageResult) -> Result<()> {
+        self.send(ConsensusEvent::StageCompleted {
+            stage,
+            result: result.clone(),
+        })
+    }
+
+    fn on_error(&self, stage: Stage, error: &anyhow::Error) -> Result<()> {
+        self.send(ConsensusEvent::Error {
+            stage,
+            error: format!("{:#}", error),
+        })
+    }
+}
+
+/// `data` of `hive ask` and `hive consensus`
+#[derive(Debug, Serialize)]
+pub struct ConsensusOutput {
+    pub query: String,
+    pub profile: String,
+    pub timestamp: String,
+    pub success: bool,
+    pub result: Option<String>,
+    pub error: Option<String>,
+    /// Seconds
+    pub total_duration: f64,
+    /// USD
+    pub total_cost: f64,
+    pub conversation_id: String,
+    /// Thread the turn was recorded in, for `hive ask`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thread_id: Option<String>,
+    pub stages: Vec<StageOutput>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StageOutput {
+    pub stage_name: String,
+    pub model: String,
+    pub answer: String,
+    pub usage: Option<TokenUsage>,
+    pub analytics: Option<StageAnalytics>,
+}
+
+impl ConsensusOutput {
+    pub fn new(query: &str, profile: &str, result: &ConsensusResult) -> Self {
+        Self {
+            query: query.to_string(),
+            profile: profile.to_string(),
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            success: result.success,
+            result: result.result.clone(),
+            error: result.error.clone(),
+            total_duration: result.total_duration,
+            total_cost: result.total_cost,
+            conversation_id: result.conversation_id.clone(),
+            thread_id: None,
+            stages: result
+                .stages
+                .iter()
+                .map(|stage| StageOutput {
+                    stage_name: stage.stage_name.clone(),
+                    model: stage.model.clone(),
+                    answer: stage.answer.clone(),
+                    usage: stage.usage.clone(),
+                    analytics: stage.analytics.clone(),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// `data` of `hive analyze`
+#[derive(Debug, Serialize)]
+pub struct AnalyzeOutput {
+    pub target: PathBuf,
+    pub depth: String,
+    pub duration_ms: u64,
+    pub analysis: RepositoryAnalysis,
+}
+
+/// `data` of `hive search`
+#[derive(Debug, Serialize)]
+pub struct SearchOutput {
+    pub query: String,
+    pub kind: Option<String>,
+    pub fuzzy: bool,
+    pub duration_ms: f64,
+    pub results: Vec<SymbolEntry>,
+}
+
+/// `data` of `hive status`
+#[derive(Debug, Serialize)]
+pub struct StatusOutput {
+    pub version: String,
+    pub config_dir: PathBuf,
+    pub config_loaded: bool,
+    pub api_key_configured: bool,
+    /// Only checked with `--check-apis`
+    pub internet: Option<bool>,
+    pub api_available: Option<bool>,
+    /// Only reported with `--performance`
+    pub memory_bytes: Option<usize>,
+}
+
+/// `data` of `hive thread list`
+#[derive(Debug, Serialize)]
+pub struct ThreadListOutput {
+    pub threads: Vec<ThreadSummary>,
+}
+
+/// `data` of `hive thread delete`
+#[derive(Debug, Serialize)]
+pub struct ThreadDeleteOutput {
+    pub id: String,
+}
+
+/// `data` of `hive sync init`
+#[derive(Debug, Serialize)]
+pub struct SyncInitOutput {
+    pub server_url: String,
+    pub team: String,
+    pub share_facts: bool,
+    pub node_id: String,
+}
+
+/// `data` of `hive sync status`
+#[derive(Debug, Serialize)]
+pub struct SyncStatusOutput {
+    pub server_url: String,
+    pub local: SyncStatus,
+    /// `None` when the server could not be reached
+    pub server: Option<ServerStatus>,
+    pub server_error: Option<String>,
+}
+
+/// `data` of `hive sync share` and `hive sync unshare`
+#[derive(Debug, Serialize)]
+pub struct SyncShareOutput {
+    pub id: String,
+    pub shared: bool,
+}
+
+/// `data` of `hive config secrets list`
+#[derive(Debug, Serialize)]
+pub struct SecretListOutput {
+    pub secrets: Vec<SecretInfo>,
+}
+
+/// `data` of `hive config secrets rotate`
+#[derive(Debug, Serialize)]
+pub struct SecretRotateOutput {
+    /// The replaced secret, or `None` when the whole file was re-sealed
+    pub name: Option<String>,
+    /// Secrets re-sealed under the new key
+    pub resealed: usize,
+    /// "keyfile" or "passphrase"
+    pub key_source: Option<String>,
+}
+
+/// `data` of `hive config secrets remove`
+#[derive(Debug, Serialize)]
+pub struct SecretRemoveOutput {
+    pub name: String,
+    pub removed: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exit_codes() {
+        let config: anyhow::Error = HiveError::ConfigMissingField {
+            field: "OPENROUTER_API_KEY".to_string(),
+        }
+        .into();
+        assert_eq!(exit_code_for(&config), exit_code::CONFIG);
+        assert_eq!(
+            exit_code_for(&config.context("Failed to start")),
+            exit_code::CONFIG
+        );
+        assert_eq!(
+            exit_code_for(&anyhow::Error::new(Cancelled)),
+            exit_code::CANCELLED
+        );
+        assert_eq!(
+            exit_code_for(&ThresholdExceeded("2 findings".to_string()).into()),
+            exit_code::THRESHOLD
+        );
+        assert_eq!(
+            exit_code_for(&UnsupportedOutput("json").into()),
+            exit_code::USAGE
+        );
+        assert_eq!(exit_code_for(&anyhow::anyhow!("boom")), exit_code::FAILURE);
+        assert_eq!(
+            exit_code_for(&consensus_error(anyhow::anyhow!("stage timed out"))),
+            exit_code::CONSENSUS
+        );
+        assert_eq!(
+            exit_code_for(&consensus_error(
+                HiveError::config_invalid("unknown profile").into()
+            )),
+            exit_code::CONFIG
+        );
+    }
+
+    #[test]
+    fn test_tagged_lines() {
+        let envelope = Envelope {
+            schema_version: SCHEMA_VERSION,
+            command: "search",
+            ok: true,
+            data: &vec![1, 2],
+        };
+        let line = tagged("result", &envelope).unwrap();
+        assert!(!line.contains('\n'));
+        assert!(line.starts_with(r#"{"type":"result","schema_version":1"#));
+        assert!(line.contains(r#""data":[1,2]"#));
+    }
+}
@@ -0,0 +1,621 @@
+//! Line editor for the REPL: history with reverse search, multi-line input
+//! and tab completion of slash commands and file paths
+//!
+//! Falls back to plain buffered reads when stdin is not a terminal.
+
+use anyhow::Result;
+use crossterm::cursor::{MoveDown, MoveToColumn, MoveUp};
+use crossterm::event::{
+    self, DisableBracketedPaste, EnableBracketedPaste, Event, KeyCode, KeyEvent, KeyEventKind,
+    KeyModifiers,
+};
+use crossterm::style::{Print, Stylize};
+use crossterm::terminal::{self, Clear, ClearType};
+use crossterm::{execute, queue};
+use is_terminal::IsTerminal;
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+
+use super::input::InputBuffer;
+use crate::consensus::mentions::MENTION_KINDS;
+
+const MAX_HISTORY: usize = 1000;
+const MAX_LISTED_COMPLETIONS: usize = 20;
+const CONTINUATION: &str = "… ";
+
+/// Result of reading one entry
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReadOutcome {
+    Line(String),
+    /// Ctrl+C on an empty line
+    Interrupted,
+    /// Ctrl+D on an empty line, or end of input
+    Eof,
+}
+
+/// Entered lines, persisted one JSON string per line so multi-line entries survive
+pub struct History {
+    entries: Vec<String>,
+    path: Option<PathBuf>,
+}
+
+impl History {
+    pub fn in_memory() -> Self {
+        Self {
+            entries: Vec::new(),
+            path: None,
+        }
+    }
+
+    /// Load history from `path`; a missing or unreadable file starts empty
+    pub fn load(path: PathBuf) -> Self {
+        let mut entries: Vec<String> = std::fs::read_to_string(&path)
+            .map(|content| {
+                content
+                    .lines()
+                    .filter_map(|line| serde_json::from_str(line).ok())
+                    .collect()
+            })
+            .unwrap_or_default();
+        if entries.len() > MAX_HISTORY {
+            entries.drain(..entries.len() - MAX_HISTORY);
+            let content: String = entries
+                .iter()
+                .filter_map(|entry| serde_json::to_string(entry).ok())
+                .map(|line| line + "\n")
+                .collect();
+            let _ = std::fs::write(&path, content);
+        }
+        Self {
+            entries,
+            path: Some(path),
+        }
+    }
+
+    pub fn entries(&self) -> &[String] {
+        &self.entries
+    }
+
+    /// Append an entry, skipping blanks and immediate repeats
+    pub fn push(&mut self, entry: &str) {
+        let entry = entry.trim();
+        if entry.is_empty() || self.entries.last().map(String::as_str) == Some(entry) {
+            return;
+        }
+        self.entries.push(entry.to_string());
+        if let Some(path) = &self.path {
+            if let (Ok(mut file), Ok(line)) = (
+                std::fs::OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(path),
+                serde_json::to_string(entry),
+            ) {
+                let _ = writeln!(file, "{}", line);
+            }
+        }
+    }
+
+    /// Index of the newest entry before `before` containing `query`
+    pub fn search(&self, query: &str, before: usize) -> Option<usize> {
+        self.entries[..before.min(self.entries.len())]
+            .iter()
+            .rposition(|entry| entry.contains(query))
+    }
+}
+
+/// Candidates replacing `line[start..]`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Completion {
+    pub start: usize,
+    pub candidates: Vec<String>,
+}
+
+/// Complete a slash command at the start of the line, otherwise the last word
+/// as an @-mention or a path
+pub fn complete(line: &str, cwd: &Path, commands: &[&str]) -> Completion {
+    if line.starts_with('/') && !line.contains(char::is_whitespace) {
+        return Completion {
+            start: 0,
+            candidates: commands
+                .iter()
+                .filter(|command| command.starts_with(line))
+                .map(|command| command.to_string())
+                .collect(),
+        };
+    }
+
+    let start = line
+        .char_indices()
+        .rev()
+        .find(|(_, c)| c.is_whitespace())
+        .map(|(i, c)| i + c.len_utf8())
+        .unwrap_or(0);
+    let word = &line[start..];
+    let candidates = match word.split_once(':') {
+        Some((kind @ ("@file" | "@dir"), path)) => complete_path(path, cwd)
+            .into_iter()
+            .filter(|candidate| kind == "@file" || candidate.ends_with('/'))
+            .map(|candidate| format!("{}:{}", kind, candidate))
+            .collect(),
+        None if word.starts_with('@') => MENTION_KINDS
+            .iter()
+            .filter(|kind| kind.starts_with(word))
+            .map(|kind| kind.to_string())
+            .collect(),
+        _ => complete_path(word, cwd),
+    };
+    Completion { start, candidates }
+}
+
+fn complete_path(word: &str, cwd: &Path) -> Vec<String> {
+    let (dir, prefix) = match word.rfind('/') {
+        Some(i) => (&word[..=i], &word[i + 1..]),
+        None => ("", word),
+    };
+    let base = if dir.starts_with('/') {
+        PathBuf::from(dir)
+    } else {
+        cwd.join(dir)
+    };
+    let Ok(entries) = std::fs::read_dir(base) else {
+        return Vec::new();
+    };
+
+    let mut candidates: Vec<String> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if !name.starts_with(prefix) || (name.starts_with('.') && !prefix.starts_with('.')) {
+                return None;
+            }
+            let is_dir = entry.file_type().map(|t| t.is_dir()).unwrap_or(false);
+            Some(format!("{}{}{}", dir, name, if is_dir { "/" } else { "" }))
+        })
+        .collect();
+    candidates.sort();
+    candidates
+}
+
+/// Longest prefix shared by all candidates
+pub fn common_prefix(candidates: &[String]) -> String {
+    let Some(first) = candidates.first() else {
+        return String::new();
+    };
+    let mut prefix: Vec<char> = first.chars().collect();
+    for candidate in &candidates[1..] {
+        let shared = prefix
+            .iter()
+            .zip(candidate.chars())
+            .take_while(|(a, b)| **a == *b)
+            .count();
+        prefix.truncate(shared);
+    }
+    prefix.into_iter().collect()
+}
+
+/// Raw mode for the duration of one read
+struct RawMode;
+
+impl RawMode {
+    fn enter() -> Result<Self> {
+        terminal::enable_raw_mode()?;
+        let _ = execute!(io::stdout(), EnableBracketedPaste);
+        Ok(Self)
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        let _ = execute!(io::stdout(), DisableBracketedPaste);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+struct Search {
+    query: String,
+    matched: Option<usize>,
+    original: String,
+}
+
+/// Per-read editing state
+struct EditState<'a> {
+    prompt: &'a str,
+    buffer: InputBuffer,
+    /// Position while browsing history; `entries.len()` is the draft
+    history_index: usize,
+    draft: String,
+    search: Option<Search>,
+    hint: Option<String>,
+    /// Cursor row within the drawn block, so the next render can start over
+    cursor_row: u16,
+    /// Last row of the drawn block
+    end_row: u16,
+}
+
+impl<'a> EditState<'a> {
+    fn new(prompt: &'a str, history_len: usize) -> Self {
+        Self {
+            prompt,
+            buffer: InputBuffer::default(),
+            history_index: history_len,
+            draft: String::new(),
+            search: None,
+            hint: None,
+            cursor_row: 0,
+            end_row: 0,
+        }
+    }
+
+    fn insert_text(&mut self, text: &str) {
+        for c in text.chars() {
+            match c {
+                '\n' => self.buffer.insert_newline(),
+                '\r' => {}
+                c => {
+                    self.buffer
+                        .handle_key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+                }
+            }
+        }
+    }
+
+    fn history_previous(&mut self, history: &History) {
+        if self.history_index == 0 {
+            return;
+        }
+        if self.history_index == history.entries().len() {
+            self.draft = self.buffer.text();
+        }
+        self.history_index -= 1;
+        self.buffer.set_text(&history.entries()[self.history_index]);
+    }
+
+    fn history_next(&mut self, history: &History) {
+        if self.history_index >= history.entries().len() {
+            return;
+        }
+        self.history_index += 1;
+        match history.entries().get(self.history_index) {
+            Some(entry) => self.buffer.set_text(entry),
+            None => self.buffer.set_text(&self.draft),
+        }
+    }
+
+    fn status_line(&self) -> Option<String> {
+        match &self.search {
+            Some(search) if search.matched.is_none() && !search.query.is_empty() => {
+                Some(format!("(failing reverse-i-search) `{}`", search.query))
+            }
+            Some(search) => Some(format!("(reverse-i-search) `{}`", search.query)),
+            None => self.hint.clone(),
+        }
+    }
+
+    /// Redraw the prompt and buffer in place
+    fn render(&mut self, out: &mut impl Write) -> Result<()> {
+        let width = terminal::size()
+            .map(|(w, _)| w.max(1) as usize)
+            .unwrap_or(80);
+        let rows_for = |len: usize| len.max(1).div_ceil(width);
+        let indent = self.prompt.chars().count();
+
+        queue!(out, MoveToColumn(0))?;
+        if self.cursor_row > 0 {
+            queue!(out, MoveUp(self.cursor_row))?;
+        }
+        queue!(out, Clear(ClearType::FromCursorDown))?;
+
+        let (cursor_line, cursor_col) = self.buffer.cursor();
+        let mut rows = 0;
+        let mut cursor = (0, 0);
+        for (i, line) in self.buffer.lines().iter().enumerate() {
+            if i == 0 {
+                queue!(out, Print(self.prompt.cyan().bold()))?;
+            } else {
+                queue!(
+                    out,
+                    Print("\r\n"),
+                    Print(format!("{:>indent$}", CONTINUATION).dark_grey())
+                )?;
+            }
+            queue!(out, Print(line))?;
+            if i == cursor_line {
+                let offset = indent + cursor_col;
+                cursor = (rows + offset / width, offset % width);
+            }
+            rows += rows_for(indent + line.chars().count());
+        }
+        if let Some(status) = self.status_line() {
+            queue!(out, Print("\r\n"), Print(status.as_str().dark_grey()))?;
+            rows += rows_for(status.chars().count());
+        }
+
+        let end_row = rows.saturating_sub(1);
+        let cursor_row = cursor.0.min(end_row);
+        if end_row > cursor_row {
+            queue!(out, MoveUp((end_row - cursor_row) as u16))?;
+        }
+        queue!(out, MoveToColumn(cursor.1 as u16))?;
+        out.flush()?;
+
+        self.cursor_row = cursor_row as u16;
+        self.end_row = end_row as u16;
+        Ok(())
+    }
+
+    /// Leave the cursor below the finished entry
+    fn finish(&mut self, out: &mut impl Write) -> Result<()> {
+        self.search = None;
+        self.hint = None;
+        self.render(out)?;
+        if self.end_row > self.cursor_row {
+            queue!(out, MoveDown(self.end_row - self.cursor_row))?;
+        }
+        queue!(out, Print("\r\n"))?;
+        out.flush()?;
+        Ok(())
+    }
+}
+
+/// Reads REPL entries from the terminal
+pub struct LineEditor {
+    pub history: History,
+    commands: Vec<&'static str>,
+    cwd: PathBuf,
+}
+
+impl LineEditor {
+    pub fn new(history: History, commands: &[&'static str], cwd: PathBuf) -> Self {
+        Self {
+            history,
+            commands: commands.to_vec(),
+            cwd,
+        }
+    }
+
+    /// Read one entry; Enter submits, Alt+Enter or a trailing `\` continues on a new line
+    pub fn read_line(&mut self, prompt: &str) -> Result<ReadOutcome> {
+        if !io::stdin().is_terminal() {
+            return read_plain(prompt);
+        }
+
+        let _raw = RawMode::enter()?;
+        let mut out = io::stdout();
+        let mut state = EditState::new(prompt, self.history.entries().len());
+        let outcome = loop {
+            state.render(&mut out)?;
+            match event::read()? {
+                Event::Key(key) if key.kind != KeyEventKind::Release => {
+                    if let Some(outcome) = self.handle_key(&mut state, key) {
+                        break outcome;
+                    }
+                }
+                Event::Paste(text) => state.insert_text(&text),
+                _ => {}
+            }
+        };
+        state.finish(&mut out)?;
+        Ok(outcome)
+    }
+
+    fn handle_key(&self, state: &mut EditState, key: KeyEvent) -> Option<ReadOutcome> {
+        if state.search.is_some() {
+            self.handle_search_key(state, key);
+            return None;
+        }
+
+        state.hint = None;
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        let (row, col) = state.buffer.cursor();
+        let last_row = state.buffer.lines().len() - 1;
+        match key.code {
+            KeyCode::Char('c') if ctrl => {
+                if state.buffer.is_empty() {
+                    return Some(ReadOutcome::Interrupted);
+                }
+                state.buffer.clear();
+            }
+            KeyCode::Char('d') if ctrl && state.buffer.is_empty() => return Some(ReadOutcome::Eof),
+            KeyCode::Char('r') if ctrl => {
+                state.search = Some(Search {
+                    query: String::new(),
+                    matched: None,
+                    original: state.buffer.text(),
+                });
+            }
+            KeyCode::Enter if key.modifiers.contains(KeyModifiers::ALT) => {
+                state.buffer.insert_newline()
+            }
+            KeyCode::Enter => {
+                let line = &state.buffer.lines()[row];
+                if row == last_row && col == line.chars().count() && line.ends_with('\\') {
+                    state
+                        .buffer
+                        .handle_key(KeyEvent::new(KeyCode::Backspace, KeyModifiers::NONE));
+                    state.buffer.insert_newline();
+                } else {
+                    return Some(ReadOutcome::Line(state.buffer.text()));
+                }
+            }
+            KeyCode::Tab => self.complete(state),
+            KeyCode::Up if row == 0 => state.history_previous(&self.history),
+            KeyCode::Down if row == last_row => state.history_next(&self.history),
+            _ => {
+                state.buffer.handle_key(key);
+            }
+        }
+        None
+    }
+
+    fn handle_search_key(&self, state: &mut EditState, key: KeyEvent) {
+        let Some(search) = state.search.as_mut() else {
+            return;
+        };
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        let newest = self.history.entries().len();
+        if key.code == KeyCode::Esc || (ctrl && matches!(key.code, KeyCode::Char('g' | 'c'))) {
+            let original = search.original.clone();
+            state.buffer.set_text(&original);
+            state.search = None;
+            return;
+        }
+        match key.code {
+            KeyCode::Char('r') if ctrl => {
+                let before = search.matched.unwrap_or(newest);
+                if let Some(index) = self.history.search(&search.query, before) {
+                    search.matched = Some(index);
+                }
+            }
+            KeyCode::Char(c) if !ctrl => {
+                search.query.push(c);
+                let before = search.matched.map(|i| i + 1).unwrap_or(newest);
+                search.matched = self.history.search(&search.query, before);
+            }
+            KeyCode::Backspace => {
+                search.query.pop();
+                search.matched = self.history.search(&search.query, newest);
+            }
+            _ => {
+                // Any other key accepts the match for editing
+                state.search = None;
+                return;
+            }
+        }
+        let text = match search.matched {
+            Some(index) => self.history.entries()[index].clone(),
+            None => search.original.clone(),
+        };
+        state.buffer.set_text(&text);
+    }
+
+    fn complete(&self, state: &mut EditState) {
+        let (row, col) = state.buffer.cursor();
+        let before: String = state.buffer.lines()[row].chars().take(col).collect();
+        let completion = complete(&before, &self.cwd, &self.commands);
+        let typed = &before[completion.start..];
+
+        let replacement = match completion.candidates.as_slice() {
+            [] => return,
+            [only] if only.ends_with('/') || only.ends_with(':') => only.clone(),
+            [only] => format!("{} ", only),
+            candidates => common_prefix(candidates),
+        };
+        if replacement.len() > typed.len() && replacement.starts_with(typed) {
+            state.insert_text(&replacement[typed.len()..]);
+        } else if completion.candidates.len() > 1 {
+            let mut listed: Vec<&str> = completion
+                .candidates
+                .iter()
+                .take(MAX_LISTED_COMPLETIONS)
+                .map(String::as_str)
+                .collect();
+            if completion.candidates.len() > MAX_LISTED_COMPLETIONS {
+                listed.push("…");
+            }
+            state.hint = Some(listed.join("  "));
+        }
+    }
+}
+
+/// Read an entry from non-interactive stdin, joining lines that end in `\`
+fn read_plain(prompt: &str) -> Result<ReadOutcome> {
+    let stdin = io::stdin();
+    let mut entry = String::new();
+    loop {
+        print!(
+            "{}",
+            if entry.is_empty() {
+                prompt
+            } else {
+                CONTINUATION
+            }
+        );
+        io::stdout().flush()?;
+
+        let mut line = String::new();
+        if stdin.lock().read_line(&mut line)? == 0 {
+            return Ok(if entry.is_empty() {
+                ReadOutcome::Eof
+            } else {
+                ReadOutcome::Line(entry)
+            });
+        }
+        let line = line.trim_end_matches(['\n', '\r']);
+        match line.strip_suffix('\\') {
+            Some(continued) => {
+                entry.push_str(continued);
+                entry.push('\n');
+            }
+            None => {
+                entry.push_str(line);
+                return Ok(ReadOutcome::Line(entry));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_complete_commands_and_paths() {
+        let dir = TempDir::new().unwrap();
+        std::fs::create_dir(dir.path().join("src")).unwrap();
+        std::fs::write(dir.path().join("src/main.rs"), "").unwrap();
+        std::fs::write(dir.path().join("src/mod.rs"), "").unwrap();
+        std::fs::write(dir.path().join(".hidden"), "").unwrap();
+
+        let commands = ["/context", "/cost", "/undo"];
+        assert_eq!(
+            complete("/co", dir.path(), &commands).candidates,
+            vec!["/context", "/cost"]
+        );
+
+        let completion = complete("/context add sr", dir.path(), &commands);
+        assert_eq!(completion.start, 13);
+        assert_eq!(completion.candidates, vec!["src/"]);
+
+        let completion = complete("look at src/m", dir.path(), &commands);
+        assert_eq!(completion.candidates, vec!["src/main.rs", "src/mod.rs"]);
+        assert_eq!(common_prefix(&completion.candidates), "src/m");
+
+        assert!(complete("", dir.path(), &commands)
+            .candidates
+            .iter()
+            .all(|c| !c.starts_with('.')));
+
+        assert_eq!(
+            complete("explain @d", dir.path(), &commands).candidates,
+            vec!["@diff", "@dir:"]
+        );
+        assert_eq!(
+            complete("explain @file:src/ma", dir.path(), &commands).candidates,
+            vec!["@file:src/main.rs"]
+        );
+        assert_eq!(
+            complete("explain @dir:", dir.path(), &commands).candidates,
+            vec!["@dir:src/"]
+        );
+    }
+
+    #[test]
+    fn test_history_persists_and_searches() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("history");
+        let mut history = History::load(path.clone());
+        history.push("explain the parser");
+        history.push("explain the parser");
+        history.push("fix the lexer\nand tests");
+        history.push("explain the walker");
+
+        let history = History::load(path);
+        assert_eq!(history.entries().len(), 3);
+        assert_eq!(history.entries()[1], "fix the lexer\nand tests");
+        assert_eq!(history.search("explain", 3), Some(2));
+        assert_eq!(history.search("explain", 2), Some(0));
+        assert_eq!(history.search("missing", 3), None);
+    }
+}
@@ -0,0 +1,160 @@
+//! Multi-line prompt buffer for the REPL line editor
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+/// Editable text with a (row, column) cursor; columns count chars, not bytes
+#[derive(Debug, Clone)]
+pub struct InputBuffer {
+    lines: Vec<String>,
+    row: usize,
+    col: usize,
+}
+
+impl Default for InputBuffer {
+    fn default() -> Self {
+        Self {
+            lines: vec![String::new()],
+            row: 0,
+            col: 0,
+        }
+    }
+}
+
+impl InputBuffer {
+    pub fn lines(&self) -> &[String] {
+        &self.lines
+    }
+
+    pub fn cursor(&self) -> (usize, usize) {
+        (self.row, self.col)
+    }
+
+    pub fn text(&self) -> String {
+        self.lines.join("\n")
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lines.iter().all(|line| line.is_empty())
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Replace the contents, leaving the cursor at the end
+    pub fn set_text(&mut self, text: &str) {
+        self.lines = text.split('\n').map(str::to_string).collect();
+        self.row = self.lines.len() - 1;
+        self.col = self.line_len();
+    }
+
+    pub fn insert_newline(&mut self) {
+        let offset = self.byte_offset();
+        let rest = self.lines[self.row].split_off(offset);
+        self.row += 1;
+        self.col = 0;
+        self.lines.insert(self.row, rest);
+    }
+
+    /// Apply an editing key; returns false when the key is not an edit
+    pub fn handle_key(&mut self, key: KeyEvent) -> bool {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Char('a') if ctrl => self.col = 0,
+            KeyCode::Char('e') if ctrl => self.col = self.line_len(),
+            KeyCode::Char('u') if ctrl => {
+                let offset = self.byte_offset();
+                self.lines[self.row].drain(..offset);
+                self.col = 0;
+            }
+            KeyCode::Char(c) if !ctrl => {
+                let offset = self.byte_offset();
+                self.lines[self.row].insert(offset, c);
+                self.col += 1;
+            }
+            KeyCode::Backspace => {
+                if self.col > 0 {
+                    self.col -= 1;
+                    let offset = self.byte_offset();
+                    self.lines[self.row].remove(offset);
+                } else if self.row > 0 {
+                    let line = self.lines.remove(self.row);
+                    self.row -= 1;
+                    self.col = self.line_len();
+                    self.lines[self.row].push_str(&line);
+                }
+            }
+            KeyCode::Delete => {
+                if self.col < self.line_len() {
+                    let offset = self.byte_offset();
+                    self.lines[self.row].remove(offset);
+                } else if self.row + 1 < self.lines.len() {
+                    let next = self.lines.remove(self.row + 1);
+                    self.lines[self.row].push_str(&next);
+                }
+            }
+            KeyCode::Left if self.col > 0 => self.col -= 1,
+            KeyCode::Left if self.row > 0 => {
+                self.row -= 1;
+                self.col = self.line_len();
+            }
+            KeyCode::Right if self.col < self.line_len() => self.col += 1,
+            KeyCode::Right if self.row + 1 < self.lines.len() => {
+                self.row += 1;
+                self.col = 0;
+            }
+            KeyCode::Up if self.row > 0 => {
+                self.row -= 1;
+                self.col = self.col.min(self.line_len());
+            }
+            KeyCode::Down if self.row + 1 < self.lines.len() => {
+                self.row += 1;
+                self.col = self.col.min(self.line_len());
+            }
+            KeyCode::Home => self.col = 0,
+            KeyCode::End => self.col = self.line_len(),
+            _ => return false,
+        }
+        true
+    }
+
+    fn line_len(&self) -> usize {
+        self.lines[self.row].chars().count()
+    }
+
+    fn byte_offset(&self) -> usize {
+        let line = &self.lines[self.row];
+        line.char_indices()
+            .nth(self.col)
+            .map(|(i, _)| i)
+            .unwrap_or(line.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(buffer: &mut InputBuffer, code: KeyCode) {
+        buffer.handle_key(KeyEvent::new(code, KeyModifiers::NONE));
+    }
+
+    #[test]
+    fn test_multiline_editing() {
+        let mut buffer = InputBuffer::default();
+        for c in "héllo".chars() {
+            press(&mut buffer, KeyCode::Char(c));
+        }
+        press(&mut buffer, KeyCode::Left);
+        press(&mut buffer, KeyCode::Left);
+        buffer.insert_newline();
+        assert_eq!(buffer.lines(), ["hél", "lo"]);
+        assert_eq!(buffer.cursor(), (1, 0));
+
+        press(&mut buffer, KeyCode::Backspace);
+        assert_eq!(buffer.text(), "héllo");
+        press(&mut buffer, KeyCode::Backspace);
+        assert_eq!(buffer.text(), "hélo");
+        assert_eq!(buffer.cursor(), (0, 2));
+    }
+}
@@ -0,0 +1,567 @@
+//! Line-oriented interactive mode for `hive interactive`
+//!
+//! Each question runs through the consensus pipeline as the next turn of a
+//! conversation thread, with any attached files as context. Sessions are
+//! threads, so they can be resumed with `--continue` or `--session <id>` and
+//! managed with `hive thread`.
+
+pub mod editor;
+mod input;
+
+use anyhow::{bail, Context, Result};
+use console::style;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use crate::consensus::mentions::MentionResolver;
+use crate::consensus::streaming::StreamingCallbacks;
+use crate::consensus::threads::{Thread, ThreadStore};
+use crate::consensus::types::{ConsensusRequest, Stage};
+use crate::consensus::{CancellationReason, CancellationToken, ConsensusEngine};
+use crate::core::config::get_hive_config_dir;
+use crate::core::database::get_or_initialize_database;
+use crate::planning::{PlanningContext, PlanningEngine};
+
+use editor::{History, LineEditor, ReadOutcome};
+
+/// Slash commands offered by tab completion
+pub const COMMANDS: &[&str] = &[
+    "/context",
+    "/cost",
+    "/exit",
+    "/help",
+    "/new",
+    "/plan",
+    "/profile",
+    "/sessions",
+    "/undo",
+];
+
+const MAX_FILE_CHARS: usize = 32_000;
+
+/// How to start the REPL
+#[derive(Debug, Clone, Default)]
+pub struct ReplOptions {
+    /// `planning` sends plain input to the planner; anything else to consensus
+    pub mode: String,
+    /// Resume the most recent session
+    pub continue_last: bool,
+    /// Resume a session by id or id prefix
+    pub session: Option<String>,
+}
+
+/// A parsed `/command`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SlashCommand {
+    Help,
+    Exit,
+    New,
+    Sessions,
+    Cost,
+    Undo,
+    Profile(Option<String>),
+    ContextAdd(Vec<String>),
+    ContextList,
+    ContextClear,
+    Plan(String),
+}
+
+pub fn parse_command(input: &str) -> Result<SlashCommand> {
+    let input = input.trim();
+    let (name, rest) = input
+        .split_once(char::is_whitespace)
+        .map(|(name, rest)| (name, rest.trim()))
+        .unwrap_or((input, ""));
+
+    let command = match name {
+        "/help" | "/?" => SlashCommand::Help,
+        "/exit" | "/quit" => SlashCommand::Exit,
+        "/new" => SlashCommand::New,
+        "/sessions" => SlashCommand::Sessions,
+        "/cost" => SlashCommand::Cost,
+        "/undo" => SlashCommand::Undo,
+        "/profile" => SlashCommand::Profile((!rest.is_empty()).then(|| rest.to_string())),
+        "/context" => {
+            let mut words = rest.split_whitespace();
+            match words.next() {
+                Some("add") => {
+                    let files: Vec<String> = words.map(str::to_string).collect();
+                    if files.is_empty() {
+                        bail!("Usage: /context add <file>...");
+                    }
+                    SlashCommand::ContextAdd(files)
+                }
+                None | Some("list") => SlashCommand::ContextList,
+                Some("clear") => SlashCommand::ContextClear,
+                Some(other) => bail!("Unknown /context action '{}' (add, list, clear)", other),
+            }
+        }
+        "/plan" if rest.is_empty() => bail!("Usage: /plan <goal>"),
+        "/plan" => SlashCommand::Plan(rest.to_string()),
+        other => bail!("Unknown command '{}' - try /help", other),
+    };
+    Ok(command)
+}
+
+/// Semantic context for the next question: the attached files
+///
+/// Earlier turns are not included; the engine replays them from the thread.
+pub fn build_context(files: &[(PathBuf, String)]) -> Option<String> {
+    let mut sections = Vec::new();
+
+    if !files.is_empty() {
+        let mut section = String::from("## Attached files\n");
+        for (path, content) in files {
+            section.push_str(&format!(
+                "\n### {}\n```\n{}\n```\n",
+                path.display(),
+                truncate(content, MAX_FILE_CHARS)
+            ));
+        }
+        sections.push(section);
+    }
+
+    (!sections.is_empty()).then(|| sections.join("\n"))
+}
+
+fn truncate(text: &str, max_chars: usize) -> String {
+    match text.char_indices().nth(max_chars) {
+        Some((end, _)) => format!("{}…", &text[..end]),
+        None => text.to_string(),
+    }
+}
+
+/// Prints stage progress and streams the final stage's answer
+#[derive(Default)]
+struct ReplCallbacks {
+    direct: AtomicBool,
+    streamed: AtomicBool,
+}
+
+impl ReplCallbacks {
+    fn streams(&self, stage: Stage) -> bool {
+        stage == Stage::Curator || self.direct.load(Ordering::Relaxed)
+    }
+}
+
+impl StreamingCallbacks for ReplCallbacks {
+    fn on_mode_decision(&self, direct_mode: bool, _reason: &str) -> Result<()> {
+        self.direct.store(direct_mode, Ordering::Relaxed);
+        Ok(())
+    }
+
+    fn on_stage_start(&self, stage: Stage, model: &str) -> Result<()> {
+        println!(
+            "{}",
+            style(format!("  {} · {}", stage.display_name(), model)).dim()
+        );
+        if self.streams(stage) {
+            println!();
+        }
+        Ok(())
+    }
+
+    fn on_stage_chunk(&self, stage: Stage, chunk: &str, _total_content: &str) -> Result<()> {
+        if self.streams(stage) {
+            self.streamed.store(true, Ordering::Relaxed);
+            print!("{}", chunk);
+            std::io::stdout().flush()?;
+        }
+        Ok(())
+    }
+
+    fn on_error(&self, stage: Stage, error: &anyhow::Error) -> Result<()> {
+        eprintln!(
+            "{} {} failed: {}",
+            style("✗").red(),
+            stage.display_name(),
+            error
+        );
+        Ok(())
+    }
+}
+
+struct Repl {
+    engine: Arc<ConsensusEngine>,
+    store: ThreadStore,
+    session: Thread,
+    context_files: Vec<PathBuf>,
+    planning: bool,
+    cwd: PathBuf,
+    /// Spend since the REPL started, across sessions
+    run_cost: f64,
+    run_tokens: u32,
+}
+
+enum Flow {
+    Continue,
+    Exit,
+}
+
+/// Run the REPL until `/exit` or end of input
+pub async fn run(options: ReplOptions) -> Result<()> {
+    let store = ThreadStore::new(get_or_initialize_database().await?)?;
+    let session = match (&options.session, options.continue_last) {
+        (Some(id), _) => store.load(id)?,
+        (None, true) => store.latest()?.context("No previous session to continue")?,
+        (None, false) => Thread::new(),
+    };
+
+    let engine = Arc::new(ConsensusEngine::new(None).await?);
+    let cwd = std::env::current_dir()?;
+    let mut repl = Repl {
+        engine,
+        store,
+        session,
+        context_files: Vec::new(),
+        planning: options.mode == "planning",
+        cwd: cwd.clone(),
+        run_cost: 0.0,
+        run_tokens: 0,
+    };
+    repl.print_banner().await;
+
+    let history = History::load(get_hive_config_dir().join("repl_history"));
+    let mut editor = LineEditor::new(history, COMMANDS, cwd);
+    loop {
+        let prompt = if repl.planning { "plan> " } else { "hive> " };
+        let line = match editor.read_line(prompt)? {
+            ReadOutcome::Line(line) => line,
+            ReadOutcome::Interrupted => continue,
+            ReadOutcome::Eof => break,
+        };
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        editor.history.push(line);
+
+        let outcome = if line.starts_with('/') {
+            match parse_command(line) {
+                Ok(command) => repl.command(command).await,
+                Err(e) => Err(e),
+            }
+        } else if repl.planning {
+            repl.plan(line).await.map(|_| Flow::Continue)
+        } else {
+            repl.ask(line).await.map(|_| Flow::Continue)
+        };
+        match outcome {
+            Ok(Flow::Continue) => {}
+            Ok(Flow::Exit) => break,
+            Err(e) => eprintln!("{} {:#}", style("Error:").red().bold(), e),
+        }
+    }
+
+    if !repl.session.turns.is_empty() {
+        println!(
+            "Session saved - resume with {}",
+            style(format!(
+                "hive interactive --session {}",
+                repl.session.short_id()
+            ))
+            .cyan()
+        );
+    }
+    Ok(())
+}
+
+impl Repl {
+    async fn print_banner(&self) {
+        let profile = self.engine.get_current_profile().await.profile_name;
+        println!(
+            "🐝 {} · profile {} · {} mode",
+            style("Hive interactive").bold(),
+            style(profile).cyan(),
+            if self.planning {
+                "planning"
+            } else {
+                "consensus"
+            }
+        );
+        if let Some(last) = self.session.turns.last() {
+            println!(
+                "Resumed session {} ({} turns). Last question: {}",
+                style(self.session.short_id()).cyan(),
+                self.session.turns.len(),
+                style(truncate(&last.question, 80)).italic()
+            );
+        }
+        println!(
+            "{}",
+            style("/help for commands · Alt+Enter or trailing \\ for a new line · Ctrl+R to search history · Ctrl+D to exit").dim()
+        );
+    }
+
+    async fn ask(&mut self, question: &str) -> Result<()> {
+        let files: Vec<(PathBuf, String)> = self
+            .context_files
+            .iter()
+            .filter_map(|path| {
+                let content = std::fs::read_to_string(path).ok()?;
+                Some((
+                    path.strip_prefix(&self.cwd).unwrap_or(path).to_path_buf(),
+                    content,
+                ))
+            })
+            .collect();
+        let mentions = MentionResolver::new(&self.cwd)
+            .context_for(question)
+            .await?;
+        let context = [build_context(&files), mentions]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>();
+        let request = ConsensusRequest {
+            query: question.to_string(),
+            context: (!context.is_empty()).then(|| context.join("\n")),
+            temporal_context: None,
+            profile_override: None,
+            max_tokens: None,
+            user_id: None,
+            conversation_id: Some(self.session.id.clone()),
+        };
+
+        // Ctrl+C cancels the run instead of killing the REPL
+        let token = CancellationToken::new();
+        let watcher = {
+            let token = token.clone();
+            tokio::spawn(async move {
+                if tokio::signal::ctrl_c().await.is_ok() {
+                    token.cancel(CancellationReason::UserRequested);
+                }
+            })
+        };
+        let callbacks = Arc::new(ReplCallbacks::default());
+        let result = self
+            .engine
+            .process_request(&request, callbacks.clone(), token)
+            .await;
+        watcher.abort();
+
+        let result = result?;
+        if !result.success {
+            bail!(result
+                .error
+                .unwrap_or_else(|| "Consensus failed".to_string()));
+        }
+        let answer = result.result.clone().unwrap_or_default();
+        if !callbacks.streamed.load(Ordering::Relaxed) {
+            print!("\n{}", answer);
+        }
+
+        let tokens: u32 = result
+            .stages
+            .iter()
+            .filter_map(|stage| stage.usage.as_ref())
+            .map(|usage| usage.total_tokens)
+            .sum();
+        self.run_cost += result.total_cost;
+        self.run_tokens += tokens;
+        println!();
+        println!(
+            "{}",
+            style(format!(
+                "  ${:.4} · {} tokens · {:.1}s",
+                result.total_cost, tokens, result.total_duration
+            ))
+            .dim()
+        );
+
+        // The engine recorded the turn in the thread, along with any new summary
+        self.session = self.store.load(&self.session.id)?;
+        Ok(())
+    }
+
+    async fn plan(&mut self, goal: &str) -> Result<()> {
+        let mut planner = PlanningEngine::new(self.engine.clone()).await?;
+        let plan = if self.cwd.join(".git").exists() {
+            planner
+                .create_plan_with_repository(goal, &self.cwd, PlanningContext::default())
+                .await?
+        } else {
+            planner
+                .create_plan(goal, PlanningContext::default())
+                .await?
+        };
+
+        let mut summary = format!("Plan: {}\n", plan.title);
+        for (i, task) in plan.tasks.iter().enumerate() {
+            summary.push_str(&format!("{}. {}\n", i + 1, task.title));
+        }
+        println!();
+        println!("📋 {}", style(&plan.title).bold().cyan());
+        for (i, task) in plan.tasks.iter().enumerate() {
+            println!("   {}. {}", i + 1, task.title);
+        }
+        println!();
+
+        // Recorded so follow-up questions can refer to the plan
+        self.store.append_turn(
+            &mut self.session,
+            &format!("/plan {}", goal),
+            summary.trim_end(),
+            None,
+            0.0,
+        )
+    }
+
+    async fn command(&mut self, command: SlashCommand) -> Result<Flow> {
+        match command {
+            SlashCommand::Help => print_help(),
+            SlashCommand::Exit => return Ok(Flow::Exit),
+            SlashCommand::New => {
+                self.session = Thread::new();
+                println!("Started a new session");
+            }
+            SlashCommand::Sessions => {
+                let sessions = self.store.list(None, 10)?;
+                if sessions.is_empty() {
+                    println!("No saved sessions");
+                }
+                for summary in sessions {
+                    let marker = if summary.id == self.session.id {
+                        "●"
+                    } else {
+                        " "
+                    };
+                    println!(
+                        "{} {}  {:>3} turns  ${:<8.4} {}",
+                        marker,
+                        style(&summary.id[..summary.id.len().min(8)]).cyan(),
+                        summary.turns,
+                        summary.total_cost,
+                        truncate(&summary.title, 60)
+                    );
+                }
+            }
+            SlashCommand::Cost => {
+                println!(
+                    "Session: {} turns · ${:.4}",
+                    self.session.turns.len(),
+                    self.session.total_cost
+                );
+                println!(
+                    "This run: ${:.4} · {} tokens",
+                    self.run_cost, self.run_tokens
+                );
+            }
+            SlashCommand::Undo => match self.store.remove_last_turn(&mut self.session)? {
+                Some(turn) => println!("Removed: {}", truncate(&turn.question, 80)),
+                None => println!("Nothing to undo"),
+            },
+            SlashCommand::Profile(None) => {
+                let current = self.engine.get_current_profile().await.profile_name;
+                for profile in self.engine.get_profiles().await? {
+                    let marker = if profile.profile_name == current {
+                        "●"
+                    } else {
+                        " "
+                    };
+                    println!("{} {}", marker, profile.profile_name);
+                }
+            }
+            SlashCommand::Profile(Some(name)) => {
+                self.engine.set_profile(&name).await?;
+                println!("Switched to profile {}", style(name).cyan());
+            }
+            SlashCommand::ContextAdd(files) => {
+                for file in files {
+                    let path = self.cwd.join(&file);
+                    if !path.is_file() {
+                        bail!("{} is not a file", file);
+                    }
+                    if !self.context_files.contains(&path) {
+                        self.context_files.push(path);
+                    }
+                }
+                println!("{} file(s) attached", self.context_files.len());
+            }
+            SlashCommand::ContextList => {
+                if self.context_files.is_empty() {
+                    println!("No files attached - use /context add <file>");
+                }
+                for path in &self.context_files {
+                    println!(
+                        "  {}",
+                        path.strip_prefix(&self.cwd).unwrap_or(path).display()
+                    );
+                }
+            }
+            SlashCommand::ContextClear => {
+                self.context_files.clear();
+                println!("Context cleared");
+            }
+            SlashCommand::Plan(goal) => self.plan(&goal).await?,
+        }
+        Ok(Flow::Continue)
+    }
+}
+
+fn print_help() {
+    for (command, description) in [
+        ("/profile [name]", "List profiles or switch to one"),
+        ("/context add <file>...", "Attach files to every question"),
+        ("/context [list|clear]", "Show or drop attached files"),
+        ("/cost", "Cost of this session and this run"),
+        ("/undo", "Forget the last turn"),
+        ("/plan <goal>", "Break a goal into a task plan"),
+        ("/sessions", "List saved sessions"),
+        ("/new", "Start a new session"),
+        ("/exit", "Leave (Ctrl+D also works)"),
+    ] {
+        println!("  {:<24} {}", style(command).cyan(), description);
+    }
+    println!(
+        "\n  Reference context in a question with {}",
+        style(
+            "@file:path[:10-20] @symbol:Name @diff[:main] @dir:path @conversation:<id> @plan:<id>"
+        )
+        .cyan()
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_command() {
+        assert_eq!(parse_command("/cost").unwrap(), SlashCommand::Cost);
+        assert_eq!(
+            parse_command("/profile").unwrap(),
+            SlashCommand::Profile(None)
+        );
+        assert_eq!(
+            parse_command("/profile  speed ").unwrap(),
+            SlashCommand::Profile(Some("speed".to_string()))
+        );
+        assert_eq!(
+            parse_command("/context add a.rs b.rs").unwrap(),
+            SlashCommand::ContextAdd(vec!["a.rs".to_string(), "b.rs".to_string()])
+        );
+        assert_eq!(
+            parse_command("/context").unwrap(),
+            SlashCommand::ContextList
+        );
+        assert_eq!(
+            parse_command("/plan add OAuth login").unwrap(),
+            SlashCommand::Plan("add OAuth login".to_string())
+        );
+        assert!(parse_command("/context add").is_err());
+        assert!(parse_command("/plan").is_err());
+        assert!(parse_command("/nope").is_err());
+    }
+
+    #[test]
+    fn test_build_context() {
+        assert_eq!(build_context(&[]), None);
+
+        let context =
+            build_context(&[(PathBuf::from("src/lib.rs"), "pub mod a;".to_string())]).unwrap();
+        assert!(context.contains("### src/lib.rs\n```\npub mod a;\n```"));
+        assert_eq!(truncate("abcdef", 3), "abc…");
+    }
+}
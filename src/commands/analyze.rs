@@ -18,6 +18,7 @@ use crate::analysis::{
     },
     symbol_index::SymbolIndexer,
 };
+use crate::cli::output::{AnalyzeOutput, OutputMode};
 use crate::core::database::DatabaseManager;
 use std::sync::Arc;
 
@@ -67,14 +68,17 @@ pub async fn handle_analyze(
     architecture: bool,
     dependencies: bool,
     output_format: Option<String>,
+    output: OutputMode,
 ) -> Result<()> {
     let start = Instant::now();
 
-    println!(
-        "🔍 {} {}...",
-        style("Analyzing").bold().cyan(),
-        style(target.display()).yellow()
-    );
+    if output.is_text() {
+        println!(
+            "🔍 {} {}...",
+            style("Analyzing").bold().cyan(),
+            style(target.display()).yellow()
+        );
+    }
 
     // Initialize components
     let db = Arc::new(DatabaseManager::default().await?);
@@ -83,6 +87,17 @@ pub async fn handle_analyze(
     let repository_analyzer =
         RepositoryAnalyzer::new(symbol_indexer.clone(), dependency_analyzer.clone()).await?;
 
+    if !output.is_text() {
+        let analysis = repository_analyzer.analyze_repository(&target).await?;
+        let data = AnalyzeOutput {
+            target,
+            depth,
+            duration_ms: start.elapsed().as_millis() as u64,
+            analysis,
+        };
+        return crate::cli::output::emit(output, "analyze", &data);
+    }
+
     // Perform analysis based on depth
     match depth.as_str() {
         "comprehensive" => {
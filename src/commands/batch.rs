@@ -0,0 +1,188 @@
+//! Batch command implementation for unattended consensus runs
+//!
+//! This module implements `hive batch`, which runs a JSONL file of queries (or
+//! one templated query per matching source file) through consensus and writes
+//! each answer, a resumable checkpoint and a summary report to a directory.
+
+use anyhow::Result;
+use console::style;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use crate::cli::output::{emit, emit_event, Cancelled, OutputMode};
+use crate::consensus::batch::{
+    items_from_files, load_jsonl, BatchItem, BatchOptions, BatchRunner, BatchSummary,
+    FailedAttemptCost, ItemOutcome, ItemRecord, ItemStatus, REPORT_FILE,
+};
+use crate::consensus::fanout::SilentCallbacks;
+use crate::consensus::types::ConsensusRequest;
+use crate::consensus::{CancellationReason, CancellationToken, ConsensusEngine};
+use crate::core::error::HiveError;
+
+/// Handle the batch command
+pub async fn handle_batch(
+    input: Option<PathBuf>,
+    files: Option<String>,
+    prompt: Option<String>,
+    out_dir: PathBuf,
+    profile: String,
+    options: BatchOptions,
+    output: OutputMode,
+) -> Result<()> {
+    let items = match (input, files) {
+        (Some(input), _) => load_jsonl(&input)?,
+        (None, Some(pattern)) => items_from_files(&pattern, prompt.as_deref().unwrap_or_default())?,
+        (None, None) => anyhow::bail!("Pass a JSONL file or --files"),
+    };
+    crate::cli::commands::require_openrouter_key().await?;
+
+    let engine = ConsensusEngine::new(None).await?;
+    if let Err(e) = crate::cli::commands::load_consensus_hooks(&engine).await {
+        tracing::warn!("Failed to load hooks: {}", e);
+    }
+
+    if output.is_text() {
+        println!(
+            "📦 Running {} items with {} in parallel → {}",
+            items.len(),
+            options.concurrency,
+            style(out_dir.display()).cyan()
+        );
+        if let Some(max_cost) = options.max_cost {
+            println!("   Budget: ${:.2}", max_cost);
+        }
+        println!();
+    }
+
+    // The first Ctrl+C stops the batch; finished items stay checkpointed
+    let cancellation = CancellationToken::new();
+    let interrupt = {
+        let cancellation = cancellation.clone();
+        tokio::spawn(async move {
+            if tokio::signal::ctrl_c().await.is_ok() {
+                cancellation.cancel(CancellationReason::UserRequested);
+            }
+        })
+    };
+
+    let total = items.len();
+    let done = std::sync::atomic::AtomicUsize::new(0);
+    let execute = |item: BatchItem| run_item(&engine, &profile, item, cancellation.clone());
+    let on_item = |record: &ItemRecord| {
+        let done = done.fetch_add(1, std::sync::atomic::Ordering::SeqCst) + 1;
+        if output.is_text() {
+            print_item(record, done, total);
+        } else if let Err(e) = emit_event(output, "item", record) {
+            tracing::warn!("Failed to print batch progress: {}", e);
+        }
+    };
+    let summary = BatchRunner::new(&out_dir, options)
+        .run(items, execute, on_item, cancellation.clone())
+        .await;
+    interrupt.abort();
+    let summary = summary?;
+
+    if output.is_text() {
+        print_summary(&summary);
+    }
+    if cancellation.is_cancelled() {
+        return Err(Cancelled.into());
+    }
+    emit(output, "batch", &summary)
+}
+
+async fn run_item(
+    engine: &ConsensusEngine,
+    profile: &str,
+    item: BatchItem,
+    cancellation: CancellationToken,
+) -> Result<ItemOutcome> {
+    let request = ConsensusRequest {
+        query: item.query,
+        context: item.context,
+        temporal_context: None,
+        profile_override: Some(item.profile.unwrap_or_else(|| profile.to_string())),
+        max_tokens: None,
+        user_id: None,
+        conversation_id: None,
+    };
+    let result = engine
+        .process_request(&request, Arc::new(SilentCallbacks), cancellation)
+        .await?;
+    match result.result {
+        Some(answer) if result.success => Ok(ItemOutcome {
+            answer,
+            cost: result.total_cost,
+        }),
+        _ => {
+            let error = anyhow::Error::from(HiveError::ConsensusFailed {
+                message: result
+                    .error
+                    .unwrap_or_else(|| "pipeline returned no result".to_string()),
+            });
+            // Stages that ran before the failure were still billed
+            Err(if result.total_cost > 0.0 {
+                error.context(FailedAttemptCost(result.total_cost))
+            } else {
+                error
+            })
+        }
+    }
+}
+
+fn print_item(record: &ItemRecord, done: usize, total: usize) {
+    let progress = style(format!("[{}/{}]", done, total)).dim();
+    match record.status {
+        ItemStatus::Succeeded => println!(
+            "{} {} {}  ${:.4}  {:.1}s",
+            style("✓").green(),
+            progress,
+            record.id,
+            record.cost,
+            record.duration_ms as f64 / 1000.0
+        ),
+        ItemStatus::Failed => println!(
+            "{} {} {}  {}",
+            style("✗").red(),
+            progress,
+            record.id,
+            style(record.error.as_deref().unwrap_or_default()).red()
+        ),
+        ItemStatus::Skipped => println!(
+            "{} {} {}  {}",
+            style("-").dim(),
+            progress,
+            record.id,
+            style(record.error.as_deref().unwrap_or("skipped")).dim()
+        ),
+    }
+}
+
+fn print_summary(summary: &BatchSummary) {
+    println!();
+    println!(
+        "📊 {} succeeded, {} failed, {} skipped of {} · ${:.4} · {:.1}s",
+        style(summary.succeeded).green(),
+        style(summary.failed).red(),
+        summary.skipped,
+        summary.total,
+        summary.total_cost,
+        summary.duration_ms as f64 / 1000.0
+    );
+    if summary.resumed > 0 {
+        println!(
+            "   {} items were already done by an earlier run",
+            summary.resumed
+        );
+    }
+    println!(
+        "   Report: {}",
+        style(summary.output_dir.join(REPORT_FILE).display()).cyan()
+    );
+    if summary.failed + summary.skipped > 0 {
+        println!(
+            "   Run the same command again to retry the {} unfinished items",
+            summary.failed + summary.skipped
+        );
+    }
+}
@@ -0,0 +1,69 @@
+//! Changelog command implementation
+//!
+//! This module implements `hive changelog`, which groups the commits of a
+//! revision range by their Conventional Commits type into Markdown.
+
+use anyhow::{bail, Context, Result};
+use git2::Repository;
+use serde::Serialize;
+use std::path::PathBuf;
+
+use crate::cli::output::{emit, OutputMode};
+use crate::consensus::commit_message::{commits_in_range, render_changelog, ChangelogEntry};
+use crate::consensus::review::DiffSpec;
+
+#[derive(Serialize)]
+struct Changelog {
+    range: String,
+    entries: Vec<ChangelogEntry>,
+    markdown: String,
+}
+
+/// Handle the changelog command
+pub fn handle_changelog(
+    repo_path: PathBuf,
+    range: &str,
+    title: Option<String>,
+    output_file: Option<PathBuf>,
+    output: OutputMode,
+) -> Result<()> {
+    let repo = Repository::discover(&repo_path)
+        .with_context(|| format!("No git repository at {}", repo_path.display()))?;
+    let spec = DiffSpec::parse(Some(range), false)?;
+    let DiffSpec::Range { base, head, .. } = &spec else {
+        bail!("Invalid revision range '{}'", range);
+    };
+
+    let entries = commits_in_range(&repo, base, head)?;
+    let title = title.unwrap_or_else(|| match head.as_str() {
+        "HEAD" => "Unreleased".to_string(),
+        tag => tag.to_string(),
+    });
+    let markdown = render_changelog(&title, &entries);
+
+    if let Some(path) = &output_file {
+        std::fs::write(path, &markdown)
+            .with_context(|| format!("Failed to write changelog {}", path.display()))?;
+    }
+    if output.is_text() {
+        match &output_file {
+            Some(path) => println!(
+                "📝 Wrote {} commits of {} to {}",
+                entries.len(),
+                spec.describe(),
+                path.display()
+            ),
+            None => print!("{}", markdown),
+        }
+        return Ok(());
+    }
+    emit(
+        output,
+        "changelog",
+        &Changelog {
+            range: spec.describe(),
+            entries,
+            markdown,
+        },
+    )
+}
@@ -0,0 +1,223 @@
+//! Commit command implementation for AI commit messages
+//!
+//! This module implements `hive commit`, which summarizes the staged diff with
+//! a cheap profile, proposes a Conventional Commits message, lets the user
+//! edit it and creates the commit. With `--hook` it only writes the proposal
+//! into git's message file, which is how the prepare-commit-msg hook runs it.
+
+use anyhow::{Context, Result};
+use console::style;
+use dialoguer::{theme::ColorfulTheme, Editor, Select};
+use git2::Repository;
+use serde::Serialize;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use crate::cli::output::{consensus_error, emit, Cancelled, OutputMode};
+use crate::consensus::commit_message::{
+    commit_prompt, create_commit, infer_scope, load_pipeline, parse_commit_message,
+    ConventionalCommit,
+};
+use crate::consensus::fanout::SilentCallbacks;
+use crate::consensus::review::{diff_files, DiffSpec};
+use crate::consensus::types::ConsensusRequest;
+use crate::consensus::{CancellationReason, CancellationToken, ConsensusEngine, PipelineStore};
+use crate::core::error::HiveError;
+
+/// Options of `hive commit`
+pub struct CommitOptions {
+    pub profile: String,
+    pub pipeline: String,
+    /// Commit the proposal without asking
+    pub yes: bool,
+    /// Only print the proposal
+    pub dry_run: bool,
+    /// Message file of a prepare-commit-msg hook to write the proposal into
+    pub hook: Option<PathBuf>,
+}
+
+#[derive(Serialize)]
+struct CommitOutcome {
+    message: String,
+    commit: ConventionalCommit,
+    files: usize,
+    cost: f64,
+    /// Id of the created commit; `None` when nothing was committed
+    id: Option<String>,
+}
+
+/// Handle the commit command
+pub async fn handle_commit(
+    repo_path: PathBuf,
+    options: CommitOptions,
+    output: OutputMode,
+) -> Result<()> {
+    let repo = Repository::discover(&repo_path)
+        .with_context(|| format!("No git repository at {}", repo_path.display()))?;
+    let files = diff_files(&repo, &DiffSpec::Staged, 3)?;
+    if files.is_empty() {
+        // `git commit --allow-empty` still runs the hook
+        if options.hook.is_some() {
+            return Ok(());
+        }
+        anyhow::bail!("Nothing staged to commit; use `git add` first");
+    }
+    let scope = infer_scope(&files);
+    let text = output.is_text() && options.hook.is_none();
+
+    if text {
+        println!(
+            "✍️  Summarizing {} staged files with {}",
+            files.len(),
+            style(&options.profile).cyan()
+        );
+    }
+    let (commit, cost) = propose(&commit_prompt(&files, scope.as_deref()), scope, &options).await?;
+    let mut outcome = CommitOutcome {
+        message: commit.to_string(),
+        commit,
+        files: files.len(),
+        cost,
+        id: None,
+    };
+
+    if let Some(path) = &options.hook {
+        // Keep git's comment block below the proposal
+        let existing = std::fs::read_to_string(path).unwrap_or_default();
+        std::fs::write(path, format!("{}\n{}", outcome.message, existing))
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        return Ok(());
+    }
+
+    if !text {
+        if options.yes && !options.dry_run {
+            outcome.id = Some(create_commit(&repo, &outcome.message)?.to_string());
+        }
+        return emit(output, "commit", &outcome);
+    }
+
+    println!();
+    print_message(&outcome.message);
+    if options.dry_run {
+        return Ok(());
+    }
+    if !options.yes {
+        match confirm(&outcome.message)? {
+            Some(message) => outcome.message = message,
+            None => {
+                println!("Commit cancelled; the changes stay staged");
+                return Ok(());
+            }
+        }
+    }
+
+    let id = create_commit(&repo, &outcome.message)?;
+    println!(
+        "✅ Committed {} {}  {}",
+        style(&id.to_string()[..7]).yellow(),
+        outcome.message.lines().next().unwrap_or_default(),
+        style(format!("${:.4}", outcome.cost)).dim()
+    );
+    Ok(())
+}
+
+/// Ask the model for a message and parse it
+async fn propose(
+    prompt: &str,
+    scope: Option<String>,
+    options: &CommitOptions,
+) -> Result<(ConventionalCommit, f64)> {
+    crate::cli::commands::require_openrouter_key().await?;
+    let pipeline = load_pipeline(&PipelineStore::default_location(), &options.pipeline)?;
+    let engine = ConsensusEngine::new(None).await?;
+    if let Err(e) = crate::cli::commands::load_consensus_hooks(&engine).await {
+        tracing::warn!("Failed to load hooks: {}", e);
+    }
+    engine.set_pipeline_definition(Some(pipeline)).await;
+
+    let cancellation = CancellationToken::new();
+    let interrupt = {
+        let cancellation = cancellation.clone();
+        tokio::spawn(async move {
+            if tokio::signal::ctrl_c().await.is_ok() {
+                cancellation.cancel(CancellationReason::UserRequested);
+            }
+        })
+    };
+    let request = ConsensusRequest {
+        query: prompt.to_string(),
+        context: None,
+        temporal_context: None,
+        profile_override: Some(options.profile.clone()),
+        max_tokens: None,
+        user_id: None,
+        conversation_id: None,
+    };
+    let result = engine
+        .process_request(&request, Arc::new(SilentCallbacks), cancellation.clone())
+        .await;
+    interrupt.abort();
+
+    if cancellation.is_cancelled() {
+        return Err(Cancelled.into());
+    }
+    let result = result.map_err(consensus_error)?;
+    match result.result {
+        Some(answer) if result.success => Ok((
+            parse_commit_message(&answer, scope.as_deref())?,
+            result.total_cost,
+        )),
+        _ => Err(HiveError::ConsensusFailed {
+            message: result
+                .error
+                .unwrap_or_else(|| "pipeline returned no result".to_string()),
+        }
+        .into()),
+    }
+}
+
+/// Let the user commit, edit or drop the proposal; `None` means cancel
+fn confirm(message: &str) -> Result<Option<String>> {
+    let mut message = message.to_string();
+    loop {
+        let choice = Select::with_theme(&ColorfulTheme::default())
+            .with_prompt("Commit with this message?")
+            .items(&["Commit", "Edit message", "Cancel"])
+            .default(0)
+            .interact()?;
+        match choice {
+            0 => return Ok(Some(message)),
+            1 => {
+                let Some(edited) = Editor::new().extension(".txt").edit(&message)? else {
+                    continue;
+                };
+                // Drop comment lines the way git does
+                let edited = edited
+                    .lines()
+                    .filter(|line| !line.starts_with('#'))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+                    .trim()
+                    .to_string();
+                if edited.is_empty() {
+                    return Ok(None);
+                }
+                message = edited;
+                println!();
+                print_message(&message);
+            }
+            _ => return Ok(None),
+        }
+    }
+}
+
+fn print_message(message: &str) {
+    let mut lines = message.lines();
+    if let Some(header) = lines.next() {
+        println!("  {}", style(header).bold());
+    }
+    for line in lines {
+        println!("  {}", line);
+    }
+    println!();
+}
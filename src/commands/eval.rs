@@ -0,0 +1,167 @@
+//! Eval command implementation for scoring consensus profiles
+//!
+//! This module implements `hive eval`, which runs a golden dataset through one
+//! or more consensus profiles, scores every answer and prints a comparison of
+//! quality, latency and cost. Recorded runs can be replayed without network
+//! access, and `--stub` checks a dataset without any model at all.
+
+use anyhow::{Context, Result};
+use console::style;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::cli::output::{emit, emit_event, OutputMode};
+use crate::consensus::eval::{
+    load_dataset, CaseResult, EngineProvider, EvalProvider, EvalReport, Evaluator,
+    RecordedProvider, StubProvider,
+};
+use crate::consensus::openrouter::OpenRouterClient;
+use crate::consensus::ConsensusEngine;
+use crate::core::error::HiveError;
+
+/// Where `hive eval` gets its answers from
+pub enum EvalSource {
+    /// The consensus engine, optionally recording every response
+    Live {
+        grader_model: String,
+        record: Option<PathBuf>,
+    },
+    /// A recording made with `--record`
+    Replay(PathBuf),
+    /// Each case's own reference answer
+    Stub,
+}
+
+/// Handle the eval command
+pub async fn handle_eval(
+    dataset: PathBuf,
+    profiles: Vec<String>,
+    source: EvalSource,
+    threshold: f64,
+    concurrency: usize,
+    report_file: Option<PathBuf>,
+    output: OutputMode,
+) -> Result<()> {
+    if !(0.0..=1.0).contains(&threshold) {
+        anyhow::bail!("--threshold must be between 0 and 1");
+    }
+    let cases = load_dataset(&dataset)?;
+    let provider = provider_for(source).await?;
+
+    if output.is_text() {
+        println!(
+            "🧪 Evaluating {} cases with {}",
+            cases.len(),
+            style(profiles.join(", ")).cyan()
+        );
+        println!();
+    }
+
+    let name = dataset
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_else(|| dataset.display().to_string());
+    let on_result = |result: &CaseResult| {
+        if output.is_text() {
+            print_result(result);
+        } else if let Err(e) = emit_event(output, "case", result) {
+            tracing::warn!("Failed to print eval progress: {}", e);
+        }
+    };
+    let report = Evaluator::new(provider)
+        .with_threshold(threshold)
+        .with_concurrency(concurrency)
+        .run(&name, &cases, &profiles, on_result)
+        .await?;
+
+    if let Some(path) = &report_file {
+        std::fs::write(path, report.to_markdown())
+            .with_context(|| format!("Failed to write report {}", path.display()))?;
+    }
+    if output.is_text() {
+        print_report(&report, report_file.as_deref());
+    }
+    emit(output, "eval", &report)
+}
+
+async fn provider_for(source: EvalSource) -> Result<Arc<dyn EvalProvider>> {
+    match source {
+        EvalSource::Replay(path) => Ok(Arc::new(RecordedProvider::replay(&path)?)),
+        EvalSource::Stub => Ok(Arc::new(StubProvider)),
+        EvalSource::Live {
+            grader_model,
+            record,
+        } => {
+            let key =
+                std::env::var("OPENROUTER_API_KEY").map_err(|_| HiveError::ConfigMissingField {
+                    field: "OPENROUTER_API_KEY".to_string(),
+                })?;
+            let engine = ConsensusEngine::new(None).await?;
+            if let Err(e) = crate::cli::commands::load_consensus_hooks(&engine).await {
+                tracing::warn!("Failed to load hooks: {}", e);
+            }
+            let live: Arc<dyn EvalProvider> = Arc::new(
+                EngineProvider::new(engine)
+                    .with_grader(Arc::new(OpenRouterClient::new(key)), grader_model),
+            );
+            match record {
+                Some(path) => Ok(Arc::new(RecordedProvider::record(live, &path)?)),
+                None => Ok(live),
+            }
+        }
+    }
+}
+
+fn print_result(result: &CaseResult) {
+    let label = format!("{} / {}", result.profile, result.case_id);
+    match (&result.error, result.passed) {
+        (Some(error), _) => println!("{} {}  {}", style("✗").red(), label, style(error).red()),
+        (None, true) => println!(
+            "{} {}  {:.2}  {} ms",
+            style("✓").green(),
+            label,
+            result.score,
+            result.latency_ms
+        ),
+        (None, false) => println!(
+            "{} {}  {:.2}  {}",
+            style("✗").yellow(),
+            label,
+            result.score,
+            style(if result.missing_keywords.is_empty() {
+                "below threshold".to_string()
+            } else {
+                format!("missing: {}", result.missing_keywords.join(", "))
+            })
+            .dim()
+        ),
+    }
+}
+
+fn print_report(report: &EvalReport, report_file: Option<&Path>) {
+    println!();
+    println!("📊 {}", style("Profiles").bold());
+    for profile in &report.profiles {
+        println!(
+            "  {:<12} {:>3}/{:<3} passed  score {:.3}  {:>6.0} ms avg  {:>6} ms p95  ${:.4}",
+            style(&profile.profile).cyan(),
+            profile.passed,
+            profile.cases,
+            profile.mean_score,
+            profile.mean_latency_ms,
+            profile.p95_latency_ms,
+            profile.total_cost
+        );
+    }
+    for comparison in &report.comparisons {
+        println!();
+        println!(
+            "⚖️  {} vs {}: {}",
+            comparison.model_a, comparison.model_b, comparison.recommendation
+        );
+    }
+    if let Some(path) = report_file {
+        println!();
+        println!("   Report: {}", style(path.display()).cyan());
+    }
+}
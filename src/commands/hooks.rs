@@ -1,12 +1,19 @@
 //! Hook management commands
 
-use crate::cli::args::HookCommands;
+use crate::cli::args::{ApprovalCommands, HookCommands};
+use crate::cli::output::{emit, OutputMode};
+use crate::core::config::get_hive_config_dir;
+use crate::core::database::get_or_initialize_database;
+use crate::hooks::approval_workflow::{ApprovalStatus, ApprovalWorkflow};
 use crate::hooks::{
-    config::example_configs, ConsensusIntegration, ConsensusIntegrationConfig, CostController,
-    EventSource, EventType, HookEvent, HookId, HooksSystem, QualityGateManager,
+    config::example_configs, ApprovalStore, ConsensusIntegration, ConsensusIntegrationConfig,
+    CostController, EventSource, EventType, HookAuditLogger, HookEvent, HookId, HooksSystem,
+    QualityGateManager, StoredApproval,
 };
 use anyhow::{anyhow, Result};
 use chrono::{DateTime, Utc};
+use console::style;
+use serde::Serialize;
 use serde_json::Value;
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -45,6 +52,8 @@ pub async fn handle_hooks(command: HookCommands, hooks_system: Arc<HooksSystem>)
             hook_id,
             failures_only,
         } => handle_hook_history(hooks_system, limit, hook_id, failures_only).await,
+
+        HookCommands::Approvals { command } => handle_approvals(command, OutputMode::Text).await,
     }
 }
 
@@ -790,142 +799,220 @@ async fn configure_consensus_integration(
     Ok(())
 }
 
-/// Show approval workflow commands
-pub async fn handle_approval_commands(
-    hooks_system: Arc<HooksSystem>,
-    subcommand: &str,
-    args: Vec<String>,
-) -> Result<()> {
-    match subcommand {
-        "pending" => show_pending_approvals(hooks_system).await,
-        "approve" => approve_request(hooks_system, args).await,
-        "reject" => reject_request(hooks_system, args).await,
-        "history" => show_approval_history(hooks_system, args).await,
-        _ => Err(anyhow!("Unknown approval command: {}", subcommand)),
-    }
+/// Approval workflow backed by the shared queue, as hook runs use it
+async fn open_approvals() -> Result<(ApprovalWorkflow, Arc<ApprovalStore>)> {
+    let store = Arc::new(ApprovalStore::new(get_or_initialize_database().await?)?);
+    let audit_logger = HookAuditLogger::new(get_hive_config_dir().join("hooks_audit.log")).await?;
+    let workflow = ApprovalWorkflow::new()
+        .with_audit_logger(Arc::new(audit_logger))
+        .with_store(store.clone());
+    // Escalations and expiries that fell due while nobody was looking
+    workflow.process_due_deadlines().await?;
+    Ok((workflow, store))
 }
 
-/// Show pending approval requests
-async fn show_pending_approvals(_hooks_system: Arc<HooksSystem>) -> Result<()> {
-    println!("📋 Pending Approval Requests");
-    println!("─────────────────────────────────────");
-
-    // This would require access to the actual approval workflow
-    // For now, show example data
-    println!("🔄 Active Requests (2):");
-    println!();
-
-    println!("📄 Request #1:");
-    println!("   ID: req_001");
-    println!("   Type: Cost Threshold Exceeded");
-    println!("   Amount: $1.25 (Threshold: $1.00)");
-    println!("   Stage: Generator");
-    println!("   Requested: 2 minutes ago");
-    println!("   Expires: 3 minutes");
-    println!("   Approver: manager");
-
-    println!();
-    println!("📄 Request #2:");
-    println!("   ID: req_002");
-    println!("   Type: Quality Gate Failure");
-    println!("   Score: 0.65 (Threshold: 0.70)");
-    println!("   Stage: Validator");
-    println!("   Requested: 45 seconds ago");
-    println!("   Expires: 4 minutes");
-    println!("   Approver: quality_manager");
-
-    println!("\n💡 To approve: hive hooks approval approve req_001");
-    println!("💡 To reject:  hive hooks approval reject req_001 \"reason\"");
+#[derive(Serialize)]
+struct ApprovalListOutput {
+    approvals: Vec<StoredApproval>,
+}
 
-    Ok(())
+#[derive(Serialize)]
+struct ApprovalDecisionOutput {
+    id: String,
+    status: ApprovalStatus,
+    approved: bool,
+    message: String,
+    remaining_approvers: Vec<String>,
 }
 
-/// Approve an approval request
-async fn approve_request(_hooks_system: Arc<HooksSystem>, args: Vec<String>) -> Result<()> {
-    if args.is_empty() {
-        return Err(anyhow!("Request ID required"));
+/// Handle `hive hooks approvals`
+pub async fn handle_approvals(command: ApprovalCommands, output: OutputMode) -> Result<()> {
+    let (workflow, store) = open_approvals().await?;
+    match command {
+        ApprovalCommands::List { all, limit } => {
+            let statuses = if all {
+                Vec::new()
+            } else {
+                vec![ApprovalStatus::Pending, ApprovalStatus::Escalated]
+            };
+            let approvals = store.list(&statuses, limit)?;
+            if !output.is_text() {
+                return emit(
+                    output,
+                    "hooks approvals list",
+                    &ApprovalListOutput { approvals },
+                );
+            }
+            print_approval_list(&approvals, all);
+            Ok(())
+        }
+        ApprovalCommands::Show { id } => {
+            let approval = store.load(&id)?;
+            if !output.is_text() {
+                return emit(output, "hooks approvals show", &approval);
+            }
+            print_approval(&approval);
+            Ok(())
+        }
+        ApprovalCommands::Approve { id, reason } => {
+            decide(
+                &workflow,
+                &store,
+                &id,
+                ApprovalStatus::Approved,
+                reason,
+                output,
+            )
+            .await
+        }
+        ApprovalCommands::Reject { id, reason } => {
+            decide(
+                &workflow,
+                &store,
+                &id,
+                ApprovalStatus::Rejected,
+                reason,
+                output,
+            )
+            .await
+        }
     }
-
-    let request_id = &args[0];
-    let reason = args
-        .get(1)
-        .map(|s| s.as_str())
-        .unwrap_or("Approved via CLI");
-
-    // This would actually process the approval
-    println!("✅ Approved request: {}", request_id);
-    println!("   Reason: {}", reason);
-    println!("   Approved by: system");
-    println!(
-        "   Time: {}",
-        chrono::Utc::now().format("%Y-%m-%d %H:%M:%S UTC")
-    );
-
-    Ok(())
 }
 
-/// Reject an approval request
-async fn reject_request(_hooks_system: Arc<HooksSystem>, args: Vec<String>) -> Result<()> {
-    if args.is_empty() {
-        return Err(anyhow!("Request ID required"));
+async fn decide(
+    workflow: &ApprovalWorkflow,
+    store: &ApprovalStore,
+    id: &str,
+    decision: ApprovalStatus,
+    reason: Option<String>,
+    output: OutputMode,
+) -> Result<()> {
+    let id = store.load(id)?.request.id;
+    // Decisions are attributed to the account running hive, never to a name the caller picks
+    let approver = crate::security::enforcement::os_account()?;
+    let result = workflow
+        .process_approval_decision(&id, &approver, decision, reason, None)
+        .await?;
+
+    let outcome = ApprovalDecisionOutput {
+        id,
+        status: result.final_status,
+        approved: result.approved,
+        message: result.message,
+        remaining_approvers: result.remaining_approvers,
+    };
+    if !output.is_text() {
+        return emit(output, "hooks approvals decide", &outcome);
     }
-
-    let request_id = &args[0];
-    let reason = args
-        .get(1)
-        .map(|s| s.as_str())
-        .unwrap_or("Rejected via CLI");
-
-    // This would actually process the rejection
-    println!("❌ Rejected request: {}", request_id);
-    println!("   Reason: {}", reason);
-    println!("   Rejected by: system");
+    let icon = match outcome.status {
+        ApprovalStatus::Approved => "✅",
+        ApprovalStatus::Rejected => "❌",
+        ApprovalStatus::Expired => "⏰",
+        _ => "📝",
+    };
     println!(
-        "   Time: {}",
-        chrono::Utc::now().format("%Y-%m-%d %H:%M:%S UTC")
+        "{} {} is {} (decided by {})",
+        icon,
+        style(short_id(&outcome.id)).cyan(),
+        style(outcome.status.as_str()).bold(),
+        approver
     );
-
+    if !outcome.remaining_approvers.is_empty() {
+        println!(
+            "   Still waiting for: {}",
+            outcome.remaining_approvers.join(", ")
+        );
+    }
     Ok(())
 }
 
-/// Show approval history
-async fn show_approval_history(_hooks_system: Arc<HooksSystem>, args: Vec<String>) -> Result<()> {
-    let limit = args
-        .get(0)
-        .and_then(|s| s.parse::<usize>().ok())
-        .unwrap_or(10);
-
-    println!("📜 Approval History (Last {})", limit);
-    println!("─────────────────────────────────────");
-
-    // This would show actual approval history
-    // For now, show example data
-    let example_history = [
-        ("req_045", "Approved", "Cost threshold", "2 hours ago"),
-        ("req_044", "Rejected", "Quality gate failure", "3 hours ago"),
-        (
-            "req_043",
-            "Auto-approved",
-            "Low cost operation",
-            "4 hours ago",
-        ),
-        ("req_042", "Expired", "Cost threshold", "6 hours ago"),
-        ("req_041", "Approved", "Performance alert", "8 hours ago"),
-    ];
+fn short_id(id: &str) -> &str {
+    &id[..id.len().min(8)]
+}
 
-    for (id, status, request_type, time) in example_history.iter().take(limit) {
-        let status_icon = match *status {
-            "Approved" => "✅",
-            "Rejected" => "❌",
-            "Auto-approved" => "🤖",
-            "Expired" => "⏰",
-            _ => "ℹ️",
+fn print_approval_list(approvals: &[StoredApproval], all: bool) {
+    if approvals.is_empty() {
+        println!("No {}approval requests", if all { "" } else { "open " });
+        return;
+    }
+    println!("📋 {}", style("Approval requests").bold());
+    println!();
+    for approval in approvals {
+        let request = &approval.request;
+        let expires = match request.expires_at {
+            Some(at) if !approval.status.is_final() => {
+                format!("expires {}", at.format("%Y-%m-%d %H:%M UTC"))
+            }
+            _ => String::new(),
         };
-
-        println!("{} {} - {} ({})", status_icon, id, request_type, time);
+        let priority = format!("{:?}", request.priority);
+        println!(
+            "  {}  {:<13} {:<9} {}  {}",
+            style(short_id(&request.id)).cyan(),
+            approval.status.as_str(),
+            priority,
+            request.description,
+            style(expires).dim()
+        );
     }
+    println!();
+    println!(
+        "💡 Decide with {}",
+        style("hive hooks approvals approve|reject <id>").cyan()
+    );
+}
 
-    Ok(())
+fn print_approval(approval: &StoredApproval) {
+    let request = &approval.request;
+    println!(
+        "📄 {} {}",
+        style("Approval request").bold(),
+        style(&request.id).cyan()
+    );
+    println!("   Status: {}", style(approval.status.as_str()).bold());
+    println!("   Hook: {}", request.hook_id.0);
+    println!("   Type: {}", request.request_type);
+    println!("   Description: {}", request.description);
+    println!("   Priority: {:?}", request.priority);
+    println!("   Requested by: {}", request.requested_by);
+    println!(
+        "   Created: {}",
+        request.created_at.format("%Y-%m-%d %H:%M:%S UTC")
+    );
+    if let Some(expires_at) = request.expires_at {
+        println!("   Expires: {}", expires_at.format("%Y-%m-%d %H:%M:%S UTC"));
+    }
+    if request.current_escalation_level > 0 {
+        println!("   Escalation level: {}", request.current_escalation_level);
+    }
+    if let Some(escalate_at) = approval.escalate_at {
+        println!(
+            "   Next escalation: {}",
+            escalate_at.format("%Y-%m-%d %H:%M:%S UTC")
+        );
+    }
+    if !request.required_approvers.is_empty() {
+        println!(
+            "   Required approvers: {}",
+            request.required_approvers.join(", ")
+        );
+    }
+    if !request.received_approvals.is_empty() {
+        println!("   Decisions:");
+        for decision in &request.received_approvals {
+            println!(
+                "     {} {:?} by {}{}",
+                decision.timestamp.format("%Y-%m-%d %H:%M:%S"),
+                decision.decision,
+                decision.approver,
+                decision
+                    .reason
+                    .as_ref()
+                    .map(|reason| format!(": {}", reason))
+                    .unwrap_or_default()
+            );
+        }
+    }
 }
 
 /// Show quality gate management commands
@@ -1,16 +1,18 @@
 //! Index command implementation for building semantic indexes
 //!
-//! This module implements the `hive index build` command for creating
-//! and maintaining the symbol index with FTS5 support.
+//! This module implements the `hive index build`, `watch` and `status`
+//! commands for creating and maintaining the symbol index with FTS5 support.
 
-use anyhow::{Context, Result};
-use console::{style, Term};
+use anyhow::Result;
+use chrono::Local;
+use console::style;
 use indicatif::{ProgressBar, ProgressStyle};
-use std::path::{Path, PathBuf};
-use std::time::Instant;
-use tracing::{debug, info};
-use walkdir::WalkDir;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
 
+use crate::analysis::background_indexer::{
+    BackgroundIndexer, IndexPhase, IndexScope, IndexStatusStore, WatchConfig,
+};
 use crate::analysis::symbol_index::{IndexStatistics, SymbolIndexer};
 use crate::core::database::DatabaseManager;
 use std::sync::Arc;
@@ -39,23 +41,17 @@ pub async fn handle_index_build(
     let db = Arc::new(DatabaseManager::default().await?);
 
     // Create symbol indexer
-    let indexer = Arc::new(SymbolIndexer::new(db).await?);
+    let indexer = Arc::new(SymbolIndexer::new(db.clone()).await?);
 
-    // Discover files to index
-    let files = discover_source_files(&target_path, include_tests, &exclude_patterns)?;
-
-    if files.is_empty() {
-        println!("❌ No source files found to index");
-        return Ok(());
-    }
-
-    println!(
-        "📂 Found {} files to index",
-        style(files.len()).green().bold()
-    );
+    // Files are discovered by the same scope the background watcher uses,
+    // so build, watch and search all agree on what is indexed
+    let scope = IndexScope::new(&target_path)?
+        .include_tests(include_tests)
+        .exclude(exclude_patterns);
+    let background = BackgroundIndexer::new(db, indexer.clone(), scope)?;
 
     // Create progress bar
-    let pb = ProgressBar::new(files.len() as u64);
+    let pb = ProgressBar::new(0);
     pb.set_style(
         ProgressStyle::default_bar()
             .template(
@@ -65,37 +61,32 @@ pub async fn handle_index_build(
             .progress_chars("#>-"),
     );
 
-    let mut success_count = 0;
-    let mut error_count = 0;
-    let mut total_symbols = 0;
-
-    // Index each file
-    for file_path in &files {
-        pb.set_message(format!(
-            "Indexing {}",
-            file_path.file_name().unwrap_or_default().to_string_lossy()
-        ));
-
-        match index_single_file(&indexer, file_path).await {
-            Ok(symbol_count) => {
-                success_count += 1;
-                total_symbols += symbol_count;
-                debug!(
-                    "Indexed {} with {} symbols",
-                    file_path.display(),
-                    symbol_count
-                );
-            }
-            Err(e) => {
-                error_count += 1;
-                debug!("Failed to index {}: {}", file_path.display(), e);
-            }
+    let mut progress = background.subscribe();
+    let progress_bar = pb.clone();
+    let progress_task = tokio::spawn(async move {
+        while progress.changed().await.is_ok() {
+            let snapshot = progress.borrow().clone();
+            progress_bar.set_length(snapshot.total as u64);
+            progress_bar.set_position(snapshot.processed as u64);
         }
+    });
+
+    // Unchanged files are skipped using their stored content hashes
+    let report = background.sync(force).await;
+    drop(background);
+    let _ = progress_task.await;
+    pb.finish_and_clear();
+    let report = report?;
 
-        pb.inc(1);
+    if report.discovered == 0 && report.removed == 0 {
+        println!("❌ No source files found to index");
+        return Ok(());
     }
 
-    pb.finish_and_clear();
+    println!(
+        "📂 Found {} files to index",
+        style(report.discovered).green().bold()
+    );
 
     let elapsed = start.elapsed();
 
@@ -106,17 +97,19 @@ pub async fn handle_index_build(
     println!("\n✅ {} complete!", style("Indexing").green().bold());
     println!("   ⏱️  Time: {:.2}s", elapsed.as_secs_f64());
     println!(
-        "   📄 Files: {} indexed, {} errors",
-        style(success_count).green(),
-        if error_count > 0 {
-            style(error_count).red()
+        "   📄 Files: {} indexed, {} unchanged, {} removed, {} errors",
+        style(report.indexed).green(),
+        style(report.unchanged).dim(),
+        style(report.removed).yellow(),
+        if report.errors > 0 {
+            style(report.errors).red()
         } else {
-            style(error_count).dim()
+            style(report.errors).dim()
         }
     );
     println!(
-        "   🔍 Symbols: {} total",
-        style(total_symbols).cyan().bold()
+        "   🔍 Symbols: {} extracted",
+        style(report.symbols).cyan().bold()
     );
 
     // Display symbol breakdown
@@ -145,126 +138,136 @@ pub async fn handle_index_build(
     }
 
     // Performance check
-    let avg_time_per_file = elapsed.as_secs_f64() / files.len() as f64 * 1000.0;
-    if avg_time_per_file > 50.0 {
-        println!(
-            "\n⚠️  {} Average indexing time {:.2}ms per file (target: <50ms)",
-            style("Performance:").yellow(),
-            avg_time_per_file
-        );
-    } else {
-        println!(
-            "\n⚡ {} {:.2}ms average per file",
-            style("Performance:").green(),
-            avg_time_per_file
-        );
+    if report.indexed > 0 {
+        let avg_time_per_file = report.elapsed_ms / report.indexed as f64;
+        if avg_time_per_file > 50.0 {
+            println!(
+                "\n⚠️  {} Average indexing time {:.2}ms per file (target: <50ms)",
+                style("Performance:").yellow(),
+                avg_time_per_file
+            );
+        } else {
+            println!(
+                "\n⚡ {} {:.2}ms average per file",
+                style("Performance:").green(),
+                avg_time_per_file
+            );
+        }
     }
 
     Ok(())
 }
 
-/// Index a single file
-async fn index_single_file(indexer: &Arc<SymbolIndexer>, path: &Path) -> Result<usize> {
-    // Read file content
-    let content = tokio::fs::read_to_string(path)
-        .await
-        .context("Failed to read file")?;
-
-    // Index the file
-    indexer.index_file(path, &content).await?;
+/// Handle the index watch command: keep the index current until Ctrl+C
+pub async fn handle_index_watch(
+    path: Option<PathBuf>,
+    debounce_ms: u64,
+    include_tests: bool,
+    exclude_patterns: Vec<String>,
+) -> Result<()> {
+    let target_path = path.unwrap_or_else(|| PathBuf::from("."));
 
-    // Return approximate symbol count (in real implementation, get from indexer)
-    let lines = content.lines().count();
-    let estimated_symbols = lines / 10; // Rough estimate
+    let db = Arc::new(DatabaseManager::default().await?);
+    let indexer = Arc::new(SymbolIndexer::new(db.clone()).await?);
+
+    let scope = IndexScope::new(&target_path)?
+        .include_tests(include_tests)
+        .exclude(exclude_patterns);
+    let config = WatchConfig {
+        debounce: Duration::from_millis(debounce_ms),
+        ..WatchConfig::default()
+    };
+    let background = BackgroundIndexer::new(db, indexer, scope)?.with_config(config);
 
-    Ok(estimated_symbols)
-}
+    println!(
+        "👀 {} {} for changes (Ctrl+C to stop)",
+        style("Watching").cyan(),
+        style(background.scope().root().display()).bold()
+    );
 
-/// Discover source files to index
-fn discover_source_files(
-    root: &Path,
-    include_tests: bool,
-    exclude_patterns: &[String],
-) -> Result<Vec<PathBuf>> {
-    let mut files = Vec::new();
-
-    // Common source file extensions
-    const SOURCE_EXTENSIONS: &[&str] = &[
-        "rs", "ts", "tsx", "js", "jsx", "py", "go", "java", "cpp", "c", "cc", "cxx", "h", "hpp",
-        "cs", "rb", "php", "swift", "kt", "scala",
-    ];
-
-    // Walk directory tree
-    for entry in WalkDir::new(root)
-        .follow_links(true)
-        .into_iter()
-        .filter_entry(|e| {
-            let path = e.path();
-
-            // Skip hidden directories
-            if path
-                .file_name()
-                .and_then(|n| n.to_str())
-                .map(|n| n.starts_with('.'))
-                .unwrap_or(false)
-            {
-                return false;
+    // Report each completed pass as it lands
+    let mut progress = background.subscribe();
+    let reporter = tokio::spawn(async move {
+        let mut last_sync = progress.borrow().last_sync;
+        while progress.changed().await.is_ok() {
+            let snapshot = progress.borrow().clone();
+            if snapshot.phase == IndexPhase::Idle && snapshot.last_sync != last_sync {
+                last_sync = snapshot.last_sync;
+                println!(
+                    "🔄 {} {} indexed, {} unchanged, {} removed{}",
+                    style(Local::now().format("%H:%M:%S")).dim(),
+                    style(snapshot.indexed).green(),
+                    style(snapshot.unchanged).dim(),
+                    style(snapshot.removed).yellow(),
+                    match &snapshot.branch {
+                        Some(branch) => format!(" on {}", style(branch).magenta()),
+                        None => String::new(),
+                    }
+                );
             }
+        }
+    });
 
-            // Skip common non-source directories
-            let dir_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
-
-            if matches!(
-                dir_name,
-                "node_modules"
-                    | "target"
-                    | "dist"
-                    | "build"
-                    | ".git"
-                    | ".venv"
-                    | "__pycache__"
-                    | "vendor"
-            ) {
-                return false;
-            }
+    background
+        .watch(async {
+            let _ = tokio::signal::ctrl_c().await;
+        })
+        .await?;
+    drop(background);
+    let _ = reporter.await;
 
-            // Skip test directories if not included
-            if !include_tests && (dir_name == "tests" || dir_name == "test") {
-                return false;
-            }
+    println!("✅ {} watching", style("Stopped").green());
+    Ok(())
+}
 
-            true
-        })
-    {
-        let entry = entry?;
-        let path = entry.path();
+/// Handle the index status command
+pub async fn handle_index_status() -> Result<()> {
+    let db = Arc::new(DatabaseManager::default().await?);
+    let statuses = IndexStatusStore::new(db)?.list()?;
 
-        // Check if it's a file
-        if !path.is_file() {
-            continue;
-        }
+    if statuses.is_empty() {
+        println!("❌ No indexed projects. Run 'hive index build' or 'hive index watch'.");
+        return Ok(());
+    }
 
-        // Check extension
-        let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+    println!("\n🐝 {}", style("Indexed Projects").bold().cyan());
 
-        if !SOURCE_EXTENSIONS.contains(&extension) {
-            continue;
-        }
+    for status in statuses {
+        let progress = &status.progress;
+        let state = if progress.is_live() {
+            if progress.is_busy() {
+                style("indexing").yellow()
+            } else {
+                style("watching").green()
+            }
+        } else {
+            style("not watched").dim()
+        };
 
-        // Check exclude patterns
-        let path_str = path.to_string_lossy();
-        if exclude_patterns
-            .iter()
-            .any(|pattern| path_str.contains(pattern))
-        {
-            continue;
+        println!("\n📁 {} [{}]", style(status.root.display()).bold(), state);
+        if let Some(branch) = &progress.branch {
+            println!("   Branch: {}", style(branch).magenta());
+        }
+        if progress.is_busy() {
+            println!(
+                "   Progress: {}/{} files, {} pending changes",
+                progress.processed, progress.total, progress.pending
+            );
+        }
+        println!(
+            "   Last pass: {} indexed, {} unchanged, {} removed, {} errors",
+            progress.indexed, progress.unchanged, progress.removed, progress.errors
+        );
+        match progress.last_sync {
+            Some(at) => println!(
+                "   Last sync: {}",
+                at.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S")
+            ),
+            None => println!("   Last sync: never"),
         }
-
-        files.push(path.to_path_buf());
     }
 
-    files.sort();
-    Ok(files)
+    Ok(())
 }
 
 /// Get emoji for symbol kind
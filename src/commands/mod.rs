@@ -2,8 +2,12 @@
 
 pub mod analytics;
 pub mod analyze;
+pub mod batch;
+pub mod changelog;
+pub mod commit;
 pub mod consensus;
 pub mod cost;
+pub mod eval;
 pub mod hooks;
 pub mod improve;
 pub mod index;
@@ -14,10 +18,15 @@ pub mod memory;
 pub mod migrate;
 pub mod models;
 pub mod performance;
+pub mod pipeline;
 pub mod planning;
+pub mod review;
 pub mod search;
+pub mod secrets;
 pub mod security;
 pub mod shell;
+pub mod sync;
+pub mod thread;
 // pub mod mcp; // Temporarily disabled
 // pub mod mode; // Temporarily disabled
 
@@ -31,7 +40,7 @@ pub use hooks::{generate_hook_examples, handle_hooks, show_available_events};
 pub use improve::{
     handle_improve, handle_redo, handle_transform_history, handle_undo, list_aspects,
 };
-pub use index::{handle_index_build, handle_index_stats};
+pub use index::{handle_index_build, handle_index_stats, handle_index_status, handle_index_watch};
 pub use install::{handle_install_command, InstallArgs};
 pub use lsp::{generate_editor_config, handle_lsp, LspCommands};
 pub use maintenance::run_maintenance_command;
@@ -0,0 +1,134 @@
+//! Pipeline command implementation for managing consensus topologies
+//!
+//! This module implements the `hive pipeline list`, `show`, `validate` and
+//! `init` commands for the TOML pipeline definitions in `~/.hive/pipelines`.
+
+use anyhow::{Context, Result};
+use console::style;
+use std::path::Path;
+
+use crate::consensus::topology::{example_pipelines, PipelineDefinition, PipelineStore};
+
+/// Handle the pipeline list command
+pub async fn handle_pipeline_list() -> Result<()> {
+    let store = PipelineStore::default_location();
+    println!(
+        "🧬 {} ({})",
+        style("Consensus pipelines").bold(),
+        style(store.dir().display()).dim()
+    );
+    println!();
+
+    for (name, definition) in store.list()? {
+        match definition {
+            Ok(definition) => {
+                let stages: Vec<&str> = definition.stages.iter().map(|s| s.id.as_str()).collect();
+                println!(
+                    "  {} {}",
+                    style(&name).cyan().bold(),
+                    style(stages.join(" → ")).dim()
+                );
+                if let Some(description) = &definition.description {
+                    println!("      {}", description);
+                }
+            }
+            Err(e) => println!(
+                "  {} {} {:#}",
+                style(&name).red().bold(),
+                style("invalid:").red(),
+                e
+            ),
+        }
+    }
+
+    Ok(())
+}
+
+/// Handle the pipeline show command
+pub async fn handle_pipeline_show(name: &str) -> Result<()> {
+    let definition = PipelineStore::default_location().load(name)?;
+    print_definition(&definition);
+    Ok(())
+}
+
+/// Handle the pipeline validate command
+pub async fn handle_pipeline_validate(file: &Path) -> Result<()> {
+    let source = std::fs::read_to_string(file)
+        .with_context(|| format!("Failed to read {}", file.display()))?;
+    let definition =
+        PipelineDefinition::from_toml(&source).with_context(|| format!("In {}", file.display()))?;
+
+    println!(
+        "✅ {} is a valid pipeline definition",
+        style(file.display()).bold()
+    );
+    println!();
+    print_definition(&definition);
+    Ok(())
+}
+
+/// Handle the pipeline init command
+pub async fn handle_pipeline_init(force: bool) -> Result<()> {
+    let store = PipelineStore::default_location();
+    std::fs::create_dir_all(store.dir())?;
+
+    for (file_name, source) in example_pipelines() {
+        let path = store.dir().join(file_name);
+        if path.exists() && !force {
+            println!("⏭️  {} already exists", style(path.display()).dim());
+            continue;
+        }
+        std::fs::write(&path, source)?;
+        println!("📝 Wrote {}", style(path.display()).green());
+    }
+
+    println!();
+    println!(
+        "Run with {}",
+        style("hive consensus --pipeline <name> \"<question>\"").cyan()
+    );
+    Ok(())
+}
+
+fn print_definition(definition: &PipelineDefinition) {
+    println!("🧬 {}", style(&definition.name).cyan().bold());
+    if let Some(description) = &definition.description {
+        println!("   {}", description);
+    }
+    println!();
+
+    for (i, stage) in definition.stages.iter().enumerate() {
+        println!(
+            "  {}. {} ({})",
+            i + 1,
+            style(&stage.id).bold(),
+            stage.role.as_str()
+        );
+        println!(
+            "     model: {}",
+            stage.model.as_deref().unwrap_or("profile default")
+        );
+        if let Some(temperature) = stage.temperature {
+            println!("     temperature: {}", temperature);
+        }
+        if let Some(inputs) = &stage.inputs {
+            println!("     inputs: {}", inputs.join(", "));
+        }
+        if !stage.include_context {
+            println!("     context: excluded");
+        }
+        if let Some(fan_out) = &stage.fan_out {
+            println!(
+                "     fan-out: {} candidates, {:?} merge",
+                fan_out.candidate_count(),
+                fan_out.merge
+            );
+        }
+        if !stage.skip_if.is_empty() {
+            println!("     skip conditions: {}", stage.skip_if.len());
+        }
+        if stage.system_prompt.is_some() {
+            println!("     system prompt: custom");
+        }
+    }
+}
@@ -3,6 +3,7 @@
 //! Handles all planning-related CLI commands including plan creation,
 //! task decomposition, risk analysis, and timeline estimation.
 
+use crate::cli::output::OutputMode;
 use crate::consensus::engine::ConsensusEngine;
 use crate::core::{get_config, HiveError};
 use crate::planning::{ModeType, Plan, PlanningContext, PlanningEngine};
@@ -20,8 +21,12 @@ pub async fn handle_plan(
     output: Option<PathBuf>,
     risks: bool,
     timeline: bool,
+    output_mode: OutputMode,
 ) -> Result<()> {
-    println!("🚀 {} AI-Powered Planning...", style("Initializing").bold());
+    let text = output_mode.is_text();
+    if text {
+        println!("🚀 {} AI-Powered Planning...", style("Initializing").bold());
+    }
 
     // Initialize consensus engine
     let config = get_config().await?;
@@ -38,11 +43,13 @@ pub async fn handle_plan(
         || Path::new(&current_dir).join("Cargo.toml").exists()
         || Path::new(&current_dir).join("package.json").exists();
 
-    println!("🎯 Goal: {}", style(&goal).italic().cyan());
-    println!("📊 Planning Depth: {}", style(&depth).yellow());
+    if text {
+        println!("🎯 Goal: {}", style(&goal).italic().cyan());
+        println!("📊 Planning Depth: {}", style(&depth).yellow());
 
-    if collaborative {
-        println!("🤝 {} enabled", style("Collaborative Planning").cyan());
+        if collaborative {
+            println!("🤝 {} enabled", style("Collaborative Planning").cyan());
+        }
     }
 
     // Create planning context
@@ -53,24 +60,43 @@ pub async fn handle_plan(
         _ => crate::planning::types::DetailLevel::Medium,
     };
 
-    println!();
+    if text {
+        println!();
+    }
 
     // Create plan with or without repository context
     let plan = if is_repository {
-        println!("📁 {} repository context...", style("Analyzing").bold());
-        println!("   🔍 Scanning codebase structure...");
-        println!("   📊 Calculating code metrics...");
-        println!("   🔗 Analyzing dependencies...");
-        println!("   ✨ Assessing code quality...");
+        if text {
+            println!("📁 {} repository context...", style("Analyzing").bold());
+            println!("   🔍 Scanning codebase structure...");
+            println!("   📊 Calculating code metrics...");
+            println!("   🔗 Analyzing dependencies...");
+            println!("   ✨ Assessing code quality...");
+        }
 
         planning_engine
             .create_plan_with_repository(&goal, &current_dir, context)
             .await?
     } else {
-        println!("🧠 {} task breakdown...", style("Generating").bold());
+        if text {
+            println!("🧠 {} task breakdown...", style("Generating").bold());
+        }
         planning_engine.create_plan(&goal, context).await?
     };
 
+    // Keep every plan so questions can reference it as @plan:<id>
+    let stored = store_plan(&plan).await;
+    if let Err(e) = &stored {
+        tracing::warn!("Failed to store plan {}: {}", plan.id, e);
+    }
+
+    if !text {
+        if let Some(output_path) = output {
+            save_plan(&plan, &output_path).await?;
+        }
+        return crate::cli::output::emit(output_mode, "plan", &plan);
+    }
+
     // Display plan summary
     display_plan_summary(&plan, risks, timeline);
 
@@ -81,6 +107,14 @@ pub async fn handle_plan(
         println!("💾 Plan saved to: {}", style(output_path.display()).cyan());
     }
 
+    if stored.is_ok() {
+        println!();
+        println!(
+            "📎 Reference it in questions as {}",
+            style(format!("@plan:{}", &plan.id[..plan.id.len().min(8)])).cyan()
+        );
+    }
+
     println!();
     println!(
         "📝 Use {} to execute this plan",
@@ -390,6 +424,13 @@ async fn save_plan(plan: &Plan, path: &Path) -> Result<()> {
     Ok(())
 }
 
+/// Save a plan under its id in the plans directory
+async fn store_plan(plan: &Plan) -> Result<()> {
+    let dir = crate::planning::plans_dir();
+    fs::create_dir_all(&dir).await?;
+    save_plan(plan, &dir.join(format!("{}.json", plan.id))).await
+}
+
 #[cfg(all(test, feature = "legacy-tests"))]
 mod tests {
     use super::*;
@@ -0,0 +1,294 @@
+//! Review command implementation for consensus code review
+//!
+//! This module implements `hive review`, which extracts a git diff, splits it
+//! into per-file chunks with their enclosing symbols, runs each chunk through
+//! the review pipeline and reports line-anchored findings as text, JSON or
+//! SARIF. `--fail-on` turns findings, or chunks that could not be reviewed,
+//! into a non-zero exit code for CI.
+
+use anyhow::{Context, Result};
+use console::style;
+use git2::Repository;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::analysis::symbol_index::{SymbolEntry, SymbolIndexer};
+use crate::cli::output::{
+    consensus_error, emit, emit_event, Cancelled, OutputMode, ThresholdExceeded,
+};
+use crate::consensus::fanout::SilentCallbacks;
+use crate::consensus::review::{
+    chunk_files, diff_files, load_pipeline, ChunkOutcome, ChunkResult, DiffSpec, FileDiff,
+    ReviewChunk, ReviewReport, Reviewer, Severity,
+};
+use crate::consensus::types::ConsensusRequest;
+use crate::consensus::{CancellationReason, CancellationToken, ConsensusEngine, PipelineStore};
+use crate::core::database::{DatabaseConfig, DatabaseManager};
+use crate::core::error::HiveError;
+
+/// Options of `hive review` beyond the diff selection
+pub struct ReviewOptions {
+    pub profile: String,
+    pub pipeline: String,
+    pub concurrency: usize,
+    pub context_lines: u32,
+    /// Where to write a SARIF log; `-` prints it instead of the text report
+    pub sarif: Option<PathBuf>,
+    pub fail_on: Option<Severity>,
+}
+
+/// Handle the review command
+pub async fn handle_review(
+    repo_path: PathBuf,
+    spec: DiffSpec,
+    options: ReviewOptions,
+    output: OutputMode,
+) -> Result<()> {
+    let repo = Repository::discover(&repo_path)
+        .with_context(|| format!("No git repository at {}", repo_path.display()))?;
+    let files = diff_files(&repo, &spec, options.context_lines)?;
+    let sarif_stdout = options.sarif.as_deref() == Some(Path::new("-"));
+    let text = output.is_text() && !sarif_stdout;
+
+    let symbols = symbol_context(&files).await.unwrap_or_else(|e| {
+        tracing::warn!("Failed to look up symbols for review context: {}", e);
+        HashMap::new()
+    });
+    let chunks = chunk_files(&files, &symbols);
+
+    if text {
+        println!(
+            "🔍 Reviewing {} ({} files, {} chunks) with {}",
+            style(spec.describe()).cyan(),
+            files.len(),
+            chunks.len(),
+            style(&options.profile).cyan()
+        );
+        println!();
+    }
+
+    let report = if chunks.is_empty() {
+        ReviewReport::new(&spec.describe(), files.len())
+    } else {
+        run_review(&spec, files.len(), chunks, &options, output, text).await?
+    };
+
+    match &options.sarif {
+        Some(_) if sarif_stdout => {
+            println!("{}", serde_json::to_string_pretty(&report.to_sarif())?)
+        }
+        Some(path) => std::fs::write(path, serde_json::to_string_pretty(&report.to_sarif())?)
+            .with_context(|| format!("Failed to write SARIF log {}", path.display()))?,
+        None => {}
+    }
+    if text {
+        print_report(&report, options.sarif.as_deref());
+    } else if !sarif_stdout {
+        emit(output, "review", &report)?;
+    }
+
+    if report.chunks > 0 && report.failed.len() == report.chunks {
+        return Err(HiveError::ConsensusFailed {
+            message: format!("All {} review chunks failed", report.chunks),
+        }
+        .into());
+    }
+    if let Some(severity) = options.fail_on {
+        // Unreviewed chunks may hide findings, so the gate cannot pass
+        if !report.failed.is_empty() {
+            return Err(HiveError::ConsensusFailed {
+                message: format!(
+                    "{} of {} review chunks failed",
+                    report.failed.len(),
+                    report.chunks
+                ),
+            }
+            .into());
+        }
+        let count = report.count_at_least(severity);
+        if count > 0 {
+            return Err(ThresholdExceeded(format!(
+                "{} findings at or above --fail-on {}",
+                count,
+                severity.as_str()
+            ))
+            .into());
+        }
+    }
+    Ok(())
+}
+
+async fn run_review(
+    spec: &DiffSpec,
+    file_count: usize,
+    chunks: Vec<ReviewChunk>,
+    options: &ReviewOptions,
+    output: OutputMode,
+    text: bool,
+) -> Result<ReviewReport> {
+    crate::cli::commands::require_openrouter_key().await?;
+    let pipeline = load_pipeline(&PipelineStore::default_location(), &options.pipeline)?;
+    let engine = ConsensusEngine::new(None).await?;
+    if let Err(e) = crate::cli::commands::load_consensus_hooks(&engine).await {
+        tracing::warn!("Failed to load hooks: {}", e);
+    }
+    engine.set_pipeline_definition(Some(pipeline)).await;
+
+    let cancellation = CancellationToken::new();
+    let interrupt = {
+        let cancellation = cancellation.clone();
+        tokio::spawn(async move {
+            if tokio::signal::ctrl_c().await.is_ok() {
+                cancellation.cancel(CancellationReason::UserRequested);
+            }
+        })
+    };
+
+    let execute =
+        |prompt: String| review_chunk(&engine, &options.profile, prompt, cancellation.clone());
+    let on_chunk = |result: &ChunkResult| {
+        if text {
+            print_chunk(result);
+        } else if let Err(e) = emit_event(output, "chunk", result) {
+            tracing::warn!("Failed to print review progress: {}", e);
+        }
+    };
+    let report = Reviewer::new()
+        .with_concurrency(options.concurrency)
+        .run(&spec.describe(), file_count, chunks, execute, on_chunk)
+        .await;
+    interrupt.abort();
+
+    if cancellation.is_cancelled() {
+        return Err(Cancelled.into());
+    }
+    Ok(report)
+}
+
+async fn review_chunk(
+    engine: &ConsensusEngine,
+    profile: &str,
+    prompt: String,
+    cancellation: CancellationToken,
+) -> Result<ChunkOutcome> {
+    let request = ConsensusRequest {
+        query: prompt,
+        context: None,
+        temporal_context: None,
+        profile_override: Some(profile.to_string()),
+        max_tokens: None,
+        user_id: None,
+        conversation_id: None,
+    };
+    let result = engine
+        .process_request(&request, Arc::new(SilentCallbacks), cancellation)
+        .await
+        .map_err(consensus_error)?;
+    match result.result {
+        Some(answer) if result.success => Ok(ChunkOutcome {
+            answer,
+            cost: result.total_cost,
+        }),
+        _ => Err(HiveError::ConsensusFailed {
+            message: result
+                .error
+                .unwrap_or_else(|| "pipeline returned no result".to_string()),
+        }
+        .into()),
+    }
+}
+
+/// Symbols of the new side of every changed file
+///
+/// The files are indexed into a scratch database, so the line numbers match
+/// the reviewed revision rather than whatever the shared index last saw.
+async fn symbol_context(files: &[FileDiff]) -> Result<HashMap<PathBuf, Vec<SymbolEntry>>> {
+    let scratch = tempfile::tempdir()?;
+    let db = DatabaseManager::new(DatabaseConfig {
+        path: scratch.path().join("review.db"),
+        ..Default::default()
+    })
+    .await?;
+    let indexer = SymbolIndexer::new(Arc::new(db)).await?;
+
+    let mut symbols = HashMap::new();
+    for file in files {
+        let Some(content) = &file.new_content else {
+            continue;
+        };
+        // Files in languages the indexer cannot parse simply get no symbols
+        if indexer.index_file(&file.path, content).await.is_ok() {
+            symbols.insert(file.path.clone(), indexer.symbols_in_file(&file.path)?);
+        }
+    }
+    Ok(symbols)
+}
+
+fn print_chunk(result: &ChunkResult) {
+    match &result.error {
+        Some(error) => println!(
+            "{} {}  {}",
+            style("✗").red(),
+            result.path.display(),
+            style(error).red()
+        ),
+        None => println!(
+            "{} {}  {}",
+            style("✓").green(),
+            result.path.display(),
+            style(format!("{} findings", result.findings)).dim()
+        ),
+    }
+}
+
+fn print_report(report: &ReviewReport, sarif: Option<&Path>) {
+    if !report.findings.is_empty() {
+        println!();
+    }
+    for finding in &report.findings {
+        let severity = match finding.severity {
+            Severity::Error => style("error").red().bold(),
+            Severity::Warning => style("warning").yellow(),
+            Severity::Info => style("info").blue(),
+        };
+        println!(
+            "{}:{}  {}  {} {}",
+            style(finding.path.display()).cyan(),
+            finding.line,
+            severity,
+            style(format!("[{}]", finding.category)).dim(),
+            style(&finding.title).bold()
+        );
+        if !finding.message.is_empty() {
+            println!("    {}", finding.message);
+        }
+        if let Some(suggestion) = &finding.suggestion {
+            println!("    {}", style("Suggested fix:").dim());
+            for line in suggestion.lines() {
+                println!("      {}", style(line).dim());
+            }
+        }
+    }
+
+    println!();
+    println!(
+        "📊 {} findings ({} errors, {} warnings) · ${:.4} · {:.1}s",
+        report.findings.len(),
+        style(report.count_at_least(Severity::Error)).red(),
+        style(report.count_at_least(Severity::Warning) - report.count_at_least(Severity::Error))
+            .yellow(),
+        report.cost,
+        report.duration_ms as f64 / 1000.0
+    );
+    if !report.failed.is_empty() {
+        println!(
+            "   {} of {} chunks could not be reviewed",
+            report.failed.len(),
+            report.chunks
+        );
+    }
+    if let Some(path) = sarif {
+        println!("   SARIF: {}", style(path.display()).cyan());
+    }
+}
@@ -9,7 +9,9 @@ use std::path::PathBuf;
 use std::time::Instant;
 use tracing::{debug, info};
 
+use crate::analysis::background_indexer::{self, IndexFreshness};
 use crate::analysis::symbol_index::{SymbolEntry, SymbolIndexer};
+use crate::cli::output::{OutputMode, SearchOutput};
 use crate::core::database::DatabaseManager;
 use std::sync::Arc;
 
@@ -20,20 +22,47 @@ pub async fn handle_search(
     path: Option<PathBuf>,
     limit: usize,
     fuzzy: bool,
+    output: OutputMode,
 ) -> Result<()> {
-    let start = Instant::now();
-
-    println!(
-        "🔍 {} for '{}'...",
-        style("Searching").bold().cyan(),
-        style(&query).yellow()
-    );
+    if output.is_text() {
+        println!(
+            "🔍 {} for '{}'...",
+            style("Searching").bold().cyan(),
+            style(&query).yellow()
+        );
+    }
 
     // Initialize database
     let db = Arc::new(DatabaseManager::default().await?);
 
     // Create symbol indexer
-    let indexer = SymbolIndexer::new(db).await?;
+    let indexer = Arc::new(SymbolIndexer::new(db.clone()).await?);
+
+    // Make sure results reflect the working tree before searching
+    let project = path.clone().unwrap_or_else(|| PathBuf::from("."));
+    match background_indexer::ensure_current(db, indexer.clone(), &project).await {
+        Ok(Some(IndexFreshness::Watched(progress))) if progress.is_busy() && output.is_text() => {
+            println!(
+                "⏳ {} is updating the index ({}/{} files, {} pending changes); results may lag",
+                style("Background indexer").yellow(),
+                progress.processed,
+                progress.total,
+                progress.pending
+            );
+        }
+        Ok(Some(IndexFreshness::Refreshed(report))) if report.changed() && output.is_text() => {
+            println!(
+                "🔄 {} index: {} re-indexed, {} removed",
+                style("Refreshed").cyan(),
+                report.indexed,
+                report.removed
+            );
+        }
+        Ok(_) => {}
+        Err(e) => debug!("Skipping index refresh: {}", e),
+    }
+
+    let start = Instant::now();
 
     // Build search query
     let search_query = if fuzzy {
@@ -55,6 +84,17 @@ pub async fn handle_search(
 
     let elapsed = start.elapsed();
 
+    if !output.is_text() {
+        let data = SearchOutput {
+            query,
+            kind,
+            fuzzy,
+            duration_ms: elapsed.as_secs_f64() * 1000.0,
+            results,
+        };
+        return crate::cli::output::emit(output, "search", &data);
+    }
+
     // Display results
     if results.is_empty() {
         println!("❌ {} found for '{}'", style("No symbols").red(), query);
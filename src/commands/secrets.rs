@@ -0,0 +1,253 @@
+//! Secrets command implementation for encrypted API key storage
+//!
+//! This module implements `hive config secrets list`, `rotate` and `remove`.
+//! Secret values are only ever read from prompts or stdin and are never
+//! printed, in any output mode.
+
+use anyhow::{bail, Context, Result};
+use console::style;
+use dialoguer::{theme::ColorfulTheme, Confirm, Password};
+use rusqlite::params;
+use std::io::{BufRead, IsTerminal};
+
+use crate::cli::args::SecretCommands;
+use crate::cli::output::{
+    emit, OutputMode, SecretListOutput, SecretRemoveOutput, SecretRotateOutput,
+};
+use crate::core::api_keys::ApiKeyManager;
+use crate::core::config::{get_hive_config_dir, SecretsConfig};
+use crate::core::database::get_or_initialize_database;
+use crate::core::secrets::{
+    load_config, migrate_plaintext, KeySource, SecretStore, KEY_FILE, PASSPHRASE_ENV,
+};
+
+/// Handle the config secrets commands
+pub async fn handle_secrets(command: SecretCommands, output: OutputMode) -> Result<()> {
+    let config = load_config();
+    let store = open_store(&config)?;
+
+    // Keys saved by older versions are still in plaintext in the database
+    let db = get_or_initialize_database().await?;
+    let conn = db.get_connection()?;
+    let migrated = migrate_plaintext(&conn, &store)?;
+    if migrated > 0 && output.is_text() {
+        println!(
+            "🔐 Moved {} plaintext API keys from the database into the secret store",
+            migrated
+        );
+        println!();
+    }
+
+    match command {
+        SecretCommands::List => list(&store, output),
+        SecretCommands::Rotate {
+            name: Some(name), ..
+        } => replace(&store, &name, output),
+        SecretCommands::Rotate {
+            name: None,
+            passphrase,
+            key_file,
+        } => {
+            let use_passphrase = passphrase || (config.use_passphrase && !key_file);
+            rotate_key(&store, &config, use_passphrase, output)
+        }
+        SecretCommands::Remove { name, yes } => {
+            if !yes {
+                if !std::io::stdin().is_terminal() {
+                    bail!("Pass --yes to remove '{}' without a prompt", name);
+                }
+                if !Confirm::with_theme(&ColorfulTheme::default())
+                    .with_prompt(format!("Remove secret '{}'?", name))
+                    .default(false)
+                    .interact()?
+                {
+                    println!("Operation cancelled.");
+                    return Ok(());
+                }
+            }
+            let removed = store.remove(&name)?;
+            conn.execute(
+                "DELETE FROM configurations WHERE key = ?1 AND value = ''",
+                params![name],
+            )?;
+            if !output.is_text() {
+                return emit(
+                    output,
+                    "config secrets remove",
+                    &SecretRemoveOutput { name, removed },
+                );
+            }
+            if removed {
+                println!("🗑️  Removed secret {}", style(&name).cyan());
+            } else {
+                println!(
+                    "No secret named {} in the secrets file",
+                    style(&name).cyan()
+                );
+            }
+            Ok(())
+        }
+    }
+}
+
+/// The configured store, prompting for the passphrase when one is needed
+fn open_store(config: &SecretsConfig) -> Result<SecretStore> {
+    let passphrase = match std::env::var(PASSPHRASE_ENV) {
+        Ok(passphrase) => Some(passphrase),
+        Err(_) if config.use_passphrase && std::io::stdin().is_terminal() => Some(
+            Password::with_theme(&ColorfulTheme::default())
+                .with_prompt("Secrets passphrase")
+                .interact()?,
+        ),
+        Err(_) => None,
+    };
+    SecretStore::from_config(config, passphrase)
+}
+
+fn list(store: &SecretStore, output: OutputMode) -> Result<()> {
+    let secrets = store.list()?;
+    if !output.is_text() {
+        return emit(output, "config secrets list", &SecretListOutput { secrets });
+    }
+
+    if secrets.is_empty() {
+        println!(
+            "No secrets stored - add one with {}",
+            style("hive config secrets rotate openrouter_api_key").cyan()
+        );
+        return Ok(());
+    }
+    println!("🔐 {}", style("Secrets").bold());
+    println!();
+    for secret in secrets {
+        let mut details = format!("from {}", secret.backend);
+        if let Some(updated_at) = &secret.updated_at {
+            details.push_str(&format!(", updated {}", updated_at));
+        }
+        if !secret.shadowed.is_empty() {
+            details.push_str(&format!(", also in {}", secret.shadowed.join(", ")));
+        }
+        println!(
+            "  {:<22} {}",
+            style(&secret.name).cyan(),
+            style(details).dim()
+        );
+    }
+    Ok(())
+}
+
+/// Store a new value for `name`, read without echoing it
+fn replace(store: &SecretStore, name: &str, output: OutputMode) -> Result<()> {
+    let value = if std::io::stdin().is_terminal() {
+        Password::with_theme(&ColorfulTheme::default())
+            .with_prompt(format!("New value for {}", name))
+            .interact()?
+    } else {
+        let mut line = String::new();
+        std::io::stdin()
+            .lock()
+            .read_line(&mut line)
+            .context("Failed to read the secret from stdin")?;
+        line.trim().to_string()
+    };
+    if value.is_empty() {
+        bail!("No value given for '{}'", name);
+    }
+    match name {
+        "openrouter_api_key" => ApiKeyManager::validate_openrouter_format(&value)?,
+        "anthropic_api_key" => ApiKeyManager::validate_anthropic_format(&value)?,
+        _ => {}
+    }
+
+    let backend = store.set(name, &value)?;
+    if !output.is_text() {
+        return emit(
+            output,
+            "config secrets rotate",
+            &SecretRotateOutput {
+                name: Some(name.to_string()),
+                resealed: 0,
+                key_source: None,
+            },
+        );
+    }
+    println!(
+        "🔄 Replaced {} in the {} backend",
+        style(name).cyan(),
+        backend
+    );
+    Ok(())
+}
+
+/// Re-seal the secrets file under a fresh key file or a new passphrase
+fn rotate_key(
+    store: &SecretStore,
+    config: &SecretsConfig,
+    use_passphrase: bool,
+    output: OutputMode,
+) -> Result<()> {
+    let file = store
+        .file()
+        .with_context(|| {
+            format!(
+                "The secrets file is unavailable; enable the file backend and set {} if it uses a passphrase",
+                PASSPHRASE_ENV
+            )
+        })?;
+    let source = if use_passphrase {
+        if !std::io::stdin().is_terminal() {
+            bail!("Choosing a new passphrase needs a terminal");
+        }
+        KeySource::Passphrase(
+            Password::with_theme(&ColorfulTheme::default())
+                .with_prompt("New secrets passphrase")
+                .with_confirmation("Repeat the passphrase", "Passphrases do not match")
+                .interact()?,
+        )
+    } else {
+        KeySource::KeyFile(get_hive_config_dir().join(KEY_FILE))
+    };
+    let resealed = file.rotate(source)?;
+
+    if !output.is_text() {
+        return emit(
+            output,
+            "config secrets rotate",
+            &SecretRotateOutput {
+                name: None,
+                resealed,
+                key_source: Some(
+                    if use_passphrase {
+                        "passphrase"
+                    } else {
+                        "keyfile"
+                    }
+                    .to_string(),
+                ),
+            },
+        );
+    }
+    println!(
+        "🔑 Re-sealed {} secrets in {} under a new {}",
+        resealed,
+        style(file.path().display()).cyan(),
+        if use_passphrase {
+            "passphrase"
+        } else {
+            "key file"
+        }
+    );
+    if use_passphrase != config.use_passphrase {
+        println!(
+            "   Set {} in the [secrets] section of config.toml so hive opens it the same way",
+            style(format!("use_passphrase = {}", use_passphrase)).cyan()
+        );
+    }
+    if use_passphrase {
+        println!(
+            "   Non-interactive commands read the passphrase from {}",
+            style(PASSPHRASE_ENV).cyan()
+        );
+    }
+    Ok(())
+}
@@ -6,8 +6,10 @@ use clap::{Parser, Subcommand};
 use serde_json;
 use std::collections::HashMap;
 use std::path::PathBuf;
+use std::sync::Arc;
 use uuid;
 
+use crate::core::database::get_or_initialize_database;
 use crate::security::audit::AuditEventType;
 use crate::security::permissions::{
     ConditionOperator, ConditionType, PermissionCondition, PermissionScope, PermissionSubject,
@@ -15,8 +17,9 @@ use crate::security::permissions::{
 use crate::security::rbac::RiskLevel;
 use crate::security::teams::{MemberStatus, TeamAccess, TeamPermissions, TeamSettings, TeamType};
 use crate::security::{
-    AuditFilter, EncryptionConfig, EnterpriseRole, EnterpriseTeam, EnterpriseUser, PasswordPolicy,
-    SecurityConfig, SecurityGroup, SecuritySystem,
+    current_principal, AuditFilter, EncryptionConfig, EnterpriseRole, EnterpriseTeam,
+    EnterpriseUser, PasswordPolicy, PolicySet, RbacStore, SecurityConfig, SecurityGroup,
+    SecuritySystem, SECURITY_ADMIN_PERMISSION,
 };
 
 /// Security management commands
@@ -52,6 +55,49 @@ pub enum SecurityCommands {
 
     /// Security status
     Status,
+
+    /// Write users, roles, groups, policies, teams and grants as TOML
+    Export {
+        /// Output file path (defaults to stdout)
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Replace the RBAC configuration with a TOML policy set
+    Import {
+        /// Policy set file
+        file: PathBuf,
+    },
+}
+
+impl SecurityCommands {
+    /// Whether the command changes users, roles, teams, groups or grants
+    fn modifies_rbac(&self) -> bool {
+        match self {
+            SecurityCommands::User(cmd) => {
+                !matches!(cmd, UserCommands::List | UserCommands::Get { .. })
+            }
+            SecurityCommands::Role(cmd) => {
+                !matches!(cmd, RoleCommands::List | RoleCommands::Get { .. })
+            }
+            SecurityCommands::Team(cmd) => !matches!(
+                cmd,
+                TeamCommands::List | TeamCommands::Get { .. } | TeamCommands::Hierarchy { .. }
+            ),
+            SecurityCommands::Permission(cmd) => matches!(
+                cmd,
+                PermissionCommands::Grant { .. } | PermissionCommands::Revoke { .. }
+            ),
+            SecurityCommands::Group(cmd) => {
+                !matches!(cmd, GroupCommands::List | GroupCommands::Get { .. })
+            }
+            SecurityCommands::Import { .. } => true,
+            SecurityCommands::Audit(_)
+            | SecurityCommands::Config(_)
+            | SecurityCommands::Status
+            | SecurityCommands::Export { .. } => false,
+        }
+    }
 }
 
 #[derive(Debug, Subcommand)]
@@ -78,6 +124,14 @@ pub enum UserCommands {
         /// Enable MFA
         #[arg(long)]
         mfa: bool,
+        /// Role granted immediately, without approval (repeatable)
+        #[arg(long = "role")]
+        roles: Vec<String>,
+    },
+    /// Delete a user
+    Delete {
+        /// User ID
+        user_id: String,
     },
     /// Get user details
     Get {
@@ -91,6 +145,13 @@ pub enum UserCommands {
         /// Role name
         role: String,
     },
+    /// Approve a pending role assignment
+    ApproveRole {
+        /// User ID
+        user_id: String,
+        /// Role name
+        role: String,
+    },
     /// Remove role from user
     RemoveRole {
         /// User ID
@@ -159,6 +220,11 @@ pub enum RoleCommands {
         #[arg(long)]
         remove: Option<String>,
     },
+    /// Delete a role nobody holds
+    Delete {
+        /// Role ID
+        role_id: String,
+    },
 }
 
 #[derive(Debug, Subcommand)]
@@ -223,6 +289,11 @@ pub enum TeamCommands {
         /// Root team ID
         root_team: String,
     },
+    /// Delete a team without child teams
+    Delete {
+        /// Team ID
+        team_id: String,
+    },
 }
 
 #[derive(Debug, Subcommand)]
@@ -247,7 +318,7 @@ pub enum PermissionCommands {
         /// Resource ID
         #[arg(long)]
         resource_id: String,
-        /// Subject type (user, role, team, api_key)
+        /// Subject type (user, role, team, api_key, service_account)
         #[arg(long)]
         subject_type: String,
         /// Subject ID
@@ -264,7 +335,7 @@ pub enum PermissionCommands {
     },
     /// List permissions for subject
     List {
-        /// Subject type (user, role, team, api_key)
+        /// Subject type (user, role, team, api_key, service_account)
         subject_type: String,
         /// Subject ID
         subject_id: String,
@@ -318,6 +389,9 @@ pub enum GroupCommands {
     Create {
         /// Group name
         name: String,
+        /// Group ID (generated when omitted)
+        #[arg(long)]
+        id: Option<String>,
         /// Group description
         #[arg(long)]
         description: Option<String>,
@@ -395,15 +469,33 @@ pub async fn handle_security(commands: SecurityCommands) -> Result<()> {
         .join("hive")
         .join("security.db");
 
-    let security_system = SecuritySystem::new(security_config, Some(db_path)).await?;
+    let store = Arc::new(RbacStore::new(get_or_initialize_database().await?)?);
+    let security_system = SecuritySystem::open(security_config, Some(db_path), store).await?;
     security_system.initialize().await?;
 
+    // Changes need `security.manage` once at least one user exists
+    let actor = if commands.modifies_rbac() {
+        let guard = security_system.guard();
+        guard
+            .authorize(SECURITY_ADMIN_PERMISSION, "cli", "security")
+            .await?;
+        guard.principal().to_string()
+    } else {
+        current_principal().unwrap_or_else(|_| "unknown".to_string())
+    };
+
     match commands {
-        SecurityCommands::User(user_cmd) => handle_user_commands(user_cmd, &security_system).await,
-        SecurityCommands::Role(role_cmd) => handle_role_commands(role_cmd, &security_system).await,
-        SecurityCommands::Team(team_cmd) => handle_team_commands(team_cmd, &security_system).await,
+        SecurityCommands::User(user_cmd) => {
+            handle_user_commands(user_cmd, &security_system, &actor).await
+        }
+        SecurityCommands::Role(role_cmd) => {
+            handle_role_commands(role_cmd, &security_system, &actor).await
+        }
+        SecurityCommands::Team(team_cmd) => {
+            handle_team_commands(team_cmd, &security_system, &actor).await
+        }
         SecurityCommands::Permission(perm_cmd) => {
-            handle_permission_commands(perm_cmd, &security_system).await
+            handle_permission_commands(perm_cmd, &security_system, &actor).await
         }
         SecurityCommands::Group(group_cmd) => {
             handle_group_commands(group_cmd, &security_system).await
@@ -415,17 +507,46 @@ pub async fn handle_security(commands: SecurityCommands) -> Result<()> {
             handle_config_commands(config_cmd, &security_system).await
         }
         SecurityCommands::Status => handle_status_command(&security_system).await,
+        SecurityCommands::Export { output } => {
+            let toml = security_system.export_policy_set()?.to_toml()?;
+            if let Some(output_path) = output {
+                std::fs::write(&output_path, toml)?;
+                println!("✅ Policy set exported to: {:?}", output_path);
+            } else {
+                print!("{}", toml);
+            }
+            Ok(())
+        }
+        SecurityCommands::Import { file } => {
+            let policy_set = PolicySet::from_toml(&std::fs::read_to_string(&file)?)?;
+            let summary = security_system
+                .import_policy_set(&policy_set, &actor)
+                .await?;
+            println!(
+                "✅ Imported {} users, {} roles, {} groups, {} policies, {} teams, {} grants",
+                summary.users,
+                summary.roles,
+                summary.groups,
+                summary.policies,
+                summary.teams,
+                summary.grants
+            );
+            for removed in &summary.removed {
+                println!("   removed {}", removed);
+            }
+            Ok(())
+        }
     }
 }
 
 async fn handle_user_commands(
     command: UserCommands,
     security_system: &SecuritySystem,
+    actor: &str,
 ) -> Result<()> {
     match command {
         UserCommands::List => {
-            // Note: list_users method not implemented yet
-            let users: Vec<crate::security::rbac::EnterpriseUser> = vec![];
+            let users = security_system.rbac().list_users().await?;
             println!("🔐 Enterprise Users ({} total)", users.len());
             println!(
                 "{:<20} {:<25} {:<30} {:<10} {:<10}",
@@ -452,7 +573,14 @@ async fn handle_user_commands(
             name,
             department,
             mfa,
+            roles,
         } => {
+            for role in &roles {
+                if security_system.rbac().get_role(role).await?.is_none() {
+                    return Err(anyhow!("Role not found: {}", role));
+                }
+            }
+
             let mut metadata = HashMap::new();
             if let Some(dept) = department {
                 metadata.insert("department".to_string(), dept);
@@ -463,7 +591,7 @@ async fn handle_user_commands(
                 username,
                 email,
                 full_name: name,
-                roles: vec![],
+                roles,
                 teams: vec![],
                 security_groups: vec![],
                 active: true,
@@ -486,6 +614,11 @@ async fn handle_user_commands(
             println!("✅ User '{}' created successfully", id);
         }
 
+        UserCommands::Delete { user_id } => {
+            security_system.rbac().delete_user(&user_id).await?;
+            println!("✅ User '{}' deleted", user_id);
+        }
+
         UserCommands::Get { user_id } => {
             if let Some(user) = security_system.rbac().get_user(&user_id).await? {
                 println!("🔐 User Details: {}", user.id);
@@ -517,31 +650,51 @@ async fn handle_user_commands(
         }
 
         UserCommands::AssignRole { user_id, role } => {
+            security_system.rbac().assign_role(&user_id, &role).await?;
+            let assigned = security_system
+                .rbac()
+                .get_user(&user_id)
+                .await?
+                .map_or(false, |user| user.roles.contains(&role));
+            if assigned {
+                println!("✅ Role '{}' assigned to user '{}'", role, user_id);
+            } else {
+                println!(
+                    "⏳ Role '{}' for user '{}' is pending approval (hive security user approve-role)",
+                    role, user_id
+                );
+            }
+        }
+
+        UserCommands::ApproveRole { user_id, role } => {
             security_system
-                .assign_role(&user_id, &role, "admin")
+                .rbac()
+                .approve_role(&user_id, &role, actor)
                 .await?;
-            println!("✅ Role '{}' assigned to user '{}'", role, user_id);
+            println!("✅ Role '{}' approved for user '{}'", role, user_id);
         }
 
         UserCommands::RemoveRole { user_id, role } => {
-            // Note: remove_role method not implemented yet
-            println!("❌ Remove role functionality not yet implemented");
+            security_system.rbac().remove_role(&user_id, &role).await?;
             println!("✅ Role '{}' removed from user '{}'", role, user_id);
         }
 
         UserCommands::Lock { user_id, reason } => {
-            // In a real implementation, this would lock the user account
+            security_system
+                .rbac()
+                .set_locked(&user_id, Some(reason.clone()))
+                .await?;
             println!("🔒 User '{}' locked. Reason: {}", user_id, reason);
         }
 
         UserCommands::Unlock { user_id } => {
-            // In a real implementation, this would unlock the user account
+            security_system.rbac().set_locked(&user_id, None).await?;
             println!("🔓 User '{}' unlocked", user_id);
         }
 
         UserCommands::ResetPassword { user_id, password } => {
             security_system
-                .reset_user_password(&user_id, &password, "admin")
+                .reset_user_password(&user_id, &password, actor)
                 .await?;
             println!("✅ Password reset for user '{}'", user_id);
         }
@@ -553,6 +706,7 @@ async fn handle_user_commands(
 async fn handle_role_commands(
     command: RoleCommands,
     security_system: &SecuritySystem,
+    actor: &str,
 ) -> Result<()> {
     match command {
         RoleCommands::List => {
@@ -608,20 +762,17 @@ async fn handle_role_commands(
                 approval_roles: vec![],
                 risk_level: risk,
                 created_at: Utc::now(),
-                created_by: "admin".to_string(),
+                created_by: actor.to_string(),
                 active: true,
                 metadata: HashMap::new(),
             };
 
-            // Note: create_role method not implemented yet
-            println!("❌ Create role functionality not yet implemented");
+            security_system.rbac().create_role(role).await?;
             println!("✅ Role '{}' created successfully", id);
         }
 
         RoleCommands::Get { role_id } => {
-            // Note: get_role method not implemented yet
-            let role: Option<EnterpriseRole> = None;
-            if let Some(role) = role {
+            if let Some(role) = security_system.rbac().get_role(&role_id).await? {
                 println!("🎭 Role Details: {}", role.id);
                 println!("Name: {}", role.name);
                 println!("Description: {}", role.description);
@@ -655,9 +806,7 @@ async fn handle_role_commands(
             add,
             remove,
         } => {
-            // Note: get_role method not implemented yet
-            let role: Option<EnterpriseRole> = None;
-            if let Some(mut role) = role {
+            if let Some(mut role) = security_system.rbac().get_role(&role_id).await? {
                 if let Some(add_perms) = add {
                     for perm in add_perms.split(',') {
                         role.permissions.insert(perm.trim().to_string());
@@ -670,13 +819,17 @@ async fn handle_role_commands(
                     }
                 }
 
-                // Note: update_role method not implemented yet
-                println!("❌ Update role functionality not yet implemented");
+                security_system.rbac().update_role(role).await?;
                 println!("✅ Role '{}' permissions updated", role_id);
             } else {
                 return Err(anyhow!("Role not found: {}", role_id));
             }
         }
+
+        RoleCommands::Delete { role_id } => {
+            security_system.rbac().delete_role(&role_id).await?;
+            println!("✅ Role '{}' deleted", role_id);
+        }
     }
 
     Ok(())
@@ -685,11 +838,11 @@ async fn handle_role_commands(
 async fn handle_team_commands(
     command: TeamCommands,
     security_system: &SecuritySystem,
+    actor: &str,
 ) -> Result<()> {
     match command {
         TeamCommands::List => {
-            // Note: list_teams method not implemented yet
-            let teams: Vec<EnterpriseTeam> = vec![];
+            let teams = security_system.teams().list_teams().await?;
             println!("👥 Enterprise Teams ({} total)", teams.len());
             println!(
                 "{:<20} {:<30} {:<15} {:<10} {:<10}",
@@ -741,11 +894,11 @@ async fn handle_team_commands(
                 settings: TeamSettings::default(),
                 metadata: HashMap::new(),
                 created_at: Utc::now(),
-                created_by: "admin".to_string(),
+                created_by: actor.to_string(),
                 active: true,
             };
 
-            security_system.create_team(team, "admin").await?;
+            security_system.create_team(team, actor).await?;
             println!("✅ Team '{}' created successfully", id);
         }
 
@@ -780,7 +933,7 @@ async fn handle_team_commands(
             role: _,
         } => {
             security_system
-                .add_user_to_team(&user_id, &team_id, "admin")
+                .add_user_to_team(&user_id, &team_id, actor)
                 .await?;
             println!("✅ User '{}' added to team '{}'", user_id, team_id);
         }
@@ -801,7 +954,7 @@ async fn handle_team_commands(
         } => {
             let invitation_id = security_system
                 .teams()
-                .create_invitation(&team_id, &email, &role, "admin", message, false, None)
+                .create_invitation(&team_id, &email, &role, actor, message, false, None)
                 .await?;
             println!(
                 "✅ Invitation sent to '{}' for team '{}' (ID: {})",
@@ -825,6 +978,11 @@ async fn handle_team_commands(
                 println!("No hierarchy found for team: {}", root_team);
             }
         }
+
+        TeamCommands::Delete { team_id } => {
+            security_system.teams().delete_team(&team_id).await?;
+            println!("✅ Team '{}' deleted", team_id);
+        }
     }
 
     Ok(())
@@ -833,6 +991,7 @@ async fn handle_team_commands(
 async fn handle_permission_commands(
     command: PermissionCommands,
     security_system: &SecuritySystem,
+    actor: &str,
 ) -> Result<()> {
     match command {
         PermissionCommands::Check {
@@ -862,13 +1021,7 @@ async fn handle_permission_commands(
             subject_id,
             scope,
         } => {
-            let subject = match subject_type.as_str() {
-                "user" => PermissionSubject::User(subject_id),
-                "role" => PermissionSubject::Role(subject_id),
-                "team" => PermissionSubject::Team(subject_id),
-                "api_key" => PermissionSubject::ApiKey(subject_id),
-                _ => return Err(anyhow!("Invalid subject type: {}", subject_type)),
-            };
+            let subject = PermissionSubject::parse(&subject_type, subject_id)?;
 
             let permission_scope = match scope.as_deref() {
                 Some("resource") => PermissionScope::Resource,
@@ -887,7 +1040,7 @@ async fn handle_permission_commands(
                     &resource_id,
                     subject,
                     permission_scope,
-                    "admin",
+                    actor,
                     vec![],
                     None,
                 )
@@ -908,20 +1061,14 @@ async fn handle_permission_commands(
             subject_type,
             subject_id,
         } => {
-            let subject = match subject_type.as_str() {
-                "user" => PermissionSubject::User(subject_id),
-                "role" => PermissionSubject::Role(subject_id),
-                "team" => PermissionSubject::Team(subject_id),
-                "api_key" => PermissionSubject::ApiKey(subject_id),
-                _ => return Err(anyhow!("Invalid subject type: {}", subject_type)),
-            };
+            let subject = PermissionSubject::parse(&subject_type, subject_id)?;
 
             let permissions = security_system
                 .permissions()
                 .list_permissions(&subject)
                 .await?;
 
-            println!("🔑 Permissions for {} ({})", subject_type, subject.get_id());
+            println!("🔑 Permissions for {} ({})", subject_type, subject.id());
             println!(
                 "{:<30} {:<20} {:<20} {:<15}",
                 "Permission", "Resource Type", "Resource ID", "Scope"
@@ -1084,8 +1231,7 @@ async fn handle_group_commands(
             println!("{:<20} {:<30} {:<10}", "ID", "Name", "Members");
             println!("{}", "-".repeat(60));
 
-            // Note: list_groups method not implemented yet
-            let groups: Vec<crate::security::rbac::SecurityGroup> = vec![];
+            let groups = security_system.rbac().list_security_groups().await?;
             for group in groups {
                 println!(
                     "{:<20} {:<30} {:<10}",
@@ -1096,9 +1242,13 @@ async fn handle_group_commands(
             }
         }
 
-        GroupCommands::Create { name, description } => {
+        GroupCommands::Create {
+            name,
+            id,
+            description,
+        } => {
             let group = SecurityGroup {
-                id: format!("grp_{}", uuid::Uuid::new_v4()),
+                id: id.unwrap_or_else(|| format!("grp_{}", uuid::Uuid::new_v4())),
                 name: name.clone(),
                 description: description.unwrap_or_else(|| "Security group".to_string()),
                 members: std::collections::HashSet::new(),
@@ -1110,30 +1260,55 @@ async fn handle_group_commands(
                 metadata: std::collections::HashMap::new(),
             };
 
-            // Note: create_group method not implemented yet
+            let group_id = group.id.clone();
+            security_system.rbac().create_security_group(group).await?;
             println!("✅ Created security group: {}", name);
-            println!("   ID: {}", group.id);
+            println!("   ID: {}", group_id);
         }
 
         GroupCommands::Get { group_id } => {
-            // Note: get_group method not implemented yet
+            let group = security_system
+                .rbac()
+                .get_security_group(&group_id)
+                .await?
+                .ok_or_else(|| anyhow!("Security group not found: {}", group_id))?;
+            let mut members: Vec<_> = group.members.iter().map(String::as_str).collect();
+            members.sort();
+
             println!("📋 Security Group Details");
-            println!("ID: {}", group_id);
-            println!("(Group details not available - method not implemented)");
+            println!("ID: {}", group.id);
+            println!("Name: {}", group.name);
+            println!("Description: {}", group.description);
+            println!("Isolation Level: {:?}", group.isolation_level);
+            println!("Members: {}", members.join(", "));
+            println!("Default Roles: {}", group.default_roles.join(", "));
+            println!(
+                "Created: {}",
+                group.created_at.format("%Y-%m-%d %H:%M:%S UTC")
+            );
         }
 
         GroupCommands::AddUser { group_id, user_id } => {
-            // Note: add_user_to_group method not implemented yet
+            security_system
+                .rbac()
+                .add_user_to_group(&group_id, &user_id)
+                .await?;
             println!("✅ Added user {} to group {}", user_id, group_id);
         }
 
         GroupCommands::RemoveUser { group_id, user_id } => {
-            // Note: remove_user_from_group method not implemented yet
+            security_system
+                .rbac()
+                .remove_user_from_group(&group_id, &user_id)
+                .await?;
             println!("✅ Removed user {} from group {}", user_id, group_id);
         }
 
         GroupCommands::Delete { group_id } => {
-            // Note: delete_group method not implemented yet
+            security_system
+                .rbac()
+                .delete_security_group(&group_id)
+                .await?;
             println!("✅ Deleted security group: {}", group_id);
         }
     }
@@ -1306,20 +1481,3 @@ async fn handle_status_command(security_system: &SecuritySystem) -> Result<()> {
 
     Ok(())
 }
-
-// Helper trait to get ID from PermissionSubject
-trait SubjectId {
-    fn get_id(&self) -> String;
-}
-
-impl SubjectId for PermissionSubject {
-    fn get_id(&self) -> String {
-        match self {
-            PermissionSubject::User(id) => id.clone(),
-            PermissionSubject::Role(id) => id.clone(),
-            PermissionSubject::Team(id) => id.clone(),
-            PermissionSubject::ApiKey(id) => id.clone(),
-            PermissionSubject::ServiceAccount(id) => id.clone(),
-        }
-    }
-}
@@ -65,6 +65,21 @@ pub enum ShellCommands {
         #[arg(long)]
         validate: bool,
     },
+
+    /// Install `hive commit` as a git prepare-commit-msg hook
+    GitHook {
+        /// Repository to install the hook in
+        #[arg(long, value_name = "DIR", default_value = ".")]
+        repo: PathBuf,
+
+        /// Replace an existing prepare-commit-msg hook (a backup is kept)
+        #[arg(long)]
+        force: bool,
+
+        /// Remove the hook instead
+        #[arg(long, conflicts_with = "force")]
+        uninstall: bool,
+    },
 }
 
 /// Shell selection options
@@ -120,6 +135,11 @@ pub fn handle_shell(cmd: ShellCommands, config: Config) -> Result<()> {
             preserve_config,
             validate,
         } => handle_uninstall(shell_integration, shell, preserve_config, validate),
+        ShellCommands::GitHook {
+            repo,
+            force,
+            uninstall,
+        } => handle_git_hook(shell_integration, repo, force, uninstall),
     }
 }
 
@@ -143,6 +163,30 @@ fn handle_install(
     Ok(())
 }
 
+/// Handle git-hook command
+fn handle_git_hook(
+    shell_integration: ShellIntegration,
+    repo: PathBuf,
+    force: bool,
+    uninstall: bool,
+) -> Result<()> {
+    let hooks = shell_integration.get_hooks();
+
+    if uninstall {
+        if hooks.uninstall_git_hook(&repo)? {
+            println!("✅ Removed the Hive prepare-commit-msg hook");
+        } else {
+            println!("ℹ️  No Hive prepare-commit-msg hook installed");
+        }
+        return Ok(());
+    }
+
+    let hook = hooks.install_git_hook(&repo, force)?;
+    println!("✅ Installed prepare-commit-msg hook: {}", hook.display());
+    println!("   `git commit` now opens the editor with a proposed message");
+    Ok(())
+}
+
 /// Handle setup command
 fn handle_setup(shell_integration: ShellIntegration) -> Result<()> {
     println!("🔧 Setting up Hive AI environment...");
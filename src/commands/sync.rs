@@ -0,0 +1,251 @@
+//! Sync command implementation for team knowledge sync
+//!
+//! This module implements `hive sync init`, `status`, `run`, `pull`, `push`,
+//! `share` and `unshare` against a team's self-hosted `hive-sync-server`.
+//! The access token and team passphrase live in the secret store and are
+//! never printed.
+
+use anyhow::{bail, Context, Result};
+use console::style;
+use dialoguer::{theme::ColorfulTheme, Password};
+use std::io::IsTerminal;
+use std::sync::Arc;
+
+use crate::cli::args::SyncCommands;
+use crate::cli::output::{emit, OutputMode, SyncInitOutput, SyncShareOutput, SyncStatusOutput};
+use crate::core::config::{get_config, save_config, TeamSyncConfig};
+use crate::core::database::get_or_initialize_database;
+use crate::core::secrets::SecretStore;
+use crate::team_sync::{SyncClient, SyncReport, TeamKey, TeamSync};
+
+const TOKEN_SECRET: &str = "team_sync_token";
+const PASSPHRASE_SECRET: &str = "team_sync_passphrase";
+
+/// Handle the sync commands
+pub async fn handle_sync(command: SyncCommands, output: OutputMode) -> Result<()> {
+    match command {
+        SyncCommands::Init {
+            server,
+            team,
+            no_facts,
+        } => init(&server, &team, !no_facts, output).await,
+        SyncCommands::Status => status(output).await,
+        SyncCommands::Run => {
+            let report = open().await?.sync().await?;
+            print_report("sync run", &report, output)
+        }
+        SyncCommands::Pull => {
+            let report = open().await?.pull().await?;
+            print_report("sync pull", &report, output)
+        }
+        SyncCommands::Push => {
+            let report = open().await?.push().await?;
+            print_report("sync push", &report, output)
+        }
+        SyncCommands::Share { id } => {
+            let id = open().await?.share_thread(&id)?;
+            if !output.is_text() {
+                return emit(output, "sync share", &SyncShareOutput { id, shared: true });
+            }
+            println!(
+                "🔗 Thread {} will be shared on the next {}",
+                style(short(&id)).cyan(),
+                style("hive sync run").cyan()
+            );
+            Ok(())
+        }
+        SyncCommands::Unshare { id } => {
+            let id = open().await?.unshare_thread(&id).await?;
+            if !output.is_text() {
+                return emit(
+                    output,
+                    "sync unshare",
+                    &SyncShareOutput { id, shared: false },
+                );
+            }
+            println!(
+                "✂️  Thread {} was removed from the team; your copy is kept",
+                style(short(&id)).cyan()
+            );
+            Ok(())
+        }
+    }
+}
+
+fn short(id: &str) -> &str {
+    &id[..id.len().min(8)]
+}
+
+/// Sync for the configured team, with the token and passphrase from the secret store
+async fn open() -> Result<TeamSync> {
+    Ok(connect().await?.0)
+}
+
+/// [`open`], also returning the server client
+async fn connect() -> Result<(TeamSync, Arc<SyncClient>)> {
+    let config = get_config().await?.team_sync.context(
+        "Team sync is not set up - run `hive sync init --server <url> --team <name>` first",
+    )?;
+    let secrets = SecretStore::open_default()?;
+    let token = secrets
+        .get(TOKEN_SECRET)?
+        .context("No team sync token; set HIVE_SYNC_TOKEN or run `hive sync init` again")?;
+    let passphrase = secrets
+        .get(PASSPHRASE_SECRET)?
+        .context("No team passphrase; set HIVE_SYNC_PASSPHRASE or run `hive sync init` again")?;
+
+    let key = TeamKey::derive(&config.team, &passphrase)?;
+    let client = Arc::new(SyncClient::new(&config.server_url, token));
+    let sync = TeamSync::new(get_or_initialize_database().await?, client.clone(), key)?
+        .with_share_facts(config.share_facts);
+    Ok((sync, client))
+}
+
+/// Prompt for a secret without echoing it; an empty answer keeps a stored value
+fn ask_secret(secrets: &SecretStore, name: &str, env: &str, prompt: &str) -> Result<()> {
+    let stored = secrets.get(name)?.is_some();
+    if !std::io::stdin().is_terminal() {
+        if stored {
+            return Ok(());
+        }
+        bail!(
+            "No {} stored; set {} or run `hive sync init` in a terminal",
+            name,
+            env
+        );
+    }
+
+    let prompt = if stored {
+        format!("{} (leave empty to keep the current one)", prompt)
+    } else {
+        prompt.to_string()
+    };
+    let value = Password::with_theme(&ColorfulTheme::default())
+        .with_prompt(prompt)
+        .allow_empty_password(stored)
+        .interact()?;
+    if !value.is_empty() {
+        secrets.set(name, &value)?;
+    }
+    Ok(())
+}
+
+async fn init(server: &str, team: &str, share_facts: bool, output: OutputMode) -> Result<()> {
+    let secrets = SecretStore::open_default()?;
+    ask_secret(
+        &secrets,
+        TOKEN_SECRET,
+        "HIVE_SYNC_TOKEN",
+        &format!("Access token for team '{}'", team),
+    )?;
+    ask_secret(
+        &secrets,
+        PASSPHRASE_SECRET,
+        "HIVE_SYNC_PASSPHRASE",
+        "Team passphrase (shared by every member; the server never sees it)",
+    )?;
+
+    // Check the token before saving anything that depends on it
+    let server_url = server.trim_end_matches('/').to_string();
+    let token = secrets
+        .get(TOKEN_SECRET)?
+        .context("No team sync token stored")?;
+    let server_status = SyncClient::new(&server_url, token).status().await?;
+    if server_status.team != team {
+        bail!(
+            "The token belongs to team '{}', not '{}'",
+            server_status.team,
+            team
+        );
+    }
+
+    let mut config = get_config().await?;
+    config.team_sync = Some(TeamSyncConfig {
+        server_url: server_url.clone(),
+        team: team.to_string(),
+        share_facts,
+    });
+    save_config(&config).await?;
+    let node_id = open().await?.node_id().to_string();
+
+    if !output.is_text() {
+        return emit(
+            output,
+            "sync init",
+            &SyncInitOutput {
+                server_url,
+                team: team.to_string(),
+                share_facts,
+                node_id,
+            },
+        );
+    }
+    println!(
+        "✅ Connected to {} as a member of {}",
+        style(&server_url).cyan(),
+        style(team).bold()
+    );
+    println!(
+        "   The team has {} records; run {} to exchange knowledge",
+        server_status.records,
+        style("hive sync run").cyan()
+    );
+    if !share_facts {
+        println!("   Curated facts stay local; share threads with `hive sync share <id>`");
+    }
+    Ok(())
+}
+
+async fn status(output: OutputMode) -> Result<()> {
+    let (sync, client) = connect().await?;
+    let local = sync.status()?;
+    let server = client.status().await;
+
+    if !output.is_text() {
+        return emit(
+            output,
+            "sync status",
+            &SyncStatusOutput {
+                server_url: client.base_url().to_string(),
+                server_error: server.as_ref().err().map(|e| format!("{:#}", e)),
+                server: server.ok(),
+                local,
+            },
+        );
+    }
+
+    println!("🔄 {}", style("Team sync").bold());
+    println!();
+    println!("  Team:            {}", style(&local.team).bold());
+    println!("  Server:          {}", style(client.base_url()).cyan());
+    println!("  Node:            {}", style(short(&local.node_id)).dim());
+    println!("  Local facts:     {}", local.facts);
+    println!("  Shared threads:  {}", local.shared_threads);
+    println!("  Pending changes: {}", local.pending);
+    match server {
+        Ok(server) => println!(
+            "  Server records:  {} ({} removed)",
+            server.records, server.deleted
+        ),
+        Err(e) => println!(
+            "  Server records:  {}",
+            style(format!("unavailable: {:#}", e)).red()
+        ),
+    }
+    Ok(())
+}
+
+fn print_report(command: &str, report: &SyncReport, output: OutputMode) -> Result<()> {
+    if !output.is_text() {
+        return emit(output, command, report);
+    }
+    if report.pulled + report.pushed + report.merged + report.removed == 0 {
+        println!("✅ Already in sync with the team");
+        return Ok(());
+    }
+    println!(
+        "✅ {} pulled, {} pushed, {} merged, {} removed",
+        report.pulled, report.pushed, report.merged, report.removed
+    );
+    Ok(())
+}
@@ -0,0 +1,142 @@
+//! Thread command implementation for conversation threads
+//!
+//! This module implements `hive thread list`, `show`, `delete` and `branch`
+//! for the threads that `hive ask --thread`, the REPL and the backend server
+//! continue.
+
+use anyhow::{Context, Result};
+use console::style;
+
+use crate::cli::output::{emit, OutputMode, ThreadDeleteOutput, ThreadListOutput};
+use crate::consensus::threads::{Thread, ThreadStore};
+use crate::core::database::{generate_id, get_or_initialize_database};
+
+async fn open_store() -> Result<ThreadStore> {
+    ThreadStore::new(get_or_initialize_database().await?)
+}
+
+fn short(id: &str) -> &str {
+    &id[..id.len().min(8)]
+}
+
+/// Thread for `hive ask`: the one named, the most recent one, or a new one
+pub async fn resolve_ask_thread(thread: Option<&str>, continue_latest: bool) -> Result<String> {
+    match (thread, continue_latest) {
+        (Some(id), _) => open_store().await?.resolve(id),
+        (None, true) => Ok(open_store()
+            .await?
+            .latest()?
+            .context("No thread to continue - ask without --continue to start one")?
+            .id),
+        (None, false) => Ok(generate_id()),
+    }
+}
+
+/// Handle the thread list command
+pub async fn handle_thread_list(limit: usize, output: OutputMode) -> Result<()> {
+    let threads = open_store().await?.list(None, limit)?;
+    if !output.is_text() {
+        return emit(output, "thread list", &ThreadListOutput { threads });
+    }
+
+    if threads.is_empty() {
+        println!(
+            "No threads yet - start one with {}",
+            style("hive ask").cyan()
+        );
+        return Ok(());
+    }
+    println!("🧵 {}", style("Conversation threads").bold());
+    println!();
+    for thread in threads {
+        println!(
+            "  {}  {:>3} turns  ${:<8.4} {}",
+            style(short(&thread.id)).cyan(),
+            thread.turns,
+            thread.total_cost,
+            thread.title
+        );
+        if let Some(parent_id) = &thread.parent_id {
+            println!(
+                "            {}",
+                style(format!("↳ branch of {}", short(parent_id))).dim()
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Handle the thread show command
+pub async fn handle_thread_show(id: &str, output: OutputMode) -> Result<()> {
+    let thread = open_store().await?.load(id)?;
+    if !output.is_text() {
+        return emit(output, "thread show", &thread);
+    }
+
+    print_thread(&thread);
+    Ok(())
+}
+
+/// Handle the thread delete command
+pub async fn handle_thread_delete(id: &str, output: OutputMode) -> Result<()> {
+    let id = open_store().await?.delete(id)?;
+    if !output.is_text() {
+        return emit(output, "thread delete", &ThreadDeleteOutput { id });
+    }
+
+    println!("🗑️  Deleted thread {}", style(short(&id)).cyan());
+    Ok(())
+}
+
+/// Handle the thread branch command
+pub async fn handle_thread_branch(id: &str, at: Option<usize>, output: OutputMode) -> Result<()> {
+    let branch = open_store().await?.branch(id, at)?;
+    if !output.is_text() {
+        return emit(output, "thread branch", &branch);
+    }
+
+    println!(
+        "🌿 Branched thread {} from {} after turn {}",
+        style(branch.short_id()).cyan(),
+        style(short(branch.parent_id.as_deref().unwrap_or_default())).cyan(),
+        branch.turns.len()
+    );
+    println!(
+        "   Continue it with {}",
+        style(format!("hive ask --thread {} \"...\"", branch.short_id())).cyan()
+    );
+    Ok(())
+}
+
+fn print_thread(thread: &Thread) {
+    println!(
+        "🧵 {} {}",
+        style(thread.title.as_deref().unwrap_or("(untitled)")).bold(),
+        style(format!("({})", thread.id)).dim()
+    );
+    println!(
+        "   {} turns · ${:.4}",
+        thread.turns.len(),
+        thread.total_cost
+    );
+    if let Some(parent_id) = &thread.parent_id {
+        println!("   Branch of {}", style(short(parent_id)).cyan());
+    }
+    if let Some(summary) = &thread.summary {
+        println!();
+        println!(
+            "{}",
+            style(format!("Summary of turns 1-{}:", thread.summarized_turns)).dim()
+        );
+        println!("{}", style(summary).dim());
+    }
+    for (i, turn) in thread.turns.iter().enumerate() {
+        println!();
+        println!(
+            "{} {}",
+            style(format!("{}.", i + 1)).cyan(),
+            style(&turn.question).bold()
+        );
+        println!("{}", turn.answer);
+    }
+}
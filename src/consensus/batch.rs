@@ -0,0 +1,877 @@
+// Batch consensus - runs many queries unattended with bounded concurrency
+// Progress is checkpointed per item so an interrupted run resumes where it stopped
+
+use anyhow::{bail, Context, Result};
+use futures::stream::{self, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::consensus::cancellation::CancellationToken;
+use crate::core::error::HiveError;
+use crate::hooks::cost_control::{
+    AlertChannel, Budget, BudgetPeriod, BudgetScope, BudgetStatus, CostControlConfig,
+    CostController,
+};
+
+const CHECKPOINT_FILE: &str = "checkpoint.jsonl";
+const SUMMARY_FILE: &str = "summary.json";
+
+/// Summary table written next to the results
+pub const REPORT_FILE: &str = "report.md";
+
+const RESULTS_DIR: &str = "results";
+
+const RETRY_MAX_DELAY: Duration = Duration::from_secs(60);
+
+/// One query of a batch
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BatchItem {
+    /// Stable id used to checkpoint the item; defaults to its line number
+    #[serde(default)]
+    pub id: String,
+    pub query: String,
+    #[serde(default)]
+    pub context: Option<String>,
+    /// Consensus profile for this item instead of the batch's
+    #[serde(default)]
+    pub profile: Option<String>,
+}
+
+/// Read batch items from a JSONL file, one `{"query": ...}` object per line
+pub fn load_jsonl(path: &Path) -> Result<Vec<BatchItem>> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+
+    let mut items = Vec::new();
+    for (i, line) in content.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let mut item: BatchItem = serde_json::from_str(line)
+            .with_context(|| format!("{}:{}: invalid batch item", path.display(), i + 1))?;
+        if item.id.is_empty() {
+            item.id = format!("line-{}", i + 1);
+        }
+        items.push(item);
+    }
+    check_items(items)
+}
+
+/// One item per file matching `pattern`; the file is attached as context
+///
+/// `{path}` and `{name}` in the template are replaced with the file's path and
+/// file name.
+pub fn items_from_files(pattern: &str, template: &str) -> Result<Vec<BatchItem>> {
+    let mut paths: Vec<PathBuf> = glob::glob(pattern)
+        .with_context(|| format!("Invalid glob pattern '{}'", pattern))?
+        .filter_map(|entry| entry.ok())
+        .filter(|path| path.is_file())
+        .collect();
+    paths.sort();
+
+    let mut items = Vec::new();
+    for path in paths {
+        let content = match std::fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) => {
+                tracing::warn!("Skipping {}: {}", path.display(), e);
+                continue;
+            }
+        };
+        let display = path.display().to_string();
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        items.push(BatchItem {
+            id: display.clone(),
+            query: template
+                .replace("{path}", &display)
+                .replace("{name}", &name),
+            context: Some(format!("File: {}\n```\n{}\n```", display, content)),
+            profile: None,
+        });
+    }
+    if items.is_empty() {
+        bail!("No readable files match '{}'", pattern);
+    }
+    check_items(items)
+}
+
+fn check_items(items: Vec<BatchItem>) -> Result<Vec<BatchItem>> {
+    let mut seen = HashSet::new();
+    for item in &items {
+        if item.query.trim().is_empty() {
+            bail!("Batch item '{}' has an empty query", item.id);
+        }
+        if !seen.insert(item.id.as_str()) {
+            bail!("Duplicate batch item id '{}'", item.id);
+        }
+    }
+    Ok(items)
+}
+
+/// Answer and cost of one successful consensus run
+#[derive(Debug, Clone)]
+pub struct ItemOutcome {
+    pub answer: String,
+    pub cost: f64,
+}
+
+/// Context for a failed attempt that was still billed, such as a pipeline
+/// that failed after its first stages ran
+#[derive(Debug, Clone, Copy)]
+pub struct FailedAttemptCost(pub f64);
+
+impl std::fmt::Display for FailedAttemptCost {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Failed after spending ${:.4}", self.0)
+    }
+}
+
+fn failed_attempt_cost(error: &anyhow::Error) -> f64 {
+    error
+        .downcast_ref::<FailedAttemptCost>()
+        .map_or(0.0, |cost| cost.0)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ItemStatus {
+    Succeeded,
+    Failed,
+    /// Not run because the budget ran out or the batch was cancelled
+    Skipped,
+}
+
+/// What happened to one item
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ItemRecord {
+    pub id: String,
+    pub status: ItemStatus,
+    pub attempts: u32,
+    /// USD
+    pub cost: f64,
+    pub duration_ms: u64,
+    /// Answer file, relative to the output directory
+    pub result_file: Option<String>,
+    pub error: Option<String>,
+    /// Completed by an earlier run of the batch
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub resumed: bool,
+}
+
+/// Whether a failed run is worth retrying
+pub fn is_transient(error: &anyhow::Error) -> bool {
+    if let Some(hive_error) = error.chain().find_map(|e| e.downcast_ref::<HiveError>()) {
+        return match hive_error {
+            HiveError::RateLimitExceeded { .. } | HiveError::NetworkTimeout { .. } => true,
+            HiveError::HttpRequest { status, .. } => *status == 429 || *status >= 500,
+            HiveError::OpenRouterApi { message } => is_transient_message(message),
+            _ => false,
+        };
+    }
+    is_transient_message(&format!("{:#}", error))
+}
+
+fn is_transient_message(message: &str) -> bool {
+    let message = message.to_lowercase();
+    [
+        "timeout",
+        "timed out",
+        "rate limit",
+        "429",
+        "502",
+        "503",
+        "504",
+        "connection",
+        "temporarily",
+        "overloaded",
+    ]
+    .iter()
+    .any(|marker| message.contains(marker))
+}
+
+fn retry_delay(base: Duration, attempt: u32) -> Duration {
+    base.saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+        .min(RETRY_MAX_DELAY)
+}
+
+/// File name for an item's answer, unique through the item's position
+fn result_file_name(index: usize, id: &str) -> String {
+    let slug: String = id
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    let slug = slug.trim_matches('_');
+    format!(
+        "{}/{:04}-{}.md",
+        RESULTS_DIR,
+        index + 1,
+        &slug[..slug.len().min(60)]
+    )
+}
+
+/// Append-only record of finished items; the last record of an id wins
+struct Checkpoint {
+    file: Mutex<std::fs::File>,
+    records: HashMap<String, ItemRecord>,
+    /// Spend of every recorded run, and the billed attempts it came from
+    spent: (f64, usize),
+}
+
+impl Checkpoint {
+    fn open(dir: &Path, restart: bool) -> Result<Self> {
+        let path = dir.join(CHECKPOINT_FILE);
+        if restart && path.exists() {
+            std::fs::remove_file(&path)?;
+        }
+
+        let mut records = HashMap::new();
+        let mut spent = (0.0, 0);
+        let content = std::fs::read_to_string(&path).unwrap_or_default();
+        for line in content.lines() {
+            // A line cut short by a crash is simply not resumed
+            if let Ok(record) = serde_json::from_str::<ItemRecord>(line) {
+                if record.cost > 0.0 {
+                    spent.0 += record.cost;
+                    spent.1 += record.attempts as usize;
+                }
+                records.insert(record.id.clone(), record);
+            }
+        }
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .with_context(|| format!("Failed to open checkpoint {}", path.display()))?;
+        if !content.is_empty() && !content.ends_with('\n') {
+            writeln!(file)?;
+        }
+        Ok(Self {
+            file: Mutex::new(file),
+            records,
+            spent,
+        })
+    }
+
+    fn succeeded(&self, id: &str) -> Option<&ItemRecord> {
+        self.records
+            .get(id)
+            .filter(|record| record.status == ItemStatus::Succeeded)
+    }
+
+    fn record(&self, record: &ItemRecord) -> Result<()> {
+        let mut file = self.file.lock().unwrap();
+        writeln!(file, "{}", serde_json::to_string(record)?)?;
+        file.sync_data()?;
+        Ok(())
+    }
+}
+
+/// `--max-cost` enforced through a [`CostController`] budget
+struct BatchBudget {
+    controller: CostController,
+    budget_id: String,
+    limit: f64,
+    usage: tokio::sync::Mutex<BudgetUsage>,
+    /// Signalled whenever an attempt is charged and its reservation released
+    released: tokio::sync::Notify,
+}
+
+#[derive(Default)]
+struct BudgetUsage {
+    spent: f64,
+    /// Billed attempts, for the average cost of the next one
+    attempts: usize,
+    /// Projected cost of the attempts running now
+    reserved: f64,
+    in_flight: usize,
+}
+
+impl BatchBudget {
+    /// Budget of `limit` USD, of which `spent` went to `attempts` attempts of
+    /// an earlier run of the batch
+    async fn new(limit: f64, (spent, attempts): (f64, usize)) -> Result<Self> {
+        let mut config = CostControlConfig::default();
+        // Console alerts would corrupt machine-readable output
+        config.alerts.channels = vec![AlertChannel::Log];
+        let controller = CostController::new(config).await?;
+
+        let now = chrono::Utc::now();
+        let budget_id = controller
+            .create_budget(Budget {
+                id: String::new(),
+                name: "hive batch".to_string(),
+                description: "Spending limit of one batch run".to_string(),
+                amount: limit,
+                period: BudgetPeriod::Custom { duration_days: 1 },
+                scope: BudgetScope::Global,
+                start_date: now,
+                end_date: now + chrono::Duration::days(1),
+                current_usage: spent,
+                alerts_sent: 0,
+                status: BudgetStatus::Active,
+                created_by: "hive batch".to_string(),
+                tags: vec!["batch".to_string()],
+            })
+            .await?;
+        if spent > 0.0 {
+            tracing::info!("Batch budget: ${:.4} already spent by earlier runs", spent);
+        }
+        Ok(Self {
+            controller,
+            budget_id,
+            limit,
+            usage: tokio::sync::Mutex::new(BudgetUsage {
+                spent,
+                attempts,
+                ..BudgetUsage::default()
+            }),
+            released: tokio::sync::Notify::new(),
+        })
+    }
+
+    /// Reserve the projected cost of one more attempt, the average so far
+    ///
+    /// Waits for running attempts when their outcome decides whether another
+    /// one fits, including the first one when nothing has been billed yet.
+    /// `None` means the budget is exhausted.
+    async fn reserve(&self) -> Option<f64> {
+        loop {
+            let released = self.released.notified();
+            {
+                let mut usage = self.usage.lock().await;
+                if usage.spent >= self.limit {
+                    return None;
+                }
+                let projected = match usage.attempts {
+                    0 if usage.in_flight > 0 => None,
+                    0 => Some(0.0),
+                    n => Some(usage.spent / n as f64),
+                };
+                match projected {
+                    Some(projected) if usage.spent + usage.reserved + projected <= self.limit => {
+                        usage.reserved += projected;
+                        usage.in_flight += 1;
+                        return Some(projected);
+                    }
+                    Some(_) if usage.in_flight == 0 => return None,
+                    _ => {}
+                }
+            }
+            released.await;
+        }
+    }
+
+    /// Charge an attempt, releasing its `reservation`
+    async fn charge(&self, reservation: f64, cost: f64) -> Result<()> {
+        let mut usage = self.usage.lock().await;
+        usage.reserved = (usage.reserved - reservation).max(0.0);
+        usage.in_flight = usage.in_flight.saturating_sub(1);
+        let update = self
+            .controller
+            .update_budget_usage(&self.budget_id, cost)
+            .await;
+        self.released.notify_waiters();
+        let update = update?;
+        usage.spent = update.new_usage;
+        if cost > 0.0 {
+            usage.attempts += 1;
+        }
+        if update.status == BudgetStatus::Exceeded {
+            tracing::warn!(
+                "Batch budget of ${:.2} exhausted (${:.4} spent)",
+                self.limit,
+                update.new_usage
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Settings of a batch run
+#[derive(Debug, Clone)]
+pub struct BatchOptions {
+    /// Items running at the same time
+    pub concurrency: usize,
+    /// Retries of an item after transient failures
+    pub max_retries: u32,
+    /// Delay before the first retry; doubled on every further one
+    pub retry_delay: Duration,
+    /// USD the batch may spend, including earlier runs it resumes
+    pub max_cost: Option<f64>,
+    /// Ignore the checkpoint of an earlier run
+    pub restart: bool,
+}
+
+impl Default for BatchOptions {
+    fn default() -> Self {
+        Self {
+            concurrency: 4,
+            max_retries: 2,
+            retry_delay: Duration::from_secs(2),
+            max_cost: None,
+            restart: false,
+        }
+    }
+}
+
+/// Totals of a batch run, written to `summary.json`
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchSummary {
+    pub output_dir: PathBuf,
+    pub total: usize,
+    pub succeeded: usize,
+    pub failed: usize,
+    pub skipped: usize,
+    /// Succeeded in an earlier run
+    pub resumed: usize,
+    /// USD spent by this run
+    pub total_cost: f64,
+    pub duration_ms: u64,
+    pub items: Vec<ItemRecord>,
+}
+
+/// Runs batch items and writes their results under one output directory
+///
+/// ```text
+/// <dir>/checkpoint.jsonl   finished items, used to resume
+/// <dir>/results/*.md       one answer per succeeded item
+/// <dir>/summary.json       BatchSummary
+/// <dir>/report.md          the summary as a table
+/// ```
+pub struct BatchRunner {
+    output_dir: PathBuf,
+    options: BatchOptions,
+}
+
+impl BatchRunner {
+    pub fn new(output_dir: impl Into<PathBuf>, options: BatchOptions) -> Self {
+        Self {
+            output_dir: output_dir.into(),
+            options,
+        }
+    }
+
+    /// Run every item not already completed by an earlier run
+    ///
+    /// `execute` runs one attempt of an item; `on_item` is called as each item
+    /// finishes. Cancelling `cancellation` stops scheduling new items, and the
+    /// ones not run are reported as skipped.
+    pub async fn run<F, Fut>(
+        &self,
+        items: Vec<BatchItem>,
+        execute: F,
+        on_item: impl Fn(&ItemRecord),
+        cancellation: CancellationToken,
+    ) -> Result<BatchSummary>
+    where
+        F: Fn(BatchItem) -> Fut,
+        Fut: Future<Output = Result<ItemOutcome>>,
+    {
+        let started = Instant::now();
+        std::fs::create_dir_all(self.output_dir.join(RESULTS_DIR)).with_context(|| {
+            format!(
+                "Failed to create output directory {}",
+                self.output_dir.display()
+            )
+        })?;
+        let checkpoint = Checkpoint::open(&self.output_dir, self.options.restart)?;
+        let budget = match self.options.max_cost {
+            Some(limit) => Some(BatchBudget::new(limit, checkpoint.spent).await?),
+            None => None,
+        };
+
+        let mut records: Vec<Option<ItemRecord>> = vec![None; items.len()];
+        let mut pending = Vec::new();
+        for (index, item) in items.into_iter().enumerate() {
+            match checkpoint.succeeded(&item.id) {
+                Some(record) => {
+                    records[index] = Some(ItemRecord {
+                        resumed: true,
+                        ..record.clone()
+                    })
+                }
+                None => pending.push((index, item)),
+            }
+        }
+        if pending.len() < records.len() {
+            tracing::info!(
+                "Resuming batch: {} of {} items already done",
+                records.len() - pending.len(),
+                records.len()
+            );
+        }
+
+        let execute = &execute;
+        let checkpoint = &checkpoint;
+        let budget = budget.as_ref();
+        let cancellation = &cancellation;
+        let mut finished = stream::iter(pending)
+            .map(|(index, item)| async move {
+                let record = self
+                    .run_item(index, item, execute, budget, cancellation)
+                    .await?;
+                if record.status != ItemStatus::Skipped {
+                    checkpoint.record(&record)?;
+                }
+                Ok::<_, anyhow::Error>((index, record))
+            })
+            .buffer_unordered(self.options.concurrency.max(1));
+        while let Some(result) = finished.next().await {
+            let (index, record) = result?;
+            on_item(&record);
+            records[index] = Some(record);
+        }
+
+        let items: Vec<ItemRecord> = records.into_iter().flatten().collect();
+        let count = |status| items.iter().filter(|item| item.status == status).count();
+        let summary = BatchSummary {
+            output_dir: self.output_dir.clone(),
+            total: items.len(),
+            succeeded: count(ItemStatus::Succeeded),
+            failed: count(ItemStatus::Failed),
+            skipped: count(ItemStatus::Skipped),
+            resumed: items.iter().filter(|item| item.resumed).count(),
+            total_cost: items
+                .iter()
+                .filter(|item| !item.resumed)
+                .map(|item| item.cost)
+                .sum(),
+            duration_ms: started.elapsed().as_millis() as u64,
+            items,
+        };
+        self.write_summary(&summary)?;
+        Ok(summary)
+    }
+
+    async fn run_item<F, Fut>(
+        &self,
+        index: usize,
+        item: BatchItem,
+        execute: &F,
+        budget: Option<&BatchBudget>,
+        cancellation: &CancellationToken,
+    ) -> Result<ItemRecord>
+    where
+        F: Fn(BatchItem) -> Fut,
+        Fut: Future<Output = Result<ItemOutcome>>,
+    {
+        let started = Instant::now();
+        let mut record = ItemRecord {
+            id: item.id.clone(),
+            status: ItemStatus::Skipped,
+            attempts: 0,
+            cost: 0.0,
+            duration_ms: 0,
+            result_file: None,
+            error: None,
+            resumed: false,
+        };
+
+        loop {
+            let reservation = match budget {
+                _ if cancellation.is_cancelled() => Err("Cancelled"),
+                Some(budget) => budget.reserve().await.ok_or("Budget exhausted"),
+                None => Ok(0.0),
+            };
+            let reservation = match reservation {
+                Ok(reservation) => reservation,
+                Err(reason) => {
+                    // A retry that cannot run keeps the error of the last attempt
+                    if record.attempts == 0 {
+                        record.error = Some(reason.to_string());
+                    }
+                    break;
+                }
+            };
+
+            record.attempts += 1;
+            let result = execute(item.clone()).await;
+            let cost = match &result {
+                Ok(outcome) => outcome.cost,
+                Err(e) => failed_attempt_cost(e),
+            };
+            if let Some(budget) = budget {
+                budget.charge(reservation, cost).await?;
+            }
+            match result {
+                Ok(outcome) => {
+                    let result_file = result_file_name(index, &item.id);
+                    std::fs::write(self.output_dir.join(&result_file), &outcome.answer)?;
+                    record.cost += outcome.cost;
+                    record.status = ItemStatus::Succeeded;
+                    record.result_file = Some(result_file);
+                    record.error = None;
+                    break;
+                }
+                Err(e) => {
+                    record.cost += cost;
+                    record.status = ItemStatus::Failed;
+                    record.error = Some(format!("{:#}", e));
+                    if cancellation.is_cancelled()
+                        || !is_transient(&e)
+                        || record.attempts > self.options.max_retries
+                    {
+                        break;
+                    }
+                    let delay = retry_delay(self.options.retry_delay, record.attempts);
+                    tracing::info!(
+                        "Batch item '{}' failed (attempt {}), retrying in {}s: {:#}",
+                        item.id,
+                        record.attempts,
+                        delay.as_secs(),
+                        e
+                    );
+                    tokio::time::sleep(delay).await;
+                }
+            }
+        }
+
+        record.duration_ms = started.elapsed().as_millis() as u64;
+        Ok(record)
+    }
+
+    fn write_summary(&self, summary: &BatchSummary) -> Result<()> {
+        std::fs::write(
+            self.output_dir.join(SUMMARY_FILE),
+            serde_json::to_string_pretty(summary)?,
+        )?;
+
+        let mut report = format!(
+            "# Batch report\n\n{} items: {} succeeded, {} failed, {} skipped ({} from an earlier run)\n\nCost: ${:.4} · Duration: {:.1}s\n\n| Item | Status | Attempts | Cost | Result |\n|------|--------|----------|------|--------|\n",
+            summary.total,
+            summary.succeeded,
+            summary.failed,
+            summary.skipped,
+            summary.resumed,
+            summary.total_cost,
+            summary.duration_ms as f64 / 1000.0
+        );
+        for item in &summary.items {
+            let result = match (&item.result_file, &item.error) {
+                (Some(file), _) => format!("[{}]({})", file, file),
+                (None, Some(error)) => error.replace('|', "\\|").replace('\n', " "),
+                (None, None) => String::new(),
+            };
+            report.push_str(&format!(
+                "| `{}` | {:?} | {} | ${:.4} | {} |\n",
+                item.id, item.status, item.attempts, item.cost, result
+            ));
+        }
+        std::fs::write(self.output_dir.join(REPORT_FILE), report)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tempfile::TempDir;
+
+    fn item(id: &str) -> BatchItem {
+        BatchItem {
+            id: id.to_string(),
+            query: format!("Explain {}", id),
+            context: None,
+            profile: None,
+        }
+    }
+
+    #[test]
+    fn test_load_jsonl() -> Result<()> {
+        let dir = TempDir::new()?;
+        let path = dir.path().join("items.jsonl");
+        std::fs::write(
+            &path,
+            "{\"query\": \"one\"}\n\n{\"id\": \"two\", \"query\": \"two\", \"profile\": \"speed\"}\n",
+        )?;
+        let items = load_jsonl(&path)?;
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].id, "line-1");
+        assert_eq!(items[1].profile.as_deref(), Some("speed"));
+
+        std::fs::write(
+            &path,
+            "{\"id\": \"a\", \"query\": \"x\"}\n{\"id\": \"a\", \"query\": \"y\"}\n",
+        )?;
+        assert!(load_jsonl(&path).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_items_from_files() -> Result<()> {
+        let dir = TempDir::new()?;
+        std::fs::write(dir.path().join("a.rs"), "fn a() {}")?;
+        std::fs::write(dir.path().join("b.rs"), "fn b() {}")?;
+        std::fs::write(dir.path().join("notes.txt"), "ignored")?;
+
+        let pattern = format!("{}/*.rs", dir.path().display());
+        let items = items_from_files(&pattern, "Document {name}")?;
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].query, "Document a.rs");
+        assert!(items[1].context.as_deref().unwrap().contains("fn b() {}"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_transient_errors() {
+        let rate_limited: anyhow::Error = HiveError::RateLimitExceeded {
+            provider: "openrouter".to_string(),
+            retry_after: 5,
+        }
+        .into();
+        assert!(is_transient(&rate_limited));
+        assert!(is_transient(&anyhow::anyhow!("503 Service Unavailable")));
+        let missing_key: anyhow::Error = HiveError::ConfigMissingField {
+            field: "OPENROUTER_API_KEY".to_string(),
+        }
+        .into();
+        assert!(!is_transient(&missing_key));
+        assert!(!is_transient(&anyhow::anyhow!("Invalid model")));
+    }
+
+    #[tokio::test]
+    async fn test_retries_and_resume() -> Result<()> {
+        let dir = TempDir::new()?;
+        let calls = AtomicUsize::new(0);
+        let execute = |item: BatchItem| {
+            let call = calls.fetch_add(1, Ordering::SeqCst);
+            async move {
+                match item.id.as_str() {
+                    // Fails transiently once, then succeeds
+                    "flaky" if call == 0 => bail!("Request timed out"),
+                    "broken" => bail!("Invalid request"),
+                    _ => Ok(ItemOutcome {
+                        answer: format!("Answer for {}", item.id),
+                        cost: 0.5,
+                    }),
+                }
+            }
+        };
+        let options = BatchOptions {
+            concurrency: 1,
+            retry_delay: Duration::from_millis(1),
+            ..BatchOptions::default()
+        };
+        let runner = BatchRunner::new(dir.path(), options);
+        let items = vec![item("flaky"), item("ok"), item("broken")];
+
+        let summary = runner
+            .run(items.clone(), execute, |_| {}, CancellationToken::new())
+            .await?;
+        assert_eq!((summary.succeeded, summary.failed), (2, 1));
+        assert_eq!(summary.items[0].attempts, 2);
+        assert_eq!(summary.items[2].attempts, 1);
+        assert!((summary.total_cost - 1.0).abs() < 1e-9);
+        let answer = std::fs::read_to_string(
+            dir.path()
+                .join(summary.items[1].result_file.as_deref().unwrap()),
+        )?;
+        assert_eq!(answer, "Answer for ok");
+        assert!(dir.path().join(REPORT_FILE).exists());
+
+        // Only the failed item runs again
+        let calls_before = calls.load(Ordering::SeqCst);
+        let summary = runner
+            .run(items, execute, |_| {}, CancellationToken::new())
+            .await?;
+        assert_eq!(calls.load(Ordering::SeqCst), calls_before + 1);
+        assert_eq!(summary.resumed, 2);
+        assert_eq!(summary.total_cost, 0.0);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_budget_stops_batch() -> Result<()> {
+        let dir = TempDir::new()?;
+        // Concurrent items reserve their projected cost before starting
+        let options = BatchOptions {
+            concurrency: 4,
+            max_cost: Some(1.0),
+            ..BatchOptions::default()
+        };
+        let items: Vec<BatchItem> = (0..5).map(|i| item(&format!("item-{}", i))).collect();
+        let summary = BatchRunner::new(dir.path(), options)
+            .run(
+                items,
+                |_| async {
+                    Ok(ItemOutcome {
+                        answer: String::new(),
+                        cost: 0.4,
+                    })
+                },
+                |_| {},
+                CancellationToken::new(),
+            )
+            .await?;
+        assert_eq!((summary.succeeded, summary.skipped), (2, 3));
+        assert!(summary.total_cost <= 1.0);
+
+        // Skipped items are not checkpointed, so a later run picks them up
+        let checkpoint = std::fs::read_to_string(dir.path().join(CHECKPOINT_FILE))?;
+        assert_eq!(checkpoint.lines().count(), 2);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_budget_charges_failures_and_resumes() -> Result<()> {
+        let dir = TempDir::new()?;
+        let options = BatchOptions {
+            concurrency: 1,
+            max_cost: Some(1.0),
+            ..BatchOptions::default()
+        };
+        let runner = BatchRunner::new(dir.path(), options);
+        let summary = runner
+            .run(
+                vec![item("broken"), item("ok")],
+                |item: BatchItem| async move {
+                    match item.id.as_str() {
+                        "broken" => {
+                            Err(anyhow::anyhow!("Invalid request").context(FailedAttemptCost(0.3)))
+                        }
+                        _ => Ok(ItemOutcome {
+                            answer: String::new(),
+                            cost: 0.3,
+                        }),
+                    }
+                },
+                |_| {},
+                CancellationToken::new(),
+            )
+            .await?;
+        assert_eq!((summary.succeeded, summary.failed), (1, 1));
+        assert!((summary.items[0].cost - 0.3).abs() < 1e-9);
+        assert!((summary.total_cost - 0.6).abs() < 1e-9);
+
+        // The resumed run starts from the $0.60 already spent
+        let calls = AtomicUsize::new(0);
+        let summary = runner
+            .run(
+                vec![item("broken"), item("ok"), item("new")],
+                |_| {
+                    calls.fetch_add(1, Ordering::SeqCst);
+                    async {
+                        Ok(ItemOutcome {
+                            answer: String::new(),
+                            cost: 0.3,
+                        })
+                    }
+                },
+                |_| {},
+                CancellationToken::new(),
+            )
+            .await?;
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_eq!((summary.resumed, summary.skipped), (1, 1));
+        Ok(())
+    }
+}
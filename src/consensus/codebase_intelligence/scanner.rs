@@ -9,6 +9,7 @@ use tokio::fs;
 use tracing::{debug, info, warn};
 
 use crate::analysis::language_detector::LanguageDetector;
+use crate::analysis::walker::RepositoryWalker;
 use crate::consensus::file_operations::{FileContent, FileReader, SecurityPolicy};
 use crate::core::Language;
 
@@ -61,16 +62,28 @@ impl CodebaseScanner {
         let mut language_stats = std::collections::HashMap::new();
         let mut ignored_paths = Vec::new();
 
-        // Walk the entire directory tree
-        self.scan_directory(
-            root_path,
-            root_path,
-            &mut files,
-            &mut total_size,
-            &mut language_stats,
-            &mut ignored_paths,
-        )
-        .await?;
+        // Walk the entire directory tree, honouring .gitignore/.hiveignore
+        let walked = RepositoryWalker::new(root_path)
+            .max_file_size(Some(self.max_file_size))
+            .walk_async()
+            .await?;
+
+        for file in walked {
+            match self.scan_file(root_path, &file.path).await {
+                Ok(Some(scanned_file)) => {
+                    total_size += scanned_file.size_bytes;
+                    *language_stats.entry(scanned_file.language).or_insert(0) += 1;
+                    files.push(scanned_file);
+                }
+                Ok(None) => {
+                    // File was ignored
+                    ignored_paths.push(file.path);
+                }
+                Err(e) => {
+                    warn!("Failed to scan file {}: {}", file.path.display(), e);
+                }
+            }
+        }
 
         // Sort languages by file count
         let mut languages: Vec<_> = language_stats.into_iter().collect();
@@ -92,61 +105,6 @@ impl CodebaseScanner {
         })
     }
 
-    /// Recursively scan a directory
-    async fn scan_directory(
-        &self,
-        root_path: &Path,
-        dir_path: &Path,
-        files: &mut Vec<ScannedFile>,
-        total_size: &mut u64,
-        language_stats: &mut std::collections::HashMap<Language, usize>,
-        ignored_paths: &mut Vec<PathBuf>,
-    ) -> Result<()> {
-        // Check if directory should be ignored
-        if self.should_ignore_path(dir_path) {
-            ignored_paths.push(dir_path.to_path_buf());
-            return Ok(());
-        }
-
-        let mut entries = fs::read_dir(dir_path).await?;
-
-        while let Some(entry) = entries.next_entry().await? {
-            let path = entry.path();
-            let file_type = entry.file_type().await?;
-
-            if file_type.is_dir() {
-                // Recurse into subdirectory
-                Box::pin(self.scan_directory(
-                    root_path,
-                    &path,
-                    files,
-                    total_size,
-                    language_stats,
-                    ignored_paths,
-                ))
-                .await?;
-            } else if file_type.is_file() {
-                // Process file
-                match self.scan_file(root_path, &path).await {
-                    Ok(Some(scanned_file)) => {
-                        *total_size += scanned_file.size_bytes;
-                        *language_stats.entry(scanned_file.language).or_insert(0) += 1;
-                        files.push(scanned_file);
-                    }
-                    Ok(None) => {
-                        // File was ignored
-                        ignored_paths.push(path);
-                    }
-                    Err(e) => {
-                        warn!("Failed to scan file {}: {}", path.display(), e);
-                    }
-                }
-            }
-        }
-
-        Ok(())
-    }
-
     /// Scan a single file
     async fn scan_file(&self, root_path: &Path, file_path: &Path) -> Result<Option<ScannedFile>> {
         // Check if file should be ignored
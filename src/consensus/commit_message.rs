@@ -0,0 +1,607 @@
+// Commit messages - Conventional Commits proposals for staged changes
+// Also groups a revision range into a Markdown changelog
+
+use anyhow::{bail, Context, Result};
+use git2::{Oid, Repository, Sort};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fmt;
+use std::io::Write;
+use std::path::{Component, Path};
+use std::process::Command;
+
+use crate::consensus::review::{FileDiff, FileStatus, LineKind};
+use crate::consensus::topology::{PipelineDefinition, PipelineStore};
+
+/// Name of the built-in commit message pipeline
+pub const COMMIT_PIPELINE: &str = "commit";
+
+/// Commit types the prompt offers and the parser accepts
+pub const COMMIT_TYPES: &[&str] = &[
+    "feat", "fix", "perf", "refactor", "docs", "test", "build", "ci", "style", "chore", "revert",
+];
+
+/// Diff lines sent to the model before the rest is summarized per file
+const MAX_PROMPT_LINES: usize = 600;
+
+const BREAKING_FOOTER: &str = "BREAKING CHANGE:";
+
+const PIPELINE_TOML: &str = r#"name = "commit"
+description = "Commit message: summarize a staged diff as a Conventional Commits message"
+
+[[stage]]
+id = "writer"
+role = "generator"
+include_context = false
+temperature = 0.2
+system_prompt = """
+You write git commit messages in the Conventional Commits format. Read the staged diff in the
+request and reply with the message only, no code fences or commentary:
+
+<type>(<scope>): <subject>
+
+<body>
+
+BREAKING CHANGE: <description>
+
+The subject is imperative, lower case and at most 72 characters without a trailing period. The
+body explains what changed and why in wrapped prose or short bullets; leave it out for trivial
+changes. Add the BREAKING CHANGE footer only when the diff breaks a public interface.
+"""
+"#;
+
+/// The built-in commit message pipeline
+pub fn commit_pipeline() -> PipelineDefinition {
+    PipelineDefinition::from_toml(PIPELINE_TOML).expect("built-in commit pipeline is valid")
+}
+
+/// Load a stored pipeline, falling back to the built-in one for `commit`
+pub fn load_pipeline(store: &PipelineStore, name: &str) -> Result<PipelineDefinition> {
+    if name == COMMIT_PIPELINE && !store.dir().join(format!("{}.toml", name)).is_file() {
+        return Ok(commit_pipeline());
+    }
+    store.load(name)
+}
+
+/// A commit message in the Conventional Commits format
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConventionalCommit {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub scope: Option<String>,
+    pub subject: String,
+    pub body: Option<String>,
+    /// Description from the `BREAKING CHANGE:` footer, or the subject for a bare `!`
+    pub breaking: Option<String>,
+}
+
+impl ConventionalCommit {
+    /// Parse a full commit message; `None` when the header is not conventional
+    pub fn parse(message: &str) -> Option<Self> {
+        let mut lines = message.lines();
+        let header = lines.next()?.trim();
+        let (prefix, subject) = header.split_once(':')?;
+        let subject = subject.trim();
+        let (prefix, bang) = match prefix.strip_suffix('!') {
+            Some(prefix) => (prefix, true),
+            None => (prefix, false),
+        };
+        let (kind, scope) = match prefix.split_once('(') {
+            Some((kind, scope)) => (kind, Some(scope.strip_suffix(')')?.trim())),
+            None => (prefix, None),
+        };
+        let kind = kind.trim().to_lowercase();
+        if subject.is_empty() || kind.is_empty() || !kind.chars().all(|c| c.is_ascii_lowercase()) {
+            return None;
+        }
+
+        let mut body = Vec::new();
+        let mut breaking = None;
+        for line in lines {
+            let footer = line
+                .strip_prefix(BREAKING_FOOTER)
+                .or_else(|| line.strip_prefix("BREAKING-CHANGE:"));
+            if let Some(description) = footer {
+                breaking = Some(description.trim().to_string());
+            } else if let Some(description) = breaking.as_mut() {
+                // Continuation lines of the footer
+                if !line.trim().is_empty() {
+                    description.push(' ');
+                    description.push_str(line.trim());
+                }
+            } else {
+                body.push(line);
+            }
+        }
+        let body = body.join("\n").trim().to_string();
+        if bang && breaking.is_none() {
+            breaking = Some(subject.to_string());
+        }
+
+        Some(Self {
+            kind,
+            scope: scope.filter(|s| !s.is_empty()).map(str::to_string),
+            subject: subject.to_string(),
+            body: (!body.is_empty()).then_some(body),
+            breaking,
+        })
+    }
+
+    pub fn header(&self) -> String {
+        format!(
+            "{}{}{}: {}",
+            self.kind,
+            self.scope
+                .as_ref()
+                .map(|scope| format!("({})", scope))
+                .unwrap_or_default(),
+            if self.breaking.is_some() { "!" } else { "" },
+            self.subject
+        )
+    }
+}
+
+impl fmt::Display for ConventionalCommit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.header())?;
+        if let Some(body) = &self.body {
+            write!(f, "\n\n{}", body)?;
+        }
+        if let Some(breaking) = &self.breaking {
+            write!(f, "\n\n{} {}", BREAKING_FOOTER, breaking)?;
+        }
+        Ok(())
+    }
+}
+
+/// Module a path belongs to: the first directory below `src/`, or the file stem
+fn module_of(path: &Path) -> Option<String> {
+    let parts: Vec<&str> = path
+        .components()
+        .filter_map(|component| match component {
+            Component::Normal(part) => part.to_str(),
+            _ => None,
+        })
+        .collect();
+    let parts = match parts.iter().position(|part| *part == "src") {
+        Some(src) if src + 1 < parts.len() => &parts[src + 1..],
+        _ => &parts[..],
+    };
+    match parts {
+        [] => None,
+        [file] => {
+            let stem = Path::new(file).file_stem()?.to_str()?;
+            match stem {
+                "lib" | "main" | "mod" => None,
+                _ if file.ends_with(".md") => Some("docs".to_string()),
+                _ if file.starts_with('.') || stem.eq_ignore_ascii_case("cargo") => None,
+                _ => Some(stem.to_lowercase()),
+            }
+        }
+        [dir, ..] => Some(dir.to_string()),
+    }
+}
+
+/// Scope for a set of changed files: their module when they all share one
+pub fn infer_scope(files: &[FileDiff]) -> Option<String> {
+    let modules: BTreeSet<Option<String>> =
+        files.iter().map(|file| module_of(&file.path)).collect();
+    match modules.into_iter().collect::<Vec<_>>().as_slice() {
+        [Some(module)] => Some(module.clone()),
+        _ => None,
+    }
+}
+
+/// Prompt asking for a commit message for `files`
+///
+/// Diffs beyond the line budget are reduced to per-file change counts, which
+/// keeps large commits within a cheap model's context window.
+pub fn commit_prompt(files: &[FileDiff], scope: Option<&str>) -> String {
+    let mut prompt = String::from("Write the commit message for these staged changes.\n");
+    match scope {
+        Some(scope) => prompt.push_str(&format!(
+            "All changes are in the `{}` module; use it as the scope.\n",
+            scope
+        )),
+        None => prompt
+            .push_str("The changes span several modules; omit the scope or pick the main one.\n"),
+    }
+
+    let mut budget = MAX_PROMPT_LINES;
+    let mut summarized = Vec::new();
+    for file in files {
+        let (added, removed) = change_counts(file);
+        let lines: usize = file.hunks.iter().map(|hunk| hunk.lines.len() + 1).sum();
+        if lines > budget {
+            summarized.push(format!(
+                "- {} ({}, +{} -{})",
+                file.path.display(),
+                status_name(file.status),
+                added,
+                removed
+            ));
+            continue;
+        }
+        budget -= lines;
+
+        prompt.push_str(&format!(
+            "\n### {} ({})\n```diff\n",
+            file.path.display(),
+            status_name(file.status)
+        ));
+        for hunk in &file.hunks {
+            prompt.push_str(&hunk.header);
+            prompt.push('\n');
+            for line in &hunk.lines {
+                let marker = match line.kind {
+                    LineKind::Added => '+',
+                    LineKind::Removed => '-',
+                    LineKind::Context => ' ',
+                };
+                prompt.push(marker);
+                prompt.push_str(&line.content);
+                prompt.push('\n');
+            }
+        }
+        prompt.push_str("```\n");
+    }
+    if !summarized.is_empty() {
+        prompt.push_str("\nOther changed files (diff omitted):\n");
+        prompt.push_str(&summarized.join("\n"));
+        prompt.push('\n');
+    }
+    prompt
+}
+
+fn change_counts(file: &FileDiff) -> (usize, usize) {
+    let lines = file.hunks.iter().flat_map(|hunk| &hunk.lines);
+    lines.fold((0, 0), |(added, removed), line| match line.kind {
+        LineKind::Added => (added + 1, removed),
+        LineKind::Removed => (added, removed + 1),
+        LineKind::Context => (added, removed),
+    })
+}
+
+fn status_name(status: FileStatus) -> &'static str {
+    match status {
+        FileStatus::Added => "added",
+        FileStatus::Modified => "modified",
+        FileStatus::Deleted => "deleted",
+        FileStatus::Renamed => "renamed",
+    }
+}
+
+/// Parse the model's answer, filling in the inferred scope when it gave none
+pub fn parse_commit_message(answer: &str, scope: Option<&str>) -> Result<ConventionalCommit> {
+    // Models sometimes wrap the message in a code fence or lead with prose
+    let text: String = answer
+        .lines()
+        .filter(|line| !line.trim_start().starts_with("```"))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let lines: Vec<&str> = text.lines().collect();
+    let start = lines
+        .iter()
+        .position(|line| ConventionalCommit::parse(line).is_some())
+        .context("The answer contains no Conventional Commits header")?;
+    let mut commit = ConventionalCommit::parse(&lines[start..].join("\n"))
+        .context("The answer contains no Conventional Commits header")?;
+
+    if !COMMIT_TYPES.contains(&commit.kind.as_str()) {
+        bail!("Unknown commit type '{}'", commit.kind);
+    }
+    if commit.scope.is_none() {
+        commit.scope = scope.map(str::to_string);
+    }
+    Ok(commit)
+}
+
+/// Commit the index with `message` through `git commit`
+///
+/// Going through git rather than libgit2 runs the repository's commit hooks
+/// and honours `commit.gpgsign`, like any other commit would.
+pub fn create_commit(repo: &Repository, message: &str) -> Result<Oid> {
+    let workdir = repo
+        .workdir()
+        .context("Cannot commit in a bare repository")?;
+    let mut file = tempfile::NamedTempFile::new()?;
+    file.write_all(message.as_bytes())?;
+    file.flush()?;
+
+    let output = Command::new("git")
+        .arg("commit")
+        .arg("--file")
+        .arg(file.path())
+        .current_dir(workdir)
+        .output()
+        .context("Failed to run git commit")?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        bail!(
+            "git commit failed: {}",
+            if stderr.trim().is_empty() {
+                stdout.trim()
+            } else {
+                stderr.trim()
+            }
+        );
+    }
+    Ok(repo.head()?.peel_to_commit()?.id())
+}
+
+/// One commit of a changelog range
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChangelogEntry {
+    /// Abbreviated commit id
+    pub id: String,
+    pub summary: String,
+    /// Parsed message; `None` for commits that do not follow the convention
+    pub conventional: Option<ConventionalCommit>,
+}
+
+/// Commits reachable from `to` but not from `from`, oldest first, skipping merges
+pub fn commits_in_range(repo: &Repository, from: &str, to: &str) -> Result<Vec<ChangelogEntry>> {
+    let resolve = |rev: &str| -> Result<Oid> {
+        Ok(repo
+            .revparse_single(rev)
+            .with_context(|| format!("Unknown revision '{}'", rev))?
+            .peel_to_commit()?
+            .id())
+    };
+    let mut walk = repo.revwalk()?;
+    walk.set_sorting(Sort::TOPOLOGICAL | Sort::REVERSE)?;
+    walk.push(resolve(to)?)?;
+    walk.hide(resolve(from)?)?;
+
+    let mut entries = Vec::new();
+    for id in walk {
+        let commit = repo.find_commit(id?)?;
+        if commit.parent_count() > 1 {
+            continue;
+        }
+        let message = commit.message().unwrap_or_default();
+        entries.push(ChangelogEntry {
+            id: commit.id().to_string()[..7].to_string(),
+            summary: message
+                .lines()
+                .next()
+                .unwrap_or_default()
+                .trim()
+                .to_string(),
+            conventional: ConventionalCommit::parse(message)
+                .filter(|commit| COMMIT_TYPES.contains(&commit.kind.as_str())),
+        });
+    }
+    Ok(entries)
+}
+
+/// Changelog sections in display order, with the commit types they collect
+const SECTIONS: &[(&str, &[&str])] = &[
+    ("Features", &["feat"]),
+    ("Bug Fixes", &["fix"]),
+    ("Performance", &["perf"]),
+    ("Refactoring", &["refactor"]),
+    ("Documentation", &["docs"]),
+    ("Reverts", &["revert"]),
+];
+
+/// Render `entries` as a Markdown changelog under `title`
+///
+/// Breaking changes are listed first; types without a section of their own
+/// and unconventional commits end up under "Other Changes".
+pub fn render_changelog(title: &str, entries: &[ChangelogEntry]) -> String {
+    let item = |entry: &ChangelogEntry, text: &str, scope: Option<&str>| match scope {
+        Some(scope) => format!("- **{}:** {} ({})\n", scope, text, entry.id),
+        None => format!("- {} ({})\n", text, entry.id),
+    };
+
+    let mut markdown = format!("## {}\n", title);
+    let breaking: Vec<String> = entries
+        .iter()
+        .filter_map(|entry| {
+            let commit = entry.conventional.as_ref()?;
+            let description = commit.breaking.as_deref()?;
+            Some(item(entry, description, commit.scope.as_deref()))
+        })
+        .collect();
+    if !breaking.is_empty() {
+        markdown.push_str("\n### ⚠ Breaking Changes\n\n");
+        markdown.push_str(&breaking.concat());
+    }
+
+    for (heading, kinds) in SECTIONS {
+        let items: Vec<String> = entries
+            .iter()
+            .filter_map(|entry| {
+                let commit = entry.conventional.as_ref()?;
+                kinds
+                    .contains(&commit.kind.as_str())
+                    .then(|| item(entry, &commit.subject, commit.scope.as_deref()))
+            })
+            .collect();
+        if !items.is_empty() {
+            markdown.push_str(&format!("\n### {}\n\n", heading));
+            markdown.push_str(&items.concat());
+        }
+    }
+
+    let other: Vec<String> = entries
+        .iter()
+        .filter(|entry| match &entry.conventional {
+            Some(commit) => !SECTIONS
+                .iter()
+                .any(|(_, kinds)| kinds.contains(&commit.kind.as_str())),
+            None => true,
+        })
+        .map(|entry| match &entry.conventional {
+            Some(commit) => item(entry, &commit.subject, commit.scope.as_deref()),
+            None => item(entry, &entry.summary, None),
+        })
+        .collect();
+    if !other.is_empty() {
+        markdown.push_str("\n### Other Changes\n\n");
+        markdown.push_str(&other.concat());
+    }
+    markdown
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consensus::review::{diff_files, DiffSpec};
+    use std::path::PathBuf;
+    use tempfile::TempDir;
+
+    fn file(path: &str) -> FileDiff {
+        FileDiff {
+            path: PathBuf::from(path),
+            status: FileStatus::Modified,
+            hunks: Vec::new(),
+            new_content: None,
+        }
+    }
+
+    fn test_repo() -> (TempDir, Repository) {
+        let dir = TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let mut config = repo.config().unwrap();
+        config.set_str("user.name", "Test").unwrap();
+        config.set_str("user.email", "test@example.com").unwrap();
+        config.set_bool("commit.gpgsign", false).unwrap();
+        (dir, repo)
+    }
+
+    fn stage(repo: &Repository, path: &str, content: &str) {
+        std::fs::write(repo.workdir().unwrap().join(path), content).unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new(path)).unwrap();
+        index.write().unwrap();
+    }
+
+    #[test]
+    fn test_parse_and_render() {
+        let commit = ConventionalCommit::parse(
+            "feat(cli)!: add hive commit\n\nProposes a message.\n\nBREAKING CHANGE: drops --old\nflag",
+        )
+        .unwrap();
+        assert_eq!(commit.kind, "feat");
+        assert_eq!(commit.scope.as_deref(), Some("cli"));
+        assert_eq!(commit.body.as_deref(), Some("Proposes a message."));
+        assert_eq!(commit.breaking.as_deref(), Some("drops --old flag"));
+        assert_eq!(
+            commit.to_string(),
+            "feat(cli)!: add hive commit\n\nProposes a message.\n\nBREAKING CHANGE: drops --old flag"
+        );
+
+        let bare = ConventionalCommit::parse("refactor!: rename module").unwrap();
+        assert_eq!(bare.breaking.as_deref(), Some("rename module"));
+        assert!(ConventionalCommit::parse("Update README").is_none());
+        assert!(ConventionalCommit::parse("Merge branch 'x': y").is_none());
+    }
+
+    #[test]
+    fn test_infer_scope() {
+        assert_eq!(
+            infer_scope(&[
+                file("src/consensus/review.rs"),
+                file("src/consensus/mod.rs")
+            ]),
+            Some("consensus".to_string())
+        );
+        assert_eq!(
+            infer_scope(&[file("README.md"), file("API_REFERENCE.md")]),
+            Some("docs".to_string())
+        );
+        assert_eq!(
+            infer_scope(&[file("src/cli/args.rs"), file("src/commands/review.rs")]),
+            None
+        );
+        assert_eq!(infer_scope(&[file("Cargo.toml")]), None);
+    }
+
+    #[test]
+    fn test_parse_model_answer() {
+        let answer =
+            "Here is the message:\n```\nfix: handle empty diffs\n\nSkip files without hunks.\n```";
+        let commit = parse_commit_message(answer, Some("review")).unwrap();
+        assert_eq!(commit.header(), "fix(review): handle empty diffs");
+        assert_eq!(commit.body.as_deref(), Some("Skip files without hunks."));
+
+        assert!(parse_commit_message("wip: stuff", None).is_err());
+        assert!(parse_commit_message("I could not tell what changed.", None).is_err());
+    }
+
+    #[test]
+    fn test_commit_staged_changes_and_changelog() {
+        let (_dir, repo) = test_repo();
+        std::fs::create_dir(repo.workdir().unwrap().join("src")).unwrap();
+
+        stage(&repo, "src/lib.rs", "pub fn one() {}\n");
+        let files = diff_files(&repo, &DiffSpec::Staged, 3).unwrap();
+        assert_eq!(files.len(), 1);
+        let prompt = commit_prompt(&files, infer_scope(&files).as_deref());
+        assert!(prompt.contains("+pub fn one() {}"));
+        create_commit(&repo, "chore: initial import").unwrap();
+        let base = repo
+            .head()
+            .unwrap()
+            .peel_to_commit()
+            .unwrap()
+            .id()
+            .to_string();
+
+        for (content, message) in [
+            (
+                "pub fn two() {}\n",
+                "feat(api): add two\n\nBREAKING CHANGE: one is gone",
+            ),
+            ("pub fn three() {}\n", "fix: correct three"),
+            ("pub fn four() {}\n", "Tweak things"),
+        ] {
+            stage(&repo, "src/lib.rs", content);
+            create_commit(&repo, message).unwrap();
+        }
+        assert!(diff_files(&repo, &DiffSpec::Staged, 3).unwrap().is_empty());
+
+        let entries = commits_in_range(&repo, &base, "HEAD").unwrap();
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].summary, "feat(api): add two");
+
+        let markdown = render_changelog("v1.1.0", &entries);
+        assert!(markdown.starts_with("## v1.1.0\n"));
+        assert!(markdown.contains("### ⚠ Breaking Changes\n\n- **api:** one is gone"));
+        assert!(markdown.contains("### Features\n\n- **api:** add two"));
+        assert!(markdown.contains("### Bug Fixes\n\n- correct three"));
+        assert!(markdown.contains("### Other Changes\n\n- Tweak things"));
+        assert!(!markdown.contains("initial import"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_commit_runs_hooks() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let (_dir, repo) = test_repo();
+        let hook = repo.path().join("hooks").join("commit-msg");
+        std::fs::create_dir_all(hook.parent().unwrap()).unwrap();
+        std::fs::write(
+            &hook,
+            "#!/bin/sh\nprintf '\\nReviewed-by: hook\\n' >> \"$1\"\n",
+        )
+        .unwrap();
+        std::fs::set_permissions(&hook, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        stage(&repo, "README.md", "hello\n");
+        let id = create_commit(&repo, "docs: add readme").unwrap();
+        let message = repo.find_commit(id).unwrap().message().unwrap().to_string();
+        assert!(message.starts_with("docs: add readme"));
+        assert!(message.contains("Reviewed-by: hook"));
+
+        // A rejecting hook stops the commit
+        std::fs::write(&hook, "#!/bin/sh\necho 'rejected by policy' >&2\nexit 1\n").unwrap();
+        stage(&repo, "README.md", "hello again\n");
+        let error = create_commit(&repo, "docs: update readme").unwrap_err();
+        assert!(error.to_string().contains("rejected by policy"));
+    }
+}
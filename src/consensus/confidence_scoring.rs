@@ -709,6 +709,62 @@ impl ConfidenceScoringEngine {
         format!("score_{:x}", hasher.finish())
     }
 
+    /// Score one of several candidate answers to the same question (0-100).
+    /// Agreement with the other candidates stands in for the historical
+    /// component and contradicted repository facts for pattern risk.
+    pub fn score_candidate_answer(
+        &self,
+        answer: &str,
+        agreement: f64,
+        contradictions: usize,
+    ) -> f32 {
+        let weights = &self.component_weights;
+        let agreement_component = (agreement.clamp(0.0, 1.0) * 100.0) as f32;
+        let pattern_component = (100.0 - 25.0 * contradictions as f32).max(0.0);
+
+        // Structure: code blocks, headings and lists make an answer easier to verify
+        let mut quality_component: f32 = 50.0;
+        if answer.contains("```") {
+            quality_component += 20.0;
+        }
+        if answer
+            .lines()
+            .any(|line| line.trim_start().starts_with('#'))
+        {
+            quality_component += 15.0;
+        }
+        if answer
+            .lines()
+            .any(|line| line.trim_start().starts_with("- ") || line.trim_start().starts_with("* "))
+        {
+            quality_component += 15.0;
+        }
+
+        // Feasibility: a substantive answer that does not hedge
+        let lower = answer.to_lowercase();
+        let hedges = [
+            "i'm not sure",
+            "i am not sure",
+            "i don't know",
+            "cannot determine",
+        ]
+        .iter()
+        .filter(|hedge| lower.contains(*hedge))
+        .count();
+        let length_score = (answer.len() as f32 / 800.0).min(1.0) * 100.0;
+        let feasibility_component = (length_score - 30.0 * hedges as f32).max(0.0);
+
+        let total = weights.historical + weights.pattern + weights.quality + weights.feasibility;
+        if total <= 0.0 {
+            return agreement_component;
+        }
+        (agreement_component * weights.historical
+            + pattern_component * weights.pattern
+            + quality_component * weights.quality
+            + feasibility_component * weights.feasibility)
+            / total
+    }
+
     pub async fn update_weights(&mut self, new_weights: ScoringWeights) {
         self.component_weights = new_weights;
         self.score_cache.write().await.clear();
@@ -0,0 +1,679 @@
+// Context budget - fit each stage's context into the window of the model running it
+// Sources are filled by priority; overflow is condensed, truncated or dropped and reported
+
+use crate::consensus::models::ModelManager;
+use crate::core::config::get_hive_config_dir;
+use crate::core::database::DatabaseManager;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tiktoken_rs::CoreBPE;
+use tokenizers::Tokenizer;
+
+/// Window assumed for models missing from `openrouter_models` and the fallback table
+pub const DEFAULT_CONTEXT_WINDOW: usize = 8_192;
+
+/// Tokens kept free for stage system prompts and message framing
+const PROMPT_OVERHEAD_TOKENS: usize = 1_024;
+
+/// Below this many tokens a truncated section is more noise than signal
+const MIN_SECTION_TOKENS: usize = 64;
+
+/// Tokenizer used by a model family; decides how text is counted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TokenizerFamily {
+    /// GPT-4o, o1 and later OpenAI models
+    O200k,
+    /// GPT-3.5 and GPT-4
+    Cl100k,
+    Claude,
+    Llama,
+    Gemini,
+    Mistral,
+    Generic,
+}
+
+impl TokenizerFamily {
+    /// Detect the family from an OpenRouter model id such as `anthropic/claude-3-opus`
+    pub fn for_model(model: &str) -> Self {
+        let model = model.to_lowercase();
+        let name = model.rsplit('/').next().unwrap_or(&model);
+        if name.starts_with("gpt-4o") || name.starts_with("o1") || name.starts_with("o3") {
+            Self::O200k
+        } else if name.starts_with("gpt-") || model.starts_with("openai/") {
+            Self::Cl100k
+        } else if name.contains("claude") {
+            Self::Claude
+        } else if name.contains("llama") {
+            Self::Llama
+        } else if name.contains("gemini") || name.contains("gemma") {
+            Self::Gemini
+        } else if name.contains("mistral") || name.contains("mixtral") || name.contains("codestral")
+        {
+            Self::Mistral
+        } else {
+            Self::Generic
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::O200k => "o200k",
+            Self::Cl100k => "cl100k",
+            Self::Claude => "claude",
+            Self::Llama => "llama",
+            Self::Gemini => "gemini",
+            Self::Mistral => "mistral",
+            Self::Generic => "generic",
+        }
+    }
+
+    /// Average characters per token on mixed English prose and code
+    fn chars_per_token(&self) -> f64 {
+        match self {
+            Self::O200k => 4.2,
+            Self::Cl100k => 3.9,
+            Self::Claude => 3.5,
+            Self::Llama => 3.8,
+            Self::Gemini => 4.0,
+            Self::Mistral => 3.4,
+            Self::Generic => 3.5,
+        }
+    }
+}
+
+/// How a counter turns text into tokens
+#[derive(Clone)]
+enum Encoder {
+    /// Hugging Face tokenizer from the override directory
+    HuggingFace(Arc<Tokenizer>),
+    /// OpenAI BPE ranks bundled with the binary
+    Bpe(Arc<CoreBPE>),
+    /// Characters-per-token estimate
+    Estimate,
+}
+
+static ENCODERS: Lazy<Mutex<HashMap<TokenizerFamily, (Encoder, bool)>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Directory searched for `<family>.json` Hugging Face tokenizers
+pub fn tokenizer_override_dir() -> std::path::PathBuf {
+    get_hive_config_dir().join("tokenizers")
+}
+
+/// Counts tokens for one tokenizer family
+///
+/// A Hugging Face tokenizer at `~/.hive/tokenizers/<family>.json` takes
+/// precedence. Otherwise OpenAI families are counted exactly with the bundled
+/// o200k and cl100k BPE ranks, and other families are approximated with
+/// cl100k, since their own vocabularies are not published or not bundled.
+#[derive(Clone)]
+pub struct TokenCounter {
+    family: TokenizerFamily,
+    encoder: Encoder,
+    exact: bool,
+}
+
+impl TokenCounter {
+    pub fn new(family: TokenizerFamily) -> Self {
+        let (encoder, exact) = ENCODERS
+            .lock()
+            .unwrap()
+            .entry(family)
+            .or_insert_with(|| Self::load_encoder(family))
+            .clone();
+        Self {
+            family,
+            encoder,
+            exact,
+        }
+    }
+
+    /// Counter that only estimates from character counts
+    pub fn estimating(family: TokenizerFamily) -> Self {
+        Self {
+            family,
+            encoder: Encoder::Estimate,
+            exact: false,
+        }
+    }
+
+    /// The encoder for `family` and whether it is the model's own tokenizer
+    fn load_encoder(family: TokenizerFamily) -> (Encoder, bool) {
+        let path = tokenizer_override_dir().join(format!("{}.json", family.as_str()));
+        if path.exists() {
+            match Tokenizer::from_file(&path) {
+                Ok(tokenizer) => return (Encoder::HuggingFace(Arc::new(tokenizer)), true),
+                Err(e) => tracing::warn!("Failed to load tokenizer {}: {}", path.display(), e),
+            }
+        }
+
+        let bpe = match family {
+            TokenizerFamily::O200k => tiktoken_rs::o200k_base(),
+            _ => tiktoken_rs::cl100k_base(),
+        };
+        match bpe {
+            Ok(bpe) => {
+                let exact = matches!(family, TokenizerFamily::O200k | TokenizerFamily::Cl100k);
+                if !exact {
+                    tracing::debug!(
+                        "No {} tokenizer in {}; approximating with cl100k",
+                        family.as_str(),
+                        tokenizer_override_dir().display()
+                    );
+                }
+                (Encoder::Bpe(Arc::new(bpe)), exact)
+            }
+            Err(e) => {
+                tracing::warn!("Failed to load bundled BPE ranks: {}", e);
+                (Encoder::Estimate, false)
+            }
+        }
+    }
+
+    pub fn family(&self) -> TokenizerFamily {
+        self.family
+    }
+
+    /// True when counts come from the model family's own tokenizer
+    pub fn is_exact(&self) -> bool {
+        self.exact
+    }
+
+    pub fn count(&self, text: &str) -> usize {
+        if text.is_empty() {
+            return 0;
+        }
+        match &self.encoder {
+            Encoder::HuggingFace(tokenizer) => {
+                if let Ok(encoding) = tokenizer.encode(text, false) {
+                    return encoding.len();
+                }
+            }
+            Encoder::Bpe(bpe) => return bpe.encode_ordinary(text).len(),
+            Encoder::Estimate => {}
+        }
+
+        // CJK and other wide scripts run close to one token per character
+        let (mut narrow, mut wide) = (0usize, 0usize);
+        for c in text.chars() {
+            if (c as u32) < 0x2E80 {
+                narrow += 1;
+            } else {
+                wide += 1;
+            }
+        }
+        (narrow as f64 / self.family.chars_per_token()).ceil() as usize + wide
+    }
+}
+
+/// Context window, tokenizer and pricing of the model running a stage
+#[derive(Debug, Clone)]
+pub struct ModelLimits {
+    pub model: String,
+    pub context_window: usize,
+    pub family: TokenizerFamily,
+    /// Price per prompt token in USD, when known
+    pub pricing_input: Option<f64>,
+    /// Price per completion token in USD, when known
+    pub pricing_output: Option<f64>,
+}
+
+impl ModelLimits {
+    /// Look the model up in `openrouter_models`, falling back to known windows
+    pub async fn lookup(database: Option<&DatabaseManager>, model: &str) -> Self {
+        if let Some(db) = database {
+            match ModelManager::new(None)
+                .get_model_by_openrouter_id(db, model)
+                .await
+            {
+                Ok(Some(info)) if info.context_window > 0 => {
+                    return Self {
+                        model: model.to_string(),
+                        context_window: info.context_window as usize,
+                        family: TokenizerFamily::for_model(model),
+                        pricing_input: info.pricing_input,
+                        pricing_output: info.pricing_output,
+                    };
+                }
+                Ok(_) => tracing::debug!("No context window stored for {}", model),
+                Err(e) => tracing::warn!("Failed to look up limits for {}: {}", model, e),
+            }
+        }
+        Self::fallback(model)
+    }
+
+    /// Limits from the model name alone
+    pub fn fallback(model: &str) -> Self {
+        let family = TokenizerFamily::for_model(model);
+        let name = model.to_lowercase();
+        let context_window = match family {
+            TokenizerFamily::Claude if name.contains("claude-2") => 100_000,
+            TokenizerFamily::Claude => 200_000,
+            TokenizerFamily::O200k => 128_000,
+            TokenizerFamily::Cl100k if name.contains("gpt-4-turbo") => 128_000,
+            TokenizerFamily::Cl100k if name.contains("gpt-4-32k") => 32_768,
+            TokenizerFamily::Cl100k if name.contains("gpt-4") => 8_192,
+            TokenizerFamily::Cl100k => 16_385,
+            TokenizerFamily::Gemini if name.contains("gemma") => 8_192,
+            TokenizerFamily::Gemini => 1_000_000,
+            TokenizerFamily::Llama if name.contains("llama-3.1") || name.contains("llama-3.3") => {
+                128_000
+            }
+            TokenizerFamily::Llama => 8_192,
+            TokenizerFamily::Mistral => 32_768,
+            TokenizerFamily::Generic => DEFAULT_CONTEXT_WINDOW,
+        };
+        Self {
+            model: model.to_string(),
+            context_window,
+            family,
+            pricing_input: None,
+            pricing_output: None,
+        }
+    }
+
+    /// Tokens to leave free for the answer: a quarter of the window, at most 8k
+    pub fn reserved_output_tokens(&self) -> usize {
+        (self.context_window / 4).min(8_192)
+    }
+
+    /// Cost of a call, when pricing is known
+    pub fn estimate_cost(&self, prompt_tokens: usize, completion_tokens: usize) -> Option<f64> {
+        let input = self.pricing_input?;
+        let output = self.pricing_output.unwrap_or(input);
+        Some(prompt_tokens as f64 * input + completion_tokens as f64 * output)
+    }
+
+    /// The tighter of two limits, for stages that fan out to several models
+    pub fn min(self, other: Self) -> Self {
+        if other.context_window < self.context_window {
+            other
+        } else {
+            self
+        }
+    }
+}
+
+/// How important a context source is; higher priorities are filled first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ContextPriority {
+    Low,
+    Normal,
+    High,
+    Critical,
+}
+
+/// One labelled block of stage context
+#[derive(Debug, Clone)]
+pub struct ContextSection {
+    pub name: String,
+    pub priority: ContextPriority,
+    pub content: String,
+}
+
+impl ContextSection {
+    pub fn new(name: impl Into<String>, priority: ContextPriority, content: String) -> Self {
+        Self {
+            name: name.into(),
+            priority,
+            content,
+        }
+    }
+}
+
+/// What happened to a section that did not fit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OverflowAction {
+    /// Reduced to headings and the first sentence of each paragraph
+    Condensed,
+    /// Cut at a line boundary
+    Truncated,
+    Dropped,
+}
+
+/// A section that lost tokens to the budget
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DroppedContext {
+    pub source: String,
+    pub action: OverflowAction,
+    #[serde(rename = "originalTokens")]
+    pub original_tokens: usize,
+    #[serde(rename = "keptTokens")]
+    pub kept_tokens: usize,
+}
+
+/// Token accounting for one stage's context, reported in the stage analytics
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContextReport {
+    #[serde(rename = "contextWindow")]
+    pub context_window: usize,
+    /// Tokens available to context after the prompt, overhead and output reserve
+    pub budget: usize,
+    pub used: usize,
+    pub tokenizer: String,
+    /// Counts come from a related tokenizer or a character estimate rather
+    /// than the model family's own tokenizer
+    #[serde(default)]
+    pub estimated: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dropped: Vec<DroppedContext>,
+}
+
+/// Stage context fitted to a model's window
+#[derive(Debug, Clone)]
+pub struct AssembledContext {
+    pub text: String,
+    pub report: ContextReport,
+}
+
+/// Allocates a model's context window across context sources
+pub struct ContextBudget {
+    limits: ModelLimits,
+    counter: TokenCounter,
+}
+
+impl ContextBudget {
+    pub fn new(limits: ModelLimits) -> Self {
+        let counter = TokenCounter::new(limits.family);
+        Self { limits, counter }
+    }
+
+    pub fn with_counter(mut self, counter: TokenCounter) -> Self {
+        self.counter = counter;
+        self
+    }
+
+    pub fn limits(&self) -> &ModelLimits {
+        &self.limits
+    }
+
+    pub fn counter(&self) -> &TokenCounter {
+        &self.counter
+    }
+
+    /// Tokens left for context once the prompt, overhead and output are reserved
+    pub fn available(&self, prompt_tokens: usize) -> usize {
+        self.limits
+            .context_window
+            .saturating_sub(self.limits.reserved_output_tokens())
+            .saturating_sub(PROMPT_OVERHEAD_TOKENS)
+            .saturating_sub(prompt_tokens)
+    }
+
+    /// Fit `sections` around a prompt of `prompt` text, keeping their original order
+    pub fn assemble(&self, prompt: &str, sections: Vec<ContextSection>) -> AssembledContext {
+        let budget = self.available(self.counter.count(prompt));
+        let separator_tokens = self.counter.count("\n\n");
+
+        let mut order: Vec<usize> = (0..sections.len()).collect();
+        order.sort_by_key(|&i| std::cmp::Reverse(sections[i].priority));
+
+        let mut kept: Vec<Option<String>> = vec![None; sections.len()];
+        let mut dropped = Vec::new();
+        let mut remaining = budget;
+
+        for i in order {
+            let section = &sections[i];
+            let tokens = self.counter.count(&section.content);
+            let available = remaining.saturating_sub(separator_tokens);
+
+            if tokens <= available {
+                remaining = available - tokens;
+                kept[i] = Some(section.content.clone());
+                continue;
+            }
+
+            let fitted = if available >= MIN_SECTION_TOKENS {
+                self.fit(&section.content, available)
+            } else {
+                None
+            };
+            match fitted {
+                Some((text, action)) => {
+                    let kept_tokens = self.counter.count(&text);
+                    remaining = available.saturating_sub(kept_tokens);
+                    dropped.push(DroppedContext {
+                        source: section.name.clone(),
+                        action,
+                        original_tokens: tokens,
+                        kept_tokens,
+                    });
+                    kept[i] = Some(text);
+                }
+                None => dropped.push(DroppedContext {
+                    source: section.name.clone(),
+                    action: OverflowAction::Dropped,
+                    original_tokens: tokens,
+                    kept_tokens: 0,
+                }),
+            }
+        }
+
+        for entry in &dropped {
+            tracing::info!(
+                "Context budget for {}: {:?} '{}' ({} -> {} tokens)",
+                self.limits.model,
+                entry.action,
+                entry.source,
+                entry.original_tokens,
+                entry.kept_tokens
+            );
+        }
+
+        let text = kept.into_iter().flatten().collect::<Vec<_>>().join("\n\n");
+        let used = self.counter.count(&text);
+        AssembledContext {
+            text,
+            report: ContextReport {
+                context_window: self.limits.context_window,
+                budget,
+                used,
+                tokenizer: self.counter.family().as_str().to_string(),
+                estimated: !self.counter.is_exact(),
+                dropped,
+            },
+        }
+    }
+
+    /// Shrink `content` to at most `limit` tokens
+    ///
+    /// The condensed form is preferred when it fits and still fills half the
+    /// limit; otherwise a truncated prefix keeps more of the original.
+    fn fit(&self, content: &str, limit: usize) -> Option<(String, OverflowAction)> {
+        let condensed = condense(content);
+        let condensed_tokens = self.counter.count(&condensed);
+        if condensed_tokens <= limit && condensed_tokens * 2 >= limit {
+            return Some((condensed, OverflowAction::Condensed));
+        }
+
+        let marker = "\n[... truncated to fit the context window]";
+        let limit = limit.checked_sub(self.counter.count(marker))?;
+        let mut text = String::new();
+        let mut used = 0;
+        for line in content.lines() {
+            let tokens = self.counter.count(line) + 1;
+            if used + tokens > limit {
+                break;
+            }
+            used += tokens;
+            text.push_str(line);
+            text.push('\n');
+        }
+        if text.trim().is_empty() {
+            return None;
+        }
+        text.truncate(text.trim_end().len());
+        text.push_str(marker);
+        Some((text, OverflowAction::Truncated))
+    }
+}
+
+/// Extractive summary: headings, list items and each paragraph's first sentence
+pub(crate) fn condense(content: &str) -> String {
+    let mut lines = Vec::new();
+    let mut in_paragraph = false;
+    let mut in_code = false;
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with("```") {
+            in_code = !in_code;
+            continue;
+        }
+        if in_code {
+            continue;
+        }
+        if trimmed.is_empty() {
+            in_paragraph = false;
+        } else if trimmed.starts_with('#') || is_list_item(trimmed) {
+            lines.push(line.to_string());
+            in_paragraph = true;
+        } else if !in_paragraph {
+            let sentence = match trimmed.find(". ") {
+                Some(end) => &trimmed[..=end],
+                None => trimmed,
+            };
+            lines.push(sentence.to_string());
+            in_paragraph = true;
+        }
+    }
+    lines.join("\n")
+}
+
+fn is_list_item(line: &str) -> bool {
+    let numbered = line.trim_start_matches(|c: char| c.is_ascii_digit());
+    line.starts_with("- ")
+        || line.starts_with("* ")
+        || (numbered.len() < line.len() && numbered.starts_with(". "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn budget(context_window: usize) -> ContextBudget {
+        let limits = ModelLimits {
+            context_window,
+            ..ModelLimits::fallback("test/model")
+        };
+        ContextBudget::new(limits).with_counter(TokenCounter::estimating(TokenizerFamily::Generic))
+    }
+
+    #[test]
+    fn test_tokenizer_family_detection() {
+        assert_eq!(
+            TokenizerFamily::for_model("openai/gpt-4o-mini"),
+            TokenizerFamily::O200k
+        );
+        assert_eq!(
+            TokenizerFamily::for_model("openai/gpt-4-turbo"),
+            TokenizerFamily::Cl100k
+        );
+        assert_eq!(
+            TokenizerFamily::for_model("anthropic/claude-3-opus"),
+            TokenizerFamily::Claude
+        );
+        assert_eq!(
+            TokenizerFamily::for_model("meta-llama/llama-3.1-70b-instruct"),
+            TokenizerFamily::Llama
+        );
+        assert_eq!(
+            ModelLimits::fallback("google/gemini-pro-1.5").context_window,
+            1_000_000
+        );
+        assert_eq!(
+            ModelLimits::fallback("unknown/model").context_window,
+            DEFAULT_CONTEXT_WINDOW
+        );
+    }
+
+    #[test]
+    fn test_bundled_bpe_counts() {
+        let overridden = |family: TokenizerFamily| {
+            tokenizer_override_dir()
+                .join(format!("{}.json", family.as_str()))
+                .exists()
+        };
+        for family in [TokenizerFamily::O200k, TokenizerFamily::Cl100k] {
+            if !overridden(family) {
+                let counter = TokenCounter::new(family);
+                assert!(counter.is_exact());
+                assert_eq!(counter.count("hello world"), 2);
+                assert_eq!(counter.count(""), 0);
+            }
+        }
+
+        // Families without a bundled vocabulary are approximated with cl100k
+        if !overridden(TokenizerFamily::Claude) {
+            let counter = TokenCounter::new(TokenizerFamily::Claude);
+            assert!(!counter.is_exact());
+            assert_eq!(counter.count("hello world"), 2);
+        }
+    }
+
+    #[test]
+    fn test_sections_fit_untouched() {
+        let assembled = budget(32_000).assemble(
+            "question",
+            vec![
+                ContextSection::new("memory", ContextPriority::High, "remembered".into()),
+                ContextSection::new("guidance", ContextPriority::Critical, "guided".into()),
+            ],
+        );
+        assert_eq!(assembled.text, "remembered\n\nguided");
+        assert!(assembled.report.dropped.is_empty());
+        assert!(assembled.report.used <= assembled.report.budget);
+        assert!(assembled.report.estimated);
+    }
+
+    #[test]
+    fn test_overflow_follows_priority() {
+        // 4096 window leaves 2048 tokens for context after reserves
+        let budget = budget(4_096);
+        let paragraph = "A long sentence about the code. More detail follows here.\n\n";
+        let big = paragraph.repeat(200);
+        let assembled = budget.assemble(
+            "question",
+            vec![
+                ContextSection::new("semantic", ContextPriority::Low, big.clone()),
+                ContextSection::new("memory", ContextPriority::High, big),
+                ContextSection::new("guidance", ContextPriority::Critical, "guided".into()),
+            ],
+        );
+
+        let report = &assembled.report;
+        assert!(report.used <= report.budget);
+        assert!(assembled.text.ends_with("guided"));
+        let memory = report
+            .dropped
+            .iter()
+            .find(|d| d.source == "memory")
+            .unwrap();
+        assert_eq!(memory.action, OverflowAction::Condensed);
+        let semantic = report
+            .dropped
+            .iter()
+            .find(|d| d.source == "semantic")
+            .unwrap();
+        assert_ne!(semantic.action, OverflowAction::Condensed);
+        assert!(semantic.kept_tokens < memory.kept_tokens);
+    }
+
+    #[test]
+    fn test_truncation_keeps_whole_lines() {
+        let budget = budget(4_096);
+        let content = (0..2_000)
+            .map(|i| format!("line {} of the repository listing", i))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let (text, action) = budget.fit(&content, 200).unwrap();
+        assert_eq!(action, OverflowAction::Truncated);
+        assert!(text.starts_with("line 0 of"));
+        assert!(text.ends_with("fit the context window]"));
+        assert!(budget.counter().count(&text) <= 200);
+    }
+}
@@ -10,7 +10,7 @@ use crate::consensus::types::{ConsensusResult, Stage};
 use crate::consensus::verification::RepositoryFacts;
 use anyhow::{anyhow, Result};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 /// Output from a single consensus stage
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -104,6 +104,15 @@ pub enum ContradictionSeverity {
     Minor,    // Small discrepancy
 }
 
+/// How well one of several alternative answers agrees with the others
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CandidateAgreement {
+    /// Mean agreement with the other candidates (0.0-1.0)
+    pub agreement: f64,
+    /// Claims that contradict verified repository facts
+    pub contradictions: usize,
+}
+
 /// Cross-validator that verifies agreement between consensus stages
 pub struct CrossValidator {
     stage_outputs: HashMap<Stage, StageOutput>,
@@ -393,6 +402,42 @@ impl CrossValidator {
         Ok(agreement_score.max(0.0))
     }
 
+    /// Compare alternative answers to the same question: how much each one agrees
+    /// with the others, on wording and on extracted claims, and how many of its
+    /// claims contradict the repository
+    pub fn compare_candidates(&self, candidates: &[&str]) -> Result<Vec<CandidateAgreement>> {
+        let mut claims = Vec::with_capacity(candidates.len());
+        let mut contradiction_counts = Vec::with_capacity(candidates.len());
+        for candidate in candidates {
+            match self
+                .fact_checker
+                .validate_stage_output(Stage::Generator, candidate)?
+            {
+                ValidationResult::Passed {
+                    verified_claims, ..
+                } => {
+                    claims.push(verified_claims);
+                    contradiction_counts.push(0);
+                }
+                ValidationResult::Failed { contradictions, .. } => {
+                    contradiction_counts.push(contradictions.len());
+                    claims.push(contradictions.into_iter().map(|c| c.claim).collect());
+                }
+            }
+        }
+
+        let wording = candidate_agreement(candidates);
+        Ok((0..candidates.len())
+            .map(|i| CandidateAgreement {
+                agreement: match claim_agreement(i, &claims) {
+                    Some(claim_score) => (wording[i] + claim_score) / 2.0,
+                    None => wording[i],
+                },
+                contradictions: contradiction_counts[i],
+            })
+            .collect())
+    }
+
     /// Advanced contradiction detection using semantic analysis
     pub fn detect_semantic_contradictions(&self) -> Result<Vec<SemanticContradiction>> {
         let mut contradictions = Vec::new();
@@ -726,6 +771,65 @@ impl CrossValidator {
     }
 }
 
+/// Mean word overlap of each answer with the other answers (0.0-1.0)
+pub fn candidate_agreement(candidates: &[&str]) -> Vec<f64> {
+    let word_sets: Vec<HashSet<String>> = candidates
+        .iter()
+        .map(|candidate| {
+            candidate
+                .split(|c: char| !c.is_alphanumeric())
+                .filter(|word| word.len() > 3)
+                .map(str::to_lowercase)
+                .collect()
+        })
+        .collect();
+
+    (0..word_sets.len())
+        .map(|i| {
+            let others: Vec<f64> = (0..word_sets.len())
+                .filter(|&j| j != i)
+                .map(|j| {
+                    let union = word_sets[i].union(&word_sets[j]).count();
+                    if union == 0 {
+                        1.0
+                    } else {
+                        word_sets[i].intersection(&word_sets[j]).count() as f64 / union as f64
+                    }
+                })
+                .collect();
+            if others.is_empty() {
+                1.0
+            } else {
+                others.iter().sum::<f64>() / others.len() as f64
+            }
+        })
+        .collect()
+}
+
+/// Share of candidate `index`'s claims that other candidates making the same
+/// kind of claim agree with; `None` when no other candidate makes comparable claims
+fn claim_agreement(index: usize, claims: &[Vec<FactClaim>]) -> Option<f64> {
+    let mut agreeing = 0usize;
+    let mut compared = 0usize;
+    for claim in &claims[index] {
+        for (j, other_claims) in claims.iter().enumerate() {
+            if j == index {
+                continue;
+            }
+            if let Some(other) = other_claims
+                .iter()
+                .find(|other| other.claim_type == claim.claim_type)
+            {
+                compared += 1;
+                if other.value.eq_ignore_ascii_case(&claim.value) {
+                    agreeing += 1;
+                }
+            }
+        }
+    }
+    (compared > 0).then(|| agreeing as f64 / compared as f64)
+}
+
 /// Comprehensive consensus report
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConsensusReport {
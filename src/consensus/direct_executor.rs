@@ -351,6 +351,8 @@ impl DirectExecutionHandler {
                     routing_variant: "direct".to_string(),
                     optimization_applied: Some(true),
                 },
+                candidates: Vec::new(),
+                context: None,
             };
 
             let token_usage = crate::consensus::types::TokenUsage {
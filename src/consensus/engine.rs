@@ -5,7 +5,9 @@ use crate::ai_helpers::AIHelperEcosystem;
 use crate::consensus::codebase_intelligence::{
     AnalysisProgress, CodebaseCommand, CodebaseIntelligence,
 };
+use crate::consensus::context_budget::{ModelLimits, TokenCounter};
 use crate::consensus::models::ModelManager;
+use crate::consensus::openrouter::OpenRouterClient;
 use crate::consensus::pipeline::ConsensusPipeline;
 use crate::consensus::profiles::{ExpertProfileManager, TemplateFilter, TemplatePreferences};
 use crate::consensus::repository_context::RepositoryContextManager;
@@ -14,15 +16,18 @@ use crate::consensus::streaming::{
     StreamingResponse,
 };
 use crate::consensus::temporal::TemporalContextProvider;
+use crate::consensus::threads::{history_budget, Summarizer, Thread, ThreadStore};
+use crate::consensus::topology::PipelineDefinition;
 use crate::consensus::types::{
     ConsensusConfig, ConsensusProfile, ConsensusRequest, ConsensusResult, ContextInjectionStrategy,
-    ResponseMetadata, RetryPolicy,
+    Message, ResponseMetadata, RetryPolicy,
 };
 use crate::core::api_keys::ApiKeyManager;
 use crate::core::config;
 use crate::core::config::get_hive_config_dir;
-use crate::core::database::DatabaseManager;
+use crate::core::database::{get_or_initialize_database, DatabaseManager};
 use crate::core::db_actor::DatabaseService;
+use crate::hooks::HooksSystem;
 use crate::subscription::{ConversationGateway, UsageTracker};
 use anyhow::{anyhow, Context, Result};
 use chrono::Utc;
@@ -49,6 +54,8 @@ pub struct ConsensusEngine {
     license_key: Option<String>,
     last_auth_remaining: Arc<RwLock<Option<u32>>>, // Store last D1 remaining count
     ai_helpers: Arc<RwLock<Option<Arc<AIHelperEcosystem>>>>, // AI Helper Ecosystem
+    hooks_system: Arc<RwLock<Option<Arc<HooksSystem>>>>,
+    pipeline_definition: Arc<RwLock<Option<PipelineDefinition>>>,
 }
 
 impl ConsensusEngine {
@@ -215,9 +222,26 @@ impl ConsensusEngine {
             license_key,
             last_auth_remaining: Arc::new(RwLock::new(None)),
             ai_helpers: Arc::new(RwLock::new(ai_helpers)),
+            hooks_system: Arc::new(RwLock::new(None)),
+            pipeline_definition: Arc::new(RwLock::new(None)),
         })
     }
 
+    /// Set the hooks system that pipelines created by this engine dispatch to
+    pub async fn set_hooks_system(&self, hooks_system: Arc<HooksSystem>) {
+        *self.hooks_system.write().await = Some(hooks_system);
+    }
+
+    /// Set the pipeline topology to run; `None` restores the built-in 4 stages
+    pub async fn set_pipeline_definition(&self, definition: Option<PipelineDefinition>) {
+        *self.pipeline_definition.write().await = definition;
+    }
+
+    /// Get the pipeline topology in use, if one was set
+    pub async fn get_pipeline_definition(&self) -> Option<PipelineDefinition> {
+        self.pipeline_definition.read().await.clone()
+    }
+
     /// Set the repository context manager for this engine
     pub async fn set_repository_context(
         &mut self,
@@ -275,8 +299,6 @@ impl ConsensusEngine {
         // This ensures the UI updates right away when consensus starts
         tracing::info!("Starting consensus processing (D1 auth handled by pipeline)");
 
-        let config = self.config.read().await.clone();
-
         // Always load the active profile from database to ensure we use the latest selection
         let profile = match self.load_active_profile_from_db().await {
             Ok(p) => {
@@ -294,33 +316,7 @@ impl ConsensusEngine {
             }
         };
 
-        let mut pipeline = ConsensusPipeline::new(config, profile, self.openrouter_api_key.clone());
-
-        // Set database if available
-        if let Some(ref db) = self.database {
-            pipeline = pipeline.with_database(db.clone());
-        }
-
-        // Set repository context if available
-        if let Some(repo_ctx) = self.repository_context.read().await.as_ref() {
-            pipeline = pipeline.with_repository_context(repo_ctx.clone());
-        }
-
-        // Set AI helpers if available
-        if let Some(ai_helpers) = self.ai_helpers.read().await.as_ref() {
-            pipeline = pipeline.with_ai_helpers(ai_helpers.clone());
-        }
-
-        // Initialize consensus memory after AI helpers are set
-        pipeline.initialize_consensus_memory().await?;
-
-        // Configure mode detection for Claude Code-style execution
-        pipeline = pipeline.with_mode_detection().await?;
-
-        // Set codebase intelligence if available
-        if let Some(ci) = self.codebase_intelligence.read().await.as_ref() {
-            pipeline = pipeline.with_codebase_intelligence(ci.clone());
-        }
+        let pipeline = self.build_pipeline(profile, None).await?;
 
         // Run the consensus pipeline (D1 auth and verification happens inside)
         let result = pipeline
@@ -341,59 +337,9 @@ impl ConsensusEngine {
     ) -> Result<ConsensusResult> {
         // Check if this is a @codebase command
         if CodebaseIntelligence::is_codebase_command(query) {
-            tracing::info!("🔍 Detected @codebase command: {}", query);
-
-            // Handle @codebase command with a special consensus result
-            let (sender, mut receiver) = mpsc::unbounded_channel();
-
-            // Handle the codebase command
-            match self.handle_codebase_command(query, sender).await {
-                Ok(_) => {
-                    // Collect all the streaming responses into a single result
-                    let mut content = String::new();
-                    while let Some(response) = receiver.recv().await {
-                        match response {
-                            StreamingResponse::TokenReceived { token } => {
-                                content.push_str(&token);
-                            }
-                            StreamingResponse::Complete { response } => {
-                                // Return a special consensus result for @codebase
-                                return Ok(ConsensusResult {
-                                    success: true,
-                                    result: Some(response.content),
-                                    error: None,
-                                    stages: vec![],
-                                    conversation_id: uuid::Uuid::new_v4().to_string(),
-                                    total_duration: response.metadata.duration_ms as f64,
-                                    total_cost: response.metadata.cost,
-                                });
-                            }
-                            StreamingResponse::Error { error, .. } => {
-                                return Err(anyhow!("@codebase command failed: {}", error));
-                            }
-                            _ => {}
-                        }
-                    }
-
-                    // If we got here without a Complete response, return what we collected
-                    return Ok(ConsensusResult {
-                        success: true,
-                        result: Some(content),
-                        error: None,
-                        stages: vec![],
-                        conversation_id: String::new(),
-                        total_duration: 0.0,
-                        total_cost: 0.0,
-                    });
-                }
-                Err(e) => {
-                    return Err(anyhow!("Failed to process @codebase command: {}", e));
-                }
-            }
+            return self.run_codebase_command(query, None).await;
         }
 
-        let config = self.config.read().await.clone();
-
         // Always load the active profile from database to ensure we use the latest selection
         let profile = match self.load_active_profile_from_db().await {
             Ok(p) => {
@@ -425,34 +371,7 @@ impl ConsensusEngine {
             }
         };
 
-        let mut pipeline = ConsensusPipeline::new(config, profile, self.openrouter_api_key.clone())
-            .with_callbacks(callbacks);
-
-        // Set database if available
-        if let Some(ref db) = self.database {
-            pipeline = pipeline.with_database(db.clone());
-        }
-
-        // Set repository context if available
-        if let Some(repo_ctx) = self.repository_context.read().await.as_ref() {
-            pipeline = pipeline.with_repository_context(repo_ctx.clone());
-        }
-
-        // Set AI helpers if available
-        if let Some(ai_helpers) = self.ai_helpers.read().await.as_ref() {
-            pipeline = pipeline.with_ai_helpers(ai_helpers.clone());
-        }
-
-        // Initialize consensus memory after AI helpers are set
-        pipeline.initialize_consensus_memory().await?;
-
-        // Configure mode detection for Claude Code-style execution
-        pipeline = pipeline.with_mode_detection().await?;
-
-        // Set codebase intelligence if available
-        if let Some(ci) = self.codebase_intelligence.read().await.as_ref() {
-            pipeline = pipeline.with_codebase_intelligence(ci.clone());
-        }
+        let pipeline = self.build_pipeline(profile, Some(callbacks)).await?;
 
         pipeline
             .run(query, semantic_context, user_id)
@@ -471,65 +390,11 @@ impl ConsensusEngine {
     ) -> Result<ConsensusResult> {
         // Check if this is a @codebase command
         if CodebaseIntelligence::is_codebase_command(query) {
-            tracing::info!("🔍 Detected @codebase command: {}", query);
-
-            // Handle @codebase command with a special consensus result
-            let (sender, mut receiver) = mpsc::unbounded_channel();
-
-            // Handle the codebase command
-            match self.handle_codebase_command(query, sender).await {
-                Ok(_) => {
-                    // Collect all the streaming responses into a single result
-                    let mut content = String::new();
-                    while let Some(response) = receiver.recv().await {
-                        // Check for cancellation
-                        if cancellation_token.is_cancelled() {
-                            return Err(anyhow!("@codebase command was cancelled"));
-                        }
-
-                        match response {
-                            StreamingResponse::TokenReceived { token } => {
-                                content.push_str(&token);
-                            }
-                            StreamingResponse::Complete { response } => {
-                                // Return a special consensus result for @codebase
-                                return Ok(ConsensusResult {
-                                    success: true,
-                                    result: Some(response.content),
-                                    error: None,
-                                    stages: vec![],
-                                    conversation_id: uuid::Uuid::new_v4().to_string(),
-                                    total_duration: response.metadata.duration_ms as f64,
-                                    total_cost: response.metadata.cost,
-                                });
-                            }
-                            StreamingResponse::Error { error, .. } => {
-                                return Err(anyhow!("@codebase command failed: {}", error));
-                            }
-                            _ => {}
-                        }
-                    }
-
-                    // If we got here without a Complete response, return what we collected
-                    return Ok(ConsensusResult {
-                        success: true,
-                        result: Some(content),
-                        error: None,
-                        stages: vec![],
-                        conversation_id: String::new(),
-                        total_duration: 0.0,
-                        total_cost: 0.0,
-                    });
-                }
-                Err(e) => {
-                    return Err(anyhow!("@codebase command failed: {}", e));
-                }
-            }
+            return self
+                .run_codebase_command(query, Some(&cancellation_token))
+                .await;
         }
 
-        // Regular consensus processing with cancellation support
-        let config = self.config.read().await.clone();
-
         // Always load the active profile from database to ensure we use the latest selection
         let profile = match self.load_active_profile_from_db().await {
             Ok(p) => {
@@ -561,11 +426,107 @@ impl ConsensusEngine {
             }
         };
 
-        let api_key = self.openrouter_api_key.clone();
+        let pipeline = self.build_pipeline(profile, Some(callbacks)).await?;
 
-        // Create pipeline with callbacks and cancellation
-        let mut pipeline =
-            ConsensusPipeline::new(config, profile, api_key).with_callbacks(callbacks);
+        pipeline
+            .run_with_cancellation(query, semantic_context, user_id, cancellation_token)
+            .await
+            .context("Failed to run consensus pipeline with callbacks and cancellation")
+    }
+
+    /// Process a `ConsensusRequest` with callbacks and cancellation support.
+    ///
+    /// Unlike `set_profile` + `process_with_callbacks`, a `profile_override` on the
+    /// request is applied to this run only and does not change the active profile,
+    /// so concurrent API clients can target different profiles safely. With a
+    /// `conversation_id` the thread's earlier turns are replayed to every stage and
+    /// the answer is recorded as its next turn.
+    pub async fn process_request(
+        &self,
+        request: &ConsensusRequest,
+        callbacks: Arc<dyn StreamingCallbacks>,
+        cancellation_token: crate::consensus::cancellation::CancellationToken,
+    ) -> Result<ConsensusResult> {
+        if CodebaseIntelligence::is_codebase_command(&request.query) {
+            return self
+                .run_codebase_command(&request.query, Some(&cancellation_token))
+                .await;
+        }
+
+        let profile = match request.profile_override.as_deref() {
+            Some(profile_name) => self.load_profile_by_name(profile_name).await?,
+            None => match self.load_active_profile_from_db().await {
+                Ok(p) => p,
+                Err(e) => {
+                    tracing::warn!(
+                        "Failed to load active profile from database: {}, using cached profile",
+                        e
+                    );
+                    self.current_profile.read().await.clone()
+                }
+            },
+        };
+
+        let _ = callbacks.on_profile_loaded(
+            &profile.profile_name,
+            &[
+                profile.generator_model.clone(),
+                profile.refiner_model.clone(),
+                profile.validator_model.clone(),
+                profile.curator_model.clone(),
+            ],
+        );
+
+        let (thread, history) = match request.conversation_id.as_deref() {
+            Some(id) => {
+                let (store, thread, history) = self
+                    .open_thread(id, request.user_id.as_deref(), &profile)
+                    .await?;
+                (Some((store, thread)), history)
+            }
+            None => (None, Vec::new()),
+        };
+
+        let pipeline = self.build_pipeline(profile, Some(callbacks)).await?;
+
+        let result = pipeline
+            .with_history(history)
+            .run_with_cancellation(
+                &request.query,
+                request.context.clone(),
+                request.user_id.clone(),
+                cancellation_token,
+            )
+            .await
+            .context("Failed to run consensus pipeline for request")?;
+
+        if let (Some((store, mut thread)), Some(answer)) = (thread, result.result.as_deref()) {
+            let model = result.stages.last().map(|stage| stage.model.as_str());
+            // The answer is already paid for, so a failed write must not lose it
+            if let Err(e) = store.append_turn(
+                &mut thread,
+                &request.query,
+                answer,
+                model,
+                result.total_cost,
+            ) {
+                tracing::warn!("Failed to record turn in thread {}: {}", thread.id, e);
+            }
+        }
+        Ok(result)
+    }
+
+    /// Build a pipeline for `profile` wired to the engine's shared services
+    async fn build_pipeline(
+        &self,
+        profile: ConsensusProfile,
+        callbacks: Option<Arc<dyn StreamingCallbacks>>,
+    ) -> Result<ConsensusPipeline> {
+        let config = self.config.read().await.clone();
+        let mut pipeline = ConsensusPipeline::new(config, profile, self.openrouter_api_key.clone());
+        if let Some(callbacks) = callbacks {
+            pipeline = pipeline.with_callbacks(callbacks);
+        }
 
         // Set database if available
         if let Some(ref db) = self.database {
@@ -582,6 +543,16 @@ impl ConsensusEngine {
             pipeline = pipeline.with_ai_helpers(ai_helpers.clone());
         }
 
+        // Set hooks system if available
+        if let Some(hooks) = self.hooks_system.read().await.as_ref() {
+            pipeline = pipeline.with_hooks(hooks.clone());
+        }
+
+        // Set pipeline topology if configured
+        if let Some(definition) = self.pipeline_definition.read().await.as_ref() {
+            pipeline = pipeline.with_topology(definition.clone());
+        }
+
         // Initialize consensus memory after AI helpers are set
         pipeline.initialize_consensus_memory().await?;
 
@@ -593,10 +564,106 @@ impl ConsensusEngine {
             pipeline = pipeline.with_codebase_intelligence(ci.clone());
         }
 
-        pipeline
-            .run_with_cancellation(query, semantic_context, user_id, cancellation_token)
+        Ok(pipeline)
+    }
+
+    /// Answer a @codebase command, collecting its streamed output into a result
+    async fn run_codebase_command(
+        &self,
+        query: &str,
+        cancellation_token: Option<&crate::consensus::cancellation::CancellationToken>,
+    ) -> Result<ConsensusResult> {
+        tracing::info!("🔍 Detected @codebase command: {}", query);
+
+        let (sender, mut receiver) = mpsc::unbounded_channel();
+        self.handle_codebase_command(query, sender)
             .await
-            .context("Failed to run consensus pipeline with callbacks and cancellation")
+            .map_err(|e| anyhow!("Failed to process @codebase command: {}", e))?;
+
+        // Collect all the streaming responses into a single result
+        let mut content = String::new();
+        while let Some(response) = receiver.recv().await {
+            if cancellation_token.is_some_and(|token| token.is_cancelled()) {
+                return Err(anyhow!("@codebase command was cancelled"));
+            }
+
+            match response {
+                StreamingResponse::TokenReceived { token } => {
+                    content.push_str(&token);
+                }
+                StreamingResponse::Complete { response } => {
+                    // Return a special consensus result for @codebase
+                    return Ok(ConsensusResult {
+                        success: true,
+                        result: Some(response.content),
+                        error: None,
+                        stages: vec![],
+                        conversation_id: uuid::Uuid::new_v4().to_string(),
+                        total_duration: response.metadata.duration_ms as f64,
+                        total_cost: response.metadata.cost,
+                    });
+                }
+                StreamingResponse::Error { error, .. } => {
+                    return Err(anyhow!("@codebase command failed: {}", error));
+                }
+                _ => {}
+            }
+        }
+
+        // If we got here without a Complete response, return what we collected
+        Ok(ConsensusResult {
+            success: true,
+            result: Some(content),
+            error: None,
+            stages: vec![],
+            conversation_id: String::new(),
+            total_duration: 0.0,
+            total_cost: 0.0,
+        })
+    }
+
+    /// Load the thread a request continues and the history to replay from it
+    ///
+    /// The history is sized for the smallest window among the profile's models;
+    /// turns that no longer fit are summarized with the curator model.
+    async fn open_thread(
+        &self,
+        id: &str,
+        user_id: Option<&str>,
+        profile: &ConsensusProfile,
+    ) -> Result<(ThreadStore, Thread, Vec<Message>)> {
+        let database = match &self.database {
+            Some(database) => database.clone(),
+            None => get_or_initialize_database().await?,
+        };
+        let store = ThreadStore::new(database.clone())?;
+        let mut thread = store.open(id, user_id)?;
+
+        let mut limits = ModelLimits::lookup(Some(&database), &profile.generator_model).await;
+        for model in [
+            &profile.refiner_model,
+            &profile.validator_model,
+            &profile.curator_model,
+        ] {
+            limits = limits.min(ModelLimits::lookup(Some(&database), model).await);
+        }
+        let summarizer = match &self.openrouter_api_key {
+            Some(key) => Summarizer::new(
+                Arc::new(OpenRouterClient::new(key.clone())),
+                profile.curator_model.clone(),
+            ),
+            None => Summarizer::offline(),
+        };
+        let history = store
+            .prepare_history(
+                &mut thread,
+                &TokenCounter::new(limits.family),
+                history_budget(&limits),
+                &summarizer,
+            )
+            .await
+            .with_context(|| format!("Failed to load thread {}", id))?;
+        Ok((store, thread, history))
     }
 
     /// Process with streaming responses for TUI integration
@@ -1012,7 +1079,7 @@ impl ConsensusEngine {
                         |row| row.get(0),
                     )
                     .optional()?;
-                Ok(key)
+                crate::core::secrets::resolve_configuration("hive_license_key", key)
             })
             .await??;
 
@@ -0,0 +1,860 @@
+// Consensus evaluation - scores consensus profiles against golden datasets
+// Answers come from an EvalProvider, so a recorded run can be replayed offline
+
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use futures::stream::{self, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::io::Write;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use crate::consensus::cancellation::CancellationToken;
+use crate::consensus::engine::ConsensusEngine;
+use crate::consensus::fanout::SilentCallbacks;
+use crate::consensus::openrouter::{OpenRouterClient, OpenRouterMessage, OpenRouterRequest};
+use crate::consensus::types::ConsensusRequest;
+use crate::providers::openrouter::performance::{ABTestAnalysis, PerformanceTracker};
+
+/// Score a case needs to pass by default
+pub const DEFAULT_PASS_THRESHOLD: f64 = 0.7;
+
+const GRADER_SYSTEM_PROMPT: &str = "You grade answers against a rubric. Reply with \
+    'SCORE: N' where N is an integer from 0 (fails the rubric) to 10 (fully meets it), \
+    followed by one sentence of justification.";
+
+/// One question of a golden dataset and how to judge its answer
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvalCase {
+    #[serde(default)]
+    pub id: String,
+    pub question: String,
+    #[serde(default)]
+    pub context: Option<String>,
+    /// Reference answer; scored by how much of its wording the answer covers
+    #[serde(default)]
+    pub expected: Option<String>,
+    /// Terms the answer must mention
+    #[serde(default)]
+    pub keywords: Vec<String>,
+    /// Instructions for the grader model
+    #[serde(default)]
+    pub rubric: Option<String>,
+}
+
+/// Read a dataset from a JSONL file, one case per line
+pub fn load_dataset(path: &Path) -> Result<Vec<EvalCase>> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read dataset {}", path.display()))?;
+
+    let mut cases = Vec::new();
+    let mut ids = HashSet::new();
+    for (i, line) in content.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let mut case: EvalCase = serde_json::from_str(line)
+            .with_context(|| format!("{}:{}: invalid eval case", path.display(), i + 1))?;
+        if case.id.is_empty() {
+            case.id = format!("case-{}", i + 1);
+        }
+        if case.expected.is_none() && case.keywords.is_empty() && case.rubric.is_none() {
+            bail!(
+                "Eval case '{}' needs an expected answer, keywords or a rubric",
+                case.id
+            );
+        }
+        if !ids.insert(case.id.clone()) {
+            bail!("Duplicate eval case id '{}'", case.id);
+        }
+        cases.push(case);
+    }
+    if cases.is_empty() {
+        bail!("Dataset {} has no cases", path.display());
+    }
+    Ok(cases)
+}
+
+/// An answer and what it took to produce it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvalAnswer {
+    pub answer: String,
+    /// USD
+    pub cost: f64,
+    pub latency_ms: u64,
+    pub completion_tokens: u32,
+}
+
+/// Source of answers and rubric grades
+#[async_trait]
+pub trait EvalProvider: Send + Sync {
+    /// Run `case` through consensus with `profile`
+    async fn answer(&self, profile: &str, case: &EvalCase) -> Result<EvalAnswer>;
+
+    /// The grader model's reply for `answer` against the case's rubric
+    async fn grade(&self, profile: &str, case: &EvalCase, answer: &str) -> Result<String>;
+}
+
+/// Live answers from the consensus engine, graded by an OpenRouter model
+pub struct EngineProvider {
+    engine: ConsensusEngine,
+    grader: Option<(Arc<OpenRouterClient>, String)>,
+}
+
+impl EngineProvider {
+    pub fn new(engine: ConsensusEngine) -> Self {
+        Self {
+            engine,
+            grader: None,
+        }
+    }
+
+    pub fn with_grader(mut self, client: Arc<OpenRouterClient>, model: impl Into<String>) -> Self {
+        self.grader = Some((client, model.into()));
+        self
+    }
+}
+
+#[async_trait]
+impl EvalProvider for EngineProvider {
+    async fn answer(&self, profile: &str, case: &EvalCase) -> Result<EvalAnswer> {
+        let request = ConsensusRequest {
+            query: case.question.clone(),
+            context: case.context.clone(),
+            temporal_context: None,
+            profile_override: Some(profile.to_string()),
+            max_tokens: None,
+            user_id: None,
+            conversation_id: None,
+        };
+        let started = Instant::now();
+        let result = self
+            .engine
+            .process_request(
+                &request,
+                Arc::new(SilentCallbacks),
+                CancellationToken::new(),
+            )
+            .await?;
+        let latency_ms = started.elapsed().as_millis() as u64;
+
+        match result.result {
+            Some(answer) if result.success => Ok(EvalAnswer {
+                answer,
+                cost: result.total_cost,
+                latency_ms,
+                completion_tokens: result
+                    .stages
+                    .iter()
+                    .filter_map(|stage| stage.usage.as_ref())
+                    .map(|usage| usage.completion_tokens)
+                    .sum(),
+            }),
+            _ => bail!(
+                "Consensus failed: {}",
+                result.error.as_deref().unwrap_or("no result")
+            ),
+        }
+    }
+
+    async fn grade(&self, _profile: &str, case: &EvalCase, answer: &str) -> Result<String> {
+        let Some((client, model)) = &self.grader else {
+            bail!("Case '{}' has a rubric but no grader model is set", case.id);
+        };
+        let request = OpenRouterRequest {
+            model: model.clone(),
+            messages: vec![
+                OpenRouterMessage {
+                    role: "system".to_string(),
+                    content: GRADER_SYSTEM_PROMPT.to_string(),
+                },
+                OpenRouterMessage {
+                    role: "user".to_string(),
+                    content: format!(
+                        "Rubric:\n{}\n\nQuestion:\n{}\n\nAnswer:\n{}",
+                        case.rubric.as_deref().unwrap_or_default(),
+                        case.question,
+                        answer
+                    ),
+                },
+            ],
+            temperature: Some(0.0),
+            max_tokens: Some(200),
+            top_p: None,
+            frequency_penalty: None,
+            presence_penalty: None,
+            stream: None,
+            provider: None,
+        };
+        let response = client.chat_completion(request).await?;
+        Ok(response
+            .choices
+            .first()
+            .and_then(|choice| choice.message.as_ref())
+            .map(|message| message.content.clone())
+            .unwrap_or_default())
+    }
+}
+
+/// Answers every case with its own reference, for checking datasets and
+/// reports without any model calls
+pub struct StubProvider;
+
+#[async_trait]
+impl EvalProvider for StubProvider {
+    async fn answer(&self, _profile: &str, case: &EvalCase) -> Result<EvalAnswer> {
+        let answer = match &case.expected {
+            Some(expected) => expected.clone(),
+            None => case.keywords.join(", "),
+        };
+        Ok(EvalAnswer {
+            completion_tokens: answer.split_whitespace().count() as u32,
+            answer,
+            cost: 0.0,
+            latency_ms: 0,
+        })
+    }
+
+    async fn grade(&self, _profile: &str, _case: &EvalCase, _answer: &str) -> Result<String> {
+        Ok("SCORE: 10".to_string())
+    }
+}
+
+/// One recorded provider response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Recorded {
+    key: String,
+    #[serde(flatten)]
+    answer: EvalAnswer,
+}
+
+/// Records another provider's responses to a JSONL file, or replays them
+///
+/// Replaying needs no network or API key, so a recorded run can be scored
+/// again offline after changing the dataset's criteria or the scoring.
+pub struct RecordedProvider {
+    inner: Option<Arc<dyn EvalProvider>>,
+    recordings: Mutex<HashMap<String, EvalAnswer>>,
+    file: Option<Mutex<std::fs::File>>,
+}
+
+impl RecordedProvider {
+    /// Answer from the recording at `path` only
+    pub fn replay(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read recording {}", path.display()))?;
+        let mut recordings = HashMap::new();
+        for (i, line) in content.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let recorded: Recorded = serde_json::from_str(line)
+                .with_context(|| format!("{}:{}: invalid recording", path.display(), i + 1))?;
+            recordings.insert(recorded.key, recorded.answer);
+        }
+        Ok(Self {
+            inner: None,
+            recordings: Mutex::new(recordings),
+            file: None,
+        })
+    }
+
+    /// Answer through `inner`, appending every response to `path`
+    pub fn record(inner: Arc<dyn EvalProvider>, path: &Path) -> Result<Self> {
+        let file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("Failed to open recording {}", path.display()))?;
+        Ok(Self {
+            inner: Some(inner),
+            recordings: Mutex::new(HashMap::new()),
+            file: Some(Mutex::new(file)),
+        })
+    }
+
+    fn replayed(&self, key: &str) -> Result<EvalAnswer> {
+        self.recordings
+            .lock()
+            .unwrap()
+            .get(key)
+            .cloned()
+            .with_context(|| format!("No recorded response for '{}'", key))
+    }
+
+    fn save(&self, key: String, answer: &EvalAnswer) -> Result<()> {
+        if let Some(file) = &self.file {
+            let line = serde_json::to_string(&Recorded {
+                key: key.clone(),
+                answer: answer.clone(),
+            })?;
+            writeln!(file.lock().unwrap(), "{}", line)?;
+        }
+        self.recordings.lock().unwrap().insert(key, answer.clone());
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl EvalProvider for RecordedProvider {
+    async fn answer(&self, profile: &str, case: &EvalCase) -> Result<EvalAnswer> {
+        let key = format!("answer/{}/{}", profile, case.id);
+        let Some(inner) = &self.inner else {
+            return self.replayed(&key);
+        };
+        let answer = inner.answer(profile, case).await?;
+        self.save(key, &answer)?;
+        Ok(answer)
+    }
+
+    async fn grade(&self, profile: &str, case: &EvalCase, answer: &str) -> Result<String> {
+        let key = format!("grade/{}/{}", profile, case.id);
+        let Some(inner) = &self.inner else {
+            return Ok(self.replayed(&key)?.answer);
+        };
+        let grade = inner.grade(profile, case, answer).await?;
+        self.save(
+            key,
+            &EvalAnswer {
+                answer: grade.clone(),
+                cost: 0.0,
+                latency_ms: 0,
+                completion_tokens: 0,
+            },
+        )?;
+        Ok(grade)
+    }
+}
+
+/// Share of `keywords` mentioned in `answer`, and the ones that are missing
+pub fn keyword_score(answer: &str, keywords: &[String]) -> (f64, Vec<String>) {
+    let answer = answer.to_lowercase();
+    let missing: Vec<String> = keywords
+        .iter()
+        .filter(|keyword| !answer.contains(&keyword.to_lowercase()))
+        .cloned()
+        .collect();
+    let score = if keywords.is_empty() {
+        1.0
+    } else {
+        1.0 - missing.len() as f64 / keywords.len() as f64
+    };
+    (score, missing)
+}
+
+/// Share of the reference answer's significant words that `answer` uses
+pub fn reference_score(answer: &str, expected: &str) -> f64 {
+    let words = |text: &str| -> HashSet<String> {
+        text.split(|c: char| !c.is_alphanumeric())
+            .filter(|word| word.len() > 3)
+            .map(str::to_lowercase)
+            .collect()
+    };
+    let expected = words(expected);
+    if expected.is_empty() {
+        return 1.0;
+    }
+    let answer = words(answer);
+    expected.intersection(&answer).count() as f64 / expected.len() as f64
+}
+
+/// The 0-1 score in a grader reply of the form `SCORE: N` (N out of 10)
+pub fn parse_grade(reply: &str) -> Option<f64> {
+    let upper = reply.to_ascii_uppercase();
+    let start = upper.find("SCORE").map(|i| i + "SCORE".len()).unwrap_or(0);
+    let number: String = reply[start..]
+        .chars()
+        .skip_while(|c| !c.is_ascii_digit())
+        .take_while(|c| c.is_ascii_digit() || *c == '.')
+        .collect();
+    let score: f64 = number.parse().ok()?;
+    (0.0..=10.0).contains(&score).then_some(score / 10.0)
+}
+
+/// Scores of one answer per criterion; absent criteria are `None`
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CriterionScores {
+    pub keywords: Option<f64>,
+    pub reference: Option<f64>,
+    pub rubric: Option<f64>,
+}
+
+/// One case answered by one profile
+#[derive(Debug, Clone, Serialize)]
+pub struct CaseResult {
+    pub case_id: String,
+    pub profile: String,
+    /// Mean of the criterion scores; zero when the run failed
+    pub score: f64,
+    pub passed: bool,
+    pub scores: CriterionScores,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub missing_keywords: Vec<String>,
+    pub latency_ms: u64,
+    pub cost: f64,
+    pub completion_tokens: u32,
+    pub answer: Option<String>,
+    pub error: Option<String>,
+}
+
+/// Totals of one profile over the dataset
+#[derive(Debug, Clone, Serialize)]
+pub struct ProfileReport {
+    pub profile: String,
+    pub cases: usize,
+    pub passed: usize,
+    /// Cases whose consensus run or grading failed
+    pub errors: usize,
+    pub pass_rate: f64,
+    pub mean_score: f64,
+    pub mean_latency_ms: f64,
+    pub p95_latency_ms: u64,
+    /// USD
+    pub total_cost: f64,
+}
+
+impl ProfileReport {
+    fn from_results(profile: &str, results: &[&CaseResult]) -> Self {
+        let cases = results.len();
+        let passed = results.iter().filter(|r| r.passed).count();
+        let mut latencies: Vec<u64> = results
+            .iter()
+            .filter(|r| r.error.is_none())
+            .map(|r| r.latency_ms)
+            .collect();
+        latencies.sort_unstable();
+        let mean = |sum: f64, n: usize| if n == 0 { 0.0 } else { sum / n as f64 };
+        Self {
+            profile: profile.to_string(),
+            cases,
+            passed,
+            errors: results.iter().filter(|r| r.error.is_some()).count(),
+            pass_rate: mean(passed as f64, cases),
+            mean_score: mean(results.iter().map(|r| r.score).sum(), cases),
+            mean_latency_ms: mean(latencies.iter().map(|&l| l as f64).sum(), latencies.len()),
+            p95_latency_ms: latencies
+                .get((latencies.len() * 95 / 100).min(latencies.len().saturating_sub(1)))
+                .copied()
+                .unwrap_or(0),
+            total_cost: results.iter().map(|r| r.cost).sum(),
+        }
+    }
+}
+
+/// Results of an evaluation run
+#[derive(Debug, Clone, Serialize)]
+pub struct EvalReport {
+    pub dataset: String,
+    pub threshold: f64,
+    pub created_at: String,
+    pub profiles: Vec<ProfileReport>,
+    /// Every other profile against the first, as A/B tests over the dataset
+    pub comparisons: Vec<ABTestAnalysis>,
+    pub results: Vec<CaseResult>,
+}
+
+impl EvalReport {
+    /// The report as a Markdown document
+    pub fn to_markdown(&self) -> String {
+        let mut out = format!(
+            "# Evaluation: {}\n\n{} · pass threshold {:.2}\n\n\
+             | Profile | Passed | Mean score | Mean latency | p95 latency | Cost | Errors |\n\
+             |---------|--------|------------|--------------|-------------|------|--------|\n",
+            self.dataset, self.created_at, self.threshold
+        );
+        for p in &self.profiles {
+            out.push_str(&format!(
+                "| {} | {}/{} ({:.0}%) | {:.3} | {:.0} ms | {} ms | ${:.4} | {} |\n",
+                p.profile,
+                p.passed,
+                p.cases,
+                p.pass_rate * 100.0,
+                p.mean_score,
+                p.mean_latency_ms,
+                p.p95_latency_ms,
+                p.total_cost,
+                p.errors
+            ));
+        }
+
+        for comparison in &self.comparisons {
+            out.push_str(&format!(
+                "\n## {} vs {}\n\n{}\n\n| Metric | {} | {} | Better |\n|--------|---|---|--------|\n",
+                comparison.model_a,
+                comparison.model_b,
+                comparison.recommendation,
+                comparison.model_a,
+                comparison.model_b
+            ));
+            let metrics = &comparison.metrics_comparison;
+            for metric in [
+                &metrics.quality_comparison,
+                &metrics.success_rate_comparison,
+                &metrics.latency_comparison,
+                &metrics.throughput_comparison,
+            ] {
+                out.push_str(&format!(
+                    "| {} | {:.2} | {:.2} | {} |\n",
+                    metric.metric_name,
+                    metric.model_a_value,
+                    metric.model_b_value,
+                    metric.better_model.as_deref().unwrap_or("-")
+                ));
+            }
+        }
+
+        let failures: Vec<&CaseResult> = self.results.iter().filter(|r| !r.passed).collect();
+        if !failures.is_empty() {
+            out.push_str("\n## Failed cases\n\n| Case | Profile | Score | Why |\n|------|---------|-------|-----|\n");
+            for result in failures {
+                let why = match (&result.error, result.missing_keywords.is_empty()) {
+                    (Some(error), _) => error.replace('|', "\\|").replace('\n', " "),
+                    (None, false) => format!("missing: {}", result.missing_keywords.join(", ")),
+                    (None, true) => "below threshold".to_string(),
+                };
+                out.push_str(&format!(
+                    "| `{}` | {} | {:.3} | {} |\n",
+                    result.case_id, result.profile, result.score, why
+                ));
+            }
+        }
+        out
+    }
+}
+
+/// Runs datasets through profiles and scores the answers
+pub struct Evaluator {
+    provider: Arc<dyn EvalProvider>,
+    threshold: f64,
+    concurrency: usize,
+}
+
+impl Evaluator {
+    pub fn new(provider: Arc<dyn EvalProvider>) -> Self {
+        Self {
+            provider,
+            threshold: DEFAULT_PASS_THRESHOLD,
+            concurrency: 2,
+        }
+    }
+
+    pub fn with_threshold(mut self, threshold: f64) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Evaluate every case with every profile; `on_result` sees each result as it lands
+    pub async fn run(
+        &self,
+        dataset: &str,
+        cases: &[EvalCase],
+        profiles: &[String],
+        on_result: impl Fn(&CaseResult),
+    ) -> Result<EvalReport> {
+        if profiles.is_empty() {
+            bail!("No profiles to evaluate");
+        }
+
+        let mut results = Vec::new();
+        for profile in profiles {
+            let mut profile_results: Vec<(usize, CaseResult)> =
+                stream::iter(cases.iter().enumerate())
+                    .map(|(index, case)| async move { (index, self.evaluate(profile, case).await) })
+                    .buffer_unordered(self.concurrency)
+                    .inspect(|(_, result)| on_result(result))
+                    .collect()
+                    .await;
+            profile_results.sort_by_key(|(index, _)| *index);
+            results.extend(profile_results.into_iter().map(|(_, result)| result));
+        }
+
+        let profile_reports = profiles
+            .iter()
+            .map(|profile| {
+                let own: Vec<&CaseResult> =
+                    results.iter().filter(|r| &r.profile == profile).collect();
+                ProfileReport::from_results(profile, &own)
+            })
+            .collect();
+
+        Ok(EvalReport {
+            dataset: dataset.to_string(),
+            threshold: self.threshold,
+            created_at: chrono::Utc::now().to_rfc3339(),
+            profiles: profile_reports,
+            comparisons: compare_profiles(dataset, cases, profiles, &results).await,
+            results,
+        })
+    }
+
+    async fn evaluate(&self, profile: &str, case: &EvalCase) -> CaseResult {
+        let mut result = CaseResult {
+            case_id: case.id.clone(),
+            profile: profile.to_string(),
+            score: 0.0,
+            passed: false,
+            scores: CriterionScores::default(),
+            missing_keywords: Vec::new(),
+            latency_ms: 0,
+            cost: 0.0,
+            completion_tokens: 0,
+            answer: None,
+            error: None,
+        };
+
+        let answer = match self.provider.answer(profile, case).await {
+            Ok(answer) => answer,
+            Err(e) => {
+                result.error = Some(format!("{:#}", e));
+                return result;
+            }
+        };
+        result.latency_ms = answer.latency_ms;
+        result.cost = answer.cost;
+        result.completion_tokens = answer.completion_tokens;
+
+        if !case.keywords.is_empty() {
+            let (score, missing) = keyword_score(&answer.answer, &case.keywords);
+            result.scores.keywords = Some(score);
+            result.missing_keywords = missing;
+        }
+        if let Some(expected) = &case.expected {
+            result.scores.reference = Some(reference_score(&answer.answer, expected));
+        }
+        if case.rubric.is_some() {
+            match self.provider.grade(profile, case, &answer.answer).await {
+                Ok(reply) => match parse_grade(&reply) {
+                    Some(score) => result.scores.rubric = Some(score),
+                    None => result.error = Some(format!("Unreadable grade: {}", reply.trim())),
+                },
+                Err(e) => result.error = Some(format!("Grading failed: {:#}", e)),
+            }
+        }
+
+        let scores: Vec<f64> = [
+            result.scores.keywords,
+            result.scores.reference,
+            result.scores.rubric,
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        if result.error.is_none() && !scores.is_empty() {
+            result.score = scores.iter().sum::<f64>() / scores.len() as f64;
+            result.passed = result.score >= self.threshold;
+        }
+        result.answer = Some(answer.answer);
+        result
+    }
+}
+
+/// A/B analyses of every profile after the first against the first one
+async fn compare_profiles(
+    dataset: &str,
+    cases: &[EvalCase],
+    profiles: &[String],
+    results: &[CaseResult],
+) -> Vec<ABTestAnalysis> {
+    let Some((baseline, others)) = profiles.split_first() else {
+        return Vec::new();
+    };
+    let tracker = PerformanceTracker::new(60);
+    let questions: Vec<String> = cases.iter().map(|case| case.question.clone()).collect();
+
+    let mut comparisons = Vec::new();
+    for other in others {
+        match ab_test(&tracker, dataset, &questions, baseline, other, results).await {
+            Ok(analysis) => comparisons.push(analysis),
+            Err(e) => tracing::warn!("Cannot compare {} with {}: {}", baseline, other, e),
+        }
+    }
+    comparisons
+}
+
+async fn ab_test(
+    tracker: &PerformanceTracker,
+    dataset: &str,
+    questions: &[String],
+    baseline: &str,
+    other: &str,
+    results: &[CaseResult],
+) -> Result<ABTestAnalysis> {
+    let test_id = tracker
+        .create_ab_test(
+            format!("eval {}: {} vs {}", dataset, baseline, other),
+            format!("Golden dataset {}", dataset),
+            baseline.to_string(),
+            other.to_string(),
+            questions.to_vec(),
+            questions.len() * 2,
+            1,
+        )
+        .await?;
+    tracker.start_ab_test(&test_id).await?;
+    for result in results
+        .iter()
+        .filter(|r| r.profile == baseline || r.profile == other)
+    {
+        tracker
+            .record_ab_test_result(
+                &test_id,
+                &result.case_id,
+                &result.profile,
+                result.latency_ms,
+                result.completion_tokens,
+                result.error.is_none(),
+                None,
+                Some(result.score as f32),
+            )
+            .await?;
+    }
+    tracker.analyze_ab_test(&test_id).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn case(id: &str, expected: Option<&str>, keywords: &[&str], rubric: Option<&str>) -> EvalCase {
+        EvalCase {
+            id: id.to_string(),
+            question: format!("Question {}", id),
+            context: None,
+            expected: expected.map(str::to_string),
+            keywords: keywords.iter().map(|k| k.to_string()).collect(),
+            rubric: rubric.map(str::to_string),
+        }
+    }
+
+    /// A weaker profile that forgets the last keyword and answers slowly
+    struct TwoProfiles;
+
+    #[async_trait]
+    impl EvalProvider for TwoProfiles {
+        async fn answer(&self, profile: &str, case: &EvalCase) -> Result<EvalAnswer> {
+            let mut keywords = case.keywords.clone();
+            if profile == "speed" {
+                keywords.pop();
+            }
+            Ok(EvalAnswer {
+                answer: keywords.join(" and "),
+                cost: if profile == "speed" { 0.001 } else { 0.01 },
+                latency_ms: if profile == "speed" { 100 } else { 900 },
+                completion_tokens: 50,
+            })
+        }
+
+        async fn grade(&self, _profile: &str, _case: &EvalCase, answer: &str) -> Result<String> {
+            Ok(format!(
+                "SCORE: {}/10 - {} words",
+                answer.len().min(10),
+                answer.len()
+            ))
+        }
+    }
+
+    #[test]
+    fn test_load_dataset() -> Result<()> {
+        let dir = TempDir::new()?;
+        let path = dir.path().join("golden.jsonl");
+        std::fs::write(
+            &path,
+            "{\"question\": \"What is 2+2?\", \"expected\": \"four\"}\n\n{\"id\": \"kw\", \"question\": \"q\", \"keywords\": [\"tokio\"]}\n",
+        )?;
+        let cases = load_dataset(&path)?;
+        assert_eq!(cases.len(), 2);
+        assert_eq!(cases[0].id, "case-1");
+        assert_eq!(cases[1].keywords, ["tokio"]);
+
+        std::fs::write(&path, "{\"question\": \"No criteria\"}\n")?;
+        assert!(load_dataset(&path).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_scores() {
+        let keywords = vec!["Tokio".to_string(), "async".to_string()];
+        let (score, missing) = keyword_score("It uses tokio for I/O", &keywords);
+        assert_eq!(score, 0.5);
+        assert_eq!(missing, ["async"]);
+
+        assert_eq!(
+            reference_score("The cache stores parsed files", "cache stores files"),
+            1.0
+        );
+        assert_eq!(reference_score("unrelated", "cache stores files"), 0.0);
+
+        assert_eq!(parse_grade("SCORE: 7\nMostly right."), Some(0.7));
+        assert_eq!(parse_grade("Score: 10/10"), Some(1.0));
+        assert_eq!(parse_grade("SCORE: 42"), None);
+        assert_eq!(parse_grade("no idea"), None);
+    }
+
+    #[tokio::test]
+    async fn test_run_compares_profiles() -> Result<()> {
+        let cases: Vec<EvalCase> = (0..6)
+            .map(|i| {
+                case(
+                    &format!("c{}", i),
+                    None,
+                    &["alpha", "beta", "gamma"],
+                    Some("Mentions all three"),
+                )
+            })
+            .collect();
+        let profiles = vec!["balanced".to_string(), "speed".to_string()];
+        let report = Evaluator::new(Arc::new(TwoProfiles))
+            .run("golden", &cases, &profiles, |_| {})
+            .await?;
+
+        assert_eq!(report.results.len(), 12);
+        let balanced = &report.profiles[0];
+        let speed = &report.profiles[1];
+        assert_eq!(balanced.passed, 6);
+        assert!(speed.mean_score < balanced.mean_score);
+        assert!(speed.mean_latency_ms < balanced.mean_latency_ms);
+
+        assert_eq!(report.comparisons.len(), 1);
+        let quality = &report.comparisons[0].metrics_comparison.quality_comparison;
+        assert_eq!(quality.better_model.as_deref(), Some("balanced"));
+        assert!(report.to_markdown().contains("| balanced | 6/6"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_record_and_replay() -> Result<()> {
+        let dir = TempDir::new()?;
+        let path = dir.path().join("recording.jsonl");
+        let cases = vec![
+            case("ref", Some("tokio runtime"), &[], None),
+            case("graded", None, &[], Some("Any answer")),
+        ];
+        let profiles = vec!["balanced".to_string()];
+
+        let recorder = RecordedProvider::record(Arc::new(StubProvider), &path)?;
+        let live = Evaluator::new(Arc::new(recorder))
+            .run("golden", &cases, &profiles, |_| {})
+            .await?;
+        assert_eq!(live.profiles[0].passed, 2);
+
+        let replay = Evaluator::new(Arc::new(RecordedProvider::replay(&path)?))
+            .run("golden", &cases, &profiles, |_| {})
+            .await?;
+        assert_eq!(replay.profiles[0].passed, 2);
+
+        // Cases that were never recorded fail instead of calling a model
+        let missing = Evaluator::new(Arc::new(RecordedProvider::replay(&path)?))
+            .run("golden", &cases, &["elite".to_string()], |_| {})
+            .await?;
+        assert_eq!(missing.profiles[0].errors, 2);
+        Ok(())
+    }
+}
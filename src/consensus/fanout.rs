@@ -0,0 +1,399 @@
+// Fan-out - run one stage on several models concurrently, then vote or merge
+// Candidates are scored on agreement, repository facts and answer quality
+
+use crate::consensus::confidence_scoring::ConfidenceScoringEngine;
+use crate::consensus::cross_validator::{candidate_agreement, CandidateAgreement, CrossValidator};
+use crate::consensus::streaming::StreamingCallbacks;
+use crate::consensus::types::{CandidateAnalytics, ConsensusProfile, Stage, StageResult};
+use crate::providers::openrouter::{ModelSelector, TaskComplexity};
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+
+/// Candidates to run when a fan-out names no models and no count
+pub const DEFAULT_CANDIDATES: usize = 3;
+
+/// Upper bound on concurrent candidates per stage
+pub const MAX_CANDIDATES: usize = 8;
+
+/// System prompt for the merge call of a synthesizing fan-out
+pub const SYNTHESIS_PROMPT: &str = "You are merging several independent answers to the same question into one. Each candidate below is labelled with its model and a confidence score. Keep what the candidates agree on, resolve disagreements in favour of the better-supported claim, drop anything only one low-scoring candidate asserts, and keep useful detail from any candidate. Reply with the merged answer only, without mentioning the candidates.";
+
+/// How a fanned-out stage turns its candidates into one answer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MergeStrategy {
+    /// Keep the highest-scoring candidate as is
+    #[default]
+    Select,
+    /// Ask a model to synthesize one answer from all candidates
+    Synthesize,
+}
+
+/// Fan-out settings for a pipeline stage (`[stage.fan_out]`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FanOutDefinition {
+    /// Models to run; defaults to the stage's model, the profile's other models
+    /// and then the `ModelSelector`'s picks
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub models: Vec<String>,
+    /// Number of candidates when `models` is empty
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub count: Option<usize>,
+    #[serde(default)]
+    pub merge: MergeStrategy,
+    /// Model for the synthesis call; defaults to the stage's model
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub merge_model: Option<String>,
+}
+
+impl FanOutDefinition {
+    pub fn with_count(count: usize) -> Self {
+        Self {
+            models: Vec::new(),
+            count: Some(count),
+            merge: MergeStrategy::default(),
+            merge_model: None,
+        }
+    }
+
+    pub fn with_merge(mut self, merge: MergeStrategy) -> Self {
+        self.merge = merge;
+        self
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        let count = self.candidate_count();
+        if !(2..=MAX_CANDIDATES).contains(&count) {
+            bail!(
+                "Fan-out needs between 2 and {} candidates, got {}",
+                MAX_CANDIDATES,
+                count
+            );
+        }
+        Ok(())
+    }
+
+    pub fn candidate_count(&self) -> usize {
+        if self.models.is_empty() {
+            self.count.unwrap_or(DEFAULT_CANDIDATES)
+        } else {
+            self.models.len()
+        }
+    }
+
+    /// Models to run: the explicit list, or the stage's model followed by the
+    /// profile's other models, topped up with the selector's picks
+    pub fn resolve_models(
+        &self,
+        stage_model: &str,
+        profile: &ConsensusProfile,
+        selector: &ModelSelector,
+    ) -> Vec<String> {
+        if !self.models.is_empty() {
+            return self.models.clone();
+        }
+
+        let count = self.candidate_count();
+        let mut models = vec![stage_model.to_string()];
+        for stage in [
+            Stage::Generator,
+            Stage::Refiner,
+            Stage::Validator,
+            Stage::Curator,
+        ] {
+            let model = profile.get_model_for_stage(stage);
+            if models.len() < count && !models.iter().any(|m| m == model) {
+                models.push(model.to_string());
+            }
+        }
+        if models.len() < count {
+            let extra =
+                selector.select_candidates(TaskComplexity::Complex, count - models.len(), &models);
+            models.extend(extra);
+        }
+        models
+    }
+}
+
+/// Callbacks that drop progress, so concurrent candidates don't interleave their
+/// streams in the UI
+pub struct SilentCallbacks;
+
+impl StreamingCallbacks for SilentCallbacks {}
+
+/// Score successful candidates against each other. Uses the cross-validator's
+/// claim checks when repository facts are available, word overlap otherwise
+pub fn score_candidates(
+    results: &[StageResult],
+    cross_validator: Option<&CrossValidator>,
+    scorer: &ConfidenceScoringEngine,
+) -> Result<Vec<CandidateAnalytics>> {
+    let answers: Vec<&str> = results.iter().map(|r| r.answer.as_str()).collect();
+    let agreements = match cross_validator {
+        Some(validator) => validator.compare_candidates(&answers)?,
+        None => candidate_agreement(&answers)
+            .into_iter()
+            .map(|agreement| CandidateAgreement {
+                agreement,
+                contradictions: 0,
+            })
+            .collect(),
+    };
+
+    Ok(results
+        .iter()
+        .zip(agreements)
+        .map(|(result, agreement)| CandidateAnalytics {
+            model: result.model.clone(),
+            duration: result.analytics.as_ref().map(|a| a.duration).unwrap_or(0.0),
+            cost: result.analytics.as_ref().map(|a| a.cost).unwrap_or(0.0),
+            total_tokens: result.usage.as_ref().map(|u| u.total_tokens).unwrap_or(0),
+            agreement: agreement.agreement,
+            contradictions: agreement.contradictions,
+            score: scorer.score_candidate_answer(
+                &result.answer,
+                agreement.agreement,
+                agreement.contradictions,
+            ),
+            selected: false,
+            error: None,
+        })
+        .collect())
+}
+
+/// Analytics entry for a candidate whose model call failed
+pub fn failed_candidate(model: &str, error: &anyhow::Error) -> CandidateAnalytics {
+    CandidateAnalytics {
+        model: model.to_string(),
+        duration: 0.0,
+        cost: 0.0,
+        total_tokens: 0,
+        agreement: 0.0,
+        contradictions: 0,
+        score: 0.0,
+        selected: false,
+        error: Some(format!("{:#}", error)),
+    }
+}
+
+/// Index of the highest-scoring successful candidate; earlier candidates win ties
+pub fn best_candidate(candidates: &[CandidateAnalytics]) -> Option<usize> {
+    candidates
+        .iter()
+        .enumerate()
+        .filter(|(_, candidate)| candidate.error.is_none())
+        .fold(
+            None,
+            |best: Option<(usize, f32)>, (i, candidate)| match best {
+                Some((_, score)) if score >= candidate.score => best,
+                _ => Some((i, candidate.score)),
+            },
+        )
+        .map(|(i, _)| i)
+}
+
+/// Candidates, best first, as the input of the synthesis call
+pub fn format_candidates(results: &[StageResult], analytics: &[CandidateAnalytics]) -> String {
+    let mut ranked: Vec<(&StageResult, &CandidateAnalytics)> =
+        results.iter().zip(analytics).collect();
+    ranked.sort_by(|a, b| b.1.score.total_cmp(&a.1.score));
+    ranked
+        .iter()
+        .enumerate()
+        .map(|(i, (result, candidate))| {
+            format!(
+                "### Candidate {} ({}, score {:.0})\n{}",
+                i + 1,
+                candidate.model,
+                candidate.score,
+                result.answer
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// Build the stage result for a fan-out: the synthesized answer, or the selected
+/// candidate, carrying the cost and tokens of every call and the per-candidate
+/// analytics. Successful candidates come first in `analytics`, in `results` order
+pub fn combine_candidates(
+    results: &[StageResult],
+    mut analytics: Vec<CandidateAnalytics>,
+    synthesis: Option<StageResult>,
+    duration: f64,
+) -> Option<StageResult> {
+    let mut combined = match synthesis {
+        Some(synthesis) => synthesis,
+        None => {
+            let best = best_candidate(&analytics)?;
+            analytics[best].selected = true;
+            results[best].clone()
+        }
+    };
+    let extra: Vec<&StageResult> = results
+        .iter()
+        .filter(|result| result.stage_id != combined.stage_id)
+        .collect();
+
+    if let Some(usage) = combined.usage.as_mut() {
+        for other in extra.iter().filter_map(|r| r.usage.as_ref()) {
+            usage.prompt_tokens += other.prompt_tokens;
+            usage.completion_tokens += other.completion_tokens;
+            usage.total_tokens += other.total_tokens;
+        }
+    }
+    if let Some(stage_analytics) = combined.analytics.as_mut() {
+        for other in extra.iter().filter_map(|r| r.analytics.as_ref()) {
+            stage_analytics.cost += other.cost;
+            stage_analytics.input_cost += other.input_cost;
+            stage_analytics.output_cost += other.output_cost;
+        }
+        stage_analytics.duration = duration;
+        stage_analytics.error_count +=
+            analytics.iter().filter(|c| c.error.is_some()).count() as u32;
+        stage_analytics.candidates = analytics;
+    }
+
+    Some(combined)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consensus::types::{AnalyticsFeatures, StageAnalytics, TokenUsage};
+    use crate::providers::openrouter::ModelSelectionStrategy;
+    use chrono::Utc;
+
+    fn result(model: &str, answer: &str, cost: f64) -> StageResult {
+        StageResult {
+            stage_id: uuid::Uuid::new_v4().to_string(),
+            stage_name: "generator".to_string(),
+            question: "How does the cache work?".to_string(),
+            answer: answer.to_string(),
+            model: model.to_string(),
+            conversation_id: "conv".to_string(),
+            timestamp: Utc::now(),
+            usage: Some(TokenUsage {
+                prompt_tokens: 100,
+                completion_tokens: 50,
+                total_tokens: 150,
+            }),
+            analytics: Some(StageAnalytics {
+                duration: 1.0,
+                cost,
+                input_cost: cost / 2.0,
+                output_cost: cost / 2.0,
+                provider: "openrouter".to_string(),
+                model_internal_id: model.to_string(),
+                quality_score: 1.0,
+                error_count: 0,
+                fallback_used: false,
+                rate_limit_hit: false,
+                retry_count: 0,
+                start_time: Utc::now(),
+                end_time: Utc::now(),
+                time_to_first_token: None,
+                classification_latency: None,
+                memory_usage: None,
+                features: AnalyticsFeatures {
+                    streaming: false,
+                    routing_variant: "balanced".to_string(),
+                    optimization_applied: None,
+                },
+                candidates: Vec::new(),
+                context: None,
+            }),
+        }
+    }
+
+    fn candidates() -> Vec<StageResult> {
+        vec![
+            result(
+                "a/one",
+                "The cache stores entries in an LRU map keyed by request hash and evicts the oldest entry when full.",
+                0.01,
+            ),
+            result(
+                "b/two",
+                "Entries live in an LRU map keyed by request hash; when the map is full the oldest entry is evicted.",
+                0.02,
+            ),
+            result("c/three", "I'm not sure. Bananas are yellow.", 0.03),
+        ]
+    }
+
+    #[test]
+    fn test_outlier_scores_lowest_and_is_not_selected() {
+        let results = candidates();
+        let scorer = ConfidenceScoringEngine::new(None, None);
+        let analytics = score_candidates(&results, None, &scorer).unwrap();
+
+        assert!(analytics[2].agreement < analytics[0].agreement);
+        assert!(analytics[2].score < analytics[0].score);
+        assert!(analytics[2].score < analytics[1].score);
+        assert_ne!(best_candidate(&analytics), Some(2));
+    }
+
+    #[test]
+    fn test_select_folds_cost_and_marks_winner() {
+        let results = candidates();
+        let scorer = ConfidenceScoringEngine::new(None, None);
+        let mut analytics = score_candidates(&results, None, &scorer).unwrap();
+        analytics.push(failed_candidate("d/four", &anyhow::anyhow!("rate limited")));
+
+        let combined = combine_candidates(&results, analytics, None, 2.5).unwrap();
+        let stage_analytics = combined.analytics.unwrap();
+        assert!((stage_analytics.cost - 0.06).abs() < 1e-9);
+        assert_eq!(stage_analytics.duration, 2.5);
+        assert_eq!(stage_analytics.error_count, 1);
+        assert_eq!(stage_analytics.candidates.len(), 4);
+        assert_eq!(
+            stage_analytics
+                .candidates
+                .iter()
+                .filter(|c| c.selected)
+                .count(),
+            1
+        );
+        assert_eq!(combined.usage.unwrap().total_tokens, 450);
+    }
+
+    #[test]
+    fn test_synthesis_adds_merge_call() {
+        let results = candidates();
+        let scorer = ConfidenceScoringEngine::new(None, None);
+        let analytics = score_candidates(&results, None, &scorer).unwrap();
+        let merged = result("m/merge", "merged", 0.005);
+
+        let combined = combine_candidates(&results, analytics, Some(merged), 3.0).unwrap();
+        assert_eq!(combined.answer, "merged");
+        let stage_analytics = combined.analytics.unwrap();
+        assert!((stage_analytics.cost - 0.065).abs() < 1e-9);
+        assert!(stage_analytics.candidates.iter().all(|c| !c.selected));
+    }
+
+    #[test]
+    fn test_validate_and_resolve_models() {
+        assert!(FanOutDefinition::with_count(1).validate().is_err());
+        assert!(FanOutDefinition::with_count(MAX_CANDIDATES + 1)
+            .validate()
+            .is_err());
+        assert!(FanOutDefinition::with_count(3).validate().is_ok());
+
+        let profile = ConsensusProfile {
+            id: "p".to_string(),
+            profile_name: "test".to_string(),
+            generator_model: "a/one".to_string(),
+            refiner_model: "a/one".to_string(),
+            validator_model: "b/two".to_string(),
+            curator_model: "b/two".to_string(),
+            created_at: Utc::now(),
+            is_active: true,
+        };
+        let selector = ModelSelector::new(ModelSelectionStrategy::QualityFirst);
+        let models = FanOutDefinition::with_count(4).resolve_models("a/one", &profile, &selector);
+        assert_eq!(models.len(), 4);
+        assert_eq!(&models[..2], ["a/one", "b/two"]);
+        assert!(models[2..].iter().all(|m| m != "a/one" && m != "b/two"));
+    }
+}
@@ -20,6 +20,7 @@ use crate::consensus::operation_preview_generator::{
 use crate::consensus::smart_decision_engine::{ExecutionDecision, SmartDecisionEngine};
 use crate::consensus::stages::file_aware_curator::FileOperation;
 use crate::core::error::HiveError;
+use crate::security::enforcement::authorize;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
@@ -445,6 +446,9 @@ impl FileOperationExecutor {
         // Safety check: validate path
         self.validate_operation_safety(&operation)?;
 
+        // Access check: the acting user needs code.write on every touched path
+        self.authorize_operation(&operation, operation_id).await?;
+
         // Create backup if needed
         let backup_info = if self.config.create_backups {
             self.backup_manager.create_backup(&operation).await.ok()
@@ -537,6 +541,33 @@ impl FileOperationExecutor {
         }
     }
 
+    /// Check `code.write` for each path against the RBAC store
+    async fn authorize_operation(
+        &self,
+        operation: &FileOperation,
+        operation_id: Uuid,
+    ) -> Result<(), HiveError> {
+        let paths = match operation {
+            FileOperation::Create { path, .. }
+            | FileOperation::Update { path, .. }
+            | FileOperation::Delete { path }
+            | FileOperation::Append { path, .. } => vec![path],
+            FileOperation::Rename { from, to } => vec![from, to],
+        };
+
+        for path in paths {
+            let resource = path.display().to_string();
+            if let Err(e) = authorize("code.write", "file", &resource).await {
+                return Err(HiveError::OperationBlocked {
+                    operation_id,
+                    reasons: vec![e.to_string()],
+                });
+            }
+        }
+
+        Ok(())
+    }
+
     /// Validate operation safety before execution
     fn validate_operation_safety(&self, operation: &FileOperation) -> Result<(), HiveError> {
         let path = match operation {
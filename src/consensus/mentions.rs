@@ -0,0 +1,756 @@
+// @-mentions - references like @file:src/main.rs:10-20 inside a question
+// Each one expands into a bounded, labelled context block for the consensus stages
+
+use anyhow::{anyhow, bail, Context, Result};
+use git2::Repository;
+use ignore::WalkBuilder;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::analysis::symbol_index::SymbolIndexer;
+use crate::consensus::review::{diff_files, DiffSpec, LineKind};
+use crate::consensus::threads::ThreadStore;
+use crate::core::database::{get_or_initialize_database, DatabaseManager};
+
+/// Mention prefixes offered by tab completion; `@codebase` is a command, not a mention
+pub const MENTION_KINDS: &[&str] = &[
+    "@file:",
+    "@symbol:",
+    "@diff",
+    "@dir:",
+    "@conversation:",
+    "@plan:",
+];
+
+/// Characters of one block before it is truncated
+const MAX_BLOCK_CHARS: usize = 12_000;
+/// Characters of all blocks together
+const MAX_TOTAL_CHARS: usize = 40_000;
+/// Entries listed for `@dir:`
+const MAX_DIR_ENTRIES: usize = 200;
+/// Symbols shown when `@symbol:` is ambiguous
+const MAX_SYMBOL_MATCHES: usize = 3;
+
+/// A reference to something outside the question
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Mention {
+    /// A file, optionally limited to a 1-based inclusive line range
+    File {
+        path: PathBuf,
+        lines: Option<(usize, usize)>,
+    },
+    Symbol(String),
+    /// Uncommitted changes, or a revision range as accepted by `hive review`
+    Diff(Option<String>),
+    Dir(PathBuf),
+    Conversation(String),
+    Plan(String),
+}
+
+/// A mention and the text it was written as
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MentionRef {
+    pub text: String,
+    pub mention: Result<Mention, String>,
+}
+
+/// Mentions in `query`, in order; malformed ones carry their parse error
+///
+/// A mention starts with `@` at the beginning of a word and ends at the next
+/// whitespace, without trailing punctuation. Unknown kinds such as `@someone`
+/// are not mentions.
+pub fn parse_mentions(query: &str) -> Vec<MentionRef> {
+    query
+        .split_whitespace()
+        .filter_map(|word| {
+            let word = word.trim_start_matches(['(', '[', '"', '\'']);
+            let text = word.trim_end_matches(['.', ',', ';', ':', '!', '?', ')', ']', '"', '\'']);
+            let (kind, argument) = match text.strip_prefix('@')?.split_once(':') {
+                Some((kind, argument)) => (kind, Some(argument)),
+                None => (&text[1..], None),
+            };
+            let mention = match (kind, argument) {
+                ("file", Some(argument)) => parse_file(argument),
+                ("symbol", Some(name)) if !name.is_empty() => Ok(Mention::Symbol(name.to_string())),
+                ("diff", None) => Ok(Mention::Diff(None)),
+                ("diff", Some(range)) if !range.is_empty() => {
+                    Ok(Mention::Diff(Some(range.to_string())))
+                }
+                ("dir", Some(path)) if !path.is_empty() => Ok(Mention::Dir(PathBuf::from(path))),
+                ("conversation", Some(id)) if !id.is_empty() => {
+                    Ok(Mention::Conversation(id.to_string()))
+                }
+                ("plan", Some(id)) if !id.is_empty() => Ok(Mention::Plan(id.to_string())),
+                ("file" | "symbol" | "diff" | "dir" | "conversation" | "plan", _) => {
+                    Err(format!("@{} needs an argument", kind))
+                }
+                _ => return None,
+            };
+            Some(MentionRef {
+                text: text.to_string(),
+                mention,
+            })
+        })
+        .collect()
+}
+
+fn parse_file(argument: &str) -> Result<Mention, String> {
+    let range = argument.rsplit_once(':').and_then(|(path, range)| {
+        let (start, end) = range.split_once('-').unwrap_or((range, range));
+        Some((
+            path,
+            start.parse::<usize>().ok()?,
+            end.parse::<usize>().ok()?,
+        ))
+    });
+    match range {
+        Some((_, start, end)) if start == 0 || end < start => {
+            Err(format!("invalid line range {}-{}", start, end))
+        }
+        Some((path, start, end)) if !path.is_empty() => Ok(Mention::File {
+            path: PathBuf::from(path),
+            lines: Some((start, end)),
+        }),
+        None if !argument.is_empty() => Ok(Mention::File {
+            path: PathBuf::from(argument),
+            lines: None,
+        }),
+        _ => Err("@file needs a path".to_string()),
+    }
+}
+
+/// Context a mention expanded into
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContextBlock {
+    /// The mention as written, so stages can match blocks to the question
+    pub label: String,
+    /// What the block holds, e.g. `lines 10-20 of src/main.rs`
+    pub description: String,
+    /// Code fence language; `None` for prose
+    pub language: Option<String>,
+    pub content: String,
+    pub truncated: bool,
+}
+
+impl ContextBlock {
+    fn new(label: &str, description: impl Into<String>, content: String) -> Self {
+        Self {
+            label: label.to_string(),
+            description: description.into(),
+            language: None,
+            content,
+            truncated: false,
+        }
+    }
+
+    fn with_language(mut self, language: &str) -> Self {
+        self.language = Some(language.to_string());
+        self
+    }
+
+    /// Cut the content to `max_chars`, marking the block as truncated
+    fn truncate(&mut self, max_chars: usize) {
+        if let Some((end, _)) = self.content.char_indices().nth(max_chars) {
+            self.content.truncate(end);
+            self.truncated = true;
+        }
+    }
+
+    pub fn render(&self) -> String {
+        let fence = self.language.as_ref().map(|language| {
+            // Content containing fences gets a longer one
+            let ticks = if self.content.contains("```") {
+                "````"
+            } else {
+                "```"
+            };
+            (ticks, language)
+        });
+        let mut text = format!("### {} ({})\n", self.label, self.description);
+        match fence {
+            Some((ticks, language)) => text.push_str(&format!(
+                "{}{}\n{}\n{}\n",
+                ticks, language, self.content, ticks
+            )),
+            None => {
+                text.push_str(&self.content);
+                text.push('\n');
+            }
+        }
+        if self.truncated {
+            text.push_str("[truncated]\n");
+        }
+        text
+    }
+}
+
+/// Context section for the blocks, or `None` when there are none
+pub fn render_context(blocks: &[ContextBlock]) -> Option<String> {
+    if blocks.is_empty() {
+        return None;
+    }
+    let rendered: Vec<String> = blocks.iter().map(ContextBlock::render).collect();
+    Some(format!("## Referenced context\n\n{}", rendered.join("\n")))
+}
+
+/// Mentions that could not be expanded, listed together
+#[derive(Debug)]
+pub struct UnresolvedMentions(pub Vec<(String, String)>);
+
+impl std::fmt::Display for UnresolvedMentions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Unresolved mentions:")?;
+        for (text, reason) in &self.0 {
+            write!(f, "\n  {}: {}", text, reason)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for UnresolvedMentions {}
+
+/// Expands mentions relative to a working directory
+pub struct MentionResolver {
+    root: PathBuf,
+    database: Option<Arc<DatabaseManager>>,
+    plans_dir: PathBuf,
+    max_block_chars: usize,
+    max_total_chars: usize,
+}
+
+impl MentionResolver {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            database: None,
+            plans_dir: crate::planning::plans_dir(),
+            max_block_chars: MAX_BLOCK_CHARS,
+            max_total_chars: MAX_TOTAL_CHARS,
+        }
+    }
+
+    /// Database for `@symbol` and `@conversation`; defaults to the shared one
+    pub fn with_database(mut self, database: Arc<DatabaseManager>) -> Self {
+        self.database = Some(database);
+        self
+    }
+
+    pub fn with_plans_dir(mut self, plans_dir: impl Into<PathBuf>) -> Self {
+        self.plans_dir = plans_dir.into();
+        self
+    }
+
+    pub fn with_limits(mut self, max_block_chars: usize, max_total_chars: usize) -> Self {
+        self.max_block_chars = max_block_chars;
+        self.max_total_chars = max_total_chars;
+        self
+    }
+
+    /// Expand every mention in `query`
+    ///
+    /// Fails with [`UnresolvedMentions`] listing each mention that could not
+    /// be expanded, so nothing is sent with half of its context missing.
+    pub async fn resolve(&self, query: &str) -> Result<Vec<ContextBlock>> {
+        let mut blocks: Vec<ContextBlock> = Vec::new();
+        let mut unresolved = Vec::new();
+        for reference in parse_mentions(query) {
+            if blocks.iter().any(|block| block.label == reference.text) {
+                continue;
+            }
+            let resolved = match &reference.mention {
+                Ok(mention) => self
+                    .resolve_one(&reference.text, mention)
+                    .await
+                    .map_err(|e| format!("{:#}", e)),
+                Err(reason) => Err(reason.clone()),
+            };
+            match resolved {
+                Ok(block) => blocks.push(block),
+                Err(reason) => unresolved.push((reference.text.clone(), reason)),
+            }
+        }
+        if !unresolved.is_empty() {
+            return Err(UnresolvedMentions(unresolved).into());
+        }
+
+        let mut remaining = self.max_total_chars;
+        for block in &mut blocks {
+            block.truncate(self.max_block_chars.min(remaining));
+            remaining -= block.content.chars().count();
+        }
+        Ok(blocks)
+    }
+
+    /// Expanded mentions of `query` as a context section
+    pub async fn context_for(&self, query: &str) -> Result<Option<String>> {
+        Ok(render_context(&self.resolve(query).await?))
+    }
+
+    async fn resolve_one(&self, label: &str, mention: &Mention) -> Result<ContextBlock> {
+        match mention {
+            Mention::File { path, lines } => self.file_block(label, path, *lines),
+            Mention::Symbol(name) => self.symbol_block(label, name).await,
+            Mention::Diff(range) => self.diff_block(label, range.as_deref()),
+            Mention::Dir(path) => self.dir_block(label, path),
+            Mention::Conversation(id) => self.conversation_block(label, id).await,
+            Mention::Plan(id) => self.plan_block(label, id),
+        }
+    }
+
+    async fn database(&self) -> Result<Arc<DatabaseManager>> {
+        match &self.database {
+            Some(database) => Ok(database.clone()),
+            None => get_or_initialize_database().await,
+        }
+    }
+
+    fn file_block(
+        &self,
+        label: &str,
+        path: &Path,
+        lines: Option<(usize, usize)>,
+    ) -> Result<ContextBlock> {
+        let full_path = self.root.join(path);
+        let content = std::fs::read_to_string(&full_path)
+            .with_context(|| format!("cannot read {}", path.display()))?;
+        let language = language_of(path);
+        let Some((start, end)) = lines else {
+            return Ok(
+                ContextBlock::new(label, path.display().to_string(), content)
+                    .with_language(language),
+            );
+        };
+
+        let total = content.lines().count();
+        if start > total {
+            bail!("{} has only {} lines", path.display(), total);
+        }
+        let end = end.min(total);
+        let excerpt: Vec<&str> = content
+            .lines()
+            .skip(start - 1)
+            .take(end - start + 1)
+            .collect();
+        Ok(ContextBlock::new(
+            label,
+            format!("lines {}-{} of {}", start, end, path.display()),
+            excerpt.join("\n"),
+        )
+        .with_language(language))
+    }
+
+    async fn symbol_block(&self, label: &str, name: &str) -> Result<ContextBlock> {
+        let indexer = SymbolIndexer::new(self.database().await?).await?;
+        // Quoted, so names are matched as a phrase rather than FTS syntax
+        let query = format!("\"{}\"", name.replace('"', ""));
+        let matches: Vec<_> = indexer
+            .search(&query, 50)
+            .await?
+            .into_iter()
+            .filter(|symbol| symbol.name == name)
+            .collect();
+        if matches.is_empty() {
+            bail!("no indexed symbol named {} (run `hive index`)", name);
+        }
+
+        let mut sections = Vec::new();
+        for symbol in matches.iter().take(MAX_SYMBOL_MATCHES) {
+            let path = self.root.join(&symbol.file_path);
+            let body = std::fs::read_to_string(&path).ok().map(|content| {
+                content
+                    .lines()
+                    .skip(symbol.start_pos.line)
+                    .take(symbol.end_pos.line.saturating_sub(symbol.start_pos.line) + 1)
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            });
+            let body = body
+                .or_else(|| symbol.signature.clone())
+                .unwrap_or_default();
+            sections.push(format!(
+                "// {}:{}-{}\n{}",
+                symbol.file_path.display(),
+                symbol.start_pos.line + 1,
+                symbol.end_pos.line + 1,
+                body
+            ));
+        }
+        let first = &matches[0];
+        let kind = format!("{:?}", first.kind).to_lowercase();
+        let description = match matches.len() {
+            1 => format!("{} in {}", kind, first.file_path.display()),
+            n => format!("{} definitions, showing {}", n, n.min(MAX_SYMBOL_MATCHES)),
+        };
+        Ok(ContextBlock::new(label, description, sections.join("\n\n"))
+            .with_language(language_of(&first.file_path)))
+    }
+
+    fn diff_block(&self, label: &str, range: Option<&str>) -> Result<ContextBlock> {
+        let repo = Repository::discover(&self.root).context("not in a git repository")?;
+        let spec = DiffSpec::parse(range, false)?;
+        let files = diff_files(&repo, &spec, 3)?;
+        if files.is_empty() {
+            bail!("no changes in {}", spec.describe());
+        }
+
+        let mut diff = String::new();
+        for file in &files {
+            diff.push_str(&format!("--- {}\n", file.path.display()));
+            for hunk in &file.hunks {
+                diff.push_str(&hunk.header);
+                diff.push('\n');
+                for line in &hunk.lines {
+                    diff.push(match line.kind {
+                        LineKind::Added => '+',
+                        LineKind::Removed => '-',
+                        LineKind::Context => ' ',
+                    });
+                    diff.push_str(&line.content);
+                    diff.push('\n');
+                }
+            }
+        }
+        Ok(ContextBlock::new(
+            label,
+            format!("{}, {} files", spec.describe(), files.len()),
+            diff.trim_end().to_string(),
+        )
+        .with_language("diff"))
+    }
+
+    fn dir_block(&self, label: &str, path: &Path) -> Result<ContextBlock> {
+        let dir = self.root.join(path);
+        if !dir.is_dir() {
+            bail!("{} is not a directory", path.display());
+        }
+
+        // Honours .gitignore, like `hive index`
+        let mut entries: Vec<String> = WalkBuilder::new(&dir)
+            .max_depth(Some(3))
+            .require_git(false)
+            .build()
+            .flatten()
+            .filter(|entry| entry.depth() > 0)
+            .map(|entry| {
+                let relative = entry.path().strip_prefix(&dir).unwrap_or(entry.path());
+                let is_dir = entry.file_type().is_some_and(|t| t.is_dir());
+                format!("{}{}", relative.display(), if is_dir { "/" } else { "" })
+            })
+            .collect();
+        entries.sort();
+        let total = entries.len();
+        entries.truncate(MAX_DIR_ENTRIES);
+        if total > entries.len() {
+            entries.push(format!("… {} more", total - MAX_DIR_ENTRIES));
+        }
+        Ok(ContextBlock::new(
+            label,
+            format!("listing of {}, {} entries", path.display(), total),
+            entries.join("\n"),
+        ))
+    }
+
+    async fn conversation_block(&self, label: &str, id: &str) -> Result<ContextBlock> {
+        let store = ThreadStore::new(self.database().await?)?;
+        let thread = store.load(id)?;
+
+        let mut text = String::new();
+        if let Some(summary) = thread.summary.as_ref().filter(|s| !s.is_empty()) {
+            text.push_str(&format!("Summary of earlier turns: {}\n\n", summary));
+        }
+        for turn in &thread.turns[thread.summarized_turns.min(thread.turns.len())..] {
+            text.push_str(&format!("Q: {}\nA: {}\n\n", turn.question, turn.answer));
+        }
+        Ok(ContextBlock::new(
+            label,
+            format!(
+                "conversation {}{}, {} turns",
+                thread.short_id(),
+                thread
+                    .title
+                    .as_ref()
+                    .map(|title| format!(" \"{}\"", title))
+                    .unwrap_or_default(),
+                thread.turns.len()
+            ),
+            text.trim_end().to_string(),
+        ))
+    }
+
+    fn plan_block(&self, label: &str, id: &str) -> Result<ContextBlock> {
+        let path = self.find_plan(id)?;
+        let plan: Value = serde_json::from_str(&std::fs::read_to_string(&path)?)
+            .with_context(|| format!("{} is not a plan", path.display()))?;
+        let field = |value: &Value, name: &str| {
+            value
+                .get(name)
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_string()
+        };
+
+        let mut text = format!(
+            "# {}\n{}\n",
+            field(&plan, "title"),
+            field(&plan, "description")
+        );
+        let tasks = plan.get("tasks").and_then(Value::as_array);
+        for (index, task) in tasks.into_iter().flatten().enumerate() {
+            text.push_str(&format!(
+                "\n{}. {} [{}]\n   {}",
+                index + 1,
+                field(task, "title"),
+                field(task, "priority"),
+                field(task, "description")
+            ));
+        }
+        Ok(ContextBlock::new(
+            label,
+            format!("plan {}", field(&plan, "id")),
+            text.trim_end().to_string(),
+        ))
+    }
+
+    /// A saved plan by id or unique id prefix, or a plan file path
+    fn find_plan(&self, id: &str) -> Result<PathBuf> {
+        let file = self.root.join(id);
+        if file.is_file() {
+            return Ok(file);
+        }
+        let matches: Vec<PathBuf> = std::fs::read_dir(&self.plans_dir)
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .map(|entry| entry.path())
+                    .filter(|path| {
+                        path.extension().is_some_and(|ext| ext == "json")
+                            && path
+                                .file_stem()
+                                .and_then(|stem| stem.to_str())
+                                .is_some_and(|stem| stem.starts_with(id))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        match matches.as_slice() {
+            [path] => Ok(path.clone()),
+            [] => Err(anyhow!("no saved plan matching '{}'", id)),
+            _ => Err(anyhow!(
+                "'{}' matches {} plans; use a longer id",
+                id,
+                matches.len()
+            )),
+        }
+    }
+}
+
+/// Context for the mentions in `query`, relative to the current directory
+pub async fn mention_context(query: &str) -> Result<Option<String>> {
+    MentionResolver::new(std::env::current_dir()?)
+        .context_for(query)
+        .await
+}
+
+/// Code fence language for a file
+fn language_of(path: &Path) -> &'static str {
+    match path.extension().and_then(|ext| ext.to_str()).unwrap_or("") {
+        "rs" => "rust",
+        "py" => "python",
+        "ts" | "tsx" => "typescript",
+        "js" | "jsx" | "mjs" => "javascript",
+        "go" => "go",
+        "java" => "java",
+        "c" | "h" => "c",
+        "cpp" | "cc" | "hpp" => "cpp",
+        "rb" => "ruby",
+        "sh" => "bash",
+        "toml" => "toml",
+        "json" => "json",
+        "yaml" | "yml" => "yaml",
+        "md" => "markdown",
+        _ => "",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consensus::threads::Thread;
+    use crate::core::database::DatabaseConfig;
+    use tempfile::TempDir;
+
+    fn parsed(query: &str) -> Vec<Result<Mention, String>> {
+        parse_mentions(query)
+            .into_iter()
+            .map(|reference| reference.mention)
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_mentions() {
+        assert_eq!(
+            parsed("Why does @file:src/main.rs:10-20 call @symbol:run_pipeline?"),
+            vec![
+                Ok(Mention::File {
+                    path: PathBuf::from("src/main.rs"),
+                    lines: Some((10, 20)),
+                }),
+                Ok(Mention::Symbol("run_pipeline".to_string())),
+            ]
+        );
+        assert_eq!(
+            parsed("(@diff) vs @diff:main... and @file:Cargo.toml:7, mail me@example.com"),
+            vec![
+                Ok(Mention::Diff(None)),
+                Ok(Mention::Diff(Some("main".to_string()))),
+                Ok(Mention::File {
+                    path: PathBuf::from("Cargo.toml"),
+                    lines: Some((7, 7)),
+                }),
+            ]
+        );
+        assert_eq!(
+            parsed("@dir:src @conversation:ab12 @plan:9f3 @codebase @alice"),
+            vec![
+                Ok(Mention::Dir(PathBuf::from("src"))),
+                Ok(Mention::Conversation("ab12".to_string())),
+                Ok(Mention::Plan("9f3".to_string())),
+            ]
+        );
+        assert!(parsed("@file: @symbol @file:a.rs:9-3")
+            .iter()
+            .all(|mention| mention.is_err()));
+    }
+
+    #[tokio::test]
+    async fn test_resolve_files_dirs_and_plans() {
+        let dir = TempDir::new().unwrap();
+        std::fs::create_dir(dir.path().join("src")).unwrap();
+        let source: String = (1..=30).map(|n| format!("line {}\n", n)).collect();
+        std::fs::write(dir.path().join("src/main.rs"), &source).unwrap();
+        std::fs::write(dir.path().join(".gitignore"), "target/\n").unwrap();
+        std::fs::create_dir(dir.path().join("target")).unwrap();
+        std::fs::write(dir.path().join("target/out"), "").unwrap();
+        let plans = dir.path().join("plans");
+        std::fs::create_dir(&plans).unwrap();
+        std::fs::write(
+            plans.join("9f3a.json"),
+            r#"{"id": "9f3a", "title": "Ship it", "description": "Release 2.0",
+                "tasks": [{"title": "Tag", "priority": "High", "description": "git tag"}]}"#,
+        )
+        .unwrap();
+        let resolver = MentionResolver::new(dir.path()).with_plans_dir(&plans);
+
+        let blocks = resolver
+            .resolve("Compare @file:src/main.rs:2-3 with @dir:. and @plan:9f")
+            .await
+            .unwrap();
+        assert_eq!(blocks.len(), 3);
+        assert_eq!(blocks[0].content, "line 2\nline 3");
+        assert_eq!(blocks[0].description, "lines 2-3 of src/main.rs");
+        assert!(blocks[1].content.contains("src/main.rs"));
+        assert!(!blocks[1].content.contains("target/out"));
+        assert!(blocks[2].content.contains("1. Tag [High]"));
+
+        let context = render_context(&blocks).unwrap();
+        assert!(context
+            .contains("### @file:src/main.rs:2-3 (lines 2-3 of src/main.rs)\n```rust\nline 2"));
+
+        // Everything that fails is reported at once
+        let err = resolver
+            .resolve("@file:missing.rs @file:src/main.rs:99 @plan:zz @file:")
+            .await
+            .unwrap_err();
+        let unresolved = err.downcast_ref::<UnresolvedMentions>().unwrap();
+        assert_eq!(unresolved.0.len(), 4);
+        assert!(err
+            .to_string()
+            .contains("@file:src/main.rs:99: src/main.rs has only 30 lines"));
+
+        // Blocks share the total budget
+        let blocks = MentionResolver::new(dir.path())
+            .with_limits(50, 60)
+            .resolve("@file:src/main.rs @file:src/main.rs:1-30")
+            .await
+            .unwrap();
+        assert_eq!(blocks[0].content.chars().count(), 50);
+        assert!(blocks[0].truncated);
+        assert_eq!(blocks[1].content.chars().count(), 10);
+    }
+
+    #[tokio::test]
+    async fn test_resolve_diff_and_conversation() {
+        let dir = TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        std::fs::write(dir.path().join("lib.rs"), "fn a() {}\n").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("lib.rs")).unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = git2::Signature::now("Test", "test@example.com").unwrap();
+        repo.commit(Some("HEAD"), &signature, &signature, "init", &tree, &[])
+            .unwrap();
+        std::fs::write(dir.path().join("lib.rs"), "fn b() {}\n").unwrap();
+
+        let db_dir = TempDir::new().unwrap();
+        let db = Arc::new(
+            DatabaseManager::new(DatabaseConfig {
+                path: db_dir.path().join("hive.db"),
+                ..DatabaseConfig::default()
+            })
+            .await
+            .unwrap(),
+        );
+        let store = ThreadStore::new(db.clone()).unwrap();
+        let mut thread = Thread::new();
+        store
+            .append_turn(&mut thread, "What is a?", "A function.", None, 0.0)
+            .unwrap();
+
+        let resolver = MentionResolver::new(dir.path()).with_database(db);
+        let query = format!("Given @diff and @conversation:{}", thread.short_id());
+        let blocks = resolver.resolve(&query).await.unwrap();
+        assert!(blocks[0].content.contains("-fn a() {}\n+fn b() {}"));
+        assert!(blocks[1].content.contains("Q: What is a?\nA: A function."));
+    }
+
+    #[tokio::test]
+    async fn test_resolve_symbol() {
+        let dir = TempDir::new().unwrap();
+        std::fs::create_dir(dir.path().join("src")).unwrap();
+        let source = "fn main() {\n    helper();\n}\n\nfn helper() {\n    println!(\"hi\");\n}\n";
+        std::fs::write(dir.path().join("src/lib.rs"), source).unwrap();
+
+        let db_dir = TempDir::new().unwrap();
+        let db = Arc::new(
+            DatabaseManager::new(DatabaseConfig {
+                path: db_dir.path().join("hive.db"),
+                ..DatabaseConfig::default()
+            })
+            .await
+            .unwrap(),
+        );
+        SymbolIndexer::new(db.clone())
+            .await
+            .unwrap()
+            .index_file(Path::new("src/lib.rs"), source)
+            .await
+            .unwrap();
+
+        let resolver = MentionResolver::new(dir.path()).with_database(db);
+        let blocks = resolver
+            .resolve("What does @symbol:helper do?")
+            .await
+            .unwrap();
+        assert_eq!(blocks.len(), 1);
+        assert!(blocks[0].description.ends_with("in src/lib.rs"));
+        assert!(blocks[0].content.starts_with("// src/lib.rs:5-"));
+        assert!(blocks[0].content.contains("\nfn helper() {"));
+        assert!(!blocks[0].content.contains("fn main"));
+
+        let err = resolver.resolve("@symbol:missing").await.unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("@symbol:missing: no indexed symbol named missing"));
+    }
+}
@@ -4,15 +4,20 @@
 pub mod ai_file_executor;
 pub mod ai_operation_parser;
 pub mod ai_rollback_executor;
+pub mod batch;
 pub mod cancellation;
 pub mod codebase_intelligence;
+pub mod commit_message;
 pub mod confidence_scoring;
+pub mod context_budget;
 pub mod cross_validator;
 pub mod curator_output_format;
 pub mod dependency_graph;
 pub mod direct_executor;
 pub mod engine;
+pub mod eval;
 pub mod fact_checker;
+pub mod fanout;
 pub mod file_executor;
 pub mod file_operations;
 pub mod file_planner;
@@ -21,6 +26,7 @@ pub mod iteration_handler;
 pub mod learning_system;
 pub mod maintenance;
 pub mod memory;
+pub mod mentions;
 pub mod mode_detector;
 pub mod models;
 pub mod openrouter;
@@ -36,6 +42,7 @@ pub mod outcome_tracker;
 pub mod pipeline;
 pub mod profiles;
 pub mod repository_context;
+pub mod review;
 pub mod rollback_executor;
 pub mod rollback_plan;
 pub mod rollback_planner;
@@ -45,7 +52,10 @@ pub mod smart_decision_engine;
 pub mod stages;
 pub mod streaming;
 pub mod streaming_executor;
+pub mod telemetry;
 pub mod temporal;
+pub mod threads;
+pub mod topology;
 pub mod types;
 pub mod verification;
 pub mod verified_context_builder;
@@ -64,6 +74,10 @@ pub use confidence_scoring::{
     ConfidenceInterval, ConfidenceScoringEngine, ImprovementSuggestion, PrimaryFactor,
     ScoreBreakdown, ScoringResult, ScoringStatistics, ScoringWeights,
 };
+pub use context_budget::{
+    ContextBudget, ContextPriority, ContextReport, ContextSection, ModelLimits, TokenCounter,
+    TokenizerFamily,
+};
 pub use cross_validator::{
     ConsensusHealth, ConsensusReport, ContradictionSeverity, ContradictionType, CrossValidator,
     SemanticContradiction, StageDiscrepancy,
@@ -78,6 +92,7 @@ pub use engine::ConsensusEngine;
 pub use fact_checker::{
     Contradiction, FactChecker, RecommendedAction as FactCheckerRecommendedAction, ValidationResult,
 };
+pub use fanout::{FanOutDefinition, MergeStrategy};
 pub use file_executor::{
     BackupInfo as FileBackupInfo, BackupManager as FileBackupManager, ExecutionResult,
     ExecutionSummary, ExecutorConfig, FileOperationExecutor, SyntaxValidator,
@@ -167,6 +182,8 @@ pub use streaming_executor::{
     ExecutionStatus, StatusCallback, StreamingExecutorBuilder, StreamingOperationExecutor,
 };
 pub use temporal::TemporalContextProvider;
+pub use threads::{Summarizer, Thread, ThreadStore, ThreadSummary};
+pub use topology::{PipelineDefinition, PipelineStore, SkipCondition, StageDefinition};
 pub use types::{
     ConsensusConfig, ConsensusProfile, ConsensusRequest, ConsensusResponse, ConsensusResult,
     ResponseMetadata, Stage,
@@ -2,7 +2,12 @@
 // Manages flow from Generator → Refiner → Validator → Curator
 
 use crate::ai_helpers::AIHelperEcosystem;
+use crate::analytics::metrics;
 use crate::consensus::cancellation::{CancellationChecker, CancellationReason, CancellationToken};
+use crate::consensus::confidence_scoring::ConfidenceScoringEngine;
+use crate::consensus::context_budget::{ContextBudget, ModelLimits};
+use crate::consensus::cross_validator::CrossValidator;
+use crate::consensus::fanout::{self, FanOutDefinition, MergeStrategy, SilentCallbacks};
 use crate::consensus::memory::ConsensusMemory;
 use crate::consensus::repository_context::RepositoryContextManager;
 use crate::consensus::stages::{
@@ -12,7 +17,9 @@ use crate::consensus::stages::{
 use crate::consensus::streaming::{
     ConsoleCallbacks, ProgressInfo, ProgressTracker, StreamingCallbacks,
 };
+use crate::consensus::telemetry;
 use crate::consensus::temporal::TemporalContextProvider;
+use crate::consensus::topology::{collect_inputs, PipelineDefinition, TemplateStage};
 use crate::consensus::verified_context_builder::VerifiedContextBuilder;
 use crate::consensus::{
     operation_analysis::{AutoAcceptMode, OperationContext as ConsensusOperationContext},
@@ -22,23 +29,28 @@ use crate::consensus::{
 };
 use std::path::PathBuf;
 // Removed DynamicModelSelector - direct execution uses profile's generator model
-use crate::consensus::types::{
-    AnalyticsFeatures, ConsensusConfig, ConsensusProfile, ConsensusResult, ResponseMetadata, Stage,
-    StageAnalytics, StageResult, TokenUsage,
-};
-// use crate::hooks::{HooksSystem, EventType, EventSource, HookEvent, ConsensusIntegration};
 use crate::consensus::models::ModelManager;
 use crate::consensus::openrouter::{
     OpenRouterClient, OpenRouterMessage, OpenRouterRequest, OpenRouterResponse,
     SimpleStreamingCallbacks, StreamingCallbacks as OpenRouterStreamingCallbacks,
 };
+use crate::consensus::types::{
+    AnalyticsFeatures, ConsensusConfig, ConsensusProfile, ConsensusResult, Message,
+    ResponseMetadata, Stage, StageAnalytics, StageResult, TokenUsage,
+};
 use crate::core::database::DatabaseManager;
 use crate::core::db_actor::DatabaseService;
 use crate::core::usage_tracker::UsageTracker;
+use crate::hooks::{
+    ConsensusIntegration, EventSource, EventType, HookEvent, HookVeto, HooksSystem,
+    StageHookResult, StageInput,
+};
+use crate::providers::openrouter::{ModelSelectionStrategy, ModelSelector};
 use crate::subscription::conversation_gateway::ConversationGateway;
 use anyhow::{anyhow, Context, Result};
 use chrono::{DateTime, Utc};
 use rusqlite::{params, OptionalExtension};
+use std::collections::HashMap;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::{Instant, SystemTime};
@@ -57,9 +69,10 @@ pub struct ConsensusPipeline {
         Option<Arc<crate::consensus::codebase_intelligence::CodebaseIntelligence>>,
     verified_context_builder: VerifiedContextBuilder,
     stages: Vec<Box<dyn ConsensusStage>>,
+    topology: Option<PipelineDefinition>,
     callbacks: Arc<dyn StreamingCallbacks>,
-    // hooks_system: Option<Arc<HooksSystem>>,
-    // consensus_integration: Option<Arc<ConsensusIntegration>>,
+    hooks_system: Option<Arc<HooksSystem>>,
+    consensus_integration: Option<Arc<ConsensusIntegration>>,
     openrouter_client: Option<Arc<OpenRouterClient>>,
     model_manager: Option<Arc<ModelManager>>,
     database: Option<Arc<DatabaseManager>>,
@@ -71,6 +84,8 @@ pub struct ConsensusPipeline {
     file_executor: Option<Arc<FileOperationExecutor>>,
     mode_detector: Option<Arc<ModeDetector>>,
     direct_handler: Option<Arc<DirectExecutionHandler>>,
+    /// Earlier turns of the thread this run continues, replayed to every stage
+    history: Vec<Message>,
 }
 
 impl ConsensusPipeline {
@@ -112,9 +127,10 @@ impl ConsensusPipeline {
                 Box::new(ValidatorStage::new()),
                 Box::new(FileAwareCuratorStage::new()),
             ],
+            topology: None, // Built-in 4 stages unless a pipeline definition is set
             callbacks,
-            // hooks_system: None,
-            // consensus_integration: None,
+            hooks_system: None,
+            consensus_integration: None,
             openrouter_client,
             model_manager,
             database: None,   // Will be set later when needed
@@ -126,6 +142,7 @@ impl ConsensusPipeline {
             file_executor: None,    // Will be set when AI helpers are configured
             mode_detector: None,    // Will be set when AI helpers are configured
             direct_handler: None,   // Will be set when components are configured
+            history: Vec::new(),
         }
     }
 
@@ -326,17 +343,29 @@ impl ConsensusPipeline {
         Ok(())
     }
 
+    /// Run the stages of a pipeline definition instead of the built-in 4 stages
+    pub fn with_topology(mut self, topology: PipelineDefinition) -> Self {
+        self.topology = Some(topology);
+        self
+    }
+
+    /// Replay earlier conversation turns between each stage's system prompt and the question
+    pub fn with_history(mut self, history: Vec<Message>) -> Self {
+        self.history = history;
+        self
+    }
+
     /// Set the hooks system for this pipeline
-    // pub fn with_hooks(mut self, hooks_system: Arc<HooksSystem>) -> Self {
-    //     self.hooks_system = Some(hooks_system);
-    //     self
-    // }
+    pub fn with_hooks(mut self, hooks_system: Arc<HooksSystem>) -> Self {
+        self.hooks_system = Some(hooks_system);
+        self
+    }
 
     /// Set the consensus integration for enterprise features
-    // pub fn with_consensus_integration(mut self, integration: Arc<ConsensusIntegration>) -> Self {
-    //     self.consensus_integration = Some(integration);
-    //     self
-    // }
+    pub fn with_consensus_integration(mut self, integration: Arc<ConsensusIntegration>) -> Self {
+        self.consensus_integration = Some(integration);
+        self
+    }
 
     /// Get the file executor if available
     pub fn get_file_executor(&self) -> Option<Arc<FileOperationExecutor>> {
@@ -376,6 +405,21 @@ impl ConsensusPipeline {
         context: Option<String>,
         user_id: Option<String>,
         cancellation_token: CancellationToken,
+    ) -> Result<ConsensusResult> {
+        let started_at = Utc::now();
+        let outcome = self
+            .run_pipeline(question, context, user_id, cancellation_token)
+            .await;
+        telemetry::global().finish_run(started_at, &outcome).await;
+        outcome
+    }
+
+    async fn run_pipeline(
+        &self,
+        question: &str,
+        context: Option<String>,
+        user_id: Option<String>,
+        cancellation_token: CancellationToken,
     ) -> Result<ConsensusResult> {
         // Create cancellation checker for periodic checks
         let mut cancellation_checker = CancellationChecker::new(
@@ -498,8 +542,11 @@ impl ConsensusPipeline {
             // Get the current license key from configurations through database service
             let license_key = db_service.get_license_key().await?;
 
-            if let Some(license_key) = license_key {
-                tracing::info!("Starting consensus with license key: {}", &license_key[..8]); // Log only first 8 chars for security
+            if let Some(license_key) = license_key.filter(|key| !key.is_empty()) {
+                tracing::info!(
+                    "Starting consensus with license key: {}…",
+                    license_key.get(..8).unwrap_or_default()
+                ); // Log only first 8 chars for security
 
                 // Call D1 to validate license and increment conversation count IMMEDIATELY
                 // D1 is the source of truth for user information
@@ -546,19 +593,42 @@ impl ConsensusPipeline {
         // Store the D1 authorization for later verification
         let d1_auth = license_key_for_d1.map(|(_, auth)| auth);
 
-        // Emit BeforeConsensus hook event
-        //         // if let Some(hooks) = &self.hooks_system {
-        //         //     let event = HookEvent::new(
-        //         //         EventType::BeforeConsensus,
-        //                 EventSource::Consensus { stage: "pipeline".to_string() }
-        //             )
-        //             .with_context("question", question)
-        //             .with_context("conversation_id", &conversation_id);
-        //
-        //             if let Err(e) = hooks.dispatch_event(event).await {
-        //                 tracing::warn!("Failed to dispatch BeforeConsensus hook event: {}", e);
-        //             }
-        //         }
+        // Emit BeforeConsensus hook event; hooks may rewrite the question or veto the run
+        let mut consensus_input = StageInput {
+            question: question.to_string(),
+            previous_answer: None,
+            context,
+        };
+        let event = HookEvent::new(
+            EventType::BeforeConsensus,
+            EventSource::Consensus {
+                stage: "pipeline".to_string(),
+            },
+        )
+        .with_context("conversation_id", &conversation_id);
+        if let Some(outcome) = self
+            .emit_hook_event(consensus_input.attach(event))
+            .await
+            .map(StageHookResult::from)
+        {
+            if let Some(veto) = &outcome.veto {
+                let error = hook_veto_error("Consensus", veto);
+                // Reported against the first stage so streaming clients see why nothing ran
+                if let Err(e) = self.callbacks.on_error(Stage::Generator, &error) {
+                    tracing::warn!("Failed to report hook veto: {}", e);
+                }
+                return Err(error);
+            }
+            if consensus_input.apply(&outcome.modifications) {
+                tracing::info!("BeforeConsensus hooks modified the question or context");
+            }
+        }
+        let StageInput {
+            question: hooked_question,
+            context,
+            ..
+        } = consensus_input;
+        let question = hooked_question.as_str();
 
         // Check if temporal context is needed
         let temporal_context = if self.temporal_provider.requires_temporal_context(question) {
@@ -728,8 +798,24 @@ impl ConsensusPipeline {
             }
         };
 
-        // Run through all 4 stages for Consensus mode
-        for (i, stage_handler) in stages_to_use.iter().enumerate() {
+        // Run the configured topology, or the built-in 4 stages
+        let topology = self
+            .topology
+            .clone()
+            .unwrap_or_else(PipelineDefinition::builtin);
+        tracing::info!(
+            "Running '{}' pipeline with {} stages",
+            topology.name,
+            topology.stages.len()
+        );
+        let regular_curator = CuratorStage::new();
+        let repo_related = self
+            .verified_context_builder
+            .is_repository_related_question(question);
+        let mut outputs: HashMap<String, String> = HashMap::new();
+        let mut last_ran: Option<&str> = None;
+
+        for (i, stage_def) in topology.stages.iter().enumerate() {
             // Add a small delay between stages to prevent UI update cascade
             if i > 0 {
                 tokio::time::sleep(std::time::Duration::from_millis(50)).await;
@@ -739,144 +825,333 @@ impl ConsensusPipeline {
             // Check for cancellation before each stage
             cancellation_checker.check_if_due()?;
 
-            let stage = stage_handler.stage();
+            if let Some(reason) = stage_def.skip_reason(question, &outputs)? {
+                tracing::info!("⏭️ Skipping '{}' stage: {}", stage_def.id, reason);
+                continue;
+            }
 
-            // Use model directly from profile - the maintenance system ensures these are always valid
-            let model = self.profile.get_model_for_stage(stage).to_string();
+            let stage = stage_def.role;
+
+            // Use the stage's model, else the profile's - the maintenance system keeps those valid
+            let model = stage_def
+                .model
+                .clone()
+                .unwrap_or_else(|| self.profile.get_model_for_stage(stage).to_string());
+
+            // Templated stages use their own prompt; others use the built-in handler for
+            // their role, with the regular curator for general knowledge questions
+            let template_stage = stage_def
+                .render_system_prompt(question, &outputs)
+                .map(|prompt| TemplateStage::new(stage, prompt));
+            let stage_handler: &dyn ConsensusStage = match &template_stage {
+                Some(template_stage) => template_stage,
+                None if stage == Stage::Curator && !repo_related => &regular_curator,
+                None => stages_to_use
+                    .iter()
+                    .find(|handler| handler.stage() == stage)
+                    .map(|handler| handler.as_ref())
+                    .ok_or_else(|| anyhow!("No handler for {} stage", stage.display_name()))?,
+            };
+            if stage == Stage::Curator && template_stage.is_none() {
+                tracing::info!(
+                    "🎨 Using {} for this question",
+                    if repo_related {
+                        "FileAwareCuratorStage"
+                    } else {
+                        "regular CuratorStage"
+                    }
+                );
+            }
 
-            // Build verified context for this specific stage (includes mandatory verification)
-            let verified_stage_context = match self
-                .verified_context_builder
-                .build_verified_stage_context(
-                    stage,
-                    question,
-                    context.clone(),
-                    temporal_context.clone(),
-                    memory_context.clone(),
-                    self.repository_context.clone(),
-                    self.ai_helpers.as_ref().cloned(),
-                )
-                .await
-            {
-                Ok(context) => {
-                    tracing::info!(
-                        "Built verified context for {} stage: {} chars",
-                        stage.display_name(),
-                        context.len()
-                    );
-                    Some(context)
+            // Fit the context to the smallest window among the models running this stage
+            let mut limits = ModelLimits::lookup(self.database.as_deref(), &model).await;
+            if let Some(fan_out) = &stage_def.fan_out {
+                for candidate in &fan_out.models {
+                    limits =
+                        limits.min(ModelLimits::lookup(self.database.as_deref(), candidate).await);
                 }
-                Err(e) => {
-                    tracing::error!(
-                        "Failed to build verified context for {} stage: {}",
-                        stage.display_name(),
-                        e
-                    );
-                    return Err(e);
+            }
+            let budget = ContextBudget::new(limits);
+            let stage_inputs = collect_inputs(&stage_def.input_ids(last_ran), &outputs);
+
+            // Build verified context for this specific stage (includes mandatory verification)
+            let (verified_stage_context, context_report) = if !stage_def.include_context {
+                (None, None)
+            } else {
+                match self
+                    .verified_context_builder
+                    .build_verified_stage_sections(
+                        stage,
+                        question,
+                        context.clone(),
+                        temporal_context.clone(),
+                        memory_context.clone(),
+                        self.repository_context.clone(),
+                        self.ai_helpers.as_ref().cloned(),
+                    )
+                    .await
+                {
+                    Ok(sections) => {
+                        let prompt = format!(
+                            "{}\n{}\n{}",
+                            render_history(&self.history),
+                            question,
+                            stage_inputs.as_deref().unwrap_or_default()
+                        );
+                        let assembled = budget.assemble(&prompt, sections);
+                        tracing::info!(
+                            "Built verified context for {} stage: {} of {} tokens{}",
+                            stage.display_name(),
+                            assembled.report.used,
+                            assembled.report.budget,
+                            if assembled.report.estimated {
+                                " (estimated)"
+                            } else {
+                                ""
+                            }
+                        );
+                        (Some(assembled.text), Some(assembled.report))
+                    }
+                    Err(e) => {
+                        tracing::error!(
+                            "Failed to build verified context for {} stage: {}",
+                            stage.display_name(),
+                            e
+                        );
+                        return Err(e);
+                    }
                 }
             };
 
-            // Execute pre-stage hooks with enterprise integration
-            // if let Some(integration) = &self.consensus_integration {
-            //     // Estimate stage cost
-            //     let estimated_cost = self.estimate_stage_cost(stage, model, question).await?;
-            //
-            //     // Execute pre-stage hooks with cost and quality checks
-            //     let pre_stage_result = integration.execute_pre_stage_hooks(
-            //         stage,
-            //         &conversation_id,
-            //         question,
-            //         model,
-            //         estimated_cost,
-            //     ).await?;
-            //
-            //     // Check if we should proceed
-            //     if !pre_stage_result.proceed {
-            //         if let Some(approval_required) = pre_stage_result.approval_required {
-            //             // Handle approval requirement
-            //             tracing::info!("Approval required for {} stage: {}", stage.as_str(), approval_required.description);
-            //
-            //             // For now, we'll continue with a warning
-            //             // In a real implementation, this would wait for approval
-            //             for warning in &pre_stage_result.warnings {
-            //                 tracing::warn!("Pre-stage warning: {}", warning);
-            //             }
-            //         } else {
-            //             // Stage was blocked
-            //             return Err(anyhow::anyhow!("Stage {} was blocked by enterprise hooks", stage.as_str()));
-            //         }
-            //     }
-            // }
-
-            // Emit BeforeStage hook event
-            //             if let Some(hooks) = &self.hooks_system {
-            //                 let stage_event_type = match stage {
-            //                     Stage::Generator => EventType::BeforeGeneratorStage,
-            //                     Stage::Refiner => EventType::BeforeRefinerStage,
-            //                     Stage::Validator => EventType::BeforeValidatorStage,
-            //                     Stage::Curator => EventType::BeforeCuratorStage,
-            //                 };
-            //
-            //                 let event = HookEvent::new(
-            //                     stage_event_type,
-            //                     EventSource::Consensus { stage: stage.as_str().to_string() }
-            //                 )
-            //                 .with_context("question", question)
-            //                 .with_context("conversation_id", &conversation_id)
-            //                 .with_context("model", model)
-            //                 .with_context("stage_index", i)
-            //                 .with_context("previous_answer", previous_answer.as_deref().unwrap_or(""));
-            //
-            //                 if let Err(e) = hooks.dispatch_event(event).await {
-            //                     tracing::warn!("Failed to dispatch Before{:?}Stage hook event: {}", stage, e);
-            //                 }
-            //             }
+            // Execute pre-stage hooks; they see the stage input and may rewrite or veto it
+            let mut stage_input = StageInput {
+                question: question.to_string(),
+                previous_answer: stage_inputs,
+                context: verified_stage_context,
+            };
+            let pre_stage_result = if let Some(integration) = &self.consensus_integration {
+                let estimated_cost = estimate_stage_cost(&budget, stage, &stage_input);
+                Some(
+                    integration
+                        .execute_pre_stage_hooks(
+                            stage,
+                            &conversation_id,
+                            &model,
+                            estimated_cost,
+                            &stage_input,
+                        )
+                        .await?,
+                )
+            } else {
+                let event = HookEvent::new(
+                    stage_event_type(stage, true),
+                    EventSource::Consensus {
+                        stage: stage.as_str().to_string(),
+                    },
+                )
+                .with_context("conversation_id", &conversation_id)
+                .with_context("model", &model)
+                .with_context("stage_index", i);
+                self.emit_hook_event(stage_input.attach(event))
+                    .await
+                    .map(StageHookResult::from)
+            };
+
+            if let Some(pre_stage_result) = pre_stage_result {
+                for warning in &pre_stage_result.warnings {
+                    tracing::warn!("Pre-{} hook warning: {}", stage.as_str(), warning);
+                }
+                if let Some(veto) = &pre_stage_result.veto {
+                    return Err(self.stage_vetoed(stage, veto));
+                }
+                if !pre_stage_result.proceed {
+                    if let Some(approval_required) = &pre_stage_result.approval_required {
+                        // Approvals are not awaited yet; continue with the warnings logged above
+                        tracing::info!(
+                            "Approval required for {} stage: {}",
+                            stage.as_str(),
+                            approval_required.description
+                        );
+                    } else {
+                        return Err(anyhow!(
+                            "{} stage was blocked by enterprise hooks",
+                            stage.display_name()
+                        ));
+                    }
+                }
+                if stage_input.apply(&pre_stage_result.modifications) {
+                    tracing::info!("Hooks modified the {} stage prompt", stage.display_name());
+                }
+            }
 
             // Notify stage start
             self.callbacks.on_stage_start(stage, &model)?;
 
-            // Run the stage with verified context (includes mandatory repository verification)
-            let stage_context = verified_stage_context.as_deref();
-
-            let stage_result = self
-                .run_single_stage(
-                    stage,
-                    stage_handler.as_ref(),
-                    question,
-                    previous_answer.as_deref(),
-                    stage_context,
-                    &conversation_id,
-                    &model,
-                    &cancellation_token,
-                )
-                .await
-                .with_context(|| format!("Failed to run {} stage", stage.display_name()))?;
+            // Run the stage with verified context (includes mandatory repository verification),
+            // on several models at once when it fans out
+            let stage_run = match &stage_def.fan_out {
+                Some(fan_out) => {
+                    self.run_fan_out_stage(
+                        stage,
+                        stage_handler,
+                        &stage_input,
+                        &conversation_id,
+                        &model,
+                        stage_def.temperature,
+                        fan_out,
+                        &cancellation_token,
+                    )
+                    .await
+                }
+                None => {
+                    self.run_single_stage(
+                        stage,
+                        stage_handler,
+                        &stage_input.question,
+                        stage_input.previous_answer.as_deref(),
+                        stage_input.context.as_deref(),
+                        &conversation_id,
+                        &model,
+                        stage_def.temperature,
+                        self.callbacks.clone(),
+                        &cancellation_token,
+                    )
+                    .await
+                }
+            };
+            let mut stage_result = match stage_run.context(telemetry::StageFailure {
+                stage,
+                model: model.clone(),
+            }) {
+                Ok(result) => result,
+                Err(e) => {
+                    let event = HookEvent::new(
+                        EventType::ConsensusError,
+                        EventSource::Consensus {
+                            stage: stage.as_str().to_string(),
+                        },
+                    )
+                    .with_context("question", question)
+                    .with_context("conversation_id", &conversation_id)
+                    .with_context("model", &model)
+                    .with_context("error", format!("{:#}", e));
+                    self.emit_hook_event(event).await;
+                    return Err(e);
+                }
+            };
+            if let Some(analytics) = stage_result.analytics.as_mut() {
+                analytics.context = context_report;
+            }
 
             // Update cost
             if let Some(analytics) = &stage_result.analytics {
                 total_cost += analytics.cost;
 
                 // Emit cost control hook events if needed
-                //                 if let Some(hooks) = &self.hooks_system {
-                //                     if analytics.cost > 0.05 { // Threshold for cost notifications
-                //                         let cost_event = HookEvent::new(
-                //                             EventType::CostThresholdReached,
-                //                             EventSource::Consensus { stage: stage.as_str().to_string() }
-                //                         )
-                //                         .with_context("estimated_cost", analytics.cost)
-                //                         .with_context("stage_cost", analytics.cost)
-                //                         .with_context("total_cost", total_cost)
-                //                         .with_context("model", model);
-                //
-                //                         if let Err(e) = hooks.dispatch_event(cost_event).await {
-                //                             tracing::warn!("Failed to dispatch CostThresholdReached hook event: {}", e);
-                //                         }
-                //                     }
-                //                 }
+                let threshold = self
+                    .hooks_system
+                    .as_ref()
+                    .map(|hooks| hooks.stage_cost_threshold());
+                if threshold.is_some_and(|threshold| analytics.cost > threshold) {
+                    let cost_event = HookEvent::new(
+                        EventType::CostThresholdReached,
+                        EventSource::Consensus {
+                            stage: stage.as_str().to_string(),
+                        },
+                    )
+                    .with_context("conversation_id", &conversation_id)
+                    .with_context("estimated_cost", analytics.cost)
+                    .with_context("stage_cost", analytics.cost)
+                    .with_context("total_cost", total_cost)
+                    .with_context("model", &model);
+                    if let Some(veto) = self.emit_hook_event(cost_event).await.and_then(|o| o.veto)
+                    {
+                        return Err(self.stage_vetoed(stage, &veto));
+                    }
+                }
+            }
+
+            // Execute post-stage hooks; they see the stage output and may replace or veto it
+            let post_stage_result = if let Some(integration) = &self.consensus_integration {
+                Some(
+                    integration
+                        .execute_post_stage_hooks(stage, &stage_result)
+                        .await?,
+                )
+            } else {
+                let event = HookEvent::new(
+                    stage_event_type(stage, false),
+                    EventSource::Consensus {
+                        stage: stage.as_str().to_string(),
+                    },
+                )
+                .with_context("question", &stage_input.question)
+                .with_context("conversation_id", &conversation_id)
+                .with_context("model", &model)
+                .with_context("stage_index", i)
+                .with_context("answer", &stage_result.answer)
+                .with_context(
+                    "duration",
+                    stage_result
+                        .analytics
+                        .as_ref()
+                        .map(|a| a.duration)
+                        .unwrap_or(0.0),
+                )
+                .with_context(
+                    "cost",
+                    stage_result
+                        .analytics
+                        .as_ref()
+                        .map(|a| a.cost)
+                        .unwrap_or(0.0),
+                )
+                .with_context(
+                    "tokens",
+                    stage_result
+                        .usage
+                        .as_ref()
+                        .map(|u| u.total_tokens)
+                        .unwrap_or(0),
+                );
+                self.emit_hook_event(event).await.map(StageHookResult::from)
+            };
+
+            if let Some(post_stage_result) = post_stage_result {
+                for warning in &post_stage_result.warnings {
+                    tracing::warn!("Post-{} hook warning: {}", stage.as_str(), warning);
+                }
+                if let Some(veto) = &post_stage_result.veto {
+                    return Err(self.stage_vetoed(stage, veto));
+                }
+                if !post_stage_result.proceed {
+                    if let Some(approval_required) = &post_stage_result.approval_required {
+                        tracing::warn!(
+                            "Quality issue in {} stage requires approval: {}",
+                            stage.as_str(),
+                            approval_required.description
+                        );
+                    } else {
+                        return Err(anyhow!(
+                            "{} stage result was rejected by quality gates",
+                            stage.display_name()
+                        ));
+                    }
+                }
+                if let Some(answer) = post_stage_result
+                    .modifications
+                    .get("answer")
+                    .and_then(|v| v.as_str())
+                {
+                    tracing::info!("Hooks replaced the {} stage answer", stage.display_name());
+                    stage_result.answer = answer.to_string();
+                }
             }
 
-            // Store the answer for next stage
+            // Store the answer for later stages
             previous_answer = Some(stage_result.answer.clone());
+            outputs.insert(stage_def.id.clone(), stage_result.answer.clone());
+            last_ran = Some(&stage_def.id);
 
             // Notify stage complete
             self.callbacks.on_stage_complete(stage, &stage_result)?;
@@ -892,60 +1167,7 @@ impl ConsensusPipeline {
                 }
             }
 
-            // Execute post-stage hooks with quality validation
-            // if let Some(integration) = &self.consensus_integration {
-            //     let post_stage_result = integration.execute_post_stage_hooks(
-            //         stage,
-            //         &stage_result,
-            //     ).await?;
-            //
-            //     // Check if we should continue
-            //     if !post_stage_result.proceed {
-            //         if let Some(approval_required) = post_stage_result.approval_required {
-            //             // Handle approval requirement
-            //             tracing::warn!("Quality issue in {} stage requires approval: {}", stage.as_str(), approval_required.description);
-            //
-            //             // For now, we'll continue with warnings
-            //             for warning in &post_stage_result.warnings {
-            //                 tracing::warn!("Post-stage warning: {}", warning);
-            //             }
-            //         } else {
-            //             // Stage result was rejected by quality gates
-            //             return Err(anyhow::anyhow!("Stage {} result was rejected by quality gates", stage.as_str()));
-            //         }
-            //     }
-            //
-            //     // Log any warnings
-            //     for warning in &post_stage_result.warnings {
-            //         tracing::warn!("Quality warning for {} stage: {}", stage.as_str(), warning);
-            //     }
-            // }
-
-            // Emit AfterStage hook event
-            //             if let Some(hooks) = &self.hooks_system {
-            //                 let stage_event_type = match stage {
-            //                     Stage::Generator => EventType::AfterGeneratorStage,
-            //                     Stage::Refiner => EventType::AfterRefinerStage,
-            //                     Stage::Validator => EventType::AfterValidatorStage,
-            //                     Stage::Curator => EventType::AfterCuratorStage,
-            //                 };
-            //
-            //                 let event = HookEvent::new(
-            //                     stage_event_type,
-            //                     EventSource::Consensus { stage: stage.as_str().to_string() }
-            //                 )
-            //                 .with_context("question", question)
-            //                 .with_context("conversation_id", &conversation_id)
-            //                 .with_context("model", model)
-            //                 .with_context("answer", &stage_result.answer)
-            //                 .with_context("duration", stage_result.analytics.as_ref().map(|a| a.duration).unwrap_or(0.0))
-            //                 .with_context("cost", stage_result.analytics.as_ref().map(|a| a.cost).unwrap_or(0.0));
-            //
-            //                 if let Err(e) = hooks.dispatch_event(event).await {
-            //                     tracing::warn!("Failed to dispatch After{:?}Stage hook event: {}", stage, e);
-            //                 }
-            //             }
-
+            metrics::global().record_stage(&stage_result);
             stage_results.push(stage_result);
 
             // Check for cancellation AFTER stage completes to prevent next stage from starting
@@ -1210,6 +1432,7 @@ impl ConsensusPipeline {
             total_duration: pipeline_start.elapsed().as_secs_f64(),
             total_cost,
         };
+        self.record_run_metrics(&result);
 
         // CRITICAL: Store conversation and curator result in database (like TypeScript implementation)
         if let Some(db) = &self.database {
@@ -1247,22 +1470,19 @@ impl ConsensusPipeline {
         // }
 
         // Emit AfterConsensus hook event
-        //         if let Some(hooks) = &self.hooks_system {
-        //             let event = HookEvent::new(
-        //                 EventType::AfterConsensus,
-        //                 EventSource::Consensus { stage: "pipeline".to_string() }
-        //             )
-        //             .with_context("question", question)
-        //             .with_context("conversation_id", &conversation_id)
-        //             .with_context("final_answer", &final_answer)
-        //             .with_context("total_duration", result.total_duration)
-        //             .with_context("total_cost", result.total_cost)
-        //             .with_context("stages_count", result.stages.len());
-        //
-        //             if let Err(e) = hooks.dispatch_event(event).await {
-        //                 tracing::warn!("Failed to dispatch AfterConsensus hook event: {}", e);
-        //             }
-        //         }
+        let event = HookEvent::new(
+            EventType::AfterConsensus,
+            EventSource::Consensus {
+                stage: "pipeline".to_string(),
+            },
+        )
+        .with_context("question", question)
+        .with_context("conversation_id", &conversation_id)
+        .with_context("final_answer", &final_answer)
+        .with_context("total_duration", result.total_duration)
+        .with_context("total_cost", result.total_cost)
+        .with_context("stages_count", result.stages.len());
+        self.emit_hook_event(event).await;
 
         // Report conversation completion to D1 if we have authorization
         if let Some(auth) = d1_auth {
@@ -1295,6 +1515,34 @@ impl ConsensusPipeline {
         Ok(result)
     }
 
+    /// Dispatch a lifecycle event and wait for its hooks. Dispatch failures are
+    /// logged rather than failing the run; `None` means no hooks system is set.
+    async fn emit_hook_event(&self, event: HookEvent) -> Option<crate::hooks::HookOutcome> {
+        let hooks = self.hooks_system.as_ref()?;
+        let event_type = event.event_type.clone();
+        match hooks.dispatch_event_and_wait(event).await {
+            Ok(outcome) => {
+                for failure in &outcome.failures {
+                    tracing::warn!("Hook failed on {:?}: {}", event_type, failure);
+                }
+                Some(outcome)
+            }
+            Err(e) => {
+                tracing::warn!("Failed to dispatch {:?} hook event: {}", event_type, e);
+                None
+            }
+        }
+    }
+
+    /// Build the error for a vetoed stage and report it through the callbacks
+    fn stage_vetoed(&self, stage: Stage, veto: &HookVeto) -> anyhow::Error {
+        let error = hook_veto_error(&format!("{} stage", stage.display_name()), veto);
+        if let Err(e) = self.callbacks.on_error(stage, &error) {
+            tracing::warn!("Failed to report hook veto: {}", e);
+        }
+        error
+    }
+
     /// Execute in Direct Mode for simple questions
     async fn execute_direct_mode(
         &self,
@@ -1307,12 +1555,15 @@ impl ConsensusPipeline {
 
         // Use the direct handler if available
         if let Some(direct_handler) = &self.direct_handler {
-            // Build minimal context - no heavy repository scanning for simple questions
-            let direct_context = if let Some(ctx) = context {
-                ctx.to_string()
-            } else {
-                String::new()
-            };
+            // Build minimal context - no heavy repository scanning for simple questions,
+            // but keep the conversation so follow-ups still make sense
+            let mut direct_context = render_history(&self.history);
+            if let Some(ctx) = context {
+                if !direct_context.is_empty() {
+                    direct_context.push_str("\n\n");
+                }
+                direct_context.push_str(ctx);
+            }
 
             // Execute with single model (Generator)
             let start_time = Instant::now();
@@ -1416,10 +1667,13 @@ impl ConsensusPipeline {
                         routing_variant: "direct".to_string(),
                         optimization_applied: Some(true),
                     },
+                    candidates: Vec::new(),
+                    context: None,
                 }),
             };
 
-            Ok(ConsensusResult {
+            metrics::global().record_stage(&stage_result);
+            let result = ConsensusResult {
                 success: true,
                 result: Some(result_text),
                 error: None,
@@ -1427,14 +1681,19 @@ impl ConsensusPipeline {
                 conversation_id: conversation_id.to_string(),
                 total_duration: start_time.elapsed().as_secs_f64(),
                 total_cost,
-            })
+            };
+            self.record_run_metrics(&result);
+            Ok(result)
         } else {
             // Fallback to creating a simple direct execution
             tracing::warn!("Direct handler not initialized, using simplified execution");
 
             // Use Generator stage directly
             let generator = GeneratorStage::new();
-            let messages = generator.build_messages(question, None, context)?;
+            let messages = splice_history(
+                generator.build_messages(question, None, context)?,
+                &self.history,
+            );
 
             let model = self
                 .profile
@@ -1443,7 +1702,7 @@ impl ConsensusPipeline {
             let mut tracker = ProgressTracker::new(Stage::Generator, self.callbacks.clone());
 
             let response = self
-                .call_model(&model, &messages, &mut tracker, cancellation_token)
+                .call_model(&model, &messages, None, &mut tracker, cancellation_token)
                 .await?;
 
             Ok(ConsensusResult {
@@ -1468,6 +1727,8 @@ impl ConsensusPipeline {
         context: Option<&str>,
         conversation_id: &str,
         model: &str,
+        temperature: Option<f64>,
+        callbacks: Arc<dyn StreamingCallbacks>,
         cancellation_token: &CancellationToken,
     ) -> Result<StageResult> {
         // Check for cancellation at start of stage
@@ -1476,26 +1737,11 @@ impl ConsensusPipeline {
         let stage_start = Instant::now();
         let stage_id = Uuid::new_v4().to_string();
 
-        // CRITICAL FIX: Dynamically choose the right curator based on question type
-        let messages = if stage == Stage::Curator {
-            // Check if this is a repository-related question
-            let is_repo_related = self
-                .verified_context_builder
-                .is_repository_related_question(question);
-
-            if is_repo_related {
-                tracing::info!("🎨 Using FileAwareCuratorStage for repository-related question");
-                handler.build_messages(question, previous_answer, context)?
-            } else {
-                tracing::info!("🎨 Using regular CuratorStage for general knowledge question");
-                // Create a regular curator that doesn't force repository context
-                let regular_curator = CuratorStage::new();
-                regular_curator.build_messages(question, previous_answer, context)?
-            }
-        } else {
-            // For non-curator stages, use the provided handler
-            handler.build_messages(question, previous_answer, context)?
-        };
+        // The caller picks the handler, including the curator variant for the question
+        let messages = splice_history(
+            handler.build_messages(question, previous_answer, context)?,
+            &self.history,
+        );
 
         tracing::info!(
             "🧠 {} stage: Using verified context (already filtered for relevance)",
@@ -1515,11 +1761,17 @@ impl ConsensusPipeline {
         }
 
         // Create progress tracker
-        let mut tracker = ProgressTracker::new(stage, self.callbacks.clone());
+        let mut tracker = ProgressTracker::new(stage, callbacks);
 
         // Call model with retry logic for fallback models
         let mut response = self
-            .call_model(model, &messages, &mut tracker, cancellation_token)
+            .call_model(
+                model,
+                &messages,
+                temperature,
+                &mut tracker,
+                cancellation_token,
+            )
             .await
             .with_context(|| {
                 format!(
@@ -1539,6 +1791,7 @@ impl ConsensusPipeline {
                 .call_model(
                     replacement_model,
                     &messages,
+                    temperature,
                     &mut tracker,
                     cancellation_token,
                 )
@@ -1584,84 +1837,134 @@ impl ConsensusPipeline {
                 classification_latency: response.analytics.classification_latency,
                 memory_usage: None,
                 features: response.analytics.features,
+                candidates: Vec::new(),
+                context: None,
             }),
         };
 
         Ok(stage_result)
     }
 
-    /// Build full context combining semantic, temporal, memory, and codebase intelligence
-    async fn build_full_context(
+    /// Run a stage on several models concurrently, score the candidates against each
+    /// other and keep the best one or synthesize a merged answer
+    async fn run_fan_out_stage(
         &self,
-        semantic_context: Option<String>,
-        temporal_context: Option<crate::consensus::temporal::TemporalContext>,
-        memory_context: Option<String>,
-        question: &str,
-        codebase_intelligence: Option<
-            Arc<crate::consensus::codebase_intelligence::CodebaseIntelligence>,
-        >,
-    ) -> Result<Option<String>> {
-        let mut contexts = Vec::new();
-
-        // Add memory context first (most important for multi-conversation intelligence)
-        // This is the AUTHORITATIVE knowledge from previous curator results
-        if let Some(memory) = memory_context {
-            if !memory.is_empty() {
-                contexts.push(memory); // Already formatted with headers
-            }
-        }
+        stage: Stage,
+        handler: &dyn ConsensusStage,
+        input: &StageInput,
+        conversation_id: &str,
+        model: &str,
+        temperature: Option<f64>,
+        fan_out: &FanOutDefinition,
+        cancellation_token: &CancellationToken,
+    ) -> Result<StageResult> {
+        let fan_out_start = Instant::now();
+        let selector = ModelSelector::new(ModelSelectionStrategy::QualityFirst);
+        let models = fan_out.resolve_models(model, &self.profile, &selector);
+        tracing::info!(
+            "🔀 {} stage fanning out to {} models: {}",
+            stage.display_name(),
+            models.len(),
+            models.join(", ")
+        );
 
-        // NEW: Add semantic codebase search results if available
-        if let Some(intelligence) = codebase_intelligence {
-            if intelligence.has_analysis().await {
-                tracing::info!("🔍 Searching indexed codebase for: '{}'", question);
-                match intelligence.get_context_for_question(question).await {
-                    Ok(codebase_context) => {
-                        if !codebase_context.is_empty() {
-                            contexts.push(format!(
-                                "## 🔍 SEMANTIC CODEBASE SEARCH RESULTS\n{}",
-                                codebase_context
-                            ));
-                            tracing::info!("Added semantic search results to context");
-                        }
-                    }
-                    Err(e) => {
-                        tracing::warn!("Failed to search codebase: {}", e);
-                    }
-                }
-            }
-        }
+        // Candidates stream silently; only the final answer reaches the UI
+        let silent: Arc<dyn StreamingCallbacks> = Arc::new(SilentCallbacks);
+        let runs = futures::future::join_all(models.iter().map(|candidate_model| {
+            self.run_single_stage(
+                stage,
+                handler,
+                &input.question,
+                input.previous_answer.as_deref(),
+                input.context.as_deref(),
+                conversation_id,
+                candidate_model,
+                temperature,
+                silent.clone(),
+                cancellation_token,
+            )
+        }))
+        .await;
+        cancellation_token.throw_if_cancelled()?;
 
-        // Add repository context (current codebase understanding)
-        if let Some(repo_ctx) = &self.repository_context {
-            let repo_context_info = repo_ctx.get_context_for_prompts().await;
-            tracing::info!("Repository context info: '{}'", repo_context_info);
-            if !repo_context_info.is_empty() {
-                contexts.push(format!("## Repository Context\n{}", repo_context_info));
-                tracing::info!("Added repository context to pipeline");
-            } else {
-                tracing::warn!("Repository context is empty - no project information available");
+        let mut results = Vec::new();
+        let mut failures = Vec::new();
+        for (candidate_model, run) in models.iter().zip(runs) {
+            match run {
+                Ok(result) => results.push(result),
+                Err(e) => {
+                    tracing::warn!("Candidate {} failed: {:#}", candidate_model, e);
+                    failures.push(fanout::failed_candidate(candidate_model, &e));
+                }
             }
-        } else {
-            tracing::warn!("No repository context manager available in pipeline");
         }
-
-        if let Some(temporal) = temporal_context {
-            contexts.push(format!(
-                "## Temporal Context\n{}\n{}",
-                temporal.search_instruction, temporal.temporal_awareness
+        if results.is_empty() {
+            return Err(anyhow!(
+                "All {} candidate models failed for {} stage",
+                models.len(),
+                stage.display_name()
             ));
         }
 
-        if let Some(semantic) = semantic_context {
-            contexts.push(format!("## Semantic Context\n{}", semantic));
+        let cross_validator = self
+            .verified_context_builder
+            .get_repository_facts()
+            .cloned()
+            .map(CrossValidator::new);
+        let scorer = ConfidenceScoringEngine::new(None, None);
+        let mut candidates = fanout::score_candidates(&results, cross_validator.as_ref(), &scorer)?;
+        for candidate in &candidates {
+            tracing::info!(
+                "🗳️ {}: score {:.0}, agreement {:.2}, {} contradictions, ${:.4}",
+                candidate.model,
+                candidate.score,
+                candidate.agreement,
+                candidate.contradictions,
+                candidate.cost
+            );
         }
 
-        if contexts.is_empty() {
-            Ok(None)
+        let synthesis = if fan_out.merge == MergeStrategy::Synthesize && results.len() > 1 {
+            let merge_model = fan_out.merge_model.as_deref().unwrap_or(model);
+            let merge_stage = TemplateStage::new(stage, fanout::SYNTHESIS_PROMPT.to_string());
+            let candidate_text = fanout::format_candidates(&results, &candidates);
+            Some(
+                self.run_single_stage(
+                    stage,
+                    &merge_stage,
+                    &input.question,
+                    Some(&candidate_text),
+                    None,
+                    conversation_id,
+                    merge_model,
+                    Some(0.3),
+                    self.callbacks.clone(),
+                    cancellation_token,
+                )
+                .await
+                .context("Failed to synthesize candidate answers")?,
+            )
         } else {
-            Ok(Some(contexts.join("\n\n")))
+            None
+        };
+        let synthesized = synthesis.is_some();
+
+        candidates.extend(failures);
+        let combined = fanout::combine_candidates(
+            &results,
+            candidates,
+            synthesis,
+            fan_out_start.elapsed().as_secs_f64(),
+        )
+        .ok_or_else(|| anyhow!("No candidate answer to select"))?;
+
+        // A selected candidate never streamed, so show it in one piece
+        if !synthesized {
+            self.callbacks
+                .on_stage_chunk(stage, &combined.answer, &combined.answer)?;
         }
+
+        Ok(combined)
     }
 
     /// Call model via OpenRouter API with streaming support
@@ -1669,6 +1972,7 @@ impl ConsensusPipeline {
         &self,
         model: &str,
         messages: &[crate::consensus::types::Message],
+        temperature: Option<f64>,
         tracker: &mut ProgressTracker,
         cancellation_token: &CancellationToken,
     ) -> Result<ModelResponse> {
@@ -1698,7 +2002,7 @@ impl ConsensusPipeline {
         let request = OpenRouterRequest {
             model: model.to_string(),
             messages: openrouter_messages,
-            temperature: Some(0.7),
+            temperature: Some(temperature.unwrap_or(0.7)),
             max_tokens: Some(8000), // Increased from 4000 to prevent truncation
             top_p: None,
             frequency_penalty: None,
@@ -1806,6 +2110,8 @@ impl ConsensusPipeline {
                                 routing_variant: "balanced".to_string(),
                                 optimization_applied: Some(true),
                             },
+                            candidates: Vec::new(),
+                            context: None,
                         },
                     })
                 }
@@ -1918,6 +2224,8 @@ impl ConsensusPipeline {
                                 routing_variant: "balanced".to_string(),
                                 optimization_applied: Some(true),
                             },
+                            candidates: Vec::new(),
+                            context: None,
                         },
                     })
                 }
@@ -1993,6 +2301,8 @@ impl ConsensusPipeline {
                                 routing_variant: "fallback".to_string(),
                                 optimization_applied: Some(false),
                             },
+                            candidates: Vec::new(),
+                            context: None,
                         },
                     });
                 }
@@ -2007,28 +2317,15 @@ impl ConsensusPipeline {
         ))
     }
 
-    /// Estimate cost for a stage operation
-    async fn estimate_stage_cost(&self, stage: Stage, model: &str, question: &str) -> Result<f64> {
-        // Simple cost estimation based on question length and model
-        let question_tokens = question.split_whitespace().count() as u32;
-        let estimated_tokens = match stage {
-            Stage::Generator => question_tokens + 500, // Generator usually produces more tokens
-            Stage::Refiner => question_tokens + 200,   // Refiner improves existing content
-            Stage::Validator => question_tokens + 100, // Validator checks quality
-            Stage::Curator => question_tokens + 150,   // Curator polishes result
-        };
-
-        // Model-based cost calculation (simplified)
-        let cost_per_token = match model {
-            m if m.contains("gpt-4") => 0.00003,
-            m if m.contains("gpt-3.5") => 0.000002,
-            m if m.contains("claude-3-opus") => 0.000075,
-            m if m.contains("claude-3-sonnet") => 0.000015,
-            m if m.contains("claude-3-haiku") => 0.000001,
-            _ => 0.00001, // Default fallback
-        };
-
-        Ok(estimated_tokens as f64 * cost_per_token)
+    /// Count a finished run in the live metrics and persist the pending rollups
+    fn record_run_metrics(&self, result: &ConsensusResult) {
+        let registry = metrics::global();
+        registry.record_run(result.total_duration);
+        if let Some(db) = &self.database {
+            if let Err(e) = db.get_connection().and_then(|conn| registry.flush(&conn)) {
+                tracing::warn!("Failed to persist live metrics: {}", e);
+            }
+        }
     }
 
     /// Store consensus result in database (matching TypeScript implementation)
@@ -2115,27 +2412,26 @@ impl ConsensusPipeline {
                 rows_affected
             );
 
-            // 2. Store all stage outputs as messages for audit trail
-            for stage_result in &stage_results {
-                // Store user message (question) - only for first stage
-                if stage_result.stage_name == "generator" {
-                    tx.execute(
-                        "INSERT INTO messages (
-                            id, conversation_id, role, content, stage, model_used, 
-                            timestamp
-                        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
-                        params![
-                            uuid::Uuid::new_v4().to_string(),
-                            conversation_id,
-                            "user",
-                            question,
-                            None::<String>, // User message has no stage
-                            None::<String>, // User message has no model
-                            &now
-                        ],
-                    )?;
-                }
+            // 2. Store the question, then every stage output as messages for audit trail.
+            // Pipeline topologies may rename, reorder or skip stages, so nothing here
+            // depends on stage names.
+            tx.execute(
+                "INSERT INTO messages (
+                    id, conversation_id, role, content, stage, model_used,
+                    timestamp
+                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    uuid::Uuid::new_v4().to_string(),
+                    conversation_id,
+                    "user",
+                    question,
+                    None::<String>, // User message has no stage
+                    None::<String>, // User message has no model
+                    &now
+                ],
+            )?;
 
+            for stage_result in &stage_results {
                 // Store assistant message for each stage
                 let sequence_number = match stage_result.stage_name.as_str() {
                     "generator" => 1,
@@ -2267,6 +2563,17 @@ impl ConsensusPipeline {
                     |row| row.get(0),
                 )
                 .optional()?;
+            let current_license_key = crate::core::secrets::resolve_configuration(
+                "hive_license_key",
+                current_license_key,
+            )
+            .unwrap_or_else(|e| {
+                tracing::warn!(
+                    "Failed to read the license key from the secret store: {}",
+                    e
+                );
+                None
+            });
 
             // Then get the user_id that matches the current license key
             let user_id_result: Option<String> = if let Some(license_key) = current_license_key {
@@ -2304,26 +2611,11 @@ impl ConsensusPipeline {
                 tracing::warn!("No user found with license key, skipping usage tracking");
             }
 
-            // 5. Store curator truth (flagged as source of truth - critical for memory system)
-            tracing::debug!("Looking for curator stage result");
-            let curator_result = stage_results
-                .iter()
-                .find(|s| s.stage_name == "curator")
-                .ok_or_else(|| {
-                    tracing::error!(
-                        "No curator stage result found! Stage names: {:?}",
-                        stage_results
-                            .iter()
-                            .map(|s| &s.stage_name)
-                            .collect::<Vec<_>>()
-                    );
-                    anyhow::anyhow!("No curator stage result found")
-                })?;
-            tracing::debug!("Found curator result");
-
-            let confidence_score = curator_result
-                .analytics
-                .as_ref()
+            // 5. Store curator truth (flagged as source of truth - critical for memory system).
+            // The last stage that ran produced the final answer, whatever its role.
+            let confidence_score = stage_results
+                .last()
+                .and_then(|s| s.analytics.as_ref())
                 .map(|a| a.quality_score)
                 .unwrap_or(0.8); // Default confidence
 
@@ -2335,7 +2627,7 @@ impl ConsensusPipeline {
                 ) VALUES (?1, ?2, ?3, ?4, ?5)",
                 params![
                     conversation_id,
-                    curator_result.answer,
+                    final_answer,
                     confidence_score,
                     topic_summary,
                     &now
@@ -2952,8 +3244,6 @@ impl ConsensusPipeline {
         context: Option<&str>,
         intelligent_decision: &crate::ai_helpers::IntelligentContextDecision,
     ) -> Result<Vec<crate::consensus::types::Message>> {
-        use crate::consensus::types::Message;
-
         // Get the base messages from the stage handler
         let mut messages = handler.build_messages(question, previous_answer, context)?;
 
@@ -3020,6 +3310,85 @@ impl ConsensusPipeline {
     }
 }
 
+/// Estimated cost of a stage from its counted input tokens and typical answer length
+fn estimate_stage_cost(budget: &ContextBudget, stage: Stage, input: &StageInput) -> f64 {
+    let counter = budget.counter();
+    let prompt_tokens = counter.count(&input.question)
+        + input
+            .previous_answer
+            .as_deref()
+            .map_or(0, |a| counter.count(a))
+        + input.context.as_deref().map_or(0, |c| counter.count(c));
+    let completion_tokens = match stage {
+        Stage::Generator => 1_500,
+        Stage::Refiner => 1_200,
+        Stage::Validator => 600,
+        Stage::Curator => 1_000,
+    }
+    .min(budget.limits().reserved_output_tokens());
+
+    budget
+        .limits()
+        .estimate_cost(prompt_tokens, completion_tokens)
+        .unwrap_or_else(|| {
+            tracing::debug!(
+                "No pricing for {}; estimating zero cost",
+                budget.limits().model
+            );
+            0.0
+        })
+}
+
+/// Hook event raised before or after a stage
+fn stage_event_type(stage: Stage, before: bool) -> EventType {
+    match (stage, before) {
+        (Stage::Generator, true) => EventType::BeforeGeneratorStage,
+        (Stage::Generator, false) => EventType::AfterGeneratorStage,
+        (Stage::Refiner, true) => EventType::BeforeRefinerStage,
+        (Stage::Refiner, false) => EventType::AfterRefinerStage,
+        (Stage::Validator, true) => EventType::BeforeValidatorStage,
+        (Stage::Validator, false) => EventType::AfterValidatorStage,
+        (Stage::Curator, true) => EventType::BeforeCuratorStage,
+        (Stage::Curator, false) => EventType::AfterCuratorStage,
+    }
+}
+
+/// Insert thread history between a stage's leading system messages and its question
+fn splice_history(mut messages: Vec<Message>, history: &[Message]) -> Vec<Message> {
+    let at = messages
+        .iter()
+        .take_while(|message| message.role == "system")
+        .count();
+    messages.splice(at..at, history.iter().cloned());
+    messages
+}
+
+/// Thread history as a transcript, for paths that take context as plain text
+fn render_history(history: &[Message]) -> String {
+    if history.is_empty() {
+        return String::new();
+    }
+    let mut transcript = String::from("## Conversation so far\n");
+    for message in history {
+        let speaker = match message.role.as_str() {
+            "user" => "User",
+            "assistant" => "Assistant",
+            _ => "Summary",
+        };
+        transcript.push_str(&format!("\n{}: {}\n", speaker, message.content));
+    }
+    transcript
+}
+
+fn hook_veto_error(what: &str, veto: &HookVeto) -> anyhow::Error {
+    anyhow!(
+        "{} vetoed by hook '{}': {}",
+        what,
+        veto.hook_name,
+        veto.reason
+    )
+}
+
 /// Response from model API
 struct ModelResponse {
     model: String,
@@ -0,0 +1,1091 @@
+// Code review - consensus review of a git diff, one chunk per changed file
+// Findings are anchored to new-file lines and exported as JSON or SARIF
+
+use anyhow::{anyhow, bail, Context, Result};
+use futures::stream::{self, StreamExt};
+use git2::{Delta, DiffOptions, Patch, Repository};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{BTreeSet, HashMap};
+use std::future::Future;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Instant;
+
+use crate::analysis::symbol_index::SymbolEntry;
+use crate::consensus::topology::{PipelineDefinition, PipelineStore};
+
+/// Name of the built-in review pipeline
+pub const REVIEW_PIPELINE: &str = "review";
+
+/// Diff lines in one chunk before a file is split at hunk boundaries
+const MAX_CHUNK_LINES: usize = 400;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+const PIPELINE_TOML: &str = r#"name = "review"
+description = "Code review: find issues in a diff, verify them, emit JSON findings"
+
+[[stage]]
+id = "reviewer"
+role = "generator"
+include_context = false
+temperature = 0.3
+system_prompt = """
+You are a meticulous senior code reviewer. Review only the added (+) lines of the diff in the
+request, using the unchanged lines and enclosing symbols for context. Report bugs, security
+problems, error handling gaps, performance problems and maintainability issues. For every issue
+give the new-file line number from the left column, a severity (error, warning or info), a short
+title, an explanation and a concrete fix as a unified diff. Do not report style nits.
+"""
+
+[[stage]]
+id = "verifier"
+role = "validator"
+include_context = false
+temperature = 0.1
+system_prompt = """
+You verify code review findings. Check each finding against the diff in the request below and
+drop it if the code does not support it, if it is about unchanged lines, or if it is a matter of
+taste. Correct wrong line numbers and severities. Keep the suggested fixes of the findings you keep.
+
+{{question}}
+"""
+
+[[stage]]
+id = "curator"
+role = "curator"
+include_context = false
+temperature = 0.1
+system_prompt = """
+Turn the verified review findings into a JSON array and reply with nothing else. Each element is
+{"line": <new-file line>, "end_line": <optional last line>, "severity": "error" | "warning" | "info",
+"category": "bug" | "security" | "performance" | "error-handling" | "maintainability",
+"title": "<short summary>", "message": "<explanation>", "suggestion": "<unified diff or null>"}.
+Reply with [] when there are no findings.
+"""
+"#;
+
+/// The built-in review pipeline
+pub fn review_pipeline() -> PipelineDefinition {
+    PipelineDefinition::from_toml(PIPELINE_TOML).expect("built-in review pipeline is valid")
+}
+
+/// Load a stored pipeline, falling back to the built-in one for `review`
+pub fn load_pipeline(store: &PipelineStore, name: &str) -> Result<PipelineDefinition> {
+    if name == REVIEW_PIPELINE && !store.dir().join(format!("{}.toml", name)).is_file() {
+        return Ok(review_pipeline());
+    }
+    store.load(name)
+}
+
+/// Which changes to review
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffSpec {
+    /// Staged and unstaged changes, including untracked files, against HEAD
+    WorkingTree,
+    /// The index against HEAD
+    Staged,
+    /// Two revisions; with `merge_base` the diff starts at their common ancestor
+    Range {
+        base: String,
+        head: String,
+        merge_base: bool,
+    },
+}
+
+impl DiffSpec {
+    /// Parse `<base>..<head>`, `<base>...<head>` or `<base>` (meaning `<base>..HEAD`)
+    pub fn parse(range: Option<&str>, staged: bool) -> Result<Self> {
+        let Some(range) = range else {
+            return Ok(if staged {
+                Self::Staged
+            } else {
+                Self::WorkingTree
+            });
+        };
+        if staged {
+            bail!("--staged cannot be combined with a revision range");
+        }
+        let (base, head, merge_base) = match range.split_once("...") {
+            Some((base, head)) => (base, head, true),
+            None => match range.split_once("..") {
+                Some((base, head)) => (base, head, false),
+                None => (range, "", false),
+            },
+        };
+        if base.is_empty() {
+            bail!("Invalid revision range '{}'", range);
+        }
+        Ok(Self::Range {
+            base: base.to_string(),
+            head: if head.is_empty() { "HEAD" } else { head }.to_string(),
+            merge_base,
+        })
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            Self::WorkingTree => "working tree".to_string(),
+            Self::Staged => "staged changes".to_string(),
+            Self::Range {
+                base,
+                head,
+                merge_base,
+            } => format!("{}{}{}", base, if *merge_base { "..." } else { ".." }, head),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileStatus {
+    Added,
+    Modified,
+    Deleted,
+    Renamed,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LineKind {
+    Context,
+    Added,
+    Removed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiffLine {
+    pub kind: LineKind,
+    pub content: String,
+    /// Line in the new file; `None` for removed lines
+    pub new_line: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Hunk {
+    pub header: String,
+    pub lines: Vec<DiffLine>,
+}
+
+impl Hunk {
+    /// New-file lines this hunk adds or changes
+    pub fn changed_lines(&self) -> impl Iterator<Item = u32> + '_ {
+        self.lines
+            .iter()
+            .filter(|line| line.kind == LineKind::Added)
+            .filter_map(|line| line.new_line)
+    }
+
+    /// First and last new-file line the hunk shows
+    fn new_range(&self) -> Option<(u32, u32)> {
+        let mut lines = self.lines.iter().filter_map(|line| line.new_line);
+        let first = lines.next()?;
+        Some((first, lines.last().unwrap_or(first)))
+    }
+}
+
+/// One changed file of a diff
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileDiff {
+    pub path: PathBuf,
+    pub status: FileStatus,
+    pub hunks: Vec<Hunk>,
+    /// Content of the new side, for symbol lookup
+    #[serde(skip)]
+    pub new_content: Option<String>,
+}
+
+/// Extract the diff selected by `spec` from `repo`, skipping binary files
+pub fn diff_files(repo: &Repository, spec: &DiffSpec, context_lines: u32) -> Result<Vec<FileDiff>> {
+    let mut options = DiffOptions::new();
+    options.context_lines(context_lines);
+
+    // An unborn HEAD diffs against the empty tree
+    let head_tree = || repo.head().ok().and_then(|head| head.peel_to_tree().ok());
+
+    let mut diff = match spec {
+        DiffSpec::WorkingTree => {
+            options
+                .include_untracked(true)
+                .recurse_untracked_dirs(true)
+                .show_untracked_content(true);
+            repo.diff_tree_to_workdir_with_index(head_tree().as_ref(), Some(&mut options))?
+        }
+        DiffSpec::Staged => {
+            repo.diff_tree_to_index(head_tree().as_ref(), None, Some(&mut options))?
+        }
+        DiffSpec::Range {
+            base,
+            head,
+            merge_base,
+        } => {
+            let head_tree = revision(repo, head)?.peel_to_tree()?;
+            let base_tree = if *merge_base {
+                let ancestor = repo
+                    .merge_base(
+                        revision(repo, base)?.peel_to_commit()?.id(),
+                        revision(repo, head)?.peel_to_commit()?.id(),
+                    )
+                    .with_context(|| format!("No common ancestor of {} and {}", base, head))?;
+                repo.find_commit(ancestor)?.tree()?
+            } else {
+                revision(repo, base)?.peel_to_tree()?
+            };
+            repo.diff_tree_to_tree(Some(&base_tree), Some(&head_tree), Some(&mut options))?
+        }
+    };
+    diff.find_similar(None)?;
+
+    let mut files = Vec::new();
+    for index in 0..diff.deltas().len() {
+        let Some(patch) = Patch::from_diff(&diff, index)? else {
+            continue;
+        };
+        let delta = patch.delta();
+        if delta.flags().is_binary() {
+            continue;
+        }
+        let status = match delta.status() {
+            Delta::Added | Delta::Untracked | Delta::Copied => FileStatus::Added,
+            Delta::Deleted => FileStatus::Deleted,
+            Delta::Renamed => FileStatus::Renamed,
+            _ => FileStatus::Modified,
+        };
+        let path = delta
+            .new_file()
+            .path()
+            .or_else(|| delta.old_file().path())
+            .ok_or_else(|| anyhow!("Diff entry without a path"))?
+            .to_path_buf();
+
+        let mut hunks = Vec::new();
+        for hunk_index in 0..patch.num_hunks() {
+            let (hunk, line_count) = patch.hunk(hunk_index)?;
+            let mut lines = Vec::with_capacity(line_count);
+            for line_index in 0..line_count {
+                let line = patch.line_in_hunk(hunk_index, line_index)?;
+                let kind = match line.origin() {
+                    '+' => LineKind::Added,
+                    '-' => LineKind::Removed,
+                    ' ' => LineKind::Context,
+                    // End-of-file newline markers
+                    _ => continue,
+                };
+                lines.push(DiffLine {
+                    kind,
+                    content: String::from_utf8_lossy(line.content())
+                        .trim_end_matches(['\n', '\r'])
+                        .to_string(),
+                    new_line: line.new_lineno(),
+                });
+            }
+            hunks.push(Hunk {
+                header: String::from_utf8_lossy(hunk.header())
+                    .trim_end()
+                    .to_string(),
+                lines,
+            });
+        }
+        if hunks.is_empty() {
+            continue;
+        }
+
+        let new_content = if status == FileStatus::Deleted {
+            None
+        } else {
+            match repo.find_blob(delta.new_file().id()) {
+                Ok(blob) => Some(String::from_utf8_lossy(blob.content()).into_owned()),
+                // Working tree files are not in the object database
+                Err(_) => repo
+                    .workdir()
+                    .and_then(|dir| std::fs::read_to_string(dir.join(&path)).ok()),
+            }
+        };
+        files.push(FileDiff {
+            path,
+            status,
+            hunks,
+            new_content,
+        });
+    }
+    Ok(files)
+}
+
+fn revision<'r>(repo: &'r Repository, rev: &str) -> Result<git2::Object<'r>> {
+    repo.revparse_single(rev)
+        .with_context(|| format!("Unknown revision '{}'", rev))
+}
+
+/// Part of a file's diff reviewed in one consensus run
+#[derive(Debug, Clone)]
+pub struct ReviewChunk {
+    pub path: PathBuf,
+    pub status: FileStatus,
+    pub hunks: Vec<Hunk>,
+    /// Enclosing symbols of the changed lines, one line each
+    pub symbols: Vec<String>,
+}
+
+impl ReviewChunk {
+    /// The question sent to the review pipeline
+    pub fn prompt(&self) -> String {
+        let mut prompt = format!(
+            "Review this change to `{}` ({}).\n",
+            self.path.display(),
+            match self.status {
+                FileStatus::Added => "new file",
+                FileStatus::Renamed => "renamed",
+                _ => "modified",
+            }
+        );
+        if !self.symbols.is_empty() {
+            prompt.push_str("\nEnclosing symbols:\n");
+            for symbol in &self.symbols {
+                prompt.push_str(&format!("- {}\n", symbol));
+            }
+        }
+        prompt.push_str("\nDiff, with new-file line numbers on the left:\n```diff\n");
+        for hunk in &self.hunks {
+            prompt.push_str(&hunk.header);
+            prompt.push('\n');
+            for line in &hunk.lines {
+                let number = line.new_line.map(|n| n.to_string()).unwrap_or_default();
+                let marker = match line.kind {
+                    LineKind::Added => '+',
+                    LineKind::Removed => '-',
+                    LineKind::Context => ' ',
+                };
+                prompt.push_str(&format!("{:>5} {}{}\n", number, marker, line.content));
+            }
+        }
+        prompt.push_str("```\n");
+        prompt
+    }
+
+    fn line_count(&self) -> usize {
+        self.hunks.iter().map(|hunk| hunk.lines.len()).sum()
+    }
+
+    /// `line` if the chunk shows it, otherwise the closest changed line
+    fn anchor(&self, line: u32) -> u32 {
+        if self
+            .hunks
+            .iter()
+            .filter_map(Hunk::new_range)
+            .any(|(first, last)| (first..=last).contains(&line))
+        {
+            return line;
+        }
+        self.hunks
+            .iter()
+            .flat_map(Hunk::changed_lines)
+            .min_by_key(|changed| changed.abs_diff(line))
+            .or_else(|| {
+                self.hunks
+                    .iter()
+                    .find_map(Hunk::new_range)
+                    .map(|(first, _)| first)
+            })
+            .unwrap_or(1)
+    }
+}
+
+/// Split each file's diff into chunks, describing the symbols around its changes
+///
+/// `symbols` maps a file path to its symbols with 0-based line positions.
+/// Deleted files are skipped: there are no new lines to anchor findings to.
+pub fn chunk_files(
+    files: &[FileDiff],
+    symbols: &HashMap<PathBuf, Vec<SymbolEntry>>,
+) -> Vec<ReviewChunk> {
+    let mut chunks = Vec::new();
+    for file in files {
+        if file.status == FileStatus::Deleted {
+            continue;
+        }
+        let file_symbols = symbols
+            .get(&file.path)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        let mut current: Vec<Hunk> = Vec::new();
+        let mut current_lines = 0;
+        for hunk in &file.hunks {
+            if !current.is_empty() && current_lines + hunk.lines.len() > MAX_CHUNK_LINES {
+                chunks.push(make_chunk(file, std::mem::take(&mut current), file_symbols));
+                current_lines = 0;
+            }
+            current_lines += hunk.lines.len();
+            current.push(hunk.clone());
+        }
+        if !current.is_empty() {
+            chunks.push(make_chunk(file, current, file_symbols));
+        }
+    }
+    chunks
+}
+
+fn make_chunk(file: &FileDiff, hunks: Vec<Hunk>, symbols: &[SymbolEntry]) -> ReviewChunk {
+    let changed: BTreeSet<u32> = hunks.iter().flat_map(Hunk::changed_lines).collect();
+    let symbols = symbols
+        .iter()
+        .filter(|symbol| {
+            changed.iter().any(|line| {
+                let line = (*line as usize).saturating_sub(1);
+                symbol.start_pos.line <= line && line <= symbol.end_pos.line
+            })
+        })
+        .map(|symbol| {
+            let label = symbol
+                .signature
+                .clone()
+                .unwrap_or_else(|| format!("{:?} {}", symbol.kind, symbol.name));
+            format!(
+                "{} (lines {}-{})",
+                label.lines().next().unwrap_or_default().trim(),
+                symbol.start_pos.line + 1,
+                symbol.end_pos.line + 1
+            )
+        })
+        .collect();
+    ReviewChunk {
+        path: file.path.clone(),
+        status: file.status,
+        hunks,
+        symbols,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Info => "info",
+            Self::Warning => "warning",
+            Self::Error => "error",
+        }
+    }
+
+    /// The SARIF `level` for this severity
+    pub fn sarif_level(&self) -> &'static str {
+        match self {
+            Self::Info => "note",
+            Self::Warning => "warning",
+            Self::Error => "error",
+        }
+    }
+}
+
+impl FromStr for Severity {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "info" | "note" | "low" | "minor" | "suggestion" => Ok(Self::Info),
+            "warning" | "warn" | "medium" | "moderate" => Ok(Self::Warning),
+            "error" | "high" | "critical" | "major" | "blocker" => Ok(Self::Error),
+            other => bail!(
+                "Unknown severity '{}' (expected info, warning or error)",
+                other
+            ),
+        }
+    }
+}
+
+/// A review comment anchored to a line of the new file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Finding {
+    pub path: PathBuf,
+    pub line: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_line: Option<u32>,
+    pub severity: Severity,
+    pub category: String,
+    pub title: String,
+    pub message: String,
+    /// Suggested fix as a unified diff
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub suggestion: Option<String>,
+}
+
+/// A finding as the curator writes it; every field is optional
+#[derive(Debug, Deserialize)]
+struct RawFinding {
+    #[serde(default, alias = "start_line")]
+    line: Option<u32>,
+    #[serde(default)]
+    end_line: Option<u32>,
+    #[serde(default)]
+    severity: Option<String>,
+    #[serde(default)]
+    category: Option<String>,
+    #[serde(default)]
+    title: Option<String>,
+    #[serde(default, alias = "description", alias = "explanation")]
+    message: Option<String>,
+    #[serde(default, alias = "patch", alias = "fix")]
+    suggestion: Option<String>,
+}
+
+/// Parse the curator's JSON findings for `chunk`, re-anchoring lines the
+/// chunk does not show to the nearest changed line
+pub fn parse_findings(answer: &str, chunk: &ReviewChunk) -> Result<Vec<Finding>> {
+    let json = extract_json_array(answer)
+        .ok_or_else(|| anyhow!("The review answer contains no JSON array of findings"))?;
+    let raw: Vec<RawFinding> =
+        serde_json::from_str(json).context("The review findings are not valid JSON")?;
+
+    Ok(raw
+        .into_iter()
+        .filter_map(|raw| {
+            let message = raw.message.unwrap_or_default().trim().to_string();
+            let title = raw
+                .title
+                .map(|title| title.trim().to_string())
+                .filter(|title| !title.is_empty())
+                .or_else(|| message.lines().next().map(str::to_string))?;
+            let line = chunk.anchor(raw.line.unwrap_or(0));
+            Some(Finding {
+                path: chunk.path.clone(),
+                line,
+                end_line: raw.end_line.filter(|end| *end > line),
+                severity: raw
+                    .severity
+                    .and_then(|severity| severity.parse().ok())
+                    .unwrap_or(Severity::Warning),
+                category: raw
+                    .category
+                    .map(|category| category.trim().to_lowercase().replace(' ', "-"))
+                    .filter(|category| !category.is_empty())
+                    .unwrap_or_else(|| "general".to_string()),
+                title,
+                message,
+                suggestion: raw
+                    .suggestion
+                    .filter(|suggestion| !suggestion.trim().is_empty()),
+            })
+        })
+        .collect())
+}
+
+/// The JSON array in an answer, preferring a fenced ```json block
+fn extract_json_array(answer: &str) -> Option<&str> {
+    let body = match answer.find("```json") {
+        Some(start) => {
+            let rest = &answer[start + "```json".len()..];
+            &rest[..rest.find("```").unwrap_or(rest.len())]
+        }
+        None => answer,
+    };
+    let start = body.find('[')?;
+    let end = body.rfind(']')?;
+    (start < end).then(|| &body[start..=end])
+}
+
+/// The pipeline's answer for one chunk
+#[derive(Debug, Clone)]
+pub struct ChunkOutcome {
+    pub answer: String,
+    pub cost: f64,
+}
+
+/// Progress record for one reviewed chunk
+#[derive(Debug, Clone, Serialize)]
+pub struct ChunkResult {
+    pub path: PathBuf,
+    pub findings: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Findings of a review, ordered by file and line
+#[derive(Debug, Clone, Serialize)]
+pub struct ReviewReport {
+    pub range: String,
+    pub files: usize,
+    pub chunks: usize,
+    pub findings: Vec<Finding>,
+    /// Chunks whose review failed, with the error
+    pub failed: Vec<ChunkResult>,
+    pub cost: f64,
+    pub duration_ms: u64,
+}
+
+impl ReviewReport {
+    /// A report without findings
+    pub fn new(range: &str, files: usize) -> Self {
+        Self {
+            range: range.to_string(),
+            files,
+            chunks: 0,
+            findings: Vec::new(),
+            failed: Vec::new(),
+            cost: 0.0,
+            duration_ms: 0,
+        }
+    }
+
+    /// Findings at or above `severity`
+    pub fn count_at_least(&self, severity: Severity) -> usize {
+        self.findings
+            .iter()
+            .filter(|finding| finding.severity >= severity)
+            .count()
+    }
+
+    /// The report as a SARIF 2.1.0 log, with one rule per finding category
+    pub fn to_sarif(&self) -> Value {
+        let categories: BTreeSet<&str> = self
+            .findings
+            .iter()
+            .map(|finding| finding.category.as_str())
+            .collect();
+        let rules: Vec<Value> = categories
+            .iter()
+            .map(|category| {
+                json!({
+                    "id": category,
+                    "shortDescription": { "text": format!("Hive review: {}", category) },
+                })
+            })
+            .collect();
+        let results: Vec<Value> = self
+            .findings
+            .iter()
+            .map(|finding| {
+                let mut region = json!({ "startLine": finding.line });
+                if let Some(end_line) = finding.end_line {
+                    region["endLine"] = json!(end_line);
+                }
+                let mut result = json!({
+                    "ruleId": finding.category,
+                    "ruleIndex": categories.iter().position(|c| *c == finding.category),
+                    "level": finding.severity.sarif_level(),
+                    "message": {
+                        "text": if finding.message.is_empty() {
+                            finding.title.clone()
+                        } else {
+                            format!("{}: {}", finding.title, finding.message)
+                        },
+                    },
+                    "locations": [{
+                        "physicalLocation": {
+                            "artifactLocation": {
+                                "uri": finding.path.to_string_lossy().replace('\\', "/"),
+                                "uriBaseId": "%SRCROOT%",
+                            },
+                            "region": region,
+                        },
+                    }],
+                });
+                if let Some(suggestion) = &finding.suggestion {
+                    result["properties"] = json!({ "suggestedPatch": suggestion });
+                }
+                result
+            })
+            .collect();
+        // Chunks that were never reviewed, so a clean result is not mistaken for a pass
+        let notifications: Vec<Value> = self
+            .failed
+            .iter()
+            .map(|chunk| {
+                json!({
+                    "level": "error",
+                    "message": {
+                        "text": format!(
+                            "Review failed: {}",
+                            chunk.error.as_deref().unwrap_or("unknown error")
+                        ),
+                    },
+                    "locations": [{
+                        "physicalLocation": {
+                            "artifactLocation": {
+                                "uri": chunk.path.to_string_lossy().replace('\\', "/"),
+                                "uriBaseId": "%SRCROOT%",
+                            },
+                        },
+                    }],
+                })
+            })
+            .collect();
+
+        json!({
+            "$schema": SARIF_SCHEMA,
+            "version": "2.1.0",
+            "runs": [{
+                "tool": {
+                    "driver": {
+                        "name": "hive",
+                        "version": env!("CARGO_PKG_VERSION"),
+                        "informationUri": env!("CARGO_PKG_REPOSITORY"),
+                        "rules": rules,
+                    },
+                },
+                "invocations": [{
+                    "executionSuccessful": self.failed.is_empty(),
+                    "toolExecutionNotifications": notifications,
+                }],
+                "results": results,
+            }],
+        })
+    }
+}
+
+/// Runs every chunk through the review pipeline and collects the findings
+pub struct Reviewer {
+    concurrency: usize,
+}
+
+impl Default for Reviewer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Reviewer {
+    pub fn new() -> Self {
+        Self { concurrency: 2 }
+    }
+
+    /// Chunks reviewed at the same time
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Review `chunks` of a diff covering `files` files; `execute` runs the
+    /// pipeline on one chunk prompt
+    pub async fn run<F, Fut>(
+        &self,
+        range: &str,
+        files: usize,
+        chunks: Vec<ReviewChunk>,
+        execute: F,
+        on_chunk: impl Fn(&ChunkResult),
+    ) -> ReviewReport
+    where
+        F: Fn(String) -> Fut,
+        Fut: Future<Output = Result<ChunkOutcome>>,
+    {
+        let started = Instant::now();
+        let chunk_count = chunks.len();
+        let execute = &execute;
+        let outcomes: Vec<(ChunkResult, Vec<Finding>, f64)> = stream::iter(chunks)
+            .map(|chunk| async move {
+                let outcome = execute(chunk.prompt()).await.and_then(|outcome| {
+                    Ok((parse_findings(&outcome.answer, &chunk)?, outcome.cost))
+                });
+                match outcome {
+                    Ok((findings, cost)) => (
+                        ChunkResult {
+                            path: chunk.path,
+                            findings: findings.len(),
+                            error: None,
+                        },
+                        findings,
+                        cost,
+                    ),
+                    Err(e) => (
+                        ChunkResult {
+                            path: chunk.path,
+                            findings: 0,
+                            error: Some(format!("{:#}", e)),
+                        },
+                        Vec::new(),
+                        0.0,
+                    ),
+                }
+            })
+            .buffer_unordered(self.concurrency)
+            .inspect(|(result, _, _)| on_chunk(result))
+            .collect()
+            .await;
+
+        let mut report = ReviewReport::new(range, files);
+        report.chunks = chunk_count;
+        for (result, findings, cost) in outcomes {
+            report.findings.extend(findings);
+            report.cost += cost;
+            if result.error.is_some() {
+                report.failed.push(result);
+            }
+        }
+        report.findings.sort_by(|a, b| {
+            (&a.path, a.line, std::cmp::Reverse(a.severity)).cmp(&(
+                &b.path,
+                b.line,
+                std::cmp::Reverse(b.severity),
+            ))
+        });
+        report.failed.sort_by(|a, b| a.path.cmp(&b.path));
+        report.duration_ms = started.elapsed().as_millis() as u64;
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::Position;
+    use git2::Signature;
+    use std::path::Path;
+    use tempfile::TempDir;
+
+    fn commit_all(repo: &Repository, message: &str) {
+        let mut index = repo.index().unwrap();
+        index
+            .add_all(["*"], git2::IndexAddOption::DEFAULT, None)
+            .unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = Signature::now("Test", "test@example.com").unwrap();
+        let parents: Vec<git2::Commit> = repo
+            .head()
+            .ok()
+            .and_then(|head| head.peel_to_commit().ok())
+            .into_iter()
+            .collect();
+        let parents: Vec<&git2::Commit> = parents.iter().collect();
+        repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            message,
+            &tree,
+            &parents,
+        )
+        .unwrap();
+    }
+
+    fn chunk_for(lines: &[(LineKind, u32)]) -> ReviewChunk {
+        ReviewChunk {
+            path: PathBuf::from("src/lib.rs"),
+            status: FileStatus::Modified,
+            hunks: vec![Hunk {
+                header: "@@ -1,3 +1,4 @@".to_string(),
+                lines: lines
+                    .iter()
+                    .map(|(kind, line)| DiffLine {
+                        kind: *kind,
+                        content: String::new(),
+                        new_line: Some(*line),
+                    })
+                    .collect(),
+            }],
+            symbols: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_parse_spec() {
+        assert_eq!(DiffSpec::parse(None, false).unwrap(), DiffSpec::WorkingTree);
+        assert_eq!(DiffSpec::parse(None, true).unwrap(), DiffSpec::Staged);
+        assert_eq!(
+            DiffSpec::parse(Some("main...feature"), false).unwrap(),
+            DiffSpec::Range {
+                base: "main".to_string(),
+                head: "feature".to_string(),
+                merge_base: true,
+            }
+        );
+        let since = DiffSpec::parse(Some("v1.0"), false).unwrap();
+        assert_eq!(since.describe(), "v1.0..HEAD");
+        assert!(DiffSpec::parse(Some("..HEAD"), false).is_err());
+        assert!(DiffSpec::parse(Some("a..b"), true).is_err());
+    }
+
+    #[test]
+    fn test_diff_and_chunk_with_symbols() {
+        let dir = TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        std::fs::write(
+            dir.path().join("lib.rs"),
+            "fn add(a: i32, b: i32) -> i32 {\n    a + b\n}\n",
+        )
+        .unwrap();
+        commit_all(&repo, "initial");
+        let base = repo
+            .head()
+            .unwrap()
+            .peel_to_commit()
+            .unwrap()
+            .id()
+            .to_string();
+
+        std::fs::write(
+            dir.path().join("lib.rs"),
+            "fn add(a: i32, b: i32) -> i32 {\n    a.checked_add(b).unwrap()\n}\n",
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("new.txt"), "hello\n").unwrap();
+
+        // Uncommitted changes include untracked files
+        let files = diff_files(&repo, &DiffSpec::WorkingTree, 3).unwrap();
+        assert_eq!(files.len(), 2);
+        let lib = files
+            .iter()
+            .find(|f| f.path == Path::new("lib.rs"))
+            .unwrap();
+        assert_eq!(lib.status, FileStatus::Modified);
+        assert_eq!(lib.hunks[0].changed_lines().collect::<Vec<_>>(), vec![2]);
+        assert!(lib.new_content.as_deref().unwrap().contains("checked_add"));
+        let untracked = files
+            .iter()
+            .find(|f| f.path == Path::new("new.txt"))
+            .unwrap();
+        assert_eq!(untracked.status, FileStatus::Added);
+
+        assert!(diff_files(&repo, &DiffSpec::Staged, 3).unwrap().is_empty());
+        commit_all(&repo, "change");
+        let range = DiffSpec::parse(Some(&base), false).unwrap();
+        let files = diff_files(&repo, &range, 3).unwrap();
+        assert_eq!(files.len(), 2);
+
+        let symbol = SymbolEntry {
+            name: "add".to_string(),
+            signature: Some("fn add(a: i32, b: i32) -> i32".to_string()),
+            start_pos: Position {
+                line: 0,
+                column: 0,
+                offset: 0,
+            },
+            end_pos: Position {
+                line: 2,
+                column: 1,
+                offset: 0,
+            },
+            ..Default::default()
+        };
+        let symbols = HashMap::from([(PathBuf::from("lib.rs"), vec![symbol])]);
+        let chunks = chunk_files(&files, &symbols);
+        assert_eq!(chunks.len(), 2);
+        let lib = chunks
+            .iter()
+            .find(|c| c.path == Path::new("lib.rs"))
+            .unwrap();
+        assert_eq!(
+            lib.symbols,
+            vec!["fn add(a: i32, b: i32) -> i32 (lines 1-3)"]
+        );
+        let prompt = lib.prompt();
+        assert!(prompt.contains("    2 +    a.checked_add(b).unwrap()"));
+        assert!(prompt.contains("      -    a + b"));
+    }
+
+    #[test]
+    fn test_parse_findings_anchors_lines() {
+        let chunk = chunk_for(&[
+            (LineKind::Context, 10),
+            (LineKind::Added, 11),
+            (LineKind::Added, 12),
+            (LineKind::Context, 13),
+        ]);
+        let answer = r#"Here you go:
+```json
+[
+  {"line": 12, "severity": "critical", "category": "Error Handling",
+   "title": "Unwrap on overflow", "message": "checked_add returns None on overflow",
+   "suggestion": "-a.checked_add(b).unwrap()\n+a.saturating_add(b)"},
+  {"line": 40, "severity": "nit", "description": "Consider a doc comment"},
+  {"line": 11}
+]
+```"#;
+        let findings = parse_findings(answer, &chunk).unwrap();
+        assert_eq!(findings.len(), 2);
+        assert_eq!(findings[0].severity, Severity::Error);
+        assert_eq!(findings[0].category, "error-handling");
+        assert!(findings[0].suggestion.is_some());
+        // Lines outside the chunk move to the nearest changed line
+        assert_eq!(findings[1].line, 12);
+        assert_eq!(findings[1].severity, Severity::Warning);
+        assert_eq!(findings[1].title, "Consider a doc comment");
+
+        assert!(parse_findings("[]", &chunk).unwrap().is_empty());
+        assert!(parse_findings("Looks good to me", &chunk).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_review_report_and_sarif() {
+        let chunks = vec![
+            chunk_for(&[(LineKind::Added, 1)]),
+            ReviewChunk {
+                path: PathBuf::from("src/main.rs"),
+                ..chunk_for(&[(LineKind::Added, 5)])
+            },
+        ];
+        let reported = std::sync::Mutex::new(Vec::new());
+        let report = Reviewer::new()
+            .with_concurrency(2)
+            .run(
+                "main..HEAD",
+                2,
+                chunks,
+                |prompt| async move {
+                    if prompt.contains("src/main.rs") {
+                        bail!("rate limited");
+                    }
+                    Ok(ChunkOutcome {
+                        answer: r#"[{"line": 1, "severity": "warning", "category": "bug", "title": "Off by one", "message": "Loop skips the last item"},
+                                    {"line": 1, "severity": "error", "category": "security", "title": "SQL injection", "message": ""}]"#
+                            .to_string(),
+                        cost: 0.01,
+                    })
+                },
+                |result| reported.lock().unwrap().push(result.path.clone()),
+            )
+            .await;
+
+        assert_eq!(reported.lock().unwrap().len(), 2);
+        assert_eq!(report.findings.len(), 2);
+        assert_eq!(report.findings[0].severity, Severity::Error);
+        assert_eq!(report.failed.len(), 1);
+        assert_eq!(report.failed[0].error.as_deref(), Some("rate limited"));
+        assert_eq!(report.count_at_least(Severity::Warning), 2);
+        assert_eq!(report.count_at_least(Severity::Error), 1);
+
+        let sarif = report.to_sarif();
+        assert_eq!(sarif["version"], "2.1.0");
+        let run = &sarif["runs"][0];
+        assert_eq!(run["tool"]["driver"]["rules"].as_array().unwrap().len(), 2);
+        let result = &run["results"][0];
+        assert_eq!(result["ruleId"], "security");
+        assert_eq!(result["level"], "error");
+        assert_eq!(result["message"]["text"], "SQL injection");
+        assert_eq!(
+            result["locations"][0]["physicalLocation"]["artifactLocation"]["uri"],
+            "src/lib.rs"
+        );
+        assert_eq!(run["results"][1]["level"], "warning");
+        let invocation = &run["invocations"][0];
+        assert_eq!(invocation["executionSuccessful"], false);
+        let notification = &invocation["toolExecutionNotifications"][0];
+        assert_eq!(
+            notification["message"]["text"],
+            "Review failed: rate limited"
+        );
+        assert_eq!(
+            notification["locations"][0]["physicalLocation"]["artifactLocation"]["uri"],
+            "src/main.rs"
+        );
+    }
+
+    #[test]
+    fn test_builtin_pipeline() {
+        let pipeline = review_pipeline();
+        assert_eq!(pipeline.name, REVIEW_PIPELINE);
+        assert_eq!(pipeline.stages.len(), 3);
+        assert!(pipeline.stages.iter().all(|stage| !stage.include_context));
+
+        let dir = TempDir::new().unwrap();
+        let store = PipelineStore::new(dir.path());
+        assert_eq!(load_pipeline(&store, REVIEW_PIPELINE).unwrap(), pipeline);
+        assert!(load_pipeline(&store, "missing").is_err());
+    }
+}
@@ -5,9 +5,28 @@
 
 use anyhow::Result;
 use std::path::{Path, PathBuf};
-use tokio::fs;
 use tracing::info;
 
+use crate::analysis::walker::RepositoryWalker;
+
+/// Build/cache directories skipped entirely
+const SKIPPED_DIRECTORIES: &[&str] = &[
+    "target",
+    "node_modules",
+    "dist",
+    "build",
+    ".git",
+    "coverage",
+    ".vscode",
+    ".idea",
+    "__pycache__",
+    "vendor",
+    "deps",
+    ".next",
+    ".nuxt",
+    "out",
+];
+
 /// File priority for reading order
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum FilePriority {
@@ -45,47 +64,41 @@ impl RepositoryScanner {
         Ok(files)
     }
 
-    /// Iteratively scan directory to avoid recursion issues
+    /// Walk the repository with the shared gitignore-aware walker
     async fn scan_directory_iterative(root_path: &Path, files: &mut Vec<FileInfo>) -> Result<()> {
-        let mut dirs_to_scan = vec![(root_path.to_path_buf(), 0u32)];
-
-        while let Some((current_path, depth)) = dirs_to_scan.pop() {
-            // Prevent infinite recursion and skip very deep directories
-            if depth > 8 {
-                continue;
-            }
-
-            let mut entries = match fs::read_dir(&current_path).await {
-                Ok(entries) => entries,
-                Err(_) => continue, // Skip directories we can't read
-            };
-
-            while let Some(entry) = entries.next_entry().await? {
-                let path = entry.path();
-                let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+        // Hidden entries are included so important dotfiles survive; hidden
+        // directories are dropped below. Depth is capped to skip very deep trees.
+        let walked = RepositoryWalker::new(root_path)
+            .include_hidden(true)
+            .max_depth(9)
+            .exclude_dirs(SKIPPED_DIRECTORIES)
+            .walk_async()
+            .await?;
+
+        for file in walked {
+            let mut components = file.relative_path.components().peekable();
+            let mut hidden = false;
+            while let Some(component) = components.next() {
+                let name = component.as_os_str().to_str().unwrap_or("");
+                let is_file_name = components.peek().is_none();
 
                 // Skip hidden files and directories (except .gitignore, .env files)
-                if file_name.starts_with('.') && !Self::is_important_dotfile(file_name) {
-                    continue;
-                }
-
-                // Skip common build/cache directories
-                if Self::should_skip_directory(file_name) {
-                    continue;
+                if name.starts_with('.') && !(is_file_name && Self::is_important_dotfile(name)) {
+                    hidden = true;
+                    break;
                 }
+            }
 
-                if path.is_dir() {
-                    // Add subdirectory to scan queue
-                    dirs_to_scan.push((path, depth + 1));
-                } else if Self::is_source_file(&path) {
-                    let priority = Self::determine_file_priority(&path, root_path);
-                    files.push(FileInfo {
-                        path,
-                        priority,
-                        size: entry.metadata().await.map(|m| m.len()).unwrap_or(0),
-                    });
-                }
+            if hidden || !Self::is_source_file(&file.path) {
+                continue;
             }
+
+            let priority = Self::determine_file_priority(&file.path, root_path);
+            files.push(FileInfo {
+                path: file.path,
+                priority,
+                size: file.size,
+            });
         }
 
         Ok(())
@@ -99,27 +112,6 @@ impl RepositoryScanner {
         )
     }
 
-    /// Check if we should skip a directory entirely
-    fn should_skip_directory(name: &str) -> bool {
-        matches!(
-            name,
-            "target"
-                | "node_modules"
-                | "dist"
-                | "build"
-                | ".git"
-                | "coverage"
-                | ".vscode"
-                | ".idea"
-                | "__pycache__"
-                | "vendor"
-                | "deps"
-                | ".next"
-                | ".nuxt"
-                | "out"
-        )
-    }
-
     /// Check if a file is a source file we should read
     fn is_source_file(path: &Path) -> bool {
         if let Some(extension) = path.extension().and_then(|ext| ext.to_str()) {
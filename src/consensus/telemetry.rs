@@ -0,0 +1,1026 @@
+// Telemetry export - Prometheus metrics and OTLP traces for consensus runs
+// Counters are cumulative for the life of the process; every run becomes one trace
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::{Arc, Mutex, RwLock};
+
+use crate::consensus::types::{ConsensusResult, Stage, StageResult};
+use crate::providers::openrouter::performance::{CircuitState, ErrorType, PerformanceTracker};
+
+/// Collector base URL; traces are posted to `<endpoint>/v1/traces`
+pub const OTLP_ENDPOINT_ENV: &str = "OTEL_EXPORTER_OTLP_ENDPOINT";
+/// Full traces URL, used as-is and preferred over the base endpoint
+pub const OTLP_TRACES_ENDPOINT_ENV: &str = "OTEL_EXPORTER_OTLP_TRACES_ENDPOINT";
+pub const SERVICE_NAME_ENV: &str = "OTEL_SERVICE_NAME";
+
+/// Upper bounds of the latency histogram buckets in seconds
+const LATENCY_BUCKETS: [f64; 12] = [
+    0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 20.0, 30.0, 60.0, 120.0, 300.0,
+];
+
+const EXPORT_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+static TELEMETRY: Lazy<Arc<Telemetry>> = Lazy::new(|| {
+    let telemetry = Telemetry::new();
+    Arc::new(match OtlpExporter::from_env() {
+        Some(exporter) => telemetry.with_exporter(exporter),
+        None => telemetry,
+    })
+});
+
+/// The process-wide telemetry the pipeline reports into
+pub fn global() -> Arc<Telemetry> {
+    Arc::clone(&TELEMETRY)
+}
+
+#[derive(Debug, Clone, Default)]
+struct Histogram {
+    /// Cumulative count per bucket, as Prometheus expects
+    buckets: [u64; LATENCY_BUCKETS.len()],
+    count: u64,
+    sum: f64,
+}
+
+impl Histogram {
+    fn observe(&mut self, seconds: f64) {
+        for (bucket, bound) in self.buckets.iter_mut().zip(LATENCY_BUCKETS) {
+            if seconds <= bound {
+                *bucket += 1;
+            }
+        }
+        self.count += 1;
+        self.sum += seconds;
+    }
+
+    fn render(&self, out: &mut String, name: &str, labels: &[(&str, &str)]) {
+        for (count, bound) in self.buckets.iter().zip(LATENCY_BUCKETS) {
+            let le = bound.to_string();
+            let mut bucket_labels = labels.to_vec();
+            bucket_labels.push(("le", &le));
+            sample(
+                out,
+                &format!("{}_bucket", name),
+                &bucket_labels,
+                *count as f64,
+            );
+        }
+        let mut inf_labels = labels.to_vec();
+        inf_labels.push(("le", "+Inf"));
+        sample(
+            out,
+            &format!("{}_bucket", name),
+            &inf_labels,
+            self.count as f64,
+        );
+        sample(out, &format!("{}_sum", name), labels, self.sum);
+        sample(out, &format!("{}_count", name), labels, self.count as f64);
+    }
+}
+
+#[derive(Debug, Default)]
+struct ModelStats {
+    latency: Histogram,
+    tokens: u64,
+    cost: f64,
+    errors: u64,
+    fallbacks: u64,
+    rate_limits: u64,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    models: BTreeMap<String, ModelStats>,
+    stages: BTreeMap<String, Histogram>,
+    stage_errors: BTreeMap<String, u64>,
+    runs_succeeded: u64,
+    runs_failed: u64,
+    run_duration: Histogram,
+}
+
+/// One model request made while running a stage
+#[derive(Debug, Clone)]
+struct ModelCall {
+    model: String,
+    duration: f64,
+    input_tokens: Option<u32>,
+    output_tokens: Option<u32>,
+    total_tokens: u32,
+    cost: f64,
+    errors: u32,
+    fallback_used: bool,
+    rate_limited: bool,
+    error: Option<String>,
+}
+
+/// The model requests behind a stage: one per candidate when it fanned out
+fn model_calls(stage: &StageResult) -> Vec<ModelCall> {
+    let Some(analytics) = &stage.analytics else {
+        return vec![ModelCall {
+            model: stage.model.clone(),
+            duration: 0.0,
+            input_tokens: stage.usage.as_ref().map(|u| u.prompt_tokens),
+            output_tokens: stage.usage.as_ref().map(|u| u.completion_tokens),
+            total_tokens: stage.usage.as_ref().map_or(0, |u| u.total_tokens),
+            cost: 0.0,
+            errors: 0,
+            fallback_used: false,
+            rate_limited: false,
+            error: None,
+        }];
+    };
+
+    if !analytics.candidates.is_empty() {
+        return analytics
+            .candidates
+            .iter()
+            .map(|candidate| ModelCall {
+                model: candidate.model.clone(),
+                duration: candidate.duration,
+                input_tokens: None,
+                output_tokens: None,
+                total_tokens: candidate.total_tokens,
+                cost: candidate.cost,
+                errors: u32::from(candidate.error.is_some()),
+                fallback_used: false,
+                rate_limited: false,
+                error: candidate.error.clone(),
+            })
+            .collect();
+    }
+
+    vec![ModelCall {
+        model: stage.model.clone(),
+        duration: analytics.duration,
+        input_tokens: stage.usage.as_ref().map(|u| u.prompt_tokens),
+        output_tokens: stage.usage.as_ref().map(|u| u.completion_tokens),
+        total_tokens: stage.usage.as_ref().map_or(0, |u| u.total_tokens),
+        cost: analytics.cost,
+        errors: analytics.error_count,
+        fallback_used: analytics.fallback_used,
+        rate_limited: analytics.rate_limit_hit,
+        error: None,
+    }]
+}
+
+/// Context of a run that failed inside a stage, naming the stage and its model
+#[derive(Debug, Clone)]
+pub struct StageFailure {
+    pub stage: Stage,
+    pub model: String,
+}
+
+impl std::fmt::Display for StageFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Failed to run {} stage", self.stage.display_name())
+    }
+}
+
+/// Process-wide consensus metrics and the optional trace exporter
+pub struct Telemetry {
+    state: Mutex<State>,
+    tracker: Arc<PerformanceTracker>,
+    exporter: RwLock<Option<Arc<OtlpExporter>>>,
+}
+
+impl Default for Telemetry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Telemetry {
+    /// Create telemetry with its own performance tracker and no exporter
+    pub fn new() -> Self {
+        Self {
+            state: Mutex::new(State::default()),
+            tracker: Arc::new(PerformanceTracker::new(60)),
+            exporter: RwLock::new(None),
+        }
+    }
+
+    /// Report model calls to an existing performance tracker
+    pub fn with_tracker(mut self, tracker: Arc<PerformanceTracker>) -> Self {
+        self.tracker = tracker;
+        self
+    }
+
+    /// Export a trace for every finished run
+    pub fn with_exporter(self, exporter: OtlpExporter) -> Self {
+        self.set_exporter(Some(exporter));
+        self
+    }
+
+    /// Replace the trace exporter; `None` turns export off
+    pub fn set_exporter(&self, exporter: Option<OtlpExporter>) {
+        *self.exporter.write().unwrap() = exporter.map(Arc::new);
+    }
+
+    pub fn exporter(&self) -> Option<Arc<OtlpExporter>> {
+        self.exporter.read().unwrap().clone()
+    }
+
+    /// The tracker whose circuit breakers are reported
+    pub fn tracker(&self) -> Arc<PerformanceTracker> {
+        Arc::clone(&self.tracker)
+    }
+
+    /// Count a stage's model calls and feed their outcome to the tracker
+    pub async fn record_stage(&self, stage: &StageResult) -> Result<()> {
+        let calls = model_calls(stage);
+        {
+            let mut state = self.state.lock().unwrap();
+            if let Some(analytics) = &stage.analytics {
+                state
+                    .stages
+                    .entry(stage.stage_name.to_lowercase())
+                    .or_default()
+                    .observe(analytics.duration);
+            }
+            for call in &calls {
+                let stats = state.models.entry(call.model.clone()).or_default();
+                stats.latency.observe(call.duration);
+                stats.tokens += call.total_tokens as u64;
+                stats.cost += call.cost;
+                stats.errors += call.errors as u64;
+                stats.fallbacks += u64::from(call.fallback_used);
+                stats.rate_limits += u64::from(call.rate_limited);
+            }
+        }
+
+        for call in &calls {
+            let error_type = if call.rate_limited {
+                ErrorType::RateLimit
+            } else {
+                ErrorType::Other(call.error.clone().unwrap_or_else(|| "retried".to_string()))
+            };
+            for _ in 0..call.errors {
+                self.tracker
+                    .record_circuit_breaker_failure(&call.model, error_type.clone())
+                    .await?;
+            }
+            if call.error.is_none() {
+                self.tracker
+                    .record_circuit_breaker_success(&call.model)
+                    .await?;
+            }
+            self.tracker
+                .track_performance(
+                    &call.model,
+                    (call.duration * 1000.0) as u64,
+                    call.output_tokens.unwrap_or(call.total_tokens),
+                    call.error.is_none(),
+                    call.error.as_ref().map(|_| error_type.clone()),
+                    None,
+                    &stage.stage_name.to_lowercase(),
+                    None,
+                )
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Record a finished run and export its trace when an exporter is set
+    pub async fn finish_run(&self, started_at: DateTime<Utc>, outcome: &Result<ConsensusResult>) {
+        let ended_at = Utc::now();
+        if let Ok(result) = outcome {
+            for stage in &result.stages {
+                if let Err(e) = self.record_stage(stage).await {
+                    tracing::warn!("Failed to record telemetry for {}: {}", stage.stage_name, e);
+                }
+            }
+        }
+        let failure = outcome
+            .as_ref()
+            .err()
+            .and_then(|e| Some((e.downcast_ref::<StageFailure>()?, format!("{:#}", e))));
+        if let Some((failure, message)) = &failure {
+            if let Err(e) = self
+                .tracker
+                .record_circuit_breaker_failure(&failure.model, ErrorType::Other(message.clone()))
+                .await
+            {
+                tracing::warn!("Failed to record telemetry for {}: {}", failure.model, e);
+            }
+        }
+        {
+            let mut state = self.state.lock().unwrap();
+            match outcome {
+                Ok(result) if result.success => state.runs_succeeded += 1,
+                _ => state.runs_failed += 1,
+            }
+            if let Some((failure, _)) = &failure {
+                *state
+                    .stage_errors
+                    .entry(failure.stage.as_str().to_string())
+                    .or_default() += 1;
+                state
+                    .models
+                    .entry(failure.model.clone())
+                    .or_default()
+                    .errors += 1;
+            }
+            let duration = (ended_at - started_at).num_milliseconds() as f64 / 1000.0;
+            state.run_duration.observe(duration);
+        }
+
+        if let Some(exporter) = self.exporter() {
+            let spans = trace_run(started_at, ended_at, outcome);
+            tokio::spawn(async move {
+                if let Err(e) = exporter.export(&spans).await {
+                    tracing::warn!("Failed to export consensus trace: {}", e);
+                }
+            });
+        }
+    }
+
+    /// Everything recorded so far in the Prometheus text exposition format
+    pub async fn render_prometheus(&self) -> String {
+        let breakers = self.tracker.get_circuit_breaker_status().await;
+        let state = self.state.lock().unwrap();
+        let mut out = String::new();
+
+        header(
+            &mut out,
+            "hive_consensus_runs_total",
+            "counter",
+            "Consensus runs by outcome",
+        );
+        sample(
+            &mut out,
+            "hive_consensus_runs_total",
+            &[("status", "success")],
+            state.runs_succeeded as f64,
+        );
+        sample(
+            &mut out,
+            "hive_consensus_runs_total",
+            &[("status", "error")],
+            state.runs_failed as f64,
+        );
+
+        header(
+            &mut out,
+            "hive_consensus_duration_seconds",
+            "histogram",
+            "Wall-clock duration of consensus runs",
+        );
+        state
+            .run_duration
+            .render(&mut out, "hive_consensus_duration_seconds", &[]);
+
+        header(
+            &mut out,
+            "hive_stage_duration_seconds",
+            "histogram",
+            "Duration of consensus stages",
+        );
+        for (stage, histogram) in &state.stages {
+            histogram.render(&mut out, "hive_stage_duration_seconds", &[("stage", stage)]);
+        }
+
+        header(
+            &mut out,
+            "hive_stage_errors_total",
+            "counter",
+            "Consensus runs that failed in a stage",
+        );
+        for (stage, errors) in &state.stage_errors {
+            sample(
+                &mut out,
+                "hive_stage_errors_total",
+                &[("stage", stage)],
+                *errors as f64,
+            );
+        }
+
+        header(
+            &mut out,
+            "hive_model_request_duration_seconds",
+            "histogram",
+            "Latency of model requests",
+        );
+        for (model, stats) in &state.models {
+            stats.latency.render(
+                &mut out,
+                "hive_model_request_duration_seconds",
+                &[("model", model)],
+            );
+        }
+
+        let counters: [(&str, &str, fn(&ModelStats) -> f64); 5] = [
+            (
+                "hive_model_tokens_total",
+                "Tokens used by model requests",
+                |s| s.tokens as f64,
+            ),
+            (
+                "hive_model_cost_dollars_total",
+                "Cost of model requests in USD",
+                |s| s.cost,
+            ),
+            (
+                "hive_model_errors_total",
+                "Failed model requests, including retried ones",
+                |s| s.errors as f64,
+            ),
+            (
+                "hive_model_fallbacks_total",
+                "Requests answered by a fallback model",
+                |s| s.fallbacks as f64,
+            ),
+            (
+                "hive_model_rate_limits_total",
+                "Requests that hit a rate limit",
+                |s| s.rate_limits as f64,
+            ),
+        ];
+        for (name, help, value) in counters {
+            header(&mut out, name, "counter", help);
+            for (model, stats) in &state.models {
+                sample(&mut out, name, &[("model", model)], value(stats));
+            }
+        }
+
+        header(
+            &mut out,
+            "hive_circuit_breaker_state",
+            "gauge",
+            "Circuit breaker state per model (1 for the current state)",
+        );
+        for breaker in &breakers {
+            for (circuit, label) in [
+                (CircuitState::Closed, "closed"),
+                (CircuitState::Open, "open"),
+                (CircuitState::HalfOpen, "half_open"),
+            ] {
+                let value = if breaker.state == circuit { 1.0 } else { 0.0 };
+                sample(
+                    &mut out,
+                    "hive_circuit_breaker_state",
+                    &[("model", &breaker.model_id), ("state", label)],
+                    value,
+                );
+            }
+        }
+        header(
+            &mut out,
+            "hive_circuit_breaker_failures",
+            "gauge",
+            "Consecutive failures counted by the circuit breaker",
+        );
+        for breaker in &breakers {
+            sample(
+                &mut out,
+                "hive_circuit_breaker_failures",
+                &[("model", &breaker.model_id)],
+                breaker.failure_count as f64,
+            );
+        }
+
+        out
+    }
+}
+
+fn header(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+}
+
+fn sample(out: &mut String, name: &str, labels: &[(&str, &str)], value: f64) {
+    out.push_str(name);
+    if !labels.is_empty() {
+        let labels: Vec<String> = labels
+            .iter()
+            .map(|(key, value)| format!("{}=\"{}\"", key, escape_label(value)))
+            .collect();
+        let _ = write!(out, "{{{}}}", labels.join(","));
+    }
+    let _ = writeln!(out, " {}", value);
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Value of a span attribute
+#[derive(Debug, Clone, PartialEq)]
+pub enum AttributeValue {
+    String(String),
+    Int(i64),
+    Double(f64),
+    Bool(bool),
+}
+
+impl AttributeValue {
+    fn to_otlp(&self) -> Value {
+        match self {
+            Self::String(value) => json!({ "stringValue": value }),
+            // OTLP/JSON encodes 64-bit integers as strings
+            Self::Int(value) => json!({ "intValue": value.to_string() }),
+            Self::Double(value) => json!({ "doubleValue": value }),
+            Self::Bool(value) => json!({ "boolValue": value }),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpanKind {
+    Internal,
+    /// An outgoing request, such as a model call
+    Client,
+}
+
+/// A finished span, ready for export
+#[derive(Debug, Clone)]
+pub struct SpanData {
+    pub trace_id: String,
+    pub span_id: String,
+    pub parent_span_id: Option<String>,
+    pub name: String,
+    pub kind: SpanKind,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub attributes: Vec<(String, AttributeValue)>,
+    /// Error message when the operation failed
+    pub error: Option<String>,
+}
+
+impl SpanData {
+    fn new(
+        trace_id: &str,
+        parent_span_id: Option<&str>,
+        name: String,
+        kind: SpanKind,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            trace_id: trace_id.to_string(),
+            span_id: hex::encode(rand::random::<[u8; 8]>()),
+            parent_span_id: parent_span_id.map(str::to_string),
+            name,
+            kind,
+            start,
+            end,
+            attributes: Vec::new(),
+            error: None,
+        }
+    }
+
+    fn attribute(mut self, key: &str, value: AttributeValue) -> Self {
+        self.attributes.push((key.to_string(), value));
+        self
+    }
+
+    fn to_otlp(&self) -> Value {
+        let nanos = |at: &DateTime<Utc>| at.timestamp_nanos_opt().unwrap_or_default().to_string();
+        let mut span = json!({
+            "traceId": self.trace_id,
+            "spanId": self.span_id,
+            "name": self.name,
+            "kind": match self.kind {
+                SpanKind::Internal => 1,
+                SpanKind::Client => 3,
+            },
+            "startTimeUnixNano": nanos(&self.start),
+            "endTimeUnixNano": nanos(&self.end),
+            "attributes": self
+                .attributes
+                .iter()
+                .map(|(key, value)| json!({ "key": key, "value": value.to_otlp() }))
+                .collect::<Vec<_>>(),
+            "status": match &self.error {
+                Some(message) => json!({ "code": 2, "message": message }),
+                None => json!({ "code": 1 }),
+            },
+        });
+        if let Some(parent) = &self.parent_span_id {
+            span["parentSpanId"] = json!(parent);
+        }
+        span
+    }
+}
+
+/// Spans for one consensus run: the run itself, a child per stage and a
+/// grandchild per model call
+pub fn trace_run(
+    started_at: DateTime<Utc>,
+    ended_at: DateTime<Utc>,
+    outcome: &Result<ConsensusResult>,
+) -> Vec<SpanData> {
+    let trace_id = hex::encode(rand::random::<[u8; 16]>());
+    let mut run = SpanData::new(
+        &trace_id,
+        None,
+        "consensus.run".to_string(),
+        SpanKind::Internal,
+        started_at,
+        ended_at,
+    );
+    let result = match outcome {
+        Ok(result) => {
+            run = run
+                .attribute(
+                    "hive.conversation_id",
+                    AttributeValue::String(result.conversation_id.clone()),
+                )
+                .attribute(
+                    "hive.stages",
+                    AttributeValue::Int(result.stages.len() as i64),
+                )
+                .attribute("hive.cost", AttributeValue::Double(result.total_cost));
+            if !result.success {
+                run.error = Some(
+                    result
+                        .error
+                        .clone()
+                        .unwrap_or_else(|| "consensus failed".to_string()),
+                );
+            }
+            Some(result)
+        }
+        Err(e) => {
+            if let Some(failure) = e.downcast_ref::<StageFailure>() {
+                run = run
+                    .attribute(
+                        "hive.failed_stage",
+                        AttributeValue::String(failure.stage.as_str().to_string()),
+                    )
+                    .attribute(
+                        "hive.failed_model",
+                        AttributeValue::String(failure.model.clone()),
+                    );
+            }
+            run.error = Some(format!("{:#}", e));
+            None
+        }
+    };
+    let run_id = run.span_id.clone();
+    let mut spans = vec![run];
+
+    for stage in result.map(|r| r.stages.as_slice()).unwrap_or_default() {
+        let (start, end) = stage
+            .analytics
+            .as_ref()
+            .map_or((started_at, ended_at), |a| (a.start_time, a.end_time));
+        let stage_name = stage.stage_name.to_lowercase();
+        let stage_span = SpanData::new(
+            &trace_id,
+            Some(&run_id),
+            format!("consensus.stage {}", stage_name),
+            SpanKind::Internal,
+            start,
+            end,
+        )
+        .attribute("hive.stage", AttributeValue::String(stage_name));
+        let stage_id = stage_span.span_id.clone();
+        spans.push(stage_span);
+
+        for call in model_calls(stage) {
+            let call_end = start + chrono::Duration::milliseconds((call.duration * 1000.0) as i64);
+            let mut span = SpanData::new(
+                &trace_id,
+                Some(&stage_id),
+                format!("chat {}", call.model),
+                SpanKind::Client,
+                start,
+                call_end.max(start),
+            )
+            .attribute(
+                "gen_ai.system",
+                AttributeValue::String("openrouter".to_string()),
+            )
+            .attribute("gen_ai.request.model", AttributeValue::String(call.model))
+            .attribute(
+                "gen_ai.usage.total_tokens",
+                AttributeValue::Int(call.total_tokens as i64),
+            )
+            .attribute("hive.cost", AttributeValue::Double(call.cost))
+            .attribute("hive.errors", AttributeValue::Int(call.errors as i64))
+            .attribute(
+                "hive.fallback_used",
+                AttributeValue::Bool(call.fallback_used),
+            );
+            if let Some(tokens) = call.input_tokens {
+                span = span.attribute(
+                    "gen_ai.usage.input_tokens",
+                    AttributeValue::Int(tokens as i64),
+                );
+            }
+            if let Some(tokens) = call.output_tokens {
+                span = span.attribute(
+                    "gen_ai.usage.output_tokens",
+                    AttributeValue::Int(tokens as i64),
+                );
+            }
+            span.error = call.error;
+            spans.push(span);
+        }
+    }
+    spans
+}
+
+/// Sends traces to an OpenTelemetry collector over OTLP/HTTP with JSON encoding
+pub struct OtlpExporter {
+    url: String,
+    service_name: String,
+    client: reqwest::Client,
+}
+
+impl OtlpExporter {
+    /// Export to the collector at `endpoint`, e.g. `http://localhost:4318`
+    pub fn new(endpoint: &str) -> Self {
+        Self::at(format!("{}/v1/traces", endpoint.trim_end_matches('/')))
+    }
+
+    fn at(url: String) -> Self {
+        Self {
+            url,
+            service_name: "hive".to_string(),
+            client: reqwest::Client::builder()
+                .timeout(EXPORT_TIMEOUT)
+                .build()
+                .unwrap_or_default(),
+        }
+    }
+
+    /// The exporter configured by the standard `OTEL_*` environment variables
+    pub fn from_env() -> Option<Self> {
+        let env = |name: &str| std::env::var(name).ok().filter(|value| !value.is_empty());
+        let exporter = match env(OTLP_TRACES_ENDPOINT_ENV) {
+            Some(url) => Self::at(url),
+            None => Self::new(&env(OTLP_ENDPOINT_ENV)?),
+        };
+        Some(match env(SERVICE_NAME_ENV) {
+            Some(name) => exporter.with_service_name(name),
+            None => exporter,
+        })
+    }
+
+    pub fn with_service_name(mut self, name: impl Into<String>) -> Self {
+        self.service_name = name.into();
+        self
+    }
+
+    /// Where traces are posted
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// The `ExportTraceServiceRequest` body for `spans`
+    pub fn encode(&self, spans: &[SpanData]) -> Value {
+        let resource = [
+            ("service.name", self.service_name.as_str()),
+            ("service.version", env!("CARGO_PKG_VERSION")),
+        ];
+        json!({
+            "resourceSpans": [{
+                "resource": {
+                    "attributes": resource
+                        .iter()
+                        .map(|(key, value)| json!({ "key": key, "value": { "stringValue": value } }))
+                        .collect::<Vec<_>>(),
+                },
+                "scopeSpans": [{
+                    "scope": { "name": "hive.consensus", "version": env!("CARGO_PKG_VERSION") },
+                    "spans": spans.iter().map(SpanData::to_otlp).collect::<Vec<_>>(),
+                }],
+            }],
+        })
+    }
+
+    pub async fn export(&self, spans: &[SpanData]) -> Result<()> {
+        let response = self
+            .client
+            .post(&self.url)
+            .json(&self.encode(spans))
+            .send()
+            .await
+            .with_context(|| format!("Failed to send traces to {}", self.url))?;
+        if !response.status().is_success() {
+            bail!("Collector at {} returned {}", self.url, response.status());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consensus::types::{
+        AnalyticsFeatures, CandidateAnalytics, StageAnalytics, TokenUsage,
+    };
+    use axum::{extract::State, http::StatusCode, routing::post, Json, Router};
+
+    fn stage(name: &str, model: &str, duration: f64, errors: u32) -> StageResult {
+        let end = Utc::now();
+        StageResult {
+            stage_id: "id".to_string(),
+            stage_name: name.to_string(),
+            question: String::new(),
+            answer: String::new(),
+            model: model.to_string(),
+            conversation_id: "conversation".to_string(),
+            timestamp: end,
+            usage: Some(TokenUsage {
+                prompt_tokens: 40,
+                completion_tokens: 60,
+                total_tokens: 100,
+            }),
+            analytics: Some(StageAnalytics {
+                duration,
+                cost: 0.002,
+                input_cost: 0.0,
+                output_cost: 0.0,
+                provider: "openrouter".to_string(),
+                model_internal_id: model.to_string(),
+                quality_score: 1.0,
+                error_count: errors,
+                fallback_used: false,
+                rate_limit_hit: errors > 0,
+                retry_count: errors,
+                start_time: end - chrono::Duration::milliseconds((duration * 1000.0) as i64),
+                end_time: end,
+                time_to_first_token: None,
+                classification_latency: None,
+                memory_usage: None,
+                features: AnalyticsFeatures {
+                    streaming: false,
+                    routing_variant: "test".to_string(),
+                    optimization_applied: None,
+                },
+                candidates: Vec::new(),
+                context: None,
+            }),
+        }
+    }
+
+    fn result(stages: Vec<StageResult>) -> ConsensusResult {
+        ConsensusResult {
+            success: true,
+            result: Some("answer".to_string()),
+            error: None,
+            stages,
+            conversation_id: "conversation".to_string(),
+            total_duration: 3.0,
+            total_cost: 0.004,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_prometheus_exposition() {
+        let telemetry = Telemetry::new();
+        let started = Utc::now() - chrono::Duration::seconds(3);
+        let outcome = Ok(result(vec![
+            stage("Generator", "openai/gpt-4o", 0.8, 0),
+            stage("Curator", "anthropic/claude-3.5-sonnet", 2.0, 5),
+        ]));
+        telemetry.finish_run(started, &outcome).await;
+        let failed = anyhow::anyhow!("503 Service Unavailable").context(StageFailure {
+            stage: Stage::Refiner,
+            model: "openai/gpt-4o".to_string(),
+        });
+        telemetry.finish_run(started, &Err(failed)).await;
+
+        let text = telemetry.render_prometheus().await;
+        assert!(text.contains("# TYPE hive_model_request_duration_seconds histogram"));
+        assert!(text.contains("hive_consensus_runs_total{status=\"success\"} 1\n"));
+        assert!(text.contains("hive_consensus_runs_total{status=\"error\"} 1\n"));
+        assert!(text.contains(
+            "hive_model_request_duration_seconds_bucket{model=\"openai/gpt-4o\",le=\"0.5\"} 0\n"
+        ));
+        assert!(text.contains(
+            "hive_model_request_duration_seconds_bucket{model=\"openai/gpt-4o\",le=\"1\"} 1\n"
+        ));
+        assert!(text.contains(
+            "hive_model_request_duration_seconds_bucket{model=\"openai/gpt-4o\",le=\"+Inf\"} 1\n"
+        ));
+        assert!(text.contains("hive_stage_duration_seconds_count{stage=\"curator\"} 1\n"));
+        assert!(text.contains("hive_stage_errors_total{stage=\"refiner\"} 1\n"));
+        assert!(text.contains("hive_model_errors_total{model=\"openai/gpt-4o\"} 1\n"));
+        assert!(text.contains("hive_model_tokens_total{model=\"openai/gpt-4o\"} 100\n"));
+        assert!(text
+            .contains("hive_model_rate_limits_total{model=\"anthropic/claude-3.5-sonnet\"} 1\n"));
+        // Five failures open the breaker; the success that followed leaves it open
+        assert!(text.contains(
+            "hive_circuit_breaker_state{model=\"anthropic/claude-3.5-sonnet\",state=\"open\"} 1\n"
+        ));
+        assert_eq!(escape_label("a\"b\\c\n"), "a\\\"b\\\\c\\n");
+    }
+
+    #[test]
+    fn test_trace_structure_for_fan_out() {
+        let mut fanned = stage("Refiner", "openai/gpt-4o", 1.5, 0);
+        fanned.analytics.as_mut().unwrap().candidates = ["openai/gpt-4o", "google/gemini-pro"]
+            .iter()
+            .enumerate()
+            .map(|(i, model)| CandidateAnalytics {
+                model: model.to_string(),
+                duration: 1.0 + i as f64 * 0.5,
+                cost: 0.001,
+                total_tokens: 50,
+                agreement: 0.9,
+                contradictions: 0,
+                score: 80.0,
+                selected: i == 0,
+                error: (i == 1).then(|| "timeout".to_string()),
+            })
+            .collect();
+        let outcome = Ok(result(vec![
+            stage("Generator", "openai/gpt-4o", 1.0, 0),
+            fanned,
+        ]));
+        let spans = trace_run(
+            Utc::now() - chrono::Duration::seconds(3),
+            Utc::now(),
+            &outcome,
+        );
+
+        assert_eq!(spans.len(), 1 + 2 + 3);
+        let run = &spans[0];
+        assert!(run.parent_span_id.is_none());
+        assert_eq!(run.trace_id.len(), 32);
+        assert!(spans.iter().all(|span| span.trace_id == run.trace_id));
+
+        let stages: Vec<&SpanData> = spans
+            .iter()
+            .filter(|span| span.parent_span_id.as_deref() == Some(run.span_id.as_str()))
+            .collect();
+        assert_eq!(stages.len(), 2);
+        let calls: Vec<&SpanData> = spans
+            .iter()
+            .filter(|span| span.parent_span_id.as_deref() == Some(stages[1].span_id.as_str()))
+            .collect();
+        assert_eq!(calls.len(), 2);
+        assert!(calls.iter().all(|span| span.kind == SpanKind::Client));
+        assert_eq!(calls[1].name, "chat google/gemini-pro");
+        assert_eq!(calls[1].error.as_deref(), Some("timeout"));
+
+        let failed = trace_run(Utc::now(), Utc::now(), &Err(anyhow::anyhow!("cancelled")));
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].error.as_deref(), Some("cancelled"));
+    }
+
+    #[tokio::test]
+    async fn test_export_to_local_collector() -> Result<()> {
+        // A stand-in for an OpenTelemetry collector's OTLP/HTTP receiver
+        let received: Arc<Mutex<Vec<Value>>> = Arc::default();
+        let app =
+            Router::new()
+                .route(
+                    "/v1/traces",
+                    post(
+                        |State(received): State<Arc<Mutex<Vec<Value>>>>,
+                         Json(body): Json<Value>| async move {
+                            received.lock().unwrap().push(body);
+                            StatusCode::OK
+                        },
+                    ),
+                )
+                .with_state(Arc::clone(&received));
+        let listener = std::net::TcpListener::bind("127.0.0.1:0")?;
+        let addr = listener.local_addr()?;
+        tokio::spawn(axum::Server::from_tcp(listener)?.serve(app.into_make_service()));
+
+        let exporter =
+            OtlpExporter::new(&format!("http://{}/", addr)).with_service_name("hive-test");
+        assert_eq!(exporter.url(), format!("http://{}/v1/traces", addr));
+        let spans = trace_run(
+            Utc::now() - chrono::Duration::seconds(2),
+            Utc::now(),
+            &Ok(result(vec![stage("Generator", "openai/gpt-4o", 1.0, 0)])),
+        );
+        exporter.export(&spans).await?;
+
+        let received = received.lock().unwrap();
+        assert_eq!(received.len(), 1);
+        let resource = &received[0]["resourceSpans"][0];
+        assert_eq!(
+            resource["resource"]["attributes"][0]["value"]["stringValue"],
+            "hive-test"
+        );
+        let exported = resource["scopeSpans"][0]["spans"].as_array().unwrap();
+        assert_eq!(exported.len(), 3);
+        assert_eq!(exported[0]["name"], "consensus.run");
+        assert_eq!(exported[2]["parentSpanId"], exported[1]["spanId"]);
+        assert_eq!(exported[2]["kind"], 3);
+        assert!(exported[2]["attributes"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|a| a["key"] == "gen_ai.usage.input_tokens" && a["value"]["intValue"] == "40"));
+
+        // A collector that refuses the request is reported as an error
+        let missing = OtlpExporter::new(&format!("http://{}/missing", addr));
+        assert!(missing.export(&spans).await.is_err());
+        Ok(())
+    }
+}
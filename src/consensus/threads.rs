@@ -0,0 +1,988 @@
+// Conversation threads - multi-turn history for consensus requests
+// Earlier turns are replayed as chat messages; those that no longer fit are folded into a rolling summary
+
+use anyhow::{anyhow, bail, Result};
+use rusqlite::{params, OptionalExtension};
+use serde::Serialize;
+use std::sync::Arc;
+
+use crate::consensus::context_budget::{condense, ModelLimits, TokenCounter};
+use crate::consensus::openrouter::{OpenRouterClient, OpenRouterMessage, OpenRouterRequest};
+use crate::consensus::types::Message;
+use crate::core::database::{current_timestamp, generate_id, DatabaseManager};
+
+/// `conversations.context_type` of thread rows
+pub const THREAD_CONTEXT_TYPE: &str = "thread";
+
+/// Written by REPL sessions before they were stored as threads
+const LEGACY_SESSION_CONTEXT_TYPE: &str = "session";
+
+const TITLE_LENGTH: usize = 80;
+
+/// Thread history never takes more than this many tokens
+const MAX_HISTORY_TOKENS: usize = 16_000;
+
+/// Role and framing tokens added to every chat message
+const MESSAGE_OVERHEAD_TOKENS: usize = 4;
+
+/// One question and the answer it got
+#[derive(Debug, Clone, Serialize)]
+pub struct Turn {
+    pub question: String,
+    pub answer: String,
+    pub model: Option<String>,
+    /// Cost of the turn; zero for turns loaded from the database
+    #[serde(skip)]
+    pub cost: f64,
+    #[serde(skip)]
+    message_ids: (String, String),
+}
+
+impl Turn {
+    /// A turn that has not been stored yet
+    pub fn new(
+        question: impl Into<String>,
+        answer: impl Into<String>,
+        model: Option<String>,
+    ) -> Self {
+        Self {
+            question: question.into(),
+            answer: answer.into(),
+            model,
+            cost: 0.0,
+            message_ids: (String::new(), String::new()),
+        }
+    }
+}
+
+/// A multi-turn conversation
+#[derive(Debug, Clone, Serialize)]
+pub struct Thread {
+    pub id: String,
+    pub title: Option<String>,
+    /// The thread this one was branched from
+    pub parent_id: Option<String>,
+    /// Owner on the backend server; `None` for local threads
+    pub user_id: Option<String>,
+    /// Rolling summary of the first `summarized_turns` turns
+    pub summary: Option<String>,
+    pub summarized_turns: usize,
+    pub turns: Vec<Turn>,
+    pub total_cost: f64,
+    /// Threads are only written once they have a first turn
+    #[serde(skip)]
+    persisted: bool,
+}
+
+impl Thread {
+    pub fn new() -> Self {
+        Self::with_id(generate_id())
+    }
+
+    /// An empty thread with a caller-chosen id
+    pub fn with_id(id: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            title: None,
+            parent_id: None,
+            user_id: None,
+            summary: None,
+            summarized_turns: 0,
+            turns: Vec::new(),
+            total_cost: 0.0,
+            persisted: false,
+        }
+    }
+
+    /// First eight characters of the id, enough to select the thread
+    pub fn short_id(&self) -> &str {
+        &self.id[..self.id.len().min(8)]
+    }
+
+    /// The thread as chat messages: its summary, then the turns it does not cover
+    pub fn history_messages(&self) -> Vec<Message> {
+        let mut messages = Vec::new();
+        if let Some(summary) = self.summary.as_ref().filter(|s| !s.is_empty()) {
+            messages.push(Message {
+                role: "system".to_string(),
+                content: format!("Summary of the earlier conversation:\n{}", summary),
+            });
+        }
+        let recent = &self.turns[self.summarized_turns.min(self.turns.len())..];
+        for turn in recent {
+            messages.push(Message {
+                role: "user".to_string(),
+                content: turn.question.clone(),
+            });
+            messages.push(Message {
+                role: "assistant".to_string(),
+                content: turn.answer.clone(),
+            });
+        }
+        messages
+    }
+}
+
+impl Default for Thread {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Listing row for thread lists
+#[derive(Debug, Clone, Serialize)]
+pub struct ThreadSummary {
+    pub id: String,
+    pub title: String,
+    pub parent_id: Option<String>,
+    pub turns: usize,
+    pub total_cost: f64,
+    pub updated_at: String,
+}
+
+/// Tokens of history a request may replay, from the smallest window among its models
+pub fn history_budget(limits: &ModelLimits) -> usize {
+    (limits.context_window / 4).min(MAX_HISTORY_TOKENS)
+}
+
+/// Folds older turns into a thread's rolling summary
+pub struct Summarizer {
+    client: Option<Arc<OpenRouterClient>>,
+    model: String,
+}
+
+impl Summarizer {
+    pub fn new(client: Arc<OpenRouterClient>, model: impl Into<String>) -> Self {
+        Self {
+            client: Some(client),
+            model: model.into(),
+        }
+    }
+
+    /// Extractive summaries only, without model calls
+    pub fn offline() -> Self {
+        Self {
+            client: None,
+            model: String::new(),
+        }
+    }
+
+    /// Merge `turns` into `previous`, in at most `limit` tokens
+    ///
+    /// Falls back to an extractive summary when no model is available or the
+    /// call fails, so a thread can always continue.
+    async fn summarize(
+        &self,
+        previous: Option<&str>,
+        turns: &[Turn],
+        limit: usize,
+        counter: &TokenCounter,
+    ) -> String {
+        if let Some(client) = &self.client {
+            match self
+                .summarize_with_model(client, previous, turns, limit)
+                .await
+            {
+                Ok(summary) if !summary.trim().is_empty() => {
+                    return keep_tail(summary.trim(), limit, counter)
+                }
+                Ok(_) => tracing::warn!("Summarizer returned nothing; condensing thread instead"),
+                Err(e) => tracing::warn!("Failed to summarize thread, condensing instead: {}", e),
+            }
+        }
+
+        let mut summary = previous.map(str::to_string).unwrap_or_default();
+        for turn in turns {
+            summary.push_str(&format!(
+                "\nUser: {}\nAssistant: {}\n",
+                turn.question,
+                condense(&turn.answer)
+            ));
+        }
+        keep_tail(summary.trim(), limit, counter)
+    }
+
+    async fn summarize_with_model(
+        &self,
+        client: &OpenRouterClient,
+        previous: Option<&str>,
+        turns: &[Turn],
+        limit: usize,
+    ) -> Result<String> {
+        let mut transcript = String::new();
+        if let Some(previous) = previous {
+            transcript.push_str(&format!("Previous summary:\n{}\n\n", previous));
+        }
+        transcript.push_str("New exchanges:\n");
+        for turn in turns {
+            transcript.push_str(&format!(
+                "\nUser: {}\nAssistant: {}\n",
+                turn.question, turn.answer
+            ));
+        }
+
+        let request = OpenRouterRequest {
+            model: self.model.clone(),
+            messages: vec![
+                OpenRouterMessage {
+                    role: "system".to_string(),
+                    content: format!(
+                        "You keep the running summary of a conversation. Merge the previous \
+                        summary and the new exchanges into one summary of at most {} tokens. \
+                        Keep facts, decisions, names, code identifiers and open questions; \
+                        drop pleasantries. Reply with the summary only.",
+                        limit
+                    ),
+                },
+                OpenRouterMessage {
+                    role: "user".to_string(),
+                    content: transcript,
+                },
+            ],
+            temperature: Some(0.2),
+            max_tokens: Some(limit as u32),
+            top_p: None,
+            frequency_penalty: None,
+            presence_penalty: None,
+            stream: None,
+            provider: None,
+        };
+
+        let response = client.chat_completion(request).await?;
+        Ok(response
+            .choices
+            .first()
+            .and_then(|choice| choice.message.as_ref())
+            .map(|message| message.content.clone())
+            .unwrap_or_default())
+    }
+}
+
+/// The newest lines of `text` that fit in `limit` tokens
+fn keep_tail(text: &str, limit: usize, counter: &TokenCounter) -> String {
+    let mut lines = Vec::new();
+    let mut used = 0;
+    for line in text.lines().rev() {
+        let tokens = counter.count(line) + 1;
+        if used + tokens > limit {
+            break;
+        }
+        used += tokens;
+        lines.push(line);
+    }
+    lines.reverse();
+    lines.join("\n")
+}
+
+fn turn_tokens(counter: &TokenCounter, turn: &Turn) -> usize {
+    counter.count(&turn.question) + counter.count(&turn.answer) + 2 * MESSAGE_OVERHEAD_TOKENS
+}
+
+/// SQLite persistence for threads
+///
+/// A thread is a `conversations` row with `context_type = 'thread'`; each turn
+/// is a user/assistant pair of `messages` rows.
+pub struct ThreadStore {
+    database: Arc<DatabaseManager>,
+}
+
+impl ThreadStore {
+    /// Open the store, adding the columns it needs to older schemas
+    pub fn new(database: Arc<DatabaseManager>) -> Result<Self> {
+        let store = Self { database };
+        store.ensure_schema()?;
+        Ok(store)
+    }
+
+    fn ensure_schema(&self) -> Result<()> {
+        let conn = self.database.get_connection()?;
+        let columns: Vec<String> = conn
+            .prepare("PRAGMA table_info(conversations)")?
+            .query_map([], |row| row.get(1))?
+            .collect::<Result<_, _>>()?;
+
+        if columns.is_empty() {
+            conn.execute_batch(
+                "CREATE TABLE conversations (
+                    id TEXT PRIMARY KEY,
+                    user_id TEXT,
+                    title TEXT,
+                    context_type TEXT DEFAULT 'general',
+                    parent_id TEXT,
+                    summary TEXT,
+                    summarized_turns INTEGER DEFAULT 0,
+                    total_cost REAL DEFAULT 0.0,
+                    created_at TEXT NOT NULL,
+                    updated_at TEXT NOT NULL
+                )",
+            )?;
+        } else {
+            for (column, definition) in [
+                ("user_id", "TEXT"),
+                ("title", "TEXT"),
+                ("context_type", "TEXT DEFAULT 'general'"),
+                ("parent_id", "TEXT"),
+                ("summary", "TEXT"),
+                ("summarized_turns", "INTEGER DEFAULT 0"),
+            ] {
+                if !columns.iter().any(|c| c == column) {
+                    conn.execute_batch(&format!(
+                        "ALTER TABLE conversations ADD COLUMN {} {}",
+                        column, definition
+                    ))?;
+                }
+            }
+        }
+
+        conn.execute(
+            "UPDATE conversations SET context_type = ?1 WHERE context_type = ?2",
+            params![THREAD_CONTEXT_TYPE, LEGACY_SESSION_CONTEXT_TYPE],
+        )?;
+
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS messages (
+                id TEXT PRIMARY KEY,
+                conversation_id TEXT NOT NULL,
+                role TEXT NOT NULL,
+                content TEXT NOT NULL,
+                stage TEXT,
+                model_used TEXT,
+                timestamp TEXT DEFAULT CURRENT_TIMESTAMP,
+                FOREIGN KEY (conversation_id) REFERENCES conversations(id)
+            );
+            CREATE INDEX IF NOT EXISTS idx_messages_conversation ON messages(conversation_id);",
+        )?;
+        Ok(())
+    }
+
+    /// Full id of the thread matching an id or unique id prefix
+    pub fn resolve(&self, id: &str) -> Result<String> {
+        let conn = self.database.get_connection()?;
+        let matches: Vec<String> = conn
+            .prepare(
+                "SELECT id FROM conversations
+                 WHERE context_type = ?1 AND id LIKE ?2 || '%'
+                 ORDER BY updated_at DESC",
+            )?
+            .query_map(params![THREAD_CONTEXT_TYPE, id], |row| row.get(0))?
+            .collect::<Result<_, _>>()?;
+
+        match matches.len() {
+            0 => bail!("No thread matching '{}'", id),
+            1 => Ok(matches.into_iter().next().unwrap()),
+            _ if matches.iter().any(|thread_id| thread_id == id) => Ok(id.to_string()),
+            n => bail!("'{}' matches {} threads; use a longer id", id, n),
+        }
+    }
+
+    /// Load a thread by id or unique id prefix
+    pub fn load(&self, id: &str) -> Result<Thread> {
+        let id = self.resolve(id)?;
+        self.load_exact(&id)
+    }
+
+    /// Load the thread with exactly this id, never matching by prefix
+    pub fn load_exact(&self, id: &str) -> Result<Thread> {
+        let conn = self.database.get_connection()?;
+        let (title, parent_id, user_id, summary, summarized_turns, total_cost) = conn
+            .query_row(
+                "SELECT title, parent_id, user_id, summary, COALESCE(summarized_turns, 0),
+                        COALESCE(total_cost, 0.0)
+                 FROM conversations WHERE id = ?1 AND context_type = ?2",
+                params![id, THREAD_CONTEXT_TYPE],
+                |row| {
+                    Ok((
+                        row.get::<_, Option<String>>(0)?,
+                        row.get::<_, Option<String>>(1)?,
+                        row.get::<_, Option<String>>(2)?,
+                        row.get::<_, Option<String>>(3)?,
+                        row.get::<_, i64>(4)? as usize,
+                        row.get::<_, f64>(5)?,
+                    ))
+                },
+            )
+            .optional()?
+            .ok_or_else(|| anyhow!("No thread {}", id))?;
+
+        let messages: Vec<(String, String, String, Option<String>)> = conn
+            .prepare(
+                "SELECT id, role, content, model_used FROM messages
+                 WHERE conversation_id = ?1
+                 ORDER BY timestamp, rowid",
+            )?
+            .query_map(params![id], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+            })?
+            .collect::<Result<_, _>>()?;
+
+        let mut turns = Vec::new();
+        let mut question: Option<(String, String)> = None;
+        for (message_id, role, content, model) in messages {
+            match role.as_str() {
+                "user" => question = Some((message_id, content)),
+                "assistant" => {
+                    if let Some((question_id, question)) = question.take() {
+                        turns.push(Turn {
+                            question,
+                            answer: content,
+                            model,
+                            cost: 0.0,
+                            message_ids: (question_id, message_id),
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Ok(Thread {
+            id: id.to_string(),
+            title,
+            parent_id,
+            user_id,
+            summarized_turns: summarized_turns.min(turns.len()),
+            summary,
+            turns,
+            total_cost,
+            persisted: true,
+        })
+    }
+
+    /// The thread with exactly this id, or a new empty one that will take it
+    ///
+    /// With a `user_id`, only threads owned by that user are returned (threads
+    /// without an owner are refused too) and a new thread is owned by them.
+    pub fn open(&self, id: &str, user_id: Option<&str>) -> Result<Thread> {
+        let conn = self.database.get_connection()?;
+        let exists = conn
+            .query_row(
+                "SELECT 1 FROM conversations WHERE id = ?1 AND context_type = ?2",
+                params![id, THREAD_CONTEXT_TYPE],
+                |_| Ok(()),
+            )
+            .optional()?
+            .is_some();
+        if !exists {
+            let mut thread = Thread::with_id(id);
+            thread.user_id = user_id.map(str::to_string);
+            return Ok(thread);
+        }
+
+        let thread = self.load_exact(id)?;
+        if user_id.is_some() && thread.user_id.as_deref() != user_id {
+            bail!("Thread {} belongs to another user", thread.short_id());
+        }
+        Ok(thread)
+    }
+
+    /// The most recently updated thread, if any
+    pub fn latest(&self) -> Result<Option<Thread>> {
+        let conn = self.database.get_connection()?;
+        let id: Option<String> = conn
+            .query_row(
+                "SELECT id FROM conversations WHERE context_type = ?1
+                 ORDER BY updated_at DESC LIMIT 1",
+                params![THREAD_CONTEXT_TYPE],
+                |row| row.get(0),
+            )
+            .optional()?;
+        id.map(|id| self.load(&id)).transpose()
+    }
+
+    /// Recent threads, newest first, optionally only those of one user
+    pub fn list(&self, user_id: Option<&str>, limit: usize) -> Result<Vec<ThreadSummary>> {
+        let conn = self.database.get_connection()?;
+        let threads = conn
+            .prepare(
+                "SELECT c.id, COALESCE(c.title, ''), c.parent_id, COALESCE(c.total_cost, 0.0),
+                        c.updated_at,
+                        (SELECT COUNT(*) FROM messages m
+                         WHERE m.conversation_id = c.id AND m.role = 'user')
+                 FROM conversations c
+                 WHERE c.context_type = ?1 AND (?3 IS NULL OR c.user_id = ?3)
+                 ORDER BY c.updated_at DESC
+                 LIMIT ?2",
+            )?
+            .query_map(params![THREAD_CONTEXT_TYPE, limit as i64, user_id], |row| {
+                Ok(ThreadSummary {
+                    id: row.get(0)?,
+                    title: row.get(1)?,
+                    parent_id: row.get(2)?,
+                    total_cost: row.get(3)?,
+                    updated_at: row.get(4)?,
+                    turns: row.get::<_, i64>(5)? as usize,
+                })
+            })?
+            .collect::<Result<_, _>>()?;
+        Ok(threads)
+    }
+
+    /// Record a completed turn, creating the thread row on the first one
+    pub fn append_turn(
+        &self,
+        thread: &mut Thread,
+        question: &str,
+        answer: &str,
+        model: Option<&str>,
+        cost: f64,
+    ) -> Result<()> {
+        let mut conn = self.database.get_connection()?;
+        let tx = conn.transaction()?;
+        let now = current_timestamp();
+
+        if !thread.persisted {
+            // Conversations recorded before threads existed may already use the id
+            let title: String = question.chars().take(TITLE_LENGTH).collect();
+            tx.execute(
+                "INSERT INTO conversations
+                    (id, user_id, title, context_type, parent_id, total_cost, created_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, 0.0, ?6, ?6)
+                 ON CONFLICT(id) DO UPDATE SET
+                    user_id = COALESCE(excluded.user_id, conversations.user_id),
+                    title = COALESCE(conversations.title, excluded.title),
+                    context_type = excluded.context_type",
+                params![
+                    thread.id,
+                    thread.user_id,
+                    title,
+                    THREAD_CONTEXT_TYPE,
+                    thread.parent_id,
+                    now
+                ],
+            )?;
+            thread.title = Some(title);
+        }
+
+        let message_ids = (generate_id(), generate_id());
+        for (message_id, role, content, model) in [
+            (&message_ids.0, "user", question, None),
+            (&message_ids.1, "assistant", answer, model),
+        ] {
+            tx.execute(
+                "INSERT INTO messages (id, conversation_id, role, content, stage, model_used, timestamp)
+                 VALUES (?1, ?2, ?3, ?4, NULL, ?5, ?6)",
+                params![message_id, thread.id, role, content, model, now],
+            )?;
+        }
+        tx.execute(
+            "UPDATE conversations
+             SET total_cost = COALESCE(total_cost, 0.0) + ?2, updated_at = ?3
+             WHERE id = ?1",
+            params![thread.id, cost, now],
+        )?;
+        tx.commit()?;
+
+        thread.persisted = true;
+        thread.total_cost += cost;
+        thread.turns.push(Turn {
+            question: question.to_string(),
+            answer: answer.to_string(),
+            model: model.map(str::to_string),
+            cost,
+            message_ids,
+        });
+        Ok(())
+    }
+
+    /// Drop the last turn from the thread; its cost stays spent
+    pub fn remove_last_turn(&self, thread: &mut Thread) -> Result<Option<Turn>> {
+        let Some(turn) = thread.turns.pop() else {
+            return Ok(None);
+        };
+        let conn = self.database.get_connection()?;
+        conn.execute(
+            "DELETE FROM messages WHERE id IN (?1, ?2)",
+            params![turn.message_ids.0, turn.message_ids.1],
+        )?;
+
+        // A summary that covered the removed turn no longer matches the thread
+        if thread.summarized_turns > thread.turns.len() {
+            thread.summary = None;
+            thread.summarized_turns = 0;
+        }
+        conn.execute(
+            "UPDATE conversations SET summary = ?2, summarized_turns = ?3, updated_at = ?4
+             WHERE id = ?1",
+            params![
+                thread.id,
+                thread.summary,
+                thread.summarized_turns as i64,
+                current_timestamp()
+            ],
+        )?;
+        Ok(Some(turn))
+    }
+
+    /// Start a new thread from the first `turns` turns of another one
+    ///
+    /// The turns are copied, so the two threads diverge independently. Without
+    /// `turns` the whole thread is copied.
+    pub fn branch(&self, id: &str, turns: Option<usize>) -> Result<Thread> {
+        let source = self.load(id)?;
+        let turns = turns.unwrap_or(source.turns.len());
+        if turns == 0 || turns > source.turns.len() {
+            bail!(
+                "Cannot branch after turn {}: thread {} has {} turns",
+                turns,
+                source.short_id(),
+                source.turns.len()
+            );
+        }
+
+        let mut branch = Thread::new();
+        branch.title = source.title.clone();
+        branch.parent_id = Some(source.id.clone());
+        branch.user_id = source.user_id.clone();
+        if source.summarized_turns <= turns {
+            branch.summary = source.summary.clone();
+            branch.summarized_turns = source.summarized_turns;
+        }
+
+        let mut conn = self.database.get_connection()?;
+        let tx = conn.transaction()?;
+        let now = current_timestamp();
+        tx.execute(
+            "INSERT INTO conversations
+                (id, user_id, title, context_type, parent_id, summary, summarized_turns,
+                 total_cost, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, 0.0, ?8, ?8)",
+            params![
+                branch.id,
+                branch.user_id,
+                branch.title,
+                THREAD_CONTEXT_TYPE,
+                branch.parent_id,
+                branch.summary,
+                branch.summarized_turns as i64,
+                now
+            ],
+        )?;
+        for turn in &source.turns[..turns] {
+            let message_ids = (generate_id(), generate_id());
+            for (message_id, role, content, model) in [
+                (&message_ids.0, "user", &turn.question, None),
+                (
+                    &message_ids.1,
+                    "assistant",
+                    &turn.answer,
+                    turn.model.as_ref(),
+                ),
+            ] {
+                tx.execute(
+                    "INSERT INTO messages (id, conversation_id, role, content, stage, model_used, timestamp)
+                     VALUES (?1, ?2, ?3, ?4, NULL, ?5, ?6)",
+                    params![message_id, branch.id, role, content, model, now],
+                )?;
+            }
+            branch.turns.push(Turn {
+                cost: 0.0,
+                message_ids,
+                ..turn.clone()
+            });
+        }
+        tx.commit()?;
+
+        branch.persisted = true;
+        Ok(branch)
+    }
+
+    /// Store `thread` as it is, replacing the turns of any thread with its id
+    ///
+    /// Used for threads received from other installations; the owner and
+    /// cost already recorded locally are kept.
+    pub fn replace(&self, thread: &mut Thread) -> Result<()> {
+        let mut conn = self.database.get_connection()?;
+        let tx = conn.transaction()?;
+        let now = current_timestamp();
+        tx.execute(
+            "INSERT INTO conversations
+                (id, user_id, title, context_type, parent_id, summary, summarized_turns,
+                 total_cost, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, 0.0, ?8, ?8)
+             ON CONFLICT(id) DO UPDATE SET
+                title = excluded.title,
+                context_type = excluded.context_type,
+                parent_id = excluded.parent_id,
+                summary = excluded.summary,
+                summarized_turns = excluded.summarized_turns,
+                updated_at = excluded.updated_at",
+            params![
+                thread.id,
+                thread.user_id,
+                thread.title,
+                THREAD_CONTEXT_TYPE,
+                thread.parent_id,
+                thread.summary,
+                thread.summarized_turns.min(thread.turns.len()) as i64,
+                now
+            ],
+        )?;
+        tx.execute(
+            "DELETE FROM messages WHERE conversation_id = ?1",
+            params![thread.id],
+        )?;
+        for turn in &mut thread.turns {
+            let message_ids = (generate_id(), generate_id());
+            for (message_id, role, content, model) in [
+                (&message_ids.0, "user", &turn.question, None),
+                (
+                    &message_ids.1,
+                    "assistant",
+                    &turn.answer,
+                    turn.model.as_ref(),
+                ),
+            ] {
+                tx.execute(
+                    "INSERT INTO messages (id, conversation_id, role, content, stage, model_used, timestamp)
+                     VALUES (?1, ?2, ?3, ?4, NULL, ?5, ?6)",
+                    params![message_id, thread.id, role, content, model, now],
+                )?;
+            }
+            turn.message_ids = message_ids;
+        }
+        tx.commit()?;
+
+        thread.persisted = true;
+        Ok(())
+    }
+
+    /// Delete a thread and its messages; branches of it are kept
+    pub fn delete(&self, id: &str) -> Result<String> {
+        let id = self.resolve(id)?;
+        let mut conn = self.database.get_connection()?;
+        let tx = conn.transaction()?;
+        tx.execute(
+            "DELETE FROM messages WHERE conversation_id = ?1",
+            params![id],
+        )?;
+        tx.execute("DELETE FROM conversations WHERE id = ?1", params![id])?;
+        tx.commit()?;
+        Ok(id)
+    }
+
+    /// Chat messages replaying `thread` in at most `budget` tokens
+    ///
+    /// When the turns not yet summarized overflow the budget, the oldest of them
+    /// are folded into the rolling summary, which is stored so later requests
+    /// only summarize what is new. The summary gets at most a third of the budget.
+    pub async fn prepare_history(
+        &self,
+        thread: &mut Thread,
+        counter: &TokenCounter,
+        budget: usize,
+        summarizer: &Summarizer,
+    ) -> Result<Vec<Message>> {
+        let pending = &thread.turns[thread.summarized_turns..];
+        let pending_tokens: Vec<usize> = pending
+            .iter()
+            .map(|turn| turn_tokens(counter, turn))
+            .collect();
+        let summary_tokens = thread.summary.as_deref().map_or(0, |summary| {
+            counter.count(summary) + MESSAGE_OVERHEAD_TOKENS
+        });
+        if summary_tokens + pending_tokens.iter().sum::<usize>() <= budget {
+            return Ok(thread.history_messages());
+        }
+
+        let summary_limit = budget / 3;
+        let mut kept = 0;
+        let mut used = 0;
+        for tokens in pending_tokens.iter().rev() {
+            if used + tokens > budget - summary_limit {
+                break;
+            }
+            used += tokens;
+            kept += 1;
+        }
+        let fold_to = thread.turns.len() - kept;
+
+        let summary = summarizer
+            .summarize(
+                thread.summary.as_deref(),
+                &thread.turns[thread.summarized_turns..fold_to],
+                summary_limit,
+                counter,
+            )
+            .await;
+        tracing::info!(
+            "Summarized turns {}..{} of thread {}",
+            thread.summarized_turns + 1,
+            fold_to,
+            thread.short_id()
+        );
+        self.save_summary(thread, summary, fold_to)?;
+        Ok(thread.history_messages())
+    }
+
+    fn save_summary(&self, thread: &mut Thread, summary: String, turns: usize) -> Result<()> {
+        if thread.persisted {
+            let conn = self.database.get_connection()?;
+            conn.execute(
+                "UPDATE conversations SET summary = ?2, summarized_turns = ?3 WHERE id = ?1",
+                params![thread.id, summary, turns as i64],
+            )?;
+        }
+        thread.summary = Some(summary);
+        thread.summarized_turns = turns;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consensus::context_budget::TokenizerFamily;
+    use crate::core::database::DatabaseConfig;
+    use tempfile::TempDir;
+
+    async fn test_store() -> Result<(ThreadStore, TempDir)> {
+        let temp_dir = TempDir::new()?;
+        let config = DatabaseConfig {
+            path: temp_dir.path().join("threads.db"),
+            ..DatabaseConfig::default()
+        };
+        let db = Arc::new(DatabaseManager::new(config).await?);
+        Ok((ThreadStore::new(db)?, temp_dir))
+    }
+
+    #[tokio::test]
+    async fn test_thread_round_trip() -> Result<()> {
+        let (store, _dir) = test_store().await?;
+        assert!(store.latest()?.is_none());
+
+        let mut thread = Thread::new();
+        store.append_turn(&mut thread, "What is Rust?", "A language.", Some("m"), 0.01)?;
+        store.append_turn(&mut thread, "Is it fast?", "Yes.", Some("m"), 0.02)?;
+
+        let loaded = store.load(thread.short_id())?;
+        assert_eq!(loaded.id, thread.id);
+        assert_eq!(loaded.title.as_deref(), Some("What is Rust?"));
+        assert_eq!(loaded.turns.len(), 2);
+        assert_eq!(loaded.turns[1].question, "Is it fast?");
+        assert!((loaded.total_cost - 0.03).abs() < 1e-9);
+        assert_eq!(loaded.history_messages().len(), 4);
+
+        let summaries = store.list(None, 10)?;
+        assert_eq!(summaries.len(), 1);
+        assert_eq!(summaries[0].turns, 2);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_undo_and_latest() -> Result<()> {
+        let (store, _dir) = test_store().await?;
+        let mut first = Thread::new();
+        store.append_turn(&mut first, "one", "1", None, 0.0)?;
+        let mut second = Thread::new();
+        store.append_turn(&mut second, "two", "2", None, 0.0)?;
+        store.append_turn(&mut second, "three", "3", None, 0.0)?;
+
+        let undone = store.remove_last_turn(&mut second)?.unwrap();
+        assert_eq!(undone.question, "three");
+
+        let latest = store.latest()?.unwrap();
+        assert_eq!(latest.id, second.id);
+        assert_eq!(latest.turns.len(), 1);
+        assert!(store.load("does-not-exist").is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_branch_and_delete() -> Result<()> {
+        let (store, _dir) = test_store().await?;
+        let mut thread = store.open("client-chosen-id", Some("alice"))?;
+        for (question, answer) in [("one", "1"), ("two", "2"), ("three", "3")] {
+            store.append_turn(&mut thread, question, answer, None, 0.0)?;
+        }
+        assert_eq!(
+            store.open("client-chosen-id", Some("alice"))?.turns.len(),
+            3
+        );
+        assert!(store.open("client-chosen-id", Some("bob")).is_err());
+        assert!(store.list(Some("bob"), 10)?.is_empty());
+        assert!(store.load_exact("client-chosen").is_err());
+
+        let mut unowned = store.open("unowned", None)?;
+        store.append_turn(&mut unowned, "q", "a", None, 0.0)?;
+        assert!(store.open("unowned", Some("bob")).is_err());
+        assert_eq!(store.open("unowned", None)?.turns.len(), 1);
+
+        let mut branch = store.branch(&thread.id, Some(2))?;
+        assert_eq!(branch.parent_id.as_deref(), Some("client-chosen-id"));
+        assert_eq!(branch.user_id.as_deref(), Some("alice"));
+        store.append_turn(&mut branch, "four", "4", None, 0.0)?;
+        let questions: Vec<String> = store
+            .load(&branch.id)?
+            .turns
+            .into_iter()
+            .map(|turn| turn.question)
+            .collect();
+        assert_eq!(questions, ["one", "two", "four"]);
+        assert!(store.branch(&thread.id, Some(4)).is_err());
+
+        store.delete(&thread.id)?;
+        assert!(store.load(&thread.id).is_err());
+        assert_eq!(store.load(&branch.id)?.turns.len(), 3);
+        assert_eq!(store.list(None, 10)?.len(), 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_replace_overwrites_turns() -> Result<()> {
+        let (store, _dir) = test_store().await?;
+        let mut thread = Thread::new();
+        store.append_turn(&mut thread, "one", "1", None, 0.5)?;
+        store.append_turn(&mut thread, "two", "2", None, 0.0)?;
+
+        let mut received = Thread::with_id(thread.id.clone());
+        received.title = Some("Shared".to_string());
+        received.turns = vec![
+            Turn::new("one", "1", None),
+            Turn::new("three", "3", Some("m".to_string())),
+        ];
+        store.replace(&mut received)?;
+
+        let loaded = store.load(&thread.id)?;
+        assert_eq!(loaded.title.as_deref(), Some("Shared"));
+        let questions: Vec<&str> = loaded.turns.iter().map(|t| t.question.as_str()).collect();
+        assert_eq!(questions, ["one", "three"]);
+        assert_eq!(loaded.turns[1].model.as_deref(), Some("m"));
+        assert!((loaded.total_cost - 0.5).abs() < 1e-9);
+
+        store.remove_last_turn(&mut received)?;
+        assert_eq!(store.load(&thread.id)?.turns.len(), 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_history_is_summarized_to_fit() -> Result<()> {
+        let (store, _dir) = test_store().await?;
+        let counter = TokenCounter::estimating(TokenizerFamily::Generic);
+        let mut thread = Thread::new();
+        for i in 0..10 {
+            let answer = format!("Answer number {}. {}", i, "More detail here. ".repeat(40));
+            store.append_turn(&mut thread, &format!("Question {}", i), &answer, None, 0.0)?;
+        }
+
+        let budget = 1_200;
+        let messages = store
+            .prepare_history(&mut thread, &counter, budget, &Summarizer::offline())
+            .await?;
+        let used: usize = messages.iter().map(|m| counter.count(&m.content)).sum();
+        assert!(used <= budget);
+        assert_eq!(messages[0].role, "system");
+        assert!(thread.summarized_turns > 0 && thread.summarized_turns < 10);
+        assert_eq!(messages.last().unwrap().role, "assistant");
+        assert!(messages
+            .last()
+            .unwrap()
+            .content
+            .starts_with("Answer number 9."));
+
+        // The summary is stored, so a reload replays the same history
+        let reloaded = store.load(&thread.id)?;
+        assert_eq!(reloaded.summarized_turns, thread.summarized_turns);
+        assert_eq!(reloaded.history_messages().len(), messages.len());
+        Ok(())
+    }
+}
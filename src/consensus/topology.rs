@@ -0,0 +1,659 @@
+// Pipeline Topology - TOML-defined stage graphs for the consensus pipeline
+// Replaces the fixed Generator → Refiner → Validator → Curator flow when configured
+
+use crate::consensus::fanout::FanOutDefinition;
+use crate::consensus::stages::ConsensusStage;
+use crate::consensus::types::{Message, Stage, StagePrompts};
+use crate::core::config::get_hive_config_dir;
+use anyhow::{anyhow, bail, Context, Result};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// Name of the built-in four-stage pipeline
+pub const DEFAULT_PIPELINE: &str = "default";
+
+/// A consensus pipeline: stages run in order, each reading the outputs of the
+/// earlier stages it names in `inputs`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PipelineDefinition {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(rename = "stage")]
+    pub stages: Vec<StageDefinition>,
+}
+
+/// One stage of a pipeline definition
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StageDefinition {
+    /// Unique id, referenced by `inputs`, `skip_if` and `{{output.<id>}}`
+    pub id: String,
+    /// Built-in role the stage plays; drives progress display, hooks and defaults
+    pub role: Stage,
+    /// Model to call; defaults to the profile's model for `role`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// System prompt template; defaults to the built-in prompts for `role`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub system_prompt: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f64>,
+    /// Earlier stages whose outputs this stage sees; defaults to the last stage that ran
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub inputs: Option<Vec<String>>,
+    /// Whether the stage receives repository, memory and temporal context
+    #[serde(default = "default_include_context")]
+    pub include_context: bool,
+    /// Skip the stage when any of these conditions holds
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub skip_if: Vec<SkipCondition>,
+    /// Run the stage on several models concurrently and keep or merge the best answer
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fan_out: Option<FanOutDefinition>,
+}
+
+fn default_include_context() -> bool {
+    true
+}
+
+/// Condition under which a stage is skipped
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "when", rename_all = "snake_case")]
+pub enum SkipCondition {
+    /// The question has fewer than `chars` characters
+    QuestionShorterThan { chars: usize },
+    /// The question matches a regular expression
+    QuestionMatches { pattern: String },
+    /// An earlier stage's output contains `text`, ignoring case
+    OutputContains { stage: String, text: String },
+}
+
+impl SkipCondition {
+    fn holds(&self, question: &str, outputs: &HashMap<String, String>) -> Result<bool> {
+        Ok(match self {
+            SkipCondition::QuestionShorterThan { chars } => question.chars().count() < *chars,
+            SkipCondition::QuestionMatches { pattern } => Regex::new(pattern)?.is_match(question),
+            SkipCondition::OutputContains { stage, text } => outputs
+                .get(stage)
+                .is_some_and(|output| output.to_lowercase().contains(&text.to_lowercase())),
+        })
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            SkipCondition::QuestionShorterThan { chars } => {
+                format!("question is shorter than {} characters", chars)
+            }
+            SkipCondition::QuestionMatches { pattern } => {
+                format!("question matches /{}/", pattern)
+            }
+            SkipCondition::OutputContains { stage, text } => {
+                format!("{} output contains \"{}\"", stage, text)
+            }
+        }
+    }
+}
+
+impl PipelineDefinition {
+    /// Parse and validate a TOML pipeline definition
+    pub fn from_toml(source: &str) -> Result<Self> {
+        let definition: Self = toml::from_str(source).context("Invalid pipeline definition")?;
+        definition.validate()?;
+        Ok(definition)
+    }
+
+    pub fn to_toml(&self) -> Result<String> {
+        Ok(toml::to_string_pretty(self)?)
+    }
+
+    /// The built-in Generator → Refiner → Validator → Curator pipeline
+    pub fn builtin() -> Self {
+        let stages = [
+            Stage::Generator,
+            Stage::Refiner,
+            Stage::Validator,
+            Stage::Curator,
+        ]
+        .into_iter()
+        .map(|role| StageDefinition {
+            id: role.as_str().to_string(),
+            role,
+            model: None,
+            system_prompt: None,
+            temperature: None,
+            inputs: None,
+            include_context: true,
+            skip_if: Vec::new(),
+            fan_out: None,
+        })
+        .collect();
+
+        Self {
+            name: DEFAULT_PIPELINE.to_string(),
+            description: Some("Generator → Refiner → Validator → Curator".to_string()),
+            stages,
+        }
+    }
+
+    /// Check ids are unique, references point at earlier stages and
+    /// patterns and temperatures are valid
+    pub fn validate(&self) -> Result<()> {
+        validate_name(&self.name)?;
+        if self.stages.is_empty() {
+            bail!("Pipeline '{}' has no stages", self.name);
+        }
+
+        let mut earlier: HashSet<&str> = HashSet::new();
+        for stage in &self.stages {
+            if stage.id.trim().is_empty() {
+                bail!("Pipeline '{}' has a stage without an id", self.name);
+            }
+            if earlier.contains(stage.id.as_str()) {
+                bail!("Duplicate stage id '{}'", stage.id);
+            }
+
+            let references = stage
+                .inputs
+                .iter()
+                .flatten()
+                .chain(
+                    stage
+                        .skip_if
+                        .iter()
+                        .filter_map(|condition| match condition {
+                            SkipCondition::OutputContains { stage: target, .. } => Some(target),
+                            _ => None,
+                        }),
+                );
+            for reference in references {
+                if !earlier.contains(reference.as_str()) {
+                    bail!(
+                        "Stage '{}' refers to '{}', which is not an earlier stage",
+                        stage.id,
+                        reference
+                    );
+                }
+            }
+
+            for condition in &stage.skip_if {
+                if let SkipCondition::QuestionMatches { pattern } = condition {
+                    Regex::new(pattern)
+                        .with_context(|| format!("Invalid skip pattern in stage '{}'", stage.id))?;
+                }
+            }
+            if let Some(temperature) = stage.temperature {
+                if !(0.0..=2.0).contains(&temperature) {
+                    bail!(
+                        "Stage '{}' temperature {} is outside 0.0-2.0",
+                        stage.id,
+                        temperature
+                    );
+                }
+            }
+
+            if let Some(fan_out) = &stage.fan_out {
+                fan_out
+                    .validate()
+                    .with_context(|| format!("Invalid fan-out in stage '{}'", stage.id))?;
+            }
+
+            earlier.insert(stage.id.as_str());
+        }
+
+        Ok(())
+    }
+
+    /// Fan out every stage playing `role`
+    pub fn with_fan_out(mut self, role: Stage, fan_out: FanOutDefinition) -> Self {
+        for stage in self.stages.iter_mut().filter(|stage| stage.role == role) {
+            stage.fan_out = Some(fan_out.clone());
+        }
+        self
+    }
+}
+
+impl StageDefinition {
+    /// Ids of the outputs this stage reads, given the last stage that ran
+    pub fn input_ids<'a>(&'a self, last_ran: Option<&'a str>) -> Vec<&'a str> {
+        match &self.inputs {
+            Some(inputs) => inputs.iter().map(String::as_str).collect(),
+            None => last_ran.into_iter().collect(),
+        }
+    }
+
+    /// Why the stage should be skipped, if any condition holds
+    pub fn skip_reason(
+        &self,
+        question: &str,
+        outputs: &HashMap<String, String>,
+    ) -> Result<Option<String>> {
+        for condition in &self.skip_if {
+            if condition.holds(question, outputs)? {
+                return Ok(Some(condition.describe()));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Render the system prompt template, if one is set. Supports
+    /// `{{question}}` and `{{output.<stage id>}}` placeholders.
+    pub fn render_system_prompt(
+        &self,
+        question: &str,
+        outputs: &HashMap<String, String>,
+    ) -> Option<String> {
+        let template = self.system_prompt.as_ref()?;
+        let placeholder = Regex::new(r"\{\{\s*([A-Za-z0-9_.\-]+)\s*\}\}").expect("valid regex");
+        let rendered = placeholder.replace_all(template, |captures: &regex::Captures| {
+            let key = &captures[1];
+            match key.strip_prefix("output.") {
+                Some(id) => outputs.get(id).cloned().unwrap_or_default(),
+                None if key == "question" => question.to_string(),
+                None => captures[0].to_string(),
+            }
+        });
+        Some(rendered.into_owned())
+    }
+}
+
+/// Join the selected outputs into the text a stage receives as its previous answer
+pub fn collect_inputs(ids: &[&str], outputs: &HashMap<String, String>) -> Option<String> {
+    let available: Vec<(&str, &String)> = ids
+        .iter()
+        .filter_map(|id| outputs.get(*id).map(|output| (*id, output)))
+        .collect();
+
+    match available.as_slice() {
+        [] => None,
+        [(_, output)] => Some((*output).clone()),
+        many => Some(
+            many.iter()
+                .map(|(id, output)| format!("### Output from {}\n{}", id, output))
+                .collect::<Vec<_>>()
+                .join("\n\n"),
+        ),
+    }
+}
+
+/// Stage whose system prompt comes from a pipeline definition
+pub struct TemplateStage {
+    role: Stage,
+    system_prompt: String,
+}
+
+impl TemplateStage {
+    pub fn new(role: Stage, system_prompt: String) -> Self {
+        Self {
+            role,
+            system_prompt,
+        }
+    }
+}
+
+impl ConsensusStage for TemplateStage {
+    fn stage(&self) -> Stage {
+        self.role
+    }
+
+    fn system_prompt(&self) -> &'static str {
+        match self.role {
+            Stage::Generator => StagePrompts::generator_system(),
+            Stage::Refiner => StagePrompts::refiner_system(),
+            Stage::Validator => StagePrompts::validator_system(),
+            Stage::Curator => StagePrompts::curator_system(),
+        }
+    }
+
+    fn build_messages(
+        &self,
+        question: &str,
+        previous_answer: Option<&str>,
+        context: Option<&str>,
+    ) -> Result<Vec<Message>> {
+        let mut messages = vec![Message {
+            role: "system".to_string(),
+            content: self.system_prompt.clone(),
+        }];
+
+        if let Some(ctx) = context {
+            messages.push(Message {
+                role: "system".to_string(),
+                content: format!("CONTEXT:\n{}", ctx),
+            });
+        }
+
+        let content = match previous_answer {
+            Some(previous) => format!(
+                "QUESTION:\n{}\n\nEARLIER STAGE OUTPUT:\n{}",
+                question, previous
+            ),
+            None => question.to_string(),
+        };
+        messages.push(Message {
+            role: "user".to_string(),
+            content,
+        });
+
+        Ok(messages)
+    }
+}
+
+/// Pipeline definitions stored as `<name>.toml` files next to the profiles
+pub struct PipelineStore {
+    dir: PathBuf,
+}
+
+impl PipelineStore {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Store under `~/.hive/pipelines`
+    pub fn default_location() -> Self {
+        Self::new(get_hive_config_dir().join("pipelines"))
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Load a pipeline by name, or from a path to a `.toml` file
+    pub fn load(&self, name: &str) -> Result<PipelineDefinition> {
+        let path = Path::new(name);
+        let path = if path.extension().is_some_and(|ext| ext == "toml") && path.is_file() {
+            path.to_path_buf()
+        } else {
+            validate_name(name)?;
+            let stored = self.dir.join(format!("{}.toml", name));
+            if !stored.is_file() {
+                if name == DEFAULT_PIPELINE {
+                    return Ok(PipelineDefinition::builtin());
+                }
+                return Err(anyhow!(
+                    "Pipeline '{}' not found in {}",
+                    name,
+                    self.dir.display()
+                ));
+            }
+            stored
+        };
+
+        let source = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        PipelineDefinition::from_toml(&source).with_context(|| format!("In {}", path.display()))
+    }
+
+    /// All stored pipelines plus the built-in one, with load errors per file
+    pub fn list(&self) -> Result<Vec<(String, Result<PipelineDefinition>)>> {
+        let mut pipelines = Vec::new();
+        if self.dir.is_dir() {
+            for entry in std::fs::read_dir(&self.dir)? {
+                let path = entry?.path();
+                if path.extension().is_some_and(|ext| ext == "toml") {
+                    if let Some(name) = path.file_stem().and_then(|s| s.to_str()) {
+                        pipelines.push((name.to_string(), self.load(name)));
+                    }
+                }
+            }
+        }
+        if !pipelines.iter().any(|(name, _)| name == DEFAULT_PIPELINE) {
+            pipelines.push((
+                DEFAULT_PIPELINE.to_string(),
+                Ok(PipelineDefinition::builtin()),
+            ));
+        }
+        pipelines.sort_by(|a, b| a.0.cmp(&b.0));
+        Ok(pipelines)
+    }
+
+    /// Validate and write a definition as `<name>.toml`
+    pub fn save(&self, definition: &PipelineDefinition) -> Result<PathBuf> {
+        definition.validate()?;
+        std::fs::create_dir_all(&self.dir)?;
+        let path = self.dir.join(format!("{}.toml", definition.name));
+        std::fs::write(&path, definition.to_toml()?)?;
+        Ok(path)
+    }
+}
+
+/// Names become file names under the store directory, so they may not
+/// contain path separators or `..`
+fn validate_name(name: &str) -> Result<()> {
+    if name.trim().is_empty() {
+        bail!("Pipeline name must not be empty");
+    }
+    if name.contains(['/', '\\']) || name.contains("..") || name.starts_with('.') {
+        bail!(
+            "Pipeline name '{}' must not contain path separators or '..'",
+            name
+        );
+    }
+    Ok(())
+}
+
+/// Example pipeline definitions
+pub fn example_pipelines() -> Vec<(&'static str, &'static str)> {
+    vec![
+        (
+            "fast.toml",
+            r#"name = "fast"
+description = "Two-stage pipeline for quick answers"
+
+[[stage]]
+id = "draft"
+role = "generator"
+temperature = 0.7
+
+[[stage]]
+id = "final"
+role = "curator"
+temperature = 0.3
+system_prompt = """
+Polish the draft answer to the question below into a concise, correct final answer.
+Question: {{question}}
+"""
+"#,
+        ),
+        (
+            "security-review.toml",
+            r#"name = "security-review"
+description = "Six-stage pipeline with dedicated security and performance reviews"
+
+[[stage]]
+id = "generator"
+role = "generator"
+
+[[stage]]
+id = "refiner"
+role = "refiner"
+
+[[stage]]
+id = "security"
+role = "validator"
+temperature = 0.2
+inputs = ["refiner"]
+system_prompt = """
+You are a security reviewer. Inspect the proposed solution for injection, authentication,
+authorization, secrets handling and unsafe input handling issues. List each finding with
+severity and a fix. Reply with NO ISSUES if there are none.
+"""
+
+[[stage]]
+id = "performance"
+role = "validator"
+temperature = 0.2
+inputs = ["refiner"]
+skip_if = [{ when = "question_shorter_than", chars = 40 }]
+system_prompt = """
+You are a performance reviewer. Point out algorithmic, allocation and I/O problems in the
+proposed solution and suggest concrete improvements.
+"""
+
+[[stage]]
+id = "validator"
+role = "validator"
+inputs = ["refiner", "security", "performance"]
+
+[[stage]]
+id = "curator"
+role = "curator"
+inputs = ["refiner", "validator"]
+"#,
+        ),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consensus::fanout::MergeStrategy;
+    use tempfile::TempDir;
+
+    fn outputs(entries: &[(&str, &str)]) -> HashMap<String, String> {
+        entries
+            .iter()
+            .map(|(id, output)| (id.to_string(), output.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_examples_parse_and_validate() {
+        for (file, source) in example_pipelines() {
+            let definition = PipelineDefinition::from_toml(source)
+                .unwrap_or_else(|e| panic!("{} is invalid: {:#}", file, e));
+            assert_eq!(format!("{}.toml", definition.name), file);
+        }
+
+        let review = PipelineDefinition::from_toml(example_pipelines()[1].1).unwrap();
+        assert_eq!(review.stages.len(), 6);
+        assert_eq!(review.stages[2].role, Stage::Validator);
+        assert!(review.stages[0].include_context);
+    }
+
+    #[test]
+    fn test_validate_rejects_bad_references() {
+        let forward = r#"
+            name = "bad"
+            [[stage]]
+            id = "a"
+            role = "generator"
+            inputs = ["b"]
+            [[stage]]
+            id = "b"
+            role = "curator"
+        "#;
+        let err = PipelineDefinition::from_toml(forward).unwrap_err();
+        assert!(format!("{:#}", err).contains("not an earlier stage"));
+
+        let duplicate = r#"
+            name = "bad"
+            [[stage]]
+            id = "a"
+            role = "generator"
+            [[stage]]
+            id = "a"
+            role = "curator"
+        "#;
+        assert!(PipelineDefinition::from_toml(duplicate).is_err());
+    }
+
+    #[test]
+    fn test_inputs_skip_and_templates() {
+        let review = PipelineDefinition::from_toml(example_pipelines()[1].1).unwrap();
+        let done = outputs(&[("refiner", "refined"), ("security", "no issues found")]);
+
+        // Default inputs follow the last stage that ran; explicit inputs skip missing outputs
+        assert_eq!(
+            review.stages[1].input_ids(Some("generator")),
+            vec!["generator"]
+        );
+        let validator_inputs = review.stages[4].input_ids(Some("security"));
+        assert_eq!(
+            collect_inputs(&validator_inputs, &done).unwrap(),
+            "### Output from refiner\nrefined\n\n### Output from security\nno issues found"
+        );
+
+        let performance = &review.stages[3];
+        assert!(performance.skip_reason("short?", &done).unwrap().is_some());
+        assert!(performance
+            .skip_reason("How should I speed up this batch importer loop?", &done)
+            .unwrap()
+            .is_none());
+
+        let mut stage = review.stages[0].clone();
+        stage.system_prompt = Some("Q={{ question }} S={{output.security}} X={{other}}".into());
+        assert_eq!(
+            stage.render_system_prompt("why?", &done).unwrap(),
+            "Q=why? S=no issues found X={{other}}"
+        );
+        assert!(review.stages[0]
+            .render_system_prompt("why?", &done)
+            .is_none());
+    }
+
+    #[test]
+    fn test_fan_out() {
+        let source = r#"
+            name = "vote"
+            [[stage]]
+            id = "draft"
+            role = "generator"
+            [stage.fan_out]
+            models = ["a/one", "b/two", "c/three"]
+            merge = "synthesize"
+            [[stage]]
+            id = "final"
+            role = "curator"
+        "#;
+        let vote = PipelineDefinition::from_toml(source).unwrap();
+        let fan_out = vote.stages[0].fan_out.as_ref().unwrap();
+        assert_eq!(fan_out.candidate_count(), 3);
+        assert_eq!(fan_out.merge, MergeStrategy::Synthesize);
+        assert!(vote.stages[1].fan_out.is_none());
+
+        let single = source.replace(r#"["a/one", "b/two", "c/three"]"#, r#"["a/one"]"#);
+        let err = PipelineDefinition::from_toml(&single).unwrap_err();
+        assert!(format!("{:#}", err).contains("Invalid fan-out in stage 'draft'"));
+
+        let builtin = PipelineDefinition::builtin()
+            .with_fan_out(Stage::Generator, FanOutDefinition::with_count(4));
+        assert_eq!(
+            builtin.stages[0]
+                .fan_out
+                .as_ref()
+                .unwrap()
+                .candidate_count(),
+            4
+        );
+        assert!(builtin.stages[1..].iter().all(|s| s.fan_out.is_none()));
+        builtin.validate().unwrap();
+    }
+
+    #[test]
+    fn test_store_round_trip() {
+        let dir = TempDir::new().unwrap();
+        let store = PipelineStore::new(dir.path());
+
+        assert_eq!(
+            store.load(DEFAULT_PIPELINE).unwrap(),
+            PipelineDefinition::builtin()
+        );
+        assert!(store.load("missing").is_err());
+
+        let fast = PipelineDefinition::from_toml(example_pipelines()[0].1).unwrap();
+        let path = store.save(&fast).unwrap();
+        assert_eq!(store.load("fast").unwrap(), fast);
+        assert_eq!(store.load(path.to_str().unwrap()).unwrap(), fast);
+
+        let names: Vec<String> = store.list().unwrap().into_iter().map(|(n, _)| n).collect();
+        assert_eq!(names, vec!["default", "fast"]);
+
+        let mut escape = fast.clone();
+        escape.name = "../escape".to_string();
+        assert!(store.save(&escape).is_err());
+        assert!(store.load("../fast").is_err());
+        assert!(!dir.path().parent().unwrap().join("escape.toml").exists());
+    }
+}
@@ -1,6 +1,7 @@
 // Type definitions for consensus engine
 // Matches TypeScript interface definitions for compatibility
 
+use crate::consensus::context_budget::ContextReport;
 use crate::consensus::temporal::TemporalContext;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -121,6 +122,32 @@ pub struct StageAnalytics {
     #[serde(rename = "memoryUsage")]
     pub memory_usage: Option<u64>,
     pub features: AnalyticsFeatures,
+    /// Per-model results when the stage fanned out to several models
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub candidates: Vec<CandidateAnalytics>,
+    /// Token budget of the stage's context and what did not fit
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context: Option<ContextReport>,
+}
+
+/// One model's answer in a fanned-out stage
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CandidateAnalytics {
+    pub model: String,
+    pub duration: f64,
+    pub cost: f64,
+    #[serde(rename = "totalTokens")]
+    pub total_tokens: u32,
+    /// Mean agreement with the other candidates (0.0-1.0)
+    pub agreement: f64,
+    /// Claims contradicting verified repository facts
+    pub contradictions: usize,
+    /// Confidence score (0-100)
+    pub score: f32,
+    /// Whether this answer was taken as the stage output
+    pub selected: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
 }
 
 /// Analytics feature flags
@@ -212,6 +239,10 @@ pub struct ConsensusRequest {
     pub profile_override: Option<String>,
     pub max_tokens: Option<u32>,
     pub user_id: Option<String>,
+    /// Thread this request continues; its earlier turns are replayed and the
+    /// new turn is recorded in it. A new thread is created for an unknown id.
+    #[serde(default)]
+    pub conversation_id: Option<String>,
 }
 
 /// Stage-specific prompts
@@ -9,7 +9,8 @@ use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::{Path, PathBuf};
 use toml::Value as TomlValue;
-use walkdir::WalkDir;
+
+use crate::analysis::walker::RepositoryWalker;
 
 /// Verified facts about a repository
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -199,14 +200,12 @@ impl RepositoryVerifier {
         let mut file_extensions = std::collections::HashSet::new();
         let mut major_directories = std::collections::HashSet::new();
 
-        for entry in WalkDir::new(&self.root_path)
-            .into_iter()
-            .filter_map(|e| e.ok())
-            .filter(|e| e.file_type().is_file())
-        {
+        let files = RepositoryWalker::new(&self.root_path).walk_async().await?;
+
+        for file in files {
             total_files += 1;
 
-            let path = entry.path();
+            let path = file.path.as_path();
 
             // Track file extensions
             if let Some(ext) = path.extension().and_then(|s| s.to_str()) {
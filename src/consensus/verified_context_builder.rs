@@ -4,6 +4,7 @@
 //! only when the question is repository-related, preventing irrelevant context injection.
 
 use crate::ai_helpers::AIHelperEcosystem;
+use crate::consensus::context_budget::{ContextPriority, ContextSection};
 use crate::consensus::repository_context::RepositoryContextManager;
 use crate::consensus::temporal::TemporalContext;
 use crate::consensus::types::Stage;
@@ -192,6 +193,43 @@ impl VerifiedContextBuilder {
         repository_context: Option<Arc<RepositoryContextManager>>,
         ai_helpers: Option<Arc<AIHelperEcosystem>>,
     ) -> Result<String> {
+        let sections = self
+            .build_verified_stage_sections(
+                stage,
+                question,
+                semantic_context,
+                temporal_context,
+                memory_context,
+                repository_context,
+                ai_helpers,
+            )
+            .await?;
+        let full_context = sections
+            .into_iter()
+            .map(|section| section.content)
+            .collect::<Vec<_>>()
+            .join("\n\n");
+        tracing::info!(
+            "Built verified context for {:?} stage: {} characters",
+            stage,
+            full_context.len()
+        );
+
+        Ok(full_context)
+    }
+
+    /// Build the labelled, prioritized context sections for a stage, for a
+    /// `ContextBudget` to fit into the stage model's window
+    pub async fn build_verified_stage_sections(
+        &self,
+        stage: Stage,
+        question: &str,
+        semantic_context: Option<String>,
+        temporal_context: Option<TemporalContext>,
+        memory_context: Option<String>,
+        repository_context: Option<Arc<RepositoryContextManager>>,
+        ai_helpers: Option<Arc<AIHelperEcosystem>>,
+    ) -> Result<Vec<ContextSection>> {
         let mut contexts = Vec::new();
 
         // Check if this question is repository-related
@@ -201,15 +239,23 @@ impl VerifiedContextBuilder {
         if is_repo_related {
             if let Some(facts) = &self.repository_facts {
                 let verification_context = build_stage_context(facts, stage);
-                contexts.push(verification_context);
+                contexts.push(ContextSection::new(
+                    "repository_verification",
+                    ContextPriority::Critical,
+                    verification_context,
+                ));
                 tracing::info!(
                     "Question is repository-related, added verification context for {:?} stage",
                     stage
                 );
             } else {
                 tracing::warn!("Repository-related question but no facts available!");
-                contexts.push(format!(
-                    "\n⚠️  WARNING: This appears to be a repository-specific question, but no repository verification was performed.\n"
+                contexts.push(ContextSection::new(
+                    "repository_verification",
+                    ContextPriority::Critical,
+                    format!(
+                        "\n⚠️  WARNING: This appears to be a repository-specific question, but no repository verification was performed.\n"
+                    ),
                 ));
             }
         } else {
@@ -224,7 +270,11 @@ impl VerifiedContextBuilder {
             {
                 Ok(helper_context) => {
                     if !helper_context.is_empty() {
-                        contexts.push(format!("## 🤖 AI HELPER INSIGHTS\n{}", helper_context));
+                        contexts.push(ContextSection::new(
+                            "ai_helpers",
+                            ContextPriority::Normal,
+                            format!("## 🤖 AI HELPER INSIGHTS\n{}", helper_context),
+                        ));
                         tracing::debug!("Added AI helper context for {:?} stage", stage);
                     }
                 }
@@ -241,7 +291,11 @@ impl VerifiedContextBuilder {
         // 3. Memory context (previous curator results)
         if let Some(memory) = memory_context {
             if !memory.is_empty() {
-                contexts.push(format!("## 📚 MEMORY CONTEXT\n{}", memory));
+                contexts.push(ContextSection::new(
+                    "memory",
+                    ContextPriority::High,
+                    format!("## 📚 MEMORY CONTEXT\n{}", memory),
+                ));
                 tracing::debug!("Added memory context");
             }
         }
@@ -249,7 +303,11 @@ impl VerifiedContextBuilder {
         // 4. Semantic context (codebase search results)
         if let Some(semantic) = semantic_context {
             if !semantic.is_empty() {
-                contexts.push(format!("## 🔍 SEMANTIC SEARCH RESULTS\n{}", semantic));
+                contexts.push(ContextSection::new(
+                    "semantic",
+                    ContextPriority::Normal,
+                    format!("## 🔍 SEMANTIC SEARCH RESULTS\n{}", semantic),
+                ));
                 tracing::debug!("Added semantic context");
             }
         }
@@ -258,7 +316,11 @@ impl VerifiedContextBuilder {
         if let Some(temporal) = temporal_context {
             let temporal_str = self.format_temporal_context(temporal);
             if !temporal_str.is_empty() {
-                contexts.push(format!("## ⏰ TEMPORAL CONTEXT\n{}", temporal_str));
+                contexts.push(ContextSection::new(
+                    "temporal",
+                    ContextPriority::High,
+                    format!("## ⏰ TEMPORAL CONTEXT\n{}", temporal_str),
+                ));
                 tracing::debug!("Added temporal context");
             }
         }
@@ -268,7 +330,11 @@ impl VerifiedContextBuilder {
             if let Some(repo_ctx) = repository_context {
                 let repo_info = repo_ctx.get_context_for_prompts().await;
                 if !repo_info.is_empty() {
-                    contexts.push(format!("## 📁 REPOSITORY CONTEXT\n{}", repo_info));
+                    contexts.push(ContextSection::new(
+                        "repository",
+                        ContextPriority::Low,
+                        format!("## 📁 REPOSITORY CONTEXT\n{}", repo_info),
+                    ));
                     tracing::debug!("Added repository context");
                 }
             }
@@ -277,18 +343,15 @@ impl VerifiedContextBuilder {
         // 7. Stage-specific guidance
         let stage_guidance = self.get_stage_specific_guidance(stage);
         if !stage_guidance.is_empty() {
-            contexts.push(format!("## 🎯 STAGE GUIDANCE\n{}", stage_guidance));
+            contexts.push(ContextSection::new(
+                "stage_guidance",
+                ContextPriority::Critical,
+                format!("## 🎯 STAGE GUIDANCE\n{}", stage_guidance),
+            ));
             tracing::debug!("Added stage-specific guidance for {:?}", stage);
         }
 
-        let full_context = contexts.join("\n\n");
-        tracing::info!(
-            "Built verified context for {:?} stage: {} characters",
-            stage,
-            full_context.len()
-        );
-
-        Ok(full_context)
+        Ok(contexts)
     }
 
     /// Get AI helper context enhanced with repository facts
@@ -10,6 +10,7 @@ use tracing::{debug, info, warn};
 
 use crate::consensus::openrouter::OpenRouterClient;
 use crate::core::database::get_database;
+use crate::core::secrets::{migrate_plaintext, SecretStore};
 
 /// API key configuration stored in database
 #[derive(Debug, Clone)]
@@ -186,26 +187,20 @@ impl ApiKeyManager {
                         params![default_user_id, "default@hive.ai", "FREE"],
                     );
 
-                    // Save API key to configurations table
+                    // Seal the keys in the secret store; the configurations table only
+                    // records that they are set
+                    let store = SecretStore::open_default()?;
                     let tx = conn.transaction()?;
-                    tx.execute(
-                        "INSERT INTO configurations (key, value, encrypted, user_id)
-                         VALUES (?1, ?2, ?3, ?4)
-                         ON CONFLICT(key) DO UPDATE SET
-                         value = excluded.value,
-                         updated_at = CURRENT_TIMESTAMP",
-                        params!["openrouter_api_key", key, false, default_user_id],
-                    )?;
+                    Self::save_secret(&tx, &store, "openrouter_api_key", key, default_user_id)?;
 
                     if let Some(hive_key) = hive_key {
                         if !hive_key.is_empty() {
-                            tx.execute(
-                                "INSERT INTO configurations (key, value, encrypted, user_id)
-                                 VALUES (?1, ?2, ?3, ?4)
-                                 ON CONFLICT(key) DO UPDATE SET
-                                 value = excluded.value,
-                                 updated_at = CURRENT_TIMESTAMP",
-                                params!["hive_license_key", hive_key, false, default_user_id],
+                            Self::save_secret(
+                                &tx,
+                                &store,
+                                "hive_license_key",
+                                hive_key,
+                                default_user_id,
                             )?;
                         }
                     }
@@ -213,19 +208,18 @@ impl ApiKeyManager {
                     if let Some(anthropic_key) = anthropic_key {
                         if !anthropic_key.is_empty() {
                             Self::validate_anthropic_format(anthropic_key)?;
-                            tx.execute(
-                                "INSERT INTO configurations (key, value, encrypted, user_id)
-                                 VALUES (?1, ?2, ?3, ?4)
-                                 ON CONFLICT(key) DO UPDATE SET
-                                 value = excluded.value,
-                                 updated_at = CURRENT_TIMESTAMP",
-                                params!["anthropic_api_key", anthropic_key, false, default_user_id],
+                            Self::save_secret(
+                                &tx,
+                                &store,
+                                "anthropic_api_key",
+                                anthropic_key,
+                                default_user_id,
                             )?;
                         }
                     }
 
                     tx.commit()?;
-                    info!("Saved API keys to database configurations table");
+                    info!("Saved API keys to the secret store");
                 }
             }
         }
@@ -233,6 +227,29 @@ impl ApiKeyManager {
         Ok(())
     }
 
+    /// Store `value` in the secret store and mark its configurations row as encrypted
+    fn save_secret(
+        tx: &rusqlite::Transaction,
+        store: &SecretStore,
+        name: &str,
+        value: &str,
+        user_id: &str,
+    ) -> Result<()> {
+        store
+            .set(name, value)
+            .with_context(|| format!("Failed to store {} securely", name))?;
+        tx.execute(
+            "INSERT INTO configurations (key, value, encrypted, user_id)
+             VALUES (?1, '', 1, ?2)
+             ON CONFLICT(key) DO UPDATE SET
+             value = '',
+             encrypted = 1,
+             updated_at = CURRENT_TIMESTAMP",
+            params![name, user_id],
+        )?;
+        Ok(())
+    }
+
     /// Load API keys from database
     pub async fn load_from_database() -> Result<ApiKeyConfig> {
         // Try to load from database directly using rusqlite
@@ -250,35 +267,31 @@ impl ApiKeyManager {
                 ).unwrap_or(0);
 
                 if table_exists > 0 {
-                    // Load OpenRouter key from configurations table
-                    let openrouter_key: Option<String> = conn
-                        .query_row(
-                            "SELECT value FROM configurations WHERE key = ?1",
-                            params!["openrouter_api_key"],
-                            |row| row.get(0),
-                        )
-                        .ok()
-                        .filter(|k: &String| !k.is_empty());
-
-                    // Load Hive key from configurations table
-                    let hive_key: Option<String> = conn
-                        .query_row(
-                            "SELECT value FROM configurations WHERE key = ?1",
-                            params!["hive_license_key"],
-                            |row| row.get(0),
-                        )
-                        .ok()
-                        .filter(|k: &String| !k.is_empty());
-
-                    // Load Anthropic key from configurations table
-                    let anthropic_key: Option<String> = conn
-                        .query_row(
-                            "SELECT value FROM configurations WHERE key = ?1",
-                            params!["anthropic_api_key"],
-                            |row| row.get(0),
-                        )
-                        .ok()
-                        .filter(|k: &String| !k.is_empty());
+                    let store = SecretStore::open_default()?;
+                    if let Err(e) = migrate_plaintext(&conn, &store) {
+                        warn!("Failed to move plaintext API keys into the secret store: {}", e);
+                    }
+
+                    // Rows that could not be migrated still hold the plaintext key
+                    let load = |name: &str| -> Option<String> {
+                        let stored: Option<String> = conn
+                            .query_row(
+                                "SELECT value FROM configurations WHERE key = ?1",
+                                params![name],
+                                |row| row.get(0),
+                            )
+                            .ok();
+                        match stored.filter(|k| !k.is_empty()) {
+                            Some(plaintext) => Some(plaintext),
+                            None => store.get(name).unwrap_or_else(|e| {
+                                warn!("Failed to read {} from the secret store: {}", name, e);
+                                None
+                            }),
+                        }
+                    };
+                    let openrouter_key = load("openrouter_api_key");
+                    let hive_key = load("hive_license_key");
+                    let anthropic_key = load("anthropic_api_key");
 
                     if openrouter_key.is_some() || hive_key.is_some() || anthropic_key.is_some() {
                         debug!("Loaded API keys from database");
@@ -394,6 +407,16 @@ impl ApiKeyManager {
             }
         }
 
+        // The secret store also covers password manager commands when there is no database
+        match SecretStore::open_default().and_then(|store| store.get("openrouter_api_key")) {
+            Ok(Some(key)) if Self::validate_openrouter_format(&key).is_ok() => {
+                debug!("Found OpenRouter key in the secret store");
+                return Ok(key);
+            }
+            Ok(_) => {}
+            Err(e) => warn!("Failed to read OpenRouter key from the secret store: {}", e),
+        }
+
         // Try environment variable as fallback
         if let Ok(key) = std::env::var("OPENROUTER_API_KEY") {
             if Self::validate_openrouter_format(&key).is_ok() {
@@ -445,6 +468,11 @@ impl ApiKeyManager {
             params!["openrouter_api_key", "hive_api_key", "anthropic_api_key"],
         )?;
 
+        let store = SecretStore::open_default()?;
+        for name in ["openrouter_api_key", "anthropic_api_key"] {
+            store.remove(name)?;
+        }
+
         info!("API keys cleared from database");
         Ok(())
     }
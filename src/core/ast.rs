@@ -254,19 +254,10 @@ impl AstEngine {
         let mut parsers = std::collections::HashMap::new();
 
         // Register parsers for each language
-        for lang in &[
-            Language::Rust,
-            Language::TypeScript,
-            Language::JavaScript,
-            Language::Python,
-            Language::Go,
-            Language::Java,
-            Language::Cpp,
-            Language::C,
-        ] {
+        for lang in Language::all() {
             parsers.insert(
-                *lang,
-                Arc::new(GenericParser { language: *lang }) as Arc<dyn LanguageParser>,
+                lang,
+                Arc::new(GenericParser { language: lang }) as Arc<dyn LanguageParser>,
             );
         }
 
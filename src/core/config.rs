@@ -25,7 +25,10 @@ pub struct HiveConfig {
     pub security: SecurityConfig,
     pub logging: LoggingConfig,
     pub openrouter: Option<OpenRouterConfig>,
+    pub secrets: Option<SecretsConfig>,
     pub cloudflare: Option<CloudflareConfig>,
+    pub team_sync: Option<TeamSyncConfig>,
+    pub hooks: Option<HooksConfig>,
     pub license: Option<LicenseConfig>,
     pub core_dirs: CoreDirsConfig,
     pub analytics: AnalyticsConfig,
@@ -147,6 +150,34 @@ pub struct OpenRouterConfig {
     pub max_retries: u32,
 }
 
+/// Secret storage configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SecretsConfig {
+    /// Backends tried in order when reading a secret: "file", "command" and "env"
+    #[serde(default = "default_secret_backends")]
+    pub backends: Vec<String>,
+    /// Shell command that prints a secret, e.g. "pass show hive/{name}"
+    #[serde(default)]
+    pub api_key_command: Option<String>,
+    /// Seal the secrets file with HIVE_SECRETS_PASSPHRASE instead of a key file
+    #[serde(default)]
+    pub use_passphrase: bool,
+}
+
+fn default_secret_backends() -> Vec<String> {
+    vec!["file".to_string(), "command".to_string(), "env".to_string()]
+}
+
+impl Default for SecretsConfig {
+    fn default() -> Self {
+        Self {
+            backends: default_secret_backends(),
+            api_key_command: None,
+            use_passphrase: false,
+        }
+    }
+}
+
 /// Cloudflare D1 configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CloudflareConfig {
@@ -156,6 +187,44 @@ pub struct CloudflareConfig {
     pub sync_enabled: bool,
 }
 
+/// Team knowledge sync through a self-hosted `hive-sync-server`
+///
+/// The access token and team passphrase are kept in the secret store.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TeamSyncConfig {
+    /// Base URL of the sync server
+    pub server_url: String,
+    /// Team name; must match the server's token entry and salts the team key
+    pub team: String,
+    /// Push curated facts; facts pulled from the team are kept either way
+    #[serde(default = "default_share_facts")]
+    pub share_facts: bool,
+}
+
+fn default_share_facts() -> bool {
+    true
+}
+
+/// Lifecycle hook settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HooksConfig {
+    /// Stage cost in USD above which `cost_threshold_reached` hooks run
+    #[serde(default = "default_stage_cost_threshold")]
+    pub stage_cost_threshold: f64,
+}
+
+fn default_stage_cost_threshold() -> f64 {
+    0.05
+}
+
+impl Default for HooksConfig {
+    fn default() -> Self {
+        Self {
+            stage_cost_threshold: default_stage_cost_threshold(),
+        }
+    }
+}
+
 /// License configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LicenseConfig {
@@ -246,7 +315,10 @@ impl Default for HiveConfig {
                 format: "pretty".to_string(),
             },
             openrouter: None,
+            secrets: None,
             cloudflare: None,
+            team_sync: None,
+            hooks: None,
             license: None,
             core_dirs: CoreDirsConfig {
                 data_dir: hive_dir.clone(),
@@ -403,6 +475,14 @@ pub async fn set_config_value(key: &str, value: &str) -> Result<()> {
         "logging.level" => config.logging.level = value.to_string(),
         "logging.format" => config.logging.format = value.to_string(),
 
+        // Handle Hooks config
+        "hooks.stage_cost_threshold" => {
+            config
+                .hooks
+                .get_or_insert_with(HooksConfig::default)
+                .stage_cost_threshold = value.parse()?
+        }
+
         // Handle Analytics config
         "analytics.collection_enabled" => config.analytics.collection_enabled = value.parse()?,
         "analytics.retention_days" => config.analytics.retention_days = value.parse()?,
@@ -509,6 +589,13 @@ pub async fn get_config_value(key: &str) -> Result<String> {
         "logging.level" => config.logging.level,
         "logging.format" => config.logging.format,
 
+        // Handle Hooks config
+        "hooks.stage_cost_threshold" => config
+            .hooks
+            .unwrap_or_default()
+            .stage_cost_threshold
+            .to_string(),
+
         // Handle Analytics config
         "analytics.collection_enabled" => config.analytics.collection_enabled.to_string(),
         "analytics.retention_days" => config.analytics.retention_days.to_string(),
@@ -471,20 +471,7 @@ pub fn format_context(context: &QueryContext) -> String {
 
 /// Convert language enum to string for syntax highlighting
 fn language_to_string(lang: Language) -> &'static str {
-    match lang {
-        Language::Rust => "rust",
-        Language::TypeScript => "typescript",
-        Language::JavaScript => "javascript",
-        Language::Python => "python",
-        Language::Go => "go",
-        Language::Java => "java",
-        Language::Cpp => "cpp",
-        Language::C => "c",
-        Language::Ruby => "ruby",
-        Language::PHP => "php",
-        Language::Swift => "swift",
-        Language::Unknown => "text",
-    }
+    lang.as_str()
 }
 
 #[cfg(all(test, feature = "legacy-tests"))]
@@ -703,6 +703,15 @@ pub async fn get_database() -> Result<Arc<DatabaseManager>> {
         .map(Arc::clone)
 }
 
+/// The global database instance, initializing it with the default config if needed
+pub async fn get_or_initialize_database() -> Result<Arc<DatabaseManager>> {
+    if let Ok(database) = get_database().await {
+        return Ok(database);
+    }
+    initialize_database(None).await?;
+    get_database().await
+}
+
 /// Generate a new UUID for use as primary keys
 pub fn generate_id() -> String {
     Uuid::new_v4().to_string()
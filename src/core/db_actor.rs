@@ -569,7 +569,11 @@ impl DatabaseActor {
             )
             .optional()?;
 
-        Ok(license_key)
+        // Migrated rows keep an empty value; the key itself is in the secret store
+        Ok(
+            crate::core::secrets::resolve_configuration("hive_license_key", license_key)?
+                .filter(|key| !key.is_empty()),
+        )
     }
 
     // Handler methods for analytics
@@ -137,6 +137,10 @@ pub enum HiveError {
     #[error("Consensus quality threshold not met: {quality} < {threshold}")]
     ConsensusQuality { quality: f64, threshold: f64 },
 
+    /// Consensus pipeline failed outside a specific stage
+    #[error("Consensus failed: {message}")]
+    ConsensusFailed { message: String },
+
     // Security and trust errors
     /// Directory not trusted
     #[error("Directory not trusted: {path}. Run 'hive trust {path}' to grant access")]
@@ -417,7 +421,8 @@ impl HiveError {
             | Self::ConsensusCuration { .. }
             | Self::NoConsensusModels { .. }
             | Self::ConsensusStreaming { .. }
-            | Self::ConsensusQuality { .. } => ErrorCategory::Consensus,
+            | Self::ConsensusQuality { .. }
+            | Self::ConsensusFailed { .. } => ErrorCategory::Consensus,
 
             Self::DirectoryNotTrusted { .. }
             | Self::SecurityPolicyDenied { .. }
@@ -0,0 +1,407 @@
+//! Language identity and detection
+//!
+//! Which languages Hive knows and how to recognise them: ids, names,
+//! extensions, well-known filenames, shebang interpreters and comment syntax.
+//! How structure is extracted from each language (tree-sitter grammars and
+//! queries, or line patterns) lives in `crate::analysis::languages`.
+
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+
+use super::Language;
+
+/// How a language is recognised and how its comments look
+pub struct LanguageInfo {
+    pub language: Language,
+    /// Stable identifier (`Language::as_str`)
+    pub id: &'static str,
+    pub display_name: &'static str,
+    /// Alternative names accepted on the command line
+    pub aliases: &'static [&'static str],
+    pub extensions: &'static [&'static str],
+    /// Exact filenames without a telling extension (e.g. `Gemfile`)
+    pub filenames: &'static [&'static str],
+    /// Interpreters recognised in a `#!` line
+    pub interpreters: &'static [&'static str],
+    pub line_comments: &'static [&'static str],
+    pub block_comment: Option<(&'static str, &'static str)>,
+}
+
+impl LanguageInfo {
+    /// Whether a trimmed, non-empty line is a comment
+    pub fn is_comment_line(&self, trimmed: &str) -> bool {
+        if self
+            .line_comments
+            .iter()
+            .any(|prefix| trimmed.starts_with(prefix))
+        {
+            return true;
+        }
+        match self.block_comment {
+            // Continuation lines of C-style block comments start with `*`
+            Some((open, _)) => {
+                trimmed.starts_with(open) || (open == "/*" && trimmed.starts_with('*'))
+            }
+            None => false,
+        }
+    }
+
+    /// Lines of code and comment lines, skipping blanks
+    pub fn count_lines(&self, source: &str) -> (usize, usize) {
+        let mut code_lines = 0;
+        let mut comment_lines = 0;
+        for line in source.lines() {
+            let trimmed = line.trim();
+            if trimmed.is_empty() {
+                continue;
+            }
+            if self.is_comment_line(trimmed) {
+                comment_lines += 1;
+            } else {
+                code_lines += 1;
+            }
+        }
+        (code_lines, comment_lines)
+    }
+}
+
+const C_STYLE_LINE: &[&str] = &["//"];
+const C_STYLE_BLOCK: Option<(&str, &str)> = Some(("/*", "*/"));
+const HASH_LINE: &[&str] = &["#"];
+
+static LANGUAGES: &[LanguageInfo] = &[
+    LanguageInfo {
+        language: Language::Rust,
+        id: "rust",
+        display_name: "Rust",
+        aliases: &["rs"],
+        extensions: &["rs"],
+        filenames: &[],
+        interpreters: &[],
+        line_comments: C_STYLE_LINE,
+        block_comment: C_STYLE_BLOCK,
+    },
+    LanguageInfo {
+        language: Language::TypeScript,
+        id: "typescript",
+        display_name: "TypeScript",
+        aliases: &["ts"],
+        extensions: &["ts", "tsx"],
+        filenames: &[],
+        interpreters: &["ts-node"],
+        line_comments: C_STYLE_LINE,
+        block_comment: C_STYLE_BLOCK,
+    },
+    LanguageInfo {
+        language: Language::JavaScript,
+        id: "javascript",
+        display_name: "JavaScript",
+        aliases: &["js"],
+        extensions: &["js", "jsx", "mjs", "cjs"],
+        filenames: &[],
+        interpreters: &["node", "deno"],
+        line_comments: C_STYLE_LINE,
+        block_comment: C_STYLE_BLOCK,
+    },
+    LanguageInfo {
+        language: Language::Python,
+        id: "python",
+        display_name: "Python",
+        aliases: &["py"],
+        extensions: &["py", "pyw", "pyi"],
+        filenames: &[],
+        interpreters: &["python", "python3"],
+        line_comments: HASH_LINE,
+        block_comment: None,
+    },
+    LanguageInfo {
+        language: Language::Go,
+        id: "go",
+        display_name: "Go",
+        aliases: &["golang"],
+        extensions: &["go"],
+        filenames: &[],
+        interpreters: &[],
+        line_comments: C_STYLE_LINE,
+        block_comment: C_STYLE_BLOCK,
+    },
+    LanguageInfo {
+        language: Language::Java,
+        id: "java",
+        display_name: "Java",
+        aliases: &[],
+        extensions: &["java"],
+        filenames: &[],
+        interpreters: &[],
+        line_comments: C_STYLE_LINE,
+        block_comment: C_STYLE_BLOCK,
+    },
+    LanguageInfo {
+        language: Language::Cpp,
+        id: "cpp",
+        display_name: "C++",
+        aliases: &["c++"],
+        extensions: &["cpp", "cxx", "cc", "hpp", "hxx", "hh", "c++", "h++"],
+        filenames: &[],
+        interpreters: &[],
+        line_comments: C_STYLE_LINE,
+        block_comment: C_STYLE_BLOCK,
+    },
+    LanguageInfo {
+        language: Language::C,
+        id: "c",
+        display_name: "C",
+        aliases: &[],
+        extensions: &["c", "h"],
+        filenames: &[],
+        interpreters: &[],
+        line_comments: C_STYLE_LINE,
+        block_comment: C_STYLE_BLOCK,
+    },
+    LanguageInfo {
+        language: Language::Ruby,
+        id: "ruby",
+        display_name: "Ruby",
+        aliases: &["rb"],
+        extensions: &["rb", "rake"],
+        filenames: &["Rakefile", "Gemfile"],
+        interpreters: &["ruby"],
+        line_comments: HASH_LINE,
+        block_comment: Some(("=begin", "=end")),
+    },
+    LanguageInfo {
+        language: Language::PHP,
+        id: "php",
+        display_name: "PHP",
+        aliases: &[],
+        extensions: &["php", "php3", "php4", "php5", "php7", "phtml"],
+        filenames: &[],
+        interpreters: &["php"],
+        line_comments: &["//", "#"],
+        block_comment: C_STYLE_BLOCK,
+    },
+    LanguageInfo {
+        language: Language::Swift,
+        id: "swift",
+        display_name: "Swift",
+        aliases: &[],
+        extensions: &["swift"],
+        filenames: &[],
+        interpreters: &["swift"],
+        line_comments: C_STYLE_LINE,
+        block_comment: C_STYLE_BLOCK,
+        // Swift parsing temporarily disabled due to tree-sitter version incompatibility
+        // TODO: Update tree-sitter-swift to version 0.20+ to match other parsers
+    },
+    LanguageInfo {
+        language: Language::Kotlin,
+        id: "kotlin",
+        display_name: "Kotlin",
+        aliases: &["kt"],
+        extensions: &["kt", "kts"],
+        filenames: &[],
+        interpreters: &["kotlin"],
+        line_comments: C_STYLE_LINE,
+        block_comment: C_STYLE_BLOCK,
+    },
+    LanguageInfo {
+        language: Language::CSharp,
+        id: "csharp",
+        display_name: "C#",
+        aliases: &["cs", "c#"],
+        extensions: &["cs", "csx"],
+        filenames: &[],
+        interpreters: &[],
+        line_comments: C_STYLE_LINE,
+        block_comment: C_STYLE_BLOCK,
+    },
+    LanguageInfo {
+        language: Language::Scala,
+        id: "scala",
+        display_name: "Scala",
+        aliases: &[],
+        extensions: &["scala", "sc"],
+        filenames: &[],
+        interpreters: &["scala"],
+        line_comments: C_STYLE_LINE,
+        block_comment: C_STYLE_BLOCK,
+    },
+    LanguageInfo {
+        language: Language::Bash,
+        id: "bash",
+        display_name: "Bash",
+        aliases: &["sh", "shell", "zsh"],
+        extensions: &["sh", "bash", "zsh"],
+        filenames: &[".bashrc", ".bash_profile", ".zshrc", ".profile"],
+        interpreters: &["bash", "sh", "zsh"],
+        line_comments: HASH_LINE,
+        block_comment: None,
+    },
+    LanguageInfo {
+        language: Language::Sql,
+        id: "sql",
+        display_name: "SQL",
+        aliases: &[],
+        extensions: &["sql", "ddl"],
+        filenames: &[],
+        interpreters: &[],
+        line_comments: &["--"],
+        block_comment: C_STYLE_BLOCK,
+    },
+    LanguageInfo {
+        language: Language::Toml,
+        id: "toml",
+        display_name: "TOML",
+        aliases: &[],
+        extensions: &["toml"],
+        filenames: &["Cargo.lock", "Pipfile"],
+        interpreters: &[],
+        line_comments: HASH_LINE,
+        block_comment: None,
+    },
+    LanguageInfo {
+        language: Language::Yaml,
+        id: "yaml",
+        display_name: "YAML",
+        aliases: &["yml"],
+        extensions: &["yaml", "yml"],
+        filenames: &[],
+        interpreters: &[],
+        line_comments: HASH_LINE,
+        block_comment: None,
+    },
+];
+
+static BY_LANGUAGE: Lazy<HashMap<Language, &'static LanguageInfo>> =
+    Lazy::new(|| LANGUAGES.iter().map(|info| (info.language, info)).collect());
+
+static BY_EXTENSION: Lazy<HashMap<&'static str, &'static LanguageInfo>> = Lazy::new(|| {
+    LANGUAGES
+        .iter()
+        .flat_map(|info| info.extensions.iter().map(move |ext| (*ext, info)))
+        .collect()
+});
+
+/// Every known language
+pub fn all() -> &'static [LanguageInfo] {
+    LANGUAGES
+}
+
+/// Entry for a language; `None` for `Language::Unknown`
+pub fn info(language: Language) -> Option<&'static LanguageInfo> {
+    BY_LANGUAGE.get(&language).copied()
+}
+
+/// Look up by file extension (case-insensitive, without the dot)
+pub fn by_extension(extension: &str) -> Option<&'static LanguageInfo> {
+    BY_EXTENSION
+        .get(extension)
+        .or_else(|| BY_EXTENSION.get(extension.to_lowercase().as_str()))
+        .copied()
+}
+
+/// Look up by exact filename
+pub fn by_filename(filename: &str) -> Option<&'static LanguageInfo> {
+    LANGUAGES
+        .iter()
+        .find(|info| info.filenames.contains(&filename))
+}
+
+/// Look up by id, display name or alias (case-insensitive)
+pub fn by_name(name: &str) -> Option<&'static LanguageInfo> {
+    let name = name.to_lowercase();
+    LANGUAGES.iter().find(|info| {
+        info.id == name
+            || info.display_name.to_lowercase() == name
+            || info.aliases.contains(&name.as_str())
+    })
+}
+
+/// Look up by the interpreter named in a `#!` line
+pub fn by_shebang(line: &str) -> Option<&'static LanguageInfo> {
+    let line = line.strip_prefix("#!")?.trim();
+    let mut parts = line.split_whitespace();
+    let mut program = parts.next()?.rsplit('/').next()?;
+    if program == "env" {
+        // Skip `env` flags such as `-S`
+        program = parts.find(|p| !p.starts_with('-'))?;
+    }
+    // `python3.11` -> `python3`, `python`
+    let program = program.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.');
+
+    LANGUAGES.iter().find(|info| {
+        info.interpreters
+            .iter()
+            .any(|i| i.trim_end_matches(|c: char| c.is_ascii_digit()) == program)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_language_is_registered_once() {
+        let mut seen = std::collections::HashSet::new();
+        for info in LANGUAGES {
+            assert!(
+                seen.insert(info.language),
+                "{:?} registered twice",
+                info.language
+            );
+            assert_eq!(by_name(info.id).unwrap().language, info.language);
+        }
+        assert!(info(Language::Unknown).is_none());
+    }
+
+    #[test]
+    fn test_extensions_are_unique() {
+        let mut seen = HashMap::new();
+        for info in LANGUAGES {
+            for ext in info.extensions {
+                if let Some(previous) = seen.insert(*ext, info.id) {
+                    panic!("extension {} claimed by {} and {}", ext, previous, info.id);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_lookups() {
+        assert_eq!(by_extension("kt").unwrap().language, Language::Kotlin);
+        assert_eq!(by_extension("CS").unwrap().language, Language::CSharp);
+        assert_eq!(by_extension("yml").unwrap().language, Language::Yaml);
+        assert_eq!(by_filename("Gemfile").unwrap().language, Language::Ruby);
+        assert_eq!(by_name("c#").unwrap().language, Language::CSharp);
+        assert_eq!(by_name("Scala").unwrap().language, Language::Scala);
+        assert!(by_name("cobol").is_none());
+    }
+
+    #[test]
+    fn test_shebangs() {
+        let lang = |line: &str| by_shebang(line).map(|s| s.language);
+        assert_eq!(lang("#!/bin/bash"), Some(Language::Bash));
+        assert_eq!(lang("#!/usr/bin/env sh"), Some(Language::Bash));
+        assert_eq!(lang("#!/usr/bin/env python3.11"), Some(Language::Python));
+        assert_eq!(
+            lang("#!/usr/bin/env -S node --harmony"),
+            Some(Language::JavaScript)
+        );
+        assert_eq!(lang("#!/usr/bin/perl"), None);
+        assert_eq!(lang("echo hi"), None);
+    }
+
+    #[test]
+    fn test_comment_lines() {
+        let sql = info(Language::Sql).unwrap();
+        assert_eq!(
+            sql.count_lines("-- users\nSELECT 1;\n\n/* note */\n"),
+            (1, 2)
+        );
+
+        let rust = info(Language::Rust).unwrap();
+        assert!(rust.is_comment_line("/// docs"));
+        assert!(rust.is_comment_line("* continued"));
+        assert!(!rust.is_comment_line("let x = 1;"));
+    }
+}
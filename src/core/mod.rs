@@ -5,6 +5,7 @@ pub mod ast;
 pub mod config;
 pub mod context;
 pub mod error;
+pub mod language;
 pub mod logging;
 pub mod semantic;
 pub mod temporal;
@@ -17,6 +18,7 @@ pub mod db_actor;
 
 // These modules require additional dependencies not in minimal build
 pub mod schema;
+pub mod secrets;
 pub mod security;
 // pub mod trust_dialog;
 // pub mod file_access;
@@ -28,6 +30,7 @@ pub mod analytics;
 pub mod memory;
 
 // Auto-update mechanism
+pub mod release_manifest;
 pub mod updater;
 
 // License management
@@ -125,6 +128,9 @@ pub struct FileInfo {
 }
 
 /// Supported programming languages
+///
+/// Recognition details (extensions, filenames, comments) live in `language`;
+/// grammars and queries in `crate::analysis::languages`.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
 pub enum Language {
     Rust,
@@ -138,25 +144,43 @@ pub enum Language {
     Ruby,
     PHP,
     Swift,
+    Kotlin,
+    CSharp,
+    Scala,
+    Bash,
+    Sql,
+    Toml,
+    Yaml,
     Unknown,
 }
 
 impl Language {
     pub fn as_str(&self) -> &'static str {
-        match self {
-            Language::Rust => "rust",
-            Language::TypeScript => "typescript",
-            Language::JavaScript => "javascript",
-            Language::Python => "python",
-            Language::Go => "go",
-            Language::Java => "java",
-            Language::Cpp => "cpp",
-            Language::C => "c",
-            Language::Ruby => "ruby",
-            Language::PHP => "php",
-            Language::Swift => "swift",
-            Language::Unknown => "text",
-        }
+        language::info(*self).map(|info| info.id).unwrap_or("text")
+    }
+
+    /// Get all supported languages
+    pub fn all() -> Vec<Language> {
+        language::all().iter().map(|info| info.language).collect()
+    }
+
+    /// Get file extensions for a language
+    pub fn extensions(&self) -> Vec<&'static str> {
+        language::info(*self)
+            .map(|info| info.extensions.to_vec())
+            .unwrap_or_default()
+    }
+
+    /// Get display name for a language
+    pub fn display_name(&self) -> &'static str {
+        language::info(*self)
+            .map(|info| info.display_name)
+            .unwrap_or("Unknown")
+    }
+
+    /// Parse a language from its id, display name or a common alias
+    pub fn from_name(name: &str) -> Option<Language> {
+        language::by_name(name).map(|info| info.language)
     }
 }
 
@@ -218,18 +242,14 @@ impl Workspace {
 
 /// Detect the programming language of a file
 pub fn detect_language(path: &Path) -> Language {
-    match path.extension().and_then(|s| s.to_str()) {
-        Some("rs") => Language::Rust,
-        Some("ts") | Some("tsx") => Language::TypeScript,
-        Some("js") | Some("jsx") => Language::JavaScript,
-        Some("py") => Language::Python,
-        Some("go") => Language::Go,
-        Some("java") => Language::Java,
-        Some("cpp") | Some("cc") | Some("cxx") => Language::Cpp,
-        Some("c") | Some("h") => Language::C,
-        Some("rb") => Language::Ruby,
-        Some("php") => Language::PHP,
-        Some("swift") => Language::Swift,
-        _ => Language::Unknown,
-    }
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .and_then(language::by_filename)
+        .or_else(|| {
+            path.extension()
+                .and_then(|ext| ext.to_str())
+                .and_then(language::by_extension)
+        })
+        .map(|info| info.language)
+        .unwrap_or(Language::Unknown)
 }
@@ -0,0 +1,508 @@
+// Release manifests - Ed25519-signed lists of a release's binaries and their digests
+// Verified against compiled-in keys before self-update installs anything, online or from a bundle
+
+use anyhow::{anyhow, bail, Context, Result};
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use chrono::{DateTime, Utc};
+use flate2::read::GzDecoder;
+use ring::signature::{Ed25519KeyPair, UnparsedPublicKey, ED25519};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::ffi::OsStr;
+use std::fs::File;
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+use tempfile::TempDir;
+
+/// Manifest file in a release directory or bundle
+pub const MANIFEST_FILE: &str = "manifest.json";
+/// Signatures over the exact bytes of [`MANIFEST_FILE`]
+pub const SIGNATURE_FILE: &str = "manifest.json.sig";
+
+/// Keys trusted by this build, one `<id> <base64 public key> [revoked]` per line
+const RELEASE_KEYS: &str = include_str!("release_keys.txt");
+
+// Builds that publish releases must ship a key that can verify the next update
+#[cfg(feature = "release-signing")]
+const _: () = assert!(
+    has_active_key(RELEASE_KEYS),
+    "src/core/release_keys.txt has no unrevoked release signing key"
+);
+
+/// Whether a key list has a line that is neither blank, a comment nor marked `revoked`
+#[cfg(any(test, feature = "release-signing"))]
+const fn has_active_key(text: &str) -> bool {
+    const REVOKED: &[u8] = b" revoked";
+    let bytes = text.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        // The line's content runs from `start` to `end`, without comment or surrounding blanks
+        let mut start = i;
+        while start < bytes.len() && bytes[start].is_ascii_whitespace() && bytes[start] != b'\n' {
+            start += 1;
+        }
+        let mut end = start;
+        while end < bytes.len() && bytes[end] != b'\n' && bytes[end] != b'#' {
+            end += 1;
+        }
+        i = end;
+        while i < bytes.len() && bytes[i] != b'\n' {
+            i += 1;
+        }
+        i += 1;
+        while end > start && bytes[end - 1].is_ascii_whitespace() {
+            end -= 1;
+        }
+        if end == start {
+            continue;
+        }
+
+        let mut revoked = end - start > REVOKED.len();
+        let mut j = 0;
+        while revoked && j < REVOKED.len() {
+            let byte = bytes[end - REVOKED.len() + j];
+            revoked = byte == REVOKED[j] || (j == 0 && byte.is_ascii_whitespace());
+            j += 1;
+        }
+        if !revoked {
+            return true;
+        }
+    }
+    false
+}
+
+/// What a release ships and how to check it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReleaseManifest {
+    pub version: String,
+    pub release_date: DateTime<Utc>,
+    #[serde(default)]
+    pub changelog: String,
+    #[serde(default)]
+    pub critical: bool,
+    pub artifacts: Vec<Artifact>,
+}
+
+/// One platform's binary
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Artifact {
+    /// Platform name from [`current_platform`], e.g. `linux-x64`
+    pub platform: String,
+    /// File name next to the manifest
+    pub file: String,
+    pub sha256: String,
+    pub size: u64,
+}
+
+impl ReleaseManifest {
+    /// The artifact for `platform`
+    pub fn artifact(&self, platform: &str) -> Result<&Artifact> {
+        let artifact = self
+            .artifacts
+            .iter()
+            .find(|artifact| artifact.platform == platform)
+            .ok_or_else(|| anyhow!("Release {} has no build for {}", self.version, platform))?;
+        // File names come from the signed manifest, but are joined onto local paths and URLs
+        if Path::new(&artifact.file).file_name() != Some(OsStr::new(&artifact.file)) {
+            bail!("Invalid artifact file name '{}'", artifact.file);
+        }
+        Ok(artifact)
+    }
+}
+
+/// Contents of [`SIGNATURE_FILE`]; releases are signed by several keys during a rotation
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ManifestSignatures {
+    pub signatures: Vec<ManifestSignature>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestSignature {
+    pub key_id: String,
+    /// Base64 Ed25519 signature
+    pub signature: String,
+}
+
+/// Sign manifest bytes, for release tooling
+pub fn sign_manifest(
+    key_pair: &Ed25519KeyPair,
+    key_id: &str,
+    manifest: &[u8],
+) -> ManifestSignature {
+    ManifestSignature {
+        key_id: key_id.to_string(),
+        signature: STANDARD.encode(key_pair.sign(manifest)),
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TrustedKey {
+    pub id: String,
+    pub public_key: Vec<u8>,
+    pub revoked: bool,
+}
+
+/// Release signing keys a manifest is checked against
+#[derive(Debug, Clone, Default)]
+pub struct Keyring {
+    keys: Vec<TrustedKey>,
+}
+
+impl Keyring {
+    /// The keys compiled into this build
+    pub fn builtin() -> Self {
+        Self::parse(RELEASE_KEYS).expect("built-in release keys are valid")
+    }
+
+    /// Parse `<id> <base64 public key> [revoked]` lines; `#` starts a comment
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut keys = Vec::new();
+        for line in text.lines() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let (id, key, revoked) = match fields.as_slice() {
+                [id, key] => (id, key, false),
+                [id, key, "revoked"] => (id, key, true),
+                _ => bail!("Invalid release key line '{}'", line),
+            };
+            let public_key = STANDARD
+                .decode(key)
+                .with_context(|| format!("Release key '{}' is not base64", id))?;
+            if public_key.len() != 32 {
+                bail!("Release key '{}' is not an Ed25519 public key", id);
+            }
+            keys.push(TrustedKey {
+                id: id.to_string(),
+                public_key,
+                revoked,
+            });
+        }
+        Ok(Self { keys })
+    }
+
+    pub fn with_key(mut self, id: &str, public_key: &[u8]) -> Self {
+        self.keys.push(TrustedKey {
+            id: id.to_string(),
+            public_key: public_key.to_vec(),
+            revoked: false,
+        });
+        self
+    }
+
+    pub fn keys(&self) -> &[TrustedKey] {
+        &self.keys
+    }
+
+    /// Parse `manifest` if one of its signatures is by a trusted, unrevoked key
+    ///
+    /// Returns the manifest and the id of the key that vouched for it.
+    pub fn verify(&self, manifest: &[u8], signatures: &[u8]) -> Result<(ReleaseManifest, String)> {
+        if !self.keys.iter().any(|key| !key.revoked) {
+            bail!("This build trusts no release signing keys, so it cannot verify updates");
+        }
+        let signatures: ManifestSignatures =
+            serde_json::from_slice(signatures).context("Invalid manifest signature file")?;
+
+        let key_id = signatures
+            .signatures
+            .iter()
+            .find_map(|signature| {
+                let key = self
+                    .keys
+                    .iter()
+                    .find(|key| key.id == signature.key_id && !key.revoked)?;
+                let bytes = STANDARD.decode(&signature.signature).ok()?;
+                UnparsedPublicKey::new(&ED25519, &key.public_key)
+                    .verify(manifest, &bytes)
+                    .ok()
+                    .map(|_| key.id.clone())
+            })
+            .ok_or_else(|| {
+                let ids: Vec<&str> = signatures
+                    .signatures
+                    .iter()
+                    .map(|signature| signature.key_id.as_str())
+                    .collect();
+                anyhow!(
+                    "Release manifest has no valid signature from a trusted key (signed by: {})",
+                    if ids.is_empty() {
+                        "nobody".to_string()
+                    } else {
+                        ids.join(", ")
+                    }
+                )
+            })?;
+
+        let manifest = serde_json::from_slice(manifest).context("Invalid release manifest")?;
+        Ok((manifest, key_id))
+    }
+}
+
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+/// Check a downloaded or unpacked binary against its manifest entry
+pub fn verify_artifact(artifact: &Artifact, bytes: &[u8]) -> Result<()> {
+    if bytes.len() as u64 != artifact.size {
+        bail!(
+            "{} is {} bytes, the manifest says {}",
+            artifact.file,
+            bytes.len(),
+            artifact.size
+        );
+    }
+    let digest = sha256_hex(bytes);
+    if !digest.eq_ignore_ascii_case(&artifact.sha256) {
+        bail!(
+            "Checksum verification failed for {}: expected {}, got {}",
+            artifact.file,
+            artifact.sha256,
+            digest
+        );
+    }
+    Ok(())
+}
+
+/// Platform name of this build, as used in [`Artifact::platform`]
+pub fn current_platform() -> Result<&'static str> {
+    Ok(match (std::env::consts::OS, std::env::consts::ARCH) {
+        ("macos", "aarch64") => "macos-arm64",
+        ("macos", "x86_64") => "macos-x64",
+        ("linux", "aarch64") => "linux-arm64",
+        ("linux", "x86_64") => "linux-x64",
+        ("windows", "x86_64") => "windows-x64",
+        (os, arch) => bail!("Unsupported platform: {} {}", os, arch),
+    })
+}
+
+/// An offline update: a tar (optionally gzipped) of a release directory
+///
+/// The bundle holds [`MANIFEST_FILE`], [`SIGNATURE_FILE`] and the artifacts
+/// side by side. It is unpacked into a scratch directory and its manifest is
+/// verified before any artifact is looked at.
+pub struct UpdateBundle {
+    dir: TempDir,
+    manifest: ReleaseManifest,
+    key_id: String,
+}
+
+impl UpdateBundle {
+    pub fn open(path: &Path, keyring: &Keyring) -> Result<Self> {
+        let file = File::open(path)
+            .with_context(|| format!("Failed to open update bundle {}", path.display()))?;
+        let gzipped = path
+            .extension()
+            .is_some_and(|ext| ext == "gz" || ext == "tgz");
+        let reader: Box<dyn Read> = if gzipped {
+            Box::new(GzDecoder::new(file))
+        } else {
+            Box::new(file)
+        };
+
+        let dir = tempfile::tempdir()?;
+        let mut seen = HashSet::new();
+        let mut archive = tar::Archive::new(reader);
+        for entry in archive.entries().context("Invalid update bundle")? {
+            let mut entry = entry?;
+            if !entry.header().entry_type().is_file() {
+                continue;
+            }
+            let name = bundle_file_name(&entry.path()?)?;
+            if !seen.insert(name.clone()) {
+                bail!("Update bundle contains {} twice", name);
+            }
+            entry.unpack(dir.path().join(&name))?;
+        }
+
+        let read = |name: &str| {
+            std::fs::read(dir.path().join(name))
+                .with_context(|| format!("Update bundle has no {}", name))
+        };
+        let (manifest, key_id) = keyring.verify(&read(MANIFEST_FILE)?, &read(SIGNATURE_FILE)?)?;
+        Ok(Self {
+            dir,
+            manifest,
+            key_id,
+        })
+    }
+
+    pub fn manifest(&self) -> &ReleaseManifest {
+        &self.manifest
+    }
+
+    /// Id of the key whose signature was accepted
+    pub fn key_id(&self) -> &str {
+        &self.key_id
+    }
+
+    /// Path of the verified binary for `platform` inside the unpacked bundle
+    pub fn artifact(&self, platform: &str) -> Result<PathBuf> {
+        let artifact = self.manifest.artifact(platform)?;
+        let path = self.dir.path().join(&artifact.file);
+        let bytes = std::fs::read(&path)
+            .with_context(|| format!("Update bundle has no {}", artifact.file))?;
+        verify_artifact(artifact, &bytes)?;
+        Ok(path)
+    }
+}
+
+/// Bundles are flat; anything but a plain file name is rejected
+fn bundle_file_name(path: &Path) -> Result<String> {
+    let mut parts = path
+        .components()
+        .filter(|component| !matches!(component, Component::CurDir));
+    match (parts.next(), parts.next()) {
+        (Some(Component::Normal(name)), None) => name
+            .to_str()
+            .map(str::to_string)
+            .ok_or_else(|| anyhow!("Non-UTF-8 file name in update bundle")),
+        _ => bail!("Unexpected path in update bundle: {}", path.display()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ring::signature::KeyPair;
+
+    fn key_pair(seed: u8) -> Ed25519KeyPair {
+        Ed25519KeyPair::from_seed_unchecked(&[seed; 32]).unwrap()
+    }
+
+    fn manifest_bytes(binary: &[u8]) -> Vec<u8> {
+        serde_json::to_vec_pretty(&ReleaseManifest {
+            version: "9.9.9".to_string(),
+            release_date: Utc::now(),
+            changelog: "Signed updates".to_string(),
+            critical: false,
+            artifacts: vec![Artifact {
+                platform: "linux-x64".to_string(),
+                file: "hive-linux-x64".to_string(),
+                sha256: sha256_hex(binary),
+                size: binary.len() as u64,
+            }],
+        })
+        .unwrap()
+    }
+
+    fn signatures(manifest: &[u8], keys: &[(&str, &Ed25519KeyPair)]) -> Vec<u8> {
+        serde_json::to_vec(&ManifestSignatures {
+            signatures: keys
+                .iter()
+                .map(|(id, key)| sign_manifest(key, id, manifest))
+                .collect(),
+        })
+        .unwrap()
+    }
+
+    fn write_bundle(path: &Path, files: &[(&str, &[u8])]) {
+        let mut builder = tar::Builder::new(File::create(path).unwrap());
+        for (name, contents) in files {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(contents.len() as u64);
+            header.set_mode(0o755);
+            header.set_cksum();
+            builder.append_data(&mut header, name, *contents).unwrap();
+        }
+        builder.finish().unwrap();
+    }
+
+    #[test]
+    fn test_verify_signatures_and_rotation() {
+        let (old, new) = (key_pair(1), key_pair(2));
+        let manifest = manifest_bytes(b"binary");
+        let both = signatures(&manifest, &[("old", &old), ("new", &new)]);
+
+        // A build that only knows the new key accepts a release signed during the rotation
+        let keyring = Keyring::default().with_key("new", new.public_key().as_ref());
+        let (parsed, key_id) = keyring.verify(&manifest, &both).unwrap();
+        assert_eq!(parsed.version, "9.9.9");
+        assert_eq!(key_id, "new");
+
+        // Tampering with the manifest breaks every signature
+        let mut tampered = manifest.clone();
+        let at = tampered.len() - 2;
+        tampered[at] ^= 1;
+        assert!(keyring.verify(&tampered, &both).is_err());
+
+        // A revoked key no longer vouches for anything
+        let text = format!(
+            "# rotated\nold {} revoked\n",
+            STANDARD.encode(old.public_key().as_ref())
+        );
+        let revoked = Keyring::parse(&text)
+            .unwrap()
+            .with_key("other", key_pair(3).public_key().as_ref());
+        let only_old = signatures(&manifest, &[("old", &old)]);
+        let err = revoked.verify(&manifest, &only_old).unwrap_err();
+        assert!(err.to_string().contains("signed by: old"));
+
+        assert!(Keyring::default().verify(&manifest, &both).is_err());
+        assert!(Keyring::parse("key not-base64!").is_err());
+        Keyring::builtin();
+    }
+
+    #[test]
+    fn test_has_active_key() {
+        assert!(!has_active_key(""));
+        assert!(!has_active_key("# release-2026 AAAA\n\n  # comment\n"));
+        assert!(!has_active_key("release-2025 AAAA revoked # rotated\n"));
+        assert!(has_active_key(
+            "release-2025 AAAA revoked\nrelease-2026 BBBB # current\n"
+        ));
+        assert!(has_active_key("  release-2026 BBBB"));
+        assert_eq!(
+            has_active_key(RELEASE_KEYS),
+            Keyring::builtin().keys().iter().any(|key| !key.revoked)
+        );
+    }
+
+    #[test]
+    fn test_open_bundle() {
+        let dir = TempDir::new().unwrap();
+        let key = key_pair(7);
+        let keyring = Keyring::default().with_key("release", key.public_key().as_ref());
+        let binary = b"#!/bin/sh\necho hive 9.9.9\n";
+        let manifest = manifest_bytes(binary);
+        let sig = signatures(&manifest, &[("release", &key)]);
+
+        let path = dir.path().join("bundle.tar");
+        write_bundle(
+            &path,
+            &[
+                ("./manifest.json", &manifest),
+                ("manifest.json.sig", &sig),
+                ("hive-linux-x64", binary),
+            ],
+        );
+        let bundle = UpdateBundle::open(&path, &keyring).unwrap();
+        assert_eq!(bundle.manifest().version, "9.9.9");
+        assert_eq!(bundle.key_id(), "release");
+        let artifact = bundle.artifact("linux-x64").unwrap();
+        assert_eq!(std::fs::read(artifact).unwrap(), binary);
+        assert!(bundle.artifact("windows-x64").is_err());
+
+        // A swapped binary fails the digest in the signed manifest
+        write_bundle(
+            &path,
+            &[
+                ("manifest.json", &manifest),
+                ("manifest.json.sig", &sig),
+                ("hive-linux-x64", b"#!/bin/sh\necho evil!!!!!!\n"),
+            ],
+        );
+        let bundle = UpdateBundle::open(&path, &keyring).unwrap();
+        assert!(bundle.artifact("linux-x64").is_err());
+
+        // So does an unsigned one, and nothing may escape the scratch directory
+        write_bundle(&path, &[("manifest.json", &manifest)]);
+        assert!(UpdateBundle::open(&path, &keyring).is_err());
+        assert!(bundle_file_name(Path::new("../hive")).is_err());
+        assert!(bundle_file_name(Path::new("bin/hive")).is_err());
+    }
+}
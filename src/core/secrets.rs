@@ -0,0 +1,925 @@
+// Secret storage for provider API keys
+// Secrets are sealed with XChaCha20-Poly1305 in a file, or read from a password manager command or the environment
+
+use anyhow::{anyhow, bail, Context, Result};
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{Key, XChaCha20Poly1305, XNonce};
+use rand::RngCore;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::{Arc, Mutex};
+use tracing::{debug, warn};
+
+use crate::core::config::{get_hive_config_dir, SecretsConfig};
+
+/// Secrets hive knows about, with the environment variable that can provide each
+pub const KNOWN_SECRETS: &[(&str, &str)] = &[
+    ("openrouter_api_key", "OPENROUTER_API_KEY"),
+    ("anthropic_api_key", "ANTHROPIC_API_KEY"),
+    ("hive_license_key", "HIVE_LICENSE_KEY"),
+    ("team_sync_token", "HIVE_SYNC_TOKEN"),
+    ("team_sync_passphrase", "HIVE_SYNC_PASSPHRASE"),
+];
+
+/// Encrypted secrets, in the hive config directory
+pub const SECRETS_FILE: &str = "secrets.json";
+/// Machine-local key that seals [`SECRETS_FILE`] unless a passphrase is used
+pub const KEY_FILE: &str = "secrets.key";
+/// Passphrase for the secrets file when `use_passphrase` is set
+pub const PASSPHRASE_ENV: &str = "HIVE_SECRETS_PASSPHRASE";
+
+const FILE_VERSION: u32 = 1;
+/// Sealed with every file so a wrong key fails before any secret is touched
+const CHECK_NAME: &str = "__check__";
+
+/// Environment variable for `name`
+fn env_var(name: &str) -> String {
+    KNOWN_SECRETS
+        .iter()
+        .find(|(known, _)| *known == name)
+        .map(|(_, var)| var.to_string())
+        .unwrap_or_else(|| name.to_uppercase())
+}
+
+fn validate_name(name: &str) -> Result<()> {
+    if name.is_empty()
+        || name == CHECK_NAME
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+    {
+        bail!(
+            "Invalid secret name '{}': use lowercase letters, digits and '_'",
+            name
+        );
+    }
+    Ok(())
+}
+
+/// A place secrets are read from, and possibly written to
+pub trait SecretBackend: Send + Sync {
+    /// Short name shown by `hive config secrets list`
+    fn name(&self) -> &'static str;
+
+    fn get(&self, name: &str) -> Result<Option<String>>;
+
+    /// Names of the secrets this backend holds, never their values
+    fn list(&self) -> Result<Vec<String>>;
+
+    fn is_writable(&self) -> bool {
+        false
+    }
+
+    fn set(&self, name: &str, _value: &str) -> Result<()> {
+        bail!("The {} backend cannot store '{}'", self.name(), name)
+    }
+
+    fn remove(&self, name: &str) -> Result<bool> {
+        bail!("The {} backend cannot remove '{}'", self.name(), name)
+    }
+}
+
+/// Reads secrets from environment variables such as `OPENROUTER_API_KEY`
+pub struct EnvBackend;
+
+impl SecretBackend for EnvBackend {
+    fn name(&self) -> &'static str {
+        "env"
+    }
+
+    fn get(&self, name: &str) -> Result<Option<String>> {
+        Ok(std::env::var(env_var(name))
+            .ok()
+            .filter(|value| !value.is_empty()))
+    }
+
+    fn list(&self) -> Result<Vec<String>> {
+        Ok(KNOWN_SECRETS
+            .iter()
+            .filter(|(name, _)| matches!(self.get(name), Ok(Some(_))))
+            .map(|(name, _)| name.to_string())
+            .collect())
+    }
+}
+
+/// Reads secrets from a password manager through `api_key_command`
+///
+/// `{name}` in the command is replaced with the secret name, and the trimmed
+/// stdout is the secret. A non-zero exit means the secret is not there.
+pub struct CommandBackend {
+    command: String,
+}
+
+impl CommandBackend {
+    pub fn new(command: impl Into<String>) -> Self {
+        Self {
+            command: command.into(),
+        }
+    }
+}
+
+impl SecretBackend for CommandBackend {
+    fn name(&self) -> &'static str {
+        "command"
+    }
+
+    fn get(&self, name: &str) -> Result<Option<String>> {
+        let command = self.command.replace("{name}", name);
+        let output = if cfg!(windows) {
+            Command::new("cmd").args(["/C", &command]).output()
+        } else {
+            Command::new("sh").args(["-c", &command]).output()
+        }
+        .with_context(|| format!("Failed to run api_key_command for '{}'", name))?;
+
+        if !output.status.success() {
+            debug!(
+                "api_key_command has no '{}' (exit {}): {}",
+                name,
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+            return Ok(None);
+        }
+        let value = String::from_utf8(output.stdout)
+            .with_context(|| format!("api_key_command printed invalid UTF-8 for '{}'", name))?;
+        let value = value.trim();
+        Ok((!value.is_empty()).then(|| value.to_string()))
+    }
+
+    fn list(&self) -> Result<Vec<String>> {
+        let mut names = Vec::new();
+        for (name, _) in KNOWN_SECRETS {
+            if self.get(name)?.is_some() {
+                names.push(name.to_string());
+            }
+        }
+        Ok(names)
+    }
+}
+
+/// Where the key that seals the secrets file comes from
+#[derive(Clone)]
+pub enum KeySource {
+    /// 32 random bytes in a file only its owner may read
+    KeyFile(PathBuf),
+    /// A passphrase, stretched with Argon2id
+    Passphrase(String),
+}
+
+impl KeySource {
+    fn kdf(&self) -> &'static str {
+        match self {
+            Self::KeyFile(_) => "keyfile",
+            Self::Passphrase(_) => "argon2id",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Sealed {
+    nonce: String,
+    ciphertext: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SealedSecret {
+    #[serde(flatten)]
+    sealed: Sealed,
+    updated_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SealedFile {
+    version: u32,
+    /// "keyfile" or "argon2id"
+    kdf: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    salt: Option<String>,
+    check: Sealed,
+    secrets: BTreeMap<String, SealedSecret>,
+}
+
+/// The secret name is authenticated with the value, so sealed values cannot be swapped
+fn seal(key: &[u8; 32], name: &str, plaintext: &str) -> Result<Sealed> {
+    let mut nonce = [0u8; 24];
+    rand::thread_rng().fill_bytes(&mut nonce);
+    let ciphertext = XChaCha20Poly1305::new(Key::from_slice(key))
+        .encrypt(
+            XNonce::from_slice(&nonce),
+            Payload {
+                msg: plaintext.as_bytes(),
+                aad: name.as_bytes(),
+            },
+        )
+        .map_err(|_| anyhow!("Failed to encrypt secret '{}'", name))?;
+    Ok(Sealed {
+        nonce: STANDARD.encode(nonce),
+        ciphertext: STANDARD.encode(ciphertext),
+    })
+}
+
+fn unseal(key: &[u8; 32], name: &str, sealed: &Sealed) -> Result<String> {
+    let nonce = STANDARD.decode(&sealed.nonce)?;
+    if nonce.len() != 24 {
+        bail!("Secret '{}' has a malformed nonce", name);
+    }
+    let ciphertext = STANDARD.decode(&sealed.ciphertext)?;
+    let plaintext = XChaCha20Poly1305::new(Key::from_slice(key))
+        .decrypt(
+            XNonce::from_slice(&nonce),
+            Payload {
+                msg: &ciphertext,
+                aad: name.as_bytes(),
+            },
+        )
+        .map_err(|_| anyhow!("Secret '{}' cannot be decrypted", name))?;
+    String::from_utf8(plaintext).with_context(|| format!("Secret '{}' is not UTF-8", name))
+}
+
+fn passphrase_key(passphrase: &str, salt: &[u8]) -> Result<[u8; 32]> {
+    let mut key = [0u8; 32];
+    argon2::Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| anyhow!("Failed to derive key from passphrase: {}", e))?;
+    Ok(key)
+}
+
+/// Write `contents` so that only the owner can read it, replacing `path` atomically
+fn write_private(path: &Path, contents: &[u8]) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    let tmp = PathBuf::from(tmp);
+    let _ = std::fs::remove_file(&tmp);
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options
+        .open(&tmp)
+        .with_context(|| format!("Failed to create {}", tmp.display()))?;
+    file.write_all(contents)?;
+    file.sync_all()?;
+    std::fs::rename(&tmp, path).with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(())
+}
+
+/// Refuse key material that other users can read
+fn check_private(path: &Path) -> Result<()> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(path)?.permissions().mode() & 0o777;
+        if mode & 0o077 != 0 {
+            bail!(
+                "{} is accessible by other users (mode {:o}); run `chmod 600 {}`",
+                path.display(),
+                mode,
+                path.display()
+            );
+        }
+    }
+    Ok(())
+}
+
+fn read_key_file(path: &Path) -> Result<[u8; 32]> {
+    check_private(path)?;
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read key file {}", path.display()))?;
+    hex::decode(content.trim())
+        .ok()
+        .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+        .with_context(|| format!("{} is not a valid secrets key", path.display()))
+}
+
+/// Where `rotate` stages a new key file until the secrets sealed with it are written
+fn staged_key_path(key_path: &Path) -> PathBuf {
+    key_path.with_extension("key.new")
+}
+
+fn random_key() -> [u8; 32] {
+    let mut key = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut key);
+    key
+}
+
+/// Secrets sealed in a JSON file with an authenticated cipher
+pub struct EncryptedFileBackend {
+    path: PathBuf,
+    /// Also serializes read-modify-write of the file
+    source: Mutex<KeySource>,
+}
+
+impl EncryptedFileBackend {
+    pub fn new(path: impl Into<PathBuf>, source: KeySource) -> Self {
+        Self {
+            path: path.into(),
+            source: Mutex::new(source),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The file and its key, or `None` when nothing was stored yet
+    fn load(&self, source: &KeySource) -> Result<Option<(SealedFile, [u8; 32])>> {
+        if !self.path.exists() {
+            return Ok(None);
+        }
+        check_private(&self.path)?;
+        let content = std::fs::read_to_string(&self.path)
+            .with_context(|| format!("Failed to read {}", self.path.display()))?;
+        let file: SealedFile = serde_json::from_str(&content)
+            .with_context(|| format!("{} is corrupt", self.path.display()))?;
+        if file.version > FILE_VERSION {
+            bail!(
+                "{} was written by a newer hive (version {})",
+                self.path.display(),
+                file.version
+            );
+        }
+
+        let key =
+            match (file.kdf.as_str(), source) {
+                ("keyfile", KeySource::KeyFile(key_path)) => {
+                    self.key_file_key(key_path, &file.check)?
+                }
+                ("argon2id", KeySource::Passphrase(passphrase)) => {
+                    let salt = STANDARD.decode(file.salt.as_deref().unwrap_or_default())?;
+                    passphrase_key(passphrase, &salt)?
+                }
+                (kdf, _) => bail!(
+                "{} is sealed with {} but {} is configured; set secrets.use_passphrase accordingly",
+                self.path.display(),
+                if kdf == "argon2id" { "a passphrase" } else { "a key file" },
+                if source.kdf() == "argon2id" { "a passphrase" } else { "a key file" }
+            ),
+            };
+        unseal(&key, CHECK_NAME, &file.check).map_err(|_| {
+            anyhow!(
+                "Wrong {} for {}",
+                if source.kdf() == "argon2id" {
+                    "passphrase"
+                } else {
+                    "key file"
+                },
+                self.path.display()
+            )
+        })?;
+        Ok(Some((file, key)))
+    }
+
+    /// The key in `key_path`, or the staged key of an interrupted rotation
+    ///
+    /// `rotate` writes the secrets file before it installs the staged key, so a
+    /// staged key that opens the file is the current one and is installed now.
+    /// One that does not belongs to a rotation still writing (or that never
+    /// wrote) the file, and is left for `rotate` to replace.
+    fn key_file_key(&self, key_path: &Path, check: &Sealed) -> Result<[u8; 32]> {
+        let staged = staged_key_path(key_path);
+        if staged.exists() {
+            let key = read_key_file(&staged)?;
+            if unseal(&key, CHECK_NAME, check).is_ok() {
+                std::fs::rename(&staged, key_path)
+                    .with_context(|| format!("Failed to install {}", key_path.display()))?;
+                warn!(
+                    "Completed an interrupted key rotation of {}",
+                    self.path.display()
+                );
+                return Ok(key);
+            }
+        }
+        read_key_file(key_path)
+    }
+
+    /// An empty file sealed under `source`; `new_key` replaces an existing key file
+    fn create(source: &KeySource, new_key: bool) -> Result<(SealedFile, [u8; 32])> {
+        let (key, salt) = match source {
+            KeySource::KeyFile(path) if path.exists() && !new_key => (read_key_file(path)?, None),
+            KeySource::KeyFile(_) => (random_key(), None),
+            KeySource::Passphrase(passphrase) => {
+                let mut salt = [0u8; 16];
+                rand::thread_rng().fill_bytes(&mut salt);
+                (
+                    passphrase_key(passphrase, &salt)?,
+                    Some(STANDARD.encode(salt)),
+                )
+            }
+        };
+        let file = SealedFile {
+            version: FILE_VERSION,
+            kdf: source.kdf().to_string(),
+            salt,
+            check: seal(&key, CHECK_NAME, "hive")?,
+            secrets: BTreeMap::new(),
+        };
+        Ok((file, key))
+    }
+
+    fn save(&self, file: &SealedFile) -> Result<()> {
+        write_private(&self.path, serde_json::to_string_pretty(file)?.as_bytes())
+    }
+
+    /// Re-seal every secret under a new key; returns how many were re-sealed
+    ///
+    /// With a key file a fresh key is generated and synced to `<key>.new`
+    /// before the secrets are re-sealed with it, then moved over the old key.
+    /// If that move never happens, the next load finds the staged key and
+    /// installs it.
+    pub fn rotate(&self, new_source: KeySource) -> Result<usize> {
+        let mut source = self.source.lock().unwrap();
+        let secrets = match self.load(&source)? {
+            Some((file, key)) => file
+                .secrets
+                .iter()
+                .map(|(name, secret)| Ok((name.clone(), unseal(&key, name, &secret.sealed)?)))
+                .collect::<Result<Vec<_>>>()?,
+            None => Vec::new(),
+        };
+
+        let (mut file, key) = Self::create(&new_source, true)?;
+        let now = chrono::Utc::now().to_rfc3339();
+        for (name, value) in &secrets {
+            file.secrets.insert(
+                name.clone(),
+                SealedSecret {
+                    sealed: seal(&key, name, value)?,
+                    updated_at: now.clone(),
+                },
+            );
+        }
+
+        match &new_source {
+            KeySource::KeyFile(key_path) => {
+                let staged = staged_key_path(key_path);
+                write_private(&staged, hex::encode(key).as_bytes())?;
+                self.save(&file)?;
+                std::fs::rename(&staged, key_path)
+                    .with_context(|| format!("Failed to install {}", key_path.display()))?;
+            }
+            KeySource::Passphrase(_) => self.save(&file)?,
+        }
+        *source = new_source;
+        Ok(secrets.len())
+    }
+
+    /// When each stored secret was last written
+    pub fn updated_at(&self) -> Result<BTreeMap<String, String>> {
+        let source = self.source.lock().unwrap();
+        Ok(self
+            .load(&source)?
+            .map(|(file, _)| {
+                file.secrets
+                    .into_iter()
+                    .map(|(name, secret)| (name, secret.updated_at))
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+}
+
+impl SecretBackend for EncryptedFileBackend {
+    fn name(&self) -> &'static str {
+        "file"
+    }
+
+    fn get(&self, name: &str) -> Result<Option<String>> {
+        let source = self.source.lock().unwrap();
+        let Some((file, key)) = self.load(&source)? else {
+            return Ok(None);
+        };
+        file.secrets
+            .get(name)
+            .map(|secret| unseal(&key, name, &secret.sealed))
+            .transpose()
+    }
+
+    fn list(&self) -> Result<Vec<String>> {
+        Ok(self.updated_at()?.into_keys().collect())
+    }
+
+    fn is_writable(&self) -> bool {
+        true
+    }
+
+    fn set(&self, name: &str, value: &str) -> Result<()> {
+        let source = self.source.lock().unwrap();
+        let (mut file, key) = match self.load(&source)? {
+            Some(loaded) => loaded,
+            None => {
+                let created = Self::create(&source, false)?;
+                if let KeySource::KeyFile(key_path) = &*source {
+                    if !key_path.exists() {
+                        write_private(key_path, hex::encode(created.1).as_bytes())?;
+                    }
+                }
+                created
+            }
+        };
+        file.secrets.insert(
+            name.to_string(),
+            SealedSecret {
+                sealed: seal(&key, name, value)?,
+                updated_at: chrono::Utc::now().to_rfc3339(),
+            },
+        );
+        self.save(&file)
+    }
+
+    fn remove(&self, name: &str) -> Result<bool> {
+        let source = self.source.lock().unwrap();
+        let Some((mut file, _)) = self.load(&source)? else {
+            return Ok(false);
+        };
+        let removed = file.secrets.remove(name).is_some();
+        if removed {
+            self.save(&file)?;
+        }
+        Ok(removed)
+    }
+}
+
+/// Where a secret is stored, without its value
+#[derive(Debug, Clone, Serialize)]
+pub struct SecretInfo {
+    pub name: String,
+    /// Backend the value is read from
+    pub backend: &'static str,
+    /// Later backends that also hold it
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub shadowed: Vec<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<String>,
+}
+
+/// Secret backends in lookup order
+#[derive(Default)]
+pub struct SecretStore {
+    backends: Vec<Arc<dyn SecretBackend>>,
+    file: Option<Arc<EncryptedFileBackend>>,
+}
+
+impl SecretStore {
+    pub fn with_backend(mut self, backend: Arc<dyn SecretBackend>) -> Self {
+        self.backends.push(backend);
+        self
+    }
+
+    pub fn with_file(mut self, file: EncryptedFileBackend) -> Self {
+        let file = Arc::new(file);
+        self.backends.push(file.clone());
+        self.file = Some(file);
+        self
+    }
+
+    /// Backends named in `config`; the secrets file needs `passphrase` when
+    /// `use_passphrase` is set and is left out without one
+    pub fn from_config(config: &SecretsConfig, passphrase: Option<String>) -> Result<Self> {
+        let dir = get_hive_config_dir();
+        let mut store = Self::default();
+        for backend in &config.backends {
+            match backend.as_str() {
+                "file" => {
+                    let source = match (&passphrase, config.use_passphrase) {
+                        (_, false) => KeySource::KeyFile(dir.join(KEY_FILE)),
+                        (Some(passphrase), true) => KeySource::Passphrase(passphrase.clone()),
+                        (None, true) => {
+                            warn!(
+                                "{} is not set; encrypted secrets are unavailable",
+                                PASSPHRASE_ENV
+                            );
+                            continue;
+                        }
+                    };
+                    store =
+                        store.with_file(EncryptedFileBackend::new(dir.join(SECRETS_FILE), source));
+                }
+                "command" => {
+                    if let Some(command) = &config.api_key_command {
+                        store = store.with_backend(Arc::new(CommandBackend::new(command.clone())));
+                    }
+                }
+                "env" => store = store.with_backend(Arc::new(EnvBackend)),
+                other => bail!(
+                    "Unknown secrets backend '{}'; use file, command or env",
+                    other
+                ),
+            }
+        }
+        Ok(store)
+    }
+
+    /// The store configured in the `[secrets]` section of config.toml
+    ///
+    /// Reads the file directly so that synchronous code can resolve secrets.
+    pub fn open_default() -> Result<Self> {
+        Self::from_config(&load_config(), std::env::var(PASSPHRASE_ENV).ok())
+    }
+
+    /// The encrypted file backend, if configured
+    pub fn file(&self) -> Option<&EncryptedFileBackend> {
+        self.file.as_deref()
+    }
+
+    /// The value from the first backend that has `name`
+    pub fn get(&self, name: &str) -> Result<Option<String>> {
+        validate_name(name)?;
+        for backend in &self.backends {
+            if let Some(value) = backend.get(name)? {
+                return Ok(Some(value));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Store `name` in the first writable backend; returns that backend's name
+    pub fn set(&self, name: &str, value: &str) -> Result<&'static str> {
+        validate_name(name)?;
+        let backend = self
+            .backends
+            .iter()
+            .find(|backend| backend.is_writable())
+            .context("No writable secrets backend; add \"file\" to secrets.backends")?;
+        backend.set(name, value)?;
+        Ok(backend.name())
+    }
+
+    /// Remove `name` from every writable backend
+    pub fn remove(&self, name: &str) -> Result<bool> {
+        validate_name(name)?;
+        let mut removed = false;
+        for backend in self.backends.iter().filter(|backend| backend.is_writable()) {
+            removed |= backend.remove(name)?;
+        }
+        Ok(removed)
+    }
+
+    /// Every stored secret and where it comes from
+    pub fn list(&self) -> Result<Vec<SecretInfo>> {
+        let updated_at = match &self.file {
+            Some(file) => file.updated_at()?,
+            None => BTreeMap::new(),
+        };
+        let mut infos: Vec<SecretInfo> = Vec::new();
+        for backend in &self.backends {
+            for name in backend.list()? {
+                match infos.iter_mut().find(|info| info.name == name) {
+                    Some(info) => info.shadowed.push(backend.name()),
+                    None => infos.push(SecretInfo {
+                        updated_at: updated_at
+                            .get(&name)
+                            .filter(|_| backend.name() == "file")
+                            .cloned(),
+                        name,
+                        backend: backend.name(),
+                        shadowed: Vec::new(),
+                    }),
+                }
+            }
+        }
+        infos.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(infos)
+    }
+}
+
+/// The `[secrets]` section of config.toml, or the defaults
+pub fn load_config() -> SecretsConfig {
+    #[derive(Deserialize)]
+    struct ConfigFile {
+        secrets: Option<SecretsConfig>,
+    }
+
+    let path = get_hive_config_dir().join("config.toml");
+    let Ok(content) = std::fs::read_to_string(&path) else {
+        return SecretsConfig::default();
+    };
+    match toml::from_str::<ConfigFile>(&content) {
+        Ok(file) => file.secrets.unwrap_or_default(),
+        Err(e) => {
+            warn!("Ignoring [secrets] in {}: {}", path.display(), e);
+            SecretsConfig::default()
+        }
+    }
+}
+
+/// Move plaintext keys from the `configurations` table into `store`
+///
+/// Migrated rows keep their key with an empty value and `encrypted = 1`, so
+/// they still record that the key is configured. Returns how many moved.
+pub fn migrate_plaintext(conn: &Connection, store: &SecretStore) -> Result<usize> {
+    let mut migrated = 0;
+    for (name, _) in KNOWN_SECRETS {
+        let value: Option<String> = conn
+            .query_row(
+                "SELECT value FROM configurations WHERE key = ?1 AND COALESCE(encrypted, 0) = 0",
+                params![name],
+                |row| row.get(0),
+            )
+            .optional()?;
+        let Some(value) = value.filter(|value| !value.is_empty()) else {
+            continue;
+        };
+        store.set(name, &value)?;
+        conn.execute(
+            "UPDATE configurations SET value = '', encrypted = 1, updated_at = CURRENT_TIMESTAMP
+             WHERE key = ?1",
+            params![name],
+        )?;
+        migrated += 1;
+    }
+    if migrated > 0 {
+        debug!("Moved {} plaintext keys into the secret store", migrated);
+    }
+    Ok(migrated)
+}
+
+/// A `configurations` value that may have been moved into the secret store
+///
+/// Plaintext values are returned as they are; migrated (empty) ones are
+/// looked up in the default store.
+pub fn resolve_configuration(name: &str, stored: Option<String>) -> Result<Option<String>> {
+    match stored.filter(|value| !value.is_empty()) {
+        Some(value) => Ok(Some(value)),
+        None => SecretStore::open_default()?.get(name),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn file_store(dir: &Path) -> SecretStore {
+        SecretStore::default().with_file(EncryptedFileBackend::new(
+            dir.join(SECRETS_FILE),
+            KeySource::KeyFile(dir.join(KEY_FILE)),
+        ))
+    }
+
+    #[test]
+    fn test_file_round_trip() -> Result<()> {
+        let dir = TempDir::new()?;
+        let store = file_store(dir.path());
+        assert_eq!(store.get("openrouter_api_key")?, None);
+
+        assert_eq!(store.set("openrouter_api_key", "sk-or-v1-secret")?, "file");
+        assert_eq!(
+            store.get("openrouter_api_key")?.as_deref(),
+            Some("sk-or-v1-secret")
+        );
+
+        // Nothing readable on disk, and a fresh store opens it with the key file
+        let content = std::fs::read_to_string(dir.path().join(SECRETS_FILE))?;
+        assert!(!content.contains("sk-or-v1-secret"));
+        assert_eq!(
+            file_store(dir.path()).get("openrouter_api_key")?.as_deref(),
+            Some("sk-or-v1-secret")
+        );
+
+        let listed = store.list()?;
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].backend, "file");
+        assert!(!serde_json::to_string(&listed)?.contains("sk-or-v1-secret"));
+
+        assert!(store.remove("openrouter_api_key")?);
+        assert_eq!(store.get("openrouter_api_key")?, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_wrong_key_and_tampering() -> Result<()> {
+        let dir = TempDir::new()?;
+        let path = dir.path().join(SECRETS_FILE);
+        let sealed = EncryptedFileBackend::new(&path, KeySource::Passphrase("right".into()));
+        sealed.set("anthropic_api_key", "sk-ant-secret")?;
+
+        let wrong = EncryptedFileBackend::new(&path, KeySource::Passphrase("wrong".into()));
+        assert!(wrong.get("anthropic_api_key").is_err());
+
+        // A value moved to another name no longer authenticates
+        let mut file: SealedFile = serde_json::from_str(&std::fs::read_to_string(&path)?)?;
+        let value = file.secrets["anthropic_api_key"].clone();
+        file.secrets.insert("hive_license_key".to_string(), value);
+        sealed.save(&file)?;
+        assert!(sealed.get("hive_license_key").is_err());
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_key_file_permissions() -> Result<()> {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = TempDir::new()?;
+        let store = file_store(dir.path());
+        store.set("hive_license_key", "HIVE-1234")?;
+        let key_path = dir.path().join(KEY_FILE);
+        assert_eq!(
+            std::fs::metadata(&key_path)?.permissions().mode() & 0o777,
+            0o600
+        );
+
+        std::fs::set_permissions(&key_path, std::fs::Permissions::from_mode(0o644))?;
+        assert!(store.get("hive_license_key").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_rotate() -> Result<()> {
+        let dir = TempDir::new()?;
+        let store = file_store(dir.path());
+        store.set("openrouter_api_key", "sk-or-v1-secret")?;
+        let old_key = std::fs::read_to_string(dir.path().join(KEY_FILE))?;
+
+        let file = store.file().unwrap();
+        assert_eq!(
+            file.rotate(KeySource::KeyFile(dir.path().join(KEY_FILE)))?,
+            1
+        );
+        assert_ne!(std::fs::read_to_string(dir.path().join(KEY_FILE))?, old_key);
+        assert_eq!(
+            file_store(dir.path()).get("openrouter_api_key")?.as_deref(),
+            Some("sk-or-v1-secret")
+        );
+
+        // Interrupted after re-sealing the file but before installing the key
+        let new_key = std::fs::read_to_string(dir.path().join(KEY_FILE))?;
+        std::fs::rename(
+            dir.path().join(KEY_FILE),
+            staged_key_path(&dir.path().join(KEY_FILE)),
+        )?;
+        write_private(&dir.path().join(KEY_FILE), old_key.as_bytes())?;
+        assert_eq!(
+            file_store(dir.path()).get("openrouter_api_key")?.as_deref(),
+            Some("sk-or-v1-secret")
+        );
+        assert_eq!(std::fs::read_to_string(dir.path().join(KEY_FILE))?, new_key);
+        assert!(!staged_key_path(&dir.path().join(KEY_FILE)).exists());
+
+        file.rotate(KeySource::Passphrase("hunter2".into()))?;
+        let reopened = EncryptedFileBackend::new(
+            dir.path().join(SECRETS_FILE),
+            KeySource::Passphrase("hunter2".into()),
+        );
+        assert_eq!(
+            reopened.get("openrouter_api_key")?.as_deref(),
+            Some("sk-or-v1-secret")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_migrate_plaintext() -> Result<()> {
+        let dir = TempDir::new()?;
+        let store = file_store(dir.path());
+        let conn = Connection::open_in_memory()?;
+        conn.execute_batch(
+            "CREATE TABLE configurations (key TEXT PRIMARY KEY, value TEXT NOT NULL,
+                 encrypted BOOLEAN DEFAULT 0, updated_at TEXT);
+             INSERT INTO configurations (key, value) VALUES
+                 ('openrouter_api_key', 'sk-or-v1-secret'), ('theme', 'dark');",
+        )?;
+
+        assert_eq!(migrate_plaintext(&conn, &store)?, 1);
+        assert_eq!(migrate_plaintext(&conn, &store)?, 0);
+        let (value, encrypted): (String, bool) = conn.query_row(
+            "SELECT value, encrypted FROM configurations WHERE key = 'openrouter_api_key'",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+        assert_eq!((value.as_str(), encrypted), ("", true));
+        assert_eq!(
+            store.get("openrouter_api_key")?.as_deref(),
+            Some("sk-or-v1-secret")
+        );
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_read_only_backends() {
+        let store = SecretStore::default().with_backend(Arc::new(CommandBackend::new(
+            "test {name} = hive_license_key && echo HIVE-from-command",
+        )));
+        assert_eq!(
+            store.get("hive_license_key").unwrap().as_deref(),
+            Some("HIVE-from-command")
+        );
+        assert_eq!(store.get("anthropic_api_key").unwrap(), None);
+        assert!(store.set("hive_license_key", "x").is_err());
+        assert!(store.get("bad name; rm -rf /").is_err());
+    }
+}
@@ -14,6 +14,7 @@ use std::sync::Arc;
 use tokio::sync::RwLock;
 
 use crate::{
+    analysis::walker::RepositoryWalker,
     cache::{self, CacheCategory, CacheKey},
     core::{
         ast::{AstEngine, Symbol, SymbolKind},
@@ -426,29 +427,13 @@ impl SemanticIndex {
 
     /// Discover files in a directory
     async fn discover_files(&self, path: &Path) -> Result<Vec<PathBuf>> {
-        let mut files = vec![];
-        let mut stack = vec![path.to_path_buf()];
-
-        while let Some(dir) = stack.pop() {
-            let mut entries = tokio::fs::read_dir(&dir).await?;
-
-            while let Some(entry) = entries.next_entry().await? {
-                let path = entry.path();
-
-                if path.is_dir() {
-                    // Skip common directories
-                    let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
-                    if !matches!(
-                        name,
-                        ".git" | "target" | "node_modules" | ".venv" | "__pycache__"
-                    ) {
-                        stack.push(path);
-                    }
-                } else if self.is_source_file(&path) {
-                    files.push(path);
-                }
-            }
-        }
+        let files = RepositoryWalker::new(path)
+            .walk_async()
+            .await?
+            .into_iter()
+            .map(|file| file.path)
+            .filter(|path| self.is_source_file(path))
+            .collect();
 
         Ok(files)
     }
@@ -10,10 +10,17 @@ use std::path::{Path, PathBuf};
 use tokio::fs as async_fs;
 use tracing::{debug, info, warn};
 
+use crate::core::release_manifest::{
+    current_platform, Artifact, Keyring, ReleaseManifest, UpdateBundle, MANIFEST_FILE,
+    SIGNATURE_FILE,
+};
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ReleaseInfo {
     pub version: String,
+    /// Release directory holding the signed manifest and the binaries
     pub download_url: String,
+    /// Unsigned; installs check the digest in the signed manifest instead
     pub checksum: String,
     pub changelog: String,
     pub release_date: DateTime<Utc>,
@@ -61,6 +68,7 @@ pub struct AutoUpdater {
     config: UpdateConfig,
     current_exe: PathBuf,
     backup_dir: PathBuf,
+    keyring: Keyring,
 }
 
 impl AutoUpdater {
@@ -84,6 +92,7 @@ impl AutoUpdater {
             config,
             current_exe,
             backup_dir,
+            keyring: Keyring::builtin(),
         })
     }
 
@@ -92,6 +101,12 @@ impl AutoUpdater {
         self
     }
 
+    /// Trust other release signing keys than the compiled-in ones
+    pub fn with_keyring(mut self, keyring: Keyring) -> Self {
+        self.keyring = keyring;
+        self
+    }
+
     /// Check for available updates
     pub async fn check_for_updates(&self) -> Result<UpdateInfo> {
         let current_version = env!("CARGO_PKG_VERSION");
@@ -177,12 +192,82 @@ impl AutoUpdater {
     }
 
     /// Install a specific update
+    ///
+    /// The release manifest must carry a valid signature from a trusted key,
+    /// name a version newer than the running one, and the binary must match
+    /// its digest, before anything is replaced.
     pub async fn install_update(&self, release_info: &ReleaseInfo) -> Result<()> {
         info!(
             "Installing Hive AI update to version {}",
             release_info.version
         );
 
+        let manifest = self.fetch_manifest(&release_info.download_url).await?;
+        if manifest.version != release_info.version {
+            return Err(anyhow::anyhow!(
+                "Signed manifest is for version {}, expected {}",
+                manifest.version,
+                release_info.version
+            ));
+        }
+        // An old signed manifest replayed by a mirror must not downgrade us
+        if !is_newer(&manifest.version)? {
+            return Err(anyhow::anyhow!(
+                "Signed manifest version {} is not newer than {}",
+                manifest.version,
+                env!("CARGO_PKG_VERSION")
+            ));
+        }
+        let artifact = manifest.artifact(current_platform()?)?;
+
+        // Download and verify new binary
+        let temp_binary = self
+            .download_binary(&release_info.download_url, artifact)
+            .await?;
+
+        self.install_binary(&temp_binary).await?;
+
+        info!("✅ Successfully updated to version {}", manifest.version);
+        info!("📝 Changelog: {}", manifest.changelog);
+
+        Ok(())
+    }
+
+    /// Install from an offline bundle (see [`UpdateBundle`])
+    ///
+    /// Versions that are not newer than the running one need `force`.
+    pub async fn install_bundle(&self, bundle_path: &Path, force: bool) -> Result<ReleaseManifest> {
+        let bundle = UpdateBundle::open(bundle_path, &self.keyring)?;
+        let manifest = bundle.manifest().clone();
+        info!(
+            "Update bundle {} verified with key {}",
+            manifest.version,
+            bundle.key_id()
+        );
+
+        if !force && !is_newer(&manifest.version)? {
+            return Err(anyhow::anyhow!(
+                "Bundle version {} is not newer than {}; use --force to install it anyway",
+                manifest.version,
+                env!("CARGO_PKG_VERSION")
+            ));
+        }
+
+        // Stage next to the binary so the final rename stays on one filesystem
+        let temp_binary = self.temp_binary_path();
+        async_fs::copy(bundle.artifact(current_platform()?)?, &temp_binary)
+            .await
+            .context("Failed to stage binary from bundle")?;
+        self.make_executable(&temp_binary).await?;
+
+        self.install_binary(&temp_binary).await?;
+
+        info!("✅ Successfully updated to version {}", manifest.version);
+        Ok(manifest)
+    }
+
+    /// Back up the running binary and swap in a verified one
+    async fn install_binary(&self, new_binary: &Path) -> Result<()> {
         // Create backup directory
         async_fs::create_dir_all(&self.backup_dir)
             .await
@@ -192,102 +277,101 @@ impl AutoUpdater {
         let backup_path = self.create_backup().await?;
         info!("Created backup at: {}", backup_path.display());
 
-        // Download new binary
-        let temp_binary = self.download_binary(release_info).await?;
-
-        // Verify checksum
-        self.verify_checksum(&temp_binary, &release_info.checksum)
-            .await?;
-
         // Atomic replacement
-        self.replace_binary(&temp_binary).await?;
+        self.replace_binary(new_binary).await?;
 
         // Clean up
-        async_fs::remove_file(temp_binary).await.ok();
-
-        info!(
-            "✅ Successfully updated to version {}",
-            release_info.version
-        );
-        info!("📝 Changelog: {}", release_info.changelog);
+        async_fs::remove_file(new_binary).await.ok();
 
         Ok(())
     }
 
-    /// Download binary from release URL
-    async fn download_binary(&self, release_info: &ReleaseInfo) -> Result<PathBuf> {
-        let platform_url = self.get_platform_download_url(&release_info.download_url)?;
+    /// Download the release manifest and check its signatures
+    async fn fetch_manifest(&self, base_url: &str) -> Result<ReleaseManifest> {
+        let manifest = self
+            .download(&format!("{}/{}", base_url, MANIFEST_FILE))
+            .await?;
+        let signatures = self
+            .download(&format!("{}/{}", base_url, SIGNATURE_FILE))
+            .await?;
 
-        info!("Downloading from: {}", platform_url);
+        let (manifest, key_id) = self.keyring.verify(&manifest, &signatures)?;
+        debug!("Release manifest verified with key {}", key_id);
+        Ok(manifest)
+    }
 
+    async fn download(&self, url: &str) -> Result<Vec<u8>> {
         let response = self
             .client
-            .get(&platform_url)
+            .get(url)
             .send()
             .await
-            .context("Failed to download binary")?;
+            .with_context(|| format!("Failed to download {}", url))?;
 
         if !response.status().is_success() {
             return Err(anyhow::anyhow!(
-                "Download failed with status {}",
+                "Download of {} failed with status {}",
+                url,
                 response.status()
             ));
         }
 
-        let temp_path = self
-            .current_exe
-            .parent()
-            .unwrap_or_else(|| Path::new("."))
-            .join(format!("hive-update-{}", uuid::Uuid::new_v4()));
-
-        let bytes = response
+        Ok(response
             .bytes()
             .await
-            .context("Failed to read download response")?;
+            .context("Failed to read download response")?
+            .to_vec())
+    }
+
+    /// Download a release binary and verify it against the manifest
+    async fn download_binary(&self, base_url: &str, artifact: &Artifact) -> Result<PathBuf> {
+        let url = format!("{}/{}", base_url, artifact.file);
+
+        info!("Downloading from: {}", url);
+
+        let bytes = self.download(&url).await?;
+        if bytes.len() as u64 != artifact.size {
+            return Err(anyhow::anyhow!(
+                "Downloaded {} bytes, the manifest says {}",
+                bytes.len(),
+                artifact.size
+            ));
+        }
 
+        let temp_path = self.temp_binary_path();
         async_fs::write(&temp_path, bytes)
             .await
             .context("Failed to write downloaded binary")?;
 
-        // Make executable on Unix systems
-        #[cfg(unix)]
-        {
-            use std::os::unix::fs::PermissionsExt;
-            let mut perms = async_fs::metadata(&temp_path).await?.permissions();
-            perms.set_mode(0o755);
-            async_fs::set_permissions(&temp_path, perms).await?;
+        if let Err(e) = self.verify_checksum(&temp_path, &artifact.sha256).await {
+            async_fs::remove_file(&temp_path).await.ok();
+            return Err(e);
         }
+        self.make_executable(&temp_path).await?;
 
         Ok(temp_path)
     }
 
-    /// Get platform-specific download URL
-    fn get_platform_download_url(&self, base_url: &str) -> Result<String> {
-        let platform = match env::consts::OS {
-            "macos" => match env::consts::ARCH {
-                "aarch64" => "macos-arm64",
-                "x86_64" => "macos-x64",
-                arch => return Err(anyhow::anyhow!("Unsupported macOS architecture: {}", arch)),
-            },
-            "linux" => match env::consts::ARCH {
-                "aarch64" => "linux-arm64",
-                "x86_64" => "linux-x64",
-                arch => return Err(anyhow::anyhow!("Unsupported Linux architecture: {}", arch)),
-            },
-            "windows" => match env::consts::ARCH {
-                "x86_64" => "windows-x64.exe",
-                arch => {
-                    return Err(anyhow::anyhow!(
-                        "Unsupported Windows architecture: {}",
-                        arch
-                    ))
-                }
-            },
-            os => return Err(anyhow::anyhow!("Unsupported operating system: {}", os)),
-        };
+    fn temp_binary_path(&self) -> PathBuf {
+        self.current_exe
+            .parent()
+            .unwrap_or_else(|| Path::new("."))
+            .join(format!("hive-update-{}", uuid::Uuid::new_v4()))
+    }
+
+    /// Make executable on Unix systems
+    async fn make_executable(&self, path: &Path) -> Result<()> {
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mut perms = async_fs::metadata(path).await?.permissions();
+            perms.set_mode(0o755);
+            async_fs::set_permissions(path, perms).await?;
+        }
+        #[cfg(not(unix))]
+        let _ = path;
 
-        let url = format!("{}/hive-{}", base_url, platform);
-        Ok(url)
+        Ok(())
     }
 
     /// Verify downloaded binary checksum
@@ -460,6 +544,31 @@ impl AutoUpdater {
     }
 }
 
+/// Whether `version` is a newer semver release than the running binary
+fn is_newer(version: &str) -> Result<bool> {
+    let current_ver = semver::Version::parse(env!("CARGO_PKG_VERSION"))
+        .context("Failed to parse current version")?;
+    let ver = semver::Version::parse(version)
+        .with_context(|| format!("Failed to parse release version '{}'", version))?;
+    Ok(ver > current_ver)
+}
+
+#[cfg(test)]
+mod version_tests {
+    use super::*;
+
+    #[test]
+    fn test_is_newer() {
+        let current = semver::Version::parse(env!("CARGO_PKG_VERSION")).unwrap();
+        let next = semver::Version::new(current.major, current.minor, current.patch + 1);
+
+        assert!(is_newer(&next.to_string()).unwrap());
+        assert!(!is_newer(env!("CARGO_PKG_VERSION")).unwrap());
+        assert!(!is_newer("0.0.0").unwrap());
+        assert!(is_newer("not-a-version").is_err());
+    }
+}
+
 #[cfg(all(test, feature = "legacy-tests"))]
 mod tests {
     use super::*;
@@ -478,6 +587,7 @@ mod tests {
             config: UpdateConfig::default(),
             current_exe: fake_exe,
             backup_dir,
+            keyring: Keyring::default(),
         };
 
         let backup_path = updater.create_backup().await.unwrap();
@@ -505,6 +615,7 @@ mod tests {
             config: UpdateConfig::default(),
             current_exe: temp_dir.path().join("dummy"),
             backup_dir: temp_dir.path().join("backups"),
+            keyring: Keyring::default(),
         };
 
         // Should succeed with correct checksum
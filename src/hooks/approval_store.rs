@@ -0,0 +1,474 @@
+//! Approval Queue - SQLite persistence for hook approval requests
+//!
+//! Requests raised by a hook in one `hive` process can be listed and decided
+//! from another terminal. Escalation and expiry deadlines are stored as
+//! timestamps, so they survive restarts and are applied by whichever process
+//! looks at the queue next.
+
+use super::approval_workflow::{
+    ApprovalDecision, ApprovalRequest, ApprovalStatus, EscalationLevel,
+};
+use crate::core::database::DatabaseManager;
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, TimeZone, Utc};
+use rusqlite::{params, OptionalExtension, TransactionBehavior};
+use serde::Serialize;
+use std::sync::Arc;
+
+/// A persisted approval request and where it stands
+#[derive(Debug, Clone, Serialize)]
+pub struct StoredApproval {
+    pub request: ApprovalRequest,
+    pub status: ApprovalStatus,
+    /// When the next escalation is due; `None` when none is scheduled
+    pub escalate_at: Option<DateTime<Utc>>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Approval requests shared by every process using the same database
+pub struct ApprovalStore {
+    database: Arc<DatabaseManager>,
+}
+
+impl ApprovalStore {
+    /// Open the store, creating its table if needed
+    pub fn new(database: Arc<DatabaseManager>) -> Result<Self> {
+        let conn = database.get_connection()?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS hook_approvals (
+                id TEXT PRIMARY KEY,
+                status TEXT NOT NULL,
+                request TEXT NOT NULL,
+                expires_at INTEGER,
+                escalate_at INTEGER,
+                created_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS idx_hook_approvals_status ON hook_approvals(status);",
+        )?;
+        Ok(Self { database })
+    }
+
+    /// Add a request to the queue
+    pub fn insert(
+        &self,
+        request: &ApprovalRequest,
+        status: &ApprovalStatus,
+        escalate_at: Option<DateTime<Utc>>,
+    ) -> Result<()> {
+        let conn = self.database.get_connection()?;
+        conn.execute(
+            "INSERT INTO hook_approvals
+                (id, status, request, expires_at, escalate_at, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                request.id,
+                status.as_str(),
+                serde_json::to_string(request)?,
+                request.expires_at.map(|t| t.timestamp()),
+                escalate_at.map(|t| t.timestamp()),
+                request.created_at.timestamp(),
+                Utc::now().timestamp(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Full id of the request matching an id or unique id prefix
+    pub fn resolve(&self, id: &str) -> Result<Option<String>> {
+        let conn = self.database.get_connection()?;
+        let matches: Vec<String> = conn
+            .prepare("SELECT id FROM hook_approvals WHERE id LIKE ?1 || '%' LIMIT 2")?
+            .query_map(params![id], |row| row.get(0))?
+            .collect::<Result<_, _>>()?;
+        match matches.as_slice() {
+            [] => Ok(None),
+            [only] => Ok(Some(only.clone())),
+            _ => bail!("Approval id '{}' is ambiguous; use more characters", id),
+        }
+    }
+
+    /// A request by id or unique id prefix, if there is one
+    pub fn find(&self, id: &str) -> Result<Option<StoredApproval>> {
+        let Some(id) = self.resolve(id)? else {
+            return Ok(None);
+        };
+        let conn = self.database.get_connection()?;
+        conn.query_row(
+            "SELECT request, status, escalate_at, updated_at FROM hook_approvals WHERE id = ?1",
+            params![id],
+            read_row,
+        )
+        .optional()?
+        .transpose()
+    }
+
+    /// A request by id or unique id prefix
+    pub fn load(&self, id: &str) -> Result<StoredApproval> {
+        self.find(id)?
+            .with_context(|| format!("No approval request matching '{}'", id))
+    }
+
+    /// Requests in `statuses` (all when empty), newest first
+    pub fn list(&self, statuses: &[ApprovalStatus], limit: usize) -> Result<Vec<StoredApproval>> {
+        let conn = self.database.get_connection()?;
+        let rows = conn
+            .prepare(
+                "SELECT request, status, escalate_at, updated_at FROM hook_approvals
+                 ORDER BY created_at DESC",
+            )?
+            .query_map([], read_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut approvals = Vec::new();
+        for row in rows {
+            let approval = row?;
+            if statuses.is_empty() || statuses.contains(&approval.status) {
+                approvals.push(approval);
+                if approvals.len() == limit {
+                    break;
+                }
+            }
+        }
+        Ok(approvals)
+    }
+
+    /// Record a decision and work out the resulting status
+    ///
+    /// The read and write happen in one immediate transaction, so decisions
+    /// landing from several processes at once are all kept. A request past
+    /// its expiry becomes `Expired` instead of taking the decision. Deciders
+    /// outside the request's approvers and its current escalation level's are
+    /// refused.
+    pub fn record_decision(
+        &self,
+        id: &str,
+        decision: ApprovalDecision,
+        escalation_levels: &[EscalationLevel],
+    ) -> Result<StoredApproval> {
+        self.modify(id, |approval| {
+            if approval.status.is_final() {
+                bail!(
+                    "Approval request {} is already {}",
+                    approval.request.id,
+                    approval.status.as_str()
+                );
+            }
+            approval
+                .request
+                .check_approver(&decision.approver, escalation_levels)?;
+            if approval.request.expires_at.is_some_and(|t| t <= Utc::now()) {
+                approval.status = ApprovalStatus::Expired;
+                approval.escalate_at = None;
+                return Ok(());
+            }
+
+            let rejected = decision.decision == ApprovalStatus::Rejected;
+            approval.request.received_approvals.push(decision);
+            if rejected {
+                approval.status = ApprovalStatus::Rejected;
+            } else if approval.request.has_sufficient_approvals() {
+                approval.status = ApprovalStatus::Approved;
+            }
+            if approval.status.is_final() {
+                approval.escalate_at = None;
+            }
+            Ok(())
+        })
+    }
+
+    /// Move an open request to `status`; `None` if it was already final
+    pub fn finish(&self, id: &str, status: ApprovalStatus) -> Result<Option<StoredApproval>> {
+        let mut changed = false;
+        let approval = self.modify(id, |approval| {
+            if !approval.status.is_final() {
+                approval.status = status;
+                approval.escalate_at = None;
+                changed = true;
+            }
+            Ok(())
+        })?;
+        Ok(changed.then_some(approval))
+    }
+
+    /// Raise an open request to escalation `level` if its escalation is still due
+    ///
+    /// Returns `None` when another process got there first.
+    pub fn escalate(
+        &self,
+        id: &str,
+        level: u32,
+        next_escalation: Option<DateTime<Utc>>,
+    ) -> Result<Option<StoredApproval>> {
+        let mut changed = false;
+        let approval = self.modify(id, |approval| {
+            let due = approval.escalate_at.is_some_and(|t| t <= Utc::now());
+            if !approval.status.is_final() && due {
+                approval.request.current_escalation_level = level;
+                approval.status = ApprovalStatus::Escalated;
+                approval.escalate_at = next_escalation;
+                changed = true;
+            }
+            Ok(())
+        })?;
+        Ok(changed.then_some(approval))
+    }
+
+    /// Open requests whose expiry has passed
+    pub fn due_expiries(&self, now: DateTime<Utc>) -> Result<Vec<String>> {
+        self.due_ids("expires_at", now)
+    }
+
+    /// Open requests whose next escalation is due
+    pub fn due_escalations(&self, now: DateTime<Utc>) -> Result<Vec<StoredApproval>> {
+        self.due_ids("escalate_at", now)?
+            .iter()
+            .map(|id| self.load(id))
+            .collect()
+    }
+
+    fn due_ids(&self, column: &str, now: DateTime<Utc>) -> Result<Vec<String>> {
+        let conn = self.database.get_connection()?;
+        let ids: Vec<String> = conn
+            .prepare(&format!(
+                "SELECT id FROM hook_approvals
+                 WHERE status IN ('pending', 'escalated') AND {} <= ?1",
+                column
+            ))?
+            .query_map(params![now.timestamp()], |row| row.get(0))?
+            .collect::<Result<_, _>>()?;
+        Ok(ids)
+    }
+
+    /// Read, change and write back one request in an immediate transaction
+    fn modify(
+        &self,
+        id: &str,
+        change: impl FnOnce(&mut StoredApproval) -> Result<()>,
+    ) -> Result<StoredApproval> {
+        let id = self
+            .resolve(id)?
+            .with_context(|| format!("No approval request matching '{}'", id))?;
+        let mut conn = self.database.get_connection()?;
+        let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+        let mut approval = tx.query_row(
+            "SELECT request, status, escalate_at, updated_at FROM hook_approvals WHERE id = ?1",
+            params![id],
+            read_row,
+        )??;
+
+        change(&mut approval)?;
+        approval.updated_at = Utc::now();
+        tx.execute(
+            "UPDATE hook_approvals
+             SET status = ?2, request = ?3, escalate_at = ?4, updated_at = ?5
+             WHERE id = ?1",
+            params![
+                id,
+                approval.status.as_str(),
+                serde_json::to_string(&approval.request)?,
+                approval.escalate_at.map(|t| t.timestamp()),
+                approval.updated_at.timestamp(),
+            ],
+        )?;
+        tx.commit()?;
+        Ok(approval)
+    }
+}
+
+/// A row, with JSON and status errors kept apart from SQLite errors
+fn read_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<Result<StoredApproval>> {
+    let request: String = row.get(0)?;
+    let status: String = row.get(1)?;
+    let escalate_at: Option<i64> = row.get(2)?;
+    let updated_at: i64 = row.get(3)?;
+    Ok(to_approval(&request, &status, escalate_at, updated_at))
+}
+
+fn to_approval(
+    request: &str,
+    status: &str,
+    escalate_at: Option<i64>,
+    updated_at: i64,
+) -> Result<StoredApproval> {
+    Ok(StoredApproval {
+        request: serde_json::from_str(request).context("Corrupt approval request")?,
+        status: status.parse()?,
+        escalate_at: escalate_at.and_then(|t| Utc.timestamp_opt(t, 0).single()),
+        updated_at: Utc
+            .timestamp_opt(updated_at, 0)
+            .single()
+            .unwrap_or_else(Utc::now),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::database::DatabaseConfig;
+    use crate::hooks::approval_workflow::{
+        ApprovalPriority, ApprovalWorkflow, ApprovalWorkflowConfig,
+    };
+    use crate::hooks::HookId;
+    use std::collections::HashMap;
+    use tempfile::TempDir;
+
+    async fn test_store() -> (ApprovalStore, Arc<DatabaseManager>, TempDir) {
+        let dir = TempDir::new().unwrap();
+        let database = Arc::new(
+            DatabaseManager::new(DatabaseConfig {
+                path: dir.path().join("approvals.db"),
+                ..DatabaseConfig::default()
+            })
+            .await
+            .unwrap(),
+        );
+        (ApprovalStore::new(database.clone()).unwrap(), database, dir)
+    }
+
+    fn request(id: &str, approvers: &[&str], expires_in: i64) -> ApprovalRequest {
+        ApprovalRequest {
+            id: id.to_string(),
+            hook_id: HookId("deploy-guard".to_string()),
+            request_type: "hook_execution".to_string(),
+            description: "Deploy to production".to_string(),
+            requested_by: "ci".to_string(),
+            created_at: Utc::now(),
+            expires_at: Some(Utc::now() + chrono::Duration::seconds(expires_in)),
+            metadata: HashMap::new(),
+            priority: ApprovalPriority::High,
+            required_approvers: approvers.iter().map(|a| a.to_string()).collect(),
+            received_approvals: Vec::new(),
+            current_escalation_level: 0,
+            notification_count: 0,
+            last_notification_at: None,
+        }
+    }
+
+    fn decision(approver: &str, decision: ApprovalStatus) -> ApprovalDecision {
+        ApprovalDecision {
+            approver: approver.to_string(),
+            decision,
+            reason: None,
+            timestamp: Utc::now(),
+            metadata: HashMap::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_decisions_from_another_store_instance() {
+        let (store, database, _dir) = test_store().await;
+        store
+            .insert(
+                &request("a1b2c3", &["alice", "bob"], 600),
+                &ApprovalStatus::Pending,
+                None,
+            )
+            .unwrap();
+
+        // A second process opening the same database
+        let other = ApprovalStore::new(database).unwrap();
+        let err = other
+            .record_decision("a1b", decision("mallory", ApprovalStatus::Approved), &[])
+            .unwrap_err();
+        assert!(err.to_string().contains("mallory may not decide"));
+        let approval = other
+            .record_decision("a1b", decision("alice", ApprovalStatus::Approved), &[])
+            .unwrap();
+        assert_eq!(approval.status, ApprovalStatus::Pending);
+        assert_eq!(approval.request.remaining_approvers(), vec!["bob"]);
+
+        let approval = other
+            .record_decision("a1b", decision("bob", ApprovalStatus::Approved), &[])
+            .unwrap();
+        assert_eq!(approval.status, ApprovalStatus::Approved);
+        assert_eq!(
+            store.load("a1b2c3").unwrap().status,
+            ApprovalStatus::Approved
+        );
+        assert!(store
+            .record_decision("a1b2c3", decision("eve", ApprovalStatus::Rejected), &[])
+            .is_err());
+
+        assert_eq!(store.list(&[ApprovalStatus::Pending], 10).unwrap().len(), 0);
+        assert_eq!(store.list(&[], 10).unwrap().len(), 1);
+        assert!(store.find("zz").unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_waiting_workflow_resumes_on_decision_from_another_process() {
+        let (store, database, _dir) = test_store().await;
+        let mut config = ApprovalWorkflowConfig::default();
+        config.notifications.enabled = false;
+        let waiting = ApprovalWorkflow::with_config(config.clone()).with_store(Arc::new(store));
+        let id = waiting
+            .submit_approval_request(request("f00d", &["alice"], 600))
+            .await
+            .unwrap();
+
+        let waiter = tokio::spawn(async move { waiting.wait_for_decision(&id).await });
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        assert!(!waiter.is_finished());
+
+        let deciding = ApprovalWorkflow::with_config(config)
+            .with_store(Arc::new(ApprovalStore::new(database).unwrap()));
+        let result = deciding
+            .process_approval_decision("f00d", "alice", ApprovalStatus::Approved, None, None)
+            .await
+            .unwrap();
+        assert!(result.approved);
+        assert_eq!(waiter.await.unwrap().unwrap(), ApprovalStatus::Approved);
+    }
+
+    #[tokio::test]
+    async fn test_deadlines_are_persisted() {
+        let (store, _database, _dir) = test_store().await;
+        let past = Utc::now() - chrono::Duration::seconds(5);
+        store
+            .insert(&request("late", &[], -5), &ApprovalStatus::Pending, None)
+            .unwrap();
+        store
+            .insert(
+                &request("slow", &[], 600),
+                &ApprovalStatus::Pending,
+                Some(past),
+            )
+            .unwrap();
+
+        assert_eq!(store.due_expiries(Utc::now()).unwrap(), vec!["late"]);
+        let expired = store
+            .record_decision("late", decision("alice", ApprovalStatus::Approved), &[])
+            .unwrap();
+        assert_eq!(expired.status, ApprovalStatus::Expired);
+
+        let due = store.due_escalations(Utc::now()).unwrap();
+        assert_eq!(due.len(), 1);
+        let next = Utc::now() + chrono::Duration::seconds(120);
+        let escalated = store.escalate("slow", 1, Some(next)).unwrap().unwrap();
+        assert_eq!(escalated.status, ApprovalStatus::Escalated);
+        assert_eq!(escalated.request.current_escalation_level, 1);
+        // Already escalated; a second process does nothing
+        assert!(store.escalate("slow", 1, None).unwrap().is_none());
+        assert!(store.due_escalations(Utc::now()).unwrap().is_empty());
+
+        // Once escalated, the level's approvers decide
+        let levels = ApprovalWorkflowConfig::default()
+            .escalation
+            .escalation_levels;
+        assert!(store
+            .record_decision("slow", decision("bob", ApprovalStatus::Rejected), &levels)
+            .is_err());
+        let rejected = store
+            .record_decision(
+                "slow",
+                decision("supervisor", ApprovalStatus::Rejected),
+                &levels,
+            )
+            .unwrap();
+        assert_eq!(rejected.status, ApprovalStatus::Rejected);
+        assert!(store
+            .finish("slow", ApprovalStatus::Expired)
+            .unwrap()
+            .is_none());
+    }
+}
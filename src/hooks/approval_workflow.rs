@@ -3,7 +3,9 @@
 //! Provides enterprise-grade approval workflows for consensus pipeline operations.
 //! Supports multi-level approvals, timeout handling, and notification systems.
 
-use super::{registry::HookId, AuditEvent, AuditEventType, HookAuditLogger};
+use super::{
+    approval_store::ApprovalStore, registry::HookId, AuditEvent, AuditEventType, HookAuditLogger,
+};
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -11,6 +13,9 @@ use std::sync::Arc;
 use tokio::sync::RwLock;
 use uuid::Uuid;
 
+/// How often a waiting process checks the approval queue for a decision
+const DECISION_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(500);
+
 /// Approval workflow manager
 pub struct ApprovalWorkflow {
     pending_requests: Arc<RwLock<HashMap<String, ApprovalRequest>>>,
@@ -18,6 +23,8 @@ pub struct ApprovalWorkflow {
     approval_rules: Arc<RwLock<Vec<ApprovalRule>>>,
     notification_handlers: Arc<RwLock<Vec<Box<dyn NotificationHandler + Send + Sync>>>>,
     audit_logger: Option<Arc<HookAuditLogger>>,
+    /// Shared queue; without one, requests live only in this process
+    store: Option<Arc<ApprovalStore>>,
     config: ApprovalWorkflowConfig,
 }
 
@@ -162,6 +169,40 @@ pub enum ApprovalStatus {
     AutoApproved,
 }
 
+impl ApprovalStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Approved => "approved",
+            Self::Rejected => "rejected",
+            Self::Expired => "expired",
+            Self::Escalated => "escalated",
+            Self::AutoApproved => "auto_approved",
+        }
+    }
+
+    /// Whether no further decisions can change the outcome
+    pub fn is_final(&self) -> bool {
+        !matches!(self, Self::Pending | Self::Escalated)
+    }
+}
+
+impl std::str::FromStr for ApprovalStatus {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "pending" => Ok(Self::Pending),
+            "approved" => Ok(Self::Approved),
+            "rejected" => Ok(Self::Rejected),
+            "expired" => Ok(Self::Expired),
+            "escalated" => Ok(Self::Escalated),
+            "auto_approved" => Ok(Self::AutoApproved),
+            other => Err(anyhow::anyhow!("Unknown approval status '{}'", other)),
+        }
+    }
+}
+
 /// Priority level for approval requests
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum ApprovalPriority {
@@ -173,6 +214,63 @@ pub enum ApprovalPriority {
     Emergency,
 }
 
+impl ApprovalRequest {
+    /// Whether every required approver, or anyone if none are required, approved
+    pub fn has_sufficient_approvals(&self) -> bool {
+        let mut approved_by = self
+            .received_approvals
+            .iter()
+            .filter(|a| a.decision == ApprovalStatus::Approved)
+            .map(|a| &a.approver);
+        if self.required_approvers.is_empty() {
+            return approved_by.next().is_some();
+        }
+        let approved_by: Vec<&String> = approved_by.collect();
+        self.required_approvers
+            .iter()
+            .all(|required| approved_by.contains(&required))
+    }
+
+    /// Required approvers who have not approved yet
+    pub fn remaining_approvers(&self) -> Vec<String> {
+        self.required_approvers
+            .iter()
+            .filter(|required| {
+                !self
+                    .received_approvals
+                    .iter()
+                    .any(|a| a.decision == ApprovalStatus::Approved && &a.approver == *required)
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Fail unless `approver` is a required approver or one of the current escalation level's
+    ///
+    /// Anyone may decide a request that names no approvers at all.
+    pub fn check_approver(&self, approver: &str, levels: &[EscalationLevel]) -> Result<()> {
+        let escalated_to = levels
+            .iter()
+            .find(|level| level.level == self.current_escalation_level)
+            .map(|level| level.approvers.as_slice())
+            .unwrap_or_default();
+        let allowed: Vec<&String> = self.required_approvers.iter().chain(escalated_to).collect();
+        if !allowed.is_empty() && !allowed.iter().any(|allowed| *allowed == approver) {
+            anyhow::bail!(
+                "{} may not decide approval request {} (approvers: {})",
+                approver,
+                self.id,
+                allowed
+                    .iter()
+                    .map(|allowed| allowed.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+        }
+        Ok(())
+    }
+}
+
 /// Completed approval record
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CompletedApproval {
@@ -240,6 +338,10 @@ impl NotificationHandler for ConsoleNotificationHandler {
         if let Some(expires_at) = request.expires_at {
             println!("Expires: {}", expires_at.format("%Y-%m-%d %H:%M:%S UTC"));
         }
+        println!(
+            "Decide with: hive hooks approvals approve|reject {}",
+            &request.id[..request.id.len().min(8)]
+        );
         println!("---");
         Ok(())
     }
@@ -342,6 +444,7 @@ impl ApprovalWorkflow {
                 Box::new(LogNotificationHandler),
             ])),
             audit_logger: None,
+            store: None,
             config: ApprovalWorkflowConfig::default(),
         }
     }
@@ -359,6 +462,12 @@ impl ApprovalWorkflow {
         self
     }
 
+    /// Keep requests in a queue other processes can decide on
+    pub fn with_store(mut self, store: Arc<ApprovalStore>) -> Self {
+        self.store = Some(store);
+        self
+    }
+
     /// Submit a new approval request
     pub async fn submit_approval_request(&self, mut request: ApprovalRequest) -> Result<String> {
         // Check if we're at the concurrent approval limit
@@ -391,6 +500,15 @@ impl ApprovalWorkflow {
         let request_id = request.id.clone();
 
         // Store the pending request
+        if let Some(store) = &self.store {
+            let escalate_at = self.config.escalation.enabled.then(|| {
+                request.created_at
+                    + chrono::Duration::seconds(
+                        self.config.escalation.escalation_timeout_seconds as i64,
+                    )
+            });
+            store.insert(&request, &ApprovalStatus::Pending, escalate_at)?;
+        }
         {
             let mut pending = self.pending_requests.write().await;
             pending.insert(request_id.clone(), request.clone());
@@ -433,6 +551,19 @@ impl ApprovalWorkflow {
         reason: Option<String>,
         metadata: Option<HashMap<String, serde_json::Value>>,
     ) -> Result<ApprovalProcessResult> {
+        let approval_decision = ApprovalDecision {
+            approver: approver.to_string(),
+            decision: decision.clone(),
+            reason,
+            timestamp: chrono::Utc::now(),
+            metadata: metadata.unwrap_or_default(),
+        };
+        if let Some(store) = &self.store {
+            return self
+                .process_stored_decision(store, request_id, approval_decision)
+                .await;
+        }
+
         let mut pending = self.pending_requests.write().await;
         let mut request = pending
             .get(request_id)
@@ -451,15 +582,9 @@ impl ApprovalWorkflow {
             }
         }
 
-        // Add the approval decision
-        let approval_decision = ApprovalDecision {
-            approver: approver.to_string(),
-            decision: decision.clone(),
-            reason,
-            timestamp: chrono::Utc::now(),
-            metadata: metadata.unwrap_or_default(),
-        };
+        request.check_approver(approver, &self.config.escalation.escalation_levels)?;
 
+        // Add the approval decision
         request.received_approvals.push(approval_decision);
 
         // Update the request in pending list
@@ -467,29 +592,7 @@ impl ApprovalWorkflow {
         drop(pending);
 
         // Log the decision
-        if let Some(audit_logger) = &self.audit_logger {
-            let event = match decision {
-                ApprovalStatus::Approved => AuditEventType::ApprovalGranted {
-                    hook_id: request.hook_id.clone(),
-                    execution_id: request_id.to_string(),
-                    approver: approver.to_string(),
-                },
-                ApprovalStatus::Rejected => AuditEventType::ApprovalDenied {
-                    hook_id: request.hook_id.clone(),
-                    execution_id: request_id.to_string(),
-                    approver: approver.to_string(),
-                },
-                _ => AuditEventType::ApprovalRequested {
-                    hook_id: request.hook_id.clone(),
-                    execution_id: request_id.to_string(),
-                    approvers: vec![approver.to_string()],
-                },
-            };
-
-            let audit_event =
-                AuditEvent::new(event).with_context("approval_decision", format!("{:?}", decision));
-            audit_logger.log_event(audit_event).await?;
-        }
+        self.log_decision(&request, approver, &decision).await?;
 
         // Check if we have a final decision
         match decision {
@@ -533,14 +636,118 @@ impl ApprovalWorkflow {
         }
     }
 
+    /// Wait until a request is decided and return its final status
+    ///
+    /// Decisions are picked up from the shared queue, so they may come from
+    /// another process; due escalations and expiries are applied meanwhile.
+    pub async fn wait_for_decision(&self, request_id: &str) -> Result<ApprovalStatus> {
+        loop {
+            let status = match &self.store {
+                Some(store) => {
+                    self.process_due_deadlines().await?;
+                    store.load(request_id)?.status
+                }
+                None => {
+                    self.cleanup_expired_requests().await?;
+                    match self.completed_requests.read().await.get(request_id) {
+                        Some(completed) => completed.final_status.clone(),
+                        None => ApprovalStatus::Pending,
+                    }
+                }
+            };
+            if status.is_final() {
+                return Ok(status);
+            }
+            if self.store.is_none() && self.get_approval_request(request_id).await?.is_none() {
+                anyhow::bail!("Approval request not found: {}", request_id);
+            }
+            tokio::time::sleep(DECISION_POLL_INTERVAL).await;
+        }
+    }
+
+    /// Apply escalations and expiries that fell due in the shared queue
+    ///
+    /// Deadlines set by other processes, or before a restart, are applied too.
+    pub async fn process_due_deadlines(&self) -> Result<()> {
+        let Some(store) = &self.store else {
+            return Ok(());
+        };
+        let now = chrono::Utc::now();
+
+        for id in store.due_expiries(now)? {
+            if let Some(expired) = store.finish(&id, ApprovalStatus::Expired)? {
+                self.pending_requests.write().await.remove(&id);
+                self.complete_request(expired.request, ApprovalStatus::Expired)
+                    .await?;
+            }
+        }
+
+        let escalation = &self.config.escalation;
+        for due in store.due_escalations(now)? {
+            let next_level = escalation
+                .escalation_levels
+                .iter()
+                .find(|level| level.level > due.request.current_escalation_level);
+            let Some(level) = next_level else {
+                // Past the last level: settle it or wait for the expiry
+                let id = &due.request.id;
+                if escalation.auto_approve_after_escalation {
+                    if let Some(approved) = store.finish(id, ApprovalStatus::AutoApproved)? {
+                        self.pending_requests.write().await.remove(id);
+                        self.complete_request(approved.request, ApprovalStatus::AutoApproved)
+                            .await?;
+                    }
+                } else {
+                    store.escalate(id, due.request.current_escalation_level, None)?;
+                }
+                continue;
+            };
+
+            let next = now + chrono::Duration::seconds(level.timeout_seconds as i64);
+            let Some(escalated) = store.escalate(&due.request.id, level.level, Some(next))? else {
+                continue;
+            };
+            self.send_notification(
+                &escalated.request,
+                &format!(
+                    "Escalated to level {}: {}",
+                    level.level,
+                    level.approvers.join(", ")
+                ),
+            )
+            .await?;
+            if let Some(audit_logger) = &self.audit_logger {
+                let event = AuditEvent::new(AuditEventType::ApprovalDecisionMade {
+                    hook_id: escalated.request.hook_id.clone(),
+                    execution_id: escalated.request.id.clone(),
+                    decision: format!("escalated to level {}", level.level),
+                })
+                .with_context("approvers", &level.approvers);
+                audit_logger.log_event(event).await?;
+            }
+        }
+        Ok(())
+    }
+
     /// Get all pending approval requests
     pub async fn get_pending_approvals(&self) -> Result<Vec<ApprovalRequest>> {
+        if let Some(store) = &self.store {
+            let open = [ApprovalStatus::Pending, ApprovalStatus::Escalated];
+            return Ok(store
+                .list(&open, usize::MAX)?
+                .into_iter()
+                .map(|approval| approval.request)
+                .collect());
+        }
         let pending = self.pending_requests.read().await;
         Ok(pending.values().cloned().collect())
     }
 
     /// Get a specific approval request by ID
     pub async fn get_approval_request(&self, request_id: &str) -> Result<Option<ApprovalRequest>> {
+        if let Some(store) = &self.store {
+            return Ok(store.find(request_id)?.map(|approval| approval.request));
+        }
         let pending = self.pending_requests.read().await;
         Ok(pending.get(request_id).cloned())
     }
@@ -548,9 +755,21 @@ impl ApprovalWorkflow {
     /// Cancel a pending approval request
     pub async fn cancel_approval_request(&self, request_id: &str, reason: &str) -> Result<()> {
         let mut pending = self.pending_requests.write().await;
-        let request = pending
-            .remove(request_id)
-            .ok_or_else(|| anyhow::anyhow!("Approval request not found: {}", request_id))?;
+        let request = match &self.store {
+            // A waiting process sees the cancellation as a rejection
+            Some(store) => {
+                let approval = store
+                    .finish(request_id, ApprovalStatus::Rejected)?
+                    .ok_or_else(|| {
+                        anyhow::anyhow!("Approval request already decided: {}", request_id)
+                    })?;
+                pending.remove(&approval.request.id);
+                approval.request
+            }
+            None => pending
+                .remove(request_id)
+                .ok_or_else(|| anyhow::anyhow!("Approval request not found: {}", request_id))?,
+        };
         drop(pending);
 
         // Log the cancellation
@@ -886,6 +1105,9 @@ impl ApprovalWorkflow {
         let duration = (now - request.created_at).num_seconds() as u64;
 
         let hook_id = request.hook_id.clone();
+        if let Some(store) = &self.store {
+            store.insert(&request, &ApprovalStatus::AutoApproved, None)?;
+        }
 
         let mut completed = self.completed_requests.write().await;
         completed.insert(
@@ -919,49 +1141,12 @@ impl ApprovalWorkflow {
 
     /// Check if request has sufficient approvals
     async fn has_sufficient_approvals(&self, request: &ApprovalRequest) -> Result<bool> {
-        if request.required_approvers.is_empty() {
-            // If no specific approvers required, any approval is sufficient
-            return Ok(request
-                .received_approvals
-                .iter()
-                .any(|a| a.decision == ApprovalStatus::Approved));
-        }
-
-        // Check if all required approvers have approved
-        let approved_by: Vec<&String> = request
-            .received_approvals
-            .iter()
-            .filter(|a| a.decision == ApprovalStatus::Approved)
-            .map(|a| &a.approver)
-            .collect();
-
-        Ok(request
-            .required_approvers
-            .iter()
-            .all(|required| approved_by.contains(&required)))
+        Ok(request.has_sufficient_approvals())
     }
 
     /// Get remaining approvers needed
     async fn get_remaining_approvers(&self, request: &ApprovalRequest) -> Result<Vec<String>> {
-        if request.required_approvers.is_empty() {
-            return Ok(Vec::new());
-        }
-
-        let approved_by: Vec<&String> = request
-            .received_approvals
-            .iter()
-            .filter(|a| a.decision == ApprovalStatus::Approved)
-            .map(|a| &a.approver)
-            .collect();
-
-        let remaining: Vec<String> = request
-            .required_approvers
-            .iter()
-            .filter(|required| !approved_by.contains(required))
-            .cloned()
-            .collect();
-
-        Ok(remaining)
+        Ok(request.remaining_approvers())
     }
 
     /// Complete a request with final status
@@ -1024,6 +1209,83 @@ impl ApprovalWorkflow {
         })
     }
 
+    /// Record a decision in the shared queue
+    async fn process_stored_decision(
+        &self,
+        store: &ApprovalStore,
+        request_id: &str,
+        decision: ApprovalDecision,
+    ) -> Result<ApprovalProcessResult> {
+        let approver = decision.approver.clone();
+        let status = decision.decision.clone();
+        let approval = store.record_decision(
+            request_id,
+            decision,
+            &self.config.escalation.escalation_levels,
+        )?;
+        let request = approval.request;
+
+        if approval.status != ApprovalStatus::Expired {
+            self.log_decision(&request, &approver, &status).await?;
+        }
+        if approval.status.is_final() {
+            self.pending_requests.write().await.remove(&request.id);
+            return self.complete_request(request, approval.status).await;
+        }
+
+        Ok(ApprovalProcessResult {
+            final_status: ApprovalStatus::Pending,
+            approved: false,
+            message: match status {
+                ApprovalStatus::Approved => "Additional approvals required".to_string(),
+                other => format!("Approval decision recorded: {:?}", other),
+            },
+            remaining_approvers: request.remaining_approvers(),
+        })
+    }
+
+    /// Write a reviewer's decision to the audit log
+    async fn log_decision(
+        &self,
+        request: &ApprovalRequest,
+        approver: &str,
+        decision: &ApprovalStatus,
+    ) -> Result<()> {
+        let Some(audit_logger) = &self.audit_logger else {
+            return Ok(());
+        };
+        let event = match decision {
+            ApprovalStatus::Approved => AuditEventType::ApprovalGranted {
+                hook_id: request.hook_id.clone(),
+                execution_id: request.id.clone(),
+                approver: approver.to_string(),
+            },
+            ApprovalStatus::Rejected => AuditEventType::ApprovalDenied {
+                hook_id: request.hook_id.clone(),
+                execution_id: request.id.clone(),
+                approver: approver.to_string(),
+            },
+            _ => AuditEventType::ApprovalRequested {
+                hook_id: request.hook_id.clone(),
+                execution_id: request.id.clone(),
+                approvers: vec![approver.to_string()],
+            },
+        };
+
+        let mut audit_event =
+            AuditEvent::new(event).with_context("approval_decision", format!("{:?}", decision));
+        if let Some(reason) = request
+            .received_approvals
+            .iter()
+            .rev()
+            .find(|a| a.approver == approver)
+            .and_then(|a| a.reason.as_ref())
+        {
+            audit_event = audit_event.with_context("reason", reason);
+        }
+        audit_logger.log_event(audit_event).await
+    }
+
     /// Send notification for a request
     async fn send_notification(&self, request: &ApprovalRequest, message: &str) -> Result<()> {
         let handlers = self.notification_handlers.read().await;
@@ -1100,6 +1362,7 @@ impl Clone for ApprovalWorkflow {
             approval_rules: self.approval_rules.clone(),
             notification_handlers: self.notification_handlers.clone(),
             audit_logger: self.audit_logger.clone(),
+            store: self.store.clone(),
             config: self.config.clone(),
         }
     }
@@ -301,6 +301,29 @@ pub fn example_configs() -> Vec<(&'static str, &'static str)> {
       "message": "✅ Quality gate passed: complexity=${complexity}, coverage=${test_coverage}%"
     }
   ]
+}"#,
+        ),
+        (
+            "consensus-budget.json",
+            r#"{
+  "name": "consensus-budget",
+  "description": "Stop consensus once a run has spent more than $1.00",
+  "events": ["cost_threshold_reached"],
+  "conditions": {
+    "type": "context_variable",
+    "key": "total_cost",
+    "operator": "greater_than",
+    "value": 1.00
+  },
+  "actions": [
+    {
+      "type": "modify_context",
+      "operation": "set",
+      "key": "veto",
+      "value": "consensus budget of $1.00 exceeded"
+    }
+  ],
+  "priority": "high"
 }"#,
         ),
         (
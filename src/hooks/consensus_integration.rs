@@ -10,7 +10,8 @@
 use super::{
     approval_workflow::{ApprovalPriority, ApprovalRequest, ApprovalStatus, ApprovalWorkflow},
     registry::HookId,
-    AuditEvent, AuditEventType, EventSource, EventType, HookAuditLogger, HookEvent, HooksSystem,
+    AuditEvent, AuditEventType, EventSource, EventType, HookAuditLogger, HookEvent, HookOutcome,
+    HookVeto, HooksSystem,
 };
 use crate::consensus::types::{ConsensusConfig, Stage, StageAnalytics, StageResult};
 use anyhow::{Context, Result};
@@ -376,9 +377,9 @@ impl ConsensusIntegration {
         &self,
         stage: Stage,
         conversation_id: &str,
-        question: &str,
         model: &str,
         estimated_cost: f64,
+        input: &StageInput,
     ) -> Result<StageHookResult> {
         let stage_name = stage.as_str();
         let start_time = Instant::now();
@@ -393,6 +394,7 @@ impl ConsensusIntegration {
                 approval_required: Some(approval_required),
                 warnings: vec![format!("Cost threshold exceeded for {} stage", stage_name)],
                 modifications: HashMap::new(),
+                veto: None,
             });
         }
 
@@ -404,25 +406,30 @@ impl ConsensusIntegration {
             Stage::Curator => EventType::BeforeCuratorStage,
         };
 
-        let event = HookEvent::new(
-            event_type,
-            EventSource::Consensus {
-                stage: stage_name.to_string(),
-            },
-        )
-        .with_context("conversation_id", conversation_id)
-        .with_context("question", question)
-        .with_context("model", model)
-        .with_context("estimated_cost", estimated_cost)
-        .with_context("timestamp", chrono::Utc::now().to_rfc3339());
-
-        // Dispatch event to hooks system
-        if let Err(e) = self.hooks_system.dispatch_event(event).await {
-            tracing::warn!("Failed to dispatch pre-{} hooks: {}", stage_name, e);
-            if !self.config.continue_on_hook_failure {
-                return Err(e.context(format!("Pre-{} hook execution failed", stage_name)));
+        let event = input.attach(
+            HookEvent::new(
+                event_type,
+                EventSource::Consensus {
+                    stage: stage_name.to_string(),
+                },
+            )
+            .with_context("conversation_id", conversation_id)
+            .with_context("model", model)
+            .with_context("estimated_cost", estimated_cost)
+            .with_context("timestamp", chrono::Utc::now().to_rfc3339()),
+        );
+
+        // Run hooks and collect prompt changes or a veto
+        let outcome = match self.hooks_system.dispatch_event_and_wait(event).await {
+            Ok(outcome) => outcome,
+            Err(e) => {
+                tracing::warn!("Failed to dispatch pre-{} hooks: {}", stage_name, e);
+                if !self.config.continue_on_hook_failure {
+                    return Err(e.context(format!("Pre-{} hook execution failed", stage_name)));
+                }
+                HookOutcome::default()
             }
-        }
+        };
 
         // Log the pre-stage execution
         let event = AuditEvent::new(AuditEventType::HookRegistered {
@@ -433,17 +440,19 @@ impl ConsensusIntegration {
             "details",
             format!("Pre-{} stage hooks executed", stage_name),
         )
-        .with_context("result", "success")
+        .with_context(
+            "result",
+            if outcome.is_vetoed() {
+                "vetoed"
+            } else {
+                "success"
+            },
+        )
         .with_context("duration", start_time.elapsed().as_secs_f64());
 
         self.audit_logger.log_event(event).await?;
 
-        Ok(StageHookResult {
-            proceed: true,
-            approval_required: None,
-            warnings: Vec::new(),
-            modifications: HashMap::new(),
-        })
+        Ok(outcome.into())
     }
 
     /// Execute post-stage hooks with quality validation
@@ -461,6 +470,11 @@ impl ConsensusIntegration {
             return Ok(StageHookResult {
                 proceed: quality_result.can_continue,
                 approval_required: quality_result.approval_required,
+                veto: (!quality_result.can_continue).then(|| HookVeto {
+                    hook_id: HookId(format!("quality_gate_{}", stage_name)),
+                    hook_name: "quality gate".to_string(),
+                    reason: quality_result.warnings.join("; "),
+                }),
                 warnings: quality_result.warnings,
                 modifications: HashMap::new(),
             });
@@ -485,7 +499,16 @@ impl ConsensusIntegration {
         )
         .with_context("conversation_id", &stage_result.conversation_id)
         .with_context("stage_id", &stage_result.stage_id)
+        .with_context("question", &stage_result.question)
         .with_context("answer", &stage_result.answer)
+        .with_context(
+            "tokens",
+            stage_result
+                .usage
+                .as_ref()
+                .map(|u| u.total_tokens)
+                .unwrap_or(0),
+        )
         .with_context("model", &stage_result.model)
         .with_context(
             "duration",
@@ -512,13 +535,17 @@ impl ConsensusIntegration {
                 .unwrap_or(0.0),
         );
 
-        // Dispatch event to hooks system
-        if let Err(e) = self.hooks_system.dispatch_event(event).await {
-            tracing::warn!("Failed to dispatch post-{} hooks: {}", stage_name, e);
-            if !self.config.continue_on_hook_failure {
-                return Err(e.context(format!("Post-{} hook execution failed", stage_name)));
+        // Run hooks and collect answer changes or a veto
+        let outcome = match self.hooks_system.dispatch_event_and_wait(event).await {
+            Ok(outcome) => outcome,
+            Err(e) => {
+                tracing::warn!("Failed to dispatch post-{} hooks: {}", stage_name, e);
+                if !self.config.continue_on_hook_failure {
+                    return Err(e.context(format!("Post-{} hook execution failed", stage_name)));
+                }
+                HookOutcome::default()
             }
-        }
+        };
 
         // Log the post-stage execution
         let event = AuditEvent::new(AuditEventType::HookRegistered {
@@ -529,17 +556,19 @@ impl ConsensusIntegration {
             "details",
             format!("Post-{} stage hooks executed", stage_name),
         )
-        .with_context("result", "success")
+        .with_context(
+            "result",
+            if outcome.is_vetoed() {
+                "vetoed"
+            } else {
+                "success"
+            },
+        )
         .with_context("duration", start_time.elapsed().as_secs_f64());
 
         self.audit_logger.log_event(event).await?;
 
-        Ok(StageHookResult {
-            proceed: true,
-            approval_required: None,
-            warnings: Vec::new(),
-            modifications: HashMap::new(),
-        })
+        Ok(outcome.into())
     }
 
     /// Check cost thresholds and determine if approval is required
@@ -1086,6 +1115,66 @@ pub struct StageHookResult {
     pub approval_required: Option<ApprovalRequest>,
     pub warnings: Vec<String>,
     pub modifications: HashMap<String, serde_json::Value>,
+    /// Hook that refused to let the stage proceed, with its reason
+    pub veto: Option<HookVeto>,
+}
+
+impl From<HookOutcome> for StageHookResult {
+    fn from(outcome: HookOutcome) -> Self {
+        Self {
+            proceed: outcome.veto.is_none(),
+            approval_required: None,
+            warnings: outcome.failures,
+            modifications: outcome
+                .modifications
+                .into_iter()
+                .filter_map(|(key, value)| value.map(|value| (key, value)))
+                .collect(),
+            veto: outcome.veto,
+        }
+    }
+}
+
+/// Prompt inputs of a consensus stage, exposed to hooks as event context.
+/// Hooks may rewrite `question`, `previous_answer` or `context` with
+/// `ModifyContext` actions.
+#[derive(Debug, Clone, Default)]
+pub struct StageInput {
+    pub question: String,
+    pub previous_answer: Option<String>,
+    pub context: Option<String>,
+}
+
+impl StageInput {
+    /// Add the inputs to an event's context
+    pub fn attach(&self, event: HookEvent) -> HookEvent {
+        event
+            .with_context("question", &self.question)
+            .with_context(
+                "previous_answer",
+                self.previous_answer.as_deref().unwrap_or(""),
+            )
+            .with_context("context", self.context.as_deref().unwrap_or(""))
+    }
+
+    /// Apply hook modifications, returning whether any input changed
+    pub fn apply(&mut self, modifications: &HashMap<String, serde_json::Value>) -> bool {
+        let mut changed = false;
+        if let Some(question) = modifications.get("question").and_then(|v| v.as_str()) {
+            self.question = question.to_string();
+            changed = true;
+        }
+        for (key, slot) in [
+            ("previous_answer", &mut self.previous_answer),
+            ("context", &mut self.context),
+        ] {
+            if let Some(value) = modifications.get(key).and_then(|v| v.as_str()) {
+                *slot = (!value.is_empty()).then(|| value.to_string());
+                changed = true;
+            }
+        }
+        changed
+    }
 }
 
 /// Result of quality gate check
@@ -1,6 +1,6 @@
 //! Event Dispatcher - Advanced event routing and processing with priority queues
 
-use super::{EventHandler, EventType, HookEvent, HookPriority};
+use super::{EventHandler, EventType, HookEvent, HookOutcome, HookPriority};
 use anyhow::{anyhow, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::{BinaryHeap, HashMap};
@@ -299,6 +299,34 @@ impl EventDispatcher {
         Ok(())
     }
 
+    /// Run matching hooks right away, bypassing the queues, and return their
+    /// combined outcome. Used where the caller has to act on hook results.
+    pub async fn dispatch_and_wait(&self, event: HookEvent) -> Result<HookOutcome> {
+        let mut stats = self.stats.write().await;
+        stats.events_received += 1;
+        drop(stats);
+
+        if !self.check_rate_limit(&event).await? {
+            let mut stats = self.stats.write().await;
+            stats.events_dropped += 1;
+            return Err(anyhow!("Rate limit exceeded for event type"));
+        }
+
+        let start = std::time::Instant::now();
+        let outcome = self.event_handler.handle_event_with_outcome(event).await?;
+
+        let mut stats = self.stats.write().await;
+        stats.events_processed += 1;
+        let duration = start.elapsed().as_secs_f64();
+        stats
+            .processing_times
+            .entry("inline".to_string())
+            .and_modify(|t| *t = (*t + duration) / 2.0)
+            .or_insert(duration);
+
+        Ok(outcome)
+    }
+
     /// Route event based on rules
     async fn route_event(&self, event: &HookEvent) -> Result<(String, HookPriority)> {
         let rules = self.routing_rules.read().await;
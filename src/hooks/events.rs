@@ -1,6 +1,6 @@
 //! Hook Events - Event types and event handling system
 
-use super::{ExecutionContext, HookExecutor, HookPriority, HookRegistry};
+use super::{ExecutionContext, HookExecutor, HookId, HookPriority, HookRegistry};
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
@@ -141,6 +141,47 @@ pub struct EventMetadata {
     pub tags: Vec<String>,
 }
 
+/// Context key a hook sets (via `ModifyContext`) to veto the operation that
+/// raised the event. The value is used as the reason shown to the user.
+pub const VETO_CONTEXT_KEY: &str = "veto";
+
+/// A hook's refusal to let an operation proceed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HookVeto {
+    pub hook_id: HookId,
+    pub hook_name: String,
+    pub reason: String,
+}
+
+/// Combined result of running every matching hook for an event in place
+#[derive(Debug, Clone, Default)]
+pub struct HookOutcome {
+    /// Context entries changed by hooks; `None` marks a removed entry
+    pub modifications: HashMap<String, Option<Value>>,
+    /// Set when a hook vetoed the operation; later hooks are not run
+    pub veto: Option<HookVeto>,
+    /// Number of hooks that executed
+    pub hooks_run: usize,
+    /// Hooks that failed, with their error
+    pub failures: Vec<String>,
+}
+
+impl HookOutcome {
+    /// New value a hook assigned to `key`, if any
+    pub fn modified(&self, key: &str) -> Option<&Value> {
+        self.modifications.get(key).and_then(|v| v.as_ref())
+    }
+
+    /// New string value a hook assigned to `key`, if any
+    pub fn modified_str(&self, key: &str) -> Option<&str> {
+        self.modified(key).and_then(|v| v.as_str())
+    }
+
+    pub fn is_vetoed(&self) -> bool {
+        self.veto.is_some()
+    }
+}
+
 /// Handles event dispatching to registered hooks with priority queue support
 pub struct EventHandler {
     registry: Arc<RwLock<HookRegistry>>,
@@ -218,6 +259,74 @@ impl EventHandler {
         Ok(())
     }
 
+    /// Run matching hooks for an event immediately and collect their effects.
+    ///
+    /// Hooks run in priority order and each sees the context as left by the
+    /// previous one. A hook setting [`VETO_CONTEXT_KEY`] stops the chain.
+    pub async fn handle_event_with_outcome(&self, mut event: HookEvent) -> Result<HookOutcome> {
+        let mut matching_hooks = {
+            let registry = self.registry.read().await;
+            registry.find_by_event(&event.event_type)
+        };
+        matching_hooks.sort_by(|a, b| b.priority.cmp(&a.priority));
+
+        let mut outcome = HookOutcome::default();
+        for hook in matching_hooks {
+            let context = ExecutionContext::from_event(&event)?;
+            let before = context.variables.clone();
+
+            let result = match self.executor.execute_hook(&hook, context).await {
+                Ok(result) => result,
+                Err(e) => {
+                    tracing::error!("Failed to execute hook {}: {}", hook.name, e);
+                    outcome.failures.push(format!("{}: {}", hook.name, e));
+                    continue;
+                }
+            };
+            outcome.hooks_run += 1;
+            if !result.success {
+                outcome.failures.push(format!(
+                    "{}: {}",
+                    hook.name,
+                    result.error.as_deref().unwrap_or("failed")
+                ));
+            }
+
+            if let Some(veto) = result.variables.get(VETO_CONTEXT_KEY) {
+                if before.get(VETO_CONTEXT_KEY) != Some(veto) && !is_falsy(veto) {
+                    let reason = match veto {
+                        Value::String(reason) => reason.clone(),
+                        Value::Bool(_) => format!("blocked by hook '{}'", hook.name),
+                        other => other.to_string(),
+                    };
+                    outcome.veto = Some(HookVeto {
+                        hook_id: hook.id.clone(),
+                        hook_name: hook.name.clone(),
+                        reason,
+                    });
+                    break;
+                }
+            }
+
+            for (key, value) in &result.variables {
+                if before.get(key) != Some(value) {
+                    event.context.insert(key.clone(), value.clone());
+                    outcome
+                        .modifications
+                        .insert(key.clone(), Some(value.clone()));
+                }
+            }
+            for key in before.keys() {
+                if !result.variables.contains_key(key) {
+                    event.context.remove(key);
+                    outcome.modifications.insert(key.clone(), None);
+                }
+            }
+        }
+
+        Ok(outcome)
+    }
+
     /// Queue an event for later processing with priority
     pub async fn queue_event(&self, event: HookEvent, priority: HookPriority) -> Result<()> {
         let mut queue = self.event_queue.lock().await;
@@ -463,6 +572,15 @@ impl EventBuilder {
     }
 }
 
+fn is_falsy(value: &Value) -> bool {
+    match value {
+        Value::Null => true,
+        Value::Bool(b) => !b,
+        Value::String(s) => s.is_empty(),
+        _ => false,
+    }
+}
+
 #[cfg(all(test, feature = "legacy-tests"))]
 mod tests {
     use super::*;
@@ -488,3 +606,73 @@ mod tests {
         assert_eq!(event.metadata.correlation_id, Some("test-123".to_string()));
     }
 }
+
+#[cfg(test)]
+mod outcome_tests {
+    use super::*;
+    use crate::hooks::HooksSystem;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    async fn register(hooks: &HooksSystem, dir: &TempDir, config: Value) {
+        let path = dir
+            .path()
+            .join(format!("{}.json", config["name"].as_str().unwrap()));
+        std::fs::write(&path, config.to_string()).unwrap();
+        hooks.register_hook(path).await.unwrap();
+    }
+
+    fn set_context_hook(name: &str, priority: &str, key: &str, value: &str) -> Value {
+        json!({
+            "name": name,
+            "events": ["before_generator_stage"],
+            "priority": priority,
+            "actions": [
+                { "type": "modify_context", "operation": "set", "key": key, "value": value }
+            ]
+        })
+    }
+
+    #[tokio::test]
+    async fn test_modifications_thread_through_hooks_until_veto() {
+        let dir = TempDir::new().unwrap();
+        let hooks = HooksSystem::new(dir.path().to_path_buf()).await.unwrap();
+
+        register(
+            &hooks,
+            &dir,
+            set_context_hook("rewrite", "critical", "question", "Rewritten"),
+        )
+        .await;
+        let mut gate = set_context_hook("gate", "high", VETO_CONTEXT_KEY, "needs review");
+        gate["conditions"] = json!({
+            "type": "context_variable",
+            "key": "question",
+            "operator": "equals",
+            "value": "Rewritten"
+        });
+        register(&hooks, &dir, gate).await;
+        register(
+            &hooks,
+            &dir,
+            set_context_hook("late", "low", "context", "unreachable"),
+        )
+        .await;
+
+        let event = HookEvent::new(
+            EventType::BeforeGeneratorStage,
+            EventSource::Consensus {
+                stage: "generator".to_string(),
+            },
+        )
+        .with_context("question", "Original");
+        let outcome = hooks.dispatch_event_and_wait(event).await.unwrap();
+
+        assert_eq!(outcome.modified_str("question"), Some("Rewritten"));
+        let veto = outcome.veto.expect("gate hook should veto the stage");
+        assert_eq!(veto.hook_name, "gate");
+        assert_eq!(veto.reason, "needs review");
+        assert!(outcome.modified("context").is_none());
+        assert_eq!(outcome.hooks_run, 2);
+    }
+}
@@ -72,6 +72,9 @@ pub struct ExecutionResult {
     pub actions_executed: Vec<ActionResult>,
     pub duration_ms: u64,
     pub error: Option<String>,
+    /// Context variables after all actions ran, including `ModifyContext` changes
+    #[serde(default)]
+    pub variables: HashMap<String, Value>,
 }
 
 /// Result of a single action execution
@@ -136,6 +139,7 @@ impl HookExecutor {
                 actions_executed: vec![],
                 duration_ms: start_time.elapsed().as_millis() as u64,
                 error: Some("Conditions not met".to_string()),
+                variables: context.variables.clone(),
             };
 
             self.audit_logger
@@ -172,8 +176,16 @@ impl HookExecutor {
                 .submit_approval_request(approval_request)
                 .await?;
 
-            // For now, auto-approve - in production this would wait for actual approval
-            let approved = true;
+            // Resumes once someone decides, possibly from another process
+            let status = self
+                .approval_workflow
+                .wait_for_decision(&request_id)
+                .await?;
+            let approved = matches!(
+                status,
+                super::approval_workflow::ApprovalStatus::Approved
+                    | super::approval_workflow::ApprovalStatus::AutoApproved
+            );
 
             if !approved {
                 let result = ExecutionResult {
@@ -183,6 +195,7 @@ impl HookExecutor {
                     actions_executed: vec![],
                     duration_ms: start_time.elapsed().as_millis() as u64,
                     error: Some("Approval denied".to_string()),
+                    variables: context.variables.clone(),
                 };
 
                 self.audit_logger
@@ -238,6 +251,7 @@ impl HookExecutor {
             } else {
                 Some("One or more actions failed".to_string())
             },
+            variables: context.variables,
         };
 
         // Log execution complete
@@ -534,12 +548,15 @@ impl HookExecutor {
             .approval_workflow
             .submit_approval_request(approval_request)
             .await?;
-        // For now, return the request ID
-        let approved = false; // Would need to wait for actual approval
+        let status = self
+            .approval_workflow
+            .wait_for_decision(&request_id)
+            .await?;
 
         Ok(Some(format!(
-            "Approval request: {}",
-            if approved { "Approved" } else { "Denied" }
+            "Approval request {}: {}",
+            request_id,
+            status.as_str()
         )))
     }
 
@@ -4,6 +4,7 @@
 //! Supports secure hook execution, approval workflows, and comprehensive audit logging.
 
 pub mod approval;
+pub mod approval_store;
 pub mod approval_workflow;
 pub mod audit;
 pub mod conditions;
@@ -28,6 +29,7 @@ pub use approval::{
     ApprovalRequest as BaseApprovalRequest, ApprovalStatus as BaseApprovalStatus,
     ApprovalWorkflow as BaseApprovalWorkflow,
 };
+pub use approval_store::{ApprovalStore, StoredApproval};
 pub use approval_workflow::{
     ApprovalDecision, ApprovalPriority, ApprovalProcessResult,
     ApprovalRequest as EnhancedApprovalRequest, ApprovalStatistics,
@@ -39,7 +41,7 @@ pub use conditions::{ConditionEvaluator, HookCondition};
 pub use config::{HookConfig, HookLoader};
 pub use consensus_integration::{
     ConsensusIntegration, ConsensusIntegrationConfig, CostSummary, PerformanceStatus,
-    QualityGateResult, QualityGateStatus, StageHookResult,
+    QualityGateResult, QualityGateStatus, StageHookResult, StageInput,
 };
 pub use cost_control::{
     AlertSeverity, Budget, BudgetUpdateResult, CostAlert, CostApprovalRequirement,
@@ -47,7 +49,10 @@ pub use cost_control::{
     CostOptimizationRecommendation, CostSummary as DetailedCostSummary, CostThreshold,
 };
 pub use dispatcher::{DispatcherConfig, DispatcherStats, EventDispatcher, RoutingRule};
-pub use events::{EventBuilder, EventHandler, EventSource, EventType, HookEvent};
+pub use events::{
+    EventBuilder, EventHandler, EventSource, EventType, HookEvent, HookOutcome, HookVeto,
+    VETO_CONTEXT_KEY,
+};
 pub use execution::{ExecutionContext, ExecutionResult, HookExecutor};
 pub use quality_gates::{
     QualityActionRequired, QualityCriterion, QualityEvaluationResult, QualityFailureType,
@@ -69,6 +74,7 @@ pub struct HooksSystem {
     audit_logger: Arc<HookAuditLogger>,
     approval_workflow: Arc<approval_workflow::ApprovalWorkflow>,
     rbac_manager: Arc<rbac::HookRbacManager>,
+    stage_cost_threshold: f64,
 }
 
 impl HooksSystem {
@@ -78,7 +84,14 @@ impl HooksSystem {
         let security_validator = Arc::new(HookSecurityValidator::new()?);
         let audit_logger =
             Arc::new(HookAuditLogger::new(config_dir.join("hooks_audit.log")).await?);
-        let approval_workflow = Arc::new(approval_workflow::ApprovalWorkflow::new());
+        let mut approval_workflow =
+            approval_workflow::ApprovalWorkflow::new().with_audit_logger(audit_logger.clone());
+        // Share approvals with other processes once the database is open
+        if let Ok(database) = crate::core::database::get_database().await {
+            approval_workflow = approval_workflow
+                .with_store(Arc::new(approval_store::ApprovalStore::new(database)?));
+        }
+        let approval_workflow = Arc::new(approval_workflow);
         let rbac_manager = Arc::new(rbac::HookRbacManager::new());
 
         // Initialize default roles
@@ -99,6 +112,13 @@ impl HooksSystem {
 
         let condition_evaluator = Arc::new(ConditionEvaluator::new());
 
+        let stage_cost_threshold = crate::core::config::get_config()
+            .await
+            .ok()
+            .and_then(|config| config.hooks)
+            .unwrap_or_default()
+            .stage_cost_threshold;
+
         Ok(Self {
             registry,
             executor,
@@ -109,9 +129,15 @@ impl HooksSystem {
             audit_logger,
             approval_workflow,
             rbac_manager,
+            stage_cost_threshold,
         })
     }
 
+    /// Stage cost in USD above which consensus emits `CostThresholdReached`
+    pub fn stage_cost_threshold(&self) -> f64 {
+        self.stage_cost_threshold
+    }
+
     /// Load hooks from configuration directory
     pub async fn load_hooks(&self, hooks_dir: PathBuf) -> Result<()> {
         let loader = HookLoader::new(self.security_validator.clone());
@@ -196,6 +222,12 @@ impl HooksSystem {
         self.event_dispatcher.dispatch(event).await
     }
 
+    /// Dispatch an event and wait for its hooks, returning context changes and
+    /// any veto so the caller can act on them
+    pub async fn dispatch_event_and_wait(&self, event: HookEvent) -> Result<HookOutcome> {
+        self.event_dispatcher.dispatch_and_wait(event).await
+    }
+
     /// Start the hooks system (starts dispatcher workers)
     pub async fn start(&mut self) -> Result<()> {
         // Start the event dispatcher
@@ -1953,6 +1953,8 @@ mod tests {
                     routing_variant: "test".to_string(),
                     optimization_applied: Some(true),
                 },
+                candidates: Vec::new(),
+                context: None,
             }),
         };
 
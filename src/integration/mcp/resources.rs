@@ -3,6 +3,7 @@
 //! Provides access to codebase resources via MCP
 
 use super::protocol::{Resource, ResourceContent, ResourceData};
+use crate::analysis::walker::RepositoryWalker;
 use crate::core::config::Config;
 use crate::core::security::SecurityManager;
 
@@ -12,7 +13,6 @@ use std::path::Path;
 use std::sync::Arc;
 use tracing::{info, warn};
 use url::Url;
-use walkdir::WalkDir;
 
 /// Resource manager for MCP server
 pub struct ResourceManager {
@@ -70,43 +70,16 @@ impl ResourceManager {
             return Ok(resources);
         }
 
-        // Walk directory tree
-        let walker = WalkDir::new(path).follow_links(false).max_depth(10);
-
-        for entry in walker {
-            let entry = match entry {
-                Ok(e) => e,
-                Err(_) => continue,
-            };
-
-            let file_path = entry.path();
-
-            // Skip hidden files and directories
-            if file_path
-                .file_name()
-                .and_then(|n| n.to_str())
-                .map(|n| n.starts_with('.'))
-                .unwrap_or(false)
-            {
-                continue;
-            }
-
-            // Skip common build/output directories
-            if let Some(parent) = file_path.parent() {
-                let parent_name = parent.file_name().and_then(|n| n.to_str()).unwrap_or("");
-
-                if matches!(
-                    parent_name,
-                    "target" | "node_modules" | ".git" | "dist" | "build"
-                ) {
-                    continue;
-                }
-            }
+        // Walk directory tree; hidden entries, ignore files and build/output
+        // directories are handled by the shared walker
+        let files = RepositoryWalker::new(path)
+            .max_depth(10)
+            .walk_async()
+            .await?;
 
-            if file_path.is_file() {
-                if let Some(resource) = self.create_file_resource(file_path)? {
-                    resources.push(resource);
-                }
+        for file in files {
+            if let Some(resource) = self.create_file_resource(&file.path)? {
+                resources.push(resource);
             }
         }
 
@@ -473,6 +473,8 @@ impl ToolRegistry {
             .get(name)
             .ok_or_else(|| anyhow!("Tool not found: {}", name))?;
 
+        crate::security::enforcement::authorize("tool.call", "mcp_tool", name).await?;
+
         info!("Executing tool: {}", name);
 
         // Execute tool directly without performance optimization for now
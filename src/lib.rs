@@ -29,10 +29,13 @@ pub mod modes;
 pub mod planning;
 pub mod providers;
 pub mod security;
+pub mod server;
 pub mod shell;
 pub mod startup;
 pub mod subscription;
+pub mod team_sync;
 pub mod transformation;
+pub mod tui;
 pub mod updates;
 
 // Version constant
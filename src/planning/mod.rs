@@ -30,6 +30,11 @@ pub use self::risk_analyzer::RiskAnalyzer;
 pub use self::timeline::TimelineEstimator;
 pub use self::types::{Dependency, ModeType, Plan, PlanningContext, Risk, Task, UserPreferences};
 
+/// Where `hive plan` keeps every plan as `<id>.json`, for `@plan:<id>` mentions
+pub fn plans_dir() -> std::path::PathBuf {
+    crate::core::config::get_hive_config_dir().join("plans")
+}
+
 /// Planning Engine - orchestrates task planning and execution
 pub struct PlanningEngine {
     decomposer: TaskDecomposer,
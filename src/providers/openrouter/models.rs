@@ -340,7 +340,7 @@ impl ModelSelector {
             .map(|model| (self.score_model(model, complexity), model))
             .collect();
 
-        scored_models.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap());
+        scored_models.sort_by(|a, b| b.0.total_cmp(&a.0));
 
         // Select primary and fallback models
         let primary = scored_models[0].1;
@@ -376,6 +376,40 @@ impl ModelSelector {
         })
     }
 
+    /// Pick up to `count` models to answer the same task side by side, best first,
+    /// preferring models from different providers so the answers are independent
+    pub fn select_candidates(
+        &self,
+        complexity: TaskComplexity,
+        count: usize,
+        exclude: &[String],
+    ) -> Vec<String> {
+        let mut scored: Vec<(f32, &ModelMetadata)> = self
+            .models
+            .values()
+            .filter(|model| !exclude.contains(&model.id))
+            .map(|model| (self.score_model(model, complexity), model))
+            .collect();
+        scored.sort_by(|a, b| b.0.total_cmp(&a.0).then(a.1.id.cmp(&b.1.id)));
+
+        let mut providers: Vec<&str> = Vec::new();
+        let (distinct, repeated): (Vec<_>, Vec<_>) = scored.into_iter().partition(|(_, model)| {
+            if providers.contains(&model.provider.as_str()) {
+                false
+            } else {
+                providers.push(&model.provider);
+                true
+            }
+        });
+
+        distinct
+            .into_iter()
+            .chain(repeated)
+            .take(count)
+            .map(|(_, model)| model.id.clone())
+            .collect()
+    }
+
     /// Score a model based on current strategy
     fn score_model(&self, model: &ModelMetadata, complexity: TaskComplexity) -> f32 {
         let cost_score = 1.0 / (1.0 + model.cost_per_1k_input + model.cost_per_1k_output);
@@ -409,7 +443,7 @@ impl ModelSelector {
         models.sort_by(|a, b| {
             a.tier
                 .cmp(&b.tier)
-                .then(b.quality_score.partial_cmp(&a.quality_score).unwrap())
+                .then(b.quality_score.total_cmp(&a.quality_score))
         });
         models
     }
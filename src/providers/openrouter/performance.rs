@@ -1053,7 +1053,7 @@ impl PerformanceTracker {
     }
 
     /// Record circuit breaker success
-    async fn record_circuit_breaker_success(&self, model_id: &str) -> Result<()> {
+    pub async fn record_circuit_breaker_success(&self, model_id: &str) -> Result<()> {
         let mut breakers = self.circuit_breakers.write().await;
 
         if let Some(breaker) = breakers.get_mut(model_id) {
@@ -1084,7 +1084,7 @@ impl PerformanceTracker {
     }
 
     /// Record circuit breaker failure
-    async fn record_circuit_breaker_failure(
+    pub async fn record_circuit_breaker_failure(
         &self,
         model_id: &str,
         error_type: ErrorType,
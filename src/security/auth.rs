@@ -231,6 +231,36 @@ impl ApiKeyManager {
         Ok(api_key)
     }
 
+    /// Register a pre-provisioned key (e.g. loaded from server configuration)
+    pub async fn register_api_key(
+        &self,
+        key: &str,
+        user_id: &str,
+        name: &str,
+        permissions: Vec<String>,
+    ) -> Result<ApiKey> {
+        if key.trim().is_empty() {
+            return Err(anyhow!("API key must not be empty"));
+        }
+
+        let api_key = ApiKey {
+            id: Uuid::new_v4().to_string(),
+            name: name.to_string(),
+            key: key.to_string(),
+            user_id: user_id.to_string(),
+            permissions,
+            created_at: Utc::now(),
+            expires_at: None,
+            last_used: None,
+            active: true,
+        };
+
+        let mut api_keys = self.api_keys.write().await;
+        api_keys.insert(api_key.key.clone(), api_key.clone());
+
+        Ok(api_key)
+    }
+
     pub async fn validate_api_key(&self, key: &str) -> Result<ApiKey> {
         let mut api_keys = self.api_keys.write().await;
 
@@ -0,0 +1,334 @@
+//! Enforcement - permission checks in front of tool calls and file edits
+//!
+//! The acting principal is the operating system account running hive; it is
+//! not taken from the environment, so it cannot be chosen per command. Checks
+//! only apply once at least one user exists in the RBAC store, so a fresh
+//! install keeps working until an administrator sets users up.
+
+use super::audit::EnterpriseAuditLogger;
+use super::permissions::{PermissionContext, PermissionManager, PermissionSubject};
+use super::rbac::{EnterpriseRbacManager, EnterpriseUser};
+use super::store::RbacStore;
+use super::teams::TeamManager;
+use super::SecurityConfig;
+use crate::core::database::get_database;
+use anyhow::{bail, Result};
+use std::sync::Arc;
+
+/// The user ID permission checks run against
+pub fn current_principal() -> Result<String> {
+    os_account()
+}
+
+/// Login name of the account this process runs as
+///
+/// Read from the system rather than the environment, so it cannot be set per command.
+#[cfg(unix)]
+pub fn os_account() -> Result<String> {
+    let uid = unsafe { libc::geteuid() };
+    let mut passwd: libc::passwd = unsafe { std::mem::zeroed() };
+    let mut buffer = vec![0 as libc::c_char; 16 * 1024];
+    let mut result: *mut libc::passwd = std::ptr::null_mut();
+    let status = unsafe {
+        libc::getpwuid_r(
+            uid,
+            &mut passwd,
+            buffer.as_mut_ptr(),
+            buffer.len(),
+            &mut result,
+        )
+    };
+    if status != 0 || result.is_null() {
+        bail!("No account found for user id {}", uid);
+    }
+    let name = unsafe { std::ffi::CStr::from_ptr(passwd.pw_name) };
+    Ok(name.to_string_lossy().into_owned())
+}
+
+/// Login name of the account this process runs as
+#[cfg(not(unix))]
+pub fn os_account() -> Result<String> {
+    use sysinfo::{ProcessRefreshKind, System, UpdateKind, Users};
+
+    let pid = sysinfo::get_current_pid().map_err(|e| anyhow::anyhow!(e))?;
+    let mut system = System::new();
+    system.refresh_process_specifics(pid, ProcessRefreshKind::new().with_user(UpdateKind::Always));
+    let users = Users::new_with_refreshed_list();
+    system
+        .process(pid)
+        .and_then(|process| process.user_id())
+        .and_then(|uid| users.get_user_by_id(uid))
+        .map(|user| user.name().to_string())
+        .ok_or_else(|| anyhow::anyhow!("Could not determine the current account"))
+}
+
+/// Answers whether the principal may perform an action on a resource
+pub struct AccessGuard {
+    rbac: Arc<EnterpriseRbacManager>,
+    permissions: Arc<PermissionManager>,
+    teams: Arc<TeamManager>,
+    principal: String,
+}
+
+impl AccessGuard {
+    pub fn new(
+        rbac: Arc<EnterpriseRbacManager>,
+        permissions: Arc<PermissionManager>,
+        teams: Arc<TeamManager>,
+    ) -> Self {
+        Self {
+            rbac,
+            permissions,
+            teams,
+            // An account that cannot be determined matches no user
+            principal: current_principal().unwrap_or_default(),
+        }
+    }
+
+    pub fn with_principal(mut self, principal: impl Into<String>) -> Self {
+        self.principal = principal.into();
+        self
+    }
+
+    /// Load the persisted RBAC state into fresh managers
+    pub async fn open(store: Arc<RbacStore>) -> Result<Self> {
+        let audit = Arc::new(
+            EnterpriseAuditLogger::new(None, SecurityConfig::default().audit_retention_days)
+                .await?,
+        );
+        let permissions = Arc::new(PermissionManager::new().await?.with_store(store.clone()));
+        permissions.load().await?;
+        let rbac = Arc::new(
+            EnterpriseRbacManager::new(permissions.clone(), audit.clone())
+                .await?
+                .with_store(store.clone()),
+        );
+        rbac.load().await?;
+        let teams = Arc::new(
+            TeamManager::new(rbac.clone(), audit)
+                .await?
+                .with_store(store),
+        );
+        teams.load().await?;
+        Ok(Self::new(rbac, permissions, teams))
+    }
+
+    pub fn principal(&self) -> &str {
+        &self.principal
+    }
+
+    /// Whether checks apply at all
+    pub async fn is_enforced(&self) -> bool {
+        self.rbac.has_users().await
+    }
+
+    /// Role permissions are checked first (access policies included), then
+    /// resource grants to the user, their roles and their teams.
+    pub async fn check(
+        &self,
+        permission: &str,
+        resource_type: &str,
+        resource_id: &str,
+    ) -> Result<bool> {
+        if !self.is_enforced().await {
+            return Ok(true);
+        }
+        let Some(user) = self.resolve_user().await? else {
+            return Ok(false);
+        };
+
+        let resource = format!("{}:{}", resource_type, resource_id);
+        if self
+            .rbac
+            .check_permission(&user.id, permission, Some(&resource))
+            .await?
+        {
+            return Ok(true);
+        }
+        if !user.active || user.account_locked {
+            return Ok(false);
+        }
+
+        let mut subjects = vec![PermissionSubject::User(user.id.clone())];
+        subjects.extend(user.roles.iter().cloned().map(PermissionSubject::Role));
+        for team in self.teams.list_user_teams(&user.id).await? {
+            subjects.push(PermissionSubject::Team(team.id));
+        }
+
+        let context = PermissionContext {
+            user_id: Some(user.id.clone()),
+            ..PermissionContext::default()
+        };
+        for subject in &subjects {
+            let result = self
+                .permissions
+                .check_permission(subject, permission, resource_type, resource_id, &context)
+                .await?;
+            if result.granted {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    /// Like [`AccessGuard::check`], but a denial is an error
+    pub async fn authorize(
+        &self,
+        permission: &str,
+        resource_type: &str,
+        resource_id: &str,
+    ) -> Result<()> {
+        if !self.check(permission, resource_type, resource_id).await? {
+            bail!(
+                "Permission denied: {} may not {} on {}:{}",
+                self.principal,
+                permission,
+                resource_type,
+                resource_id
+            );
+        }
+        Ok(())
+    }
+
+    /// Match the principal against user IDs first, then usernames
+    async fn resolve_user(&self) -> Result<Option<EnterpriseUser>> {
+        if self.principal.is_empty() {
+            return Ok(None);
+        }
+        if let Some(user) = self.rbac.get_user(&self.principal).await? {
+            return Ok(Some(user));
+        }
+        Ok(self
+            .rbac
+            .list_users()
+            .await?
+            .into_iter()
+            .find(|user| user.username == self.principal))
+    }
+}
+
+/// Check the current principal against the shared database.
+/// Passes when no database is open or no users have been defined.
+pub async fn authorize(permission: &str, resource_type: &str, resource_id: &str) -> Result<()> {
+    let Ok(database) = get_database().await else {
+        return Ok(());
+    };
+    let store = Arc::new(RbacStore::new(database)?);
+    if !store.has_users()? {
+        return Ok(());
+    }
+    AccessGuard::open(store)
+        .await?
+        .authorize(permission, resource_type, resource_id)
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::permissions::PermissionScope;
+    use chrono::Utc;
+    use std::collections::HashMap;
+
+    async fn guard() -> AccessGuard {
+        let audit = Arc::new(EnterpriseAuditLogger::new(None, 30).await.unwrap());
+        let permissions = Arc::new(PermissionManager::new().await.unwrap());
+        let rbac = Arc::new(
+            EnterpriseRbacManager::new(permissions.clone(), audit.clone())
+                .await
+                .unwrap(),
+        );
+        rbac.initialize_default_roles().await.unwrap();
+        let teams = Arc::new(TeamManager::new(rbac.clone(), audit).await.unwrap());
+        AccessGuard::new(rbac, permissions, teams).with_principal("alice")
+    }
+
+    fn user(id: &str, roles: &[&str]) -> EnterpriseUser {
+        EnterpriseUser {
+            id: id.to_string(),
+            username: id.to_string(),
+            email: format!("{}@example.com", id),
+            full_name: id.to_string(),
+            roles: roles.iter().map(|r| r.to_string()).collect(),
+            teams: vec![],
+            security_groups: vec![],
+            active: true,
+            created_at: Utc::now(),
+            last_login: None,
+            password_hash: None,
+            mfa_enabled: false,
+            account_locked: false,
+            lock_reason: None,
+            last_activity: None,
+            failed_login_attempts: 0,
+            password_expires_at: None,
+            must_change_password: false,
+            session_timeout: None,
+            ip_restrictions: vec![],
+            metadata: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn principal_is_the_os_account() {
+        std::env::set_var("HIVE_USER", "mallory");
+        let principal = current_principal().unwrap();
+        assert!(!principal.is_empty());
+        assert_ne!(principal, "mallory");
+        std::env::remove_var("HIVE_USER");
+    }
+
+    #[tokio::test]
+    async fn nothing_is_enforced_without_users() {
+        let guard = guard().await;
+        assert!(!guard.is_enforced().await);
+        assert!(guard
+            .check("security.manage", "cli", "security")
+            .await
+            .unwrap());
+    }
+
+    #[tokio::test]
+    async fn role_permissions_and_grants_are_honoured() {
+        let guard = guard().await;
+        guard
+            .rbac
+            .create_user(user("alice", &["developer"]))
+            .await
+            .unwrap();
+
+        assert!(guard
+            .check("tool.call", "mcp_tool", "analyze")
+            .await
+            .unwrap());
+        assert!(!guard
+            .check("security.manage", "cli", "security")
+            .await
+            .unwrap());
+        assert!(!guard.check("deploy.run", "env", "prod").await.unwrap());
+
+        guard
+            .permissions
+            .grant_permission(
+                "deploy.run",
+                "env",
+                "prod",
+                PermissionSubject::Role("developer".to_string()),
+                PermissionScope::Resource,
+                "admin",
+                vec![],
+                None,
+            )
+            .await
+            .unwrap();
+        assert!(guard.check("deploy.run", "env", "prod").await.unwrap());
+
+        let stranger = guard().await.with_principal("mallory");
+        stranger.rbac.create_user(user("alice", &[])).await.unwrap();
+        let error = stranger
+            .authorize("tool.call", "mcp_tool", "analyze")
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("Permission denied: mallory"));
+    }
+}
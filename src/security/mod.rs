@@ -10,8 +10,11 @@
 pub mod audit;
 pub mod auth;
 pub mod compliance;
+pub mod enforcement;
 pub mod permissions;
+pub mod policy_set;
 pub mod rbac;
+pub mod store;
 pub mod teams;
 pub mod trust_dialog;
 
@@ -35,14 +38,17 @@ pub use compliance::{
     ComplianceManager, ComplianceReport as ComplianceReportType, ComplianceRule,
     ComplianceStandard, ComplianceStatus, ComplianceViolation,
 };
+pub use enforcement::{current_principal, AccessGuard};
 pub use permissions::{
     PermissionContext, PermissionInheritance, PermissionManager, PermissionScope,
     PermissionTemplate, ResourcePermission,
 };
+pub use policy_set::{ImportSummary, PolicySet};
 pub use rbac::{
     AccessPolicy, EnterpriseRbacManager, EnterpriseRole, EnterpriseUser, RoleInheritance,
-    SecurityGroup,
+    SecurityGroup, SECURITY_ADMIN_PERMISSION,
 };
+pub use store::{RbacSnapshot, RbacStore};
 pub use teams::{
     EnterpriseTeam, TeamAccess, TeamHierarchy, TeamInvitation, TeamManager, TeamPermissions,
     TeamRole,
@@ -60,6 +66,7 @@ pub struct SecuritySystem {
     rbac_manager: Arc<EnterpriseRbacManager>,
     team_manager: Arc<TeamManager>,
     trust_dialog_system: Arc<TrustDialogSystem>,
+    store: Option<Arc<RbacStore>>,
     config: SecurityConfig,
 }
 
@@ -146,6 +153,23 @@ impl Default for SecurityConfig {
 impl SecuritySystem {
     /// Create a new enterprise security system
     pub async fn new(config: SecurityConfig, db_path: Option<std::path::PathBuf>) -> Result<Self> {
+        Self::build(config, db_path, None).await
+    }
+
+    /// Create a security system whose RBAC state lives in `store`
+    pub async fn open(
+        config: SecurityConfig,
+        db_path: Option<std::path::PathBuf>,
+        store: Arc<RbacStore>,
+    ) -> Result<Self> {
+        Self::build(config, db_path, Some(store)).await
+    }
+
+    async fn build(
+        config: SecurityConfig,
+        db_path: Option<std::path::PathBuf>,
+        store: Option<Arc<RbacStore>>,
+    ) -> Result<Self> {
         let auth_manager = Arc::new(AuthenticationManager::new(config.clone()).await?);
         let audit_logger = Arc::new(
             EnterpriseAuditLogger::new(db_path.clone(), config.audit_retention_days).await?,
@@ -154,12 +178,22 @@ impl SecuritySystem {
             ComplianceManager::new(config.compliance_standards.clone(), audit_logger.clone())
                 .await?,
         );
-        let permission_manager = Arc::new(PermissionManager::new().await?);
-        let rbac_manager = Arc::new(
-            EnterpriseRbacManager::new(permission_manager.clone(), audit_logger.clone()).await?,
-        );
-        let team_manager =
-            Arc::new(TeamManager::new(rbac_manager.clone(), audit_logger.clone()).await?);
+        let mut permission_manager = PermissionManager::new().await?;
+        if let Some(store) = &store {
+            permission_manager = permission_manager.with_store(store.clone());
+        }
+        let permission_manager = Arc::new(permission_manager);
+        let mut rbac_manager =
+            EnterpriseRbacManager::new(permission_manager.clone(), audit_logger.clone()).await?;
+        if let Some(store) = &store {
+            rbac_manager = rbac_manager.with_store(store.clone());
+        }
+        let rbac_manager = Arc::new(rbac_manager);
+        let mut team_manager = TeamManager::new(rbac_manager.clone(), audit_logger.clone()).await?;
+        if let Some(store) = &store {
+            team_manager = team_manager.with_store(store.clone());
+        }
+        let team_manager = Arc::new(team_manager);
         let trust_dialog_system = Arc::new(TrustDialogSystem::new(config.trust_dialog.clone()));
 
         Ok(Self {
@@ -170,6 +204,7 @@ impl SecuritySystem {
             rbac_manager,
             team_manager,
             trust_dialog_system,
+            store,
             config,
         })
     }
@@ -179,7 +214,8 @@ impl SecuritySystem {
         // Initialize authentication system
         self.auth_manager.initialize().await?;
 
-        // Initialize RBAC with default roles
+        // Load persisted RBAC state, then seed default roles into an empty store
+        self.reload().await?;
         self.rbac_manager.initialize_default_roles().await?;
 
         // Initialize compliance monitoring
@@ -200,6 +236,59 @@ impl SecuritySystem {
         Ok(())
     }
 
+    /// Re-read users, roles, teams and grants from the store
+    pub async fn reload(&self) -> Result<()> {
+        self.permission_manager.load().await?;
+        self.rbac_manager.load().await?;
+        self.team_manager.load().await
+    }
+
+    /// Describe the persisted RBAC state as a policy set
+    pub fn export_policy_set(&self) -> Result<PolicySet> {
+        let store = self.require_store()?;
+        Ok(PolicySet::from_snapshot(&store.snapshot()?))
+    }
+
+    /// Make the persisted RBAC state match `policy_set`
+    pub async fn import_policy_set(
+        &self,
+        policy_set: &PolicySet,
+        actor: &str,
+    ) -> Result<ImportSummary> {
+        let store = self.require_store()?;
+        let (snapshot, summary) = policy_set.apply(&store.snapshot()?, actor)?;
+        store.replace(&snapshot)?;
+        self.reload().await?;
+
+        self.audit_logger
+            .log_system_event(
+                AuditEventType::ConfigChanged,
+                format!(
+                    "Policy set imported by {}: {} users, {} roles, {} teams, {} grants",
+                    actor, summary.users, summary.roles, summary.teams, summary.grants
+                ),
+                Some(actor.to_string()),
+            )
+            .await?;
+
+        Ok(summary)
+    }
+
+    /// Permission checks against this system's managers
+    pub fn guard(&self) -> AccessGuard {
+        AccessGuard::new(
+            self.rbac_manager.clone(),
+            self.permission_manager.clone(),
+            self.team_manager.clone(),
+        )
+    }
+
+    fn require_store(&self) -> Result<&Arc<RbacStore>> {
+        self.store
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("Security system has no RBAC store attached"))
+    }
+
     // Authentication methods
     pub async fn authenticate_user(
         &self,
@@ -13,11 +13,14 @@ use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
+use super::store::RbacStore;
+
 /// Permission manager for enterprise-grade access control
 pub struct PermissionManager {
     permissions: Arc<RwLock<HashMap<String, ResourcePermission>>>,
     templates: Arc<RwLock<HashMap<String, PermissionTemplate>>>,
     inheritance_rules: Arc<RwLock<Vec<PermissionInheritance>>>,
+    store: Option<Arc<RbacStore>>,
 }
 
 /// Resource-specific permission
@@ -102,6 +105,50 @@ pub enum PermissionSubject {
     ServiceAccount(String),
 }
 
+impl PermissionSubject {
+    /// Subject kinds accepted by [`PermissionSubject::parse`]
+    pub const KINDS: &'static [&'static str] =
+        &["user", "role", "team", "api_key", "service_account"];
+
+    pub fn parse(kind: &str, id: impl Into<String>) -> Result<Self> {
+        let id = id.into();
+        Ok(match kind {
+            "user" => PermissionSubject::User(id),
+            "role" => PermissionSubject::Role(id),
+            "team" => PermissionSubject::Team(id),
+            "api_key" => PermissionSubject::ApiKey(id),
+            "service_account" => PermissionSubject::ServiceAccount(id),
+            _ => {
+                return Err(anyhow!(
+                    "Invalid subject type: {} (expected one of {})",
+                    kind,
+                    Self::KINDS.join(", ")
+                ))
+            }
+        })
+    }
+
+    pub fn kind(&self) -> &'static str {
+        match self {
+            PermissionSubject::User(_) => "user",
+            PermissionSubject::Role(_) => "role",
+            PermissionSubject::Team(_) => "team",
+            PermissionSubject::ApiKey(_) => "api_key",
+            PermissionSubject::ServiceAccount(_) => "service_account",
+        }
+    }
+
+    pub fn id(&self) -> &str {
+        match self {
+            PermissionSubject::User(id)
+            | PermissionSubject::Role(id)
+            | PermissionSubject::Team(id)
+            | PermissionSubject::ApiKey(id)
+            | PermissionSubject::ServiceAccount(id) => id,
+        }
+    }
+}
+
 /// Permission template for common patterns
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PermissionTemplate {
@@ -196,9 +243,28 @@ impl PermissionManager {
             permissions: Arc::new(RwLock::new(HashMap::new())),
             templates: Arc::new(RwLock::new(HashMap::new())),
             inheritance_rules: Arc::new(RwLock::new(Vec::new())),
+            store: None,
         })
     }
 
+    /// Write grants and revocations through to `store`
+    pub fn with_store(mut self, store: Arc<RbacStore>) -> Self {
+        self.store = Some(store);
+        self
+    }
+
+    /// Replace the in-memory grants with what the store holds
+    pub async fn load(&self) -> Result<()> {
+        if let Some(store) = &self.store {
+            *self.permissions.write().await = store
+                .load_grants()?
+                .into_iter()
+                .map(|grant| (grant.id.clone(), grant))
+                .collect();
+        }
+        Ok(())
+    }
+
     /// Initialize with default templates and inheritance rules
     pub async fn initialize_defaults(&self) -> Result<()> {
         self.create_default_templates().await?;
@@ -342,6 +408,9 @@ impl PermissionManager {
             metadata: HashMap::new(),
         };
 
+        if let Some(store) = &self.store {
+            store.save_grant(&permission)?;
+        }
         let mut permissions = self.permissions.write().await;
         permissions.insert(permission_id.clone(), permission);
 
@@ -353,6 +422,9 @@ impl PermissionManager {
         let mut permissions = self.permissions.write().await;
         if let Some(permission) = permissions.get_mut(permission_id) {
             permission.active = false;
+            if let Some(store) = &self.store {
+                store.save_grant(permission)?;
+            }
             Ok(())
         } else {
             Err(anyhow!("Permission not found: {}", permission_id))
@@ -0,0 +1,853 @@
+//! Policy Set - the whole RBAC configuration as a reviewable TOML document
+//!
+//! `hive security export` writes roles, users, security groups, access
+//! policies, teams and permission grants in a stable order so the file diffs
+//! cleanly in version control. `hive security import` makes the store match
+//! the file again, keeping runtime state such as login times and team join
+//! dates for entities that already exist.
+
+use super::permissions::{
+    PermissionCondition, PermissionScope, PermissionSubject, ResourcePermission,
+};
+use super::rbac::{
+    AccessPolicy, EnterpriseRole, EnterpriseUser, IsolationLevel, PolicyCondition, PolicyEffect,
+    PolicyType, RiskLevel, SecurityGroup, TimeRestriction,
+};
+use super::store::RbacSnapshot;
+use super::teams::{
+    EnterpriseTeam, MemberStatus, TeamAccess, TeamMember, TeamPermissions, TeamSettings, TeamType,
+};
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Format version written to exported files
+pub const POLICY_SET_VERSION: u32 = 1;
+
+/// Every RBAC entity an administrator defines
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PolicySet {
+    pub version: u32,
+    #[serde(default, rename = "role", skip_serializing_if = "Vec::is_empty")]
+    pub roles: Vec<RoleSpec>,
+    #[serde(default, rename = "user", skip_serializing_if = "Vec::is_empty")]
+    pub users: Vec<UserSpec>,
+    #[serde(default, rename = "group", skip_serializing_if = "Vec::is_empty")]
+    pub groups: Vec<GroupSpec>,
+    #[serde(default, rename = "policy", skip_serializing_if = "Vec::is_empty")]
+    pub policies: Vec<PolicySpec>,
+    #[serde(default, rename = "team", skip_serializing_if = "Vec::is_empty")]
+    pub teams: Vec<TeamSpec>,
+    #[serde(default, rename = "grant", skip_serializing_if = "Vec::is_empty")]
+    pub grants: Vec<GrantSpec>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoleSpec {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub permissions: Vec<String>,
+    /// Roles whose permissions this role inherits
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub parents: Vec<String>,
+    #[serde(default = "default_risk_level")]
+    pub risk_level: RiskLevel,
+    #[serde(default)]
+    pub requires_approval: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub approval_roles: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_assignment_days: Option<i64>,
+    #[serde(default = "default_true")]
+    pub active: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserSpec {
+    pub id: String,
+    pub username: String,
+    #[serde(default)]
+    pub email: String,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub roles: Vec<String>,
+    #[serde(default = "default_true")]
+    pub active: bool,
+    #[serde(default)]
+    pub mfa_enabled: bool,
+    #[serde(default)]
+    pub locked: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lock_reason: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub metadata: BTreeMap<String, String>,
+}
+
+/// Security group; its member list is the source of truth for users' groups
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroupSpec {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub members: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub default_roles: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub security_policies: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub access_policies: Vec<String>,
+    #[serde(default = "default_isolation_level")]
+    pub isolation_level: IsolationLevel,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PolicySpec {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub policy_type: PolicyType,
+    pub effect: PolicyEffect,
+    #[serde(default)]
+    pub priority: u32,
+    #[serde(default)]
+    pub principals: Vec<String>,
+    #[serde(default)]
+    pub resources: Vec<String>,
+    #[serde(default)]
+    pub actions: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ip_restrictions: Vec<String>,
+    #[serde(default = "default_true")]
+    pub active: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time_restrictions: Option<TimeRestriction>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub conditions: Vec<PolicyCondition>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TeamSpec {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub team_type: TeamType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<String>,
+    #[serde(default = "default_true")]
+    pub active: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub members: Vec<MemberSpec>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemberSpec {
+    pub user: String,
+    #[serde(default = "default_member_role")]
+    pub role: String,
+}
+
+/// An active resource-level permission grant
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GrantSpec {
+    pub id: String,
+    pub permission: String,
+    pub subject_type: String,
+    pub subject_id: String,
+    pub resource_type: String,
+    pub resource_id: String,
+    #[serde(default = "default_scope")]
+    pub scope: PermissionScope,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub conditions: Vec<PermissionCondition>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_risk_level() -> RiskLevel {
+    RiskLevel::Medium
+}
+
+fn default_isolation_level() -> IsolationLevel {
+    IsolationLevel::Basic
+}
+
+fn default_member_role() -> String {
+    "member".to_string()
+}
+
+fn default_scope() -> PermissionScope {
+    PermissionScope::Resource
+}
+
+/// What an import changed, per entity kind
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ImportSummary {
+    pub roles: usize,
+    pub users: usize,
+    pub groups: usize,
+    pub policies: usize,
+    pub teams: usize,
+    pub grants: usize,
+    /// IDs present before the import but missing from the file
+    pub removed: Vec<String>,
+}
+
+impl PolicySet {
+    /// Parse and validate a TOML policy set
+    pub fn from_toml(source: &str) -> Result<Self> {
+        let set: Self = toml::from_str(source).context("Invalid policy set")?;
+        if set.version > POLICY_SET_VERSION {
+            bail!(
+                "Policy set version {} is newer than this build supports ({})",
+                set.version,
+                POLICY_SET_VERSION
+            );
+        }
+        set.validate()?;
+        Ok(set)
+    }
+
+    pub fn to_toml(&self) -> Result<String> {
+        Ok(toml::to_string_pretty(self)?)
+    }
+
+    /// Describe the persisted state, leaving out runtime-only fields
+    pub fn from_snapshot(snapshot: &RbacSnapshot) -> Self {
+        let mut roles: Vec<RoleSpec> = snapshot
+            .roles
+            .iter()
+            .map(|role| RoleSpec {
+                id: role.id.clone(),
+                name: role.name.clone(),
+                description: role.description.clone(),
+                permissions: sorted(role.permissions.iter().cloned()),
+                parents: role.parent_roles.clone(),
+                risk_level: role.risk_level.clone(),
+                requires_approval: role.requires_approval,
+                approval_roles: role.approval_roles.clone(),
+                max_assignment_days: role.max_assignment_duration.map(|d| d.num_days()),
+                active: role.active,
+            })
+            .collect();
+        roles.sort_by(|a, b| a.id.cmp(&b.id));
+
+        let mut users: Vec<UserSpec> = snapshot
+            .users
+            .iter()
+            .map(|user| UserSpec {
+                id: user.id.clone(),
+                username: user.username.clone(),
+                email: user.email.clone(),
+                name: user.full_name.clone(),
+                roles: user.roles.clone(),
+                active: user.active,
+                mfa_enabled: user.mfa_enabled,
+                locked: user.account_locked,
+                lock_reason: user.lock_reason.clone(),
+                metadata: user.metadata.clone().into_iter().collect(),
+            })
+            .collect();
+        users.sort_by(|a, b| a.id.cmp(&b.id));
+
+        let mut groups: Vec<GroupSpec> = snapshot
+            .groups
+            .iter()
+            .map(|group| GroupSpec {
+                id: group.id.clone(),
+                name: group.name.clone(),
+                description: group.description.clone(),
+                members: sorted(group.members.iter().cloned()),
+                default_roles: group.default_roles.clone(),
+                security_policies: group.security_policies.clone(),
+                access_policies: group.access_policies.clone(),
+                isolation_level: group.isolation_level.clone(),
+            })
+            .collect();
+        groups.sort_by(|a, b| a.id.cmp(&b.id));
+
+        let mut policies: Vec<PolicySpec> = snapshot
+            .policies
+            .iter()
+            .map(|policy| PolicySpec {
+                id: policy.id.clone(),
+                name: policy.name.clone(),
+                description: policy.description.clone(),
+                policy_type: policy.policy_type.clone(),
+                effect: policy.effect.clone(),
+                priority: policy.priority,
+                principals: policy.principals.clone(),
+                resources: policy.resources.clone(),
+                actions: policy.actions.clone(),
+                ip_restrictions: policy.ip_restrictions.clone(),
+                active: policy.active,
+                time_restrictions: policy.time_restrictions.clone(),
+                conditions: policy.conditions.clone(),
+            })
+            .collect();
+        policies.sort_by(|a, b| a.id.cmp(&b.id));
+
+        let mut teams: Vec<TeamSpec> = snapshot
+            .teams
+            .iter()
+            .map(|team| TeamSpec {
+                id: team.id.clone(),
+                name: team.name.clone(),
+                description: team.description.clone(),
+                team_type: team.team_type.clone(),
+                parent: team.parent_team.clone(),
+                active: team.active,
+                members: team
+                    .members
+                    .iter()
+                    .map(|member| MemberSpec {
+                        user: member.user_id.clone(),
+                        role: member.role.clone(),
+                    })
+                    .collect(),
+            })
+            .collect();
+        teams.sort_by(|a, b| a.id.cmp(&b.id));
+
+        let mut grants: Vec<GrantSpec> = snapshot
+            .grants
+            .iter()
+            .filter(|grant| grant.active)
+            .map(|grant| GrantSpec {
+                id: grant.id.clone(),
+                permission: grant.permission_name.clone(),
+                subject_type: grant.granted_to.kind().to_string(),
+                subject_id: grant.granted_to.id().to_string(),
+                resource_type: grant.resource_type.clone(),
+                resource_id: grant.resource_id.clone(),
+                scope: grant.scope.clone(),
+                expires_at: grant.expires_at,
+                conditions: grant.conditions.clone(),
+            })
+            .collect();
+        grants.sort_by(|a, b| a.id.cmp(&b.id));
+
+        Self {
+            version: POLICY_SET_VERSION,
+            roles,
+            users,
+            groups,
+            policies,
+            teams,
+            grants,
+        }
+    }
+
+    /// Check that IDs are unique and every reference resolves
+    pub fn validate(&self) -> Result<()> {
+        let mut problems = Vec::new();
+
+        let role_ids = unique_ids("role", self.roles.iter().map(|r| &r.id), &mut problems);
+        let user_ids = unique_ids("user", self.users.iter().map(|u| &u.id), &mut problems);
+        unique_ids("group", self.groups.iter().map(|g| &g.id), &mut problems);
+        unique_ids("policy", self.policies.iter().map(|p| &p.id), &mut problems);
+        let team_ids = unique_ids("team", self.teams.iter().map(|t| &t.id), &mut problems);
+        unique_ids("grant", self.grants.iter().map(|g| &g.id), &mut problems);
+
+        for role in &self.roles {
+            for parent in role.parents.iter().chain(&role.approval_roles) {
+                if !role_ids.contains(parent.as_str()) {
+                    problems.push(format!(
+                        "role {} references unknown role {}",
+                        role.id, parent
+                    ));
+                }
+            }
+        }
+        for user in &self.users {
+            for role in &user.roles {
+                if !role_ids.contains(role.as_str()) {
+                    problems.push(format!("user {} has unknown role {}", user.id, role));
+                }
+            }
+        }
+        for group in &self.groups {
+            for member in &group.members {
+                if !user_ids.contains(member.as_str()) {
+                    problems.push(format!("group {} has unknown member {}", group.id, member));
+                }
+            }
+        }
+        for team in &self.teams {
+            if let Some(parent) = &team.parent {
+                if !team_ids.contains(parent.as_str()) {
+                    problems.push(format!("team {} has unknown parent {}", team.id, parent));
+                }
+            }
+            for member in &team.members {
+                if !user_ids.contains(member.user.as_str()) {
+                    problems.push(format!(
+                        "team {} has unknown member {}",
+                        team.id, member.user
+                    ));
+                }
+            }
+        }
+        for grant in &self.grants {
+            if let Err(e) = PermissionSubject::parse(&grant.subject_type, &grant.subject_id) {
+                problems.push(format!("grant {}: {}", grant.id, e));
+            }
+        }
+
+        if !problems.is_empty() {
+            bail!("Invalid policy set:\n  - {}", problems.join("\n  - "));
+        }
+        Ok(())
+    }
+
+    /// Build the state the store should hold after importing this set.
+    /// Entities in `current` keep their runtime fields; new ones are
+    /// attributed to `actor`.
+    pub fn apply(
+        &self,
+        current: &RbacSnapshot,
+        actor: &str,
+    ) -> Result<(RbacSnapshot, ImportSummary)> {
+        self.validate()?;
+        let now = Utc::now();
+
+        let existing_roles: HashMap<&str, &EnterpriseRole> =
+            current.roles.iter().map(|r| (r.id.as_str(), r)).collect();
+        let roles: Vec<EnterpriseRole> = self
+            .roles
+            .iter()
+            .map(|spec| {
+                let previous = existing_roles.get(spec.id.as_str());
+                EnterpriseRole {
+                    id: spec.id.clone(),
+                    name: spec.name.clone(),
+                    description: spec.description.clone(),
+                    permissions: spec.permissions.iter().cloned().collect(),
+                    parent_roles: spec.parents.clone(),
+                    child_roles: self
+                        .roles
+                        .iter()
+                        .filter(|r| r.parents.contains(&spec.id))
+                        .map(|r| r.id.clone())
+                        .collect(),
+                    auto_assign_conditions: previous
+                        .map(|r| r.auto_assign_conditions.clone())
+                        .unwrap_or_default(),
+                    max_assignment_duration: spec.max_assignment_days.map(Duration::days),
+                    requires_approval: spec.requires_approval,
+                    approval_roles: spec.approval_roles.clone(),
+                    risk_level: spec.risk_level.clone(),
+                    created_at: previous.map_or(now, |r| r.created_at),
+                    created_by: previous
+                        .map_or_else(|| actor.to_string(), |r| r.created_by.clone()),
+                    active: spec.active,
+                    metadata: previous.map(|r| r.metadata.clone()).unwrap_or_default(),
+                }
+            })
+            .collect();
+
+        let mut user_groups: HashMap<&str, Vec<String>> = HashMap::new();
+        for group in &self.groups {
+            for member in &group.members {
+                user_groups
+                    .entry(member.as_str())
+                    .or_default()
+                    .push(group.id.clone());
+            }
+        }
+
+        let existing_users: HashMap<&str, &EnterpriseUser> =
+            current.users.iter().map(|u| (u.id.as_str(), u)).collect();
+        let users: Vec<EnterpriseUser> = self
+            .users
+            .iter()
+            .map(|spec| {
+                let mut user = existing_users
+                    .get(spec.id.as_str())
+                    .map(|u| (*u).clone())
+                    .unwrap_or_else(|| EnterpriseUser {
+                        id: spec.id.clone(),
+                        username: String::new(),
+                        email: String::new(),
+                        full_name: String::new(),
+                        roles: vec![],
+                        teams: vec![],
+                        security_groups: vec![],
+                        active: true,
+                        created_at: now,
+                        last_login: None,
+                        password_hash: None,
+                        mfa_enabled: false,
+                        account_locked: false,
+                        lock_reason: None,
+                        last_activity: None,
+                        failed_login_attempts: 0,
+                        password_expires_at: None,
+                        must_change_password: false,
+                        session_timeout: None,
+                        ip_restrictions: vec![],
+                        metadata: HashMap::new(),
+                    });
+                user.username = spec.username.clone();
+                user.email = spec.email.clone();
+                user.full_name = spec.name.clone();
+                user.roles = spec.roles.clone();
+                user.security_groups = user_groups.remove(spec.id.as_str()).unwrap_or_default();
+                user.active = spec.active;
+                user.mfa_enabled = spec.mfa_enabled;
+                user.account_locked = spec.locked;
+                user.lock_reason = spec.lock_reason.clone();
+                user.metadata = spec.metadata.clone().into_iter().collect();
+                user
+            })
+            .collect();
+
+        let existing_groups: HashMap<&str, &SecurityGroup> =
+            current.groups.iter().map(|g| (g.id.as_str(), g)).collect();
+        let groups: Vec<SecurityGroup> = self
+            .groups
+            .iter()
+            .map(|spec| {
+                let previous = existing_groups.get(spec.id.as_str());
+                SecurityGroup {
+                    id: spec.id.clone(),
+                    name: spec.name.clone(),
+                    description: spec.description.clone(),
+                    members: spec.members.iter().cloned().collect(),
+                    security_policies: spec.security_policies.clone(),
+                    access_policies: spec.access_policies.clone(),
+                    default_roles: spec.default_roles.clone(),
+                    isolation_level: spec.isolation_level.clone(),
+                    created_at: previous.map_or(now, |g| g.created_at),
+                    metadata: previous.map(|g| g.metadata.clone()).unwrap_or_default(),
+                }
+            })
+            .collect();
+
+        let existing_policies: HashMap<&str, &AccessPolicy> = current
+            .policies
+            .iter()
+            .map(|p| (p.id.as_str(), p))
+            .collect();
+        let policies: Vec<AccessPolicy> = self
+            .policies
+            .iter()
+            .map(|spec| AccessPolicy {
+                id: spec.id.clone(),
+                name: spec.name.clone(),
+                description: spec.description.clone(),
+                policy_type: spec.policy_type.clone(),
+                conditions: spec.conditions.clone(),
+                effect: spec.effect.clone(),
+                resources: spec.resources.clone(),
+                actions: spec.actions.clone(),
+                principals: spec.principals.clone(),
+                time_restrictions: spec.time_restrictions.clone(),
+                ip_restrictions: spec.ip_restrictions.clone(),
+                priority: spec.priority,
+                active: spec.active,
+                created_at: existing_policies
+                    .get(spec.id.as_str())
+                    .map_or(now, |p| p.created_at),
+            })
+            .collect();
+
+        let existing_teams: HashMap<&str, &EnterpriseTeam> =
+            current.teams.iter().map(|t| (t.id.as_str(), t)).collect();
+        let teams: Vec<EnterpriseTeam> = self
+            .teams
+            .iter()
+            .map(|spec| {
+                let mut team = existing_teams
+                    .get(spec.id.as_str())
+                    .map(|t| (*t).clone())
+                    .unwrap_or_else(|| EnterpriseTeam {
+                        id: spec.id.clone(),
+                        name: String::new(),
+                        description: String::new(),
+                        team_type: spec.team_type.clone(),
+                        parent_team: None,
+                        child_teams: vec![],
+                        members: vec![],
+                        roles: vec![],
+                        permissions: TeamPermissions::default(),
+                        access_controls: TeamAccess::default(),
+                        settings: TeamSettings::default(),
+                        metadata: HashMap::new(),
+                        created_at: now,
+                        created_by: actor.to_string(),
+                        active: true,
+                    });
+                let previous_members: HashMap<String, TeamMember> = team
+                    .members
+                    .drain(..)
+                    .map(|m| (m.user_id.clone(), m))
+                    .collect();
+                team.name = spec.name.clone();
+                team.description = spec.description.clone();
+                team.team_type = spec.team_type.clone();
+                team.parent_team = spec.parent.clone();
+                team.child_teams = self
+                    .teams
+                    .iter()
+                    .filter(|t| t.parent.as_ref() == Some(&spec.id))
+                    .map(|t| t.id.clone())
+                    .collect();
+                team.active = spec.active;
+                team.members =
+                    spec.members
+                        .iter()
+                        .map(|member| {
+                            let mut entry = previous_members
+                                .get(&member.user)
+                                .cloned()
+                                .unwrap_or_else(|| TeamMember {
+                                    user_id: member.user.clone(),
+                                    role: String::new(),
+                                    joined_at: now,
+                                    invited_by: actor.to_string(),
+                                    status: MemberStatus::Active,
+                                    permissions: vec![],
+                                    last_activity: None,
+                                    temporary: false,
+                                    expires_at: None,
+                                });
+                            entry.role = member.role.clone();
+                            entry
+                        })
+                        .collect();
+                team
+            })
+            .collect();
+
+        let existing_grants: HashMap<&str, &ResourcePermission> =
+            current.grants.iter().map(|g| (g.id.as_str(), g)).collect();
+        let grants = self
+            .grants
+            .iter()
+            .map(|spec| {
+                let previous = existing_grants.get(spec.id.as_str());
+                Ok(ResourcePermission {
+                    id: spec.id.clone(),
+                    resource_type: spec.resource_type.clone(),
+                    resource_id: spec.resource_id.clone(),
+                    permission_name: spec.permission.clone(),
+                    scope: spec.scope.clone(),
+                    conditions: spec.conditions.clone(),
+                    granted_to: PermissionSubject::parse(&spec.subject_type, &spec.subject_id)?,
+                    granted_by: previous
+                        .map_or_else(|| actor.to_string(), |g| g.granted_by.clone()),
+                    granted_at: previous.map_or(now, |g| g.granted_at),
+                    expires_at: spec.expires_at,
+                    active: true,
+                    metadata: previous.map(|g| g.metadata.clone()).unwrap_or_default(),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut removed = Vec::new();
+        removed.extend(missing(
+            "role",
+            current.roles.iter().map(|r| &r.id),
+            &roles,
+            |r| &r.id,
+        ));
+        removed.extend(missing(
+            "user",
+            current.users.iter().map(|u| &u.id),
+            &users,
+            |u| &u.id,
+        ));
+        removed.extend(missing(
+            "group",
+            current.groups.iter().map(|g| &g.id),
+            &groups,
+            |g| &g.id,
+        ));
+        removed.extend(missing(
+            "policy",
+            current.policies.iter().map(|p| &p.id),
+            &policies,
+            |p| &p.id,
+        ));
+        removed.extend(missing(
+            "team",
+            current.teams.iter().map(|t| &t.id),
+            &teams,
+            |t| &t.id,
+        ));
+        removed.extend(missing(
+            "grant",
+            current.grants.iter().filter(|g| g.active).map(|g| &g.id),
+            &grants,
+            |g| &g.id,
+        ));
+
+        let summary = ImportSummary {
+            roles: roles.len(),
+            users: users.len(),
+            groups: groups.len(),
+            policies: policies.len(),
+            teams: teams.len(),
+            grants: grants.len(),
+            removed,
+        };
+        let snapshot = RbacSnapshot {
+            users,
+            roles,
+            groups,
+            policies,
+            teams,
+            grants,
+        };
+        Ok((snapshot, summary))
+    }
+}
+
+fn sorted(items: impl Iterator<Item = String>) -> Vec<String> {
+    let mut items: Vec<String> = items.collect();
+    items.sort();
+    items
+}
+
+fn unique_ids<'a>(
+    kind: &str,
+    ids: impl Iterator<Item = &'a String>,
+    problems: &mut Vec<String>,
+) -> HashSet<&'a str> {
+    let mut seen = HashSet::new();
+    for id in ids {
+        if !seen.insert(id.as_str()) {
+            problems.push(format!("duplicate {} id {}", kind, id));
+        }
+    }
+    seen
+}
+
+/// `kind:id` labels for IDs in `before` that `after` no longer has
+fn missing<'a, T>(
+    kind: &str,
+    before: impl Iterator<Item = &'a String>,
+    after: &[T],
+    id: impl Fn(&T) -> &String,
+) -> Vec<String> {
+    let kept: HashSet<&String> = after.iter().map(id).collect();
+    before
+        .filter(|existing| !kept.contains(existing))
+        .map(|existing| format!("{}:{}", kind, existing))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = r#"
+version = 1
+
+[[role]]
+id = "reviewer"
+name = "Reviewer"
+permissions = ["code.read", "tool.call"]
+
+[[role]]
+id = "maintainer"
+name = "Maintainer"
+permissions = ["code.write", "security.manage"]
+parents = ["reviewer"]
+risk_level = "high"
+
+[[user]]
+id = "alice"
+username = "alice"
+email = "alice@example.com"
+roles = ["maintainer"]
+
+[[user]]
+id = "bob"
+username = "bob"
+roles = ["reviewer"]
+
+[[group]]
+id = "core"
+name = "Core maintainers"
+members = ["alice"]
+
+[[team]]
+id = "platform"
+name = "Platform"
+team_type = "functional"
+members = [{ user = "bob" }]
+
+[[grant]]
+id = "docs-write"
+permission = "code.write"
+subject_type = "user"
+subject_id = "bob"
+resource_type = "file"
+resource_id = "docs"
+scope = "resource_and_children"
+"#;
+
+    #[test]
+    fn import_then_export_round_trips() {
+        let set = PolicySet::from_toml(SAMPLE).unwrap();
+        let (snapshot, summary) = set.apply(&RbacSnapshot::default(), "tester").unwrap();
+        assert_eq!(summary.users, 2);
+        assert!(summary.removed.is_empty());
+
+        let alice = snapshot.users.iter().find(|u| u.id == "alice").unwrap();
+        assert_eq!(alice.security_groups, vec!["core".to_string()]);
+        let reviewer = snapshot.roles.iter().find(|r| r.id == "reviewer").unwrap();
+        assert_eq!(reviewer.child_roles, vec!["maintainer".to_string()]);
+        assert_eq!(snapshot.teams[0].members[0].role, "member");
+
+        let exported = PolicySet::from_snapshot(&snapshot).to_toml().unwrap();
+        let reparsed = PolicySet::from_toml(&exported).unwrap();
+        assert_eq!(reparsed.to_toml().unwrap(), exported);
+        assert_eq!(reparsed.roles[0].id, "maintainer");
+    }
+
+    #[test]
+    fn reimport_keeps_runtime_state_and_reports_removals() {
+        let set = PolicySet::from_toml(SAMPLE).unwrap();
+        let (mut snapshot, _) = set.apply(&RbacSnapshot::default(), "tester").unwrap();
+        let login = Utc::now() - Duration::days(1);
+        snapshot.users[0].last_login = Some(login);
+
+        let mut trimmed = set.clone();
+        trimmed.users.retain(|u| u.id == "alice");
+        trimmed.teams.clear();
+        trimmed.grants.clear();
+        let (next, summary) = trimmed.apply(&snapshot, "tester").unwrap();
+
+        assert_eq!(next.users[0].last_login, Some(login));
+        assert_eq!(
+            summary.removed,
+            vec!["user:bob", "team:platform", "grant:docs-write"]
+        );
+    }
+
+    #[test]
+    fn dangling_references_are_rejected() {
+        let broken = SAMPLE.replace(r#"roles = ["reviewer"]"#, r#"roles = ["owner"]"#);
+        let error = PolicySet::from_toml(&broken).unwrap_err().to_string();
+        assert!(
+            error.contains("user bob has unknown role owner"),
+            "{}",
+            error
+        );
+    }
+}
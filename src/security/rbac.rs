@@ -14,7 +14,10 @@ use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
-use super::{audit::EnterpriseAuditLogger, PermissionManager};
+use super::{audit::EnterpriseAuditLogger, store::RbacStore, PermissionManager};
+
+/// Permission required to change users, roles, groups, teams and grants
+pub const SECURITY_ADMIN_PERMISSION: &str = "security.manage";
 
 /// Enterprise RBAC manager
 pub struct EnterpriseRbacManager {
@@ -25,6 +28,7 @@ pub struct EnterpriseRbacManager {
     role_assignments: Arc<RwLock<HashMap<String, Vec<RoleAssignment>>>>,
     permission_manager: Arc<PermissionManager>,
     audit_logger: Arc<EnterpriseAuditLogger>,
+    store: Option<Arc<RbacStore>>,
 }
 
 /// Enhanced enterprise user with additional security features
@@ -60,7 +64,9 @@ pub struct EnterpriseRole {
     pub name: String,
     pub description: String,
     pub permissions: HashSet<String>,
+    /// Roles whose permissions this role inherits
     pub parent_roles: Vec<String>,
+    /// Roles that inherit this role's permissions
     pub child_roles: Vec<String>,
     pub auto_assign_conditions: Vec<AutoAssignCondition>,
     pub max_assignment_duration: Option<Duration>,
@@ -181,6 +187,17 @@ pub enum ApprovalStatus {
     Expired,
 }
 
+impl ApprovalStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ApprovalStatus::Pending => "pending",
+            ApprovalStatus::Approved => "approved",
+            ApprovalStatus::Rejected => "rejected",
+            ApprovalStatus::Expired => "expired",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum AssignmentSource {
@@ -251,19 +268,63 @@ impl EnterpriseRbacManager {
             role_assignments: Arc::new(RwLock::new(HashMap::new())),
             permission_manager,
             audit_logger,
+            store: None,
         })
     }
 
-    /// Initialize with default enterprise roles
+    /// Write every change through to `store`
+    pub fn with_store(mut self, store: Arc<RbacStore>) -> Self {
+        self.store = Some(store);
+        self
+    }
+
+    /// Replace the in-memory state with what the store holds
+    pub async fn load(&self) -> Result<()> {
+        let Some(store) = &self.store else {
+            return Ok(());
+        };
+
+        *self.users.write().await = by_id(store.load_users()?, |u| &u.id);
+        *self.roles.write().await = by_id(store.load_roles()?, |r| &r.id);
+        *self.security_groups.write().await = by_id(store.load_groups()?, |g| &g.id);
+        *self.access_policies.write().await = by_id(store.load_policies()?, |p| &p.id);
+
+        let mut assignments: HashMap<String, Vec<RoleAssignment>> = HashMap::new();
+        for assignment in store.load_assignments()? {
+            assignments
+                .entry(assignment.user_id.clone())
+                .or_default()
+                .push(assignment);
+        }
+        *self.role_assignments.write().await = assignments;
+
+        Ok(())
+    }
+
+    fn persist(&self, write: impl FnOnce(&RbacStore) -> Result<()>) -> Result<()> {
+        match &self.store {
+            Some(store) => write(store),
+            None => Ok(()),
+        }
+    }
+
+    /// Initialize with default enterprise roles.
+    ///
+    /// With a store attached the defaults are only seeded into an empty
+    /// store, so roles an administrator removed do not come back.
     pub async fn initialize_default_roles(&self) -> Result<()> {
+        if self.store.is_some() && !self.roles.read().await.is_empty() {
+            return Ok(());
+        }
+
         let default_roles = vec![
             EnterpriseRole {
                 id: "super_admin".to_string(),
                 name: "Super Administrator".to_string(),
                 description: "Full system access with all privileges".to_string(),
                 permissions: ["*".to_string()].into_iter().collect(),
-                parent_roles: vec![],
-                child_roles: vec!["admin".to_string()],
+                parent_roles: vec!["admin".to_string()],
+                child_roles: vec![],
                 auto_assign_conditions: vec![],
                 max_assignment_duration: None,
                 requires_approval: true,
@@ -287,11 +348,12 @@ impl EnterpriseRbacManager {
                     "role.read".to_string(),
                     "role.update".to_string(),
                     "system.configure".to_string(),
+                    SECURITY_ADMIN_PERMISSION.to_string(),
                 ]
                 .into_iter()
                 .collect(),
-                parent_roles: vec!["super_admin".to_string()],
-                child_roles: vec!["manager".to_string()],
+                parent_roles: vec!["manager".to_string()],
+                child_roles: vec!["super_admin".to_string()],
                 auto_assign_conditions: vec![],
                 max_assignment_duration: Some(Duration::days(365)),
                 requires_approval: true,
@@ -316,8 +378,8 @@ impl EnterpriseRbacManager {
                 ]
                 .into_iter()
                 .collect(),
-                parent_roles: vec!["admin".to_string()],
-                child_roles: vec!["developer".to_string(), "analyst".to_string()],
+                parent_roles: vec!["developer".to_string(), "analyst".to_string()],
+                child_roles: vec!["admin".to_string()],
                 auto_assign_conditions: vec![AutoAssignCondition {
                     condition_type: "department".to_string(),
                     operator: "equals".to_string(),
@@ -343,11 +405,13 @@ impl EnterpriseRbacManager {
                     "code.write".to_string(),
                     "build.create".to_string(),
                     "test.run".to_string(),
+                    "tool.call".to_string(),
+                    "command.run".to_string(),
                 ]
                 .into_iter()
                 .collect(),
-                parent_roles: vec!["manager".to_string()],
-                child_roles: vec![],
+                parent_roles: vec![],
+                child_roles: vec!["manager".to_string()],
                 auto_assign_conditions: vec![AutoAssignCondition {
                     condition_type: "department".to_string(),
                     operator: "equals".to_string(),
@@ -374,8 +438,8 @@ impl EnterpriseRbacManager {
                 ]
                 .into_iter()
                 .collect(),
-                parent_roles: vec!["manager".to_string()],
-                child_roles: vec![],
+                parent_roles: vec![],
+                child_roles: vec!["manager".to_string()],
                 auto_assign_conditions: vec![],
                 max_assignment_duration: Some(Duration::days(60)),
                 requires_approval: false,
@@ -413,8 +477,10 @@ impl EnterpriseRbacManager {
 
         let mut roles = self.roles.write().await;
         for role in default_roles {
+            self.persist(|store| store.save_role(&role))?;
             roles.insert(role.id.clone(), role);
         }
+        drop(roles);
 
         // Create default security groups
         self.create_default_security_groups().await?;
@@ -467,6 +533,7 @@ impl EnterpriseRbacManager {
 
         let mut groups = self.security_groups.write().await;
         for group in default_groups {
+            self.persist(|store| store.save_group(&group))?;
             groups.insert(group.id.clone(), group);
         }
 
@@ -547,6 +614,7 @@ impl EnterpriseRbacManager {
 
         let mut policies = self.access_policies.write().await;
         for policy in default_policies {
+            self.persist(|store| store.save_policy(&policy))?;
             policies.insert(policy.id.clone(), policy);
         }
 
@@ -564,8 +632,13 @@ impl EnterpriseRbacManager {
         // Auto-assign roles based on conditions
         let auto_assigned_roles = self.evaluate_auto_assign_roles(&user).await?;
         let mut user_with_roles = user.clone();
-        user_with_roles.roles.extend(auto_assigned_roles);
+        for role in auto_assigned_roles {
+            if !user_with_roles.roles.contains(&role) {
+                user_with_roles.roles.push(role);
+            }
+        }
 
+        self.persist(|store| store.save_user(&user_with_roles))?;
         users.insert(user.id.clone(), user_with_roles);
 
         // Log user creation
@@ -632,6 +705,10 @@ impl EnterpriseRbacManager {
 
     /// Assign role to user with approval workflow
     pub async fn assign_role(&self, user_id: &str, role_id: &str) -> Result<()> {
+        if !self.users.read().await.contains_key(user_id) {
+            return Err(anyhow!("User not found: {}", user_id));
+        }
+
         // Check if role requires approval
         let role = {
             let roles = self.roles.read().await;
@@ -662,20 +739,22 @@ impl EnterpriseRbacManager {
         };
 
         // Store assignment
+        self.persist(|store| store.save_assignment(&assignment))?;
         let mut assignments = self.role_assignments.write().await;
         assignments
             .entry(user_id.to_string())
             .or_insert_with(Vec::new)
             .push(assignment);
+        drop(assignments);
 
         // If no approval required, assign immediately
         if !role.requires_approval {
-            let mut users = self.users.write().await;
-            if let Some(user) = users.get_mut(user_id) {
+            self.modify_user(user_id, |user| {
                 if !user.roles.contains(&role_id.to_string()) {
                     user.roles.push(role_id.to_string());
                 }
-            }
+            })
+            .await?;
 
             self.audit_logger
                 .log_user_event(
@@ -801,6 +880,11 @@ impl EnterpriseRbacManager {
             let actual_value = match condition.attribute.as_str() {
                 "permission" => permission.to_string(),
                 "resource" => resource.unwrap_or("").to_string(),
+                "action" => permission.to_string(),
+                "resource_type" => resource
+                    .and_then(|r| r.split(':').next())
+                    .unwrap_or("")
+                    .to_string(),
                 "user_id" => user.id.clone(),
                 "department" => user.metadata.get("department").cloned().unwrap_or_default(),
                 "time" => {
@@ -884,10 +968,328 @@ impl EnterpriseRbacManager {
         Ok(users.get(user_id).cloned())
     }
 
-    /// List all roles
+    /// List all users, ordered by ID
+    pub async fn list_users(&self) -> Result<Vec<EnterpriseUser>> {
+        let users = self.users.read().await;
+        Ok(sorted_by_id(users.values().cloned().collect(), |u| &u.id))
+    }
+
+    /// Whether any user exists
+    pub async fn has_users(&self) -> bool {
+        !self.users.read().await.is_empty()
+    }
+
+    /// Apply `change` to a user and persist the result
+    async fn modify_user(
+        &self,
+        user_id: &str,
+        change: impl FnOnce(&mut EnterpriseUser),
+    ) -> Result<EnterpriseUser> {
+        let mut users = self.users.write().await;
+        let user = users
+            .get_mut(user_id)
+            .ok_or_else(|| anyhow!("User not found: {}", user_id))?;
+        change(user);
+        self.persist(|store| store.save_user(user))?;
+        Ok(user.clone())
+    }
+
+    /// Delete a user, their role assignments and their group memberships
+    pub async fn delete_user(&self, user_id: &str) -> Result<()> {
+        if self.users.write().await.remove(user_id).is_none() {
+            return Err(anyhow!("User not found: {}", user_id));
+        }
+        self.role_assignments.write().await.remove(user_id);
+        self.persist(|store| store.delete_user(user_id).map(|_| ()))?;
+
+        let mut groups = self.security_groups.write().await;
+        for group in groups.values_mut() {
+            if group.members.remove(user_id) {
+                self.persist(|store| store.save_group(group))?;
+            }
+        }
+        drop(groups);
+
+        self.audit_logger
+            .log_user_event(
+                super::audit::AuditEventType::UserDeleted,
+                user_id,
+                format!("Enterprise user deleted: {}", user_id),
+            )
+            .await
+    }
+
+    /// Remove a role from a user and deactivate its assignments
+    pub async fn remove_role(&self, user_id: &str, role_id: &str) -> Result<()> {
+        let has_role = self
+            .users
+            .read()
+            .await
+            .get(user_id)
+            .ok_or_else(|| anyhow!("User not found: {}", user_id))?
+            .roles
+            .iter()
+            .any(|r| r == role_id);
+        if !has_role {
+            return Err(anyhow!("User {} does not have role {}", user_id, role_id));
+        }
+
+        self.modify_user(user_id, |user| user.roles.retain(|r| r != role_id))
+            .await?;
+
+        if let Some(assignments) = self.role_assignments.write().await.get_mut(user_id) {
+            for assignment in assignments
+                .iter_mut()
+                .filter(|a| a.role_id == role_id && a.active)
+            {
+                assignment.active = false;
+                self.persist(|store| store.save_assignment(assignment))?;
+            }
+        }
+
+        self.audit_logger
+            .log_user_event(
+                super::audit::AuditEventType::RoleRevoked,
+                user_id,
+                format!("Role {} removed", role_id),
+            )
+            .await
+    }
+
+    /// Approve a pending role assignment and grant the role
+    pub async fn approve_role(&self, user_id: &str, role_id: &str, approver: &str) -> Result<()> {
+        {
+            let mut assignments = self.role_assignments.write().await;
+            let assignment = assignments
+                .get_mut(user_id)
+                .and_then(|list| {
+                    list.iter_mut().find(|a| {
+                        a.role_id == role_id && a.approval_status == ApprovalStatus::Pending
+                    })
+                })
+                .ok_or_else(|| {
+                    anyhow!(
+                        "No pending assignment of role {} for user {}",
+                        role_id,
+                        user_id
+                    )
+                })?;
+            assignment.approval_status = ApprovalStatus::Approved;
+            assignment.approved_by = Some(approver.to_string());
+            assignment.approved_at = Some(Utc::now());
+            assignment.active = true;
+            self.persist(|store| store.save_assignment(assignment))?;
+        }
+
+        self.modify_user(user_id, |user| {
+            if !user.roles.iter().any(|r| r == role_id) {
+                user.roles.push(role_id.to_string());
+            }
+        })
+        .await?;
+
+        self.audit_logger
+            .log_user_event(
+                super::audit::AuditEventType::RoleAssigned,
+                user_id,
+                format!("Role {} approved by {}", role_id, approver),
+            )
+            .await
+    }
+
+    /// Lock a user account, or unlock it when `reason` is `None`
+    pub async fn set_locked(&self, user_id: &str, reason: Option<String>) -> Result<()> {
+        let locked = reason.is_some();
+        self.modify_user(user_id, |user| {
+            user.account_locked = locked;
+            user.lock_reason = reason;
+            if !locked {
+                user.failed_login_attempts = 0;
+            }
+        })
+        .await?;
+
+        let (event, details) = if locked {
+            (
+                super::audit::AuditEventType::UserSuspended,
+                "Account locked",
+            )
+        } else {
+            (
+                super::audit::AuditEventType::UserReactivated,
+                "Account unlocked",
+            )
+        };
+        self.audit_logger
+            .log_user_event(event, user_id, details.to_string())
+            .await
+    }
+
+    /// List all roles, ordered by ID
     pub async fn list_roles(&self) -> Result<Vec<EnterpriseRole>> {
         let roles = self.roles.read().await;
-        Ok(roles.values().cloned().collect())
+        Ok(sorted_by_id(roles.values().cloned().collect(), |r| &r.id))
+    }
+
+    /// Get role by ID
+    pub async fn get_role(&self, role_id: &str) -> Result<Option<EnterpriseRole>> {
+        Ok(self.roles.read().await.get(role_id).cloned())
+    }
+
+    /// Create a new role
+    pub async fn create_role(&self, role: EnterpriseRole) -> Result<()> {
+        let mut roles = self.roles.write().await;
+        if roles.contains_key(&role.id) {
+            return Err(anyhow!("Role already exists: {}", role.id));
+        }
+        self.persist(|store| store.save_role(&role))?;
+        roles.insert(role.id.clone(), role);
+        Ok(())
+    }
+
+    /// Replace an existing role
+    pub async fn update_role(&self, role: EnterpriseRole) -> Result<()> {
+        let mut roles = self.roles.write().await;
+        if !roles.contains_key(&role.id) {
+            return Err(anyhow!("Role not found: {}", role.id));
+        }
+        self.persist(|store| store.save_role(&role))?;
+        roles.insert(role.id.clone(), role);
+        Ok(())
+    }
+
+    /// Delete a role that no user holds
+    pub async fn delete_role(&self, role_id: &str) -> Result<()> {
+        let holders = self
+            .users
+            .read()
+            .await
+            .values()
+            .filter(|u| u.roles.iter().any(|r| r == role_id))
+            .count();
+        if holders > 0 {
+            return Err(anyhow!(
+                "Role {} is still assigned to {} user(s)",
+                role_id,
+                holders
+            ));
+        }
+
+        if self.roles.write().await.remove(role_id).is_none() {
+            return Err(anyhow!("Role not found: {}", role_id));
+        }
+        self.persist(|store| store.delete_role(role_id).map(|_| ()))
+    }
+
+    /// List security groups, ordered by ID
+    pub async fn list_security_groups(&self) -> Result<Vec<SecurityGroup>> {
+        let groups = self.security_groups.read().await;
+        Ok(sorted_by_id(groups.values().cloned().collect(), |g| &g.id))
+    }
+
+    /// Get security group by ID
+    pub async fn get_security_group(&self, group_id: &str) -> Result<Option<SecurityGroup>> {
+        Ok(self.security_groups.read().await.get(group_id).cloned())
+    }
+
+    /// Create a new security group
+    pub async fn create_security_group(&self, group: SecurityGroup) -> Result<()> {
+        let mut groups = self.security_groups.write().await;
+        if groups.contains_key(&group.id) {
+            return Err(anyhow!("Security group already exists: {}", group.id));
+        }
+        self.persist(|store| store.save_group(&group))?;
+        groups.insert(group.id.clone(), group);
+        Ok(())
+    }
+
+    /// Add a user to a security group
+    pub async fn add_user_to_group(&self, group_id: &str, user_id: &str) -> Result<()> {
+        self.set_group_membership(group_id, user_id, true).await
+    }
+
+    /// Remove a user from a security group
+    pub async fn remove_user_from_group(&self, group_id: &str, user_id: &str) -> Result<()> {
+        self.set_group_membership(group_id, user_id, false).await
+    }
+
+    /// Keep the group's member set and the user's group list in step
+    async fn set_group_membership(
+        &self,
+        group_id: &str,
+        user_id: &str,
+        member: bool,
+    ) -> Result<()> {
+        let user_exists = self.users.read().await.contains_key(user_id);
+        if member && !user_exists {
+            return Err(anyhow!("User not found: {}", user_id));
+        }
+
+        {
+            let mut groups = self.security_groups.write().await;
+            let group = groups
+                .get_mut(group_id)
+                .ok_or_else(|| anyhow!("Security group not found: {}", group_id))?;
+            let changed = if member {
+                group.members.insert(user_id.to_string())
+            } else {
+                group.members.remove(user_id)
+            };
+            if !changed {
+                return Err(anyhow!(
+                    "User {} is {} a member of group {}",
+                    user_id,
+                    if member { "already" } else { "not" },
+                    group_id
+                ));
+            }
+            self.persist(|store| store.save_group(group))?;
+        }
+
+        if user_exists {
+            self.modify_user(user_id, |user| {
+                user.security_groups.retain(|g| g != group_id);
+                if member {
+                    user.security_groups.push(group_id.to_string());
+                }
+            })
+            .await?;
+        }
+        Ok(())
+    }
+
+    /// Delete a security group and drop it from its members
+    pub async fn delete_security_group(&self, group_id: &str) -> Result<()> {
+        let group = self
+            .security_groups
+            .write()
+            .await
+            .remove(group_id)
+            .ok_or_else(|| anyhow!("Security group not found: {}", group_id))?;
+        self.persist(|store| store.delete_group(group_id).map(|_| ()))?;
+
+        for user_id in &group.members {
+            if self.users.read().await.contains_key(user_id) {
+                self.modify_user(user_id, |user| {
+                    user.security_groups.retain(|g| g != group_id)
+                })
+                .await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// List access policies, highest priority first
+    pub async fn list_access_policies(&self) -> Result<Vec<AccessPolicy>> {
+        let mut policies: Vec<AccessPolicy> = self
+            .access_policies
+            .read()
+            .await
+            .values()
+            .cloned()
+            .collect();
+        policies.sort_by(|a, b| b.priority.cmp(&a.priority).then_with(|| a.id.cmp(&b.id)));
+        Ok(policies)
     }
 
     /// Get RBAC statistics
@@ -917,6 +1319,18 @@ impl EnterpriseRbacManager {
     }
 }
 
+fn by_id<T>(items: Vec<T>, id: impl Fn(&T) -> &String) -> HashMap<String, T> {
+    items
+        .into_iter()
+        .map(|item| (id(&item).clone(), item))
+        .collect()
+}
+
+fn sorted_by_id<T>(mut items: Vec<T>, id: impl Fn(&T) -> &String) -> Vec<T> {
+    items.sort_by(|a, b| id(a).cmp(id(b)));
+    items
+}
+
 /// RBAC statistics for monitoring
 #[derive(Debug, Serialize, Deserialize)]
 pub struct RbacStatistics {
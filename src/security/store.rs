@@ -0,0 +1,400 @@
+//! RBAC Store - SQLite persistence for users, roles, teams and grants
+//!
+//! The RBAC, team and permission managers keep their maps as a cache and
+//! write every change through to these tables, so `hive security` edits
+//! survive restarts and are seen by enforcement checks in other processes.
+
+use super::permissions::ResourcePermission;
+use super::rbac::{AccessPolicy, EnterpriseRole, EnterpriseUser, RoleAssignment, SecurityGroup};
+use super::teams::EnterpriseTeam;
+use crate::core::database::DatabaseManager;
+use anyhow::{Context, Result};
+use chrono::Utc;
+use rusqlite::{params, types::ToSql, Connection, TransactionBehavior};
+use serde::{de::DeserializeOwned, Serialize};
+use std::sync::Arc;
+
+/// Same statements as the numbered migration; all of them are idempotent
+const SCHEMA: &str = include_str!("../../migrations/014_add_rbac_tables.sql");
+
+const USERS: &str = "rbac_users";
+const ROLES: &str = "rbac_roles";
+const GROUPS: &str = "rbac_security_groups";
+const POLICIES: &str = "rbac_access_policies";
+const ASSIGNMENTS: &str = "rbac_role_assignments";
+const TEAMS: &str = "security_teams";
+const GRANTS: &str = "permission_grants";
+
+/// Every persisted RBAC entity except role assignment history
+#[derive(Debug, Clone, Default)]
+pub struct RbacSnapshot {
+    pub users: Vec<EnterpriseUser>,
+    pub roles: Vec<EnterpriseRole>,
+    pub groups: Vec<SecurityGroup>,
+    pub policies: Vec<AccessPolicy>,
+    pub teams: Vec<EnterpriseTeam>,
+    pub grants: Vec<ResourcePermission>,
+}
+
+/// RBAC state shared by every process using the same database
+pub struct RbacStore {
+    database: Arc<DatabaseManager>,
+}
+
+impl RbacStore {
+    /// Open the store, creating its tables if needed
+    pub fn new(database: Arc<DatabaseManager>) -> Result<Self> {
+        let conn = database.get_connection()?;
+        conn.execute_batch(SCHEMA)
+            .context("Failed to create RBAC tables")?;
+        Ok(Self { database })
+    }
+
+    /// Whether any user exists; enforcement is off until one does
+    pub fn has_users(&self) -> Result<bool> {
+        let conn = self.database.get_connection()?;
+        let count: i64 = conn.query_row("SELECT COUNT(*) FROM rbac_users", [], |row| row.get(0))?;
+        Ok(count > 0)
+    }
+
+    pub fn save_user(&self, user: &EnterpriseUser) -> Result<()> {
+        let conn = self.database.get_connection()?;
+        upsert_user(&conn, user)
+    }
+
+    pub fn load_users(&self) -> Result<Vec<EnterpriseUser>> {
+        self.load_all(USERS)
+    }
+
+    /// Remove a user together with their role assignment history
+    pub fn delete_user(&self, id: &str) -> Result<bool> {
+        let conn = self.database.get_connection()?;
+        conn.execute(
+            "DELETE FROM rbac_role_assignments WHERE user_id = ?1",
+            params![id],
+        )?;
+        delete_row(&conn, USERS, id)
+    }
+
+    pub fn save_role(&self, role: &EnterpriseRole) -> Result<()> {
+        let conn = self.database.get_connection()?;
+        upsert_role(&conn, role)
+    }
+
+    pub fn load_roles(&self) -> Result<Vec<EnterpriseRole>> {
+        self.load_all(ROLES)
+    }
+
+    pub fn delete_role(&self, id: &str) -> Result<bool> {
+        let conn = self.database.get_connection()?;
+        delete_row(&conn, ROLES, id)
+    }
+
+    pub fn save_group(&self, group: &SecurityGroup) -> Result<()> {
+        let conn = self.database.get_connection()?;
+        upsert_group(&conn, group)
+    }
+
+    pub fn load_groups(&self) -> Result<Vec<SecurityGroup>> {
+        self.load_all(GROUPS)
+    }
+
+    pub fn delete_group(&self, id: &str) -> Result<bool> {
+        let conn = self.database.get_connection()?;
+        delete_row(&conn, GROUPS, id)
+    }
+
+    pub fn save_policy(&self, policy: &AccessPolicy) -> Result<()> {
+        let conn = self.database.get_connection()?;
+        upsert_policy(&conn, policy)
+    }
+
+    pub fn load_policies(&self) -> Result<Vec<AccessPolicy>> {
+        self.load_all(POLICIES)
+    }
+
+    pub fn save_assignment(&self, assignment: &RoleAssignment) -> Result<()> {
+        let conn = self.database.get_connection()?;
+        upsert(
+            &conn,
+            ASSIGNMENTS,
+            &assignment.id,
+            assignment,
+            &[
+                ("user_id", &assignment.user_id),
+                ("role_id", &assignment.role_id),
+                ("approval_status", &assignment.approval_status.as_str()),
+            ],
+        )
+    }
+
+    pub fn load_assignments(&self) -> Result<Vec<RoleAssignment>> {
+        self.load_all(ASSIGNMENTS)
+    }
+
+    pub fn save_team(&self, team: &EnterpriseTeam) -> Result<()> {
+        let conn = self.database.get_connection()?;
+        upsert_team(&conn, team)
+    }
+
+    pub fn load_teams(&self) -> Result<Vec<EnterpriseTeam>> {
+        self.load_all(TEAMS)
+    }
+
+    pub fn delete_team(&self, id: &str) -> Result<bool> {
+        let conn = self.database.get_connection()?;
+        delete_row(&conn, TEAMS, id)
+    }
+
+    pub fn save_grant(&self, grant: &ResourcePermission) -> Result<()> {
+        let conn = self.database.get_connection()?;
+        upsert_grant(&conn, grant)
+    }
+
+    pub fn load_grants(&self) -> Result<Vec<ResourcePermission>> {
+        self.load_all(GRANTS)
+    }
+
+    /// Everything needed to export the policy set
+    pub fn snapshot(&self) -> Result<RbacSnapshot> {
+        Ok(RbacSnapshot {
+            users: self.load_users()?,
+            roles: self.load_roles()?,
+            groups: self.load_groups()?,
+            policies: self.load_policies()?,
+            teams: self.load_teams()?,
+            grants: self.load_grants()?,
+        })
+    }
+
+    /// Replace every entity in the snapshot's tables in one transaction.
+    /// Role assignment history is kept.
+    pub fn replace(&self, snapshot: &RbacSnapshot) -> Result<()> {
+        let mut conn = self.database.get_connection()?;
+        let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+        for table in [USERS, ROLES, GROUPS, POLICIES, TEAMS, GRANTS] {
+            tx.execute(&format!("DELETE FROM {}", table), [])?;
+        }
+        for user in &snapshot.users {
+            upsert_user(&tx, user)?;
+        }
+        for role in &snapshot.roles {
+            upsert_role(&tx, role)?;
+        }
+        for group in &snapshot.groups {
+            upsert_group(&tx, group)?;
+        }
+        for policy in &snapshot.policies {
+            upsert_policy(&tx, policy)?;
+        }
+        for team in &snapshot.teams {
+            upsert_team(&tx, team)?;
+        }
+        for grant in &snapshot.grants {
+            upsert_grant(&tx, grant)?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn load_all<T: DeserializeOwned>(&self, table: &str) -> Result<Vec<T>> {
+        let conn = self.database.get_connection()?;
+        let rows: Vec<(String, String)> = conn
+            .prepare(&format!("SELECT id, data FROM {} ORDER BY id", table))?
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<rusqlite::Result<_>>()?;
+        rows.into_iter()
+            .map(|(id, data)| {
+                serde_json::from_str(&data)
+                    .with_context(|| format!("Corrupt row '{}' in {}", id, table))
+            })
+            .collect()
+    }
+}
+
+fn upsert_user(conn: &Connection, user: &EnterpriseUser) -> Result<()> {
+    upsert(
+        conn,
+        USERS,
+        &user.id,
+        user,
+        &[
+            ("username", &user.username),
+            ("email", &user.email),
+            ("active", &user.active),
+        ],
+    )
+}
+
+fn upsert_role(conn: &Connection, role: &EnterpriseRole) -> Result<()> {
+    upsert(
+        conn,
+        ROLES,
+        &role.id,
+        role,
+        &[("name", &role.name), ("active", &role.active)],
+    )
+}
+
+fn upsert_group(conn: &Connection, group: &SecurityGroup) -> Result<()> {
+    upsert(conn, GROUPS, &group.id, group, &[("name", &group.name)])
+}
+
+fn upsert_policy(conn: &Connection, policy: &AccessPolicy) -> Result<()> {
+    upsert(
+        conn,
+        POLICIES,
+        &policy.id,
+        policy,
+        &[("priority", &policy.priority), ("active", &policy.active)],
+    )
+}
+
+fn upsert_team(conn: &Connection, team: &EnterpriseTeam) -> Result<()> {
+    upsert(
+        conn,
+        TEAMS,
+        &team.id,
+        team,
+        &[
+            ("name", &team.name),
+            ("parent_team", &team.parent_team),
+            ("active", &team.active),
+        ],
+    )
+}
+
+fn upsert_grant(conn: &Connection, grant: &ResourcePermission) -> Result<()> {
+    upsert(
+        conn,
+        GRANTS,
+        &grant.id,
+        grant,
+        &[
+            ("subject_type", &grant.granted_to.kind()),
+            ("subject_id", &grant.granted_to.id()),
+            ("permission_name", &grant.permission_name),
+            ("resource_type", &grant.resource_type),
+            ("resource_id", &grant.resource_id),
+            ("active", &grant.active),
+        ],
+    )
+}
+
+/// Insert or replace a row; `columns` are the lookup copies next to `data`
+fn upsert<T: Serialize>(
+    conn: &Connection,
+    table: &str,
+    id: &str,
+    entity: &T,
+    columns: &[(&str, &dyn ToSql)],
+) -> Result<()> {
+    let data = serde_json::to_string(entity)?;
+    let updated_at = Utc::now().to_rfc3339();
+
+    let names: Vec<&str> = columns.iter().map(|(name, _)| *name).collect();
+    let placeholders: Vec<String> = (0..columns.len()).map(|i| format!("?{}", i + 4)).collect();
+    let updates: Vec<String> = names
+        .iter()
+        .map(|name| format!("{0} = excluded.{0}", name))
+        .collect();
+    let sql = format!(
+        "INSERT INTO {table} (id, data, updated_at{sep}{names})
+         VALUES (?1, ?2, ?3{sep}{placeholders})
+         ON CONFLICT(id) DO UPDATE SET
+            data = excluded.data, updated_at = excluded.updated_at{sep}{updates}",
+        table = table,
+        sep = if columns.is_empty() { "" } else { ", " },
+        names = names.join(", "),
+        placeholders = placeholders.join(", "),
+        updates = updates.join(", "),
+    );
+
+    let mut values: Vec<&dyn ToSql> = vec![&id, &data, &updated_at];
+    values.extend(columns.iter().map(|(_, value)| *value));
+    conn.execute(&sql, values.as_slice())?;
+    Ok(())
+}
+
+fn delete_row(conn: &Connection, table: &str, id: &str) -> Result<bool> {
+    let deleted = conn.execute(&format!("DELETE FROM {} WHERE id = ?1", table), params![id])?;
+    Ok(deleted > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::database::DatabaseConfig;
+    use crate::security::permissions::{PermissionScope, PermissionSubject};
+    use std::collections::HashMap;
+    use tempfile::TempDir;
+
+    async fn test_store() -> (RbacStore, TempDir) {
+        let dir = TempDir::new().unwrap();
+        let database = Arc::new(
+            DatabaseManager::new(DatabaseConfig {
+                path: dir.path().join("rbac.db"),
+                ..DatabaseConfig::default()
+            })
+            .await
+            .unwrap(),
+        );
+        (RbacStore::new(database).unwrap(), dir)
+    }
+
+    fn grant(id: &str, user: &str) -> ResourcePermission {
+        ResourcePermission {
+            id: id.to_string(),
+            resource_type: "file".to_string(),
+            resource_id: "src".to_string(),
+            permission_name: "code.write".to_string(),
+            scope: PermissionScope::ResourceAndChildren,
+            conditions: vec![],
+            granted_to: PermissionSubject::User(user.to_string()),
+            granted_by: "admin".to_string(),
+            granted_at: Utc::now(),
+            expires_at: None,
+            active: true,
+            metadata: HashMap::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn saved_rows_are_updated_in_place() {
+        let (store, _dir) = test_store().await;
+        assert!(store.load_grants().unwrap().is_empty());
+
+        let mut first = grant("g1", "alice");
+        store.save_grant(&first).unwrap();
+        store.save_grant(&grant("g2", "bob")).unwrap();
+        first.active = false;
+        store.save_grant(&first).unwrap();
+
+        let grants = store.load_grants().unwrap();
+        assert_eq!(grants.len(), 2);
+        assert_eq!(grants[0].id, "g1");
+        assert!(!grants[0].active);
+        assert_eq!(grants[1].granted_to.id(), "bob");
+        assert!(!store.has_users().unwrap());
+    }
+
+    #[tokio::test]
+    async fn replace_swaps_the_whole_set() {
+        let (store, _dir) = test_store().await;
+        store.save_grant(&grant("old", "alice")).unwrap();
+
+        let snapshot = RbacSnapshot {
+            grants: vec![grant("new", "carol")],
+            ..RbacSnapshot::default()
+        };
+        store.replace(&snapshot).unwrap();
+
+        let ids: Vec<String> = store
+            .load_grants()
+            .unwrap()
+            .into_iter()
+            .map(|g| g.id)
+            .collect();
+        assert_eq!(ids, vec!["new".to_string()]);
+    }
+}
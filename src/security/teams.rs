@@ -13,7 +13,7 @@ use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
-use super::{audit::EnterpriseAuditLogger, EnterpriseRbacManager};
+use super::{audit::EnterpriseAuditLogger, store::RbacStore, EnterpriseRbacManager};
 
 /// Team manager for enterprise team operations
 pub struct TeamManager {
@@ -22,6 +22,7 @@ pub struct TeamManager {
     invitations: Arc<RwLock<HashMap<String, TeamInvitation>>>,
     rbac_manager: Arc<EnterpriseRbacManager>,
     audit_logger: Arc<EnterpriseAuditLogger>,
+    store: Option<Arc<RbacStore>>,
 }
 
 /// Enhanced enterprise team with advanced features
@@ -288,9 +289,77 @@ impl TeamManager {
             invitations: Arc::new(RwLock::new(HashMap::new())),
             rbac_manager,
             audit_logger,
+            store: None,
         })
     }
 
+    /// Write every team change through to `store`
+    pub fn with_store(mut self, store: Arc<RbacStore>) -> Self {
+        self.store = Some(store);
+        self
+    }
+
+    /// Replace the in-memory teams with what the store holds and rebuild
+    /// the hierarchies from their parent links
+    pub async fn load(&self) -> Result<()> {
+        let Some(store) = &self.store else {
+            return Ok(());
+        };
+
+        let teams: HashMap<String, EnterpriseTeam> = store
+            .load_teams()?
+            .into_iter()
+            .map(|team| (team.id.clone(), team))
+            .collect();
+
+        let mut hierarchies: HashMap<String, TeamHierarchy> = HashMap::new();
+        for team in teams.values() {
+            let Some(parent_id) = &team.parent_team else {
+                continue;
+            };
+
+            // Walk up to the root, guarding against cycles in edited data
+            let mut level = 1;
+            let mut root = parent_id.clone();
+            let mut seen = HashSet::from([team.id.clone()]);
+            while let Some(next) = teams.get(&root).and_then(|t| t.parent_team.clone()) {
+                if !seen.insert(root.clone()) {
+                    break;
+                }
+                root = next;
+                level += 1;
+            }
+
+            let hierarchy = hierarchies
+                .entry(root.clone())
+                .or_insert_with(|| TeamHierarchy {
+                    root_team: root,
+                    levels: HashMap::new(),
+                    parent_child_map: HashMap::new(),
+                    inheritance_rules: vec![],
+                    created_at: Utc::now(),
+                    updated_at: Utc::now(),
+                });
+            hierarchy.levels.insert(team.id.clone(), level);
+            hierarchy
+                .parent_child_map
+                .entry(parent_id.clone())
+                .or_default()
+                .push(team.id.clone());
+        }
+
+        *self.teams.write().await = teams;
+        *self.hierarchies.write().await = hierarchies;
+        Ok(())
+    }
+
+    fn persist(&self, team: &EnterpriseTeam) -> Result<()> {
+        match &self.store {
+            Some(store) => store.save_team(team),
+            None => Ok(()),
+        }
+    }
+
     /// Create a new enterprise team
     pub async fn create_team(&self, team: EnterpriseTeam) -> Result<()> {
         let mut teams = self.teams.write().await;
@@ -306,7 +375,9 @@ impl TeamManager {
             }
         }
 
+        self.persist(&team)?;
         teams.insert(team.id.clone(), team.clone());
+        drop(teams); // find_root_team reads the map
 
         // Update hierarchy if this team has a parent
         if let Some(parent_id) = &team.parent_team {
@@ -418,6 +489,7 @@ impl TeamManager {
         };
 
         team.members.push(member);
+        self.persist(team)?;
 
         self.audit_logger
             .log_team_event(
@@ -443,6 +515,7 @@ impl TeamManager {
         if team.members.len() == initial_len {
             return Err(anyhow!("User is not a member of team: {}", team_id));
         }
+        self.persist(team)?;
 
         self.audit_logger
             .log_team_event(
@@ -562,6 +635,7 @@ impl TeamManager {
             member.temporary = true;
             member.expires_at = duration.map(|d| Utc::now() + d);
         }
+        self.persist(team)?;
 
         Ok(())
     }
@@ -572,6 +646,53 @@ impl TeamManager {
         Ok(teams.get(team_id).cloned())
     }
 
+    /// List all teams, ordered by ID
+    pub async fn list_teams(&self) -> Result<Vec<EnterpriseTeam>> {
+        let mut teams: Vec<EnterpriseTeam> = self.teams.read().await.values().cloned().collect();
+        teams.sort_by(|a, b| a.id.cmp(&b.id));
+        Ok(teams)
+    }
+
+    /// Delete a team that has no child teams
+    pub async fn delete_team(&self, team_id: &str) -> Result<()> {
+        let mut teams = self.teams.write().await;
+        if let Some(child) = teams
+            .values()
+            .find(|t| t.parent_team.as_deref() == Some(team_id))
+        {
+            return Err(anyhow!(
+                "Team {} still has child team {}",
+                team_id,
+                child.id
+            ));
+        }
+        if teams.remove(team_id).is_none() {
+            return Err(anyhow!("Team not found: {}", team_id));
+        }
+        if let Some(store) = &self.store {
+            store.delete_team(team_id)?;
+        }
+        drop(teams);
+
+        let mut hierarchies = self.hierarchies.write().await;
+        hierarchies.remove(team_id);
+        for hierarchy in hierarchies.values_mut() {
+            hierarchy.levels.remove(team_id);
+            for children in hierarchy.parent_child_map.values_mut() {
+                children.retain(|child| child != team_id);
+            }
+        }
+        drop(hierarchies);
+
+        self.audit_logger
+            .log_team_event(
+                super::audit::AuditEventType::TeamDeleted,
+                team_id,
+                format!("Team {} deleted", team_id),
+            )
+            .await
+    }
+
     /// List teams for user
     pub async fn list_user_teams(&self, user_id: &str) -> Result<Vec<EnterpriseTeam>> {
         let teams = self.teams.read().await;
@@ -740,6 +861,7 @@ impl TeamManager {
         let mut teams = self.teams.write().await;
         if let Some(team) = teams.get_mut(team_id) {
             team.roles = default_roles;
+            self.persist(team)?;
         }
 
         Ok(())
@@ -767,7 +889,10 @@ impl TeamManager {
                         true // Keep non-temporary members
                     }
                 });
-                expired_memberships += initial_count - team.members.len();
+                if team.members.len() != initial_count {
+                    expired_memberships += initial_count - team.members.len();
+                    self.persist(team)?;
+                }
             }
         }
 
@@ -0,0 +1,1187 @@
+//! Durable consensus job queue
+//!
+//! Decouples consensus runs from the connection that started them:
+//! - Submitting a query returns a job id immediately
+//! - Job status, per-stage output and every streamed event are persisted in SQLite
+//! - Clients reattach and replay the event stream from any offset
+//! - Jobs are cancelled through the shared `CancellationToken`
+//! - Concurrency is bounded per user
+
+use crate::consensus::cancellation::{CancellationReason, CancellationToken};
+use crate::consensus::engine::ConsensusEngine;
+use crate::consensus::streaming::StreamingCallbacks;
+use crate::consensus::types::{ConsensusRequest, Stage, StageResult};
+use crate::core::database::DatabaseManager;
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use futures::Stream;
+use rusqlite::{params, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{broadcast, mpsc, Mutex, RwLock, Semaphore};
+use tracing::{debug, error, info, warn};
+
+/// Lifecycle state of a job
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    Queued,
+    Running,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+impl JobStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JobStatus::Queued => "queued",
+            JobStatus::Running => "running",
+            JobStatus::Completed => "completed",
+            JobStatus::Failed => "failed",
+            JobStatus::Cancelled => "cancelled",
+        }
+    }
+
+    pub fn parse(value: &str) -> Result<Self> {
+        match value {
+            "queued" => Ok(JobStatus::Queued),
+            "running" => Ok(JobStatus::Running),
+            "completed" => Ok(JobStatus::Completed),
+            "failed" => Ok(JobStatus::Failed),
+            "cancelled" => Ok(JobStatus::Cancelled),
+            other => Err(anyhow!("Unknown job status: {}", other)),
+        }
+    }
+
+    /// Whether no further events will be produced
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self,
+            JobStatus::Completed | JobStatus::Failed | JobStatus::Cancelled
+        )
+    }
+}
+
+/// Persisted job row
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobRecord {
+    pub id: String,
+    pub user_id: String,
+    pub conversation_id: String,
+    pub query: String,
+    pub context: Option<String>,
+    pub profile: Option<String>,
+    pub status: JobStatus,
+    pub result: Option<String>,
+    pub error: Option<String>,
+    pub total_tokens: u32,
+    pub total_cost: f64,
+    pub created_at: DateTime<Utc>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+/// Output of a completed stage
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobStageOutput {
+    pub stage: String,
+    pub model: String,
+    pub output: String,
+    pub tokens: u32,
+    pub cost: f64,
+    pub completed_at: DateTime<Utc>,
+}
+
+/// What happened in a job. Progress ticks are not recorded since they can be
+/// derived from chunks.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum JobEventKind {
+    StatusChanged {
+        status: JobStatus,
+    },
+    ProfileLoaded {
+        name: String,
+        models: Vec<String>,
+    },
+    ModeDecision {
+        direct_mode: bool,
+        reason: String,
+    },
+    StageStarted {
+        stage: Stage,
+        model: String,
+    },
+    Chunk {
+        stage: Stage,
+        chunk: String,
+    },
+    StageCompleted {
+        stage: Stage,
+        model: String,
+        prompt_tokens: u32,
+        completion_tokens: u32,
+        cost: f64,
+    },
+    Error {
+        stage: Option<Stage>,
+        message: String,
+    },
+    Completed {
+        result: String,
+        total_tokens: u32,
+        total_cost: f64,
+    },
+}
+
+/// A sequenced job event; `seq` is the replay offset
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobEvent {
+    pub job_id: String,
+    pub seq: u64,
+    pub timestamp: DateTime<Utc>,
+    #[serde(flatten)]
+    pub kind: JobEventKind,
+}
+
+/// Parameters for a new job
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobSubmission {
+    pub query: String,
+    #[serde(default)]
+    pub profile: Option<String>,
+    #[serde(default)]
+    pub context: Option<String>,
+    /// Thread the job continues and records its turn in; a new one is started when absent
+    #[serde(default)]
+    pub conversation_id: Option<String>,
+}
+
+/// SQLite persistence for jobs, their events and stage outputs
+pub struct JobStore {
+    database: Arc<DatabaseManager>,
+}
+
+impl JobStore {
+    /// Open the store, creating its tables if needed
+    pub fn new(database: Arc<DatabaseManager>) -> Result<Self> {
+        let store = Self { database };
+        store.ensure_schema()?;
+        Ok(store)
+    }
+
+    fn ensure_schema(&self) -> Result<()> {
+        let conn = self.database.get_connection()?;
+        conn.execute_batch(
+            r#"
+            CREATE TABLE IF NOT EXISTS consensus_jobs (
+                id TEXT PRIMARY KEY,
+                user_id TEXT NOT NULL,
+                conversation_id TEXT NOT NULL,
+                query TEXT NOT NULL,
+                context TEXT,
+                profile TEXT,
+                status TEXT NOT NULL,
+                result TEXT,
+                error TEXT,
+                total_tokens INTEGER NOT NULL DEFAULT 0,
+                total_cost REAL NOT NULL DEFAULT 0.0,
+                created_at TEXT NOT NULL,
+                started_at TEXT,
+                finished_at TEXT
+            );
+            CREATE INDEX IF NOT EXISTS idx_consensus_jobs_user ON consensus_jobs(user_id, created_at);
+            CREATE INDEX IF NOT EXISTS idx_consensus_jobs_status ON consensus_jobs(status);
+
+            CREATE TABLE IF NOT EXISTS consensus_job_events (
+                job_id TEXT NOT NULL,
+                seq INTEGER NOT NULL,
+                created_at TEXT NOT NULL,
+                payload TEXT NOT NULL,
+                PRIMARY KEY (job_id, seq),
+                FOREIGN KEY (job_id) REFERENCES consensus_jobs(id) ON DELETE CASCADE
+            );
+
+            CREATE TABLE IF NOT EXISTS consensus_job_stages (
+                job_id TEXT NOT NULL,
+                stage TEXT NOT NULL,
+                model TEXT NOT NULL,
+                output TEXT NOT NULL,
+                tokens INTEGER NOT NULL DEFAULT 0,
+                cost REAL NOT NULL DEFAULT 0.0,
+                completed_at TEXT NOT NULL,
+                PRIMARY KEY (job_id, stage),
+                FOREIGN KEY (job_id) REFERENCES consensus_jobs(id) ON DELETE CASCADE
+            );
+            "#,
+        )?;
+        Ok(())
+    }
+
+    pub fn insert_job(&self, job: &JobRecord) -> Result<()> {
+        let conn = self.database.get_connection()?;
+        conn.execute(
+            "INSERT INTO consensus_jobs
+                (id, user_id, conversation_id, query, context, profile, status, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                job.id,
+                job.user_id,
+                job.conversation_id,
+                job.query,
+                job.context,
+                job.profile,
+                job.status.as_str(),
+                job.created_at.to_rfc3339(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn mark_running(&self, job_id: &str) -> Result<()> {
+        let conn = self.database.get_connection()?;
+        conn.execute(
+            "UPDATE consensus_jobs SET status = 'running', started_at = ?2 WHERE id = ?1",
+            params![job_id, Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    pub fn finish_job(
+        &self,
+        job_id: &str,
+        status: JobStatus,
+        result: Option<&str>,
+        error: Option<&str>,
+        total_tokens: u32,
+        total_cost: f64,
+    ) -> Result<()> {
+        let conn = self.database.get_connection()?;
+        conn.execute(
+            "UPDATE consensus_jobs
+             SET status = ?2, result = ?3, error = ?4, total_tokens = ?5, total_cost = ?6, finished_at = ?7
+             WHERE id = ?1",
+            params![
+                job_id,
+                status.as_str(),
+                result,
+                error,
+                total_tokens,
+                total_cost,
+                Utc::now().to_rfc3339(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn get_job(&self, job_id: &str) -> Result<Option<JobRecord>> {
+        let conn = self.database.get_connection()?;
+        conn.query_row(
+            "SELECT id, user_id, conversation_id, query, context, profile, status, result, error,
+                    total_tokens, total_cost, created_at, started_at, finished_at
+             FROM consensus_jobs WHERE id = ?1",
+            params![job_id],
+            Self::job_from_row,
+        )
+        .optional()
+        .map_err(Into::into)
+    }
+
+    /// Most recent jobs first, optionally restricted to one user
+    pub fn list_jobs(&self, user_id: Option<&str>, limit: usize) -> Result<Vec<JobRecord>> {
+        let conn = self.database.get_connection()?;
+        let mut stmt = conn.prepare(
+            "SELECT id, user_id, conversation_id, query, context, profile, status, result, error,
+                    total_tokens, total_cost, created_at, started_at, finished_at
+             FROM consensus_jobs
+             WHERE (?1 IS NULL OR user_id = ?1)
+             ORDER BY created_at DESC
+             LIMIT ?2",
+        )?;
+        let jobs = stmt
+            .query_map(params![user_id, limit as i64], Self::job_from_row)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(jobs)
+    }
+
+    /// Jobs left unfinished by a previous server process
+    pub fn unfinished_jobs(&self) -> Result<Vec<JobRecord>> {
+        let conn = self.database.get_connection()?;
+        let mut stmt = conn.prepare(
+            "SELECT id, user_id, conversation_id, query, context, profile, status, result, error,
+                    total_tokens, total_cost, created_at, started_at, finished_at
+             FROM consensus_jobs
+             WHERE status IN ('queued', 'running')
+             ORDER BY created_at ASC",
+        )?;
+        let jobs = stmt
+            .query_map([], Self::job_from_row)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(jobs)
+    }
+
+    /// Persist a batch of events atomically
+    pub fn append_events(&self, events: &[JobEvent]) -> Result<()> {
+        let mut conn = self.database.get_connection()?;
+        let tx = conn.transaction()?;
+        {
+            let mut stmt = tx.prepare(
+                "INSERT INTO consensus_job_events (job_id, seq, created_at, payload)
+                 VALUES (?1, ?2, ?3, ?4)",
+            )?;
+            for event in events {
+                stmt.execute(params![
+                    event.job_id,
+                    event.seq as i64,
+                    event.timestamp.to_rfc3339(),
+                    serde_json::to_string(&event.kind)?,
+                ])?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Events with `seq >= offset`, in order
+    pub fn events_since(&self, job_id: &str, offset: u64) -> Result<Vec<JobEvent>> {
+        let conn = self.database.get_connection()?;
+        let mut stmt = conn.prepare(
+            "SELECT seq, created_at, payload FROM consensus_job_events
+             WHERE job_id = ?1 AND seq >= ?2
+             ORDER BY seq ASC",
+        )?;
+        let rows = stmt
+            .query_map(params![job_id, offset as i64], |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                ))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        rows.into_iter()
+            .map(|(seq, created_at, payload)| {
+                Ok(JobEvent {
+                    job_id: job_id.to_string(),
+                    seq: seq as u64,
+                    timestamp: parse_timestamp(&created_at),
+                    kind: serde_json::from_str(&payload)?,
+                })
+            })
+            .collect()
+    }
+
+    /// Next free sequence number for a job
+    pub fn next_seq(&self, job_id: &str) -> Result<u64> {
+        let conn = self.database.get_connection()?;
+        let next: i64 = conn.query_row(
+            "SELECT COALESCE(MAX(seq) + 1, 0) FROM consensus_job_events WHERE job_id = ?1",
+            params![job_id],
+            |row| row.get(0),
+        )?;
+        Ok(next as u64)
+    }
+
+    pub fn record_stage_output(&self, job_id: &str, output: &JobStageOutput) -> Result<()> {
+        let conn = self.database.get_connection()?;
+        conn.execute(
+            "INSERT OR REPLACE INTO consensus_job_stages
+                (job_id, stage, model, output, tokens, cost, completed_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                job_id,
+                output.stage,
+                output.model,
+                output.output,
+                output.tokens,
+                output.cost,
+                output.completed_at.to_rfc3339(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn stage_outputs(&self, job_id: &str) -> Result<Vec<JobStageOutput>> {
+        let conn = self.database.get_connection()?;
+        let mut stmt = conn.prepare(
+            "SELECT stage, model, output, tokens, cost, completed_at
+             FROM consensus_job_stages WHERE job_id = ?1
+             ORDER BY completed_at ASC",
+        )?;
+        let outputs = stmt
+            .query_map(params![job_id], |row| {
+                Ok(JobStageOutput {
+                    stage: row.get(0)?,
+                    model: row.get(1)?,
+                    output: row.get(2)?,
+                    tokens: row.get(3)?,
+                    cost: row.get(4)?,
+                    completed_at: parse_timestamp(&row.get::<_, String>(5)?),
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(outputs)
+    }
+
+    fn job_from_row(row: &Row<'_>) -> rusqlite::Result<JobRecord> {
+        let status: String = row.get(6)?;
+        Ok(JobRecord {
+            id: row.get(0)?,
+            user_id: row.get(1)?,
+            conversation_id: row.get(2)?,
+            query: row.get(3)?,
+            context: row.get(4)?,
+            profile: row.get(5)?,
+            status: JobStatus::parse(&status).unwrap_or(JobStatus::Failed),
+            result: row.get(7)?,
+            error: row.get(8)?,
+            total_tokens: row.get(9)?,
+            total_cost: row.get(10)?,
+            created_at: parse_timestamp(&row.get::<_, String>(11)?),
+            started_at: row
+                .get::<_, Option<String>>(12)?
+                .map(|s| parse_timestamp(&s)),
+            finished_at: row
+                .get::<_, Option<String>>(13)?
+                .map(|s| parse_timestamp(&s)),
+        })
+    }
+}
+
+fn parse_timestamp(value: &str) -> DateTime<Utc> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|dt| dt.with_timezone(&Utc))
+        .unwrap_or_else(|_| Utc::now())
+}
+
+/// Job subsystem configuration
+#[derive(Debug, Clone)]
+pub struct JobConfig {
+    /// Jobs a single user may run at once; further jobs stay queued
+    pub max_concurrent_per_user: usize,
+    /// Capacity of the live event broadcast per job
+    pub event_buffer: usize,
+    /// How long a job waits for the consensus engine to finish initializing
+    pub engine_wait: Duration,
+}
+
+impl Default for JobConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrent_per_user: 2,
+            event_buffer: 1024,
+            engine_wait: Duration::from_secs(120),
+        }
+    }
+}
+
+/// Live handles for a job owned by this process
+struct ActiveJob {
+    cancellation: CancellationToken,
+    events: broadcast::Sender<JobEvent>,
+}
+
+/// Submits, runs, cancels and replays consensus jobs
+#[derive(Clone)]
+pub struct JobManager {
+    store: Arc<JobStore>,
+    engine: Arc<RwLock<Option<ConsensusEngine>>>,
+    config: JobConfig,
+    active: Arc<Mutex<HashMap<String, ActiveJob>>>,
+    user_limits: Arc<Mutex<HashMap<String, Arc<Semaphore>>>>,
+}
+
+impl JobManager {
+    pub fn new(
+        store: Arc<JobStore>,
+        engine: Arc<RwLock<Option<ConsensusEngine>>>,
+        config: JobConfig,
+    ) -> Self {
+        Self {
+            store,
+            engine,
+            config,
+            active: Arc::new(Mutex::new(HashMap::new())),
+            user_limits: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    pub fn store(&self) -> &Arc<JobStore> {
+        &self.store
+    }
+
+    /// Requeue jobs left queued by a previous process and fail the ones that
+    /// were mid-run (their partial output stays available for replay).
+    pub async fn recover(&self) -> Result<usize> {
+        let mut requeued = 0;
+        for job in self.store.unfinished_jobs()? {
+            match job.status {
+                JobStatus::Queued => {
+                    self.start(job).await?;
+                    requeued += 1;
+                }
+                _ => {
+                    let message = "Server restarted while the job was running";
+                    let seq = self.store.next_seq(&job.id)?;
+                    self.store.append_events(&[
+                        JobEvent {
+                            job_id: job.id.clone(),
+                            seq,
+                            timestamp: Utc::now(),
+                            kind: JobEventKind::Error {
+                                stage: None,
+                                message: message.to_string(),
+                            },
+                        },
+                        JobEvent {
+                            job_id: job.id.clone(),
+                            seq: seq + 1,
+                            timestamp: Utc::now(),
+                            kind: JobEventKind::StatusChanged {
+                                status: JobStatus::Failed,
+                            },
+                        },
+                    ])?;
+                    self.store.finish_job(
+                        &job.id,
+                        JobStatus::Failed,
+                        None,
+                        Some(message),
+                        job.total_tokens,
+                        job.total_cost,
+                    )?;
+                }
+            }
+        }
+        if requeued > 0 {
+            info!("Requeued {} consensus jobs from previous run", requeued);
+        }
+        Ok(requeued)
+    }
+
+    /// Persist a new job and schedule it
+    pub async fn submit(&self, user_id: &str, submission: JobSubmission) -> Result<JobRecord> {
+        if submission.query.trim().is_empty() {
+            return Err(anyhow!("Query must not be empty"));
+        }
+
+        let job = JobRecord {
+            id: uuid::Uuid::new_v4().to_string(),
+            user_id: user_id.to_string(),
+            conversation_id: submission
+                .conversation_id
+                .unwrap_or_else(crate::core::database::generate_id),
+            query: submission.query,
+            context: submission.context,
+            profile: submission.profile,
+            status: JobStatus::Queued,
+            result: None,
+            error: None,
+            total_tokens: 0,
+            total_cost: 0.0,
+            created_at: Utc::now(),
+            started_at: None,
+            finished_at: None,
+        };
+        self.store.insert_job(&job)?;
+        self.start(job.clone()).await?;
+        Ok(job)
+    }
+
+    /// Request cancellation of a queued or running job
+    pub async fn cancel(&self, job_id: &str) -> Result<bool> {
+        let active = self.active.lock().await;
+        match active.get(job_id) {
+            Some(job) => {
+                job.cancellation.cancel(CancellationReason::UserRequested);
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// Replay events from `offset` and follow the live stream until the job ends
+    pub async fn attach(
+        &self,
+        job_id: &str,
+        offset: u64,
+    ) -> Result<impl Stream<Item = JobEvent> + Send + 'static> {
+        // Subscribe before reading history so no event falls between the two
+        let live = {
+            let active = self.active.lock().await;
+            active.get(job_id).map(|job| job.events.subscribe())
+        };
+
+        if self.store.get_job(job_id)?.is_none() {
+            return Err(anyhow!("Job {} not found", job_id));
+        }
+
+        let replay = self.store.events_since(job_id, offset)?;
+        let next = replay.last().map(|e| e.seq + 1).unwrap_or(offset);
+        let store = self.store.clone();
+        let job_id = job_id.to_string();
+
+        let state = AttachState {
+            replay: replay.into_iter(),
+            live,
+            next,
+            store,
+            job_id,
+        };
+
+        Ok(futures::stream::unfold(state, |mut state| async move {
+            if let Some(event) = state.replay.next() {
+                return Some((event, state));
+            }
+            loop {
+                let live = state.live.as_mut()?;
+                match live.recv().await {
+                    Ok(event) if event.seq < state.next => continue,
+                    Ok(event) => {
+                        state.next = event.seq + 1;
+                        return Some((event, state));
+                    }
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        // Fell behind the live buffer; catch up from the database
+                        debug!("Job stream lagged by {} events, replaying", skipped);
+                        let missed = state
+                            .store
+                            .events_since(&state.job_id, state.next)
+                            .unwrap_or_default();
+                        if let Some(last) = missed.last() {
+                            state.next = last.seq + 1;
+                        }
+                        state.replay = missed.into_iter();
+                        if let Some(event) = state.replay.next() {
+                            return Some((event, state));
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Closed) => {
+                        // Job finished; flush anything persisted after our last event
+                        let rest = state
+                            .store
+                            .events_since(&state.job_id, state.next)
+                            .unwrap_or_default();
+                        state.live = None;
+                        if let Some(last) = rest.last() {
+                            state.next = last.seq + 1;
+                        }
+                        state.replay = rest.into_iter();
+                        let event = state.replay.next()?;
+                        return Some((event, state));
+                    }
+                }
+            }
+        }))
+    }
+
+    async fn user_limit(&self, user_id: &str) -> Arc<Semaphore> {
+        let mut limits = self.user_limits.lock().await;
+        limits
+            .entry(user_id.to_string())
+            .or_insert_with(|| Arc::new(Semaphore::new(self.config.max_concurrent_per_user.max(1))))
+            .clone()
+    }
+
+    async fn start(&self, job: JobRecord) -> Result<()> {
+        let cancellation = CancellationToken::new();
+        let (events_tx, _) = broadcast::channel(self.config.event_buffer);
+        let next_seq = self.store.next_seq(&job.id)?;
+
+        self.active.lock().await.insert(
+            job.id.clone(),
+            ActiveJob {
+                cancellation: cancellation.clone(),
+                events: events_tx.clone(),
+            },
+        );
+
+        let (emit_tx, emit_rx) = mpsc::unbounded_channel();
+        let writer = tokio::spawn(write_events(
+            self.store.clone(),
+            job.id.clone(),
+            next_seq,
+            emit_rx,
+            events_tx,
+        ));
+
+        let manager = self.clone();
+        tokio::spawn(async move {
+            let job_id = job.id.clone();
+            let emitter = JobEmitter { tx: emit_tx };
+            let outcome = manager.run(&job, &emitter, cancellation).await;
+            drop(emitter);
+
+            // All events must be durable before the final status is visible
+            if let Err(e) = writer.await {
+                error!("Job {} event writer failed: {}", job_id, e);
+            }
+
+            let (status, result, error, tokens, cost) = outcome;
+            if let Err(e) = manager.store.finish_job(
+                &job_id,
+                status,
+                result.as_deref(),
+                error.as_deref(),
+                tokens,
+                cost,
+            ) {
+                error!("Failed to persist final state of job {}: {}", job_id, e);
+            }
+            manager.active.lock().await.remove(&job_id);
+            info!("Job {} finished with status {}", job_id, status.as_str());
+        });
+
+        Ok(())
+    }
+
+    async fn run(
+        &self,
+        job: &JobRecord,
+        emitter: &JobEmitter,
+        cancellation: CancellationToken,
+    ) -> (JobStatus, Option<String>, Option<String>, u32, f64) {
+        let mut cancelled = cancellation.subscribe();
+        let limit = self.user_limit(&job.user_id).await;
+
+        let permit = tokio::select! {
+            permit = limit.acquire_owned() => permit.ok(),
+            _ = cancelled.recv() => None,
+        };
+        if permit.is_none() || cancellation.is_cancelled() {
+            return self.cancelled(emitter);
+        }
+
+        let engine = match self.wait_for_engine().await {
+            Some(engine) => engine,
+            None => {
+                let message = "Consensus engine not initialized".to_string();
+                emitter.emit(JobEventKind::Error {
+                    stage: None,
+                    message: message.clone(),
+                });
+                emitter.emit(JobEventKind::StatusChanged {
+                    status: JobStatus::Failed,
+                });
+                return (JobStatus::Failed, None, Some(message), 0, 0.0);
+            }
+        };
+
+        if let Err(e) = self.store.mark_running(&job.id) {
+            warn!("Failed to mark job {} running: {}", job.id, e);
+        }
+        emitter.emit(JobEventKind::StatusChanged {
+            status: JobStatus::Running,
+        });
+
+        let request = ConsensusRequest {
+            query: job.query.clone(),
+            context: job.context.clone(),
+            temporal_context: None,
+            profile_override: job.profile.clone(),
+            max_tokens: None,
+            user_id: Some(job.user_id.clone()),
+            // The engine replays the thread and records this turn in it
+            conversation_id: Some(job.conversation_id.clone()),
+        };
+        let callbacks = Arc::new(JobCallbacks {
+            emitter: emitter.clone(),
+            store: self.store.clone(),
+            job_id: job.id.clone(),
+        });
+
+        // Dropping the pipeline future on cancellation stops in-flight model calls
+        let outcome = tokio::select! {
+            result = engine.process_request(&request, callbacks, cancellation.clone()) => Some(result),
+            _ = cancelled.recv() => None,
+        };
+
+        match outcome {
+            None => self.cancelled(emitter),
+            Some(_) if cancellation.is_cancelled() => self.cancelled(emitter),
+            Some(Ok(result)) => {
+                let total_tokens: u32 = result
+                    .stages
+                    .iter()
+                    .filter_map(|stage| stage.usage.as_ref())
+                    .map(|usage| usage.total_tokens)
+                    .sum();
+                let answer = result.result.clone().unwrap_or_default();
+                emitter.emit(JobEventKind::Completed {
+                    result: answer.clone(),
+                    total_tokens,
+                    total_cost: result.total_cost,
+                });
+                emitter.emit(JobEventKind::StatusChanged {
+                    status: JobStatus::Completed,
+                });
+                (
+                    JobStatus::Completed,
+                    Some(answer),
+                    None,
+                    total_tokens,
+                    result.total_cost,
+                )
+            }
+            Some(Err(e)) => {
+                let message = format!("Consensus failed: {}", e);
+                emitter.emit(JobEventKind::Error {
+                    stage: None,
+                    message: message.clone(),
+                });
+                emitter.emit(JobEventKind::StatusChanged {
+                    status: JobStatus::Failed,
+                });
+                (JobStatus::Failed, None, Some(message), 0, 0.0)
+            }
+        }
+    }
+
+    fn cancelled(
+        &self,
+        emitter: &JobEmitter,
+    ) -> (JobStatus, Option<String>, Option<String>, u32, f64) {
+        emitter.emit(JobEventKind::StatusChanged {
+            status: JobStatus::Cancelled,
+        });
+        (
+            JobStatus::Cancelled,
+            None,
+            Some("Cancelled by user".to_string()),
+            0,
+            0.0,
+        )
+    }
+
+    async fn wait_for_engine(&self) -> Option<ConsensusEngine> {
+        let deadline = tokio::time::Instant::now() + self.config.engine_wait;
+        loop {
+            if let Some(engine) = self.engine.read().await.clone() {
+                return Some(engine);
+            }
+            if tokio::time::Instant::now() >= deadline {
+                return None;
+            }
+            tokio::time::sleep(Duration::from_millis(500)).await;
+        }
+    }
+}
+
+struct AttachState {
+    replay: std::vec::IntoIter<JobEvent>,
+    live: Option<broadcast::Receiver<JobEvent>>,
+    next: u64,
+    store: Arc<JobStore>,
+    job_id: String,
+}
+
+/// Sends event kinds to the job's writer task
+#[derive(Clone)]
+struct JobEmitter {
+    tx: mpsc::UnboundedSender<JobEventKind>,
+}
+
+impl JobEmitter {
+    fn emit(&self, kind: JobEventKind) {
+        let _ = self.tx.send(kind);
+    }
+}
+
+/// Assigns sequence numbers, persists events in batches, then broadcasts them
+async fn write_events(
+    store: Arc<JobStore>,
+    job_id: String,
+    mut next_seq: u64,
+    mut rx: mpsc::UnboundedReceiver<JobEventKind>,
+    live: broadcast::Sender<JobEvent>,
+) {
+    while let Some(first) = rx.recv().await {
+        let mut kinds = vec![first];
+        while let Ok(kind) = rx.try_recv() {
+            kinds.push(kind);
+        }
+
+        let events: Vec<JobEvent> = kinds
+            .into_iter()
+            .map(|kind| {
+                let event = JobEvent {
+                    job_id: job_id.clone(),
+                    seq: next_seq,
+                    timestamp: Utc::now(),
+                    kind,
+                };
+                next_seq += 1;
+                event
+            })
+            .collect();
+
+        if let Err(e) = store.append_events(&events) {
+            error!("Failed to persist events for job {}: {}", job_id, e);
+        }
+        for event in events {
+            let _ = live.send(event);
+        }
+    }
+}
+
+/// Streaming callbacks that record pipeline activity as job events
+struct JobCallbacks {
+    emitter: JobEmitter,
+    store: Arc<JobStore>,
+    job_id: String,
+}
+
+impl StreamingCallbacks for JobCallbacks {
+    fn on_profile_loaded(&self, profile_name: &str, models: &[String]) -> Result<()> {
+        self.emitter.emit(JobEventKind::ProfileLoaded {
+            name: profile_name.to_string(),
+            models: models.to_vec(),
+        });
+        Ok(())
+    }
+
+    fn on_mode_decision(&self, direct_mode: bool, reason: &str) -> Result<()> {
+        self.emitter.emit(JobEventKind::ModeDecision {
+            direct_mode,
+            reason: reason.to_string(),
+        });
+        Ok(())
+    }
+
+    fn on_stage_start(&self, stage: Stage, model: &str) -> Result<()> {
+        self.emitter.emit(JobEventKind::StageStarted {
+            stage,
+            model: model.to_string(),
+        });
+        Ok(())
+    }
+
+    fn on_stage_chunk(&self, stage: Stage, chunk: &str, _total_content: &str) -> Result<()> {
+        if !chunk.is_empty() {
+            self.emitter.emit(JobEventKind::Chunk {
+                stage,
+                chunk: chunk.to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    fn on_stage_complete(&self, stage: Stage, result: &StageResult) -> Result<()> {
+        let (prompt_tokens, completion_tokens) = result
+            .usage
+            .as_ref()
+            .map(|u| (u.prompt_tokens, u.completion_tokens))
+            .unwrap_or((0, 0));
+        let cost = result.analytics.as_ref().map(|a| a.cost).unwrap_or(0.0);
+
+        // Full stage output gets its own table so clients can fetch it
+        // without replaying every chunk
+        let output = JobStageOutput {
+            stage: stage.as_str().to_string(),
+            model: result.model.clone(),
+            output: result.answer.clone(),
+            tokens: prompt_tokens + completion_tokens,
+            cost,
+            completed_at: Utc::now(),
+        };
+        if let Err(e) = self.store.record_stage_output(&self.job_id, &output) {
+            warn!(
+                "Failed to store {} output for job {}: {}",
+                stage.as_str(),
+                self.job_id,
+                e
+            );
+        }
+
+        self.emitter.emit(JobEventKind::StageCompleted {
+            stage,
+            model: result.model.clone(),
+            prompt_tokens,
+            completion_tokens,
+            cost,
+        });
+        Ok(())
+    }
+
+    fn on_error(&self, stage: Stage, error: &anyhow::Error) -> Result<()> {
+        self.emitter.emit(JobEventKind::Error {
+            stage: Some(stage),
+            message: error.to_string(),
+        });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::database::DatabaseConfig;
+    use tempfile::TempDir;
+
+    async fn test_store() -> Result<(JobStore, TempDir)> {
+        let temp_dir = TempDir::new()?;
+        let config = DatabaseConfig {
+            path: temp_dir.path().join("jobs.db"),
+            ..DatabaseConfig::default()
+        };
+        let db = Arc::new(DatabaseManager::new(config).await?);
+        Ok((JobStore::new(db)?, temp_dir))
+    }
+
+    fn job(id: &str, user_id: &str) -> JobRecord {
+        JobRecord {
+            id: id.to_string(),
+            user_id: user_id.to_string(),
+            conversation_id: format!("conv-{}", id),
+            query: "What is Rust?".to_string(),
+            context: None,
+            profile: None,
+            status: JobStatus::Queued,
+            result: None,
+            error: None,
+            total_tokens: 0,
+            total_cost: 0.0,
+            created_at: Utc::now(),
+            started_at: None,
+            finished_at: None,
+        }
+    }
+
+    fn event(job_id: &str, seq: u64, kind: JobEventKind) -> JobEvent {
+        JobEvent {
+            job_id: job_id.to_string(),
+            seq,
+            timestamp: Utc::now(),
+            kind,
+        }
+    }
+
+    #[test]
+    fn test_status_round_trip() {
+        for status in [
+            JobStatus::Queued,
+            JobStatus::Running,
+            JobStatus::Completed,
+            JobStatus::Failed,
+            JobStatus::Cancelled,
+        ] {
+            assert_eq!(JobStatus::parse(status.as_str()).unwrap(), status);
+        }
+        assert!(JobStatus::parse("paused").is_err());
+        assert!(!JobStatus::Running.is_terminal());
+        assert!(JobStatus::Cancelled.is_terminal());
+    }
+
+    #[tokio::test]
+    async fn test_job_lifecycle() -> Result<()> {
+        let (store, _temp_dir) = test_store().await?;
+        store.insert_job(&job("job-1", "alice"))?;
+        store.insert_job(&job("job-2", "bob"))?;
+
+        assert_eq!(store.unfinished_jobs()?.len(), 2);
+        assert_eq!(store.list_jobs(Some("alice"), 10)?.len(), 1);
+        assert_eq!(store.list_jobs(None, 10)?.len(), 2);
+
+        store.mark_running("job-1")?;
+        let running = store.get_job("job-1")?.unwrap();
+        assert_eq!(running.status, JobStatus::Running);
+        assert!(running.started_at.is_some());
+
+        store.finish_job(
+            "job-1",
+            JobStatus::Completed,
+            Some("answer"),
+            None,
+            42,
+            0.01,
+        )?;
+        let done = store.get_job("job-1")?.unwrap();
+        assert_eq!(done.status, JobStatus::Completed);
+        assert_eq!(done.result.as_deref(), Some("answer"));
+        assert_eq!(done.total_tokens, 42);
+        assert_eq!(store.unfinished_jobs()?.len(), 1);
+
+        assert!(store.get_job("missing")?.is_none());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_event_replay_from_offset() -> Result<()> {
+        let (store, _temp_dir) = test_store().await?;
+        store.insert_job(&job("job-1", "alice"))?;
+        assert_eq!(store.next_seq("job-1")?, 0);
+
+        store.append_events(&[
+            event(
+                "job-1",
+                0,
+                JobEventKind::StatusChanged {
+                    status: JobStatus::Running,
+                },
+            ),
+            event(
+                "job-1",
+                1,
+                JobEventKind::Chunk {
+                    stage: Stage::Generator,
+                    chunk: "Hello".to_string(),
+                },
+            ),
+            event(
+                "job-1",
+                2,
+                JobEventKind::Chunk {
+                    stage: Stage::Generator,
+                    chunk: " world".to_string(),
+                },
+            ),
+        ])?;
+        assert_eq!(store.next_seq("job-1")?, 3);
+
+        let replay = store.events_since("job-1", 1)?;
+        assert_eq!(replay.len(), 2);
+        assert_eq!(replay[0].seq, 1);
+        match &replay[1].kind {
+            JobEventKind::Chunk { stage, chunk } => {
+                assert_eq!(*stage, Stage::Generator);
+                assert_eq!(chunk, " world");
+            }
+            other => panic!("unexpected event {:?}", other),
+        }
+        assert!(store.events_since("job-1", 3)?.is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_stage_outputs() -> Result<()> {
+        let (store, _temp_dir) = test_store().await?;
+        store.insert_job(&job("job-1", "alice"))?;
+
+        let output = JobStageOutput {
+            stage: Stage::Generator.as_str().to_string(),
+            model: "openai/gpt-4o".to_string(),
+            output: "draft".to_string(),
+            tokens: 10,
+            cost: 0.001,
+            completed_at: Utc::now(),
+        };
+        store.record_stage_output("job-1", &output)?;
+        // Re-recording a stage replaces its output
+        store.record_stage_output(
+            "job-1",
+            &JobStageOutput {
+                output: "revised draft".to_string(),
+                ..output
+            },
+        )?;
+
+        let outputs = store.stage_outputs("job-1")?;
+        assert_eq!(outputs.len(), 1);
+        assert_eq!(outputs[0].output, "revised draft");
+        Ok(())
+    }
+}
@@ -0,0 +1,30 @@
+//! Prometheus scrape endpoint
+//!
+//! Serves the process-wide consensus telemetry at `/metrics`, either as a
+//! route on the backend server or as a standalone listener for `hive serve`.
+
+use crate::consensus::telemetry;
+use anyhow::{Context, Result};
+use axum::{http::header, response::IntoResponse, routing::get, Router};
+use std::net::SocketAddr;
+
+/// Content type of the Prometheus text exposition format
+pub const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// `GET /metrics`
+pub async fn metrics_handler() -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, CONTENT_TYPE)],
+        telemetry::global().render_prometheus().await,
+    )
+}
+
+/// Serve `/metrics` on `addr` until the process exits
+pub async fn serve(addr: SocketAddr) -> Result<()> {
+    let app = Router::new().route("/metrics", get(metrics_handler));
+    axum::Server::try_bind(&addr)
+        .with_context(|| format!("Failed to bind the metrics listener to {}", addr))?
+        .serve(app.into_make_service())
+        .await
+        .context("Metrics listener stopped")
+}
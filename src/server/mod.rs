@@ -0,0 +1,21 @@
+//! Shared building blocks for the Hive backend server
+//!
+//! The `hive-backend-server-enhanced` binary owns routing and process setup;
+//! this module holds the protocol types and adapters it exposes so they can be
+//! reused and exercised without spinning up the server:
+//! - OpenAI-compatible `/v1/models` and `/v1/chat/completions` surface
+//! - Durable consensus job queue with resumable event streams
+//! - Prometheus `/metrics` endpoint
+
+pub mod jobs;
+pub mod metrics;
+pub mod openai;
+
+pub use jobs::{
+    JobConfig, JobEvent, JobEventKind, JobManager, JobRecord, JobStageOutput, JobStatus, JobStore,
+    JobSubmission,
+};
+pub use openai::{
+    ApiKeyAuth, ChatCompletionChunk, ChatCompletionRequest, ChatCompletionResponse, ChatMessage,
+    ChatStreamCallbacks, ChatStreamEvent, ModelList, OpenAiError,
+};
@@ -0,0 +1,801 @@
+//! OpenAI-compatible chat completions surface
+//!
+//! Exposes every consensus profile as a virtual model (`hive/<profile-name>`)
+//! so editor plugins and scripts that speak the OpenAI API can drive the
+//! consensus pipeline unchanged. This module only translates between the two
+//! shapes; routing lives in the backend server binary.
+
+use crate::consensus::streaming::StreamingCallbacks;
+use crate::consensus::types::{ConsensusProfile, ConsensusRequest, ConsensusResult, Stage};
+use crate::security::auth::{ApiKey, ApiKeyManager};
+use crate::security::SecurityConfig;
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tokio::sync::mpsc;
+
+/// Prefix used for virtual model ids derived from consensus profiles
+pub const MODEL_PREFIX: &str = "hive/";
+
+/// Model id that always resolves to the currently active profile
+pub const ACTIVE_PROFILE_MODEL: &str = "hive";
+
+/// Owner reported for every virtual model
+const MODEL_OWNER: &str = "hivetechs";
+
+/// Permission granted to keys loaded from server configuration
+pub const CHAT_PERMISSION: &str = "consensus:chat";
+
+/// Build the virtual model id for a consensus profile
+pub fn model_id_for_profile(profile_name: &str) -> String {
+    format!("{}{}", MODEL_PREFIX, profile_name)
+}
+
+/// Resolve a requested model id to a profile name.
+///
+/// Returns `None` when the active profile should be used. Bare profile names
+/// are accepted as well as `hive/<profile>` ids.
+pub fn profile_for_model(model: &str) -> Option<String> {
+    let model = model.trim();
+    if model.is_empty() || model == ACTIVE_PROFILE_MODEL {
+        return None;
+    }
+    let name = model.strip_prefix(MODEL_PREFIX).unwrap_or(model);
+    if name.is_empty() {
+        None
+    } else {
+        Some(name.to_string())
+    }
+}
+
+/// A chat message as sent by OpenAI clients
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatMessage {
+    pub role: String,
+    /// Either a plain string or an array of content parts
+    #[serde(default)]
+    pub content: serde_json::Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+impl ChatMessage {
+    /// Flatten the message content into plain text, ignoring non-text parts
+    pub fn text(&self) -> String {
+        match &self.content {
+            serde_json::Value::String(s) => s.clone(),
+            serde_json::Value::Array(parts) => parts
+                .iter()
+                .filter_map(|part| {
+                    if part.get("type").and_then(|t| t.as_str()) == Some("text") {
+                        part.get("text").and_then(|t| t.as_str())
+                    } else {
+                        None
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join("\n"),
+            _ => String::new(),
+        }
+    }
+}
+
+/// Options controlling what a streamed response includes
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StreamOptions {
+    #[serde(default)]
+    pub include_usage: bool,
+}
+
+/// `POST /v1/chat/completions` request body
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatCompletionRequest {
+    pub model: String,
+    pub messages: Vec<ChatMessage>,
+    #[serde(default)]
+    pub stream: bool,
+    #[serde(default)]
+    pub stream_options: Option<StreamOptions>,
+    #[serde(default)]
+    pub max_tokens: Option<u32>,
+    #[serde(default)]
+    pub max_completion_tokens: Option<u32>,
+    #[serde(default)]
+    pub user: Option<String>,
+    /// Sampling parameters are accepted for compatibility; the profile's
+    /// stage configuration decides how each model is called.
+    #[serde(default)]
+    pub temperature: Option<f32>,
+    #[serde(default)]
+    pub top_p: Option<f32>,
+}
+
+impl ChatCompletionRequest {
+    /// Whether the final streamed chunk should carry usage
+    pub fn include_usage(&self) -> bool {
+        self.stream_options
+            .as_ref()
+            .map(|o| o.include_usage)
+            .unwrap_or(false)
+    }
+
+    /// Map the chat transcript onto a consensus request.
+    ///
+    /// The last user message becomes the query; system messages and earlier
+    /// turns are folded into the context string the pipeline already accepts.
+    pub fn to_consensus_request(
+        &self,
+        user_id: Option<String>,
+    ) -> Result<ConsensusRequest, OpenAiError> {
+        let last_user = self
+            .messages
+            .iter()
+            .rposition(|m| m.role == "user")
+            .ok_or_else(|| {
+                OpenAiError::invalid_request("messages must contain at least one user message")
+            })?;
+
+        let query = self.messages[last_user].text();
+        if query.trim().is_empty() {
+            return Err(OpenAiError::invalid_request(
+                "the last user message has no text content",
+            ));
+        }
+
+        let mut system = Vec::new();
+        let mut history = Vec::new();
+        for message in &self.messages[..last_user] {
+            let text = message.text();
+            if text.trim().is_empty() {
+                continue;
+            }
+            match message.role.as_str() {
+                "system" | "developer" => system.push(text),
+                role => history.push(format!("{}: {}", role, text)),
+            }
+        }
+        // Instructions that follow the last user turn still apply to it
+        for message in &self.messages[last_user + 1..] {
+            if matches!(message.role.as_str(), "system" | "developer") {
+                system.push(message.text());
+            }
+        }
+
+        let mut context = String::new();
+        if !system.is_empty() {
+            context.push_str("System instructions:\n");
+            context.push_str(&system.join("\n"));
+            context.push('\n');
+        }
+        if !history.is_empty() {
+            if !context.is_empty() {
+                context.push('\n');
+            }
+            context.push_str("Previous conversation context:\n");
+            context.push_str(&history.join("\n"));
+            context.push('\n');
+        }
+
+        Ok(ConsensusRequest {
+            query,
+            context: if context.is_empty() {
+                None
+            } else {
+                Some(context)
+            },
+            temporal_context: None,
+            profile_override: profile_for_model(&self.model),
+            max_tokens: self.max_completion_tokens.or(self.max_tokens),
+            user_id: user_id.or_else(|| self.user.clone()),
+            // OpenAI clients resend the whole conversation with every request
+            conversation_id: None,
+        })
+    }
+}
+
+/// Token usage with the consensus cost attached
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct Usage {
+    pub prompt_tokens: u32,
+    pub completion_tokens: u32,
+    pub total_tokens: u32,
+    /// Total cost in USD across all stages
+    pub cost: f64,
+}
+
+impl Usage {
+    /// Sum usage and cost over all stages of a consensus run
+    pub fn from_result(result: &ConsensusResult) -> Self {
+        let (prompt_tokens, completion_tokens) = result
+            .stages
+            .iter()
+            .filter_map(|stage| stage.usage.as_ref())
+            .fold((0u32, 0u32), |(p, c), usage| {
+                (p + usage.prompt_tokens, c + usage.completion_tokens)
+            });
+
+        Self {
+            prompt_tokens,
+            completion_tokens,
+            total_tokens: prompt_tokens + completion_tokens,
+            cost: result.total_cost,
+        }
+    }
+}
+
+/// Assistant message in a non-streaming response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResponseMessage {
+    pub role: String,
+    pub content: String,
+}
+
+/// A single completion choice
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Choice {
+    pub index: u32,
+    pub message: ResponseMessage,
+    pub finish_reason: String,
+}
+
+/// `POST /v1/chat/completions` non-streaming response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatCompletionResponse {
+    pub id: String,
+    pub object: String,
+    pub created: i64,
+    pub model: String,
+    pub choices: Vec<Choice>,
+    pub usage: Usage,
+}
+
+impl ChatCompletionResponse {
+    pub fn from_result(id: &str, model: &str, created: i64, result: &ConsensusResult) -> Self {
+        Self {
+            id: id.to_string(),
+            object: "chat.completion".to_string(),
+            created,
+            model: model.to_string(),
+            choices: vec![Choice {
+                index: 0,
+                message: ResponseMessage {
+                    role: "assistant".to_string(),
+                    content: result.result.clone().unwrap_or_default(),
+                },
+                finish_reason: "stop".to_string(),
+            }],
+            usage: Usage::from_result(result),
+        }
+    }
+}
+
+/// Incremental message content in a streamed chunk
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ChunkDelta {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub role: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+}
+
+/// A choice inside a streamed chunk
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChunkChoice {
+    pub index: u32,
+    pub delta: ChunkDelta,
+    pub finish_reason: Option<String>,
+}
+
+/// `chat.completion.chunk` object sent as an SSE `data:` payload
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatCompletionChunk {
+    pub id: String,
+    pub object: String,
+    pub created: i64,
+    pub model: String,
+    pub choices: Vec<ChunkChoice>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub usage: Option<Usage>,
+}
+
+impl ChatCompletionChunk {
+    fn with_choices(id: &str, model: &str, created: i64, choices: Vec<ChunkChoice>) -> Self {
+        Self {
+            id: id.to_string(),
+            object: "chat.completion.chunk".to_string(),
+            created,
+            model: model.to_string(),
+            choices,
+            usage: None,
+        }
+    }
+
+    /// Opening chunk announcing the assistant role
+    pub fn role(id: &str, model: &str, created: i64) -> Self {
+        Self::with_choices(
+            id,
+            model,
+            created,
+            vec![ChunkChoice {
+                index: 0,
+                delta: ChunkDelta {
+                    role: Some("assistant".to_string()),
+                    content: Some(String::new()),
+                },
+                finish_reason: None,
+            }],
+        )
+    }
+
+    /// Content delta
+    pub fn content(id: &str, model: &str, created: i64, content: &str) -> Self {
+        Self::with_choices(
+            id,
+            model,
+            created,
+            vec![ChunkChoice {
+                index: 0,
+                delta: ChunkDelta {
+                    role: None,
+                    content: Some(content.to_string()),
+                },
+                finish_reason: None,
+            }],
+        )
+    }
+
+    /// Closing chunk carrying the finish reason
+    pub fn finish(id: &str, model: &str, created: i64, finish_reason: &str) -> Self {
+        Self::with_choices(
+            id,
+            model,
+            created,
+            vec![ChunkChoice {
+                index: 0,
+                delta: ChunkDelta::default(),
+                finish_reason: Some(finish_reason.to_string()),
+            }],
+        )
+    }
+
+    /// Trailing usage-only chunk (sent when `stream_options.include_usage` is set)
+    pub fn usage(id: &str, model: &str, created: i64, usage: Usage) -> Self {
+        let mut chunk = Self::with_choices(id, model, created, Vec::new());
+        chunk.usage = Some(usage);
+        chunk
+    }
+}
+
+/// A virtual model entry in `GET /v1/models`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelObject {
+    pub id: String,
+    pub object: String,
+    pub created: i64,
+    pub owned_by: String,
+    /// Underlying models per stage, for clients that want to show them
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub stage_models: Vec<String>,
+}
+
+/// `GET /v1/models` response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelList {
+    pub object: String,
+    pub data: Vec<ModelObject>,
+}
+
+impl ModelList {
+    /// List each profile as a virtual model, plus the `hive` alias for the active one
+    pub fn from_profiles(profiles: &[ConsensusProfile]) -> Self {
+        let mut data = vec![ModelObject {
+            id: ACTIVE_PROFILE_MODEL.to_string(),
+            object: "model".to_string(),
+            created: 0,
+            owned_by: MODEL_OWNER.to_string(),
+            stage_models: profiles
+                .iter()
+                .find(|p| p.is_active)
+                .map(stage_models)
+                .unwrap_or_default(),
+        }];
+
+        data.extend(profiles.iter().map(|profile| ModelObject {
+            id: model_id_for_profile(&profile.profile_name),
+            object: "model".to_string(),
+            created: profile.created_at.timestamp(),
+            owned_by: MODEL_OWNER.to_string(),
+            stage_models: stage_models(profile),
+        }));
+
+        Self {
+            object: "list".to_string(),
+            data,
+        }
+    }
+}
+
+fn stage_models(profile: &ConsensusProfile) -> Vec<String> {
+    vec![
+        profile.generator_model.clone(),
+        profile.refiner_model.clone(),
+        profile.validator_model.clone(),
+        profile.curator_model.clone(),
+    ]
+}
+
+/// Events produced while a chat completion is streaming
+#[derive(Debug, Clone)]
+pub enum ChatStreamEvent {
+    /// Text of the answering stage
+    Content(String),
+    /// Pipeline finished
+    Done(Box<ConsensusResult>),
+    /// Pipeline failed
+    Failed(String),
+}
+
+/// Streaming callbacks that forward only the answer-producing stage.
+///
+/// In consensus mode the Curator produces the answer; when the AI helpers route
+/// a question to direct mode the Generator answers alone. Intermediate stage
+/// output is not part of the OpenAI response.
+pub struct ChatStreamCallbacks {
+    tx: mpsc::UnboundedSender<ChatStreamEvent>,
+    direct_mode: AtomicBool,
+}
+
+impl ChatStreamCallbacks {
+    pub fn new(tx: mpsc::UnboundedSender<ChatStreamEvent>) -> Self {
+        Self {
+            tx,
+            direct_mode: AtomicBool::new(false),
+        }
+    }
+
+    fn answering_stage(&self) -> Stage {
+        if self.direct_mode.load(Ordering::Relaxed) {
+            Stage::Generator
+        } else {
+            Stage::Curator
+        }
+    }
+}
+
+impl StreamingCallbacks for ChatStreamCallbacks {
+    fn on_mode_decision(&self, direct_mode: bool, _reason: &str) -> anyhow::Result<()> {
+        self.direct_mode.store(direct_mode, Ordering::Relaxed);
+        Ok(())
+    }
+
+    fn on_stage_chunk(
+        &self,
+        stage: Stage,
+        chunk: &str,
+        _total_content: &str,
+    ) -> anyhow::Result<()> {
+        if stage == self.answering_stage() && !chunk.is_empty() {
+            let _ = self.tx.send(ChatStreamEvent::Content(chunk.to_string()));
+        }
+        Ok(())
+    }
+}
+
+/// Error in the OpenAI `{"error": {...}}` shape
+#[derive(Debug, Clone)]
+pub struct OpenAiError {
+    pub status: StatusCode,
+    pub message: String,
+    pub error_type: &'static str,
+    pub code: Option<&'static str>,
+}
+
+impl OpenAiError {
+    pub fn invalid_request(message: impl Into<String>) -> Self {
+        Self {
+            status: StatusCode::BAD_REQUEST,
+            message: message.into(),
+            error_type: "invalid_request_error",
+            code: None,
+        }
+    }
+
+    pub fn unauthorized(message: impl Into<String>) -> Self {
+        Self {
+            status: StatusCode::UNAUTHORIZED,
+            message: message.into(),
+            error_type: "invalid_request_error",
+            code: Some("invalid_api_key"),
+        }
+    }
+
+    pub fn model_not_found(model: &str) -> Self {
+        Self {
+            status: StatusCode::NOT_FOUND,
+            message: format!("The model '{}' does not exist", model),
+            error_type: "invalid_request_error",
+            code: Some("model_not_found"),
+        }
+    }
+
+    pub fn unavailable(message: impl Into<String>) -> Self {
+        Self {
+            status: StatusCode::SERVICE_UNAVAILABLE,
+            message: message.into(),
+            error_type: "server_error",
+            code: None,
+        }
+    }
+
+    pub fn internal(message: impl Into<String>) -> Self {
+        Self {
+            status: StatusCode::INTERNAL_SERVER_ERROR,
+            message: message.into(),
+            error_type: "server_error",
+            code: None,
+        }
+    }
+
+    /// JSON body, also used for errors raised mid-stream
+    pub fn body(&self) -> serde_json::Value {
+        serde_json::json!({
+            "error": {
+                "message": self.message,
+                "type": self.error_type,
+                "code": self.code,
+            }
+        })
+    }
+}
+
+impl IntoResponse for OpenAiError {
+    fn into_response(self) -> Response {
+        (self.status, Json(self.body())).into_response()
+    }
+}
+
+/// Bearer-token authentication backed by `security::auth::ApiKeyManager`.
+///
+/// When no keys are configured, authentication is disabled so the server keeps
+/// working for local single-user setups, matching the other backend routes.
+#[derive(Clone)]
+pub struct ApiKeyAuth {
+    manager: Option<Arc<ApiKeyManager>>,
+}
+
+impl ApiKeyAuth {
+    /// Authentication disabled
+    pub fn disabled() -> Self {
+        Self { manager: None }
+    }
+
+    /// Load keys from a comma-separated list of `key` or `user=key` entries
+    ///
+    /// Keys may contain `:`; a trailing run of `=` is base64 padding, not a user.
+    pub async fn from_key_list(list: &str) -> anyhow::Result<Self> {
+        let entries: Vec<&str> = list
+            .split(',')
+            .map(str::trim)
+            .filter(|e| !e.is_empty())
+            .collect();
+        if entries.is_empty() {
+            return Ok(Self::disabled());
+        }
+
+        let manager = ApiKeyManager::new(SecurityConfig::default());
+        for (index, entry) in entries.iter().enumerate() {
+            let (user_id, key) = match entry.split_once('=') {
+                Some((user, key))
+                    if !user.is_empty() && !key.trim_start_matches('=').is_empty() =>
+                {
+                    (user.trim().to_string(), key.trim())
+                }
+                _ => (format!("api-client-{}", index + 1), *entry),
+            };
+            manager
+                .register_api_key(
+                    key,
+                    &user_id,
+                    "openai-compatible",
+                    vec![CHAT_PERMISSION.to_string()],
+                )
+                .await?;
+        }
+
+        Ok(Self {
+            manager: Some(Arc::new(manager)),
+        })
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.manager.is_some()
+    }
+
+    /// Validate the `Authorization: Bearer <key>` header.
+    ///
+    /// Returns `Ok(None)` when authentication is disabled.
+    pub async fn authorize(&self, headers: &HeaderMap) -> Result<Option<ApiKey>, OpenAiError> {
+        let Some(manager) = &self.manager else {
+            return Ok(None);
+        };
+
+        let token = headers
+            .get(header::AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| {
+                v.strip_prefix("Bearer ")
+                    .or_else(|| v.strip_prefix("bearer "))
+            })
+            .map(str::trim)
+            .ok_or_else(|| OpenAiError::unauthorized("Missing bearer API key"))?;
+
+        let key = manager
+            .validate_api_key(token)
+            .await
+            .map_err(|e| OpenAiError::unauthorized(e.to_string()))?;
+
+        if !key.permissions.iter().any(|p| p == CHAT_PERMISSION) {
+            return Err(OpenAiError {
+                status: StatusCode::FORBIDDEN,
+                message: "API key is not allowed to run consensus".to_string(),
+                error_type: "invalid_request_error",
+                code: Some("insufficient_permissions"),
+            });
+        }
+
+        Ok(Some(key))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consensus::types::{StageResult, TokenUsage};
+    use chrono::Utc;
+
+    fn message(role: &str, content: &str) -> ChatMessage {
+        ChatMessage {
+            role: role.to_string(),
+            content: serde_json::Value::String(content.to_string()),
+            name: None,
+        }
+    }
+
+    fn request(model: &str, messages: Vec<ChatMessage>) -> ChatCompletionRequest {
+        ChatCompletionRequest {
+            model: model.to_string(),
+            messages,
+            stream: false,
+            stream_options: None,
+            max_tokens: None,
+            max_completion_tokens: None,
+            user: None,
+            temperature: None,
+            top_p: None,
+        }
+    }
+
+    #[test]
+    fn test_profile_for_model() {
+        assert_eq!(profile_for_model("hive"), None);
+        assert_eq!(
+            profile_for_model("hive/lightning-fast"),
+            Some("lightning-fast".to_string())
+        );
+        assert_eq!(
+            profile_for_model("deep-researcher"),
+            Some("deep-researcher".to_string())
+        );
+    }
+
+    #[test]
+    fn test_messages_map_to_consensus_request() {
+        let req = request(
+            "hive/balanced-performer",
+            vec![
+                message("system", "Answer tersely."),
+                message("user", "What is Rust?"),
+                message("assistant", "A systems language."),
+                message("user", "Who created it?"),
+            ],
+        );
+
+        let consensus = req.to_consensus_request(None).unwrap();
+        assert_eq!(consensus.query, "Who created it?");
+        assert_eq!(
+            consensus.profile_override.as_deref(),
+            Some("balanced-performer")
+        );
+        let context = consensus.context.unwrap();
+        assert!(context.contains("Answer tersely."));
+        assert!(context.contains("user: What is Rust?"));
+        assert!(context.contains("assistant: A systems language."));
+    }
+
+    #[test]
+    fn test_content_parts_are_flattened() {
+        let msg = ChatMessage {
+            role: "user".to_string(),
+            content: serde_json::json!([
+                {"type": "text", "text": "first"},
+                {"type": "image_url", "image_url": {"url": "x"}},
+                {"type": "text", "text": "second"}
+            ]),
+            name: None,
+        };
+        assert_eq!(msg.text(), "first\nsecond");
+    }
+
+    #[test]
+    fn test_request_without_user_message_is_rejected() {
+        let req = request("hive", vec![message("system", "hi")]);
+        let err = req.to_consensus_request(None).unwrap_err();
+        assert_eq!(err.status, StatusCode::BAD_REQUEST);
+    }
+
+    #[test]
+    fn test_usage_sums_stages() {
+        let stage = |prompt, completion| StageResult {
+            stage_id: "s".to_string(),
+            stage_name: "generator".to_string(),
+            question: String::new(),
+            answer: String::new(),
+            model: "m".to_string(),
+            conversation_id: "c".to_string(),
+            timestamp: Utc::now(),
+            usage: Some(TokenUsage {
+                prompt_tokens: prompt,
+                completion_tokens: completion,
+                total_tokens: prompt + completion,
+            }),
+            analytics: None,
+        };
+        let result = ConsensusResult {
+            success: true,
+            result: Some("answer".to_string()),
+            error: None,
+            stages: vec![stage(10, 5), stage(20, 7)],
+            conversation_id: "c".to_string(),
+            total_duration: 1.0,
+            total_cost: 0.25,
+        };
+
+        let response = ChatCompletionResponse::from_result("id", "hive", 0, &result);
+        assert_eq!(response.usage.prompt_tokens, 30);
+        assert_eq!(response.usage.completion_tokens, 12);
+        assert_eq!(response.usage.total_tokens, 42);
+        assert_eq!(response.usage.cost, 0.25);
+        assert_eq!(response.choices[0].message.content, "answer");
+    }
+
+    #[tokio::test]
+    async fn test_api_key_auth() {
+        let auth = ApiKeyAuth::from_key_list("alice=sk:secret-1, c2VjcmV0LTI=")
+            .await
+            .unwrap();
+        assert!(auth.is_enabled());
+
+        let mut headers = HeaderMap::new();
+        assert!(auth.authorize(&headers).await.is_err());
+
+        headers.insert(header::AUTHORIZATION, "Bearer sk:secret-1".parse().unwrap());
+        let key = auth.authorize(&headers).await.unwrap().unwrap();
+        assert_eq!(key.user_id, "alice");
+
+        headers.insert(
+            header::AUTHORIZATION,
+            "Bearer c2VjcmV0LTI=".parse().unwrap(),
+        );
+        let key = auth.authorize(&headers).await.unwrap().unwrap();
+        assert_eq!(key.user_id, "api-client-2");
+
+        headers.insert(header::AUTHORIZATION, "Bearer wrong".parse().unwrap());
+        assert!(auth.authorize(&headers).await.is_err());
+
+        let open = ApiKeyAuth::from_key_list("").await.unwrap();
+        assert!(open.authorize(&HeaderMap::new()).await.unwrap().is_none());
+    }
+}
@@ -4,11 +4,14 @@
 use anyhow::{Context, Result};
 use std::fs::{read_to_string, OpenOptions};
 use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use super::{utils, ShellType};
 use crate::core::config::Config;
 
+/// First line after the shebang of the git hook Hive writes
+const GIT_HOOK_MARKER: &str = "# Hive AI prepare-commit-msg hook";
+
 /// Shell hooks manager for aliases and convenient functions
 pub struct ShellHooks {
     config: Config,
@@ -1030,6 +1033,97 @@ if (not (has-env HIVE_HOOKS_WELCOME_SHOWN)) {
         );
         Ok(())
     }
+
+    /// Install `hive commit` as the prepare-commit-msg hook of a git repository
+    ///
+    /// A foreign hook is only replaced with `force`, after backing it up.
+    pub fn install_git_hook(&self, repo: &Path, force: bool) -> Result<PathBuf> {
+        let hook = self.git_hook_path(repo)?;
+
+        if hook.exists() {
+            if self.is_git_hook_installed(repo)? {
+                tracing::info!("Git hook already installed at {}", hook.display());
+                return Ok(hook);
+            }
+            if !force {
+                anyhow::bail!(
+                    "{} already exists; use --force to replace it (a backup is kept)",
+                    hook.display()
+                );
+            }
+            utils::backup_file(&hook)?;
+        }
+        if let Some(parent) = hook.parent() {
+            utils::create_directory_safe(&parent.to_path_buf())?;
+        }
+
+        std::fs::write(&hook, self.generate_git_hook())
+            .with_context(|| format!("Failed to write git hook: {}", hook.display()))?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&hook, std::fs::Permissions::from_mode(0o755))?;
+        }
+
+        tracing::info!("Installed prepare-commit-msg hook at {}", hook.display());
+        Ok(hook)
+    }
+
+    /// Check if the Hive prepare-commit-msg hook is installed in a repository
+    pub fn is_git_hook_installed(&self, repo: &Path) -> Result<bool> {
+        let hook = self.git_hook_path(repo)?;
+        if !hook.exists() {
+            return Ok(false);
+        }
+        Ok(read_to_string(&hook)?.contains(GIT_HOOK_MARKER))
+    }
+
+    /// Remove the Hive prepare-commit-msg hook; other hooks are left alone
+    pub fn uninstall_git_hook(&self, repo: &Path) -> Result<bool> {
+        if !self.is_git_hook_installed(repo)? {
+            return Ok(false);
+        }
+        let hook = self.git_hook_path(repo)?;
+        std::fs::remove_file(&hook)
+            .with_context(|| format!("Failed to remove git hook: {}", hook.display()))?;
+
+        tracing::info!("Removed prepare-commit-msg hook from {}", hook.display());
+        Ok(true)
+    }
+
+    /// Path of the prepare-commit-msg hook, honouring `core.hooksPath`
+    fn git_hook_path(&self, repo: &Path) -> Result<PathBuf> {
+        let repository = git2::Repository::discover(repo)
+            .with_context(|| format!("No git repository at {}", repo.display()))?;
+
+        let hooks_dir = match repository.config()?.get_path("core.hooksPath") {
+            Ok(path) if path.is_absolute() => path,
+            Ok(path) => repository
+                .workdir()
+                .unwrap_or_else(|| repository.path())
+                .join(path),
+            Err(_) => repository.path().join("hooks"),
+        };
+        Ok(hooks_dir.join("prepare-commit-msg"))
+    }
+
+    /// Generate the prepare-commit-msg hook script
+    fn generate_git_hook(&self) -> String {
+        format!(
+            r#"#!/bin/sh
+{}
+# Added by Hive AI Shell Integration; remove with: hive shell git-hook --uninstall
+
+# Only plain `git commit`: -m, -F, templates, merges, squashes and amends keep their message
+[ -z "$2" ] || exit 0
+command -v hive >/dev/null 2>&1 || exit 0
+
+# A failed proposal must never block the commit
+hive commit --hook "$1" || true
+"#,
+            GIT_HOOK_MARKER
+        )
+    }
 }
 
 #[cfg(all(test, feature = "legacy-tests"))]
@@ -1102,3 +1196,95 @@ mod tests {
         assert!(content.contains("alias ha="));
     }
 }
+
+#[cfg(test)]
+mod git_hook_tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn hooks() -> ShellHooks {
+        ShellHooks::new(Config::default())
+    }
+
+    fn backups(dir: &Path) -> Vec<PathBuf> {
+        std::fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| path.to_string_lossy().ends_with("hive-backup"))
+            .collect()
+    }
+
+    #[test]
+    fn test_install_and_uninstall() -> Result<()> {
+        let dir = TempDir::new()?;
+        let repo = git2::Repository::init(dir.path())?;
+        let hooks = hooks();
+
+        let hook = hooks.install_git_hook(dir.path(), false)?;
+        assert_eq!(hook, repo.path().join("hooks").join("prepare-commit-msg"));
+        assert!(read_to_string(&hook)?.contains("hive commit --hook"));
+        assert!(hooks.is_git_hook_installed(dir.path())?);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(
+                std::fs::metadata(&hook)?.permissions().mode() & 0o111,
+                0o111
+            );
+        }
+
+        // Installing again keeps the hook as it is
+        assert_eq!(hooks.install_git_hook(dir.path(), false)?, hook);
+
+        assert!(hooks.uninstall_git_hook(dir.path())?);
+        assert!(!hook.exists());
+        assert!(!hooks.uninstall_git_hook(dir.path())?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_foreign_hook_needs_force_and_is_backed_up() -> Result<()> {
+        let dir = TempDir::new()?;
+        let repo = git2::Repository::init(dir.path())?;
+        let hooks = hooks();
+        let hooks_dir = repo.path().join("hooks");
+        std::fs::create_dir_all(&hooks_dir)?;
+        let hook = hooks_dir.join("prepare-commit-msg");
+        std::fs::write(&hook, "#!/bin/sh\necho team hook\n")?;
+
+        assert!(hooks.install_git_hook(dir.path(), false).is_err());
+        assert_eq!(read_to_string(&hook)?, "#!/bin/sh\necho team hook\n");
+        assert!(!hooks.uninstall_git_hook(dir.path())?);
+        assert!(hook.exists());
+
+        hooks.install_git_hook(dir.path(), true)?;
+        assert!(hooks.is_git_hook_installed(dir.path())?);
+        let backups = backups(&hooks_dir);
+        assert_eq!(backups.len(), 1);
+        assert_eq!(read_to_string(&backups[0])?, "#!/bin/sh\necho team hook\n");
+        Ok(())
+    }
+
+    #[test]
+    fn test_core_hooks_path() -> Result<()> {
+        let dir = TempDir::new()?;
+        let repo = git2::Repository::init(dir.path())?;
+        repo.config()?.set_str("core.hooksPath", ".githooks")?;
+        let hooks = hooks();
+
+        let hook = hooks.install_git_hook(dir.path(), false)?;
+        assert_eq!(
+            hook,
+            dir.path().join(".githooks").join("prepare-commit-msg")
+        );
+        assert!(!repo
+            .path()
+            .join("hooks")
+            .join("prepare-commit-msg")
+            .exists());
+
+        assert!(hooks.uninstall_git_hook(dir.path())?);
+        assert!(!hook.exists());
+        Ok(())
+    }
+}
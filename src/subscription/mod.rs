@@ -309,6 +309,11 @@ impl SubscriptionDisplay {
                 [],
                 |row| row.get::<_, String>(0)
             ).optional()?;
+            let current_license_key = crate::core::secrets::resolve_configuration(
+                "hive_license_key",
+                current_license_key,
+            )?
+            .filter(|key| !key.is_empty());
 
             if current_license_key.is_none() {
                 return Ok(("No license".to_string(), "free".to_string(), String::new(), 0, false, None, 0));
@@ -0,0 +1,978 @@
+//! Sync client - exchanges local knowledge with the team sync server
+//!
+//! Every curated fact takes part unless fact sharing is turned off; threads
+//! only once shared with `hive sync share`. Pulls run before pushes so most
+//! edits arrive already merged. When both sides changed a record:
+//! - Facts keep the more confident content and the union of topics
+//! - Threads keep their common turns, then both divergent tails
+//! - An edit wins over a concurrent removal
+
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use chrono::Utc;
+use rusqlite::{params, OptionalExtension, Row};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use super::{
+    Causality, PullResponse, PushRequest, PushResponse, RecordKind, ServerStatus, SyncRecord,
+    TeamKey, VersionVector, PAGE_SIZE, SCHEMA,
+};
+use crate::consensus::threads::{Thread, ThreadStore, Turn};
+use crate::core::database::{generate_id, DatabaseManager};
+
+/// Curated facts live in a migration-created table that older databases lack
+const FACTS_SCHEMA: &str = include_str!("../../migrations/013_add_consensus_facts_table.sql");
+
+const NODE_ID_KEY: &str = "node_id";
+
+/// Merge-and-retry rounds before a push leaves conflicts for the next sync
+const MAX_PUSH_ROUNDS: usize = 3;
+
+/// The fields of a curated fact shared with the team
+///
+/// Access counters, related fact ids and the source conversation stay local.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SharedFact {
+    pub semantic_fingerprint: String,
+    pub content: String,
+    pub curator_confidence: f64,
+    pub source_question: String,
+    pub consensus_stages: serde_json::Value,
+    pub created_at: String,
+    pub topics: Vec<String>,
+    pub entities: serde_json::Value,
+    pub metadata: serde_json::Value,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SharedTurn {
+    pub question: String,
+    pub answer: String,
+    pub model: Option<String>,
+}
+
+/// A thread as shared with the team; costs and the owner stay local
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SharedThread {
+    pub id: String,
+    pub title: Option<String>,
+    pub parent_id: Option<String>,
+    pub summary: Option<String>,
+    pub summarized_turns: usize,
+    pub turns: Vec<SharedTurn>,
+}
+
+impl From<&Thread> for SharedThread {
+    fn from(thread: &Thread) -> Self {
+        Self {
+            id: thread.id.clone(),
+            title: thread.title.clone(),
+            parent_id: thread.parent_id.clone(),
+            summary: thread.summary.clone(),
+            summarized_turns: thread.summarized_turns,
+            turns: thread
+                .turns
+                .iter()
+                .map(|turn| SharedTurn {
+                    question: turn.question.clone(),
+                    answer: turn.answer.clone(),
+                    model: turn.model.clone(),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl SharedThread {
+    fn to_thread(&self) -> Thread {
+        let mut thread = Thread::with_id(self.id.clone());
+        thread.title = self.title.clone();
+        thread.parent_id = self.parent_id.clone();
+        thread.summary = self.summary.clone();
+        thread.summarized_turns = self.summarized_turns;
+        thread.turns = self
+            .turns
+            .iter()
+            .map(|turn| {
+                Turn::new(
+                    turn.question.clone(),
+                    turn.answer.clone(),
+                    turn.model.clone(),
+                )
+            })
+            .collect();
+        thread
+    }
+}
+
+/// Keep the more confident content; topics from both, the earliest creation time
+pub fn merge_facts(local: &SharedFact, remote: &SharedFact) -> SharedFact {
+    let local_wins =
+        (local.curator_confidence, &local.content) >= (remote.curator_confidence, &remote.content);
+    let (winner, other) = if local_wins {
+        (local, remote)
+    } else {
+        (remote, local)
+    };
+
+    let mut merged = winner.clone();
+    for topic in &other.topics {
+        if !merged.topics.contains(topic) {
+            merged.topics.push(topic.clone());
+        }
+    }
+    merged.created_at = winner.created_at.clone().min(other.created_at.clone());
+    merged
+}
+
+/// Keep the turns both versions share, then each divergent tail
+///
+/// The tails are ordered by their first turn so that every member computes
+/// the same merge. A summary covering diverged turns is dropped.
+pub fn merge_threads(local: &SharedThread, remote: &SharedThread) -> SharedThread {
+    let common = local
+        .turns
+        .iter()
+        .zip(&remote.turns)
+        .take_while(|(a, b)| a == b)
+        .count();
+    if common == remote.turns.len() {
+        return local.clone();
+    }
+    if common == local.turns.len() {
+        return remote.clone();
+    }
+
+    let (local_turn, remote_turn) = (&local.turns[common], &remote.turns[common]);
+    let (first, second) = if (&local_turn.question, &local_turn.answer)
+        <= (&remote_turn.question, &remote_turn.answer)
+    {
+        (local, remote)
+    } else {
+        (remote, local)
+    };
+    let mut merged = first.clone();
+    merged.turns.extend(second.turns[common..].iter().cloned());
+    if merged.summarized_turns > common {
+        merged.summary = None;
+        merged.summarized_turns = 0;
+    }
+    merged
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Item {
+    Fact(SharedFact),
+    Thread(SharedThread),
+}
+
+impl Item {
+    fn kind(&self) -> RecordKind {
+        match self {
+            Item::Fact(_) => RecordKind::Fact,
+            Item::Thread(_) => RecordKind::Thread,
+        }
+    }
+
+    /// Semantic fingerprint or thread id
+    fn key(&self) -> &str {
+        match self {
+            Item::Fact(fact) => &fact.semantic_fingerprint,
+            Item::Thread(thread) => &thread.id,
+        }
+    }
+
+    fn hash(&self) -> Result<String> {
+        Ok(blake3::hash(&serde_json::to_vec(self)?)
+            .to_hex()
+            .to_string())
+    }
+
+    fn merge(self, remote: Item) -> Item {
+        match (self, remote) {
+            (Item::Fact(local), Item::Fact(remote)) => Item::Fact(merge_facts(&local, &remote)),
+            (Item::Thread(local), Item::Thread(remote)) => {
+                Item::Thread(merge_threads(&local, &remote))
+            }
+            (local, _) => local,
+        }
+    }
+}
+
+/// Sealed plaintext of a record; `item` is `None` for removals
+#[derive(Debug, Serialize, Deserialize)]
+struct Envelope {
+    key: String,
+    item: Option<Item>,
+}
+
+/// Carries records to and from a sync server
+#[async_trait]
+pub trait SyncTransport: Send + Sync {
+    async fn push(&self, records: Vec<SyncRecord>) -> Result<PushResponse>;
+    async fn pull(&self, since: u64, limit: usize) -> Result<PullResponse>;
+}
+
+/// HTTP client of `hive-sync-server`
+pub struct SyncClient {
+    http: reqwest::Client,
+    base_url: String,
+    token: String,
+}
+
+impl SyncClient {
+    pub fn new(base_url: &str, token: impl Into<String>) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            token: token.into(),
+        }
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Record counts of the team the token belongs to
+    pub async fn status(&self) -> Result<ServerStatus> {
+        let response = self
+            .http
+            .get(format!("{}/api/sync/status", self.base_url))
+            .bearer_auth(&self.token)
+            .send()
+            .await
+            .with_context(|| format!("Failed to reach sync server {}", self.base_url))?;
+        Self::read(response).await
+    }
+
+    async fn read<T: DeserializeOwned>(response: reqwest::Response) -> Result<T> {
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            bail!("Sync server returned {}: {}", status, body.trim());
+        }
+        Ok(response.json().await?)
+    }
+}
+
+#[async_trait]
+impl SyncTransport for SyncClient {
+    async fn push(&self, records: Vec<SyncRecord>) -> Result<PushResponse> {
+        let response = self
+            .http
+            .post(format!("{}/api/sync/push", self.base_url))
+            .bearer_auth(&self.token)
+            .json(&PushRequest { records })
+            .send()
+            .await
+            .with_context(|| format!("Failed to reach sync server {}", self.base_url))?;
+        Self::read(response).await
+    }
+
+    async fn pull(&self, since: u64, limit: usize) -> Result<PullResponse> {
+        let response = self
+            .http
+            .get(format!("{}/api/sync/pull", self.base_url))
+            .query(&[("since", since), ("limit", limit as u64)])
+            .bearer_auth(&self.token)
+            .send()
+            .await
+            .with_context(|| format!("Failed to reach sync server {}", self.base_url))?;
+        Self::read(response).await
+    }
+}
+
+/// What one pull, push or sync did
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SyncReport {
+    /// Records taken from the server as they were
+    pub pulled: usize,
+    /// Records the server accepted
+    pub pushed: usize,
+    /// Records edited on both sides and merged
+    pub merged: usize,
+    /// Records the team removed; local copies are kept but no longer synced
+    pub removed: usize,
+}
+
+impl SyncReport {
+    fn absorb(&mut self, other: SyncReport) {
+        self.pulled += other.pulled;
+        self.pushed += other.pushed;
+        self.merged += other.merged;
+        self.removed += other.removed;
+    }
+}
+
+/// Local side of team sync
+#[derive(Debug, Clone, Serialize)]
+pub struct SyncStatus {
+    pub node_id: String,
+    pub team: String,
+    /// Position in the team's change feed
+    pub cursor: u64,
+    pub facts: usize,
+    pub shared_threads: usize,
+    /// Records changed since they were last exchanged
+    pub pending: usize,
+}
+
+/// What was last exchanged for one record
+struct Tracked {
+    version: VersionVector,
+    hash: Option<String>,
+    shared: bool,
+}
+
+/// Pushes and pulls the facts and shared threads of one database
+pub struct TeamSync {
+    database: Arc<DatabaseManager>,
+    threads: ThreadStore,
+    transport: Arc<dyn SyncTransport>,
+    key: TeamKey,
+    node_id: String,
+    share_facts: bool,
+}
+
+impl TeamSync {
+    /// Open the local sync state, creating its tables and node id if needed
+    pub fn new(
+        database: Arc<DatabaseManager>,
+        transport: Arc<dyn SyncTransport>,
+        key: TeamKey,
+    ) -> Result<Self> {
+        let conn = database.get_connection()?;
+        conn.execute_batch(FACTS_SCHEMA)?;
+        conn.execute_batch(SCHEMA)
+            .context("Failed to create team sync tables")?;
+        let node_id = match conn
+            .query_row(
+                "SELECT value FROM team_sync_state WHERE key = ?1",
+                params![NODE_ID_KEY],
+                |row| row.get(0),
+            )
+            .optional()?
+        {
+            Some(node_id) => node_id,
+            None => {
+                let node_id = generate_id();
+                conn.execute(
+                    "INSERT INTO team_sync_state (key, value) VALUES (?1, ?2)",
+                    params![NODE_ID_KEY, node_id],
+                )?;
+                node_id
+            }
+        };
+        drop(conn);
+
+        Ok(Self {
+            threads: ThreadStore::new(database.clone())?,
+            database,
+            transport,
+            key,
+            node_id,
+            share_facts: true,
+        })
+    }
+
+    /// Whether curated facts are pushed; pulled facts are always kept
+    pub fn with_share_facts(mut self, share_facts: bool) -> Self {
+        self.share_facts = share_facts;
+        self
+    }
+
+    /// Identifies this installation in version vectors
+    pub fn node_id(&self) -> &str {
+        &self.node_id
+    }
+
+    /// Share a thread with the team from the next push on; returns its full id
+    pub fn share_thread(&self, id: &str) -> Result<String> {
+        let id = self.threads.resolve(id)?;
+        self.database.get_connection()?.execute(
+            "INSERT INTO team_sync_records (kind, record_key) VALUES (?1, ?2)
+             ON CONFLICT(kind, record_key) DO UPDATE SET shared = 1",
+            params![RecordKind::Thread.as_str(), id],
+        )?;
+        Ok(id)
+    }
+
+    /// Remove a shared thread from the team; the local copy is kept
+    pub async fn unshare_thread(&self, id: &str) -> Result<String> {
+        let id = self.threads.resolve(id)?;
+        let Some(tracked) = self
+            .tracked(RecordKind::Thread, &id)?
+            .filter(|tracked| tracked.shared)
+        else {
+            bail!("Thread {} is not shared", &id[..id.len().min(8)]);
+        };
+
+        let mut version = tracked.version;
+        version.increment(&self.node_id);
+        let record = self.seal(RecordKind::Thread, &id, version.clone(), None)?;
+        let response = self.transport.push(vec![record]).await?;
+        if !response.conflicts.is_empty() {
+            bail!(
+                "Thread {} changed on the server; run `hive sync` and try again",
+                &id[..id.len().min(8)]
+            );
+        }
+        self.track(RecordKind::Thread, &id, &version, None)?;
+        self.unshare(RecordKind::Thread, &id)?;
+        Ok(id)
+    }
+
+    /// Apply every change the team made since the last pull
+    pub async fn pull(&self) -> Result<SyncReport> {
+        let mut report = SyncReport::default();
+        let mut cursor = self.cursor()?;
+        loop {
+            let page = self.transport.pull(cursor, PAGE_SIZE).await?;
+            for record in &page.records {
+                self.apply_remote(record, &mut report)?;
+            }
+            cursor = page.cursor;
+            self.set_cursor(cursor)?;
+            if !page.more || page.records.is_empty() {
+                break;
+            }
+        }
+        Ok(report)
+    }
+
+    /// Send every record changed since it was last exchanged
+    ///
+    /// Records the server holds a newer or concurrent version of are merged
+    /// locally and pushed again.
+    pub async fn push(&self) -> Result<SyncReport> {
+        let mut report = SyncReport::default();
+        for _ in 0..MAX_PUSH_ROUNDS {
+            let pending = self.pending()?;
+            if pending.is_empty() {
+                break;
+            }
+
+            let mut conflicts = 0;
+            for batch in pending.chunks(PAGE_SIZE) {
+                let records = batch
+                    .iter()
+                    .map(|(item, version, _)| {
+                        self.seal(item.kind(), item.key(), version.clone(), Some(item))
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                let ids: Vec<String> = records.iter().map(|record| record.id.clone()).collect();
+                let response = self.transport.push(records).await?;
+
+                let accepted: HashSet<&String> = response.accepted.iter().collect();
+                for (id, (item, version, hash)) in ids.iter().zip(batch) {
+                    if accepted.contains(id) {
+                        self.track(item.kind(), item.key(), version, Some(hash))?;
+                        report.pushed += 1;
+                    }
+                }
+                for conflict in &response.conflicts {
+                    self.apply_remote(conflict, &mut report)?;
+                    conflicts += 1;
+                }
+            }
+            if conflicts == 0 {
+                break;
+            }
+        }
+        Ok(report)
+    }
+
+    /// Pull, then push what changed locally
+    pub async fn sync(&self) -> Result<SyncReport> {
+        let mut report = self.pull().await?;
+        report.absorb(self.push().await?);
+        Ok(report)
+    }
+
+    pub fn status(&self) -> Result<SyncStatus> {
+        let conn = self.database.get_connection()?;
+        let facts: i64 =
+            conn.query_row("SELECT COUNT(*) FROM consensus_facts", [], |row| row.get(0))?;
+        let shared_threads: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM team_sync_records WHERE kind = ?1 AND shared = 1",
+            params![RecordKind::Thread.as_str()],
+            |row| row.get(0),
+        )?;
+        drop(conn);
+
+        Ok(SyncStatus {
+            node_id: self.node_id.clone(),
+            team: self.key.team().to_string(),
+            cursor: self.cursor()?,
+            facts: facts as usize,
+            shared_threads: shared_threads as usize,
+            pending: self.pending()?.len(),
+        })
+    }
+
+    /// Local records that changed since they were last exchanged, with the
+    /// version and hash to push them under
+    fn pending(&self) -> Result<Vec<(Item, VersionVector, String)>> {
+        let mut items = Vec::new();
+        if self.share_facts {
+            items.extend(self.local_facts()?.into_iter().map(Item::Fact));
+        }
+        let conn = self.database.get_connection()?;
+        let thread_ids: Vec<String> = conn
+            .prepare(
+                "SELECT record_key FROM team_sync_records
+                 WHERE kind = ?1 AND shared = 1 ORDER BY record_key",
+            )?
+            .query_map(params![RecordKind::Thread.as_str()], |row| row.get(0))?
+            .collect::<rusqlite::Result<_>>()?;
+        drop(conn);
+        for id in thread_ids {
+            items.extend(self.read_local(RecordKind::Thread, &id)?);
+        }
+
+        let mut pending = Vec::new();
+        for item in items {
+            let tracked = self.tracked(item.kind(), item.key())?;
+            let hash = item.hash()?;
+            let mut version = match tracked {
+                Some(tracked) if !tracked.shared => continue,
+                Some(tracked) if tracked.hash.as_deref() == Some(hash.as_str()) => continue,
+                Some(tracked) => tracked.version,
+                None => VersionVector::new(),
+            };
+            version.increment(&self.node_id);
+            pending.push((item, version, hash));
+        }
+        Ok(pending)
+    }
+
+    /// Bring one record from the server into the local database
+    fn apply_remote(&self, record: &SyncRecord, report: &mut SyncReport) -> Result<()> {
+        let envelope: Envelope = serde_json::from_slice(&self.key.open(record)?)
+            .with_context(|| format!("Sync record {} is malformed", record.id))?;
+        let matches = self.key.record_id(record.kind, &envelope.key) == record.id
+            && envelope.item.as_ref().map_or(true, |item| {
+                item.kind() == record.kind && item.key() == envelope.key
+            });
+        if !matches {
+            bail!("Sync record {} does not match its contents", record.id);
+        }
+
+        let (kind, key) = (record.kind, envelope.key.as_str());
+        let tracked = self.tracked(kind, key)?;
+        let mut version = record.version.clone();
+        if let Some(tracked) = &tracked {
+            if matches!(
+                record.version.compare(&tracked.version),
+                Causality::Equal | Causality::Before
+            ) {
+                return Ok(());
+            }
+            version.merge(&tracked.version);
+            // Removed from the team here; later versions are not taken back in
+            if !tracked.shared {
+                return self.track(kind, key, &version, None);
+            }
+        }
+
+        let current = self.read_local(kind, key)?;
+        let current_hash = current.as_ref().map(Item::hash).transpose()?;
+        let remote_hash = envelope.item.as_ref().map(Item::hash).transpose()?;
+        let changed_locally = match &tracked {
+            Some(tracked) => tracked.hash != current_hash,
+            None => current.is_some(),
+        };
+
+        match (current, envelope.item) {
+            (Some(current), remote) if changed_locally => {
+                let merged = match remote {
+                    Some(remote) => current.merge(remote),
+                    None => current,
+                };
+                if Some(merged.hash()?) != current_hash {
+                    self.write_local(&merged)?;
+                }
+                report.merged += 1;
+            }
+            (_, Some(remote)) => {
+                self.write_local(&remote)?;
+                report.pulled += 1;
+            }
+            (current, None) => {
+                // Removed from the team: stop syncing it, but the local copy stays
+                self.track(kind, key, &version, None)?;
+                self.unshare(kind, key)?;
+                if current.is_some() {
+                    report.removed += 1;
+                }
+                return Ok(());
+            }
+        }
+        // The remote hash makes a merged result differ, so the next push sends it
+        self.track(kind, key, &version, remote_hash.as_deref())
+    }
+
+    fn seal(
+        &self,
+        kind: RecordKind,
+        key: &str,
+        version: VersionVector,
+        item: Option<&Item>,
+    ) -> Result<SyncRecord> {
+        let envelope = Envelope {
+            key: key.to_string(),
+            item: item.cloned(),
+        };
+        self.key.seal(
+            kind,
+            key,
+            version,
+            item.is_none(),
+            &serde_json::to_vec(&envelope)?,
+            &self.node_id,
+        )
+    }
+
+    fn cursor_key(&self) -> String {
+        format!("cursor:{}", self.key.team())
+    }
+
+    fn cursor(&self) -> Result<u64> {
+        let cursor: Option<String> = self
+            .database
+            .get_connection()?
+            .query_row(
+                "SELECT value FROM team_sync_state WHERE key = ?1",
+                params![self.cursor_key()],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(cursor.and_then(|c| c.parse().ok()).unwrap_or(0))
+    }
+
+    fn set_cursor(&self, cursor: u64) -> Result<()> {
+        self.database.get_connection()?.execute(
+            "INSERT INTO team_sync_state (key, value) VALUES (?1, ?2)
+             ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+            params![self.cursor_key(), cursor.to_string()],
+        )?;
+        Ok(())
+    }
+
+    fn tracked(&self, kind: RecordKind, key: &str) -> Result<Option<Tracked>> {
+        let row: Option<(String, Option<String>, bool)> = self
+            .database
+            .get_connection()?
+            .query_row(
+                "SELECT version, content_hash, shared FROM team_sync_records
+                 WHERE kind = ?1 AND record_key = ?2",
+                params![kind.as_str(), key],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .optional()?;
+        row.map(|(version, hash, shared)| {
+            Ok(Tracked {
+                version: VersionVector::from_json(&version)?,
+                hash,
+                shared,
+            })
+        })
+        .transpose()
+    }
+
+    /// Keep a record out of future pushes and pulls
+    fn unshare(&self, kind: RecordKind, key: &str) -> Result<()> {
+        self.database.get_connection()?.execute(
+            "UPDATE team_sync_records SET shared = 0 WHERE kind = ?1 AND record_key = ?2",
+            params![kind.as_str(), key],
+        )?;
+        Ok(())
+    }
+
+    fn track(
+        &self,
+        kind: RecordKind,
+        key: &str,
+        version: &VersionVector,
+        hash: Option<&str>,
+    ) -> Result<()> {
+        self.database.get_connection()?.execute(
+            "INSERT INTO team_sync_records (kind, record_key, version, content_hash, synced_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)
+             ON CONFLICT(kind, record_key) DO UPDATE SET
+                version = excluded.version,
+                content_hash = excluded.content_hash,
+                synced_at = excluded.synced_at",
+            params![
+                kind.as_str(),
+                key,
+                version.to_json(),
+                hash,
+                Utc::now().to_rfc3339()
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn local_facts(&self) -> Result<Vec<SharedFact>> {
+        let conn = self.database.get_connection()?;
+        let facts = conn
+            .prepare(&format!(
+                "{} ORDER BY semantic_fingerprint",
+                SELECT_SHARED_FACT
+            ))?
+            .query_map([], row_to_fact)?
+            .collect::<rusqlite::Result<_>>()?;
+        Ok(facts)
+    }
+
+    fn read_local(&self, kind: RecordKind, key: &str) -> Result<Option<Item>> {
+        match kind {
+            RecordKind::Fact => {
+                let fact = self
+                    .database
+                    .get_connection()?
+                    .query_row(
+                        &format!("{} WHERE semantic_fingerprint = ?1", SELECT_SHARED_FACT),
+                        params![key],
+                        row_to_fact,
+                    )
+                    .optional()?;
+                Ok(fact.map(Item::Fact))
+            }
+            RecordKind::Thread => {
+                let thread = self.threads.open(key, None)?;
+                Ok((!thread.turns.is_empty()).then(|| Item::Thread(SharedThread::from(&thread))))
+            }
+        }
+    }
+
+    fn write_local(&self, item: &Item) -> Result<()> {
+        match item {
+            Item::Fact(fact) => {
+                let stages = fact.consensus_stages.to_string();
+                let topics = serde_json::to_string(&fact.topics)?;
+                let entities = fact.entities.to_string();
+                let metadata = fact.metadata.to_string();
+                let conn = self.database.get_connection()?;
+                let updated = conn.execute(
+                    "UPDATE consensus_facts SET
+                        content = ?2, curator_confidence = ?3, source_question = ?4,
+                        consensus_stages = ?5, created_at = ?6, topics = ?7, entities = ?8,
+                        metadata = ?9
+                     WHERE semantic_fingerprint = ?1",
+                    params![
+                        fact.semantic_fingerprint,
+                        fact.content,
+                        fact.curator_confidence,
+                        fact.source_question,
+                        stages,
+                        fact.created_at,
+                        topics,
+                        entities,
+                        metadata
+                    ],
+                )?;
+                if updated == 0 {
+                    conn.execute(
+                        "INSERT INTO consensus_facts (
+                            id, semantic_fingerprint, content, curator_confidence,
+                            source_question, consensus_stages, created_at, last_accessed,
+                            topics, entities, metadata
+                        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+                        params![
+                            generate_id(),
+                            fact.semantic_fingerprint,
+                            fact.content,
+                            fact.curator_confidence,
+                            fact.source_question,
+                            stages,
+                            fact.created_at,
+                            Utc::now(),
+                            topics,
+                            entities,
+                            metadata
+                        ],
+                    )?;
+                }
+            }
+            Item::Thread(thread) => self.threads.replace(&mut thread.to_thread())?,
+        }
+        Ok(())
+    }
+}
+
+const SELECT_SHARED_FACT: &str = "SELECT semantic_fingerprint, content, curator_confidence,
+        source_question, consensus_stages, created_at, topics, entities, metadata
+     FROM consensus_facts";
+
+fn row_to_fact(row: &Row) -> rusqlite::Result<SharedFact> {
+    let json = |index: usize| -> rusqlite::Result<serde_json::Value> {
+        let text: String = row.get(index)?;
+        Ok(serde_json::from_str(&text).unwrap_or(serde_json::Value::Null))
+    };
+    Ok(SharedFact {
+        semantic_fingerprint: row.get(0)?,
+        content: row.get(1)?,
+        curator_confidence: row.get(2)?,
+        source_question: row.get(3)?,
+        consensus_stages: json(4)?,
+        created_at: row.get(5)?,
+        topics: serde_json::from_str(&row.get::<_, String>(6)?).unwrap_or_default(),
+        entities: json(7)?,
+        metadata: json(8)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::database::DatabaseConfig;
+    use crate::team_sync::SyncServerStore;
+    use tempfile::TempDir;
+
+    /// Talks to a server store in-process
+    struct LocalServer {
+        store: Arc<SyncServerStore>,
+        team: String,
+    }
+
+    #[async_trait]
+    impl SyncTransport for LocalServer {
+        async fn push(&self, records: Vec<SyncRecord>) -> Result<PushResponse> {
+            self.store.push(&self.team, &records)
+        }
+
+        async fn pull(&self, since: u64, limit: usize) -> Result<PullResponse> {
+            self.store.pull(&self.team, since, limit)
+        }
+    }
+
+    async fn database(dir: &TempDir, name: &str) -> Arc<DatabaseManager> {
+        Arc::new(
+            DatabaseManager::new(DatabaseConfig {
+                path: dir.path().join(name),
+                ..DatabaseConfig::default()
+            })
+            .await
+            .unwrap(),
+        )
+    }
+
+    fn fact(fingerprint: &str, content: &str, confidence: f64, topics: &[&str]) -> SharedFact {
+        SharedFact {
+            semantic_fingerprint: fingerprint.to_string(),
+            content: content.to_string(),
+            curator_confidence: confidence,
+            source_question: "q".to_string(),
+            consensus_stages: serde_json::json!([]),
+            created_at: "2025-01-01T00:00:00Z".to_string(),
+            topics: topics.iter().map(|t| t.to_string()).collect(),
+            entities: serde_json::json!([]),
+            metadata: serde_json::json!({}),
+        }
+    }
+
+    fn questions(store: &ThreadStore, id: &str) -> Vec<String> {
+        store
+            .load(id)
+            .unwrap()
+            .turns
+            .into_iter()
+            .map(|turn| turn.question)
+            .collect()
+    }
+
+    #[test]
+    fn conflicting_facts_merge_the_same_way_on_both_sides() {
+        let local = fact("fp", "Rust is fast", 0.9, &["rust"]);
+        let remote = fact("fp", "Rust is quick", 0.7, &["performance", "rust"]);
+        let merged = merge_facts(&local, &remote);
+        assert_eq!(merged.content, "Rust is fast");
+        assert_eq!(merged.topics, ["rust", "performance"]);
+        assert_eq!(
+            serde_json::to_string(&merge_facts(&remote, &local)).unwrap(),
+            serde_json::to_string(&merged).unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn members_converge_on_facts_and_shared_threads() {
+        let dir = TempDir::new().unwrap();
+        let store = Arc::new(SyncServerStore::new(database(&dir, "server.db").await).unwrap());
+        let transport = Arc::new(LocalServer {
+            store,
+            team: "platform".to_string(),
+        });
+        let key = TeamKey::derive("platform", "team passphrase").unwrap();
+
+        let alice_db = database(&dir, "alice.db").await;
+        let bob_db = database(&dir, "bob.db").await;
+        let alice = TeamSync::new(alice_db.clone(), transport.clone(), key.clone()).unwrap();
+        let bob = TeamSync::new(bob_db.clone(), transport, key).unwrap();
+        let alice_threads = ThreadStore::new(alice_db).unwrap();
+        let bob_threads = ThreadStore::new(bob_db).unwrap();
+
+        // Alice learns a fact and shares one of two threads
+        alice
+            .write_local(&Item::Fact(fact("fp-1", "WAL helps", 0.8, &["sqlite"])))
+            .unwrap();
+        let mut shared = Thread::new();
+        alice_threads
+            .append_turn(&mut shared, "one", "1", None, 0.1)
+            .unwrap();
+        let mut private = Thread::new();
+        alice_threads
+            .append_turn(&mut private, "secret", "s", None, 0.0)
+            .unwrap();
+        alice.share_thread(shared.short_id()).unwrap();
+
+        assert_eq!(alice.status().unwrap().pending, 2);
+        assert_eq!(alice.sync().await.unwrap().pushed, 2);
+        assert_eq!(alice.status().unwrap().pending, 0);
+
+        let report = bob.sync().await.unwrap();
+        assert_eq!(report.pulled, 2);
+        assert_eq!(report.pushed, 0);
+        assert_eq!(questions(&bob_threads, &shared.id), ["one"]);
+        assert!(bob_threads.load(&private.id).is_err());
+        assert_eq!(bob.status().unwrap().facts, 1);
+
+        // Both continue the thread before syncing
+        alice_threads
+            .append_turn(&mut shared, "alice asks", "a", None, 0.0)
+            .unwrap();
+        let mut bobs_copy = bob_threads.load(&shared.id).unwrap();
+        bob_threads
+            .append_turn(&mut bobs_copy, "bob asks", "b", None, 0.0)
+            .unwrap();
+
+        alice.sync().await.unwrap();
+        let report = bob.sync().await.unwrap();
+        assert_eq!(report.merged, 1);
+        assert_eq!(report.pushed, 1);
+        alice.sync().await.unwrap();
+
+        let merged = ["one", "alice asks", "bob asks"];
+        assert_eq!(questions(&alice_threads, &shared.id), merged);
+        assert_eq!(questions(&bob_threads, &shared.id), merged);
+        assert_eq!(alice.status().unwrap().pending, 0);
+        assert_eq!(bob.status().unwrap().pending, 0);
+
+        // Removing the thread from the team keeps every copy but stops syncing it
+        alice.unshare_thread(&shared.id).await.unwrap();
+        assert_eq!(bob.sync().await.unwrap().removed, 1);
+        assert_eq!(questions(&bob_threads, &shared.id), merged);
+        assert_eq!(bob.status().unwrap().shared_threads, 0);
+        let mut bobs_copy = bob_threads.load(&shared.id).unwrap();
+        bob_threads
+            .append_turn(&mut bobs_copy, "bob again", "b", None, 0.0)
+            .unwrap();
+        assert_eq!(bob.sync().await.unwrap().pushed, 0);
+        assert_eq!(alice.sync().await.unwrap().pushed, 0);
+        assert_eq!(questions(&alice_threads, &shared.id), merged);
+    }
+}
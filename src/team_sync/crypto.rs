@@ -0,0 +1,183 @@
+//! Team key - end-to-end encryption of sync records
+//!
+//! Every member derives the same key from the team passphrase with Argon2id,
+//! salted with the team name. The server never sees the passphrase or key.
+
+use anyhow::{anyhow, bail, Result};
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{Key, XChaCha20Poly1305, XNonce};
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+
+use super::{RecordKind, SyncRecord, VersionVector};
+
+/// Symmetric key shared by the members of one team
+#[derive(Clone)]
+pub struct TeamKey {
+    team: String,
+    key: [u8; 32],
+}
+
+impl TeamKey {
+    /// Stretch the team passphrase into the team key
+    pub fn derive(team: &str, passphrase: &str) -> Result<Self> {
+        if passphrase.is_empty() {
+            bail!("The team sync passphrase is empty");
+        }
+        let salt = Sha256::digest(format!("hive-team-sync:{}", team).as_bytes());
+        let mut key = [0u8; 32];
+        argon2::Argon2::default()
+            .hash_password_into(passphrase.as_bytes(), &salt[..16], &mut key)
+            .map_err(|e| anyhow!("Failed to derive team key: {}", e))?;
+        Ok(Self {
+            team: team.to_string(),
+            key,
+        })
+    }
+
+    pub fn team(&self) -> &str {
+        &self.team
+    }
+
+    /// Opaque server-side id of a record, so fingerprints and thread ids stay private
+    pub fn record_id(&self, kind: RecordKind, record_key: &str) -> String {
+        let input = format!("{}:{}", kind.as_str(), record_key);
+        blake3::keyed_hash(&self.key, input.as_bytes())
+            .to_hex()
+            .to_string()
+    }
+
+    /// Team, kind, id, version, deletion and author are authenticated with the
+    /// payload, so the server cannot move ciphertext between records, replay it
+    /// under a newer version or credit it to another member
+    fn associated_data(
+        &self,
+        kind: RecordKind,
+        id: &str,
+        version: &VersionVector,
+        deleted: bool,
+        author: &str,
+    ) -> String {
+        format!(
+            "{}\n{}\n{}\n{}\n{}\n{}",
+            self.team,
+            kind.as_str(),
+            id,
+            version.to_json(),
+            deleted,
+            author
+        )
+    }
+
+    /// Encrypt `plaintext` into a record with the given metadata
+    pub fn seal(
+        &self,
+        kind: RecordKind,
+        record_key: &str,
+        version: VersionVector,
+        deleted: bool,
+        plaintext: &[u8],
+        author: &str,
+    ) -> Result<SyncRecord> {
+        let id = self.record_id(kind, record_key);
+        let aad = self.associated_data(kind, &id, &version, deleted, author);
+        let mut nonce = [0u8; 24];
+        rand::thread_rng().fill_bytes(&mut nonce);
+        let ciphertext = XChaCha20Poly1305::new(Key::from_slice(&self.key))
+            .encrypt(
+                XNonce::from_slice(&nonce),
+                Payload {
+                    msg: plaintext,
+                    aad: aad.as_bytes(),
+                },
+            )
+            .map_err(|_| anyhow!("Failed to encrypt sync record"))?;
+        Ok(SyncRecord {
+            kind,
+            id,
+            version,
+            deleted,
+            nonce: STANDARD.encode(nonce),
+            ciphertext: STANDARD.encode(ciphertext),
+            author: author.to_string(),
+            updated_at: chrono::Utc::now().to_rfc3339(),
+            seq: 0,
+        })
+    }
+
+    /// Decrypt a record sealed with this key
+    pub fn open(&self, record: &SyncRecord) -> Result<Vec<u8>> {
+        let nonce = STANDARD.decode(&record.nonce)?;
+        if nonce.len() != 24 {
+            bail!("Sync record {} has a malformed nonce", record.id);
+        }
+        let ciphertext = STANDARD.decode(&record.ciphertext)?;
+        let aad = self.associated_data(
+            record.kind,
+            &record.id,
+            &record.version,
+            record.deleted,
+            &record.author,
+        );
+        XChaCha20Poly1305::new(Key::from_slice(&self.key))
+            .decrypt(
+                XNonce::from_slice(&nonce),
+                Payload {
+                    msg: &ciphertext,
+                    aad: aad.as_bytes(),
+                },
+            )
+            .map_err(|_| {
+                anyhow!(
+                    "Sync record {} cannot be decrypted; check the team passphrase",
+                    record.id
+                )
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_round_trip_and_resist_tampering() {
+        let key = TeamKey::derive("platform", "correct horse").unwrap();
+        let mut version = VersionVector::new();
+        version.increment("node-a");
+        let record = key
+            .seal(
+                RecordKind::Fact,
+                "fp-1",
+                version,
+                false,
+                b"payload",
+                "node-a",
+            )
+            .unwrap();
+        assert_eq!(record.id, key.record_id(RecordKind::Fact, "fp-1"));
+        assert!(!record.id.contains("fp-1"));
+        assert_eq!(key.open(&record).unwrap(), b"payload");
+
+        // A replay under a newer version is rejected
+        let mut replayed = record.clone();
+        replayed.version.increment("node-b");
+        assert!(key.open(&replayed).is_err());
+
+        let mut flipped = record.clone();
+        flipped.deleted = true;
+        assert!(key.open(&flipped).is_err());
+
+        let mut reattributed = record.clone();
+        reattributed.author = "node-b".to_string();
+        assert!(key.open(&reattributed).is_err());
+
+        // Other teams and other passphrases cannot read it
+        let other_team = TeamKey::derive("mobile", "correct horse").unwrap();
+        assert!(other_team.open(&record).is_err());
+        let wrong = TeamKey::derive("platform", "battery staple").unwrap();
+        assert!(wrong.open(&record).is_err());
+        assert_ne!(wrong.record_id(RecordKind::Fact, "fp-1"), record.id);
+    }
+}
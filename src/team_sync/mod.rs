@@ -0,0 +1,223 @@
+//! Team knowledge sync through a self-hostable server
+//!
+//! Curated facts and explicitly shared threads are exchanged with a
+//! `hive-sync-server` instance the team runs itself:
+//! - Every record carries a version vector, so concurrent edits are detected
+//!   rather than overwritten
+//! - The server only accepts versions that supersede what it holds; clients
+//!   merge conflicting versions and push the result
+//! - Records are sealed with a key derived from the team passphrase, so the
+//!   server sees keyed-hash ids, version vectors and ciphertext only
+//! - Each access token belongs to one team and only sees that team's records
+
+pub mod client;
+pub mod crypto;
+pub mod server;
+
+pub use client::{
+    SharedFact, SharedThread, SyncClient, SyncReport, SyncStatus, SyncTransport, TeamSync,
+};
+pub use crypto::TeamKey;
+pub use server::{SyncServerStore, TeamTokens};
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+
+/// Same statements as the numbered migration; all of them are idempotent
+pub(crate) const SCHEMA: &str = include_str!("../../migrations/015_add_team_sync_tables.sql");
+
+/// Records sent or returned per request
+pub const PAGE_SIZE: usize = 200;
+
+/// What a sync record holds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecordKind {
+    Fact,
+    Thread,
+}
+
+impl RecordKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RecordKind::Fact => "fact",
+            RecordKind::Thread => "thread",
+        }
+    }
+
+    pub fn parse(value: &str) -> Result<Self> {
+        match value {
+            "fact" => Ok(RecordKind::Fact),
+            "thread" => Ok(RecordKind::Thread),
+            other => Err(anyhow!("Unknown sync record kind: {}", other)),
+        }
+    }
+}
+
+/// How two versions of a record relate
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Causality {
+    Equal,
+    /// This version is an ancestor of the other
+    Before,
+    /// This version supersedes the other
+    After,
+    /// Both were edited independently
+    Concurrent,
+}
+
+/// Per-node edit counters of one record
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct VersionVector(BTreeMap<String, u64>);
+
+impl VersionVector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, node: &str) -> u64 {
+        self.0.get(node).copied().unwrap_or(0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Record one more edit by `node`
+    pub fn increment(&mut self, node: &str) {
+        *self.0.entry(node.to_string()).or_insert(0) += 1;
+    }
+
+    /// Pointwise maximum; the result has seen every edit either side has
+    pub fn merge(&mut self, other: &VersionVector) {
+        for (node, &counter) in &other.0 {
+            let entry = self.0.entry(node.clone()).or_insert(0);
+            *entry = (*entry).max(counter);
+        }
+    }
+
+    pub fn compare(&self, other: &VersionVector) -> Causality {
+        let mut ordering = Ordering::Equal;
+        for node in self.0.keys().chain(other.0.keys()) {
+            match (self.get(node).cmp(&other.get(node)), ordering) {
+                (Ordering::Equal, _) => {}
+                (step, Ordering::Equal) => ordering = step,
+                (step, current) if step != current => return Causality::Concurrent,
+                _ => {}
+            }
+        }
+        match ordering {
+            Ordering::Equal => Causality::Equal,
+            Ordering::Less => Causality::Before,
+            Ordering::Greater => Causality::After,
+        }
+    }
+
+    /// Canonical JSON, also bound into each record's ciphertext
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(&self.0).unwrap_or_else(|_| "{}".to_string())
+    }
+
+    pub fn from_json(value: &str) -> Result<Self> {
+        Ok(serde_json::from_str(value)?)
+    }
+}
+
+/// One version of a record as the server stores it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncRecord {
+    pub kind: RecordKind,
+    /// Keyed hash of the record key; see [`TeamKey::record_id`]
+    pub id: String,
+    pub version: VersionVector,
+    pub deleted: bool,
+    /// Base64 XChaCha20 nonce
+    pub nonce: String,
+    /// Base64 sealed payload; deletions only carry the record key
+    pub ciphertext: String,
+    /// Node that wrote this version
+    pub author: String,
+    pub updated_at: String,
+    /// Position in the team's change feed, assigned by the server
+    #[serde(default)]
+    pub seq: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PushRequest {
+    pub records: Vec<SyncRecord>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PushResponse {
+    /// Ids of records now stored (or already stored) at the pushed version
+    pub accepted: Vec<String>,
+    /// The server's version of each record that was not superseded
+    pub conflicts: Vec<SyncRecord>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PullResponse {
+    pub records: Vec<SyncRecord>,
+    /// Pass as `since` on the next pull
+    pub cursor: u64,
+    /// Whether more records follow `cursor`
+    pub more: bool,
+}
+
+/// Reply to `GET /api/sync/status`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ServerStatus {
+    pub team: String,
+    pub records: u64,
+    pub deleted: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vector(entries: &[(&str, u64)]) -> VersionVector {
+        VersionVector(
+            entries
+                .iter()
+                .map(|(node, counter)| (node.to_string(), *counter))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn version_vectors_order_causally() {
+        let base = vector(&[("a", 1)]);
+        let mut next = base.clone();
+        next.increment("b");
+
+        assert_eq!(base.compare(&base), Causality::Equal);
+        assert_eq!(base.compare(&next), Causality::Before);
+        assert_eq!(next.compare(&base), Causality::After);
+        assert_eq!(VersionVector::new().compare(&base), Causality::Before);
+
+        let mut other = base.clone();
+        other.increment("a");
+        assert_eq!(next.compare(&other), Causality::Concurrent);
+
+        let mut merged = next.clone();
+        merged.merge(&other);
+        assert_eq!(merged, vector(&[("a", 2), ("b", 1)]));
+        assert_eq!(merged.compare(&next), Causality::After);
+        assert_eq!(merged.compare(&other), Causality::After);
+    }
+
+    #[test]
+    fn version_vector_json_is_canonical() {
+        let version = vector(&[("z", 3), ("a", 1)]);
+        assert_eq!(version.to_json(), r#"{"a":1,"z":3}"#);
+        assert_eq!(
+            VersionVector::from_json(&version.to_json()).unwrap(),
+            version
+        );
+    }
+}
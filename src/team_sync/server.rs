@@ -0,0 +1,313 @@
+//! Sync server store - encrypted records of every team
+//!
+//! Used by the `hive-sync-server` binary. The store cannot read records; it
+//! only orders versions by their version vectors and hands out a per-team
+//! change feed that clients page through with a cursor.
+
+use anyhow::{Context, Result};
+use rusqlite::{params, types::Type, OptionalExtension, Row, TransactionBehavior};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use super::{
+    Causality, PullResponse, PushResponse, RecordKind, SyncRecord, VersionVector, PAGE_SIZE, SCHEMA,
+};
+use crate::core::database::DatabaseManager;
+
+/// Access tokens and the team each one belongs to
+///
+/// Only token hashes are kept in memory.
+#[derive(Debug, Clone, Default)]
+pub struct TeamTokens {
+    teams: HashMap<[u8; 32], String>,
+}
+
+impl TeamTokens {
+    /// Parse a comma-separated list of `team:token` entries
+    pub fn from_list(list: &str) -> Result<Self> {
+        let mut tokens = Self::default();
+        for entry in list.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            let (team, token) = entry
+                .split_once(':')
+                .filter(|(team, token)| !team.is_empty() && !token.is_empty())
+                .with_context(|| format!("Invalid team token entry '{}': use team:token", entry))?;
+            tokens.insert(team, token);
+        }
+        Ok(tokens)
+    }
+
+    pub fn insert(&mut self, team: &str, token: &str) {
+        self.teams.insert(Self::hash(token), team.to_string());
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.teams.is_empty()
+    }
+
+    /// The team `token` grants access to
+    pub fn team(&self, token: &str) -> Option<&str> {
+        self.teams.get(&Self::hash(token)).map(String::as_str)
+    }
+
+    fn hash(token: &str) -> [u8; 32] {
+        Sha256::digest(token.as_bytes()).into()
+    }
+}
+
+/// SQLite storage behind the sync server
+pub struct SyncServerStore {
+    database: Arc<DatabaseManager>,
+}
+
+impl SyncServerStore {
+    /// Open the store, creating its tables if needed
+    pub fn new(database: Arc<DatabaseManager>) -> Result<Self> {
+        let conn = database.get_connection()?;
+        conn.execute_batch(SCHEMA)
+            .context("Failed to create team sync tables")?;
+        Ok(Self { database })
+    }
+
+    /// Store every record that supersedes the team's current version
+    ///
+    /// Re-pushing the stored version is accepted, so retries are harmless.
+    /// Anything older or concurrent comes back as a conflict carrying the
+    /// stored version for the client to merge.
+    pub fn push(&self, team: &str, records: &[SyncRecord]) -> Result<PushResponse> {
+        let mut conn = self.database.get_connection()?;
+        let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+        let mut seq: u64 = tx.query_row(
+            "SELECT COALESCE(MAX(seq), 0) FROM team_sync_server_records",
+            [],
+            |row| row.get::<_, i64>(0),
+        )? as u64;
+
+        let mut response = PushResponse::default();
+        for record in records {
+            let current = tx
+                .query_row(
+                    "SELECT kind, id, version, deleted, nonce, ciphertext, author, updated_at, seq
+                     FROM team_sync_server_records WHERE team = ?1 AND kind = ?2 AND id = ?3",
+                    params![team, record.kind.as_str(), record.id],
+                    row_to_record,
+                )
+                .optional()?;
+
+            match current.as_ref().map(|c| record.version.compare(&c.version)) {
+                None | Some(Causality::After) => {
+                    seq += 1;
+                    tx.execute(
+                        "INSERT INTO team_sync_server_records
+                            (team, kind, id, version, deleted, nonce, ciphertext, author,
+                             updated_at, seq)
+                         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+                         ON CONFLICT(team, kind, id) DO UPDATE SET
+                            version = excluded.version,
+                            deleted = excluded.deleted,
+                            nonce = excluded.nonce,
+                            ciphertext = excluded.ciphertext,
+                            author = excluded.author,
+                            updated_at = excluded.updated_at,
+                            seq = excluded.seq",
+                        params![
+                            team,
+                            record.kind.as_str(),
+                            record.id,
+                            record.version.to_json(),
+                            record.deleted,
+                            record.nonce,
+                            record.ciphertext,
+                            record.author,
+                            record.updated_at,
+                            seq as i64
+                        ],
+                    )?;
+                    response.accepted.push(record.id.clone());
+                }
+                Some(Causality::Equal) => response.accepted.push(record.id.clone()),
+                Some(Causality::Before) | Some(Causality::Concurrent) => {
+                    response.conflicts.extend(current);
+                }
+            }
+        }
+        tx.commit()?;
+        Ok(response)
+    }
+
+    /// The team's records changed after `since`, oldest change first
+    pub fn pull(&self, team: &str, since: u64, limit: usize) -> Result<PullResponse> {
+        let limit = limit.clamp(1, PAGE_SIZE);
+        let conn = self.database.get_connection()?;
+        let mut records = conn
+            .prepare(
+                "SELECT kind, id, version, deleted, nonce, ciphertext, author, updated_at, seq
+                 FROM team_sync_server_records
+                 WHERE team = ?1 AND seq > ?2
+                 ORDER BY seq
+                 LIMIT ?3",
+            )?
+            .query_map(params![team, since as i64, limit as i64 + 1], row_to_record)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        let more = records.len() > limit;
+        records.truncate(limit);
+        let cursor = records.last().map_or(since, |record| record.seq);
+        Ok(PullResponse {
+            records,
+            cursor,
+            more,
+        })
+    }
+
+    /// Live and deleted record counts of a team
+    pub fn counts(&self, team: &str) -> Result<(u64, u64)> {
+        let conn = self.database.get_connection()?;
+        let (live, deleted) = conn.query_row(
+            "SELECT COALESCE(SUM(deleted = 0), 0), COALESCE(SUM(deleted = 1), 0)
+             FROM team_sync_server_records WHERE team = ?1",
+            params![team],
+            |row| Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?)),
+        )?;
+        Ok((live as u64, deleted as u64))
+    }
+}
+
+fn row_to_record(row: &Row) -> rusqlite::Result<SyncRecord> {
+    let kind: String = row.get(0)?;
+    let version: String = row.get(2)?;
+    Ok(SyncRecord {
+        kind: RecordKind::parse(&kind).map_err(|e| conversion_error(0, e))?,
+        id: row.get(1)?,
+        version: VersionVector::from_json(&version).map_err(|e| conversion_error(2, e))?,
+        deleted: row.get(3)?,
+        nonce: row.get(4)?,
+        ciphertext: row.get(5)?,
+        author: row.get(6)?,
+        updated_at: row.get(7)?,
+        seq: row.get::<_, i64>(8)? as u64,
+    })
+}
+
+fn conversion_error(column: usize, error: anyhow::Error) -> rusqlite::Error {
+    rusqlite::Error::FromSqlConversionFailure(column, Type::Text, error.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::database::DatabaseConfig;
+    use crate::team_sync::TeamKey;
+    use tempfile::TempDir;
+
+    async fn test_store() -> (SyncServerStore, TempDir) {
+        let dir = TempDir::new().unwrap();
+        let database = Arc::new(
+            DatabaseManager::new(DatabaseConfig {
+                path: dir.path().join("sync.db"),
+                ..DatabaseConfig::default()
+            })
+            .await
+            .unwrap(),
+        );
+        (SyncServerStore::new(database).unwrap(), dir)
+    }
+
+    fn record(key: &TeamKey, version: &VersionVector, author: &str) -> SyncRecord {
+        key.seal(
+            RecordKind::Fact,
+            "fp-1",
+            version.clone(),
+            false,
+            b"fact",
+            author,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn tokens_map_to_their_team() {
+        let tokens = TeamTokens::from_list("platform:abc, mobile:def").unwrap();
+        assert_eq!(tokens.team("abc"), Some("platform"));
+        assert_eq!(tokens.team("def"), Some("mobile"));
+        assert_eq!(tokens.team("platform"), None);
+        assert!(TeamTokens::from_list("no-token").is_err());
+        assert!(TeamTokens::from_list("").unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn only_superseding_versions_are_stored() {
+        let (store, _dir) = test_store().await;
+        let key = TeamKey::derive("platform", "secret").unwrap();
+
+        let mut first = VersionVector::new();
+        first.increment("a");
+        let response = store
+            .push("platform", &[record(&key, &first, "a")])
+            .unwrap();
+        assert_eq!(response.accepted.len(), 1);
+
+        // Retrying the same version is fine
+        let response = store
+            .push("platform", &[record(&key, &first, "a")])
+            .unwrap();
+        assert_eq!(response.accepted.len(), 1);
+        assert!(response.conflicts.is_empty());
+
+        // Two edits made independently on top of the first
+        let mut from_a = first.clone();
+        from_a.increment("a");
+        let mut from_b = first.clone();
+        from_b.increment("b");
+        store
+            .push("platform", &[record(&key, &from_a, "a")])
+            .unwrap();
+        let response = store
+            .push("platform", &[record(&key, &from_b, "b")])
+            .unwrap();
+        assert!(response.accepted.is_empty());
+        assert_eq!(response.conflicts.len(), 1);
+        assert_eq!(response.conflicts[0].version, from_a);
+
+        // The merge supersedes both
+        let mut merged = from_a.clone();
+        merged.merge(&from_b);
+        merged.increment("b");
+        let response = store
+            .push("platform", &[record(&key, &merged, "b")])
+            .unwrap();
+        assert_eq!(response.accepted.len(), 1);
+
+        let pulled = store.pull("platform", 0, 10).unwrap();
+        assert_eq!(pulled.records.len(), 1);
+        assert_eq!(pulled.records[0].version, merged);
+        assert!(!pulled.more);
+        assert!(store
+            .pull("platform", pulled.cursor, 10)
+            .unwrap()
+            .records
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn teams_only_see_their_own_records() {
+        let (store, _dir) = test_store().await;
+        let platform = TeamKey::derive("platform", "secret").unwrap();
+        let mobile = TeamKey::derive("mobile", "secret").unwrap();
+        let mut version = VersionVector::new();
+        version.increment("a");
+
+        store
+            .push("platform", &[record(&platform, &version, "a")])
+            .unwrap();
+        store
+            .push("mobile", &[record(&mobile, &version, "a")])
+            .unwrap();
+
+        let pulled = store.pull("platform", 0, 10).unwrap();
+        assert_eq!(pulled.records.len(), 1);
+        assert!(platform.open(&pulled.records[0]).is_ok());
+        assert_eq!(store.counts("mobile").unwrap(), (1, 0));
+        assert_eq!(store.counts("design").unwrap(), (0, 0));
+    }
+}
@@ -0,0 +1,664 @@
+//! TUI application state and input handling
+//!
+//! `App` is a plain state machine: events go in, `Action`s for the event loop
+//! come out, and rendering in `ui` only reads it.
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseEvent, MouseEventKind};
+use std::path::PathBuf;
+use tui_textarea::TextArea;
+
+use super::event::TuiEvent;
+use super::files::FileTree;
+use super::operations::{Decision, PendingOperation};
+use crate::consensus::streaming::ConsensusEvent;
+use crate::consensus::types::{ConsensusProfile, Stage};
+
+/// Pane arrangement selected with `hive tui --layout`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LayoutMode {
+    /// Files | conversation | stages, with the diff viewer below the conversation
+    Default,
+    /// Conversation and stages only
+    Minimal,
+}
+
+impl LayoutMode {
+    pub fn parse(name: &str) -> Self {
+        match name {
+            "minimal" => Self::Minimal,
+            _ => Self::Default,
+        }
+    }
+}
+
+/// Pane receiving keyboard input
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Focus {
+    Input,
+    Conversation,
+    Files,
+    Operations,
+}
+
+/// Who wrote a conversation entry
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Author {
+    User,
+    Stage(Stage),
+    System,
+}
+
+#[derive(Debug, Clone)]
+pub struct ChatMessage {
+    pub author: Author,
+    pub content: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StageState {
+    Running,
+    Done,
+    Failed,
+}
+
+/// Sidebar row for one stage of the current run
+#[derive(Debug, Clone)]
+pub struct StageStatus {
+    pub stage: Stage,
+    pub model: String,
+    pub state: StageState,
+    pub percentage: f32,
+    pub tokens: u32,
+    pub cost: f64,
+    pub duration: f64,
+}
+
+/// Work the event loop performs on behalf of the app
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Action {
+    None,
+    Quit,
+    Submit(String),
+    Cancel,
+    SwitchProfile(String),
+    /// Look for file operations in the final answer
+    ParseOperations(String),
+    RefreshFiles,
+}
+
+pub struct App {
+    pub layout: LayoutMode,
+    pub focus: Focus,
+    pub input: TextArea<'static>,
+    pub messages: Vec<ChatMessage>,
+    /// Lines scrolled up from the bottom of the conversation
+    pub scroll_back: u16,
+    pub stages: Vec<StageStatus>,
+    pub total_cost: f64,
+    pub running: bool,
+    pub files: FileTree,
+    pub file_cursor: usize,
+    pub operations: Vec<PendingOperation>,
+    pub operation_cursor: usize,
+    pub diff_scroll: u16,
+    pub profiles: Vec<ConsensusProfile>,
+    pub current_profile: String,
+    pub profile_picker: Option<usize>,
+    pub status: String,
+    pub tick: u64,
+    operations_this_run: bool,
+}
+
+impl App {
+    pub fn new(layout: LayoutMode, files: FileTree, current_profile: String) -> Self {
+        Self {
+            layout,
+            focus: Focus::Input,
+            input: TextArea::default(),
+            messages: Vec::new(),
+            scroll_back: 0,
+            stages: Vec::new(),
+            total_cost: 0.0,
+            running: false,
+            files,
+            file_cursor: 0,
+            operations: Vec::new(),
+            operation_cursor: 0,
+            diff_scroll: 0,
+            profiles: Vec::new(),
+            current_profile,
+            profile_picker: None,
+            status:
+                "Enter: ask · Alt+Enter: newline · Tab: next pane · Ctrl+P: profile · Ctrl+Q: quit"
+                    .to_string(),
+            tick: 0,
+            operations_this_run: false,
+        }
+    }
+
+    pub fn root(&self) -> PathBuf {
+        self.files.root().to_path_buf()
+    }
+
+    pub fn pending_operations(&self) -> usize {
+        self.operations
+            .iter()
+            .filter(|op| op.decision == Decision::Pending)
+            .count()
+    }
+
+    pub fn handle_event(&mut self, event: TuiEvent) -> Action {
+        match event {
+            TuiEvent::Key(key) => self.handle_key(key),
+            TuiEvent::Mouse(mouse) => {
+                self.handle_mouse(mouse);
+                Action::None
+            }
+            TuiEvent::Resize => Action::None,
+            TuiEvent::Tick => {
+                self.tick = self.tick.wrapping_add(1);
+                Action::None
+            }
+            TuiEvent::Consensus(event) => {
+                self.handle_consensus_event(event);
+                Action::None
+            }
+            TuiEvent::OperationsProposed(operations) => {
+                let root = self.root();
+                self.operations = operations
+                    .into_iter()
+                    .map(|proposed| PendingOperation::new(&root, proposed))
+                    .collect();
+                self.operation_cursor = 0;
+                self.diff_scroll = 0;
+                self.operations_this_run = true;
+                if !self.operations.is_empty() {
+                    self.status = format!(
+                        "{} file operation(s) to review · Tab to the diff pane · a: accept · r: reject",
+                        self.operations.len()
+                    );
+                }
+                Action::None
+            }
+            TuiEvent::Finished(result) => {
+                self.running = false;
+                match result {
+                    Ok(result) => {
+                        self.total_cost += result.total_cost;
+                        self.status = format!(
+                            "Done in {:.1}s · ${:.4} · session ${:.4}",
+                            result.total_duration, result.total_cost, self.total_cost
+                        );
+                        match result.result {
+                            Some(answer) if !self.operations_this_run => {
+                                return Action::ParseOperations(answer)
+                            }
+                            _ => {}
+                        }
+                    }
+                    Err(error) => {
+                        self.status = "Consensus failed".to_string();
+                        self.push(Author::System, error);
+                    }
+                }
+                Action::None
+            }
+            TuiEvent::ProfilesLoaded(profiles) => {
+                self.profiles = profiles;
+                Action::None
+            }
+            TuiEvent::ProfileChanged(result) => {
+                match result {
+                    Ok(profile) => {
+                        self.status = format!("Profile: {}", profile.profile_name);
+                        self.current_profile = profile.profile_name;
+                    }
+                    Err(error) => self.status = format!("Profile switch failed: {}", error),
+                }
+                Action::None
+            }
+        }
+    }
+
+    fn handle_consensus_event(&mut self, event: ConsensusEvent) {
+        match event {
+            ConsensusEvent::StageStarted { stage, model } => {
+                self.stages.retain(|s| s.stage != stage);
+                self.stages.push(StageStatus {
+                    stage,
+                    model,
+                    state: StageState::Running,
+                    percentage: 0.0,
+                    tokens: 0,
+                    cost: 0.0,
+                    duration: 0.0,
+                });
+                self.push(Author::Stage(stage), String::new());
+            }
+            ConsensusEvent::Token { stage, chunk, .. } => match self.messages.last_mut() {
+                Some(message) if message.author == Author::Stage(stage) => {
+                    message.content.push_str(&chunk)
+                }
+                _ => self.push(Author::Stage(stage), chunk),
+            },
+            ConsensusEvent::Progress {
+                stage,
+                tokens,
+                percentage,
+                ..
+            } => {
+                if let Some(status) = self.stage_mut(stage) {
+                    status.tokens = tokens;
+                    status.percentage = percentage.min(100.0);
+                }
+            }
+            ConsensusEvent::StageCompleted { stage, result } => {
+                // The hooks may have replaced the streamed text
+                if let Some(message) = self
+                    .messages
+                    .iter_mut()
+                    .rev()
+                    .find(|m| m.author == Author::Stage(stage))
+                {
+                    message.content = result.answer.clone();
+                }
+                if let Some(status) = self.stage_mut(stage) {
+                    status.state = StageState::Done;
+                    status.percentage = 100.0;
+                    status.model = result.model.clone();
+                    if let Some(usage) = &result.usage {
+                        status.tokens = usage.total_tokens;
+                    }
+                    if let Some(analytics) = &result.analytics {
+                        status.cost = analytics.cost;
+                        status.duration = analytics.duration;
+                    }
+                }
+            }
+            ConsensusEvent::Error { stage, error } => {
+                if let Some(status) = self.stage_mut(stage) {
+                    status.state = StageState::Failed;
+                }
+                self.push(
+                    Author::System,
+                    format!("{} failed: {}", stage.display_name(), error),
+                );
+            }
+            ConsensusEvent::Completed | ConsensusEvent::FinalResponse { .. } => {}
+        }
+    }
+
+    fn stage_mut(&mut self, stage: Stage) -> Option<&mut StageStatus> {
+        self.stages.iter_mut().find(|s| s.stage == stage)
+    }
+
+    fn push(&mut self, author: Author, content: String) {
+        self.messages.push(ChatMessage { author, content });
+        self.scroll_back = 0;
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> Action {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+
+        if ctrl && matches!(key.code, KeyCode::Char('q')) {
+            return Action::Quit;
+        }
+        if ctrl && matches!(key.code, KeyCode::Char('c')) {
+            return if self.running {
+                self.status = "Cancelling…".to_string();
+                Action::Cancel
+            } else {
+                Action::Quit
+            };
+        }
+        if self.profile_picker.is_some() {
+            return self.handle_profile_key(key);
+        }
+        if ctrl && matches!(key.code, KeyCode::Char('p')) {
+            if self.profiles.is_empty() {
+                self.status = "No profiles available - run 'hive quickstart'".to_string();
+            } else {
+                let current = self
+                    .profiles
+                    .iter()
+                    .position(|p| p.profile_name == self.current_profile)
+                    .unwrap_or(0);
+                self.profile_picker = Some(current);
+            }
+            return Action::None;
+        }
+        match key.code {
+            KeyCode::Tab => {
+                self.cycle_focus(true);
+                return Action::None;
+            }
+            KeyCode::BackTab => {
+                self.cycle_focus(false);
+                return Action::None;
+            }
+            KeyCode::Esc if self.running => {
+                self.status = "Cancelling…".to_string();
+                return Action::Cancel;
+            }
+            _ => {}
+        }
+
+        match self.focus {
+            Focus::Input => self.handle_input_key(key),
+            Focus::Conversation => {
+                self.handle_scroll_key(key);
+                Action::None
+            }
+            Focus::Files => self.handle_files_key(key),
+            Focus::Operations => self.handle_operations_key(key),
+        }
+    }
+
+    fn cycle_focus(&mut self, forward: bool) {
+        let mut order = vec![Focus::Input, Focus::Conversation];
+        if self.layout == LayoutMode::Default {
+            order.push(Focus::Files);
+        }
+        if !self.operations.is_empty() {
+            order.push(Focus::Operations);
+        }
+        let current = order.iter().position(|f| *f == self.focus).unwrap_or(0);
+        let next = if forward {
+            (current + 1) % order.len()
+        } else {
+            (current + order.len() - 1) % order.len()
+        };
+        self.focus = order[next];
+    }
+
+    fn handle_input_key(&mut self, key: KeyEvent) -> Action {
+        match key.code {
+            KeyCode::Enter if key.modifiers.contains(KeyModifiers::ALT) => {
+                self.input.insert_newline();
+                Action::None
+            }
+            KeyCode::Enter => {
+                let question = self.input.lines().join("\n").trim().to_string();
+                if question.is_empty() {
+                    return Action::None;
+                }
+                if self.running {
+                    self.status = "A consensus run is in progress (Esc to cancel)".to_string();
+                    return Action::None;
+                }
+                self.input = TextArea::default();
+                self.push(Author::User, question.clone());
+                self.stages.clear();
+                self.running = true;
+                self.operations_this_run = false;
+                self.status = format!("Running consensus with {}…", self.current_profile);
+                Action::Submit(question)
+            }
+            _ => {
+                self.input.input(key);
+                Action::None
+            }
+        }
+    }
+
+    fn handle_scroll_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.scroll_back = self.scroll_back.saturating_add(1)
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.scroll_back = self.scroll_back.saturating_sub(1)
+            }
+            KeyCode::PageUp => self.scroll_back = self.scroll_back.saturating_add(10),
+            KeyCode::PageDown => self.scroll_back = self.scroll_back.saturating_sub(10),
+            KeyCode::End | KeyCode::Char('G') => self.scroll_back = 0,
+            _ => {}
+        }
+    }
+
+    fn handle_mouse(&mut self, mouse: MouseEvent) {
+        match mouse.kind {
+            MouseEventKind::ScrollUp => self.scroll_back = self.scroll_back.saturating_add(3),
+            MouseEventKind::ScrollDown => self.scroll_back = self.scroll_back.saturating_sub(3),
+            _ => {}
+        }
+    }
+
+    fn handle_files_key(&mut self, key: KeyEvent) -> Action {
+        let visible = self.files.visible().len();
+        match key.code {
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.file_cursor = self.file_cursor.saturating_sub(1)
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.file_cursor = (self.file_cursor + 1).min(visible.saturating_sub(1))
+            }
+            KeyCode::Enter | KeyCode::Char(' ') => {
+                let dir = self
+                    .files
+                    .visible()
+                    .get(self.file_cursor)
+                    .filter(|entry| entry.is_dir)
+                    .map(|entry| entry.path.clone());
+                if let Some(dir) = dir {
+                    self.files.toggle(&dir);
+                }
+            }
+            KeyCode::Char('r') => return Action::RefreshFiles,
+            _ => {}
+        }
+        Action::None
+    }
+
+    fn handle_operations_key(&mut self, key: KeyEvent) -> Action {
+        let root = self.root();
+        match key.code {
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.operation_cursor = self.operation_cursor.saturating_sub(1);
+                self.diff_scroll = 0;
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.operation_cursor =
+                    (self.operation_cursor + 1).min(self.operations.len().saturating_sub(1));
+                self.diff_scroll = 0;
+            }
+            KeyCode::PageDown => self.diff_scroll = self.diff_scroll.saturating_add(10),
+            KeyCode::PageUp => self.diff_scroll = self.diff_scroll.saturating_sub(10),
+            KeyCode::Char('a') => {
+                if let Some(operation) = self.operations.get_mut(self.operation_cursor) {
+                    if operation.decision == Decision::Pending {
+                        operation.accept(&root);
+                        self.status = match &operation.message {
+                            Some(error) => format!("Failed: {}", error),
+                            None => format!("Applied {}", operation.label()),
+                        };
+                        return Action::RefreshFiles;
+                    }
+                }
+            }
+            KeyCode::Char('A') => {
+                let mut applied = 0;
+                for operation in &mut self.operations {
+                    if operation.decision == Decision::Pending {
+                        operation.accept(&root);
+                        if operation.decision == Decision::Accepted {
+                            applied += 1;
+                        }
+                    }
+                }
+                self.status = format!("Applied {} operation(s)", applied);
+                return Action::RefreshFiles;
+            }
+            KeyCode::Char('r') => {
+                if let Some(operation) = self.operations.get_mut(self.operation_cursor) {
+                    if operation.decision == Decision::Pending {
+                        operation.reject();
+                        self.status = format!("Rejected {}", operation.label());
+                    }
+                }
+            }
+            _ => {}
+        }
+        Action::None
+    }
+
+    fn handle_profile_key(&mut self, key: KeyEvent) -> Action {
+        let Some(cursor) = self.profile_picker else {
+            return Action::None;
+        };
+        match key.code {
+            KeyCode::Esc => self.profile_picker = None,
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.profile_picker = Some(cursor.saturating_sub(1))
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.profile_picker = Some((cursor + 1).min(self.profiles.len().saturating_sub(1)))
+            }
+            KeyCode::Enter => {
+                self.profile_picker = None;
+                if let Some(profile) = self.profiles.get(cursor) {
+                    if profile.profile_name != self.current_profile {
+                        self.status = format!("Switching to {}…", profile.profile_name);
+                        return Action::SwitchProfile(profile.profile_name.clone());
+                    }
+                }
+            }
+            _ => {}
+        }
+        Action::None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consensus::ai_operation_parser::{FileOperationWithMetadata, SourceLocation};
+    use crate::consensus::stages::file_aware_curator::FileOperation;
+    use crate::consensus::types::ConsensusResult;
+    use std::path::Path;
+    use tempfile::TempDir;
+
+    fn key(code: KeyCode) -> TuiEvent {
+        TuiEvent::Key(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    fn app(root: &Path) -> App {
+        App::new(
+            LayoutMode::Default,
+            FileTree::from_paths(root, Vec::new(), Default::default()),
+            "balanced".to_string(),
+        )
+    }
+
+    fn finished(answer: &str) -> TuiEvent {
+        TuiEvent::Finished(Ok(ConsensusResult {
+            success: true,
+            result: Some(answer.to_string()),
+            error: None,
+            stages: Vec::new(),
+            conversation_id: "c".to_string(),
+            total_duration: 1.0,
+            total_cost: 0.02,
+        }))
+    }
+
+    #[test]
+    fn test_alt_enter_inserts_newline_and_submit_clears_input() {
+        let dir = TempDir::new().unwrap();
+        let mut app = app(dir.path());
+        for c in "ab".chars() {
+            app.handle_event(key(KeyCode::Char(c)));
+        }
+        app.handle_event(TuiEvent::Key(KeyEvent::new(
+            KeyCode::Enter,
+            KeyModifiers::ALT,
+        )));
+        app.handle_event(key(KeyCode::Char('c')));
+        app.handle_event(key(KeyCode::Backspace));
+        app.handle_event(key(KeyCode::Char('d')));
+        assert_eq!(app.input.lines(), ["ab", "d"]);
+        assert_eq!(app.input.cursor(), (1, 1));
+
+        assert_eq!(
+            app.handle_event(key(KeyCode::Enter)),
+            Action::Submit("ab\nd".to_string())
+        );
+        assert_eq!(app.input.lines(), [""]);
+    }
+
+    #[test]
+    fn test_submit_streams_stages_into_conversation() {
+        let dir = TempDir::new().unwrap();
+        let mut app = app(dir.path());
+        for c in "hi".chars() {
+            app.handle_event(key(KeyCode::Char(c)));
+        }
+        assert_eq!(
+            app.handle_event(key(KeyCode::Enter)),
+            Action::Submit("hi".to_string())
+        );
+        assert!(app.running);
+        assert_eq!(app.handle_event(key(KeyCode::Esc)), Action::Cancel);
+
+        app.handle_event(TuiEvent::Consensus(ConsensusEvent::StageStarted {
+            stage: Stage::Generator,
+            model: "a/one".to_string(),
+        }));
+        for chunk in ["Hel", "lo"] {
+            app.handle_event(TuiEvent::Consensus(ConsensusEvent::Token {
+                stage: Stage::Generator,
+                chunk: chunk.to_string(),
+                total_content: String::new(),
+            }));
+        }
+        assert_eq!(app.messages.last().unwrap().content, "Hello");
+        assert_eq!(app.stages[0].state, StageState::Running);
+
+        assert_eq!(
+            app.handle_event(finished("Hello")),
+            Action::ParseOperations("Hello".to_string())
+        );
+        assert!(!app.running);
+        assert!((app.total_cost - 0.02).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_review_operations() {
+        let dir = TempDir::new().unwrap();
+        let mut app = app(dir.path());
+        let proposed = |name: &str| FileOperationWithMetadata {
+            operation: FileOperation::Create {
+                path: PathBuf::from(name),
+                content: "x\n".to_string(),
+            },
+            confidence: 80.0,
+            rationale: None,
+            dependencies: Vec::new(),
+            source_location: SourceLocation {
+                start: 0,
+                end: 0,
+                line: 0,
+            },
+        };
+        app.handle_event(TuiEvent::OperationsProposed(vec![
+            proposed("a.txt"),
+            proposed("b.txt"),
+        ]));
+        assert_eq!(app.pending_operations(), 2);
+        // Operations proposed during the run are not parsed again
+        assert_eq!(app.handle_event(finished("done")), Action::None);
+
+        app.focus = Focus::Operations;
+        assert_eq!(
+            app.handle_event(key(KeyCode::Char('a'))),
+            Action::RefreshFiles
+        );
+        app.handle_event(key(KeyCode::Down));
+        app.handle_event(key(KeyCode::Char('r')));
+
+        assert!(dir.path().join("a.txt").exists());
+        assert!(!dir.path().join("b.txt").exists());
+        assert_eq!(app.operations[1].decision, Decision::Rejected);
+        assert_eq!(app.pending_operations(), 0);
+    }
+}
@@ -0,0 +1,117 @@
+//! Events driving the TUI: terminal input, consensus streaming and background results
+
+use anyhow::Result;
+use crossterm::event::{self, Event, KeyEvent, KeyEventKind, MouseEvent};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+use crate::consensus::ai_operation_parser::FileOperationWithMetadata;
+use crate::consensus::streaming::{ConsensusEvent, ProgressInfo, StreamingCallbacks};
+use crate::consensus::types::{ConsensusProfile, ConsensusResult, Stage, StageResult};
+
+/// Everything the TUI event loop reacts to
+#[derive(Debug)]
+pub enum TuiEvent {
+    Key(KeyEvent),
+    Mouse(MouseEvent),
+    Resize,
+    Tick,
+    /// Streaming update from the running consensus
+    Consensus(ConsensusEvent),
+    /// File operations the pipeline wants confirmed
+    OperationsProposed(Vec<FileOperationWithMetadata>),
+    /// The consensus run ended
+    Finished(std::result::Result<ConsensusResult, String>),
+    ProfilesLoaded(Vec<ConsensusProfile>),
+    ProfileChanged(std::result::Result<ConsensusProfile, String>),
+}
+
+/// Streaming callbacks that forward pipeline activity into the TUI event loop
+pub struct TuiCallbacks {
+    sender: mpsc::UnboundedSender<TuiEvent>,
+}
+
+impl TuiCallbacks {
+    pub fn new(sender: mpsc::UnboundedSender<TuiEvent>) -> Arc<Self> {
+        Arc::new(Self { sender })
+    }
+
+    fn send(&self, event: ConsensusEvent) {
+        let _ = self.sender.send(TuiEvent::Consensus(event));
+    }
+}
+
+impl StreamingCallbacks for TuiCallbacks {
+    fn on_stage_start(&self, stage: Stage, model: &str) -> Result<()> {
+        self.send(ConsensusEvent::StageStarted {
+            stage,
+            model: model.to_string(),
+        });
+        Ok(())
+    }
+
+    fn on_stage_chunk(&self, stage: Stage, chunk: &str, total_content: &str) -> Result<()> {
+        self.send(ConsensusEvent::Token {
+            stage,
+            chunk: chunk.to_string(),
+            total_content: total_content.to_string(),
+        });
+        Ok(())
+    }
+
+    fn on_stage_progress(&self, stage: Stage, progress: ProgressInfo) -> Result<()> {
+        self.send(ConsensusEvent::Progress {
+            stage,
+            tokens: progress.tokens,
+            estimated_total: progress.estimated_total,
+            percentage: progress.percentage,
+        });
+        Ok(())
+    }
+
+    fn on_stage_complete(&self, stage: Stage, result: &StageResult) -> Result<()> {
+        self.send(ConsensusEvent::StageCompleted {
+            stage,
+            result: result.clone(),
+        });
+        Ok(())
+    }
+
+    fn on_error(&self, stage: Stage, error: &anyhow::Error) -> Result<()> {
+        self.send(ConsensusEvent::Error {
+            stage,
+            error: format!("{:#}", error),
+        });
+        Ok(())
+    }
+
+    fn on_operations_require_confirmation(
+        &self,
+        operations: Vec<FileOperationWithMetadata>,
+    ) -> Result<()> {
+        let _ = self.sender.send(TuiEvent::OperationsProposed(operations));
+        Ok(())
+    }
+}
+
+/// Read terminal input on a dedicated thread, emitting `Tick` when idle.
+/// The thread exits once the receiving side is dropped.
+pub fn spawn_input_reader(sender: mpsc::UnboundedSender<TuiEvent>, tick_rate: Duration) {
+    std::thread::spawn(move || loop {
+        let event = match event::poll(tick_rate) {
+            Ok(true) => match event::read() {
+                Ok(Event::Key(key)) if key.kind == KeyEventKind::Press => TuiEvent::Key(key),
+                Ok(Event::Mouse(mouse)) => TuiEvent::Mouse(mouse),
+                Ok(Event::Resize(..)) => TuiEvent::Resize,
+                Ok(_) => continue,
+                Err(_) => break,
+            },
+            Ok(false) => TuiEvent::Tick,
+            Err(_) => break,
+        };
+        if sender.send(event).is_err() {
+            break;
+        }
+    });
+}
@@ -0,0 +1,267 @@
+//! File explorer model: the repository tree with git status markers
+
+use anyhow::Result;
+use git2::{Repository, Status, StatusOptions};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use crate::analysis::walker::RepositoryWalker;
+
+/// Git status of a file, as shown next to it in the tree
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GitStatus {
+    Modified,
+    Added,
+    Deleted,
+    Renamed,
+    Untracked,
+    Conflicted,
+}
+
+impl GitStatus {
+    fn from_git(status: Status) -> Option<Self> {
+        if status.is_conflicted() {
+            Some(Self::Conflicted)
+        } else if status.is_wt_new() {
+            Some(Self::Untracked)
+        } else if status.is_index_new() {
+            Some(Self::Added)
+        } else if status.is_wt_deleted() || status.is_index_deleted() {
+            Some(Self::Deleted)
+        } else if status.is_wt_renamed() || status.is_index_renamed() {
+            Some(Self::Renamed)
+        } else if status.is_wt_modified()
+            || status.is_index_modified()
+            || status.is_wt_typechange()
+            || status.is_index_typechange()
+        {
+            Some(Self::Modified)
+        } else {
+            None
+        }
+    }
+
+    /// One-letter marker, as in `git status --short`
+    pub fn marker(&self) -> char {
+        match self {
+            Self::Modified => 'M',
+            Self::Added => 'A',
+            Self::Deleted => 'D',
+            Self::Renamed => 'R',
+            Self::Untracked => '?',
+            Self::Conflicted => 'U',
+        }
+    }
+}
+
+/// A file or directory row in the tree
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileEntry {
+    /// Path relative to the tree root
+    pub path: PathBuf,
+    pub depth: usize,
+    pub is_dir: bool,
+    /// The file's status; directories report `Modified` when anything below changed
+    pub status: Option<GitStatus>,
+}
+
+impl FileEntry {
+    pub fn name(&self) -> String {
+        self.path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| self.path.display().to_string())
+    }
+}
+
+/// Repository tree for the explorer pane
+#[derive(Debug, Clone, Default)]
+pub struct FileTree {
+    root: PathBuf,
+    entries: Vec<FileEntry>,
+    collapsed: HashSet<PathBuf>,
+}
+
+impl FileTree {
+    /// Walk `root` (respecting ignore files) and attach git status
+    pub fn load(root: &Path) -> Result<Self> {
+        let files: Vec<PathBuf> = RepositoryWalker::new(root)
+            .walk()?
+            .into_iter()
+            .map(|file| file.relative_path)
+            .collect();
+        Ok(Self::from_paths(root, files, git_statuses(root)))
+    }
+
+    /// Build the tree from relative file paths; deleted files only known to git are included
+    pub fn from_paths(
+        root: &Path,
+        files: Vec<PathBuf>,
+        statuses: HashMap<PathBuf, GitStatus>,
+    ) -> Self {
+        let files: BTreeSet<PathBuf> = files.into_iter().chain(statuses.keys().cloned()).collect();
+
+        // Directories are inserted ahead of the first file below them
+        let mut entries = Vec::new();
+        let mut seen_dirs: HashSet<PathBuf> = HashSet::new();
+        for file in &files {
+            let ancestors: Vec<&Path> = file
+                .ancestors()
+                .skip(1)
+                .filter(|dir| !dir.as_os_str().is_empty())
+                .collect();
+            for dir in ancestors.into_iter().rev() {
+                if seen_dirs.insert(dir.to_path_buf()) {
+                    let changed = statuses.keys().any(|path| path.starts_with(dir));
+                    entries.push(FileEntry {
+                        path: dir.to_path_buf(),
+                        depth: dir.components().count() - 1,
+                        is_dir: true,
+                        status: changed.then_some(GitStatus::Modified),
+                    });
+                }
+            }
+            entries.push(FileEntry {
+                path: file.clone(),
+                depth: file.components().count() - 1,
+                is_dir: false,
+                status: statuses.get(file).copied(),
+            });
+        }
+
+        // Directories sort before files at each level
+        entries.sort_by(|a, b| {
+            let a_key = sort_key(a);
+            let b_key = sort_key(b);
+            a_key.cmp(&b_key)
+        });
+
+        Self {
+            root: root.to_path_buf(),
+            entries,
+            collapsed: HashSet::new(),
+        }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Rows not hidden inside a collapsed directory
+    pub fn visible(&self) -> Vec<&FileEntry> {
+        self.entries
+            .iter()
+            .filter(|entry| {
+                !self
+                    .collapsed
+                    .iter()
+                    .any(|dir| entry.path != *dir && entry.path.starts_with(dir))
+            })
+            .collect()
+    }
+
+    pub fn is_collapsed(&self, dir: &Path) -> bool {
+        self.collapsed.contains(dir)
+    }
+
+    /// Collapse or expand a directory
+    pub fn toggle(&mut self, dir: &Path) {
+        if !self.collapsed.remove(dir) {
+            self.collapsed.insert(dir.to_path_buf());
+        }
+    }
+
+    /// Number of files with a git status
+    pub fn changed_files(&self) -> usize {
+        self.entries
+            .iter()
+            .filter(|entry| !entry.is_dir && entry.status.is_some())
+            .count()
+    }
+}
+
+/// Sort by path components, directories before files among siblings
+fn sort_key(entry: &FileEntry) -> Vec<(bool, String)> {
+    let components: Vec<String> = entry
+        .path
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().to_lowercase())
+        .collect();
+    let last = components.len() - 1;
+    components
+        .into_iter()
+        .enumerate()
+        .map(|(i, name)| (i == last && !entry.is_dir, name))
+        .collect()
+}
+
+/// Status of changed files under `root`, keyed by path relative to `root`;
+/// empty when `root` is not inside a git repository
+pub fn git_statuses(root: &Path) -> HashMap<PathBuf, GitStatus> {
+    let mut statuses = HashMap::new();
+    let Ok(repo) = Repository::discover(root) else {
+        return statuses;
+    };
+    let Some(workdir) = repo.workdir().and_then(|dir| dir.canonicalize().ok()) else {
+        return statuses;
+    };
+    let root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+
+    let mut options = StatusOptions::new();
+    options.include_untracked(true).recurse_untracked_dirs(true);
+    let Ok(entries) = repo.statuses(Some(&mut options)) else {
+        return statuses;
+    };
+    for entry in entries.iter() {
+        let (Some(path), Some(status)) = (entry.path(), GitStatus::from_git(entry.status())) else {
+            continue;
+        };
+        if let Ok(relative) = workdir.join(path).strip_prefix(&root) {
+            statuses.insert(relative.to_path_buf(), status);
+        }
+    }
+    statuses
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tree_orders_dirs_first_and_propagates_status() {
+        let files = vec![
+            PathBuf::from("README.md"),
+            PathBuf::from("src/main.rs"),
+            PathBuf::from("src/cli/args.rs"),
+        ];
+        let statuses = HashMap::from([
+            (PathBuf::from("src/cli/args.rs"), GitStatus::Modified),
+            (PathBuf::from("src/old.rs"), GitStatus::Deleted),
+        ]);
+        let mut tree = FileTree::from_paths(Path::new("/repo"), files, statuses);
+
+        let rows: Vec<(String, usize, Option<char>)> = tree
+            .visible()
+            .iter()
+            .map(|e| (e.name(), e.depth, e.status.map(|s| s.marker())))
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                ("src".to_string(), 0, Some('M')),
+                ("cli".to_string(), 1, Some('M')),
+                ("args.rs".to_string(), 2, Some('M')),
+                ("main.rs".to_string(), 1, None),
+                ("old.rs".to_string(), 1, Some('D')),
+                ("README.md".to_string(), 0, None),
+            ]
+        );
+        assert_eq!(tree.changed_files(), 2);
+
+        tree.toggle(Path::new("src"));
+        assert!(tree.is_collapsed(Path::new("src")));
+        assert_eq!(tree.visible().len(), 2);
+        tree.toggle(Path::new("src"));
+        assert_eq!(tree.visible().len(), 6);
+    }
+}
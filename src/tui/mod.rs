@@ -0,0 +1,187 @@
+//! Terminal UI for `hive tui`
+//!
+//! A conversation pane with live per-stage streaming, a stage/cost sidebar,
+//! a git-aware file tree and a diff viewer for reviewing file operations.
+//! Everything renders with plain terminal escapes, so it works over SSH.
+
+pub mod app;
+pub mod event;
+pub mod files;
+pub mod operations;
+pub mod ui;
+
+use anyhow::{Context, Result};
+use crossterm::event::{DisableMouseCapture, EnableMouseCapture};
+use crossterm::terminal::{
+    disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen,
+};
+use ratatui::backend::CrosstermBackend;
+use ratatui::Terminal;
+use std::io::{self, Stdout};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+use crate::consensus::ai_operation_parser::AIOperationParser;
+use crate::consensus::mentions::MentionResolver;
+use crate::consensus::operation_intelligence::OperationContext;
+use crate::consensus::{CancellationReason, CancellationToken, ConsensusEngine};
+use crate::core::config::{get_config, get_hive_config_dir};
+
+pub use app::{Action, App, LayoutMode};
+pub use event::{TuiCallbacks, TuiEvent};
+
+const TICK_RATE: Duration = Duration::from_millis(200);
+
+/// Restores the terminal when dropped, including on early return
+struct TerminalGuard {
+    mouse: bool,
+}
+
+impl TerminalGuard {
+    fn enter(mouse: bool) -> Result<Self> {
+        enable_raw_mode().context("Failed to enable raw mode")?;
+        let guard = Self { mouse };
+        crossterm::execute!(io::stdout(), EnterAlternateScreen)?;
+        if mouse {
+            crossterm::execute!(io::stdout(), EnableMouseCapture)?;
+        }
+        Ok(guard)
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        if self.mouse {
+            let _ = crossterm::execute!(io::stdout(), DisableMouseCapture);
+        }
+        let _ = crossterm::execute!(io::stdout(), LeaveAlternateScreen);
+        let _ = disable_raw_mode();
+    }
+}
+
+/// Run the TUI in the current directory until the user quits
+pub async fn run(layout: &str) -> Result<()> {
+    let config = get_config().await?.interface.tui;
+    let root = std::env::current_dir()?;
+
+    let engine = Arc::new(ConsensusEngine::new(None).await?);
+    let hooks_dir = get_hive_config_dir().join("hooks");
+    let mut hook_error = None;
+    if hooks_dir.is_dir() {
+        let hooks_system = crate::hooks::HooksSystem::new(get_hive_config_dir()).await?;
+        match hooks_system.load_hooks(hooks_dir).await {
+            Ok(()) => engine.set_hooks_system(Arc::new(hooks_system)).await,
+            Err(e) => hook_error = Some(format!("Hooks disabled: {:#}", e)),
+        }
+    }
+
+    let files = files::FileTree::load(&root).unwrap_or_else(|_| {
+        files::FileTree::from_paths(&root, Vec::new(), files::git_statuses(&root))
+    });
+    let profile = engine.get_current_profile().await.profile_name;
+    let mut app = App::new(LayoutMode::parse(layout), files, profile);
+    if let Some(error) = hook_error {
+        app.status = error;
+    }
+    let theme = ui::Theme::from_name(&config.theme);
+
+    let (sender, mut receiver) = mpsc::unbounded_channel();
+    {
+        let engine = engine.clone();
+        let sender = sender.clone();
+        tokio::spawn(async move {
+            let profiles = engine.get_profiles().await.unwrap_or_default();
+            let _ = sender.send(TuiEvent::ProfilesLoaded(profiles));
+        });
+    }
+
+    let _guard = TerminalGuard::enter(config.mouse_enabled)?;
+    let mut terminal: Terminal<CrosstermBackend<Stdout>> =
+        Terminal::new(CrosstermBackend::new(io::stdout()))?;
+    terminal.clear()?;
+    event::spawn_input_reader(sender.clone(), TICK_RATE);
+
+    let mut cancellation: Option<CancellationToken> = None;
+    let mut last_question = String::new();
+    loop {
+        terminal.draw(|frame| ui::draw(frame, &app, &theme))?;
+        let Some(event) = receiver.recv().await else {
+            break;
+        };
+
+        match app.handle_event(event) {
+            Action::None => {}
+            Action::Quit => break,
+            Action::Submit(question) => {
+                let token = CancellationToken::new();
+                cancellation = Some(token.clone());
+                last_question = question.clone();
+                let engine = engine.clone();
+                let sender = sender.clone();
+                let mentions = MentionResolver::new(&root);
+                tokio::spawn(async move {
+                    let callbacks = TuiCallbacks::new(sender.clone());
+                    let result = match mentions.context_for(&question).await {
+                        Ok(context) => engine
+                            .process_with_callbacks_and_cancellation(
+                                &question, context, callbacks, None, token,
+                            )
+                            .await
+                            .map_err(|e| format!("{:#}", e)),
+                        Err(e) => Err(format!("{:#}", e)),
+                    };
+                    let _ = sender.send(TuiEvent::Finished(result));
+                });
+            }
+            Action::Cancel => {
+                if let Some(token) = cancellation.take() {
+                    token.cancel(CancellationReason::UserRequested);
+                }
+            }
+            Action::SwitchProfile(name) => {
+                let engine = engine.clone();
+                let sender = sender.clone();
+                tokio::spawn(async move {
+                    let result = match engine.set_profile(&name).await {
+                        Ok(()) => Ok(engine.get_current_profile().await),
+                        Err(e) => Err(format!("{:#}", e)),
+                    };
+                    let _ = sender.send(TuiEvent::ProfileChanged(result));
+                });
+            }
+            Action::ParseOperations(answer) => {
+                let context = OperationContext {
+                    repository_path: root.clone(),
+                    git_commit: None,
+                    source_question: last_question.clone(),
+                    related_files: Vec::new(),
+                    project_metadata: Default::default(),
+                };
+                let sender = sender.clone();
+                tokio::spawn(async move {
+                    if let Ok(parsed) = AIOperationParser::new()
+                        .parse_response(&answer, &context)
+                        .await
+                    {
+                        if !parsed.operations.is_empty() {
+                            let _ = sender.send(TuiEvent::OperationsProposed(parsed.operations));
+                        }
+                    }
+                });
+            }
+            Action::RefreshFiles => {
+                if let Ok(files) = files::FileTree::load(&root) {
+                    app.file_cursor = app.file_cursor.min(files.visible().len().saturating_sub(1));
+                    app.files = files;
+                }
+            }
+        }
+    }
+
+    if let Some(token) = cancellation {
+        token.cancel(CancellationReason::UserRequested);
+    }
+    terminal.show_cursor()?;
+    Ok(())
+}
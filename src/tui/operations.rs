@@ -0,0 +1,282 @@
+//! Pending file operations proposed by the curator, with diffs for review
+
+use anyhow::{bail, Context, Result};
+use similar::{ChangeTag, TextDiff};
+use std::path::{Component, Path, PathBuf};
+
+use crate::consensus::ai_operation_parser::FileOperationWithMetadata;
+use crate::consensus::stages::file_aware_curator::FileOperation;
+
+/// Kind of a diff line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffLineKind {
+    Header,
+    Context,
+    Added,
+    Removed,
+}
+
+/// One rendered line of a diff
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffLine {
+    pub kind: DiffLineKind,
+    pub text: String,
+}
+
+/// Review state of a pending operation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Decision {
+    Pending,
+    Accepted,
+    Rejected,
+    Failed,
+}
+
+/// A proposed file operation awaiting accept/reject
+#[derive(Debug, Clone)]
+pub struct PendingOperation {
+    pub operation: FileOperation,
+    pub rationale: Option<String>,
+    pub diff: Vec<DiffLine>,
+    pub decision: Decision,
+    pub message: Option<String>,
+}
+
+impl PendingOperation {
+    /// Diff the operation against the current contents under `root`
+    pub fn new(root: &Path, proposed: FileOperationWithMetadata) -> Self {
+        let diff = operation_diff(root, &proposed.operation);
+        Self {
+            operation: proposed.operation,
+            rationale: proposed.rationale,
+            diff,
+            decision: Decision::Pending,
+            message: None,
+        }
+    }
+
+    /// Short label such as `update src/main.rs`
+    pub fn label(&self) -> String {
+        match &self.operation {
+            FileOperation::Create { path, .. } => format!("create {}", path.display()),
+            FileOperation::Update { path, .. } => format!("update {}", path.display()),
+            FileOperation::Append { path, .. } => format!("append {}", path.display()),
+            FileOperation::Delete { path } => format!("delete {}", path.display()),
+            FileOperation::Rename { from, to } => {
+                format!("rename {} → {}", from.display(), to.display())
+            }
+        }
+    }
+
+    /// Apply the operation under `root` and record the outcome
+    pub fn accept(&mut self, root: &Path) {
+        match apply_operation(root, &self.operation) {
+            Ok(()) => {
+                self.decision = Decision::Accepted;
+                self.message = None;
+            }
+            Err(e) => {
+                self.decision = Decision::Failed;
+                self.message = Some(format!("{:#}", e));
+            }
+        }
+    }
+
+    pub fn reject(&mut self) {
+        self.decision = Decision::Rejected;
+    }
+}
+
+/// Unified diff of what the operation would change
+pub fn operation_diff(root: &Path, operation: &FileOperation) -> Vec<DiffLine> {
+    let checked = match operation {
+        FileOperation::Rename { from, to } => confined(root, from).and(confined(root, to)),
+        FileOperation::Create { path, .. }
+        | FileOperation::Update { path, .. }
+        | FileOperation::Append { path, .. }
+        | FileOperation::Delete { path } => confined(root, path),
+    };
+    if let Err(e) = checked {
+        return vec![DiffLine {
+            kind: DiffLineKind::Header,
+            text: e.to_string(),
+        }];
+    }
+
+    let read = |path: &Path| std::fs::read_to_string(root.join(path)).unwrap_or_default();
+    let (path, before, after) = match operation {
+        FileOperation::Create { path, content } => (path, String::new(), content.clone()),
+        FileOperation::Update { path, content } => (path, read(path), content.clone()),
+        FileOperation::Append { path, content } => {
+            let before = read(path);
+            let after = format!("{}{}", before, content);
+            (path, before, after)
+        }
+        FileOperation::Delete { path } => (path, read(path), String::new()),
+        FileOperation::Rename { from, to } => {
+            return vec![DiffLine {
+                kind: DiffLineKind::Header,
+                text: format!("rename {} → {}", from.display(), to.display()),
+            }];
+        }
+    };
+
+    let mut lines = vec![DiffLine {
+        kind: DiffLineKind::Header,
+        text: format!("--- a/{0}\n+++ b/{0}", path.display()),
+    }];
+    let diff = TextDiff::from_lines(&before, &after);
+    for (i, group) in diff.grouped_ops(3).iter().enumerate() {
+        if i > 0 {
+            lines.push(DiffLine {
+                kind: DiffLineKind::Header,
+                text: "⋯".to_string(),
+            });
+        }
+        for op in group {
+            for change in diff.iter_changes(op) {
+                let (kind, sign) = match change.tag() {
+                    ChangeTag::Equal => (DiffLineKind::Context, ' '),
+                    ChangeTag::Insert => (DiffLineKind::Added, '+'),
+                    ChangeTag::Delete => (DiffLineKind::Removed, '-'),
+                };
+                lines.push(DiffLine {
+                    kind,
+                    text: format!("{}{}", sign, change.value().trim_end_matches('\n')),
+                });
+            }
+        }
+    }
+    lines
+}
+
+/// Resolve `path` under `root`, refusing absolute paths and `..`
+fn confined(root: &Path, path: &Path) -> Result<PathBuf> {
+    if path
+        .components()
+        .any(|c| !matches!(c, Component::Normal(_) | Component::CurDir))
+    {
+        bail!("Refusing to touch {} outside the project", path.display());
+    }
+    Ok(root.join(path))
+}
+
+/// Write an accepted operation to disk
+pub fn apply_operation(root: &Path, operation: &FileOperation) -> Result<()> {
+    let create_parent = |path: &Path| -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        Ok(())
+    };
+
+    match operation {
+        FileOperation::Create { path, content } | FileOperation::Update { path, content } => {
+            let target = confined(root, path)?;
+            create_parent(&target)?;
+            std::fs::write(&target, content)
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+        }
+        FileOperation::Append { path, content } => {
+            use std::io::Write;
+            let target = confined(root, path)?;
+            create_parent(&target)?;
+            std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&target)
+                .and_then(|mut file| file.write_all(content.as_bytes()))
+                .with_context(|| format!("Failed to append to {}", path.display()))?;
+        }
+        FileOperation::Delete { path } => {
+            std::fs::remove_file(confined(root, path)?)
+                .with_context(|| format!("Failed to delete {}", path.display()))?;
+        }
+        FileOperation::Rename { from, to } => {
+            let target = confined(root, to)?;
+            create_parent(&target)?;
+            std::fs::rename(confined(root, from)?, &target)
+                .with_context(|| format!("Failed to rename {}", from.display()))?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consensus::ai_operation_parser::SourceLocation;
+    use tempfile::TempDir;
+
+    fn proposed(operation: FileOperation) -> FileOperationWithMetadata {
+        FileOperationWithMetadata {
+            operation,
+            confidence: 90.0,
+            rationale: None,
+            dependencies: Vec::new(),
+            source_location: SourceLocation {
+                start: 0,
+                end: 0,
+                line: 0,
+            },
+        }
+    }
+
+    #[test]
+    fn test_update_diff_and_accept() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(dir.path().join("lib.rs"), "fn a() {}\nfn b() {}\n").unwrap();
+
+        let mut pending = PendingOperation::new(
+            dir.path(),
+            proposed(FileOperation::Update {
+                path: PathBuf::from("lib.rs"),
+                content: "fn a() {}\nfn c() {}\n".to_string(),
+            }),
+        );
+        let changes: Vec<&str> = pending
+            .diff
+            .iter()
+            .filter(|l| matches!(l.kind, DiffLineKind::Added | DiffLineKind::Removed))
+            .map(|l| l.text.as_str())
+            .collect();
+        assert_eq!(changes, vec!["-fn b() {}", "+fn c() {}"]);
+
+        pending.accept(dir.path());
+        assert_eq!(pending.decision, Decision::Accepted);
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("lib.rs")).unwrap(),
+            "fn a() {}\nfn c() {}\n"
+        );
+    }
+
+    #[test]
+    fn test_paths_outside_root_are_refused() {
+        let dir = TempDir::new().unwrap();
+        let outside = dir.path().join("secret.txt");
+        std::fs::write(&outside, "token\n").unwrap();
+        let root = dir.path().join("project");
+        std::fs::create_dir(&root).unwrap();
+
+        let diff = operation_diff(
+            &root,
+            &FileOperation::Delete {
+                path: PathBuf::from("../secret.txt"),
+            },
+        );
+        assert_eq!(diff.len(), 1);
+        assert!(diff[0].text.contains("outside the project"));
+        assert!(!diff.iter().any(|l| l.text.contains("token")));
+
+        let mut pending = PendingOperation::new(
+            dir.path(),
+            proposed(FileOperation::Create {
+                path: PathBuf::from("../escape.txt"),
+                content: "x".to_string(),
+            }),
+        );
+        pending.accept(dir.path());
+        assert_eq!(pending.decision, Decision::Failed);
+        assert!(pending.message.unwrap().contains("outside the project"));
+    }
+}
@@ -0,0 +1,432 @@
+//! Rendering of the TUI panes
+
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph};
+use ratatui::Frame;
+
+use super::app::{App, Author, Focus, LayoutMode, StageState};
+use super::operations::{Decision, DiffLineKind};
+
+const SPINNER: [&str; 4] = ["◐", "◓", "◑", "◒"];
+const INPUT_HEIGHT: u16 = 5;
+
+/// Colours for the `interface.tui.theme` setting
+#[derive(Debug, Clone, Copy)]
+pub struct Theme {
+    pub border: Color,
+    pub focused: Color,
+    pub user: Color,
+    pub stage: Color,
+    pub system: Color,
+    pub added: Color,
+    pub removed: Color,
+    pub muted: Color,
+}
+
+impl Theme {
+    pub fn from_name(name: &str) -> Self {
+        match name {
+            "light" => Self {
+                border: Color::Gray,
+                focused: Color::Blue,
+                user: Color::Magenta,
+                stage: Color::Blue,
+                system: Color::Red,
+                added: Color::Green,
+                removed: Color::Red,
+                muted: Color::DarkGray,
+            },
+            _ => Self {
+                border: Color::DarkGray,
+                focused: Color::Yellow,
+                user: Color::Cyan,
+                stage: Color::LightBlue,
+                system: Color::LightRed,
+                added: Color::LightGreen,
+                removed: Color::LightRed,
+                muted: Color::Gray,
+            },
+        }
+    }
+}
+
+pub fn draw(frame: &mut Frame, app: &App, theme: &Theme) {
+    let [main, status] = split(
+        frame.size(),
+        Direction::Vertical,
+        [Constraint::Min(1), Constraint::Length(1)],
+    );
+
+    let center = match app.layout {
+        LayoutMode::Default => {
+            let [files, center, sidebar] = split(
+                main,
+                Direction::Horizontal,
+                [
+                    Constraint::Percentage(20),
+                    Constraint::Min(40),
+                    Constraint::Length(34),
+                ],
+            );
+            draw_files(frame, app, theme, files);
+            draw_sidebar(frame, app, theme, sidebar);
+            center
+        }
+        LayoutMode::Minimal => {
+            let [center, sidebar] = split(
+                main,
+                Direction::Horizontal,
+                [Constraint::Min(40), Constraint::Length(34)],
+            );
+            draw_sidebar(frame, app, theme, sidebar);
+            center
+        }
+    };
+
+    if app.operations.is_empty() {
+        let [conversation, input] = split(
+            center,
+            Direction::Vertical,
+            [Constraint::Min(3), Constraint::Length(INPUT_HEIGHT)],
+        );
+        draw_conversation(frame, app, theme, conversation);
+        draw_input(frame, app, theme, input);
+    } else {
+        let [conversation, operations, input] = split(
+            center,
+            Direction::Vertical,
+            [
+                Constraint::Percentage(50),
+                Constraint::Percentage(50),
+                Constraint::Length(INPUT_HEIGHT),
+            ],
+        );
+        draw_conversation(frame, app, theme, conversation);
+        draw_operations(frame, app, theme, operations);
+        draw_input(frame, app, theme, input);
+    }
+
+    frame.render_widget(
+        Paragraph::new(Line::from(vec![
+            Span::styled(
+                format!(" {} ", app.current_profile),
+                Style::default().add_modifier(Modifier::REVERSED),
+            ),
+            Span::raw(" "),
+            Span::styled(app.status.as_str(), Style::default().fg(theme.muted)),
+        ])),
+        status,
+    );
+
+    if let Some(cursor) = app.profile_picker {
+        draw_profile_picker(frame, app, theme, cursor);
+    }
+}
+
+fn split<const N: usize>(
+    area: Rect,
+    direction: Direction,
+    constraints: [Constraint; N],
+) -> [Rect; N] {
+    let chunks = Layout::default()
+        .direction(direction)
+        .constraints(constraints)
+        .split(area);
+    std::array::from_fn(|i| chunks[i])
+}
+
+fn pane<'a>(title: String, focused: bool, theme: &Theme) -> Block<'a> {
+    let color = if focused { theme.focused } else { theme.border };
+    Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(color))
+        .title(title)
+}
+
+fn draw_conversation(frame: &mut Frame, app: &App, theme: &Theme, area: Rect) {
+    let block = pane(
+        " Conversation ".to_string(),
+        app.focus == Focus::Conversation,
+        theme,
+    );
+    let inner = block.inner(area);
+    let width = inner.width.max(1) as usize;
+
+    let mut lines: Vec<Line> = Vec::new();
+    for message in &app.messages {
+        let (label, color) = match message.author {
+            Author::User => ("You".to_string(), theme.user),
+            Author::Stage(stage) => (stage.display_name().to_string(), theme.stage),
+            Author::System => ("Error".to_string(), theme.system),
+        };
+        lines.push(Line::from(Span::styled(
+            label,
+            Style::default().fg(color).add_modifier(Modifier::BOLD),
+        )));
+        for line in message.content.lines() {
+            lines.extend(wrap(line, width).into_iter().map(Line::from));
+        }
+        lines.push(Line::default());
+    }
+
+    // Wrapping is done above so the bottom of the transcript can be pinned
+    let height = inner.height as usize;
+    let top = lines
+        .len()
+        .saturating_sub(height)
+        .saturating_sub(app.scroll_back as usize);
+    frame.render_widget(
+        Paragraph::new(lines).block(block).scroll((top as u16, 0)),
+        area,
+    );
+}
+
+/// Hard-wrap a line to `width` characters
+fn wrap(line: &str, width: usize) -> Vec<String> {
+    let chars: Vec<char> = line.chars().collect();
+    if chars.is_empty() {
+        return vec![String::new()];
+    }
+    chars
+        .chunks(width)
+        .map(|chunk| chunk.iter().collect())
+        .collect()
+}
+
+fn draw_input(frame: &mut Frame, app: &App, theme: &Theme, area: Rect) {
+    let title = if app.running {
+        " Ask (running - Esc to cancel) ".to_string()
+    } else {
+        " Ask (Enter to send, Alt+Enter for newline) ".to_string()
+    };
+    let block = pane(title, app.focus == Focus::Input, theme);
+    let inner = block.inner(area);
+
+    // tui-textarea resolves a newer ratatui than ours, so its widget can't be
+    // rendered here; draw the editor state through our own Paragraph instead.
+    let (row, col) = app.input.cursor();
+    let top = row.saturating_sub(inner.height.saturating_sub(1) as usize);
+    let lines: Vec<Line> = app
+        .input
+        .lines()
+        .iter()
+        .map(|line| Line::from(line.as_str()))
+        .collect();
+    frame.render_widget(
+        Paragraph::new(lines).block(block).scroll((top as u16, 0)),
+        area,
+    );
+
+    if app.focus == Focus::Input && app.profile_picker.is_none() {
+        let x = inner.x + (col as u16).min(inner.width.saturating_sub(1));
+        let y = inner.y + (row - top) as u16;
+        frame.set_cursor(x, y);
+    }
+}
+
+fn draw_files(frame: &mut Frame, app: &App, theme: &Theme, area: Rect) {
+    let title = match app.files.changed_files() {
+        0 => " Files ".to_string(),
+        n => format!(" Files ({} changed) ", n),
+    };
+    let items: Vec<ListItem> = app
+        .files
+        .visible()
+        .into_iter()
+        .map(|entry| {
+            let icon = if !entry.is_dir {
+                " "
+            } else if app.files.is_collapsed(&entry.path) {
+                "▸"
+            } else {
+                "▾"
+            };
+            let marker = entry.status.map(|s| s.marker()).unwrap_or(' ');
+            let style = match entry.status {
+                Some(_) => Style::default().fg(theme.focused),
+                None if entry.is_dir => Style::default().add_modifier(Modifier::BOLD),
+                None => Style::default(),
+            };
+            ListItem::new(Line::from(vec![
+                Span::styled(format!("{} ", marker), Style::default().fg(theme.muted)),
+                Span::raw("  ".repeat(entry.depth)),
+                Span::styled(format!("{}{}", icon, entry.name()), style),
+            ]))
+        })
+        .collect();
+
+    let list = List::new(items)
+        .block(pane(title, app.focus == Focus::Files, theme))
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+    let mut state = ListState::default().with_selected(Some(app.file_cursor));
+    frame.render_stateful_widget(list, area, &mut state);
+}
+
+fn draw_operations(frame: &mut Frame, app: &App, theme: &Theme, area: Rect) {
+    let [list_area, diff_area] = split(
+        area,
+        Direction::Horizontal,
+        [Constraint::Percentage(35), Constraint::Percentage(65)],
+    );
+    let focused = app.focus == Focus::Operations;
+
+    let items: Vec<ListItem> = app
+        .operations
+        .iter()
+        .map(|op| {
+            let (mark, color) = match op.decision {
+                Decision::Pending => ("•", theme.muted),
+                Decision::Accepted => ("✓", theme.added),
+                Decision::Rejected => ("✗", theme.muted),
+                Decision::Failed => ("!", theme.removed),
+            };
+            ListItem::new(Line::from(vec![
+                Span::styled(format!("{} ", mark), Style::default().fg(color)),
+                Span::raw(op.label()),
+            ]))
+        })
+        .collect();
+    let title = format!(" Operations ({} pending) ", app.pending_operations());
+    let list = List::new(items)
+        .block(pane(title, focused, theme))
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+    let mut state = ListState::default().with_selected(Some(app.operation_cursor));
+    frame.render_stateful_widget(list, list_area, &mut state);
+
+    let Some(operation) = app.operations.get(app.operation_cursor) else {
+        return;
+    };
+    let mut lines: Vec<Line> = Vec::new();
+    if let Some(message) = &operation.message {
+        lines.push(Line::from(Span::styled(
+            message.as_str(),
+            Style::default().fg(theme.removed),
+        )));
+    }
+    if let Some(rationale) = &operation.rationale {
+        lines.push(Line::from(Span::styled(
+            rationale.as_str(),
+            Style::default().fg(theme.muted),
+        )));
+    }
+    for line in &operation.diff {
+        let style = match line.kind {
+            DiffLineKind::Header => Style::default().add_modifier(Modifier::BOLD),
+            DiffLineKind::Context => Style::default(),
+            DiffLineKind::Added => Style::default().fg(theme.added),
+            DiffLineKind::Removed => Style::default().fg(theme.removed),
+        };
+        lines.extend(
+            line.text
+                .lines()
+                .map(|text| Line::from(Span::styled(text, style))),
+        );
+    }
+    frame.render_widget(
+        Paragraph::new(lines)
+            .block(pane(
+                " Diff (a: accept · A: accept all · r: reject) ".to_string(),
+                focused,
+                theme,
+            ))
+            .scroll((app.diff_scroll, 0)),
+        diff_area,
+    );
+}
+
+fn draw_sidebar(frame: &mut Frame, app: &App, theme: &Theme, area: Rect) {
+    let mut lines = vec![
+        Line::from(vec![
+            Span::styled("Profile ", Style::default().fg(theme.muted)),
+            Span::raw(app.current_profile.as_str()),
+        ]),
+        Line::default(),
+    ];
+
+    if app.stages.is_empty() {
+        lines.push(Line::from(Span::styled(
+            "No consensus run yet",
+            Style::default().fg(theme.muted),
+        )));
+    }
+    let spinner = SPINNER[(app.tick % SPINNER.len() as u64) as usize];
+    for status in &app.stages {
+        let (icon, color) = match status.state {
+            StageState::Running => (spinner, theme.focused),
+            StageState::Done => ("✓", theme.added),
+            StageState::Failed => ("✗", theme.removed),
+        };
+        lines.push(Line::from(vec![
+            Span::styled(format!("{} ", icon), Style::default().fg(color)),
+            Span::styled(
+                status.stage.display_name(),
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+        ]));
+        lines.push(Line::from(Span::styled(
+            format!("  {}", status.model),
+            Style::default().fg(theme.muted),
+        )));
+        let detail = match status.state {
+            StageState::Running => format!(
+                "  {} {:>3.0}% · {} tok",
+                progress_bar(status.percentage, 10),
+                status.percentage,
+                status.tokens
+            ),
+            _ => format!(
+                "  {} tok · ${:.4} · {:.1}s",
+                status.tokens, status.cost, status.duration
+            ),
+        };
+        lines.push(Line::from(detail));
+    }
+
+    let run_cost: f64 = app.stages.iter().map(|s| s.cost).sum();
+    lines.push(Line::default());
+    lines.push(Line::from(format!("Run      ${:.4}", run_cost)));
+    lines.push(Line::from(format!("Session  ${:.4}", app.total_cost)));
+
+    frame.render_widget(
+        Paragraph::new(lines).block(pane(" Consensus ".to_string(), false, theme)),
+        area,
+    );
+}
+
+fn progress_bar(percentage: f32, width: usize) -> String {
+    let filled = ((percentage.clamp(0.0, 100.0) / 100.0) * width as f32).round() as usize;
+    format!("{}{}", "█".repeat(filled), "░".repeat(width - filled))
+}
+
+fn draw_profile_picker(frame: &mut Frame, app: &App, theme: &Theme, cursor: usize) {
+    let area = frame.size();
+    let width = 40.min(area.width);
+    let height = (app.profiles.len() as u16 + 2).min(area.height);
+    let popup = Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    };
+
+    let items: Vec<ListItem> = app
+        .profiles
+        .iter()
+        .map(|profile| {
+            let current = if profile.profile_name == app.current_profile {
+                "● "
+            } else {
+                "  "
+            };
+            ListItem::new(format!("{}{}", current, profile.profile_name))
+        })
+        .collect();
+    let list = List::new(items)
+        .block(pane(" Switch profile ".to_string(), true, theme))
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+    let mut state = ListState::default().with_selected(Some(cursor));
+    frame.render_widget(Clear, popup);
+    frame.render_stateful_widget(list, popup, &mut state);
+}